@@ -1,9 +1,12 @@
+use alloc::vec::Vec;
 use core::ops::{Add, Mul, Sub};
 
 use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 
+use crate::ExtensionExpr;
+
 /// An AIR (algebraic intermediate representation).
 pub trait BaseAir<F>: Sync {
     /// The number of columns (a.k.a. registers) in this AIR.
@@ -12,6 +15,24 @@ pub trait BaseAir<F>: Sync {
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
+
+    /// The number of consecutive rows which constraints can refer to, i.e. the height of the
+    /// matrices returned by `AirBuilder::main` and `PairBuilder::preprocessed`.
+    ///
+    /// It must be at least 2, as transition constraints always see the next row. Provers panic on
+    /// smaller windows, and verifiers reject their proofs.
+    fn window_size(&self) -> usize {
+        2
+    }
+
+    /// Columns which repeat themselves with a short period, and are therefore not committed to.
+    ///
+    /// The `i`-th row of the `j`-th column takes the value `columns[j][i % columns[j].len()]`,
+    /// where each length must be a power of two no larger than the trace height. The values are
+    /// available through `PeriodicAirBuilder::periodic_values`.
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        Vec::new()
+    }
 }
 
 ///  An AIR with 0 or more public values.
@@ -63,6 +84,13 @@ pub trait AirBuilder: Sized {
     }
     fn is_transition_window(&self, size: usize) -> Self::Expr;
 
+    /// Returns an expression which is nonzero exactly on the rows `i` such that row `i + step` is
+    /// also in the trace, i.e. all but the last `step` rows. The AIR's window must have more than
+    /// `step` rows.
+    fn is_transition_step(&self, step: usize) -> Self::Expr {
+        self.is_transition_window(step + 1)
+    }
+
     /// Returns a sub-builder whose constraints are enforced only when `condition` is nonzero.
     fn when<I: Into<Self::Expr>>(&mut self, condition: I) -> FilteredAirBuilder<'_, Self> {
         FilteredAirBuilder {
@@ -100,6 +128,12 @@ pub trait AirBuilder: Sized {
         self.when(self.is_transition_window(size))
     }
 
+    /// Returns a sub-builder whose constraints are enforced on all rows except the last `step`,
+    /// e.g. to relate rows `step` apart in an AIR which spreads each operation over `step` rows.
+    fn when_transition_step(&mut self, step: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_transition_step(step))
+    }
+
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I);
 
     fn assert_one<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -121,6 +155,24 @@ pub trait AirBuilder: Sized {
         let x = x.into();
         self.assert_zero(x.clone() * (x.clone() - Self::Expr::ONE) * (x - Self::Expr::TWO));
     }
+
+    /// Assert that an element of an extension field, given by its coordinates, is zero.
+    fn assert_zero_extension<EF: ExtensionField<Self::F>>(
+        &mut self,
+        x: ExtensionExpr<Self::F, EF, Self::Expr>,
+    ) {
+        for coordinate in x.into_coordinates() {
+            self.assert_zero(coordinate);
+        }
+    }
+
+    fn assert_eq_extension<EF: ExtensionField<Self::F>>(
+        &mut self,
+        x: ExtensionExpr<Self::F, EF, Self::Expr>,
+        y: ExtensionExpr<Self::F, EF, Self::Expr>,
+    ) {
+        self.assert_zero_extension(x - y);
+    }
 }
 
 pub trait AirBuilderWithPublicValues: AirBuilder {
@@ -129,6 +181,13 @@ pub trait AirBuilderWithPublicValues: AirBuilder {
     fn public_values(&self) -> &[Self::PublicVar];
 }
 
+/// A builder which provides the values of the AIR's periodic columns on the current row.
+pub trait PeriodicAirBuilder: AirBuilder {
+    type PeriodicVar: Into<Self::Expr> + Copy;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar];
+}
+
 pub trait PairBuilder: AirBuilder {
     fn preprocessed(&self) -> Self::M;
 }
@@ -209,6 +268,22 @@ impl<AB: AirBuilder> AirBuilder for FilteredAirBuilder<'_, AB> {
     }
 }
 
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for FilteredAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for FilteredAirBuilder<'_, AB> {
+    type PeriodicVar = AB::PeriodicVar;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.inner.periodic_values()
+    }
+}
+
 impl<AB: ExtensionBuilder> ExtensionBuilder for FilteredAirBuilder<'_, AB> {
     type EF = AB::EF;
     type ExprEF = AB::ExprEF;
@@ -0,0 +1,156 @@
+//! Constraints on traces over an extension field.
+//!
+//! A trace over an extension `EF` of the base field is committed as base field columns, with each
+//! extension column flattened into `EF::D` consecutive columns holding its coordinates, as by
+//! `RowMajorMatrix::flatten_to_base`. Constraints on such a trace are written in terms of
+//! `ExtensionExpr`s, which reduce extension field arithmetic to arithmetic on the coordinates, and
+//! are therefore understood by every `AirBuilder`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use p3_field::{ExtensionField, Field, FieldAlgebra};
+
+/// An element of `EF`, an extension of `F`, given by the expressions for its `EF::D` coordinates
+/// in the power basis over `F`.
+#[derive(Clone, Debug)]
+pub struct ExtensionExpr<F, EF, Expr> {
+    coordinates: Vec<Expr>,
+    _phantom: PhantomData<(F, EF)>,
+}
+
+impl<F, EF, Expr> ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    pub fn from_coordinates(coordinates: Vec<Expr>) -> Self {
+        assert_eq!(coordinates.len(), EF::D);
+        Self {
+            coordinates,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// The value of the `column`-th extension column of a flattened row.
+    pub fn from_row<V: Into<Expr> + Copy>(row: &[V], column: usize) -> Self {
+        Self::from_coordinates(
+            row[column * EF::D..(column + 1) * EF::D]
+                .iter()
+                .map(|&v| v.into())
+                .collect(),
+        )
+    }
+
+    pub fn constant(value: EF) -> Self {
+        Self::from_coordinates(value.as_base_slice().iter().map(|&c| c.into()).collect())
+    }
+
+    pub fn coordinates(&self) -> &[Expr] {
+        &self.coordinates
+    }
+
+    pub fn into_coordinates(self) -> Vec<Expr> {
+        self.coordinates
+    }
+}
+
+impl<F, EF, Expr> From<Expr> for ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    fn from(x: Expr) -> Self {
+        let mut coordinates = vec![Expr::ZERO; EF::D];
+        coordinates[0] = x;
+        Self::from_coordinates(coordinates)
+    }
+}
+
+impl<F, EF, Expr> Add for ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::from_coordinates(
+            self.coordinates
+                .into_iter()
+                .zip(rhs.coordinates)
+                .map(|(x, y)| x + y)
+                .collect(),
+        )
+    }
+}
+
+impl<F, EF, Expr> Sub for ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_coordinates(
+            self.coordinates
+                .into_iter()
+                .zip(rhs.coordinates)
+                .map(|(x, y)| x - y)
+                .collect(),
+        )
+    }
+}
+
+impl<F, EF, Expr> Neg for ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::from_coordinates(self.coordinates.into_iter().map(|x| -x).collect())
+    }
+}
+
+impl<F, EF, Expr> Mul for ExtensionExpr<F, EF, Expr>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    Expr: FieldAlgebra + From<F> + Mul<F, Output = Expr>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let d = EF::D;
+        // The products of basis elements `X^i X^j` with `i + j >= D`, reduced in `EF`.
+        let high_powers = (d..2 * d - 1)
+            .map(|m| EF::monomial(d - 1) * EF::monomial(m + 1 - d))
+            .collect::<Vec<_>>();
+        let mut coordinates = vec![Expr::ZERO; d];
+        for (i, x) in self.coordinates.iter().enumerate() {
+            for (j, y) in rhs.coordinates.iter().enumerate() {
+                let product = x.clone() * y.clone();
+                if i + j < d {
+                    coordinates[i + j] += product;
+                } else {
+                    for (k, &c) in high_powers[i + j - d].as_base_slice().iter().enumerate() {
+                        if !c.is_zero() {
+                            coordinates[k] += product.clone() * c;
+                        }
+                    }
+                }
+            }
+        }
+        Self::from_coordinates(coordinates)
+    }
+}
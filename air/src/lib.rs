@@ -5,8 +5,16 @@
 extern crate alloc;
 
 mod air;
+mod extension;
+mod lookup;
+mod padding;
+mod sub_air;
 pub mod utils;
 mod virtual_column;
 
 pub use air::*;
+pub use extension::*;
+pub use lookup::*;
+pub use padding::*;
+pub use sub_air::*;
 pub use virtual_column::*;
@@ -0,0 +1,261 @@
+//! Lookups via the LogUp (logarithmic derivative) argument.
+//!
+//! An AIR declares the tuples its rows send to and receive from a lookup bus. Sends and receives
+//! must balance: every tuple must be sent as many times as it is received, counting multiplicities.
+//!
+//! Given challenges `beta` and `gamma`, each interaction on each row contributes
+//! `± multiplicity / (gamma - fingerprint)` to a running sum, where the fingerprint of a tuple
+//! `(v_0, ..., v_{k-1})` on bus `b` is `b + v_0 beta + ... + v_{k-1} beta^k`. With overwhelming
+//! probability, sends and receives balance exactly when the total sum is zero.
+//!
+//! Sends and receives may also be split across several AIRs proven together, in which case only the
+//! sum over all AIRs must be zero.
+//!
+//! The running sum lives in a permutation trace over the extension field, with one column per
+//! interaction holding the inverse `1 / (gamma - fingerprint)`, followed by the running sum itself.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::{batch_multiplicative_inverse, ExtensionField, Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::{BaseAir, PairBuilder, PermutationAirBuilder, VirtualPairCol};
+
+/// The degree of the constraints added by `eval_logup`.
+pub const LOGUP_CONSTRAINT_DEGREE: usize = 3;
+
+/// Whether an interaction puts tuples on the lookup bus or takes them off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionKind {
+    Send,
+    Receive,
+}
+
+/// A tuple of values, affine in the columns of the current row, sent or received on every row
+/// with a given multiplicity.
+#[derive(Clone, Debug)]
+pub struct Interaction<F: Field> {
+    pub values: Vec<VirtualPairCol<F>>,
+    pub multiplicity: VirtualPairCol<F>,
+    pub kind: InteractionKind,
+    /// Interactions on different buses never balance each other.
+    pub bus: usize,
+}
+
+impl<F: Field> Interaction<F> {
+    pub const fn send(
+        values: Vec<VirtualPairCol<F>>,
+        multiplicity: VirtualPairCol<F>,
+        bus: usize,
+    ) -> Self {
+        Self {
+            values,
+            multiplicity,
+            kind: InteractionKind::Send,
+            bus,
+        }
+    }
+
+    pub const fn receive(
+        values: Vec<VirtualPairCol<F>>,
+        multiplicity: VirtualPairCol<F>,
+        bus: usize,
+    ) -> Self {
+        Self {
+            values,
+            multiplicity,
+            kind: InteractionKind::Receive,
+            bus,
+        }
+    }
+
+    /// The interactions asserting that the tuples `left` and `right`, taken over all rows, are
+    /// equal as multisets, i.e. permutations of each other.
+    pub fn multiset_equality(
+        left: Vec<VirtualPairCol<F>>,
+        right: Vec<VirtualPairCol<F>>,
+        bus: usize,
+    ) -> [Self; 2] {
+        [
+            Self::send(left, VirtualPairCol::ONE, bus),
+            Self::receive(right, VirtualPairCol::ONE, bus),
+        ]
+    }
+}
+
+/// An AIR whose rows take part in lookups.
+pub trait LookupAir<F: Field>: BaseAir<F> {
+    fn interactions(&self) -> Vec<Interaction<F>>;
+}
+
+/// The number of constraints added by `eval_logup` for the given interactions.
+pub fn num_logup_constraints<F: Field>(interactions: &[Interaction<F>]) -> usize {
+    if interactions.is_empty() {
+        0
+    } else {
+        interactions.len() + 3
+    }
+}
+
+/// The width, in extension field elements, of the permutation trace for the given interactions.
+pub fn logup_trace_width<F: Field>(interactions: &[Interaction<F>]) -> usize {
+    interactions.len() + 1
+}
+
+/// Generate the permutation trace of the LogUp argument for `interactions`, given the
+/// challenges `beta` and `gamma`.
+///
+/// The last entry of the last row is the total sum, which is zero iff sends and receives balance.
+pub fn generate_logup_trace<F, EF>(
+    interactions: &[Interaction<F>],
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    main: &RowMajorMatrix<F>,
+    beta: EF,
+    gamma: EF,
+) -> RowMajorMatrix<EF>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    let height = main.height();
+    let width = logup_trace_width(interactions);
+
+    let mut denominators = Vec::with_capacity(height * interactions.len());
+    let mut multiplicities = Vec::with_capacity(height * interactions.len());
+    for r in 0..height {
+        let main_row = main.row_slice(r);
+        let preprocessed_row = preprocessed.map(|preprocessed| preprocessed.row_slice(r));
+        let preprocessed_row = preprocessed_row.as_deref().unwrap_or(&[]);
+        for interaction in interactions {
+            let fingerprint = interaction
+                .values
+                .iter()
+                .rev()
+                .fold(EF::ZERO, |acc, value| {
+                    (acc + value.apply::<F, F>(preprocessed_row, &main_row)) * beta
+                })
+                + F::from_canonical_usize(interaction.bus);
+            denominators.push(gamma - fingerprint);
+
+            let multiplicity = interaction
+                .multiplicity
+                .apply::<F, F>(preprocessed_row, &main_row);
+            multiplicities.push(match interaction.kind {
+                InteractionKind::Send => multiplicity,
+                InteractionKind::Receive => -multiplicity,
+            });
+        }
+    }
+    let inverses = batch_multiplicative_inverse(&denominators);
+
+    let mut values = vec![EF::ZERO; height * width];
+    let mut running_sum = EF::ZERO;
+    for (r, row) in values.chunks_exact_mut(width).enumerate() {
+        let range = r * interactions.len()..(r + 1) * interactions.len();
+        for (i, (&inverse, &multiplicity)) in inverses[range.clone()]
+            .iter()
+            .zip(&multiplicities[range])
+            .enumerate()
+        {
+            row[i] = inverse;
+            running_sum += inverse * multiplicity;
+        }
+        row[interactions.len()] = running_sum;
+    }
+    RowMajorMatrix::new(values, width)
+}
+
+/// Evaluate the LogUp constraints for `interactions` on the permutation trace of `builder`, whose
+/// first two permutation challenges are `beta` and `gamma`.
+///
+/// `cumulative_sum` is the expected total sum, which is zero for a single AIR whose sends and
+/// receives balance.
+pub fn eval_logup<AB>(
+    builder: &mut AB,
+    interactions: &[Interaction<AB::F>],
+    cumulative_sum: AB::ExprEF,
+) where
+    AB: PermutationAirBuilder + PairBuilder,
+{
+    if interactions.is_empty() {
+        return;
+    }
+
+    let preprocessed = builder.preprocessed();
+    let main = builder.main();
+    let permutation = builder.permutation();
+    let randomness = builder.permutation_randomness();
+    let beta: AB::ExprEF = randomness[0].into();
+    let gamma: AB::ExprEF = randomness[1].into();
+
+    let (preprocessed_local, preprocessed_next) =
+        (preprocessed.row_slice(0), preprocessed.row_slice(1));
+    let (main_local, main_next) = (main.row_slice(0), main.row_slice(1));
+    let (permutation_local, permutation_next) =
+        (permutation.row_slice(0), permutation.row_slice(1));
+
+    for (interaction, &inverse) in interactions.iter().zip(permutation_local.iter()) {
+        let fingerprint = interaction
+            .values
+            .iter()
+            .rev()
+            .fold(AB::ExprEF::ZERO, |acc, value| {
+                (acc + value.apply::<AB::Expr, AB::Var>(&preprocessed_local, &main_local))
+                    * beta.clone()
+            })
+            + AB::ExprEF::from_canonical_usize(interaction.bus);
+        let inverse: AB::ExprEF = inverse.into();
+        builder.assert_one_ext(inverse * (gamma.clone() - fingerprint));
+    }
+
+    let local_sum = row_sum::<AB>(
+        interactions,
+        &preprocessed_local,
+        &main_local,
+        &permutation_local,
+    );
+    let next_sum = row_sum::<AB>(
+        interactions,
+        &preprocessed_next,
+        &main_next,
+        &permutation_next,
+    );
+    let running_sum_local: AB::ExprEF = permutation_local[interactions.len()].into();
+    let running_sum_next: AB::ExprEF = permutation_next[interactions.len()].into();
+
+    builder
+        .when_first_row()
+        .assert_eq_ext(running_sum_local.clone(), local_sum);
+    builder
+        .when_transition()
+        .assert_eq_ext(running_sum_next, running_sum_local.clone() + next_sum);
+    builder
+        .when_last_row()
+        .assert_eq_ext(running_sum_local, cumulative_sum);
+}
+
+/// The contribution `sum ± multiplicity / (gamma - fingerprint)` of one row to the running sum.
+fn row_sum<AB: PermutationAirBuilder>(
+    interactions: &[Interaction<AB::F>],
+    preprocessed: &[AB::Var],
+    main: &[AB::Var],
+    permutation: &[AB::VarEF],
+) -> AB::ExprEF {
+    interactions
+        .iter()
+        .zip(permutation)
+        .map(|(interaction, &inverse)| {
+            let multiplicity = interaction
+                .multiplicity
+                .apply::<AB::Expr, AB::Var>(preprocessed, main);
+            let inverse: AB::ExprEF = inverse.into();
+            let term = inverse * multiplicity;
+            match interaction.kind {
+                InteractionKind::Send => term,
+                InteractionKind::Receive => -term,
+            }
+        })
+        .sum()
+}
@@ -0,0 +1,109 @@
+//! Sizing and padding traces, so that traces proven together can be padded consistently.
+
+use alloc::vec::Vec;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::BaseAir;
+
+/// An AIR which knows how to pad its traces to a larger height.
+pub trait TraceSizer<F: Field>: BaseAir<F> {
+    /// The number of rows each operation of the AIR takes, which must be a power of two. Padded
+    /// traces hold a whole number of operations.
+    fn rows_per_operation(&self) -> usize {
+        1
+    }
+
+    /// The smallest height of a trace, e.g. that of the preprocessed trace.
+    fn min_height(&self) -> usize {
+        1
+    }
+
+    /// Generate the padding row at index `index`, following `previous`, the row before it, or
+    /// `None` if the trace is empty. Together with the rows of the trace, padding rows must satisfy
+    /// the constraints of the AIR.
+    fn padding_row(&self, index: usize, previous: Option<&[F]>) -> Vec<F>;
+
+    /// The smallest height to which a trace of `height` rows can be padded.
+    fn padded_height(&self, height: usize) -> usize {
+        let rows_per_operation = self.rows_per_operation();
+        assert!(
+            rows_per_operation.is_power_of_two(),
+            "operations must take a power of two rows"
+        );
+        height
+            .max(self.min_height())
+            .max(rows_per_operation)
+            .next_power_of_two()
+    }
+}
+
+/// How to choose the heights to which traces proven together are padded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    /// Pad each trace to the smallest height it can be padded to.
+    Minimal,
+    /// Pad every trace to the height of the tallest padded trace.
+    SharedMaximum,
+    /// Pad every trace to the given height, e.g. so that all proofs have the same shape. Panics if
+    /// a trace does not fit.
+    Fixed(usize),
+}
+
+/// Append padding rows generated by `air` to `trace`, until it has `height` rows.
+pub fn pad_trace<F, A>(air: &A, trace: &mut RowMajorMatrix<F>, height: usize)
+where
+    F: Field,
+    A: TraceSizer<F>,
+{
+    assert!(
+        height.is_power_of_two(),
+        "traces must have a power of two rows"
+    );
+    assert!(
+        height >= air.padded_height(trace.height()),
+        "cannot pad a trace of height {} to height {height}",
+        trace.height()
+    );
+    assert_eq!(
+        height % air.rows_per_operation(),
+        0,
+        "the padded trace must hold a whole number of operations"
+    );
+    let width = trace.width();
+    trace.values.reserve((height - trace.height()) * width);
+    for index in trace.height()..height {
+        let previous = index
+            .checked_sub(1)
+            .map(|previous| &trace.values[previous * width..index * width]);
+        let row = air.padding_row(index, previous);
+        assert_eq!(
+            row.len(),
+            width,
+            "padding rows must have the width of the trace"
+        );
+        trace.values.extend(row);
+    }
+}
+
+/// Pad each trace with the padding rows of its AIR, to the heights chosen by `policy`.
+pub fn pad_traces<F, A>(airs_and_traces: &mut [(A, RowMajorMatrix<F>)], policy: PaddingPolicy)
+where
+    F: Field,
+    A: TraceSizer<F>,
+{
+    let heights: Vec<usize> = airs_and_traces
+        .iter()
+        .map(|(air, trace)| air.padded_height(trace.height()))
+        .collect();
+    let shared_height = match policy {
+        PaddingPolicy::Minimal => None,
+        PaddingPolicy::SharedMaximum => heights.iter().copied().max(),
+        PaddingPolicy::Fixed(height) => Some(height),
+    };
+    for ((air, trace), height) in airs_and_traces.iter_mut().zip(heights) {
+        pad_trace(air, trace, shared_height.unwrap_or(height));
+    }
+}
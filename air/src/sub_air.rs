@@ -0,0 +1,243 @@
+//! Composition of AIRs, by evaluating each sub-AIR on its own columns of a larger trace.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, ExtensionBuilder, PairBuilder,
+    PeriodicAirBuilder, PermutationAirBuilder,
+};
+
+/// A builder through which a sub-AIR constrains some of the columns of its parent's trace.
+///
+/// The `i`-th column of the sub-AIR's main trace is the `columns[i]`-th column of the parent's, so
+/// a sub-AIR can be laid out anywhere in the parent's trace, and the same sub-AIR can be evaluated
+/// several times on different columns. Likewise for the preprocessed trace and for the periodic
+/// columns, which are by default those of the parent. Public values and the permutation trace are
+/// shared with the parent.
+pub struct SubAirBuilder<'a, AB: AirBuilder> {
+    pub inner: &'a mut AB,
+    columns: Vec<usize>,
+    preprocessed_columns: Option<Vec<usize>>,
+    periodic_columns: Option<Range<usize>>,
+}
+
+impl<'a, AB: AirBuilder> SubAirBuilder<'a, AB> {
+    pub fn new<I: IntoIterator<Item = usize>>(inner: &'a mut AB, columns: I) -> Self {
+        Self {
+            inner,
+            columns: columns.into_iter().collect(),
+            preprocessed_columns: None,
+            periodic_columns: None,
+        }
+    }
+
+    /// Restricts the preprocessed trace seen by the sub-AIR to the given columns of the parent's.
+    #[must_use]
+    pub fn with_preprocessed_columns<I: IntoIterator<Item = usize>>(mut self, columns: I) -> Self {
+        self.preprocessed_columns = Some(columns.into_iter().collect());
+        self
+    }
+
+    /// Restricts the periodic columns seen by the sub-AIR to the given range of the parent's.
+    #[must_use]
+    pub fn with_periodic_columns(mut self, columns: Range<usize>) -> Self {
+        self.periodic_columns = Some(columns);
+        self
+    }
+
+    /// Evaluates `air` on the columns of this builder.
+    pub fn eval<A: Air<Self>>(mut self, air: &A) {
+        debug_assert_eq!(air.width(), self.columns.len());
+        air.eval(&mut self);
+    }
+}
+
+/// The columns `columns` of every row of `matrix`.
+fn select_columns<T, M>(matrix: &M, columns: &[usize]) -> RowMajorMatrix<T>
+where
+    T: Clone + Send + Sync,
+    M: Matrix<T>,
+{
+    let values = (0..matrix.height())
+        .flat_map(|r| columns.iter().map(move |&c| matrix.get(r, c)))
+        .collect();
+    RowMajorMatrix::new(values, columns.len())
+}
+
+impl<AB: AirBuilder> AirBuilder for SubAirBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = RowMajorMatrix<AB::Var>;
+
+    fn main(&self) -> Self::M {
+        select_columns(&self.inner.main(), &self.columns)
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.inner.is_first_row()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.inner.is_last_row()
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        self.inner.is_transition_window(size)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> AirBuilderWithPublicValues for SubAirBuilder<'_, AB> {
+    type PublicVar = AB::PublicVar;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        self.inner.public_values()
+    }
+}
+
+impl<AB: PeriodicAirBuilder> PeriodicAirBuilder for SubAirBuilder<'_, AB> {
+    type PeriodicVar = AB::PeriodicVar;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        let values = self.inner.periodic_values();
+        match &self.periodic_columns {
+            Some(columns) => &values[columns.clone()],
+            None => values,
+        }
+    }
+}
+
+impl<AB: PairBuilder> PairBuilder for SubAirBuilder<'_, AB> {
+    fn preprocessed(&self) -> Self::M {
+        let preprocessed = self.inner.preprocessed();
+        match &self.preprocessed_columns {
+            Some(columns) => select_columns(&preprocessed, columns),
+            None => select_columns(
+                &preprocessed,
+                &(0..preprocessed.width()).collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+impl<AB: ExtensionBuilder> ExtensionBuilder for SubAirBuilder<'_, AB> {
+    type EF = AB::EF;
+    type ExprEF = AB::ExprEF;
+    type VarEF = AB::VarEF;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.inner.assert_zero_ext(x);
+    }
+}
+
+impl<AB: PermutationAirBuilder> PermutationAirBuilder for SubAirBuilder<'_, AB> {
+    type MP = AB::MP;
+
+    type RandomVar = AB::RandomVar;
+
+    fn permutation(&self) -> Self::MP {
+        self.inner.permutation()
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.inner.permutation_randomness()
+    }
+}
+
+/// Assigns consecutive columns of a trace to the sub-AIRs laid out in it.
+#[derive(Clone, Debug, Default)]
+pub struct ColumnLayout {
+    width: usize,
+}
+
+impl ColumnLayout {
+    pub const fn new() -> Self {
+        Self { width: 0 }
+    }
+
+    /// Reserves the next `width` columns.
+    pub fn allocate(&mut self, width: usize) -> Range<usize> {
+        let columns = self.width..self.width + width;
+        self.width += width;
+        columns
+    }
+
+    /// Reserves the next `air.width()` columns, for the trace of `air`.
+    pub fn allocate_air<F, A: BaseAir<F>>(&mut self, air: &A) -> Range<usize> {
+        self.allocate(air.width())
+    }
+
+    /// The number of columns reserved so far.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+}
+
+/// Two AIRs side by side, whose trace is the trace of `left` followed by the columns of the trace
+/// of `right`. Combinations of more AIRs can be built by nesting.
+///
+/// Neither AIR may have a preprocessed trace or periodic columns; AIRs which do can instead be laid
+/// out by hand with `SubAirBuilder`.
+#[derive(Clone, Copy, Debug)]
+pub struct HorizontalAir<L, R> {
+    pub left: L,
+    pub right: R,
+}
+
+impl<L, R> HorizontalAir<L, R> {
+    pub const fn new(left: L, right: R) -> Self {
+        Self { left, right }
+    }
+}
+
+impl<F, L: BaseAir<F>, R: BaseAir<F>> BaseAir<F> for HorizontalAir<L, R> {
+    fn width(&self) -> usize {
+        self.left.width() + self.right.width()
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        assert!(
+            self.left.preprocessed_trace().is_none() && self.right.preprocessed_trace().is_none(),
+            "horizontally composed AIRs cannot have preprocessed traces"
+        );
+        None
+    }
+
+    fn window_size(&self) -> usize {
+        self.left.window_size().max(self.right.window_size())
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        assert!(
+            self.left.periodic_columns().is_empty() && self.right.periodic_columns().is_empty(),
+            "horizontally composed AIRs cannot have periodic columns"
+        );
+        Vec::new()
+    }
+}
+
+impl<AB, L, R> Air<AB> for HorizontalAir<L, R>
+where
+    AB: AirBuilder,
+    L: for<'a> Air<SubAirBuilder<'a, AB>>,
+    R: for<'a> Air<SubAirBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let mut layout = ColumnLayout::new();
+        let left_columns = layout.allocate_air(&self.left);
+        let right_columns = layout.allocate_air(&self.right);
+        SubAirBuilder::new(builder, left_columns).eval(&self.left);
+        SubAirBuilder::new(builder, right_columns).eval(&self.right);
+    }
+}
@@ -1,5 +1,7 @@
 //! A collection of utility functions helpful in defining AIR's.
 
+use alloc::vec;
+use alloc::vec::Vec;
 use core::array;
 
 use p3_field::{Field, FieldAlgebra};
@@ -24,6 +26,19 @@ where
     output
 }
 
+/// A periodic column, as returned by `BaseAir::periodic_columns`, which is one on the rows `i` with
+/// `i % period == phase` and zero on all others.
+///
+/// This selects e.g. the first row of each operation in an AIR which spreads operations over
+/// `period` rows. The period must be a power of two.
+pub fn periodic_selector<F: Field>(period: usize, phase: usize) -> Vec<F> {
+    assert!(period.is_power_of_two());
+    assert!(phase < period);
+    let mut column = vec![F::ZERO; period];
+    column[phase] = F::ONE;
+    column
+}
+
 /// Computes the arithmetic generalization of boolean `xor`.
 ///
 /// For boolean inputs, `x ^ y = x + y - 2 xy`.
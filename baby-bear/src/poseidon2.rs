@@ -261,6 +261,7 @@ impl InternalLayerParameters<BabyBearParameters, 24> for BabyBearInternalLayerPa
 #[cfg(test)]
 mod tests {
     use p3_field::FieldAlgebra;
+    use p3_poseidon2::Poseidon2Params;
     use p3_symmetric::Permutation;
     use rand::{Rng, SeedableRng};
     use rand_xoshiro::Xoroshiro128Plus;
@@ -366,4 +367,67 @@ mod tests {
 
         assert_eq!(input1, input2);
     }
+
+    /// Export the parameters of the permutation, import them back, and check that the imported
+    /// permutation is the same.
+    #[test]
+    fn test_poseidon2_params_round_trip() {
+        let params =
+            Poseidon2Params::<F, 16>::new_from_rng_128::<GenericPoseidon2LinearLayersBabyBear, _>(
+                BABYBEAR_S_BOX_DEGREE,
+                &mut Xoroshiro128Plus::seed_from_u64(1),
+            );
+        assert_eq!(params.rounds_f(), 8);
+        assert_eq!(params.rounds_p(), 13);
+        assert_eq!(params.mat_internal_diag_m_1[0], -F::TWO);
+
+        let json = serde_json::to_string(&params).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["field_modulus"], "2013265921");
+        assert_eq!(value["width"], 16);
+        assert_eq!(
+            value["external_mat4"][0],
+            serde_json::json!(["2", "3", "1", "1"])
+        );
+
+        let imported: Poseidon2Params<F, 16> = serde_json::from_str(&json).unwrap();
+        assert_eq!(imported, params);
+
+        let mut rng = rand::thread_rng();
+        let input: [F; 16] = rng.gen();
+        let expected =
+            Poseidon2BabyBear::<16>::new_from_rng_128(&mut Xoroshiro128Plus::seed_from_u64(1))
+                .permute(input);
+        assert_eq!(
+            Poseidon2BabyBear::<16>::from_params(&imported).permute(input),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_poseidon2_params_import_errors() {
+        let params =
+            Poseidon2Params::<F, 16>::new_from_rng_128::<GenericPoseidon2LinearLayersBabyBear, _>(
+                BABYBEAR_S_BOX_DEGREE,
+                &mut Xoroshiro128Plus::seed_from_u64(1),
+            );
+        let value = serde_json::to_value(&params).unwrap();
+        let import = |edit: fn(&mut serde_json::Value)| {
+            let mut value = value.clone();
+            edit(&mut value);
+            serde_json::from_value::<Poseidon2Params<F, 16>>(value)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let err = import(|v| v["field_modulus"] = "2130706433".into());
+        assert!(err.contains("expected field modulus 2013265921"), "{err}");
+        let err = import(|v| v["internal_constants"][0] = "2013265921".into());
+        assert!(err.contains("invalid field element"), "{err}");
+        let err = import(|v| v["internal_constants"][0] = "0012".into());
+        assert!(err.contains("invalid field element"), "{err}");
+        let err = import(|v| v["rounds_p"] = 14.into());
+        assert!(err.contains("in internal_constants"), "{err}");
+        assert!(serde_json::from_value::<Poseidon2Params<F, 24>>(value).is_err());
+    }
 }
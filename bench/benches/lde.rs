@@ -0,0 +1,60 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use p3_baby_bear::BabyBear;
+use p3_bench::BenchAir;
+use p3_circle::{CircleDomain, CircleEvaluations};
+use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_field::TwoAdicField;
+use p3_goldilocks::Goldilocks;
+use p3_koala_bear::KoalaBear;
+use p3_mersenne_31::Mersenne31;
+
+const LOG_HEIGHT: usize = 16;
+const WIDTH: usize = 64;
+
+/// The blowup of the benchmark FRI config.
+const LOG_BLOWUP: usize = 1;
+
+fn bench_lde(criterion: &mut Criterion) {
+    bench_two_adic_lde::<BabyBear>(criterion, "BabyBear");
+    bench_two_adic_lde::<KoalaBear>(criterion, "KoalaBear");
+    bench_circle_lde(criterion);
+    bench_two_adic_lde::<Goldilocks>(criterion, "Goldilocks");
+}
+
+fn bench_two_adic_lde<F: TwoAdicField>(criterion: &mut Criterion, name: &str) {
+    let dft = Radix2DitParallel::<F>::default();
+    let trace = BenchAir::new(WIDTH).generate_trace::<F>(1 << LOG_HEIGHT);
+
+    let mut group = criterion.benchmark_group("lde");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((WIDTH << LOG_HEIGHT) as u64));
+    group.bench_function(BenchmarkId::new(name, 1 << LOG_HEIGHT), |b| {
+        b.iter_batched(
+            || trace.clone(),
+            |trace| dft.coset_lde_batch(trace, LOG_BLOWUP, F::GENERATOR),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_circle_lde(criterion: &mut Criterion) {
+    let trace = BenchAir::new(WIDTH).generate_trace::<Mersenne31>(1 << LOG_HEIGHT);
+    let domain = CircleDomain::standard(LOG_HEIGHT);
+    let lde_domain = CircleDomain::standard(LOG_HEIGHT + LOG_BLOWUP);
+
+    let mut group = criterion.benchmark_group("lde");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((WIDTH << LOG_HEIGHT) as u64));
+    group.bench_function(BenchmarkId::new("Mersenne31", 1 << LOG_HEIGHT), |b| {
+        b.iter_batched(
+            || trace.clone(),
+            |trace| CircleEvaluations::from_natural_order(domain, trace).extrapolate(lde_domain),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_lde);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use p3_bench::{for_each_combination, BenchAir, Combination, CombinationVisitor};
+use p3_commit::Mmcs;
+use p3_uni_stark::Val;
+
+const LOG_HEIGHT: usize = 16;
+const WIDTH: usize = 64;
+
+struct MerkleBenches<'a> {
+    criterion: &'a mut Criterion,
+}
+
+impl CombinationVisitor for MerkleBenches<'_> {
+    fn visit<C: Combination>(&mut self) {
+        let mmcs = C::val_mmcs();
+        let matrix = BenchAir::new(WIDTH).generate_trace::<Val<C::Config>>(1 << LOG_HEIGHT);
+
+        let mut group = self.criterion.benchmark_group("merkle");
+        group.sample_size(10);
+        group.throughput(Throughput::Elements((WIDTH << LOG_HEIGHT) as u64));
+        group.bench_function(BenchmarkId::new(C::NAME, 1 << LOG_HEIGHT), |b| {
+            b.iter_batched(
+                || matrix.clone(),
+                |matrix| mmcs.commit_matrix(matrix),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+fn bench_merkle(criterion: &mut Criterion) {
+    for_each_combination(&mut MerkleBenches { criterion });
+}
+
+criterion_group!(benches, bench_merkle);
+criterion_main!(benches);
@@ -0,0 +1,137 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_blake3::Blake3;
+use p3_field::{Field, FieldAlgebra, PackedValue, PrimeField32, PrimeField64};
+use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_keccak::{Keccak256Hash, KeccakF};
+use p3_koala_bear::{KoalaBear, Poseidon2KoalaBear};
+use p3_mersenne_31::{Mersenne31, Poseidon2Mersenne31};
+use p3_symmetric::{
+    CryptographicHasher, PaddingFreeSponge, Permutation, SerializingHasher32, SerializingHasher64,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// The number of field elements hashed at once.
+const HASH_LEN: usize = 1 << 10;
+
+fn bench_permutations(criterion: &mut Criterion) {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    bench_poseidon2::<BabyBear, _, 16>(
+        criterion,
+        "BabyBear",
+        Poseidon2BabyBear::<16>::new_from_rng_128(&mut rng),
+    );
+    bench_poseidon2::<KoalaBear, _, 16>(
+        criterion,
+        "KoalaBear",
+        Poseidon2KoalaBear::<16>::new_from_rng_128(&mut rng),
+    );
+    bench_poseidon2::<Mersenne31, _, 16>(
+        criterion,
+        "Mersenne31",
+        Poseidon2Mersenne31::<16>::new_from_rng_128(&mut rng),
+    );
+    bench_poseidon2::<Goldilocks, _, 8>(
+        criterion,
+        "Goldilocks",
+        Poseidon2Goldilocks::<8>::new_from_rng_128(&mut rng),
+    );
+
+    let mut group = criterion.benchmark_group("permutation");
+    group.throughput(Throughput::Bytes(200));
+    group.bench_function(BenchmarkId::new("Keccak-f", 25), |b| {
+        let mut state = [0u64; 25];
+        b.iter(|| KeccakF.permute_mut(black_box(&mut state)))
+    });
+    group.finish();
+}
+
+/// The throughput of a packed Poseidon2 permutation, in field elements.
+fn bench_poseidon2<F, Perm, const WIDTH: usize>(criterion: &mut Criterion, name: &str, perm: Perm)
+where
+    F: Field,
+    Perm: Permutation<[F::Packing; WIDTH]>,
+{
+    let mut group = criterion.benchmark_group("permutation");
+    group.throughput(Throughput::Elements((WIDTH * F::Packing::WIDTH) as u64));
+    group.bench_function(BenchmarkId::new(format!("{name}-Poseidon2"), WIDTH), |b| {
+        let mut state = [F::Packing::ZERO; WIDTH];
+        b.iter(|| perm.permute_mut(black_box(&mut state)))
+    });
+    group.finish();
+}
+
+fn bench_hashes(criterion: &mut Criterion) {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    bench_32_bit_hashes::<BabyBear>(criterion, "BabyBear");
+    bench_hash::<BabyBear, _, _, 8>(
+        criterion,
+        "BabyBear-Poseidon2",
+        PaddingFreeSponge::<_, 16, 8, 8>::new(Poseidon2BabyBear::<16>::new_from_rng_128(&mut rng)),
+    );
+    bench_32_bit_hashes::<KoalaBear>(criterion, "KoalaBear");
+    bench_hash::<KoalaBear, _, _, 8>(
+        criterion,
+        "KoalaBear-Poseidon2",
+        PaddingFreeSponge::<_, 16, 8, 8>::new(Poseidon2KoalaBear::<16>::new_from_rng_128(&mut rng)),
+    );
+    bench_32_bit_hashes::<Mersenne31>(criterion, "Mersenne31");
+    bench_hash::<Mersenne31, _, _, 8>(
+        criterion,
+        "Mersenne31-Poseidon2",
+        PaddingFreeSponge::<_, 16, 8, 8>::new(Poseidon2Mersenne31::<16>::new_from_rng_128(
+            &mut rng,
+        )),
+    );
+    bench_64_bit_hashes::<Goldilocks>(criterion, "Goldilocks");
+    bench_hash::<Goldilocks, _, _, 4>(
+        criterion,
+        "Goldilocks-Poseidon2",
+        PaddingFreeSponge::<_, 8, 4, 4>::new(Poseidon2Goldilocks::<8>::new_from_rng_128(&mut rng)),
+    );
+}
+
+fn bench_32_bit_hashes<F: PrimeField32>(criterion: &mut Criterion, name: &str) {
+    bench_hash::<F, _, _, 32>(
+        criterion,
+        &format!("{name}-Blake3"),
+        SerializingHasher32::new(Blake3 {}),
+    );
+    bench_hash::<F, _, _, 32>(
+        criterion,
+        &format!("{name}-Keccak"),
+        SerializingHasher32::new(Keccak256Hash {}),
+    );
+}
+
+fn bench_64_bit_hashes<F: PrimeField64>(criterion: &mut Criterion, name: &str) {
+    bench_hash::<F, _, _, 32>(
+        criterion,
+        &format!("{name}-Blake3"),
+        SerializingHasher64::new(Blake3 {}),
+    );
+    bench_hash::<F, _, _, 32>(
+        criterion,
+        &format!("{name}-Keccak"),
+        SerializingHasher64::new(Keccak256Hash {}),
+    );
+}
+
+/// The throughput of hashing `HASH_LEN` field elements, as the leaves of a Merkle tree are.
+fn bench_hash<F, H, D, const OUT: usize>(criterion: &mut Criterion, name: &str, hasher: H)
+where
+    F: Field,
+    H: CryptographicHasher<F, [D; OUT]>,
+{
+    let input: Vec<F> = (0..HASH_LEN).map(F::from_canonical_usize).collect();
+    let mut group = criterion.benchmark_group("hash");
+    group.throughput(Throughput::Elements(HASH_LEN as u64));
+    group.bench_function(BenchmarkId::new(name, HASH_LEN), |b| {
+        b.iter(|| hasher.hash_slice(black_box(&input)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_permutations, bench_hashes);
+criterion_main!(benches);
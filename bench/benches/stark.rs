@@ -0,0 +1,73 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use p3_bench::{for_each_combination, BenchAir, Combination, CombinationVisitor};
+use p3_uni_stark::{prove, prove_with_metrics, verify, Val};
+
+const LOG_HEIGHT: usize = 14;
+const WIDTH: usize = 64;
+
+struct StarkBenches<'a> {
+    criterion: &'a mut Criterion,
+}
+
+impl CombinationVisitor for StarkBenches<'_> {
+    fn visit<C: Combination>(&mut self) {
+        let air = BenchAir::new(WIDTH);
+        let config = C::config();
+        let trace = air.generate_trace::<Val<C::Config>>(1 << LOG_HEIGHT);
+        let id = BenchmarkId::new(C::NAME, 1 << LOG_HEIGHT);
+
+        // The quotient is evaluated as part of proving, and timed by `prove_with_metrics`.
+        let mut group = self.criterion.benchmark_group("quotient");
+        group.sample_size(10);
+        group.throughput(Throughput::Elements((WIDTH << LOG_HEIGHT) as u64));
+        group.bench_function(id.clone(), |b| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| {
+                        let (_, metrics) = prove_with_metrics(
+                            &config,
+                            &air,
+                            &mut C::challenger(),
+                            trace.clone(),
+                            &[],
+                        );
+                        metrics
+                            .quotient
+                            .duration
+                            .expect("stages are timed with the std feature")
+                    })
+                    .sum()
+            })
+        });
+        group.finish();
+
+        let mut group = self.criterion.benchmark_group("prove");
+        group.sample_size(10);
+        group.throughput(Throughput::Elements((WIDTH << LOG_HEIGHT) as u64));
+        group.bench_function(id.clone(), |b| {
+            b.iter_batched(
+                || trace.clone(),
+                |trace| prove(&config, &air, &mut C::challenger(), trace, &[]),
+                BatchSize::LargeInput,
+            )
+        });
+        group.finish();
+
+        let proof = prove(&config, &air, &mut C::challenger(), trace, &[]);
+        let mut group = self.criterion.benchmark_group("verify");
+        group.bench_function(id, |b| {
+            b.iter(|| {
+                verify(&config, &air, &mut C::challenger(), &proof, &[])
+                    .expect("verification failed")
+            })
+        });
+        group.finish();
+    }
+}
+
+fn bench_stark(criterion: &mut Criterion) {
+    for_each_combination(&mut StarkBenches { criterion });
+}
+
+criterion_group!(benches, bench_stark);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// An AIR of `width` columns, each of which is cubed and added to the next column in every
+/// transition, so that its constraints have degree 3 like those of typical hash AIRs.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchAir {
+    pub width: usize,
+}
+
+impl BenchAir {
+    pub const fn new(width: usize) -> Self {
+        Self { width }
+    }
+
+    /// A trace of `height` rows satisfying the constraints.
+    pub fn generate_trace<F: Field>(&self, height: usize) -> RowMajorMatrix<F> {
+        let w = self.width;
+        let mut values = F::zero_vec(height * w);
+        for (i, value) in values[..w].iter_mut().enumerate() {
+            *value = F::from_canonical_usize(i + 1);
+        }
+        for row in 1..height {
+            let (previous, current) = values[(row - 1) * w..(row + 1) * w].split_at_mut(w);
+            for i in 0..w {
+                current[i] = previous[i].cube() + previous[(i + 1) % w];
+            }
+        }
+        RowMajorMatrix::new(values, w)
+    }
+}
+
+impl<F> BaseAir<F> for BenchAir {
+    fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for BenchAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let mut when_transition = builder.when_transition();
+        for i in 0..self.width {
+            let x: AB::Expr = local[i].into();
+            when_transition.assert_eq(next[i], x.cube() + local[(i + 1) % self.width]);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Prints the results of the benchmarks of `p3-bench`, one JSON object per line.
+//!
+//! Reads the latest results Criterion saved in the given directory, `target/criterion` by default,
+//! so it should be run after `cargo bench -p p3-bench`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+fn main() {
+    let root = std::env::args()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("target/criterion"), PathBuf::from);
+
+    let mut benchmarks = Vec::new();
+    find_benchmarks(&root, &mut benchmarks);
+    benchmarks.sort();
+
+    for dir in benchmarks {
+        match summarize(&dir) {
+            Some(summary) => println!("{summary}"),
+            None => eprintln!("skipping malformed results in {}", dir.display()),
+        }
+    }
+}
+
+/// Collects every `new` directory below `dir` holding the latest results of a benchmark.
+fn find_benchmarks(dir: &Path, benchmarks: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if path.file_name().is_some_and(|name| name == "new")
+            && path.join("benchmark.json").is_file()
+        {
+            benchmarks.push(path);
+        } else {
+            find_benchmarks(&path, benchmarks);
+        }
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// The id, timings in nanoseconds and throughput of the benchmark whose results are in `dir`.
+fn summarize(dir: &Path) -> Option<Value> {
+    let benchmark = read_json(&dir.join("benchmark.json"))?;
+    let estimates = read_json(&dir.join("estimates.json"))?;
+    let point_estimate = |statistic: &str| estimates[statistic]["point_estimate"].as_f64();
+
+    Some(json!({
+        "id": benchmark["full_id"],
+        "group": benchmark["group_id"],
+        "function": benchmark["function_id"],
+        "parameter": benchmark["value_str"],
+        "mean_ns": point_estimate("mean")?,
+        "median_ns": point_estimate("median")?,
+        "std_dev_ns": point_estimate("std_dev")?,
+        "throughput": benchmark["throughput"],
+    }))
+}
@@ -0,0 +1,322 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_blake3::Blake3;
+use p3_challenger::{
+    DuplexChallenger, HashChallenger, SerializingChallenger32, SerializingChallenger64,
+};
+use p3_circle::CirclePcs;
+use p3_commit::{ExtensionMmcs, Mmcs};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, TwoAdicField};
+use p3_fri::{create_benchmark_fri_config, TwoAdicFriPcs};
+use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+use p3_keccak::Keccak256Hash;
+use p3_koala_bear::{KoalaBear, Poseidon2KoalaBear};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_mersenne_31::{Mersenne31, Poseidon2Mersenne31};
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, SerializingHasher64,
+    TruncatedPermutation,
+};
+use p3_uni_stark::{StarkConfig, StarkGenericConfig, Val};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// A field and hash of the benchmark matrix, with a STARK config over the field, whose Merkle
+/// trees and transcript use the hash.
+///
+/// Each config uses the FRI parameters of `create_benchmark_fri_config`. Poseidon2 is set up with
+/// fixed round constants, so that every config and challenger of a combination agree.
+pub trait Combination {
+    /// The name of the combination in benchmark identifiers, e.g. `BabyBear-Poseidon2`.
+    const NAME: &'static str;
+
+    type ValMmcs: Mmcs<Val<Self::Config>>;
+    type Config: StarkGenericConfig;
+
+    fn val_mmcs() -> Self::ValMmcs;
+
+    fn config() -> Self::Config;
+
+    /// A fresh challenger for proving or verifying with `config()`.
+    fn challenger() -> <Self::Config as StarkGenericConfig>::Challenger;
+}
+
+/// Code run for each `Combination`, by `for_each_combination`.
+pub trait CombinationVisitor {
+    fn visit<C: Combination>(&mut self);
+}
+
+/// Visit every combination of field and hash, field by field.
+pub fn for_each_combination(visitor: &mut impl CombinationVisitor) {
+    visitor.visit::<BabyBearPoseidon2>();
+    visitor.visit::<BabyBearBlake3>();
+    visitor.visit::<BabyBearKeccak>();
+    visitor.visit::<KoalaBearPoseidon2>();
+    visitor.visit::<KoalaBearBlake3>();
+    visitor.visit::<KoalaBearKeccak>();
+    visitor.visit::<Mersenne31Poseidon2>();
+    visitor.visit::<Mersenne31Blake3>();
+    visitor.visit::<Mersenne31Keccak>();
+    visitor.visit::<GoldilocksPoseidon2>();
+    visitor.visit::<GoldilocksBlake3>();
+    visitor.visit::<GoldilocksKeccak>();
+}
+
+/// A Merkle tree hashing with a Poseidon2 sponge, and compressing with the truncated permutation.
+pub type Poseidon2Mmcs<F, Perm, const WIDTH: usize, const RATE: usize, const DIGEST: usize> =
+    MerkleTreeMmcs<
+        <F as Field>::Packing,
+        <F as Field>::Packing,
+        PaddingFreeSponge<Perm, WIDTH, RATE, DIGEST>,
+        TruncatedPermutation<Perm, 2, DIGEST, WIDTH>,
+        DIGEST,
+    >;
+
+/// A Merkle tree hashing the serialized field elements with a byte hash such as Keccak.
+pub type ByteHashMmcs<F, FieldHash, H> =
+    MerkleTreeMmcs<F, u8, FieldHash, CompressionFunctionFromHasher<H, 2, 32>, 32>;
+
+pub type TwoAdicPcs<F, Challenge, ValMmcs> =
+    TwoAdicFriPcs<F, Radix2DitParallel<F>, ValMmcs, ExtensionMmcs<F, Challenge, ValMmcs>>;
+
+pub type CircleFriPcs<F, Challenge, ValMmcs> =
+    CirclePcs<F, ValMmcs, ExtensionMmcs<F, Challenge, ValMmcs>>;
+
+fn two_adic_pcs<F: TwoAdicField, Challenge, ValMmcs: Clone>(
+    mmcs: ValMmcs,
+) -> TwoAdicPcs<F, Challenge, ValMmcs> {
+    let fri_config = create_benchmark_fri_config(ExtensionMmcs::new(mmcs.clone()));
+    TwoAdicFriPcs::new(Radix2DitParallel::default(), mmcs, fri_config)
+}
+
+fn circle_pcs<F: Field, Challenge, ValMmcs: Clone>(
+    mmcs: ValMmcs,
+) -> CircleFriPcs<F, Challenge, ValMmcs> {
+    let fri_config = create_benchmark_fri_config(ExtensionMmcs::new(mmcs.clone()));
+    CirclePcs::new(mmcs, fri_config)
+}
+
+/// The RNG from which the Poseidon2 round constants are drawn.
+fn constants_rng() -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(0)
+}
+
+macro_rules! poseidon2_combination {
+    (
+        $combination:ident, $name:literal, $field:ty, $challenge:ty, $pcs:ident, $new_pcs:ident,
+        $perm:ty, $width:literal, $rate:literal, $digest:literal
+    ) => {
+        #[derive(Debug)]
+        pub struct $combination;
+
+        impl Combination for $combination {
+            const NAME: &'static str = $name;
+
+            type ValMmcs = Poseidon2Mmcs<$field, $perm, $width, $rate, $digest>;
+            type Config = StarkConfig<
+                $pcs<$field, $challenge, Self::ValMmcs>,
+                $challenge,
+                DuplexChallenger<$field, $perm, $width, $rate>,
+            >;
+
+            fn val_mmcs() -> Self::ValMmcs {
+                let perm = <$perm>::new_from_rng_128(&mut constants_rng());
+                MerkleTreeMmcs::new(
+                    PaddingFreeSponge::new(perm.clone()),
+                    TruncatedPermutation::new(perm),
+                )
+            }
+
+            fn config() -> Self::Config {
+                StarkConfig::new($new_pcs(Self::val_mmcs()))
+            }
+
+            fn challenger() -> <Self::Config as StarkGenericConfig>::Challenger {
+                DuplexChallenger::new(<$perm>::new_from_rng_128(&mut constants_rng()))
+            }
+        }
+    };
+}
+
+macro_rules! byte_hash_combination {
+    (
+        $combination:ident, $name:literal, $field:ty, $challenge:ty, $pcs:ident, $new_pcs:ident,
+        $hash:ident, $field_hash:ident, $challenger:ident
+    ) => {
+        #[derive(Debug)]
+        pub struct $combination;
+
+        impl Combination for $combination {
+            const NAME: &'static str = $name;
+
+            type ValMmcs = ByteHashMmcs<$field, $field_hash<$hash>, $hash>;
+            type Config = StarkConfig<
+                $pcs<$field, $challenge, Self::ValMmcs>,
+                $challenge,
+                $challenger<$field, HashChallenger<u8, $hash, 32>>,
+            >;
+
+            fn val_mmcs() -> Self::ValMmcs {
+                MerkleTreeMmcs::new(
+                    $field_hash::new($hash {}),
+                    CompressionFunctionFromHasher::new($hash {}),
+                )
+            }
+
+            fn config() -> Self::Config {
+                StarkConfig::new($new_pcs(Self::val_mmcs()))
+            }
+
+            fn challenger() -> <Self::Config as StarkGenericConfig>::Challenger {
+                $challenger::from_hasher(vec![], $hash {})
+            }
+        }
+    };
+}
+
+type BabyBearChallenge = BinomialExtensionField<BabyBear, 4>;
+type KoalaBearChallenge = BinomialExtensionField<KoalaBear, 4>;
+type Mersenne31Challenge = BinomialExtensionField<Mersenne31, 3>;
+type GoldilocksChallenge = BinomialExtensionField<Goldilocks, 2>;
+
+poseidon2_combination!(
+    BabyBearPoseidon2,
+    "BabyBear-Poseidon2",
+    BabyBear,
+    BabyBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Poseidon2BabyBear<16>,
+    16,
+    8,
+    8
+);
+byte_hash_combination!(
+    BabyBearBlake3,
+    "BabyBear-Blake3",
+    BabyBear,
+    BabyBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Blake3,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+byte_hash_combination!(
+    BabyBearKeccak,
+    "BabyBear-Keccak",
+    BabyBear,
+    BabyBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Keccak256Hash,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+
+poseidon2_combination!(
+    KoalaBearPoseidon2,
+    "KoalaBear-Poseidon2",
+    KoalaBear,
+    KoalaBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Poseidon2KoalaBear<16>,
+    16,
+    8,
+    8
+);
+byte_hash_combination!(
+    KoalaBearBlake3,
+    "KoalaBear-Blake3",
+    KoalaBear,
+    KoalaBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Blake3,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+byte_hash_combination!(
+    KoalaBearKeccak,
+    "KoalaBear-Keccak",
+    KoalaBear,
+    KoalaBearChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Keccak256Hash,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+
+// Mersenne31 is not two-adic, so it is proven with the circle PCS.
+poseidon2_combination!(
+    Mersenne31Poseidon2,
+    "Mersenne31-Poseidon2",
+    Mersenne31,
+    Mersenne31Challenge,
+    CircleFriPcs,
+    circle_pcs,
+    Poseidon2Mersenne31<16>,
+    16,
+    8,
+    8
+);
+byte_hash_combination!(
+    Mersenne31Blake3,
+    "Mersenne31-Blake3",
+    Mersenne31,
+    Mersenne31Challenge,
+    CircleFriPcs,
+    circle_pcs,
+    Blake3,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+byte_hash_combination!(
+    Mersenne31Keccak,
+    "Mersenne31-Keccak",
+    Mersenne31,
+    Mersenne31Challenge,
+    CircleFriPcs,
+    circle_pcs,
+    Keccak256Hash,
+    SerializingHasher32,
+    SerializingChallenger32
+);
+
+poseidon2_combination!(
+    GoldilocksPoseidon2,
+    "Goldilocks-Poseidon2",
+    Goldilocks,
+    GoldilocksChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Poseidon2Goldilocks<8>,
+    8,
+    4,
+    4
+);
+byte_hash_combination!(
+    GoldilocksBlake3,
+    "Goldilocks-Blake3",
+    Goldilocks,
+    GoldilocksChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Blake3,
+    SerializingHasher64,
+    SerializingChallenger64
+);
+byte_hash_combination!(
+    GoldilocksKeccak,
+    "Goldilocks-Keccak",
+    Goldilocks,
+    GoldilocksChallenge,
+    TwoAdicPcs,
+    two_adic_pcs,
+    Keccak256Hash,
+    SerializingHasher64,
+    SerializingChallenger64
+);
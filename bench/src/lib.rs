@@ -0,0 +1,20 @@
+//! Benchmarks spanning the supported fields and hashes, so that performance can be tracked across
+//! the whole matrix of (BabyBear, KoalaBear, Mersenne31, Goldilocks) × (Poseidon2, Blake3, Keccak)
+//! rather than per crate.
+//!
+//! The benches are
+//! - `primitives`: the throughput of each permutation and hash,
+//! - `lde`: the low-degree extension of a trace, for each field,
+//! - `merkle`: building the Merkle tree of a trace, for each `Combination`,
+//! - `stark`: evaluating the quotient, proving and verifying `BenchAir`, for each `Combination`.
+//!
+//! Each benchmark is identified as `<operation>/<field>-<hash>/<size>`, or `<operation>/<field>/<size>`
+//! for the LDE, which does not hash. After running
+//! `cargo bench -p p3-bench`, `cargo run -p p3-bench --bin collect-benchmarks` prints the results
+//! as JSON lines, one per benchmark, for tracking performance over time.
+
+mod air;
+mod combinations;
+
+pub use air::*;
+pub use combinations::*;
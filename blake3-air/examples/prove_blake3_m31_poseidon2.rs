@@ -1,5 +1,4 @@
 use std::fmt::Debug;
-use std::marker::PhantomData;
 
 use p3_blake3_air::{generate_trace_rows, Blake3Air};
 use p3_challenger::DuplexChallenger;
@@ -58,21 +57,18 @@ fn main() -> Result<(), impl Debug> {
     let fri_config = create_benchmark_fri_config(challenge_mmcs);
 
     type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
-    let pcs = Pcs {
-        mmcs: val_mmcs,
-        fri_config,
-        _phantom: PhantomData,
-    };
+    let pcs = Pcs::new(val_mmcs, fri_config);
 
     type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
     let config = MyConfig::new(pcs);
 
     let inputs = (0..NUM_HASHES).map(|_| random()).collect::<Vec<_>>();
-    let trace = generate_trace_rows::<Val>(inputs);
+    let trace = generate_trace_rows::<Val>(inputs, 0);
 
+    let air: Blake3Air = Blake3Air {};
     let mut challenger = Challenger::new(perm24.clone());
-    let proof = prove(&config, &Blake3Air {}, &mut challenger, trace, &vec![]);
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
 
     let mut challenger = Challenger::new(perm24);
-    verify(&config, &Blake3Air {}, &mut challenger, &proof, &vec![])
+    verify(&config, &air, &mut challenger, &proof, &vec![])
 }
@@ -2,25 +2,37 @@ use alloc::vec::Vec;
 use core::borrow::Borrow;
 
 use itertools::izip;
-use p3_air::utils::{add2, add3, pack_bits_le, xor, xor_32_shift};
+use p3_air::utils::{add2, add3, xor, xor_32_shift};
 use p3_air::{Air, AirBuilder, BaseAir};
 use p3_field::{FieldAlgebra, PrimeField64};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use p3_u32_air_gadgets::pack_limbs;
 use rand::random;
 
-use crate::columns::{Blake3Cols, NUM_BLAKE3_COLS};
-use crate::constants::{permute, BITS_PER_LIMB, IV};
-use crate::{generate_trace_rows, Blake3State, FullRound, QuarterRound};
+use crate::columns::{num_blake3_cols, num_blake3_limb_cols, Blake3Cols, Blake3LimbCols};
+use crate::constants::{permute, IV, NUM_FULL_ROUNDS};
+use crate::{generate_trace_rows_with_rounds, Blake3State, FullRound, QuarterRound};
 
 /// Assumes the field size is at least 16 bits.
+///
+/// Proves compressions with `NUM_ROUNDS` full rounds. This is `7` for Blake-3 itself, and can be
+/// lowered to prove the reduced-round variants.
+///
+/// With `LIMB_ROWS` set, the trace has the narrower layout of `Blake3LimbCols`, whose xors are
+/// looked up in a `ByteLookupAir` on `BLAKE3_BYTE_BUS`, with which it must be proven, e.g. as a
+/// `Blake3LimbTable`. Otherwise the trace has the layout of `Blake3Cols` and no lookups.
 #[derive(Debug)]
-pub struct Blake3Air {}
+pub struct Blake3Air<const NUM_ROUNDS: usize = NUM_FULL_ROUNDS, const LIMB_ROWS: bool = false> {}
 
-impl Blake3Air {
-    pub fn generate_trace_rows<F: PrimeField64>(&self, num_hashes: usize) -> RowMajorMatrix<F> {
+impl<const NUM_ROUNDS: usize> Blake3Air<NUM_ROUNDS> {
+    pub fn generate_trace_rows<F: PrimeField64>(
+        &self,
+        num_hashes: usize,
+        extra_capacity_bits: usize,
+    ) -> RowMajorMatrix<F> {
         let inputs = (0..num_hashes).map(|_| random()).collect::<Vec<_>>();
-        generate_trace_rows(inputs)
+        generate_trace_rows_with_rounds::<F, NUM_ROUNDS>(inputs, extra_capacity_bits)
     }
 
     /// Verify that the quarter round function has been correctly computed.
@@ -36,14 +48,11 @@ impl Blake3Air {
     ) {
         // We need to pack some bits together to verify the additions.
         // First we verify a' = a + b + m_{2i} mod 2^32
-        let b_0_16 = pack_bits_le(trace.b[..BITS_PER_LIMB].iter().copied());
-        let b_16_32 = pack_bits_le(trace.b[BITS_PER_LIMB..].iter().copied());
-
         add3(
             builder,
             trace.a_prime,
             trace.a,
-            &[b_0_16, b_16_32],
+            &pack_limbs(trace.b),
             trace.m_two_i,
         );
 
@@ -52,28 +61,18 @@ impl Blake3Air {
         xor_32_shift(builder, trace.a_prime, trace.d, trace.d_prime, 16);
 
         // Next we verify c' = c + d' mod 2^32
-        let d_prime_0_16 = pack_bits_le(trace.d_prime[..BITS_PER_LIMB].iter().copied());
-        let d_prime_16_32 = pack_bits_le(trace.d_prime[BITS_PER_LIMB..].iter().copied());
-        add2(
-            builder,
-            trace.c_prime,
-            trace.c,
-            &[d_prime_0_16, d_prime_16_32],
-        );
+        add2(builder, trace.c_prime, trace.c, &pack_limbs(trace.d_prime));
 
         // Next we verify that b' = (c' ^ b) >> 12 which is equivalently: c' = b ^ (b' << 12)
         // This also range checks b' and c'.
         xor_32_shift(builder, trace.c_prime, trace.b, trace.b_prime, 12);
 
         // Next we verify a'' = a' + b' + m_{2i + 1} mod 2^32
-        let b_prime_0_16 = pack_bits_le(trace.b_prime[..BITS_PER_LIMB].iter().copied());
-        let b_prime_16_32 = pack_bits_le(trace.b_prime[BITS_PER_LIMB..].iter().copied());
-
         add3(
             builder,
             trace.a_output,
             trace.a_prime,
-            &[b_prime_0_16, b_prime_16_32],
+            &pack_limbs(trace.b_prime),
             trace.m_two_i_plus_one,
         );
 
@@ -83,13 +82,11 @@ impl Blake3Air {
         xor_32_shift(builder, trace.a_output, trace.d_prime, trace.d_output, 8);
 
         // Next we verify c'' = c' + d'' mod 2^32
-        let d_output_0_16 = pack_bits_le(trace.d_output[..BITS_PER_LIMB].iter().copied());
-        let d_output_16_32 = pack_bits_le(trace.d_output[BITS_PER_LIMB..].iter().copied());
         add2(
             builder,
             trace.c_output,
             trace.c_prime,
-            &[d_output_0_16, d_output_16_32],
+            &pack_limbs(trace.d_output),
         );
 
         // Finally we verify that b'' = (c'' ^ b') << 7 which is equivalently: c'' = b' ^ (b'' << 7)
@@ -99,67 +96,6 @@ impl Blake3Air {
         // Assuming all checks pass, a'', b'', c'', d'' are the correct values and have all been range checked.
     }
 
-    /// Given data for a full round, produce the data corresponding to a
-    /// single application of the quarter round function on a column.
-    fn full_round_to_column_quarter_round<'a, T: Copy, U>(
-        &self,
-        input: &'a Blake3State<T>,
-        round_data: &'a FullRound<T>,
-        m_vector: &'a [[U; 2]; 16],
-        index: usize,
-    ) -> QuarterRound<'a, T, U> {
-        QuarterRound {
-            a: &input.row0[index],
-            b: &input.row1[index],
-            c: &input.row2[index],
-            d: &input.row3[index],
-
-            m_two_i: &m_vector[2 * index],
-
-            a_prime: &round_data.state_prime.row0[index],
-            b_prime: &round_data.state_prime.row1[index],
-            c_prime: &round_data.state_prime.row2[index],
-            d_prime: &round_data.state_prime.row3[index],
-
-            m_two_i_plus_one: &m_vector[2 * index + 1],
-
-            a_output: &round_data.state_middle.row0[index],
-            b_output: &round_data.state_middle.row1[index],
-            c_output: &round_data.state_middle.row2[index],
-            d_output: &round_data.state_middle.row3[index],
-        }
-    }
-
-    /// Given data for a full round, produce the data corresponding to a
-    /// single application of the quarter round function on a diagonal.
-    fn full_round_to_diagonal_quarter_round<'a, T: Copy, U>(
-        &self,
-        round_data: &'a FullRound<T>,
-        m_vector: &'a [[U; 2]; 16],
-        index: usize,
-    ) -> QuarterRound<'a, T, U> {
-        QuarterRound {
-            a: &round_data.state_middle.row0[index],
-            b: &round_data.state_middle.row1[(index + 1) % 4],
-            c: &round_data.state_middle.row2[(index + 2) % 4],
-            d: &round_data.state_middle.row3[(index + 3) % 4],
-
-            m_two_i: &m_vector[2 * index + 8],
-
-            a_prime: &round_data.state_middle_prime.row0[index],
-            b_prime: &round_data.state_middle_prime.row1[(index + 1) % 4],
-            c_prime: &round_data.state_middle_prime.row2[(index + 2) % 4],
-            d_prime: &round_data.state_middle_prime.row3[(index + 3) % 4],
-
-            m_two_i_plus_one: &m_vector[2 * index + 9],
-
-            a_output: &round_data.state_output.row0[index],
-            b_output: &round_data.state_output.row1[(index + 1) % 4],
-            c_output: &round_data.state_output.row2[(index + 2) % 4],
-            d_output: &round_data.state_output.row3[(index + 3) % 4],
-        }
-    }
-
     /// Verify a full round of the Blake-3 permutation.
     fn verify_round<AB: AirBuilder>(
         &self,
@@ -218,19 +154,105 @@ impl Blake3Air {
     }
 }
 
-impl<F> BaseAir<F> for Blake3Air {
+impl<const NUM_ROUNDS: usize, const LIMB_ROWS: bool> Blake3Air<NUM_ROUNDS, LIMB_ROWS> {
+    /// Given data for a full round, produce the data corresponding to a
+    /// single application of the quarter round function on a column.
+    pub(crate) fn full_round_to_column_quarter_round<'a, T: Copy, U, W>(
+        &self,
+        input: &'a Blake3State<T, W>,
+        round_data: &'a FullRound<T, W>,
+        m_vector: &'a [[U; 2]; 16],
+        index: usize,
+    ) -> QuarterRound<'a, T, U, W> {
+        QuarterRound {
+            a: &input.row0[index],
+            b: &input.row1[index],
+            c: &input.row2[index],
+            d: &input.row3[index],
+
+            m_two_i: &m_vector[2 * index],
+
+            a_prime: &round_data.state_prime.row0[index],
+            b_prime: &round_data.state_prime.row1[index],
+            c_prime: &round_data.state_prime.row2[index],
+            d_prime: &round_data.state_prime.row3[index],
+
+            m_two_i_plus_one: &m_vector[2 * index + 1],
+
+            a_output: &round_data.state_middle.row0[index],
+            b_output: &round_data.state_middle.row1[index],
+            c_output: &round_data.state_middle.row2[index],
+            d_output: &round_data.state_middle.row3[index],
+        }
+    }
+
+    /// Given data for a full round, produce the data corresponding to a
+    /// single application of the quarter round function on a diagonal.
+    pub(crate) fn full_round_to_diagonal_quarter_round<'a, T: Copy, U, W>(
+        &self,
+        round_data: &'a FullRound<T, W>,
+        m_vector: &'a [[U; 2]; 16],
+        index: usize,
+    ) -> QuarterRound<'a, T, U, W> {
+        QuarterRound {
+            a: &round_data.state_middle.row0[index],
+            b: &round_data.state_middle.row1[(index + 1) % 4],
+            c: &round_data.state_middle.row2[(index + 2) % 4],
+            d: &round_data.state_middle.row3[(index + 3) % 4],
+
+            m_two_i: &m_vector[2 * index + 8],
+
+            a_prime: &round_data.state_middle_prime.row0[index],
+            b_prime: &round_data.state_middle_prime.row1[(index + 1) % 4],
+            c_prime: &round_data.state_middle_prime.row2[(index + 2) % 4],
+            d_prime: &round_data.state_middle_prime.row3[(index + 3) % 4],
+
+            m_two_i_plus_one: &m_vector[2 * index + 9],
+
+            a_output: &round_data.state_output.row0[index],
+            b_output: &round_data.state_output.row1[(index + 1) % 4],
+            c_output: &round_data.state_output.row2[(index + 2) % 4],
+            d_output: &round_data.state_output.row3[(index + 3) % 4],
+        }
+    }
+}
+
+impl<F, const NUM_ROUNDS: usize, const LIMB_ROWS: bool> BaseAir<F>
+    for Blake3Air<NUM_ROUNDS, LIMB_ROWS>
+{
     fn width(&self) -> usize {
-        NUM_BLAKE3_COLS
+        if LIMB_ROWS {
+            num_blake3_limb_cols::<NUM_ROUNDS>()
+        } else {
+            num_blake3_cols::<NUM_ROUNDS>()
+        }
     }
 }
 
-impl<AB: AirBuilder> Air<AB> for Blake3Air {
+impl<AB: AirBuilder, const NUM_ROUNDS: usize, const LIMB_ROWS: bool> Air<AB>
+    for Blake3Air<NUM_ROUNDS, LIMB_ROWS>
+{
     #[inline]
     fn eval(&self, builder: &mut AB) {
         let main = builder.main();
         let local = main.row_slice(0);
-        let local: &Blake3Cols<AB::Var> = (*local).borrow();
+        if LIMB_ROWS {
+            let local: &Blake3LimbCols<AB::Var, NUM_ROUNDS> = (*local).borrow();
+            Blake3Air::<NUM_ROUNDS, true> {}.eval_limb_compression(builder, local);
+        } else {
+            let local: &Blake3Cols<AB::Var, NUM_ROUNDS> = (*local).borrow();
+            Blake3Air::<NUM_ROUNDS> {}.eval_compression(builder, local);
+        }
+    }
+}
 
+impl<const NUM_ROUNDS: usize> Blake3Air<NUM_ROUNDS> {
+    /// Verify that the outputs of `local` are the Blake-3 compression of its inputs.
+    pub(crate) fn eval_compression<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3Cols<AB::Var, NUM_ROUNDS>,
+    ) {
         let initial_row_3 = [
             local.counter_low,
             local.counter_hi,
@@ -254,8 +276,7 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
             .iter()
             .zip(local.initial_row0)
             .for_each(|(bits, word)| {
-                let low_16 = pack_bits_le(bits[..BITS_PER_LIMB].iter().copied());
-                let hi_16 = pack_bits_le(bits[BITS_PER_LIMB..].iter().copied());
+                let [low_16, hi_16] = pack_limbs::<AB::Expr, _>(bits);
                 builder.assert_eq(low_16, word[0]);
                 builder.assert_eq(hi_16, word[1]);
             });
@@ -270,12 +291,7 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
                 builder.assert_eq(row_elem[1], AB::Expr::from_canonical_u32(constant[1]));
             });
 
-        let mut m_values: [[AB::Expr; 2]; 16] = local.inputs.map(|bits| {
-            [
-                pack_bits_le(bits[..BITS_PER_LIMB].iter().copied()),
-                pack_bits_le(bits[BITS_PER_LIMB..].iter().copied()),
-            ]
-        });
+        let mut m_values: [[AB::Expr; 2]; 16] = local.inputs.map(|bits| pack_limbs(&bits));
 
         let initial_state = Blake3State {
             row0: local.initial_row0,
@@ -284,76 +300,18 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
             row3: initial_row_3,
         };
 
-        // Now we can move to verifying that each of the seven rounds have been computed correctly.
-
-        // Round 1:
-        self.verify_round(builder, &initial_state, &local.full_rounds[0], &m_values);
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 2:
-        self.verify_round(
-            builder,
-            &local.full_rounds[0].state_output,
-            &local.full_rounds[1],
-            &m_values,
-        );
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 3:
-        self.verify_round(
-            builder,
-            &local.full_rounds[1].state_output,
-            &local.full_rounds[2],
-            &m_values,
-        );
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 4:
-        self.verify_round(
-            builder,
-            &local.full_rounds[2].state_output,
-            &local.full_rounds[3],
-            &m_values,
-        );
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 5:
-        self.verify_round(
-            builder,
-            &local.full_rounds[3].state_output,
-            &local.full_rounds[4],
-            &m_values,
-        );
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 6:
-        self.verify_round(
-            builder,
-            &local.full_rounds[4].state_output,
-            &local.full_rounds[5],
-            &m_values,
-        );
-
-        // Permute the vector of m_values.
-        permute(&mut m_values);
-
-        // Round 7:
-        self.verify_round(
-            builder,
-            &local.full_rounds[5].state_output,
-            &local.full_rounds[6],
-            &m_values,
-        );
+        // Now we can move to verifying that each of the rounds have been computed correctly. The
+        // input to each round is the output of the previous one, and the vector of m_values is
+        // permuted between rounds.
+        let mut round_input = &initial_state;
+        for (i, round) in local.full_rounds.iter().enumerate() {
+            if i > 0 {
+                permute(&mut m_values);
+            }
+            self.verify_round(builder, round_input, round, &m_values);
+            round_input = &round.state_output;
+        }
+        let final_state = round_input;
 
         // Verify the final set of xor's.
         // For the first 8 of these we xor state[i] and state[i + 8] (i = 0, .., 7)
@@ -364,10 +322,9 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
         local
             .final_round_helpers
             .iter()
-            .zip(local.full_rounds[6].state_output.row2)
+            .zip(final_state.row2)
             .for_each(|(bits, word)| {
-                let low_16 = pack_bits_le(bits[..BITS_PER_LIMB].iter().copied());
-                let hi_16 = pack_bits_le(bits[BITS_PER_LIMB..].iter().copied());
+                let [low_16, hi_16] = pack_limbs::<AB::Expr, _>(bits);
                 builder.assert_eq(low_16, word[0]);
                 builder.assert_eq(hi_16, word[1]);
             });
@@ -380,11 +337,11 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
             .for_each(|bits| bits.iter().for_each(|&bit| builder.assert_bool(bit)));
 
         // Finally we check the xor by xor'ing the output with final_round_helpers, packing the bits
-        // and comparing with the words in local.full_rounds[6].state_output.row0.
+        // and comparing with the words in final_state.row0.
 
         for (out_bits, left_words, right_bits) in izip!(
             local.outputs[0],
-            local.full_rounds[6].state_output.row0,
+            final_state.row0,
             local.final_round_helpers
         ) {
             // We can reuse xor_32_shift with a shift of 0.
@@ -396,11 +353,9 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
         // When i = 4, 5, 6, 7 we already have the bits of state[i] and state[i + 8] making this easy.
         // This check also ensures that local.outputs[1] contains only boolean values.
 
-        for (out_bits, left_bits, right_bits) in izip!(
-            local.outputs[1],
-            local.full_rounds[6].state_output.row1,
-            local.full_rounds[6].state_output.row3
-        ) {
+        for (out_bits, left_bits, right_bits) in
+            izip!(local.outputs[1], final_state.row1, final_state.row3)
+        {
             for (out_bit, left_bit, right_bit) in izip!(out_bits, left_bits, right_bits) {
                 builder.assert_eq(out_bit, xor(left_bit.into(), right_bit.into()));
             }
@@ -425,11 +380,9 @@ impl<AB: AirBuilder> Air<AB> for Blake3Air {
         // This is easy when i = 12, 13, 14, 15 as we already have the bits.
         // This check also ensures that local.outputs[3] contains only boolean values.
 
-        for (out_bits, left_bits, right_bits) in izip!(
-            local.outputs[3],
-            local.chaining_values[1],
-            local.full_rounds[6].state_output.row3
-        ) {
+        for (out_bits, left_bits, right_bits) in
+            izip!(local.outputs[3], local.chaining_values[1], final_state.row3)
+        {
             for (out_bit, left_bit, right_bit) in izip!(out_bits, left_bits, right_bits) {
                 builder.assert_eq(out_bit, xor(left_bit.into(), right_bit.into()));
             }
@@ -1,13 +1,16 @@
 use core::borrow::{Borrow, BorrowMut};
 use core::mem::size_of;
 
-use crate::constants::U32_LIMBS;
+use crate::constants::{BLOCKS_PER_CHUNK, BLOCK_LEN, NUM_FULL_ROUNDS, U32_LIMBS};
 
 /// Columns for a Blake-3 AIR which computes one permutation per row.
 ///
-/// This is a pretty wide trace but that should be fine.
+/// This is a pretty wide trace but that should be fine. `Blake3LimbCols` is a narrower layout.
+///
+/// The number of full rounds is `NUM_ROUNDS`, which is `7` for Blake-3 itself. Fewer rounds give
+/// the reduced-round variants of the compression function.
 #[repr(C)]
-pub struct Blake3Cols<T> {
+pub struct Blake3Cols<T, const NUM_ROUNDS: usize = NUM_FULL_ROUNDS> {
     // The inputs to the hash function.
     pub inputs: [[T; 32]; 16],
 
@@ -24,7 +27,7 @@ pub struct Blake3Cols<T> {
     pub initial_row0: [[T; U32_LIMBS]; 4],
     pub initial_row2: [[T; U32_LIMBS]; 4],
 
-    pub full_rounds: [FullRound<T>; 7],
+    pub full_rounds: [FullRound<T>; NUM_ROUNDS],
 
     pub final_round_helpers: [[T; 32]; 4],
 
@@ -34,18 +37,19 @@ pub struct Blake3Cols<T> {
 /// A state at a single instance of time.
 ///
 /// Rows `0` and `2` are saved as `2` `16` bit limbs.
-/// Rows `1` and `3` are saved as `32` boolean values.
+/// Rows `1` and `3` are saved as `32` boolean values, or in the limb layout of `Blake3Air`, where
+/// `W` is `[T; U32_LIMBS]`, as `2` `16` bit limbs too.
 #[repr(C)]
-pub struct Blake3State<T> {
+pub struct Blake3State<T, W = [T; 32]> {
     pub row0: [[T; U32_LIMBS]; 4],
-    pub row1: [[T; 32]; 4],
+    pub row1: [W; 4],
     pub row2: [[T; U32_LIMBS]; 4],
-    pub row3: [[T; 32]; 4],
+    pub row3: [W; 4],
 }
 
 /// Full round columns.
 #[repr(C)]
-pub struct FullRound<T> {
+pub struct FullRound<T, W = [T; 32]> {
     // A full round of the Blake3 hash consists of 2 sub rounds each containing 4 applications
     // of the quarter round function.
     //
@@ -55,50 +59,188 @@ pub struct FullRound<T> {
     // We use the output of the previous row to get the input to this row.
     //
     /// The outputs after applying the first half of the column quarter round functions.
-    pub state_prime: Blake3State<T>,
+    pub state_prime: Blake3State<T, W>,
 
     /// The outputs after the first sub round.
-    pub state_middle: Blake3State<T>,
+    pub state_middle: Blake3State<T, W>,
 
     /// The outputs after applying the first half of the diagonal quarter round functions.
-    pub state_middle_prime: Blake3State<T>,
+    pub state_middle_prime: Blake3State<T, W>,
 
     /// This will also be the input to the next row.
-    pub state_output: Blake3State<T>,
+    pub state_output: Blake3State<T, W>,
 }
 
 /// Data needed to verify a single quarter round function.
 #[repr(C)]
-pub(crate) struct QuarterRound<'a, T, U> {
+pub(crate) struct QuarterRound<'a, T, U, W = [T; 32]> {
     // The inputs to the quarter round function.
     pub a: &'a [T; U32_LIMBS],
-    pub b: &'a [T; 32],
+    pub b: &'a W,
     pub c: &'a [T; U32_LIMBS],
-    pub d: &'a [T; 32],
+    pub d: &'a W,
 
     pub m_two_i: &'a [U; U32_LIMBS], // m_{2i}
 
     // The state after the first half of the quarter round function.
     pub a_prime: &'a [T; U32_LIMBS],
-    pub b_prime: &'a [T; 32],
+    pub b_prime: &'a W,
     pub c_prime: &'a [T; U32_LIMBS],
-    pub d_prime: &'a [T; 32],
+    pub d_prime: &'a W,
 
     pub m_two_i_plus_one: &'a [U; U32_LIMBS], // m_{2i + 1}
 
     // The output from the quarter round function.
     pub a_output: &'a [T; U32_LIMBS],
-    pub b_output: &'a [T; 32],
+    pub b_output: &'a W,
     pub c_output: &'a [T; U32_LIMBS],
-    pub d_output: &'a [T; 32],
+    pub d_output: &'a W,
+}
+
+pub const NUM_BLAKE3_COLS: usize = num_blake3_cols::<NUM_FULL_ROUNDS>();
+
+/// The width of a trace for Blake-3 with `NUM_ROUNDS` full rounds.
+pub const fn num_blake3_cols<const NUM_ROUNDS: usize>() -> usize {
+    size_of::<Blake3Cols<u8, NUM_ROUNDS>>()
+}
+
+impl<T, const NUM_ROUNDS: usize> Borrow<Blake3Cols<T, NUM_ROUNDS>> for [T] {
+    fn borrow(&self) -> &Blake3Cols<T, NUM_ROUNDS> {
+        debug_assert_eq!(self.len(), num_blake3_cols::<NUM_ROUNDS>());
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Blake3Cols<T, NUM_ROUNDS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T, const NUM_ROUNDS: usize> BorrowMut<Blake3Cols<T, NUM_ROUNDS>> for [T] {
+    fn borrow_mut(&mut self) -> &mut Blake3Cols<T, NUM_ROUNDS> {
+        debug_assert_eq!(self.len(), num_blake3_cols::<NUM_ROUNDS>());
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Blake3Cols<T, NUM_ROUNDS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
+
+/// Columns for the limb layout of `Blake3Air`, which computes one compression per row like
+/// `Blake3Cols` but saves rows `1` and `3` of every state as `2` `16` bit limbs.
+///
+/// Without the bits of those rows, the xors of the quarter rounds are checked by lookups into a
+/// `ByteLookupAir`, so each xor has helper columns holding the bytes of its inputs and output. This
+/// cuts the width of the main trace by about 40%, in exchange for the lookups, each of which adds a
+/// column to the permutation trace, and the `2^16` rows of the table.
+#[repr(C)]
+pub struct Blake3LimbCols<T, const NUM_ROUNDS: usize = NUM_FULL_ROUNDS> {
+    // The inputs to the hash function.
+    pub inputs: [[T; 32]; 16],
+
+    // The chaining values are the first eight outputs of the previous compression.
+    pub chaining_values: [[[T; 32]; 4]; 2],
+
+    // A few auxiliary values use to flesh out the first state.
+    pub counter_low: [T; 32],
+    pub counter_hi: [T; 32],
+    pub block_len: [T; 32],
+    pub flags: [T; 32],
+
+    /// The first state, packed from the chaining values, the IV and the auxiliary values.
+    pub initial_state: Blake3State<T, [T; U32_LIMBS]>,
+
+    pub full_rounds: [FullRound<T, [T; U32_LIMBS]>; NUM_ROUNDS],
+
+    /// The bytes of the xors of each round: those of the column quarter rounds followed by those of
+    /// the diagonal quarter rounds.
+    pub xors: [[QuarterRoundXors<T>; 8]; NUM_ROUNDS],
+
+    /// The bits of row `2` of the final state.
+    pub final_round_helpers: [[T; 32]; 4],
+
+    /// The bytes of rows `1` and `3` of the final state.
+    pub final_row1_bytes: [[T; 4]; 4],
+    pub final_row3_bytes: [[T; 4]; 4],
+
+    pub outputs: [[[T; 32]; 4]; 4],
+}
+
+/// The bytes of the four xors of a quarter round in the limb layout, each of which is followed by
+/// a rotation.
+///
+/// The rotations of `b` are not by a multiple of `8`, so for those the bytes of the xor are split
+/// further: `b_prime_high` and `b_output_high` hold the bits of each byte above the bit the
+/// rotation splits it at.
+#[repr(C)]
+pub struct QuarterRoundXors<T> {
+    /// `d' = (a' ^ d) >>> 16`
+    pub d_prime: XorBytes<T>,
+    /// `b' = (c' ^ b) >>> 12`
+    pub b_prime: XorBytes<T>,
+    pub b_prime_high: [T; 4],
+    /// `d'' = (a'' ^ d') >>> 8`
+    pub d_output: XorBytes<T>,
+    /// `b'' = (c'' ^ b') >>> 7`
+    pub b_output: XorBytes<T>,
+    pub b_output_high: [T; 4],
+}
+
+/// The little-endian bytes of the words in a xor `z = x ^ y`.
+#[repr(C)]
+pub struct XorBytes<T> {
+    pub x: [T; 4],
+    pub y: [T; 4],
+    pub z: [T; 4],
+}
+
+/// The width of a trace for the limb layout of Blake-3 with `NUM_ROUNDS` full rounds.
+pub const fn num_blake3_limb_cols<const NUM_ROUNDS: usize>() -> usize {
+    size_of::<Blake3LimbCols<u8, NUM_ROUNDS>>()
+}
+
+impl<T, const NUM_ROUNDS: usize> Borrow<Blake3LimbCols<T, NUM_ROUNDS>> for [T] {
+    fn borrow(&self) -> &Blake3LimbCols<T, NUM_ROUNDS> {
+        debug_assert_eq!(self.len(), num_blake3_limb_cols::<NUM_ROUNDS>());
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Blake3LimbCols<T, NUM_ROUNDS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T, const NUM_ROUNDS: usize> BorrowMut<Blake3LimbCols<T, NUM_ROUNDS>> for [T] {
+    fn borrow_mut(&mut self) -> &mut Blake3LimbCols<T, NUM_ROUNDS> {
+        debug_assert_eq!(self.len(), num_blake3_limb_cols::<NUM_ROUNDS>());
+        let (prefix, shorts, suffix) =
+            unsafe { self.align_to_mut::<Blake3LimbCols<T, NUM_ROUNDS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
+
+/// Columns for a Blake-3 AIR which hashes messages of up to one chunk, with one compression per
+/// row and the blocks of each message in consecutive rows.
+#[repr(C)]
+pub struct Blake3HashCols<T> {
+    pub compression: Blake3Cols<T>,
+
+    /// The index of the block within its chunk, one-hot encoded.
+    pub block_index: [T; BLOCKS_PER_CHUNK],
+
+    /// Whether each byte of the block lies past the block length, and so must be zero.
+    pub is_padding: [T; BLOCK_LEN],
 }
 
-pub const NUM_BLAKE3_COLS: usize = size_of::<Blake3Cols<u8>>();
+pub const NUM_BLAKE3_HASH_COLS: usize = size_of::<Blake3HashCols<u8>>();
 
-impl<T> Borrow<Blake3Cols<T>> for [T] {
-    fn borrow(&self) -> &Blake3Cols<T> {
-        debug_assert_eq!(self.len(), NUM_BLAKE3_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to::<Blake3Cols<T>>() };
+impl<T> Borrow<Blake3HashCols<T>> for [T] {
+    fn borrow(&self) -> &Blake3HashCols<T> {
+        debug_assert_eq!(self.len(), NUM_BLAKE3_HASH_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Blake3HashCols<T>>() };
         debug_assert!(prefix.is_empty(), "Alignment should match");
         debug_assert!(suffix.is_empty(), "Alignment should match");
         debug_assert_eq!(shorts.len(), 1);
@@ -106,10 +248,10 @@ impl<T> Borrow<Blake3Cols<T>> for [T] {
     }
 }
 
-impl<T> BorrowMut<Blake3Cols<T>> for [T] {
-    fn borrow_mut(&mut self) -> &mut Blake3Cols<T> {
-        debug_assert_eq!(self.len(), NUM_BLAKE3_COLS);
-        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Blake3Cols<T>>() };
+impl<T> BorrowMut<Blake3HashCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut Blake3HashCols<T> {
+        debug_assert_eq!(self.len(), NUM_BLAKE3_HASH_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Blake3HashCols<T>>() };
         debug_assert!(prefix.is_empty(), "Alignment should match");
         debug_assert!(suffix.is_empty(), "Alignment should match");
         debug_assert_eq!(shorts.len(), 1);
@@ -0,0 +1,98 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::Matrix;
+
+use crate::columns::{Blake3Cols, NUM_BLAKE3_COLS};
+use crate::constants::{
+    iv_word, BLOCK_LEN, CHUNK_END, CHUNK_START, DIGEST_LEN, NUM_FULL_ROUNDS, ROOT,
+};
+use crate::Blake3Air;
+
+/// A table of Blake-3 compressions of pairs of digests, for use as a lookup table by other AIRs.
+///
+/// Each row hashes the `BLOCK_LEN` bytes of a left and a right digest as a single chunk, i.e. with
+/// the IV as the chaining value, a zero counter and the flags of the only block of the root, as
+/// `CompressionFunctionFromHasher<Blake3, 2, DIGEST_LEN>` does. The row then receives the bytes of
+/// the two digests followed by those of the output on `bus`, as many times as its multiplicity,
+/// which is the column following the `Blake3Cols`.
+#[derive(Debug)]
+pub struct Blake3CompressionAir {
+    pub bus: usize,
+}
+
+impl Blake3CompressionAir {
+    pub const fn new(bus: usize) -> Self {
+        Self { bus }
+    }
+}
+
+impl<F> BaseAir<F> for Blake3CompressionAir {
+    fn width(&self) -> usize {
+        NUM_BLAKE3_COLS + 1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for Blake3CompressionAir {
+    #[inline]
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Blake3Cols<AB::Var> = local[..NUM_BLAKE3_COLS].borrow();
+
+        Blake3Air::<NUM_FULL_ROUNDS> {}.eval_compression(builder, local);
+
+        for (i, &flag) in local.flags.iter().enumerate() {
+            builder.assert_eq(
+                flag,
+                AB::Expr::from_bool([CHUNK_START, CHUNK_END, ROOT].contains(&i)),
+            );
+        }
+        for &bit in local.counter_low.iter().chain(&local.counter_hi) {
+            builder.assert_zero(bit);
+        }
+        for (i, &bit) in local.block_len.iter().enumerate() {
+            builder.assert_eq(bit, AB::Expr::from_bool((BLOCK_LEN >> i) & 1 == 1));
+        }
+        for (i, &word) in local.chaining_values.iter().flatten().enumerate() {
+            for (j, bit) in word.into_iter().enumerate() {
+                builder.assert_eq(bit, AB::Expr::from_bool((iv_word(i) >> j) & 1 == 1));
+            }
+        }
+    }
+}
+
+impl<F: Field> LookupAir<F> for Blake3CompressionAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let indices: Vec<usize> = (0..NUM_BLAKE3_COLS).collect();
+        let cols: &Blake3Cols<usize> = indices[..].borrow();
+
+        // The bytes of the block, followed by those of the first eight words of the output.
+        let byte = |bits: &[usize]| {
+            VirtualPairCol::new_main(
+                bits.iter()
+                    .enumerate()
+                    .map(|(i, &col)| (col, F::from_canonical_u32(1 << i)))
+                    .collect(),
+                F::ZERO,
+            )
+        };
+        let values: Vec<_> = cols
+            .inputs
+            .iter()
+            .chain(cols.outputs[..2].iter().flatten())
+            .flat_map(|word| word.chunks_exact(8))
+            .map(byte)
+            .collect();
+        debug_assert_eq!(values.len(), BLOCK_LEN + DIGEST_LEN);
+
+        vec![Interaction::receive(
+            values,
+            VirtualPairCol::single_main(NUM_BLAKE3_COLS),
+            self.bus,
+        )]
+    }
+}
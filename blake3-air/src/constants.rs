@@ -1,5 +1,20 @@
-pub const BITS_PER_LIMB: usize = 16;
-pub const U32_LIMBS: usize = 32 / BITS_PER_LIMB;
+pub(crate) use p3_u32_air_gadgets::U32_LIMBS;
+
+/// The number of full rounds in a compression.
+pub const NUM_FULL_ROUNDS: usize = 7;
+
+/// The number of bytes in a block, the input to a single compression.
+pub const BLOCK_LEN: usize = 64;
+/// The number of bytes in a chunk, the leaves of the tree of compressions.
+pub const CHUNK_LEN: usize = 1024;
+/// The number of bytes in a hash.
+pub const DIGEST_LEN: usize = 32;
+pub(crate) const BLOCKS_PER_CHUNK: usize = CHUNK_LEN / BLOCK_LEN;
+
+// The positions of the domain separation bits within the flags.
+pub(crate) const CHUNK_START: usize = 0;
+pub(crate) const CHUNK_END: usize = 1;
+pub(crate) const ROOT: usize = 3;
 
 // The constants from the reference implementation.
 // Saved as pairs of 16 bit integers in [lo, hi] format.
@@ -14,6 +29,11 @@ pub(crate) const IV: [[u32; 2]; 8] = [
     [0xCD19, 0x5BE0],
 ];
 
+/// The `i`-th word of the IV.
+pub(crate) const fn iv_word(i: usize) -> u32 {
+    IV[i][0] + (IV[i][1] << 16)
+}
+
 // The index map for the permutation used to permute the block words is:
 // `[2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8]`
 //
@@ -1,67 +1,251 @@
+use alloc::vec;
 use alloc::vec::Vec;
-use core::array;
+use core::borrow::BorrowMut;
+use core::{array, iter};
 
-use p3_air::utils::u32_to_bits_le;
-use p3_field::{FieldAlgebra, PrimeField64};
+use p3_byte_lookup_air::{ByteMultiplicities, ByteOpcode};
+use p3_field::{Field, FieldAlgebra, PackedValue, PrimeField64};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_maybe_rayon::prelude::*;
 use tracing::instrument;
 
-use crate::columns::{Blake3Cols, NUM_BLAKE3_COLS};
-use crate::constants::{permute, IV};
-use crate::{Blake3State, FullRound};
-
+use crate::columns::{
+    num_blake3_cols, num_blake3_limb_cols, Blake3Cols, Blake3HashCols, Blake3LimbCols,
+    QuarterRoundXors, XorBytes, NUM_BLAKE3_COLS, NUM_BLAKE3_HASH_COLS,
+};
+use crate::constants::{
+    iv_word, permute, BLOCK_LEN, CHUNK_END, CHUNK_LEN, CHUNK_START, DIGEST_LEN, IV,
+    NUM_FULL_ROUNDS, ROOT,
+};
+use crate::{Blake3State, FullRound, U32_LIMBS};
+
+/// Generates a trace proving the Blake-3 compressions of `inputs`, whose number must be a power of
+/// two.
+///
+/// The trace is allocated with room for `extra_capacity_bits` more bits of height, so that a low
+/// degree extension with that log blowup can be computed in place. Rows are filled in parallel, in
+/// groups of the packing width of `F`.
 // TODO: Take generic iterable
+pub fn generate_trace_rows<F: PrimeField64>(
+    inputs: Vec<[u32; 24]>,
+    extra_capacity_bits: usize,
+) -> RowMajorMatrix<F> {
+    generate_trace_rows_with_rounds::<F, NUM_FULL_ROUNDS>(inputs, extra_capacity_bits)
+}
+
+/// As `generate_trace_rows`, but for the variant of Blake-3 with `NUM_ROUNDS` full rounds.
 #[instrument(name = "generate Blake3 trace", skip_all)]
-pub fn generate_trace_rows<F: PrimeField64>(inputs: Vec<[u32; 24]>) -> RowMajorMatrix<F> {
+pub fn generate_trace_rows_with_rounds<F: PrimeField64, const NUM_ROUNDS: usize>(
+    inputs: Vec<[u32; 24]>,
+    extra_capacity_bits: usize,
+) -> RowMajorMatrix<F> {
     let num_rows = inputs.len();
     assert!(
         num_rows.is_power_of_two(),
-        "Callers expected to pad inputs to VECTOR_LEN times a power of two"
+        "Callers expected to pad inputs to a power of two"
     );
 
-    let mut trace = RowMajorMatrix::new(F::zero_vec(num_rows * NUM_BLAKE3_COLS), NUM_BLAKE3_COLS);
-    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<Blake3Cols<F>>() };
+    let width = num_blake3_cols::<NUM_ROUNDS>();
+    let trace_len = num_rows * width;
+    let mut values = Vec::with_capacity(trace_len << extra_capacity_bits);
+    values.resize(trace_len, F::ZERO);
+    let mut trace = RowMajorMatrix::new(values, width);
+    let (prefix, rows, suffix) =
+        unsafe { trace.values.align_to_mut::<Blake3Cols<F, NUM_ROUNDS>>() };
     assert!(prefix.is_empty(), "Alignment should match");
     assert!(suffix.is_empty(), "Alignment should match");
     assert_eq!(rows.len(), num_rows);
 
-    rows.par_iter_mut()
-        .zip(inputs)
+    let group_len = F::Packing::WIDTH;
+    rows.par_chunks_mut(group_len)
+        .zip(inputs.par_chunks(group_len))
         .enumerate()
-        .for_each(|(counter, (row, input))| {
-            generate_trace_rows_for_perm(row, input, counter, num_rows);
+        .for_each(|(group, (rows, inputs))| {
+            for (i, (row, &input)) in rows.iter_mut().zip(inputs).enumerate() {
+                generate_trace_rows_for_perm(row, input, group * group_len + i, num_rows);
+            }
         });
 
     trace
 }
 
+/// Generates a trace for the limb layout of `Blake3Air`, i.e. `Blake3Air<NUM_ROUNDS, true>`,
+/// proving the compressions of `inputs`, whose number must be a power of two, and records its
+/// lookups into the byte table in `multiplicities`.
+#[instrument(name = "generate Blake3 limb trace", skip_all)]
+pub fn generate_limb_trace_rows<F: PrimeField64, const NUM_ROUNDS: usize>(
+    inputs: Vec<[u32; 24]>,
+    multiplicities: &mut ByteMultiplicities,
+) -> RowMajorMatrix<F> {
+    let num_rows = inputs.len();
+    assert!(
+        num_rows.is_power_of_two(),
+        "Callers expected to pad inputs to a power of two"
+    );
+
+    let width = num_blake3_limb_cols::<NUM_ROUNDS>();
+    let mut trace = RowMajorMatrix::new(F::zero_vec(num_rows * width), width);
+    for (counter, (row, input)) in trace.rows_mut().zip(inputs).enumerate() {
+        let row: &mut Blake3LimbCols<F, NUM_ROUNDS> = row.borrow_mut();
+        generate_limb_compression_row(
+            row,
+            array::from_fn(|i| input[i]),
+            array::from_fn(|i| input[16 + i]),
+            counter as u64,
+            num_rows as u32,
+            0,
+            multiplicities,
+        );
+    }
+
+    trace
+}
+
+/// Generates a trace for `Blake3HashAir` hashing each of `messages`, which must have at most
+/// `CHUNK_LEN` bytes, with the hashes of the first `num_public_digests` messages public. The trace
+/// is padded to a power of two height with hashes of the empty message.
+#[instrument(name = "generate Blake3 hash trace", skip_all)]
+pub fn generate_hash_trace_rows<F: PrimeField64>(
+    messages: &[&[u8]],
+    num_public_digests: usize,
+) -> RowMajorMatrix<F> {
+    assert!(
+        num_public_digests <= messages.len(),
+        "each public digest must be the hash of a message"
+    );
+    let num_real_rows: usize = messages
+        .iter()
+        .map(|message| message.len().div_ceil(BLOCK_LEN).max(1))
+        .sum();
+    let num_rows = num_real_rows.next_power_of_two();
+    let width = NUM_BLAKE3_HASH_COLS + num_public_digests;
+
+    let mut trace = RowMajorMatrix::new(F::zero_vec(num_rows * width), width);
+
+    let padding = vec![&[][..]; num_rows - num_real_rows];
+    let mut rows = trace.rows_mut();
+    for (message_index, &message) in messages.iter().chain(&padding).enumerate() {
+        assert!(
+            message.len() <= CHUNK_LEN,
+            "messages longer than a chunk are not supported"
+        );
+        let blocks = if message.is_empty() {
+            vec![message]
+        } else {
+            message.chunks(BLOCK_LEN).collect()
+        };
+        let mut chaining_value = array::from_fn(iv_word);
+        for (i, (block, row)) in blocks.iter().zip(rows.by_ref()).enumerate() {
+            let (row, digest_index) = row.split_at_mut(NUM_BLAKE3_HASH_COLS);
+            let row: &mut Blake3HashCols<F> = row.borrow_mut();
+            let mut bytes = [0; BLOCK_LEN];
+            bytes[..block.len()].copy_from_slice(block);
+            let words =
+                array::from_fn(|j| u32::from_le_bytes(array::from_fn(|k| bytes[4 * j + k])));
+            let is_last = i == blocks.len() - 1;
+            let flags = u32::from(i == 0) << CHUNK_START
+                | u32::from(is_last) << CHUNK_END
+                | u32::from(is_last) << ROOT;
+
+            chaining_value = generate_compression_row(
+                &mut row.compression,
+                words,
+                chaining_value,
+                0,
+                block.len() as u32,
+                flags,
+            );
+            row.block_index[i] = F::ONE;
+            for is_padding in &mut row.is_padding[block.len()..] {
+                *is_padding = F::ONE;
+            }
+            if let Some(is_index) = digest_index.get_mut(message_index) {
+                *is_index = F::ONE;
+            }
+        }
+    }
+
+    trace
+}
+
+/// Generates a trace for `Blake3CompressionAir` with a row for each pair of digests in `inputs`,
+/// each with multiplicity one. The trace is padded to a power of two height with rows of
+/// multiplicity zero.
+#[instrument(name = "generate Blake3 compression trace", skip_all)]
+pub fn generate_compression_trace_rows<F: PrimeField64>(
+    inputs: &[[[u8; DIGEST_LEN]; 2]],
+) -> RowMajorMatrix<F> {
+    let num_rows = inputs.len().next_power_of_two();
+    let width = NUM_BLAKE3_COLS + 1;
+    let mut trace = RowMajorMatrix::new(F::zero_vec(num_rows * width), width);
+
+    let padding = [[0; DIGEST_LEN]; 2];
+    let inputs = inputs.iter().map(Some).chain(iter::repeat(None));
+    for (row, input) in trace.rows_mut().zip(inputs) {
+        let (row, multiplicity) = row.split_at_mut(NUM_BLAKE3_COLS);
+        let row: &mut Blake3Cols<F> = row.borrow_mut();
+        let bytes = input.unwrap_or(&padding).concat();
+        let block = array::from_fn(|j| u32::from_le_bytes(array::from_fn(|k| bytes[4 * j + k])));
+        let flags = 1 << CHUNK_START | 1 << CHUNK_END | 1 << ROOT;
+        generate_compression_row(
+            row,
+            block,
+            array::from_fn(iv_word),
+            0,
+            BLOCK_LEN as u32,
+            flags,
+        );
+        multiplicity[0] = F::from_bool(input.is_some());
+    }
+
+    trace
+}
+
 /// Each row is one full implementation of the Blake-3 hash.
-fn generate_trace_rows_for_perm<F: PrimeField64>(
-    row: &mut Blake3Cols<F>,
+fn generate_trace_rows_for_perm<F: PrimeField64, const NUM_ROUNDS: usize>(
+    row: &mut Blake3Cols<F, NUM_ROUNDS>,
     input: [u32; 24],
     counter: usize,
     block_len: usize,
 ) {
     // We split the input into 2 parts.
     // The first 16 elements we treat as the inputs or block_words
-    row.inputs = array::from_fn(|i| u32_to_bits_le(input[i]));
-
     // the remaining 8 elements are interpreted as the chaining values.
-    row.chaining_values =
-        array::from_fn(|i| array::from_fn(|j| u32_to_bits_le(input[16 + 4 * i + j])));
+    generate_compression_row(
+        row,
+        array::from_fn(|i| input[i]),
+        array::from_fn(|i| input[16 + i]),
+        counter as u64,
+        block_len as u32,
+        // We set the flags initial value to just be 0.
+        0,
+    );
+}
 
-    row.counter_low = u32_to_bits_le(counter as u32);
-    row.counter_hi = u32_to_bits_le((counter >> 32) as u32);
-    row.block_len = u32_to_bits_le(block_len as u32);
+/// Fills in `row` with the compression of `block` under `chaining_value`, and returns the first
+/// eight words of the output, i.e. the next chaining value.
+pub(crate) fn generate_compression_row<F: PrimeField64, const NUM_ROUNDS: usize>(
+    row: &mut Blake3Cols<F, NUM_ROUNDS>,
+    block: [u32; 16],
+    chaining_value: [u32; 8],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 8] {
+    row.inputs = array::from_fn(|i| bits_le(block[i]));
 
-    // We set the flags initial value to just be 0.
-    row.flags = u32_to_bits_le(0);
+    row.chaining_values =
+        array::from_fn(|i| array::from_fn(|j| bits_le(chaining_value[4 * i + j])));
+
+    row.counter_low = bits_le(counter as u32);
+    row.counter_hi = bits_le((counter >> 32) as u32);
+    row.block_len = bits_le(block_len);
+    row.flags = bits_le(flags);
 
     row.initial_row0 = array::from_fn(|i| {
         [
-            F::from_canonical_u16(input[16 + i] as u16),
-            F::from_canonical_u32(input[16 + i] >> 16),
+            F::from_canonical_u16(chaining_value[i] as u16),
+            F::from_canonical_u32(chaining_value[i] >> 16),
         ]
     });
 
@@ -74,41 +258,209 @@ fn generate_trace_rows_for_perm<F: PrimeField64>(
 
     // We save the state and m_vec as u_32's we will quickly compute the hash using these whilst saving
     // the appropriate data in the trace as we go.
-    let mut m_vec: [u32; 16] = array::from_fn(|i| input[i]);
+    let mut m_vec = block;
     let mut state = [
-        [input[16], input[16 + 1], input[16 + 2], input[16 + 3]],
-        [input[16 + 4], input[16 + 5], input[16 + 6], input[16 + 7]],
+        [
+            chaining_value[0],
+            chaining_value[1],
+            chaining_value[2],
+            chaining_value[3],
+        ],
+        [
+            chaining_value[4],
+            chaining_value[5],
+            chaining_value[6],
+            chaining_value[7],
+        ],
         [
             IV[0][0] + (IV[0][1] << 16),
             IV[1][0] + (IV[1][1] << 16),
             IV[2][0] + (IV[2][1] << 16),
             IV[3][0] + (IV[3][1] << 16),
         ],
-        [counter as u32, (counter >> 32) as u32, block_len as u32, 0],
+        [counter as u32, (counter >> 32) as u32, block_len, flags],
     ];
 
-    generate_trace_row_for_round(&mut row.full_rounds[0], &mut state, &m_vec); // round 1
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[1], &mut state, &m_vec); // round 2
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[2], &mut state, &m_vec); // round 3
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[3], &mut state, &m_vec); // round 4
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[4], &mut state, &m_vec); // round 5
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[5], &mut state, &m_vec); // round 6
-    permute(&mut m_vec);
-    generate_trace_row_for_round(&mut row.full_rounds[6], &mut state, &m_vec); // round 7
+    for (i, round) in row.full_rounds.iter_mut().enumerate() {
+        if i > 0 {
+            permute(&mut m_vec);
+        }
+        generate_trace_row_for_round(round, &mut state, &m_vec);
+    }
 
     // After performing all the rounds, all that is left to do is to populate the final xor data.
 
-    row.final_round_helpers = array::from_fn(|i| u32_to_bits_le(state[2][i]));
+    row.final_round_helpers = array::from_fn(|i| bits_le(state[2][i]));
+
+    row.outputs[0] = array::from_fn(|i| bits_le(state[0][i] ^ state[2][i]));
+    row.outputs[1] = array::from_fn(|i| bits_le(state[1][i] ^ state[3][i]));
+    row.outputs[2] = array::from_fn(|i| bits_le(state[2][i] ^ chaining_value[i]));
+    row.outputs[3] = array::from_fn(|i| bits_le(state[3][i] ^ chaining_value[4 + i]));
 
-    row.outputs[0] = array::from_fn(|i| u32_to_bits_le(state[0][i] ^ state[2][i]));
-    row.outputs[1] = array::from_fn(|i| u32_to_bits_le(state[1][i] ^ state[3][i]));
-    row.outputs[2] = array::from_fn(|i| u32_to_bits_le(state[2][i] ^ input[16 + i]));
-    row.outputs[3] = array::from_fn(|i| u32_to_bits_le(state[3][i] ^ input[20 + i]));
+    array::from_fn(|i| state[i / 4][i % 4] ^ state[i / 4 + 2][i % 4])
+}
+
+/// As `generate_compression_row`, in the limb layout, recording the lookups of `row` in
+/// `multiplicities`.
+fn generate_limb_compression_row<F: PrimeField64, const NUM_ROUNDS: usize>(
+    row: &mut Blake3LimbCols<F, NUM_ROUNDS>,
+    block: [u32; 16],
+    chaining_value: [u32; 8],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+    multiplicities: &mut ByteMultiplicities,
+) -> [u32; 8] {
+    row.inputs = array::from_fn(|i| bits_le(block[i]));
+
+    row.chaining_values =
+        array::from_fn(|i| array::from_fn(|j| bits_le(chaining_value[4 * i + j])));
+
+    row.counter_low = bits_le(counter as u32);
+    row.counter_hi = bits_le((counter >> 32) as u32);
+    row.block_len = bits_le(block_len);
+    row.flags = bits_le(flags);
+
+    let mut m_vec = block;
+    let mut state = [
+        array::from_fn(|i| chaining_value[i]),
+        array::from_fn(|i| chaining_value[4 + i]),
+        array::from_fn(iv_word),
+        [counter as u32, (counter >> 32) as u32, block_len, flags],
+    ];
+    save_limb_state_to_trace(&mut row.initial_state, &state);
+
+    for (i, (round, xors)) in row.full_rounds.iter_mut().zip(&mut row.xors).enumerate() {
+        if i > 0 {
+            permute(&mut m_vec);
+        }
+        generate_limb_trace_row_for_round(round, xors, &mut state, &m_vec, multiplicities);
+    }
+
+    row.final_round_helpers = array::from_fn(|i| bits_le(state[2][i]));
+    row.final_row1_bytes = array::from_fn(|i| state[1][i].to_le_bytes().map(F::from_canonical_u8));
+    row.final_row3_bytes = array::from_fn(|i| state[3][i].to_le_bytes().map(F::from_canonical_u8));
+    for i in 0..4 {
+        let row1 = state[1][i].to_le_bytes();
+        let row3 = state[3][i].to_le_bytes();
+        let chaining_value = chaining_value[4 + i].to_le_bytes();
+        for k in 0..4 {
+            multiplicities.add(ByteOpcode::Xor, row1[k], row3[k]);
+            multiplicities.add(ByteOpcode::Xor, chaining_value[k], row3[k]);
+        }
+    }
+
+    row.outputs[0] = array::from_fn(|i| bits_le(state[0][i] ^ state[2][i]));
+    row.outputs[1] = array::from_fn(|i| bits_le(state[1][i] ^ state[3][i]));
+    row.outputs[2] = array::from_fn(|i| bits_le(state[2][i] ^ chaining_value[i]));
+    row.outputs[3] = array::from_fn(|i| bits_le(state[3][i] ^ chaining_value[4 + i]));
+
+    array::from_fn(|i| state[i / 4][i % 4] ^ state[i / 4 + 2][i % 4])
+}
+
+/// As `generate_trace_row_for_round`, in the limb layout, where the column quarter rounds fill in
+/// the first four `xors` and the diagonal quarter rounds the last four.
+fn generate_limb_trace_row_for_round<F: PrimeField64>(
+    round_data: &mut FullRound<F, [F; U32_LIMBS]>,
+    xors: &mut [QuarterRoundXors<F>; 8],
+    state: &mut [[u32; 4]; 4],
+    m_vec: &[u32; 16],
+    multiplicities: &mut ByteMultiplicities,
+) {
+    // The positions in the state of the inputs to the column quarter rounds, then the diagonal ones.
+    let positions = |i: usize| {
+        if i < 4 {
+            [i, i, i, i]
+        } else {
+            [i % 4, (i + 1) % 4, (i + 2) % 4, (i + 3) % 4]
+        }
+    };
+    let mut half_rounds = |state: &mut [[u32; 4]; 4], range: core::ops::Range<usize>, flag| {
+        for i in range {
+            let [p0, p1, p2, p3] = positions(i);
+            (state[0][p0], state[1][p1], state[2][p2], state[3][p3]) = limb_half_round(
+                state[0][p0],
+                state[1][p1],
+                state[2][p2],
+                state[3][p3],
+                m_vec[2 * i + usize::from(flag)],
+                flag,
+                &mut xors[i],
+                multiplicities,
+            );
+        }
+    };
+
+    half_rounds(state, 0..4, false);
+    save_limb_state_to_trace(&mut round_data.state_prime, state);
+    half_rounds(state, 0..4, true);
+    save_limb_state_to_trace(&mut round_data.state_middle, state);
+    half_rounds(state, 4..8, false);
+    save_limb_state_to_trace(&mut round_data.state_middle_prime, state);
+    half_rounds(state, 4..8, true);
+    save_limb_state_to_trace(&mut round_data.state_output, state);
+}
+
+/// As `verifiable_half_round`, also filling in the bytes of its two xors and recording their
+/// lookups.
+#[allow(clippy::too_many_arguments)]
+fn limb_half_round<F: Field>(
+    mut a: u32,
+    mut b: u32,
+    mut c: u32,
+    mut d: u32,
+    m: u32,
+    flag: bool,
+    xors: &mut QuarterRoundXors<F>,
+    multiplicities: &mut ByteMultiplicities,
+) -> (u32, u32, u32, u32) {
+    let (rot_1, rot_2) = if flag { (8, 7) } else { (16, 12) };
+    let (first_xor, second_xor, second_high) = if flag {
+        (
+            &mut xors.d_output,
+            &mut xors.b_output,
+            &mut xors.b_output_high,
+        )
+    } else {
+        (&mut xors.d_prime, &mut xors.b_prime, &mut xors.b_prime_high)
+    };
+
+    a = a.wrapping_add(b).wrapping_add(m);
+    d = generate_xor(first_xor, a, d, multiplicities).rotate_right(rot_1);
+    c = c.wrapping_add(d);
+    let z = generate_xor(second_xor, c, b, multiplicities);
+    *second_high = generate_high_bits(z, rot_2 % 8, multiplicities);
+    b = z.rotate_right(rot_2);
+
+    (a, b, c, d)
+}
+
+/// Fills in the bytes of `x ^ y`, recording their lookups, and returns it.
+fn generate_xor<F: Field>(
+    bytes: &mut XorBytes<F>,
+    x: u32,
+    y: u32,
+    multiplicities: &mut ByteMultiplicities,
+) -> u32 {
+    let (x, y) = (x.to_le_bytes(), y.to_le_bytes());
+    let z = array::from_fn(|k| multiplicities.add(ByteOpcode::Xor, x[k], y[k]));
+    bytes.x = x.map(F::from_canonical_u8);
+    bytes.y = y.map(F::from_canonical_u8);
+    bytes.z = z.map(F::from_canonical_u8);
+    u32::from_le_bytes(z)
+}
+
+/// The bits of each byte of `z` above bit `split`, recording the range checks of the split.
+fn generate_high_bits<F: Field>(
+    z: u32,
+    split: usize,
+    multiplicities: &mut ByteMultiplicities,
+) -> [F; 4] {
+    z.to_le_bytes().map(|byte| {
+        let (low, high) = (byte & ((1 << split) - 1), byte >> split);
+        multiplicities.add(ByteOpcode::Range, low << (8 - split), high);
+        F::from_canonical_u8(high)
+    })
 }
 
 fn generate_trace_row_for_round<F: PrimeField64>(
@@ -205,14 +557,13 @@ fn verifiable_half_round(
     let (rot_1, rot_2) = if flag { (8, 7) } else { (16, 12) };
 
     // The first summation:
-    a += b;
-    a += m;
+    a = a.wrapping_add(b).wrapping_add(m);
 
     // The first xor:
     d = (d ^ a).rotate_right(rot_1);
 
     // The second summation:
-    c += d;
+    c = c.wrapping_add(d);
 
     // The second xor:
     b = (b ^ c).rotate_right(rot_2);
@@ -227,12 +578,46 @@ fn save_state_to_trace<FA: FieldAlgebra>(trace: &mut Blake3State<FA>, state: &[[
             FA::from_canonical_u32(state[0][i] >> 16),  // Store the top 16 bits packed.
         ]
     });
-    trace.row1 = array::from_fn(|i| u32_to_bits_le(state[1][i])); // Store all 32 bits unpacked.
+    trace.row1 = array::from_fn(|i| bits_le(state[1][i])); // Store all 32 bits unpacked.
     trace.row2 = array::from_fn(|i| {
         [
             FA::from_canonical_u16(state[2][i] as u16), // Store the bottom 16 bits packed.
             FA::from_canonical_u32(state[2][i] >> 16),  // Store the top 16 bits packed.
         ]
     });
-    trace.row3 = array::from_fn(|i| u32_to_bits_le(state[3][i])); // Store all 32 bits unpacked.
+    trace.row3 = array::from_fn(|i| bits_le(state[3][i])); // Store all 32 bits unpacked.
+}
+
+fn save_limb_state_to_trace<FA: FieldAlgebra>(
+    trace: &mut Blake3State<FA, [FA; U32_LIMBS]>,
+    state: &[[u32; 4]; 4],
+) {
+    let limbs = |row: [u32; 4]| {
+        row.map(|word| {
+            [
+                FA::from_canonical_u16(word as u16), // Store the bottom 16 bits packed.
+                FA::from_canonical_u32(word >> 16),  // Store the top 16 bits packed.
+            ]
+        })
+    };
+    trace.row0 = limbs(state[0]);
+    trace.row1 = limbs(state[1]);
+    trace.row2 = limbs(state[2]);
+    trace.row3 = limbs(state[3]);
+}
+
+/// Converts `val` into its 32 little-endian bits, building a packed vector of bits at a time.
+#[inline]
+fn bits_le<F: Field>(val: u32) -> [F; 32] {
+    let mut bits = [F::ZERO; 32];
+    let (packed, suffix) = F::Packing::pack_slice_with_suffix_mut(&mut bits);
+    let width = F::Packing::WIDTH;
+    for (i, packed_bits) in packed.iter_mut().enumerate() {
+        *packed_bits = F::Packing::from_fn(|j| F::from_bool((val >> (i * width + j)) & 1 == 1));
+    }
+    let offset = 32 - suffix.len();
+    for (j, bit) in suffix.iter_mut().enumerate() {
+        *bit = F::from_bool((val >> (offset + j)) & 1 == 1);
+    }
+    bits
 }
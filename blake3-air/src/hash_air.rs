@@ -0,0 +1,220 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::utils::pack_bits_le;
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+use crate::columns::{Blake3HashCols, NUM_BLAKE3_HASH_COLS};
+use crate::constants::{
+    iv_word, BLOCK_LEN, CHUNK_END, CHUNK_START, DIGEST_LEN, NUM_FULL_ROUNDS, ROOT,
+};
+use crate::Blake3Air;
+
+/// An AIR for the Blake-3 hashes of messages of up to `CHUNK_LEN` bytes.
+///
+/// Each message takes one row per block, in order, with the chaining value of each block after
+/// the first being the output of the previous row. The flags, counter and block length of each
+/// compression are those of the reference implementation, and the bytes of the last block past
+/// the end of the message must be zero, so the output of the last row of a message is its hash.
+/// Longer messages, whose hash is a tree of chunks, are not supported.
+///
+/// The hashes of the first `num_public_digests` messages are public values, each given as its
+/// `DIGEST_LEN` bytes. To locate them, the trace has `num_public_digests` columns after the
+/// `Blake3HashCols`, which one-hot encode the index of the message of each row among those whose
+/// hashes are public.
+///
+/// Assumes the field size is at least 16 bits.
+#[derive(Debug, Default)]
+pub struct Blake3HashAir {
+    pub num_public_digests: usize,
+}
+
+impl Blake3HashAir {
+    pub const fn new(num_public_digests: usize) -> Self {
+        Self { num_public_digests }
+    }
+
+    /// The public values proving that the first messages of the trace have the given hashes.
+    pub fn public_values<F: FieldAlgebra>(digests: &[[u8; DIGEST_LEN]]) -> Vec<F> {
+        digests
+            .iter()
+            .flatten()
+            .map(|&byte| F::from_canonical_u8(byte))
+            .collect()
+    }
+}
+
+impl<F> BaseAir<F> for Blake3HashAir {
+    fn width(&self) -> usize {
+        NUM_BLAKE3_HASH_COLS + self.num_public_digests
+    }
+}
+
+impl<F> BaseAirWithPublicValues<F> for Blake3HashAir {
+    fn num_public_values(&self) -> usize {
+        self.num_public_digests * DIGEST_LEN
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Blake3HashAir {
+    #[inline]
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (local, local_digest_index) = local.split_at(NUM_BLAKE3_HASH_COLS);
+        let (next, next_digest_index) = next.split_at(NUM_BLAKE3_HASH_COLS);
+        let local: &Blake3HashCols<AB::Var> = local.borrow();
+        let next: &Blake3HashCols<AB::Var> = next.borrow();
+        let compression = &local.compression;
+
+        Blake3Air::<NUM_FULL_ROUNDS> {}.eval_compression(builder, compression);
+
+        let flags = &compression.flags;
+        let (chunk_start, chunk_end) = (flags[CHUNK_START], flags[CHUNK_END]);
+
+        // Every message is a single chunk, so its last block is also the root, and no other
+        // flags are set. The counter is the index of the chunk, which is always zero.
+        for (i, &flag) in flags.iter().enumerate() {
+            if ![CHUNK_START, CHUNK_END, ROOT].contains(&i) {
+                builder.assert_zero(flag);
+            }
+        }
+        builder.assert_eq(flags[ROOT], chunk_end);
+        for &bit in compression
+            .counter_low
+            .iter()
+            .chain(&compression.counter_hi)
+        {
+            builder.assert_zero(bit);
+        }
+
+        // The block index is one-hot, and zero exactly at the start of a chunk.
+        for &is_index in &local.block_index {
+            builder.assert_bool(is_index);
+        }
+        builder.assert_one(
+            local
+                .block_index
+                .iter()
+                .fold(AB::Expr::ZERO, |sum, &is_index| sum + is_index),
+        );
+        builder.assert_eq(chunk_start, local.block_index[0]);
+
+        // A chunk starts from the IV.
+        let mut when_chunk_start = builder.when(chunk_start);
+        for (i, &word) in compression.chaining_values.iter().flatten().enumerate() {
+            for (j, bit) in word.into_iter().enumerate() {
+                when_chunk_start.assert_eq(bit, AB::Expr::from_bool((iv_word(i) >> j) & 1 == 1));
+            }
+        }
+
+        // The padding is a suffix of the block, which is empty unless this is the last block,
+        // and is not the whole block unless the message is empty.
+        for &is_padding in &local.is_padding {
+            builder.assert_bool(is_padding);
+        }
+        for pair in local.is_padding.windows(2) {
+            builder.when(pair[0]).assert_one(pair[1]);
+        }
+        builder
+            .when(AB::Expr::ONE - chunk_end)
+            .assert_zero(local.is_padding[BLOCK_LEN - 1]);
+        builder
+            .when(AB::Expr::ONE - chunk_start)
+            .assert_zero(local.is_padding[0]);
+
+        // The padding bytes are zero, and the block length counts the others.
+        for (byte, &is_padding) in local.is_padding.iter().enumerate() {
+            let word = &compression.inputs[byte / 4];
+            for &bit in &word[8 * (byte % 4)..8 * (byte % 4 + 1)] {
+                builder.when(is_padding).assert_zero(bit);
+            }
+        }
+        for &bit in &compression.block_len[7..] {
+            builder.assert_zero(bit);
+        }
+        let num_padding_bytes = local
+            .is_padding
+            .iter()
+            .fold(AB::Expr::ZERO, |sum, &is_padding| sum + is_padding);
+        builder.assert_eq(
+            pack_bits_le::<AB::Expr, _, _>(compression.block_len[..7].iter().copied()),
+            AB::Expr::from_canonical_usize(BLOCK_LEN) - num_padding_bytes,
+        );
+
+        // The trace is a sequence of whole messages.
+        builder.when_first_row().assert_one(chunk_start);
+        builder.when_last_row().assert_one(chunk_end);
+
+        let next_chunk_start = next.compression.flags[CHUNK_START];
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next_chunk_start, chunk_end);
+
+        // Within a chunk, the block index increases, up to its limit, and the chaining value is
+        // the output of the previous block.
+        let mut when_chunk_continues = when_transition.when(AB::Expr::ONE - next_chunk_start);
+        for i in 0..local.block_index.len() - 1 {
+            when_chunk_continues.assert_eq(next.block_index[i + 1], local.block_index[i]);
+        }
+        when_chunk_continues.assert_zero(local.block_index[local.block_index.len() - 1]);
+        for (next_word, output_word) in next
+            .compression
+            .chaining_values
+            .iter()
+            .flatten()
+            .zip(compression.outputs[..2].iter().flatten())
+        {
+            for (&next_bit, &output_bit) in next_word.iter().zip(output_word) {
+                when_chunk_continues.assert_eq(next_bit, output_bit);
+            }
+        }
+
+        if self.num_public_digests == 0 {
+            return;
+        }
+
+        // The digest index starts at the first message, and moves to the next at the start of each
+        // message, until it passes the last public digest. It must have reached the last public
+        // digest by the end of the trace, so each public digest is the hash of some message.
+        let n = self.num_public_digests;
+        for &is_index in local_digest_index {
+            builder.assert_bool(is_index);
+        }
+        builder.when_first_row().assert_one(local_digest_index[0]);
+        for &is_index in &local_digest_index[..n - 1] {
+            builder.when_last_row().assert_zero(is_index);
+        }
+        let mut when_transition = builder.when_transition();
+        let mut when_chunk_continues = when_transition.when(AB::Expr::ONE - next_chunk_start);
+        for (&next_is_index, &is_index) in next_digest_index.iter().zip(local_digest_index) {
+            when_chunk_continues.assert_eq(next_is_index, is_index);
+        }
+        let mut when_next_chunk_start = when_transition.when(next_chunk_start);
+        when_next_chunk_start.assert_zero(next_digest_index[0]);
+        for i in 0..n - 1 {
+            when_next_chunk_start.assert_eq(next_digest_index[i + 1], local_digest_index[i]);
+        }
+
+        // The output of the last block of each message with a public digest is that digest.
+        let public_values = builder.public_values();
+        let digests: Vec<AB::Expr> = public_values.iter().map(|&x| x.into()).collect();
+        let output_bytes = compression.outputs[..2]
+            .iter()
+            .flatten()
+            .flat_map(|word| word.chunks_exact(8));
+        for (i, &is_index) in local_digest_index.iter().enumerate() {
+            let mut when_digest = builder.when(is_index * chunk_end);
+            for (byte, digest_byte) in output_bytes
+                .clone()
+                .zip(&digests[i * DIGEST_LEN..(i + 1) * DIGEST_LEN])
+            {
+                when_digest.assert_eq(
+                    pack_bits_le::<AB::Expr, _, _>(byte.iter().copied()),
+                    digest_byte.clone(),
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
-//! An AIR for the Blake-3 permutation. Assumes the field size is between 2^20 and 2^32.
+//! AIRs for the Blake-3 permutation and hash function. Assumes the field size is between 2^20 and
+//! 2^32.
 
 #![no_std]
 
@@ -6,9 +7,16 @@ extern crate alloc;
 
 mod air;
 mod columns;
+mod compression_air;
 mod constants;
 mod generation;
+mod hash_air;
+mod limb_air;
 
 pub use air::*;
 pub use columns::*;
+pub use compression_air::*;
+pub use constants::{BLOCK_LEN, CHUNK_LEN, DIGEST_LEN, NUM_FULL_ROUNDS};
 pub use generation::*;
+pub use hash_air::*;
+pub use limb_air::*;
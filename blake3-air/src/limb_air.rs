@@ -0,0 +1,422 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::array;
+use core::borrow::Borrow;
+
+use itertools::izip;
+use p3_air::utils::{add2, add3, xor, xor_32_shift};
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_byte_lookup_air::{ByteLookupAir, ByteOpcode};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_u32_air_gadgets::pack_limbs;
+
+use crate::columns::{num_blake3_limb_cols, Blake3LimbCols, QuarterRoundXors, XorBytes};
+use crate::constants::{permute, IV, NUM_FULL_ROUNDS};
+use crate::{Blake3Air, Blake3State, FullRound, QuarterRound, U32_LIMBS};
+
+/// The bus on which the limb layout of `Blake3Air` looks up the xors of its bytes, and so the bus
+/// of the `ByteLookupAir` it is proven with.
+pub const BLAKE3_BYTE_BUS: usize = 0;
+
+/// The rotations following the four xors of a quarter round, in the order of `QuarterRoundXors`.
+const QUARTER_ROUND_ROTATIONS: [usize; 4] = [16, 12, 8, 7];
+
+/// The xors of a quarter round, each with the rotation following it and, if that rotation is not
+/// by a multiple of `8`, the high bits of the bytes it splits.
+fn rotated_xors<T>(xors: &QuarterRoundXors<T>) -> [(&XorBytes<T>, Option<&[T; 4]>, usize); 4] {
+    let [d_prime, b_prime, d_output, b_output] = QUARTER_ROUND_ROTATIONS;
+    [
+        (&xors.d_prime, None, d_prime),
+        (&xors.b_prime, Some(&xors.b_prime_high), b_prime),
+        (&xors.d_output, None, d_output),
+        (&xors.b_output, Some(&xors.b_output_high), b_output),
+    ]
+}
+
+/// Pack the little-endian bytes of a word into `2, 16` bit limbs.
+#[inline]
+fn pack_bytes<FA, Var>(bytes: &[Var; 4]) -> [FA; U32_LIMBS]
+where
+    FA: FieldAlgebra,
+    Var: Into<FA> + Clone,
+{
+    array::from_fn(|i| {
+        bytes[2 * i].clone().into()
+            + bytes[2 * i + 1].clone().into() * FA::from_canonical_u32(1 << 8)
+    })
+}
+
+/// The little-endian bytes of `z >>> shift`, given those of `z` and, if `shift` is not a multiple
+/// of `8`, the bits of each byte of `z` above bit `shift % 8`.
+///
+/// We assume the bytes are range checked, and the high bits checked to split them as claimed.
+#[inline]
+fn rotate_right_bytes<FA, Var>(z: &[Var; 4], high: Option<&[Var; 4]>, shift: usize) -> [FA; 4]
+where
+    FA: FieldAlgebra,
+    Var: Into<FA> + Clone,
+{
+    let (offset, split) = (shift / 8, shift % 8);
+    match high {
+        None => {
+            debug_assert_eq!(split, 0);
+            array::from_fn(|k| z[(k + offset) % 4].clone().into())
+        }
+        Some(high) => array::from_fn(|k| {
+            // Byte `k` of the output is made of the high bits of byte `k + offset` of `z` and the
+            // low bits of the byte after it.
+            let (j, l) = ((k + offset) % 4, (k + offset + 1) % 4);
+            let low =
+                z[l].clone().into() - high[l].clone().into() * FA::from_canonical_u32(1 << split);
+            high[j].clone().into() + low * FA::from_canonical_u32(1 << (8 - split))
+        }),
+    }
+}
+
+/// Assert that the limbs of a word are equal to the given packed values.
+#[inline]
+fn assert_limbs<AB: AirBuilder>(
+    builder: &mut AB,
+    limbs: &[AB::Var; U32_LIMBS],
+    packed: [AB::Expr; U32_LIMBS],
+) {
+    for (limb, packed) in limbs.iter().zip(packed) {
+        builder.assert_eq(*limb, packed);
+    }
+}
+
+impl<const NUM_ROUNDS: usize> Blake3Air<NUM_ROUNDS, true> {
+    /// Verify that the quarter round function has been correctly computed, in the limb layout.
+    ///
+    /// As in `quarter_round_function`, we assume that the inputs have been range checked. The
+    /// bytes of the xors are checked by the lookups of `interactions`, and here we check that they
+    /// are the bytes of the words in the state, which range checks those.
+    fn limb_quarter_round_function<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        trace: &QuarterRound<AB::Var, AB::Expr, [AB::Var; U32_LIMBS]>,
+        xors: &QuarterRoundXors<AB::Var>,
+    ) {
+        // First we verify a' = a + b + m_{2i} mod 2^32
+        add3(
+            builder,
+            trace.a_prime,
+            trace.a,
+            &trace.b.map(Into::into),
+            trace.m_two_i,
+        );
+
+        // Then the quarter round alternates xors, each followed by a rotation, with sums.
+        // d' = (a' ^ d) >>> 16
+        // c' = c + d' mod 2^32
+        // b' = (c' ^ b) >>> 12
+        // a'' = a' + b' + m_{2i + 1} mod 2^32
+        // d'' = (a'' ^ d') >>> 8
+        // c'' = c' + d'' mod 2^32
+        // b'' = (c'' ^ b') >>> 7
+        let [d_prime, b_prime, d_output, b_output] = rotated_xors(xors);
+        self.verify_rotated_xor(builder, trace.a_prime, trace.d, trace.d_prime, d_prime);
+        add2(
+            builder,
+            trace.c_prime,
+            trace.c,
+            &trace.d_prime.map(Into::into),
+        );
+        self.verify_rotated_xor(builder, trace.c_prime, trace.b, trace.b_prime, b_prime);
+        add3(
+            builder,
+            trace.a_output,
+            trace.a_prime,
+            &trace.b_prime.map(Into::into),
+            trace.m_two_i_plus_one,
+        );
+        self.verify_rotated_xor(
+            builder,
+            trace.a_output,
+            trace.d_prime,
+            trace.d_output,
+            d_output,
+        );
+        add2(
+            builder,
+            trace.c_output,
+            trace.c_prime,
+            &trace.d_output.map(Into::into),
+        );
+        self.verify_rotated_xor(
+            builder,
+            trace.c_output,
+            trace.b_prime,
+            trace.b_output,
+            b_output,
+        );
+    }
+
+    /// Verify that `out = (x ^ y) >>> shift`, given the bytes of the xor.
+    fn verify_rotated_xor<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        x: &[AB::Var; U32_LIMBS],
+        y: &[AB::Var; U32_LIMBS],
+        out: &[AB::Var; U32_LIMBS],
+        (bytes, high, shift): (&XorBytes<AB::Var>, Option<&[AB::Var; 4]>, usize),
+    ) {
+        assert_limbs(builder, x, pack_bytes(&bytes.x));
+        assert_limbs(builder, y, pack_bytes(&bytes.y));
+        let rotated = rotate_right_bytes::<AB::Expr, _>(&bytes.z, high, shift);
+        assert_limbs(builder, out, pack_bytes(&rotated));
+    }
+
+    /// Verify a full round of the Blake-3 permutation, in the limb layout.
+    fn verify_limb_round<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        input: &Blake3State<AB::Var, [AB::Var; U32_LIMBS]>,
+        round_data: &FullRound<AB::Var, [AB::Var; U32_LIMBS]>,
+        xors: &[QuarterRoundXors<AB::Var>; 8],
+        m_vector: &[[AB::Expr; 2]; 16],
+    ) {
+        // First we mix the columns, then the diagonals, as in `verify_round`.
+        for (index, xors) in xors[..4].iter().enumerate() {
+            let trace = self.full_round_to_column_quarter_round(input, round_data, m_vector, index);
+            self.limb_quarter_round_function(builder, &trace, xors);
+        }
+        for (index, xors) in xors[4..].iter().enumerate() {
+            let trace = self.full_round_to_diagonal_quarter_round(round_data, m_vector, index);
+            self.limb_quarter_round_function(builder, &trace, xors);
+        }
+    }
+
+    /// Verify that the outputs of `local` are the Blake-3 compression of its inputs, in the limb
+    /// layout.
+    pub(crate) fn eval_limb_compression<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3LimbCols<AB::Var, NUM_ROUNDS>,
+    ) {
+        let initial_row_3 = [
+            local.counter_low,
+            local.counter_hi,
+            local.block_len,
+            local.flags,
+        ];
+
+        // We start by checking that all the initialization inputs are boolean values.
+        local
+            .inputs
+            .iter()
+            .chain(local.chaining_values[0].iter())
+            .chain(local.chaining_values[1].iter())
+            .chain(initial_row_3.iter())
+            .for_each(|elem| elem.iter().for_each(|&bool| builder.assert_bool(bool)));
+
+        // The first state packs the chaining values and the auxiliary values, with the first four
+        // constants of the IV in row 2.
+        let initial_state = &local.initial_state;
+        for (bits, word) in local
+            .chaining_values
+            .iter()
+            .flatten()
+            .chain(&initial_row_3)
+            .zip(
+                initial_state
+                    .row0
+                    .iter()
+                    .chain(&initial_state.row1)
+                    .chain(&initial_state.row3),
+            )
+        {
+            assert_limbs(builder, word, pack_limbs(bits));
+        }
+        for (word, constant) in initial_state.row2.iter().zip(IV) {
+            assert_limbs(builder, word, constant.map(AB::Expr::from_canonical_u32));
+        }
+
+        let mut m_values: [[AB::Expr; 2]; 16] = local.inputs.map(|bits| pack_limbs(&bits));
+
+        let mut round_input = initial_state;
+        for (i, (round, xors)) in local.full_rounds.iter().zip(&local.xors).enumerate() {
+            if i > 0 {
+                permute(&mut m_values);
+            }
+            self.verify_limb_round(builder, round_input, round, xors, &m_values);
+            round_input = &round.state_output;
+        }
+        let final_state = round_input;
+
+        // Verify the final set of xor's. We have the bits of row 2, as in `eval_compression`, and
+        // the bytes of rows 1 and 3, whose xors are looked up.
+        for (bits, word) in local.final_round_helpers.iter().zip(&final_state.row2) {
+            assert_limbs(builder, word, pack_limbs(bits));
+        }
+        for (bytes, word) in local
+            .final_row1_bytes
+            .iter()
+            .chain(&local.final_row3_bytes)
+            .zip(final_state.row1.iter().chain(&final_state.row3))
+        {
+            assert_limbs(builder, word, pack_bytes(bytes));
+        }
+
+        // The outputs are boolean, which the lookups of outputs 1 and 3 do not check.
+        local
+            .final_round_helpers
+            .iter()
+            .chain(local.outputs.iter().flatten())
+            .for_each(|bits| bits.iter().for_each(|&bit| builder.assert_bool(bit)));
+
+        // Outputs 0 are row 0 xor row 2.
+        for (out_bits, left_words, right_bits) in izip!(
+            local.outputs[0],
+            final_state.row0,
+            local.final_round_helpers
+        ) {
+            xor_32_shift(builder, &left_words, &out_bits, &right_bits, 0)
+        }
+
+        // Outputs 2 are row 2 xor the first four chaining values.
+        for (out_bits, left_bits, right_bits) in izip!(
+            local.outputs[2],
+            local.chaining_values[0],
+            local.final_round_helpers
+        ) {
+            for (out_bit, left_bit, right_bit) in izip!(out_bits, left_bits, right_bits) {
+                builder.assert_eq(out_bit, xor(left_bit.into(), right_bit.into()));
+            }
+        }
+    }
+}
+
+impl<F: Field, const NUM_ROUNDS: usize> LookupAir<F> for Blake3Air<NUM_ROUNDS, true> {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let indices: Vec<usize> = (0..num_blake3_limb_cols::<NUM_ROUNDS>()).collect();
+        let cols: &Blake3LimbCols<usize, NUM_ROUNDS> = indices[..].borrow();
+
+        let send_xor =
+            |x, y, z| ByteOpcode::Xor.send(x, y, z, VirtualPairCol::ONE, BLAKE3_BYTE_BUS);
+        let mut interactions = vec![];
+
+        for quarter_round in cols.xors.iter().flatten() {
+            for (bytes, high, shift) in rotated_xors(quarter_round) {
+                for k in 0..4 {
+                    interactions.push(send_xor(
+                        VirtualPairCol::single_main(bytes.x[k]),
+                        VirtualPairCol::single_main(bytes.y[k]),
+                        VirtualPairCol::single_main(bytes.z[k]),
+                    ));
+                }
+
+                // Splitting a byte into `low + 2^split high` is right exactly when both `high`
+                // and `2^(8 - split) low` are bytes.
+                let Some(high) = high else {
+                    continue;
+                };
+                let split = shift % 8;
+                for k in 0..4 {
+                    let shifted_low = VirtualPairCol::new_main(
+                        vec![
+                            (bytes.z[k], F::from_canonical_u32(1 << (8 - split))),
+                            (high[k], -F::from_canonical_u32(1 << 8)),
+                        ],
+                        F::ZERO,
+                    );
+                    interactions.push(ByteOpcode::send_range_check(
+                        shifted_low,
+                        VirtualPairCol::single_main(high[k]),
+                        VirtualPairCol::ONE,
+                        BLAKE3_BYTE_BUS,
+                    ));
+                }
+            }
+        }
+
+        // Outputs 1 are row 1 xor row 3, and outputs 3 are row 3 xor the last four chaining
+        // values, whose bytes are packed from their bits.
+        let byte = |bits: &[usize]| {
+            VirtualPairCol::new_main(
+                bits.iter()
+                    .enumerate()
+                    .map(|(i, &col)| (col, F::from_canonical_u32(1 << i)))
+                    .collect(),
+                F::ZERO,
+            )
+        };
+        for (row1, row3, chaining_value, out1, out3) in izip!(
+            cols.final_row1_bytes,
+            cols.final_row3_bytes,
+            cols.chaining_values[1],
+            cols.outputs[1],
+            cols.outputs[3]
+        ) {
+            for k in 0..4 {
+                let bits = 8 * k..8 * (k + 1);
+                interactions.push(send_xor(
+                    VirtualPairCol::single_main(row1[k]),
+                    VirtualPairCol::single_main(row3[k]),
+                    byte(&out1[bits.clone()]),
+                ));
+                interactions.push(send_xor(
+                    byte(&chaining_value[bits.clone()]),
+                    VirtualPairCol::single_main(row3[k]),
+                    byte(&out3[bits]),
+                ));
+            }
+        }
+
+        interactions
+    }
+}
+
+/// The limb layout of `Blake3Air` together with the table of byte operations its rows look up, for
+/// proving both with `prove_multiple_with_lookups`, after committing to the table's preprocessed
+/// trace with `setup_multiple_preprocessed`.
+#[derive(Debug)]
+pub enum Blake3LimbTable<const NUM_ROUNDS: usize = NUM_FULL_ROUNDS> {
+    Compressions(Blake3Air<NUM_ROUNDS, true>),
+    Bytes(ByteLookupAir),
+}
+
+impl<const NUM_ROUNDS: usize> Blake3LimbTable<NUM_ROUNDS> {
+    /// The compressions and the byte table, in the order their traces are proven.
+    pub const fn airs() -> [Self; 2] {
+        [
+            Self::Compressions(Blake3Air {}),
+            Self::Bytes(ByteLookupAir::new(BLAKE3_BYTE_BUS)),
+        ]
+    }
+}
+
+impl<F: Field, const NUM_ROUNDS: usize> BaseAir<F> for Blake3LimbTable<NUM_ROUNDS> {
+    fn width(&self) -> usize {
+        match self {
+            Self::Compressions(air) => BaseAir::<F>::width(air),
+            Self::Bytes(air) => BaseAir::<F>::width(air),
+        }
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        match self {
+            Self::Compressions(_) => None,
+            Self::Bytes(air) => air.preprocessed_trace(),
+        }
+    }
+}
+
+impl<AB: AirBuilder, const NUM_ROUNDS: usize> Air<AB> for Blake3LimbTable<NUM_ROUNDS> {
+    fn eval(&self, builder: &mut AB) {
+        match self {
+            Self::Compressions(air) => air.eval(builder),
+            Self::Bytes(air) => air.eval(builder),
+        }
+    }
+}
+
+impl<F: Field, const NUM_ROUNDS: usize> LookupAir<F> for Blake3LimbTable<NUM_ROUNDS> {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        match self {
+            Self::Compressions(air) => air.interactions(),
+            Self::Bytes(air) => air.interactions(),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use core::borrow::{Borrow, BorrowMut};
+
+use p3_blake3_air::{
+    generate_limb_trace_rows, generate_trace_rows, generate_trace_rows_with_rounds,
+    num_blake3_cols, Blake3Air, Blake3Cols, Blake3LimbCols, Blake3LimbTable, NUM_BLAKE3_COLS,
+    NUM_FULL_ROUNDS,
+};
+use p3_byte_lookup_air::ByteMultiplicities;
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{
+    prove, prove_multiple_with_lookups, setup_multiple_preprocessed, verify,
+    verify_multiple_with_lookups,
+};
+use p3_util::log2_strict_usize;
+use rand::{random, thread_rng};
+
+#[test]
+fn test_blake3_air() {
+    let inputs = (0..8).map(|_| random()).collect::<Vec<_>>();
+    let trace = generate_trace_rows::<Val>(inputs, 1);
+    assert!(trace.values.capacity() >= 2 * trace.values.len());
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let air: Blake3Air = Blake3Air {};
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_reduced_round_blake3_air() {
+    const NUM_ROUNDS: usize = 2;
+    let inputs = (0..8).map(|_| random()).collect::<Vec<_>>();
+    let trace = generate_trace_rows_with_rounds::<Val, NUM_ROUNDS>(inputs, 0);
+    assert_eq!(trace.width, num_blake3_cols::<NUM_ROUNDS>());
+    assert!(trace.width < NUM_BLAKE3_COLS);
+
+    let air = Blake3Air::<NUM_ROUNDS> {};
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_blake3_limb_air() {
+    let inputs: Vec<[u32; 24]> = (0..4).map(|_| random()).collect();
+    let bit_trace = generate_trace_rows::<Val>(inputs.clone(), 0);
+    let mut multiplicities = ByteMultiplicities::default();
+    let limb_trace = generate_limb_trace_rows::<Val, NUM_FULL_ROUNDS>(inputs, &mut multiplicities);
+    assert!(limb_trace.width < bit_trace.width);
+
+    // Both layouts compute the same compressions.
+    for i in 0..bit_trace.height() {
+        let (bit_row, limb_row) = (bit_trace.row_slice(i), limb_trace.row_slice(i));
+        let bit_row: &Blake3Cols<Val> = (*bit_row).borrow();
+        let limb_row: &Blake3LimbCols<Val> = (*limb_row).borrow();
+        assert_eq!(bit_row.inputs, limb_row.inputs);
+        assert_eq!(bit_row.chaining_values, limb_row.chaining_values);
+        assert_eq!(bit_row.outputs, limb_row.outputs);
+    }
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let air: Blake3Air = Blake3Air {};
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, bit_trace, &[]);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+
+    let [compressions, bytes] = Blake3LimbTable::<NUM_FULL_ROUNDS>::airs();
+    let airs_and_traces = [
+        (compressions, limb_trace),
+        (bytes, multiplicities.generate_trace_rows()),
+    ];
+    let degree_bits = airs_and_traces
+        .iter()
+        .map(|(_, trace)| log2_strict_usize(trace.height()))
+        .collect::<Vec<_>>();
+    let airs = Blake3LimbTable::<NUM_FULL_ROUNDS>::airs();
+    let (prover_data, vk) = setup_multiple_preprocessed(&config, &airs, &degree_bits).unwrap();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple_with_lookups(
+        &config,
+        &airs_and_traces,
+        &mut challenger,
+        &[],
+        Some(&prover_data),
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_multiple_with_lookups(&config, &airs, &mut challenger, &proof, &[], Some(&vk))
+        .expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "lookup sends and receives do not balance")]
+fn test_wrong_blake3_limb_output() {
+    let inputs: Vec<[u32; 24]> = (0..4).map(|_| random()).collect();
+    let mut multiplicities = ByteMultiplicities::default();
+    let mut limb_trace =
+        generate_limb_trace_rows::<Val, NUM_FULL_ROUNDS>(inputs, &mut multiplicities);
+
+    // Flip a bit of the xor of rows 1 and 3, which only the lookups check.
+    let row: &mut Blake3LimbCols<Val> = limb_trace.row_mut(0).borrow_mut();
+    row.outputs[1][0][0] = Val::ONE - row.outputs[1][0][0];
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let [compressions, bytes] = Blake3LimbTable::<NUM_FULL_ROUNDS>::airs();
+    let airs_and_traces = [
+        (compressions, limb_trace),
+        (bytes, multiplicities.generate_trace_rows()),
+    ];
+    let degree_bits = airs_and_traces
+        .iter()
+        .map(|(_, trace)| log2_strict_usize(trace.height()))
+        .collect::<Vec<_>>();
+    let airs = Blake3LimbTable::<NUM_FULL_ROUNDS>::airs();
+    let (prover_data, _) = setup_multiple_preprocessed(&config, &airs, &degree_bits).unwrap();
+    let mut challenger = Challenger::new(perm);
+    prove_multiple_with_lookups(
+        &config,
+        &airs_and_traces,
+        &mut challenger,
+        &[],
+        Some(&prover_data),
+    );
+}
@@ -0,0 +1,135 @@
+use core::borrow::{Borrow, BorrowMut};
+
+use p3_blake3::Blake3;
+use p3_blake3_air::{
+    generate_hash_trace_rows, Blake3HashAir, Blake3HashCols, BLOCK_LEN, DIGEST_LEN,
+    NUM_BLAKE3_HASH_COLS,
+};
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{debug_constraints, prove, verify};
+use rand::thread_rng;
+
+/// Messages of zero, one, two and the maximum of sixteen blocks.
+fn messages() -> Vec<Vec<u8>> {
+    [0, 3, 65, 1024]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (i * 7 + len) as u8).collect())
+        .collect()
+}
+
+/// The output of the given row as bytes.
+fn row_output(row: &Blake3HashCols<Val>) -> Vec<u8> {
+    row.compression.outputs[..2]
+        .iter()
+        .flatten()
+        .flat_map(|bits| {
+            let word = bits
+                .iter()
+                .rev()
+                .fold(0, |word, bit| (word << 1) | bit.as_canonical_u32());
+            word.to_le_bytes()
+        })
+        .collect()
+}
+
+#[test]
+fn test_blake3_hash_air() {
+    let messages = messages();
+    let message_slices = messages.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let trace = generate_hash_trace_rows::<Val>(&message_slices, 0);
+
+    // The last row of each message outputs its hash.
+    let mut row_index = 0;
+    for message in &messages {
+        row_index += message.len().div_ceil(BLOCK_LEN).max(1);
+        let row = trace.row_slice(row_index - 1);
+        let row: &Blake3HashCols<Val> = (*row).borrow();
+        assert_eq!(row_output(row), Blake3.hash_iter(message.iter().copied()));
+    }
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &Blake3HashAir::default(),
+        &mut challenger,
+        trace,
+        &[],
+    );
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &Blake3HashAir::default(),
+        &mut challenger,
+        &proof,
+        &[],
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_blake3_hash_air_public_digests() {
+    let messages = messages();
+    let message_slices = messages.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let air = Blake3HashAir::new(3);
+    let trace = generate_hash_trace_rows::<Val>(&message_slices, 3);
+    let digests = messages[..3]
+        .iter()
+        .map(|message| Blake3.hash_iter(message.iter().copied()))
+        .collect::<Vec<_>>();
+    let public_values = Blake3HashAir::public_values::<Val>(&digests);
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+
+    let mut wrong_digests = digests;
+    wrong_digests[2][0] ^= 1;
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &air,
+        &mut challenger,
+        &proof,
+        &Blake3HashAir::public_values::<Val>(&wrong_digests),
+    )
+    .expect_err("verification should fail with the wrong digest");
+}
+
+#[test]
+fn test_blake3_hash_air_missing_digest() {
+    // The trace has a single message, but claims the hash of a second one.
+    let trace = generate_hash_trace_rows::<Val>(&[b"abc"], 1);
+    let trace = RowMajorMatrix::new(
+        trace
+            .values
+            .chunks_exact(trace.width())
+            .flat_map(|row| row.iter().copied().chain([Val::ZERO]))
+            .collect(),
+        NUM_BLAKE3_HASH_COLS + 2,
+    );
+    let digests = [Blake3.hash_iter(*b"abc"), [0; DIGEST_LEN]];
+    debug_constraints(
+        &Blake3HashAir::new(2),
+        &trace,
+        &Blake3HashAir::public_values(&digests),
+    )
+    .expect_err("every public digest should be the hash of a message");
+}
+
+#[test]
+fn test_blake3_hash_air_wrong_padding() {
+    let mut trace = generate_hash_trace_rows::<Val>(&[b"abc"], 0);
+    // Claim the fourth byte of the block is part of the message, which disagrees with the length.
+    let row: &mut Blake3HashCols<Val> = trace.row_mut(0).borrow_mut();
+    row.is_padding[3] = Val::ZERO;
+    debug_constraints(&Blake3HashAir::default(), &trace, &[])
+        .expect_err("padding should determine the block length");
+}
@@ -0,0 +1,64 @@
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::columns::{BYTE_PREPROCESSED_COL_MAP, NUM_BYTE_MULTIPLICITY_COLS};
+use crate::{generate_preprocessed_trace, ByteOpcode};
+
+/// A lookup table of the operations of `ByteOpcode` on every pair of bytes.
+///
+/// The table is the preprocessed trace, of height `NUM_BYTE_PAIRS`, so the main trace, which holds
+/// the multiplicity of each lookup, must have that height too. Each row receives every operation
+/// on its pair of bytes on `bus`, as many times as its multiplicity. The AIR has no constraints of
+/// its own.
+#[derive(Debug)]
+pub struct ByteLookupAir {
+    pub bus: usize,
+}
+
+impl ByteLookupAir {
+    pub const fn new(bus: usize) -> Self {
+        Self { bus }
+    }
+}
+
+impl<F: Field> BaseAir<F> for ByteLookupAir {
+    fn width(&self) -> usize {
+        NUM_BYTE_MULTIPLICITY_COLS
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(generate_preprocessed_trace())
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ByteLookupAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+impl<F: Field> LookupAir<F> for ByteLookupAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let cols = BYTE_PREPROCESSED_COL_MAP;
+        ByteOpcode::ALL
+            .into_iter()
+            .map(|opcode| {
+                let result = match opcode {
+                    ByteOpcode::Range => VirtualPairCol::constant(F::ZERO),
+                    ByteOpcode::Xor => VirtualPairCol::single_preprocessed(cols.xor),
+                    ByteOpcode::And => VirtualPairCol::single_preprocessed(cols.and),
+                };
+                Interaction::receive(
+                    opcode.values(
+                        VirtualPairCol::single_preprocessed(cols.a),
+                        VirtualPairCol::single_preprocessed(cols.b),
+                        result,
+                    ),
+                    VirtualPairCol::single_main(opcode as usize),
+                    self.bus,
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,79 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::{size_of, transmute};
+
+use p3_util::indices_arr;
+
+use crate::NUM_BYTE_OPCODES;
+
+/// The preprocessed columns of `ByteLookupAir`, with a row for every pair of bytes.
+#[repr(C)]
+pub struct BytePreprocessedCols<T> {
+    pub a: T,
+    pub b: T,
+    pub xor: T,
+    pub and: T,
+}
+
+pub const NUM_BYTE_PREPROCESSED_COLS: usize = size_of::<BytePreprocessedCols<u8>>();
+pub(crate) const BYTE_PREPROCESSED_COL_MAP: BytePreprocessedCols<usize> =
+    make_preprocessed_col_map();
+
+const fn make_preprocessed_col_map() -> BytePreprocessedCols<usize> {
+    let indices_arr = indices_arr::<NUM_BYTE_PREPROCESSED_COLS>();
+    unsafe {
+        transmute::<[usize; NUM_BYTE_PREPROCESSED_COLS], BytePreprocessedCols<usize>>(indices_arr)
+    }
+}
+
+/// The main columns of `ByteLookupAir`: the number of times each operation is looked up on the
+/// pair of bytes of the row, indexed by `ByteOpcode`.
+#[repr(C)]
+pub struct ByteMultiplicityCols<T> {
+    pub multiplicities: [T; NUM_BYTE_OPCODES],
+}
+
+pub const NUM_BYTE_MULTIPLICITY_COLS: usize = size_of::<ByteMultiplicityCols<u8>>();
+
+impl<T> Borrow<BytePreprocessedCols<T>> for [T] {
+    fn borrow(&self) -> &BytePreprocessedCols<T> {
+        debug_assert_eq!(self.len(), NUM_BYTE_PREPROCESSED_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<BytePreprocessedCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T> BorrowMut<BytePreprocessedCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut BytePreprocessedCols<T> {
+        debug_assert_eq!(self.len(), NUM_BYTE_PREPROCESSED_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<BytePreprocessedCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
+
+impl<T> Borrow<ByteMultiplicityCols<T>> for [T] {
+    fn borrow(&self) -> &ByteMultiplicityCols<T> {
+        debug_assert_eq!(self.len(), NUM_BYTE_MULTIPLICITY_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<ByteMultiplicityCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T> BorrowMut<ByteMultiplicityCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut ByteMultiplicityCols<T> {
+        debug_assert_eq!(self.len(), NUM_BYTE_MULTIPLICITY_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<ByteMultiplicityCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
@@ -0,0 +1,72 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::BorrowMut;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::columns::{
+    BytePreprocessedCols, NUM_BYTE_MULTIPLICITY_COLS, NUM_BYTE_PREPROCESSED_COLS,
+};
+use crate::{ByteOpcode, NUM_BYTE_OPCODES, NUM_BYTE_PAIRS};
+
+/// The index of the row of the table for the pair of bytes `(a, b)`.
+const fn row_index(a: u8, b: u8) -> usize {
+    ((a as usize) << 8) | b as usize
+}
+
+/// Generates the preprocessed trace of `ByteLookupAir`.
+pub fn generate_preprocessed_trace<F: Field>() -> RowMajorMatrix<F> {
+    let mut trace = RowMajorMatrix::new(
+        F::zero_vec(NUM_BYTE_PAIRS * NUM_BYTE_PREPROCESSED_COLS),
+        NUM_BYTE_PREPROCESSED_COLS,
+    );
+    for (i, row) in trace.rows_mut().enumerate() {
+        let (a, b) = ((i >> 8) as u8, i as u8);
+        let row: &mut BytePreprocessedCols<F> = row.borrow_mut();
+        row.a = F::from_canonical_u8(a);
+        row.b = F::from_canonical_u8(b);
+        row.xor = F::from_canonical_u8(ByteOpcode::Xor.apply(a, b));
+        row.and = F::from_canonical_u8(ByteOpcode::And.apply(a, b));
+    }
+    trace
+}
+
+/// The number of times each operation is looked up on each pair of bytes, collected while
+/// generating the traces of the AIRs which send the lookups.
+#[derive(Clone, Debug)]
+pub struct ByteMultiplicities {
+    counts: Vec<[u32; NUM_BYTE_OPCODES]>,
+}
+
+impl Default for ByteMultiplicities {
+    fn default() -> Self {
+        Self {
+            counts: vec![[0; NUM_BYTE_OPCODES]; NUM_BYTE_PAIRS],
+        }
+    }
+}
+
+impl ByteMultiplicities {
+    /// Record a lookup of `opcode` on `a` and `b`, and return its result.
+    pub fn add(&mut self, opcode: ByteOpcode, a: u8, b: u8) -> u8 {
+        self.counts[row_index(a, b)][opcode as usize] += 1;
+        opcode.apply(a, b)
+    }
+
+    /// The number of times `opcode` has been looked up on `a` and `b`.
+    pub fn get(&self, opcode: ByteOpcode, a: u8, b: u8) -> u32 {
+        self.counts[row_index(a, b)][opcode as usize]
+    }
+
+    /// Generates the main trace of `ByteLookupAir` receiving the recorded lookups.
+    pub fn generate_trace_rows<F: Field>(&self) -> RowMajorMatrix<F> {
+        let values = self
+            .counts
+            .iter()
+            .flatten()
+            .map(|&count| F::from_canonical_u32(count))
+            .collect();
+        RowMajorMatrix::new(values, NUM_BYTE_MULTIPLICITY_COLS)
+    }
+}
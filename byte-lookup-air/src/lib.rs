@@ -0,0 +1,25 @@
+//! A lookup table of 8-bit operations, with which other AIRs can check range checks and bitwise
+//! operations on bytes by lookups rather than by constraints on bits.
+//!
+//! `ByteLookupAir` has a preprocessed trace with a row for every pair of bytes `(a, b)`, holding
+//! `a ^ b` and `a & b`, and a main trace counting how many times each row is looked up by each
+//! operation. Other AIRs send the tuples of their operations on the same bus, using the helpers
+//! of `ByteOpcode`, and `ByteMultiplicities` collects those tuples while generating their traces
+//! to produce the main trace of the table.
+
+#![no_std]
+
+extern crate alloc;
+
+mod air;
+mod columns;
+mod generation;
+mod opcode;
+
+pub use air::*;
+pub use columns::*;
+pub use generation::*;
+pub use opcode::*;
+
+/// The number of rows of the table, one for each pair of bytes.
+pub const NUM_BYTE_PAIRS: usize = 1 << 16;
@@ -0,0 +1,70 @@
+use alloc::vec;
+
+use p3_air::{Interaction, VirtualPairCol};
+use p3_field::Field;
+
+/// The number of operations in the table.
+pub const NUM_BYTE_OPCODES: usize = 3;
+
+/// An operation on a pair of bytes, checked by a lookup into `ByteLookupAir`.
+///
+/// A lookup is the tuple `(opcode, a, b, result)`, which is in the table exactly when `a` and `b`
+/// are bytes and `result` is the result of the operation on them. A range check has no result, so
+/// its result is zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOpcode {
+    Range,
+    Xor,
+    And,
+}
+
+impl ByteOpcode {
+    pub const ALL: [Self; NUM_BYTE_OPCODES] = [Self::Range, Self::Xor, Self::And];
+
+    /// The result of the operation on `a` and `b`.
+    pub const fn apply(self, a: u8, b: u8) -> u8 {
+        match self {
+            Self::Range => 0,
+            Self::Xor => a ^ b,
+            Self::And => a & b,
+        }
+    }
+
+    /// The interaction sending the lookup of this operation on `a` and `b`, with the given result,
+    /// on `bus`, as many times as `multiplicity`.
+    pub fn send<F: Field>(
+        self,
+        a: VirtualPairCol<F>,
+        b: VirtualPairCol<F>,
+        result: VirtualPairCol<F>,
+        multiplicity: VirtualPairCol<F>,
+        bus: usize,
+    ) -> Interaction<F> {
+        Interaction::send(self.values(a, b, result), multiplicity, bus)
+    }
+
+    /// The interaction sending a range check of `a` and `b` on `bus`, as many times as
+    /// `multiplicity`.
+    pub fn send_range_check<F: Field>(
+        a: VirtualPairCol<F>,
+        b: VirtualPairCol<F>,
+        multiplicity: VirtualPairCol<F>,
+        bus: usize,
+    ) -> Interaction<F> {
+        Self::Range.send(a, b, VirtualPairCol::constant(F::ZERO), multiplicity, bus)
+    }
+
+    pub(crate) fn values<F: Field>(
+        self,
+        a: VirtualPairCol<F>,
+        b: VirtualPairCol<F>,
+        result: VirtualPairCol<F>,
+    ) -> vec::Vec<VirtualPairCol<F>> {
+        vec![
+            VirtualPairCol::constant(F::from_canonical_usize(self as usize)),
+            a,
+            b,
+            result,
+        ]
+    }
+}
@@ -0,0 +1,151 @@
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_byte_lookup_air::{
+    ByteLookupAir, ByteMultiplicities, ByteOpcode, NUM_BYTE_MULTIPLICITY_COLS, NUM_BYTE_PAIRS,
+};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{
+    prove_with_lookups, setup_preprocessed, verify_with_lookups, verify_with_preprocessed,
+};
+use p3_util::log2_strict_usize;
+use rand::{random, thread_rng};
+
+/// Looks up `c = a ^ b` and a range check of `a` and `b` on each row, alongside the table which
+/// receives them.
+struct XorAir {
+    table: ByteLookupAir,
+}
+
+const A: usize = NUM_BYTE_MULTIPLICITY_COLS;
+const B: usize = A + 1;
+const C: usize = A + 2;
+
+impl<F: Field> BaseAir<F> for XorAir {
+    fn width(&self) -> usize {
+        NUM_BYTE_MULTIPLICITY_COLS + 3
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.table.preprocessed_trace()
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for XorAir {
+    fn eval(&self, builder: &mut AB) {
+        self.table.eval(builder);
+    }
+}
+
+impl<F: Field> LookupAir<F> for XorAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let mut interactions = self.table.interactions();
+        interactions.push(ByteOpcode::Xor.send(
+            VirtualPairCol::single_main(A),
+            VirtualPairCol::single_main(B),
+            VirtualPairCol::single_main(C),
+            VirtualPairCol::ONE,
+            self.table.bus,
+        ));
+        interactions.push(ByteOpcode::send_range_check(
+            VirtualPairCol::single_main(A),
+            VirtualPairCol::single_main(B),
+            VirtualPairCol::ONE,
+            self.table.bus,
+        ));
+        interactions
+    }
+}
+
+/// A trace of random lookups, the last of which has the wrong `c` if `corrupt` is set.
+fn generate_trace(corrupt: bool) -> RowMajorMatrix<Val> {
+    let mut multiplicities = ByteMultiplicities::default();
+    let mut rows = Vec::with_capacity(NUM_BYTE_PAIRS);
+    for _ in 0..NUM_BYTE_PAIRS {
+        let (a, b): (u8, u8) = (random(), random());
+        let c = multiplicities.add(ByteOpcode::Xor, a, b);
+        multiplicities.add(ByteOpcode::Range, a, b);
+        rows.push([a, b, c]);
+    }
+    if corrupt {
+        rows[NUM_BYTE_PAIRS - 1][2] ^= 1;
+    }
+
+    let table = multiplicities.generate_trace_rows::<Val>();
+    let values = table
+        .rows()
+        .zip(&rows)
+        .flat_map(|(table_row, row)| {
+            table_row
+                .chain(row.iter().map(|&byte| Val::from_canonical_u8(byte)))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    RowMajorMatrix::new(values, NUM_BYTE_MULTIPLICITY_COLS + 3)
+}
+
+#[test]
+fn test_byte_lookups() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = XorAir {
+        table: ByteLookupAir::new(0),
+    };
+    let trace = generate_trace(false);
+    let (prover_data, vk) =
+        setup_preprocessed(&config, &air, log2_strict_usize(trace.height())).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_lookups(
+        &config,
+        &air,
+        &mut challenger,
+        trace,
+        &[],
+        Some(&prover_data),
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_lookups(&config, &air, &mut challenger, &proof, &[], Some(&vk))
+        .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify_with_preprocessed(&config, &air, &mut challenger, &proof, &[], Some(&vk))
+        .expect_err("verification should fail without the lookups");
+}
+
+#[test]
+fn test_multiplicities() {
+    let mut multiplicities = ByteMultiplicities::default();
+    assert_eq!(multiplicities.add(ByteOpcode::Xor, 0b1100, 0b1010), 0b0110);
+    assert_eq!(multiplicities.add(ByteOpcode::And, 0b1100, 0b1010), 0b1000);
+    assert_eq!(multiplicities.add(ByteOpcode::And, 0b1100, 0b1010), 0b1000);
+    assert_eq!(multiplicities.add(ByteOpcode::Range, 0xFF, 0), 0);
+    assert_eq!(multiplicities.get(ByteOpcode::Xor, 0b1100, 0b1010), 1);
+    assert_eq!(multiplicities.get(ByteOpcode::And, 0b1100, 0b1010), 2);
+    assert_eq!(multiplicities.get(ByteOpcode::Range, 0b1100, 0b1010), 0);
+    assert_eq!(multiplicities.get(ByteOpcode::Range, 0xFF, 0), 1);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "lookup sends and receives do not balance")]
+fn test_wrong_xor() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = XorAir {
+        table: ByteLookupAir::new(0),
+    };
+    let trace = generate_trace(true);
+    let (prover_data, _) =
+        setup_preprocessed(&config, &air, log2_strict_usize(trace.height())).unwrap();
+
+    let mut challenger = Challenger::new(perm);
+    prove_with_lookups(
+        &config,
+        &air,
+        &mut challenger,
+        trace,
+        &[],
+        Some(&prover_data),
+    );
+}
@@ -2,9 +2,11 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_field::{ExtensionField, Field, PrimeField64};
-use p3_symmetric::{CryptographicPermutation, Hash};
+use p3_symmetric::{CryptographicPermutation, Hash, MerkleCap};
 
-use crate::{CanObserve, CanSample, CanSampleBits, FieldChallenger};
+use crate::{
+    CanObserve, CanSample, CanSampleBits, CanSnapshot, ChallengerSnapshot, FieldChallenger,
+};
 
 #[derive(Clone, Debug)]
 pub struct DuplexChallenger<F, P, const WIDTH: usize, const RATE: usize>
@@ -51,6 +53,64 @@ where
     }
 }
 
+/// The number of bits of an integer absorbed or squeezed per field element by the integer and byte
+/// methods of `DuplexChallenger`. Every supported field has more than `2^LIMB_BITS` elements, so
+/// the encoding does not depend on the field.
+const LIMB_BITS: usize = 16;
+
+impl<F, P, const WIDTH: usize, const RATE: usize> DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: PrimeField64,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    /// Observe a `u32`, as two little-endian 16-bit limbs.
+    pub fn observe_u32(&mut self, value: u32) {
+        for i in 0..2 {
+            self.observe(F::from_canonical_u32((value >> (i * LIMB_BITS)) & 0xffff));
+        }
+    }
+
+    /// Observe a `u64`, as four little-endian 16-bit limbs.
+    pub fn observe_u64(&mut self, value: u64) {
+        for i in 0..4 {
+            self.observe(F::from_canonical_u64((value >> (i * LIMB_BITS)) & 0xffff));
+        }
+    }
+
+    /// Observe a byte string.
+    ///
+    /// The length is observed first, via `observe_u64`, followed by the bytes in little-endian
+    /// pairs, the last of which is zero-padded if the length is odd.
+    pub fn observe_bytes(&mut self, bytes: &[u8]) {
+        self.observe_u64(bytes.len() as u64);
+        for pair in bytes.chunks(2) {
+            let lo = pair[0] as u32;
+            let hi = pair.get(1).copied().unwrap_or(0) as u32;
+            self.observe(F::from_canonical_u32(lo | (hi << 8)));
+        }
+    }
+
+    /// Sample a `u32` from the low 16 bits of two field elements.
+    pub fn sample_u32(&mut self) -> u32 {
+        (0..2).fold(0, |acc, i| {
+            acc | ((self.sample_limb() as u32) << (i * LIMB_BITS))
+        })
+    }
+
+    /// Sample a `u64` from the low 16 bits of four field elements.
+    ///
+    /// As with `sample_bits`, the result is only uniform up to a bias of roughly `2^16 / |F|` per
+    /// limb.
+    pub fn sample_u64(&mut self) -> u64 {
+        (0..4).fold(0, |acc, i| acc | (self.sample_limb() << (i * LIMB_BITS)))
+    }
+
+    fn sample_limb(&mut self) -> u64 {
+        let rand_f: F = self.sample();
+        rand_f.as_canonical_u64() & 0xffff
+    }
+}
+
 impl<F, P, const WIDTH: usize, const RATE: usize> FieldChallenger<F>
     for DuplexChallenger<F, P, WIDTH, RATE>
 where
@@ -59,6 +119,32 @@ where
 {
 }
 
+impl<F, P, const WIDTH: usize, const RATE: usize> CanSnapshot
+    for DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: Field,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    type Snapshot = ChallengerSnapshot<F, F>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        ChallengerSnapshot {
+            sponge_state: self.sponge_state.to_vec(),
+            input_buffer: self.input_buffer.clone(),
+            output_buffer: self.output_buffer.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.sponge_state = snapshot
+            .sponge_state
+            .try_into()
+            .expect("snapshot of a sponge of another width");
+        self.input_buffer = snapshot.input_buffer;
+        self.output_buffer = snapshot.output_buffer;
+    }
+}
+
 impl<F, P, const WIDTH: usize, const RATE: usize> CanObserve<F>
     for DuplexChallenger<F, P, WIDTH, RATE>
 where
@@ -103,6 +189,19 @@ where
     }
 }
 
+impl<F, P, const N: usize, const WIDTH: usize, const RATE: usize> CanObserve<MerkleCap<F, F, N>>
+    for DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: Copy,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, F, N>) {
+        for digest in cap {
+            self.observe(digest);
+        }
+    }
+}
+
 // for TrivialPcs
 impl<F, P, const WIDTH: usize, const RATE: usize> CanObserve<Vec<Vec<F>>>
     for DuplexChallenger<F, P, WIDTH, RATE>
@@ -154,6 +253,20 @@ where
         let rand_usize = rand_f.as_canonical_u64() as usize;
         rand_usize & ((1 << bits) - 1)
     }
+
+    fn sample_uniform_bits(&mut self, bits: usize) -> usize {
+        debug_assert!(bits < (usize::BITS as usize));
+        debug_assert!((1 << bits) < F::ORDER_U64);
+        // The largest multiple of `2^bits` which is at most the order of the field.
+        let bound = F::ORDER_U64 - F::ORDER_U64 % (1 << bits);
+        loop {
+            let rand_f: F = self.sample();
+            let rand_u64 = rand_f.as_canonical_u64();
+            if rand_u64 < bound {
+                return (rand_u64 & ((1 << bits) - 1)) as usize;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -201,4 +314,97 @@ mod tests {
         let samples = <Chal as CanSample<F>>::sample_vec(&mut duplex_challenger, 16);
         assert_eq!(samples, expected_samples);
     }
+
+    #[test]
+    fn test_domain_separation() {
+        type Chal = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+        // Short enough transcripts are still buffered, which lets us compare what was absorbed.
+        let absorbed = |labels: &[&'static str]| -> Vec<F> {
+            let mut challenger = Chal::new(TestPermutation {});
+            for &label in labels {
+                challenger.observe_labelled(label, F::ONE);
+            }
+            challenger.input_buffer
+        };
+
+        assert_eq!(absorbed(&["alpha"]), absorbed(&["alpha"]));
+        assert_ne!(absorbed(&["alpha"]), absorbed(&["alphb"]));
+        assert_ne!(absorbed(&[""]), vec![F::ONE]);
+        assert_ne!(absorbed(&["ab", "c"]), absorbed(&["a", "bc"]));
+    }
+
+    #[test]
+    fn test_integer_and_byte_observations() {
+        type Chal = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+        let mut challenger = Chal::new(TestPermutation {});
+        challenger.observe_u64(0x0123_4567_89ab_cdef);
+        challenger.observe_u32(0xdead_beef);
+        challenger.observe_bytes(b"abc");
+        let limbs = [
+            0xcdef, 0x89ab, 0x4567, 0x0123, 0xbeef, 0xdead, 3, 0, 0, 0, 0x6261, 0x63,
+        ]
+        .map(F::from_canonical_u32);
+        assert_eq!(challenger.input_buffer, limbs);
+
+        let absorbed = |bytes: &[u8]| {
+            let mut challenger = Chal::new(TestPermutation {});
+            challenger.observe_bytes(bytes);
+            challenger.input_buffer
+        };
+        assert_ne!(absorbed(b"a"), absorbed(b"a\0"));
+        assert_ne!(absorbed(b""), absorbed(b"\0"));
+    }
+
+    #[test]
+    fn test_sample_u64() {
+        type Chal = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+        let mut challenger = Chal::new(TestPermutation {});
+        // Fill the rate, so that the samples are nonzero.
+        (0..RATE as u32).for_each(|i| challenger.observe(F::from_canonical_u32(0x1_0001 * i)));
+        let mut copy = challenger.clone();
+
+        let limbs: [F; 4] = copy.sample_array();
+        let expected = limbs.iter().rev().fold(0, |acc, limb| {
+            (acc << 16) | (limb.as_canonical_u64() & 0xffff)
+        });
+        assert_eq!(challenger.sample_u64(), expected);
+    }
+
+    #[test]
+    fn test_sample_uniform_bits() {
+        type Chal = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+        // With the reversing permutation, the first two samples are `inputs[8]` and `inputs[9]`.
+        let mut inputs = [F::ZERO; RATE];
+        inputs[8] = F::NEG_ONE;
+        inputs[9] = F::from_canonical_u64((5 << 40) | 7);
+        let mut challenger = Chal::new(TestPermutation {});
+        inputs.iter().for_each(|&x| challenger.observe(x));
+        let mut copy = challenger.clone();
+
+        // -1 lies in the final, partial block of 2^40 values, so it is rejected.
+        assert_eq!(copy.sample_bits(40), (1 << 40) - (1 << 32));
+        assert_eq!(challenger.sample_uniform_bits(40), 7);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        type Chal = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+        let mut challenger = Chal::new(TestPermutation {});
+        (0..20).for_each(|element| challenger.observe(F::from_canonical_u8(element)));
+        let _: F = challenger.sample();
+        let snapshot = challenger.snapshot();
+
+        let mut restored = Chal::new(TestPermutation {});
+        restored.restore(snapshot);
+        challenger.observe(F::ONE);
+        restored.observe(F::ONE);
+        let expected: Vec<F> = challenger.sample_vec(20);
+        let actual: Vec<F> = restored.sample_vec(20);
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,91 @@
+use p3_field::Field;
+
+use crate::FieldChallenger;
+
+/// A saved challenger state, which can be restored with `ForkingChallenger::restore_state`.
+#[derive(Clone, Debug)]
+pub struct ChallengerState<C>(C);
+
+/// Checkpointing and forking of a challenger's transcript.
+///
+/// This lets protocols which branch, e.g. batch proving where several sub-proofs share a prefix
+/// of the transcript, reuse the common prefix instead of observing it again for every branch.
+///
+/// Implemented for every `FieldChallenger` which is `Clone`.
+pub trait ForkingChallenger<F: Field>: FieldChallenger<F> + Clone {
+    /// Save the current transcript state.
+    fn save_state(&self) -> ChallengerState<Self> {
+        ChallengerState(self.clone())
+    }
+
+    /// Rewind the transcript to a state previously returned by `save_state`.
+    fn restore_state(&mut self, state: &ChallengerState<Self>) {
+        *self = state.0.clone();
+    }
+
+    /// Branch off a new transcript which continues from the current state.
+    ///
+    /// The branch first observes `label` as a domain separator, so that branches with distinct
+    /// labels produce independent challenges even when they observe the same values afterwards.
+    fn fork(&self, label: &'static str) -> Self {
+        let mut branch = self.clone();
+        branch.observe_domain_separator(label);
+        branch
+    }
+}
+
+impl<F: Field, C: FieldChallenger<F> + Clone> ForkingChallenger<F> for C {}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{CanObserve, CanSample, DuplexChallenger};
+
+    type F = Goldilocks;
+    type Perm = Poseidon2Goldilocks<8>;
+    type Chal = DuplexChallenger<F, Perm, 8, 4>;
+
+    fn challenger() -> Chal {
+        let mut challenger = Chal::new(Perm::new_from_rng_128(&mut thread_rng()));
+        challenger.observe_slice(&[F::ONE, F::TWO, F::from_canonical_u8(3)]);
+        challenger
+    }
+
+    #[test]
+    fn restore_rewinds_transcript() {
+        let mut challenger = challenger();
+        let state = challenger.save_state();
+
+        challenger.observe(F::from_canonical_u8(4));
+        let first: F = challenger.sample();
+
+        challenger.restore_state(&state);
+        challenger.observe(F::from_canonical_u8(4));
+        let second: F = challenger.sample();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn forks_are_independent() {
+        let challenger = challenger();
+
+        let mut fork_a = challenger.fork("a");
+        let mut fork_a_again = challenger.fork("a");
+        let mut fork_b = challenger.fork("b");
+        let mut unforked = challenger.clone();
+
+        let samples: [F; 4] = [
+            fork_a.sample(),
+            fork_a_again.sample(),
+            fork_b.sample(),
+            unforked.sample(),
+        ];
+        assert_eq!(samples[0], samples[1]);
+        assert_ne!(samples[0], samples[2]);
+        assert_ne!(samples[0], samples[3]);
+    }
+}
@@ -1,4 +1,4 @@
-use p3_field::{Field, PrimeField, PrimeField32, PrimeField64};
+use p3_field::{Field, PackedValue, PrimeField, PrimeField32, PrimeField64};
 use p3_maybe_rayon::prelude::*;
 use p3_symmetric::CryptographicPermutation;
 use tracing::instrument;
@@ -10,6 +10,8 @@ pub trait GrindingChallenger:
 {
     type Witness: Field;
 
+    /// Find a witness which passes `check_witness`. This is the least such witness, so that
+    /// proofs are reproducible regardless of how the search is parallelized.
     fn grind(&mut self, bits: usize) -> Self::Witness;
 
     #[must_use]
@@ -19,6 +21,15 @@ pub trait GrindingChallenger:
     }
 }
 
+/// A grinding challenger which can hash several candidate witnesses per permutation call.
+///
+/// This is separate from `GrindingChallenger` so that challengers over permutations without a
+/// packed implementation can still grind.
+pub trait PackedGrindingChallenger: GrindingChallenger {
+    /// Find the same witness as `grind`, checking a packed batch of candidates at once.
+    fn grind_packed(&mut self, bits: usize) -> Self::Witness;
+}
+
 impl<F, P, const WIDTH: usize, const RATE: usize> GrindingChallenger
     for DuplexChallenger<F, P, WIDTH, RATE>
 where
@@ -29,16 +40,84 @@ where
 
     #[instrument(name = "grind for proof-of-work witness", skip_all)]
     fn grind(&mut self, bits: usize) -> Self::Witness {
+        let (state, witness_index) = self.grinding_state();
         let witness = (0..F::ORDER_U64)
             .into_par_iter()
-            .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
+            .map(F::from_canonical_u64)
+            .find_first(|&witness| {
+                let mut state = state;
+                state[witness_index] = witness;
+                self.permutation.permute_mut(&mut state);
+                is_pow_solution(state[RATE - 1], bits)
+            })
+            .expect("failed to find witness");
+        assert!(self.check_witness(bits, witness));
+        witness
+    }
+}
+
+impl<F, P, const WIDTH: usize, const RATE: usize> PackedGrindingChallenger
+    for DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: PrimeField64,
+    P: CryptographicPermutation<[F; WIDTH]> + CryptographicPermutation<[F::Packing; WIDTH]>,
+{
+    /// Checks `F::Packing::WIDTH` candidate witnesses per permutation call.
+    #[instrument(name = "grind for proof-of-work witness", skip_all)]
+    fn grind_packed(&mut self, bits: usize) -> Self::Witness {
+        let (state, witness_index) = self.grinding_state();
+        let packed_state = state.map(F::Packing::from);
+        let width = F::Packing::WIDTH as u64;
+        let witness = (0..F::ORDER_U64.div_ceil(width))
+            .into_par_iter()
+            .map(|batch| {
+                // The last batch may run past the field order; its lanes beyond it repeat the
+                // largest candidate and are skipped.
+                let candidate = |i: usize| batch * width + i as u64;
+                let mut state = packed_state;
+                state[witness_index] = F::Packing::from_fn(|i| {
+                    F::from_canonical_u64(candidate(i).min(F::ORDER_U64 - 1))
+                });
+                self.permutation.permute_mut(&mut state);
+                let samples = state[RATE - 1];
+                (0..F::Packing::WIDTH)
+                    .find(|&i| {
+                        candidate(i) < F::ORDER_U64 && is_pow_solution(samples.as_slice()[i], bits)
+                    })
+                    .map(|i| F::from_canonical_u64(candidate(i)))
+            })
+            .find_first(Option::is_some)
+            .flatten()
             .expect("failed to find witness");
         assert!(self.check_witness(bits, witness));
         witness
     }
 }
 
+impl<F, P, const WIDTH: usize, const RATE: usize> DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: PrimeField64,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    /// The state which `check_witness` would permute, with the witness still to be written at the
+    /// returned index.
+    ///
+    /// Observing a witness and sampling right after always performs a single duplexing, whose
+    /// input is the buffered input followed by the witness. The sample is then the last element of
+    /// the rate.
+    fn grinding_state(&self) -> ([F; WIDTH], usize) {
+        let mut state = self.sponge_state;
+        state[..self.input_buffer.len()].copy_from_slice(&self.input_buffer);
+        (state, self.input_buffer.len())
+    }
+}
+
+/// Whether `sample_bits(bits)` would return zero for the sample `sample`.
+#[inline]
+fn is_pow_solution<F: PrimeField64>(sample: F, bits: usize) -> bool {
+    sample.as_canonical_u64() & ((1 << bits) - 1) == 0
+}
+
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> GrindingChallenger
     for MultiField32Challenger<F, PF, P, WIDTH, RATE>
 where
@@ -53,9 +132,44 @@ where
         let witness = (0..F::ORDER_U64)
             .into_par_iter()
             .map(F::from_canonical_u64)
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
+            .find_first(|witness| self.clone().check_witness(bits, *witness))
             .expect("failed to find witness");
         assert!(self.check_witness(bits, witness));
         witness
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::{Goldilocks, Poseidon2Goldilocks};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::CanObserve;
+
+    type F = Goldilocks;
+    type Perm = Poseidon2Goldilocks<8>;
+    type Chal = DuplexChallenger<F, Perm, 8, 4>;
+
+    #[test]
+    fn grinding_witnesses_pass_check() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        // Cover the witness landing both mid-rate and in the last position of the rate.
+        for num_observed in [0, 1, 3, 4] {
+            let mut challenger = Chal::new(perm.clone());
+            (0..num_observed).for_each(|i| challenger.observe(F::from_canonical_u32(i)));
+
+            let witness = challenger.clone().grind(8);
+            assert!(challenger.clone().check_witness(8, witness));
+
+            // Both searches return the least witness.
+            let least = (0..)
+                .map(F::from_canonical_u64)
+                .find(|&w| challenger.clone().check_witness(8, w))
+                .unwrap();
+            assert_eq!(witness, least);
+            assert_eq!(challenger.clone().grind_packed(8), least);
+        }
+    }
+}
@@ -2,8 +2,10 @@ use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_symmetric::CryptographicHasher;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
-use crate::{CanObserve, CanSample};
+use crate::{CanObserve, CanSample, CanSnapshot, ChallengerSnapshot};
 
 #[derive(Clone, Debug)]
 pub struct HashChallenger<T, H, const OUT_LEN: usize>
@@ -40,6 +42,29 @@ where
     }
 }
 
+/// The snapshot of a `HashChallenger` has no sponge state, since the chaining values are kept in
+/// its input buffer.
+impl<T, H, const OUT_LEN: usize> CanSnapshot for HashChallenger<T, H, OUT_LEN>
+where
+    T: Clone + Serialize + DeserializeOwned,
+    H: CryptographicHasher<T, [T; OUT_LEN]>,
+{
+    type Snapshot = ChallengerSnapshot<T, T>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        ChallengerSnapshot {
+            sponge_state: vec![],
+            input_buffer: self.input_buffer.clone(),
+            output_buffer: self.output_buffer.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.input_buffer = snapshot.input_buffer;
+        self.output_buffer = snapshot.output_buffer;
+    }
+}
+
 impl<T, H, const OUT_LEN: usize> CanObserve<T> for HashChallenger<T, H, OUT_LEN>
 where
     T: Clone,
@@ -5,20 +5,28 @@
 extern crate alloc;
 
 mod duplex_challenger;
+mod forking_challenger;
 mod grinding_challenger;
 mod hash_challenger;
 mod multi_field_challenger;
+mod recording_challenger;
 mod serializing_challenger;
+mod sha256_challenger;
+mod snapshot;
 
 use alloc::vec::Vec;
 use core::array;
 
 pub use duplex_challenger::*;
+pub use forking_challenger::*;
 pub use grinding_challenger::*;
 pub use hash_challenger::*;
 pub use multi_field_challenger::*;
 use p3_field::{Field, FieldExtensionAlgebra};
+pub use recording_challenger::*;
 pub use serializing_challenger::*;
+pub use sha256_challenger::*;
+pub use snapshot::*;
 
 pub trait CanObserve<T> {
     fn observe(&mut self, value: T);
@@ -47,6 +55,16 @@ pub trait CanSample<T> {
 
 pub trait CanSampleBits<T> {
     fn sample_bits(&mut self, bits: usize) -> T;
+
+    /// Sample `bits` bits which are uniformly distributed.
+    ///
+    /// `sample_bits` may take the low bits of a sampled field element, which are biased towards
+    /// small values since the order of the field is not a power of two. Implementations for which
+    /// this is the case override this method to reject samples from the final, partial block of
+    /// `2^bits` values. The default is `sample_bits`, for implementations sampling uniform bytes.
+    fn sample_uniform_bits(&mut self, bits: usize) -> T {
+        self.sample_bits(bits)
+    }
 }
 
 pub trait FieldChallenger<F: Field>:
@@ -60,6 +78,62 @@ pub trait FieldChallenger<F: Field>:
         let vec = self.sample_vec(EF::D);
         EF::from_base_slice(&vec)
     }
+
+    /// Absorb a domain-separation tag into the transcript.
+    ///
+    /// The tag is length-prefixed, so distinct tags are never absorbed as the same sequence of
+    /// elements. Protocols sharing a challenger construction should tag their messages so that a
+    /// transcript of one can never be reinterpreted as a transcript of another.
+    ///
+    /// The default packs the tag into as many bytes per element as are below the order of `F`, up
+    /// to three, so it needs a field of at least 9 bits.
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        // Any value below 2^(bits - 1) is canonical, since the order is at least that.
+        let bytes_per_element = ((F::bits() - 1) / 8).min(3);
+        assert!(
+            bytes_per_element > 0,
+            "domain separators need a field of at least 9 bits"
+        );
+        self.observe(F::from_canonical_usize(label.len()));
+        for chunk in label.as_bytes().chunks(bytes_per_element) {
+            let mut bytes = [0; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            self.observe(F::from_canonical_u32(u32::from_le_bytes(bytes)));
+        }
+    }
+
+    /// Observe `value`, preceded by the domain-separation tag `label`.
+    fn observe_labelled<T>(&mut self, label: &'static str, value: T)
+    where
+        Self: CanObserve<T>,
+    {
+        self.observe_domain_separator(label);
+        self.observe(value);
+    }
+
+    /// Sample a value, after absorbing the domain-separation tag `label`.
+    fn sample_labelled<T>(&mut self, label: &'static str) -> T
+    where
+        Self: CanSample<T>,
+    {
+        self.observe_domain_separator(label);
+        self.sample()
+    }
+
+    /// Sample `bits` random bits, after absorbing the domain-separation tag `label`.
+    fn sample_bits_labelled(&mut self, label: &'static str, bits: usize) -> usize {
+        self.observe_domain_separator(label);
+        self.sample_bits(bits)
+    }
+
+    /// Sample an extension field element, after absorbing the domain-separation tag `label`.
+    fn sample_ext_element_labelled<EF: FieldExtensionAlgebra<F>>(
+        &mut self,
+        label: &'static str,
+    ) -> EF {
+        self.observe_domain_separator(label);
+        self.sample_ext_element()
+    }
 }
 
 impl<C, T> CanObserve<T> for &mut C
@@ -108,6 +182,11 @@ where
     fn sample_bits(&mut self, bits: usize) -> T {
         (**self).sample_bits(bits)
     }
+
+    #[inline(always)]
+    fn sample_uniform_bits(&mut self, bits: usize) -> T {
+        (**self).sample_uniform_bits(bits)
+    }
 }
 
 impl<C, F: Field> FieldChallenger<F> for &mut C
@@ -123,4 +202,9 @@ where
     fn sample_ext_element<EF: FieldExtensionAlgebra<F>>(&mut self) -> EF {
         (**self).sample_ext_element()
     }
+
+    #[inline(always)]
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        (**self).observe_domain_separator(label)
+    }
 }
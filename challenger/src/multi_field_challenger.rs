@@ -2,17 +2,23 @@ use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use p3_field::{reduce_32, split_32, ExtensionField, Field, PrimeField, PrimeField32};
-use p3_symmetric::{CryptographicPermutation, Hash};
+use p3_field::{
+    reduce_32, reduce_mod_32, split_32, ExtensionField, Field, PrimeField, PrimeField32,
+};
+use p3_symmetric::{CryptographicPermutation, Hash, MerkleCap};
 
-use crate::{CanObserve, CanSample, CanSampleBits, FieldChallenger};
+use crate::{
+    CanObserve, CanSample, CanSampleBits, CanSnapshot, ChallengerSnapshot, FieldChallenger,
+};
 
 /// A challenger that operates natively on PF but produces challenges of F: PrimeField32.
 ///
 /// Used for optimizing the cost of recursive proof verification of STARKs in SNARKs.
 ///
 /// SAFETY: There are some bias complications with using this challenger. In particular,
-/// samples are actually random in [0, 2^64) and then reduced to be in F.
+/// samples are actually random in [0, 2^64) and then reduced to be in F. A challenger created with
+/// `new_low_bias` instead reduces each whole PF element into a single F sample, which bounds the
+/// bias of each sample by `|F| / |PF|`, e.g. about `2^-222` for BabyBear samples over BN254.
 #[derive(Clone, Debug)]
 pub struct MultiField32Challenger<F, PF, P, const WIDTH: usize, const RATE: usize>
 where
@@ -25,6 +31,7 @@ where
     output_buffer: Vec<F>,
     permutation: P,
     num_f_elms: usize,
+    low_bias: bool,
 }
 
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> MultiField32Challenger<F, PF, P, WIDTH, RATE>
@@ -44,8 +51,21 @@ where
             output_buffer: vec![],
             permutation,
             num_f_elms,
+            low_bias: false,
         })
     }
+
+    /// Create a challenger which squeezes a single F sample out of each PF element, by reducing
+    /// it modulo the order of F.
+    ///
+    /// Each sample is then within statistical distance `|F| / |PF|` of uniform, so that challenges
+    /// over e.g. a BabyBear or KoalaBear extension drawn from a BN254 sponge have negligible bias,
+    /// at the cost of fewer samples per permutation.
+    pub fn new_low_bias(permutation: P) -> Result<Self, String> {
+        let mut challenger = Self::new(permutation)?;
+        challenger.low_bias = true;
+        Ok(challenger)
+    }
 }
 
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> MultiField32Challenger<F, PF, P, WIDTH, RATE>
@@ -67,9 +87,13 @@ where
 
         self.output_buffer.clear();
         for &pf_val in self.sponge_state.iter() {
-            let f_vals = split_32(pf_val, self.num_f_elms);
-            for f_val in f_vals {
-                self.output_buffer.push(f_val);
+            if self.low_bias {
+                self.output_buffer.push(reduce_mod_32(pf_val));
+            } else {
+                let f_vals = split_32(pf_val, self.num_f_elms);
+                for f_val in f_vals {
+                    self.output_buffer.push(f_val);
+                }
             }
         }
     }
@@ -84,6 +108,33 @@ where
 {
 }
 
+impl<F, PF, P, const WIDTH: usize, const RATE: usize> CanSnapshot
+    for MultiField32Challenger<F, PF, P, WIDTH, RATE>
+where
+    F: PrimeField32,
+    PF: Field,
+    P: CryptographicPermutation<[PF; WIDTH]>,
+{
+    type Snapshot = ChallengerSnapshot<PF, F>;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        ChallengerSnapshot {
+            sponge_state: self.sponge_state.to_vec(),
+            input_buffer: self.input_buffer.clone(),
+            output_buffer: self.output_buffer.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.sponge_state = snapshot
+            .sponge_state
+            .try_into()
+            .expect("snapshot of a sponge of another width");
+        self.input_buffer = snapshot.input_buffer;
+        self.output_buffer = snapshot.output_buffer;
+    }
+}
+
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> CanObserve<F>
     for MultiField32Challenger<F, PF, P, WIDTH, RATE>
 where
@@ -134,6 +185,20 @@ where
     }
 }
 
+impl<F, PF, const N: usize, P, const WIDTH: usize, const RATE: usize>
+    CanObserve<MerkleCap<F, PF, N>> for MultiField32Challenger<F, PF, P, WIDTH, RATE>
+where
+    F: PrimeField32,
+    PF: PrimeField,
+    P: CryptographicPermutation<[PF; WIDTH]>,
+{
+    fn observe(&mut self, cap: MerkleCap<F, PF, N>) {
+        for digest in cap.hashes() {
+            self.observe(digest);
+        }
+    }
+}
+
 // for TrivialPcs
 impl<F, PF, P, const WIDTH: usize, const RATE: usize> CanObserve<Vec<Vec<F>>>
     for MultiField32Challenger<F, PF, P, WIDTH, RATE>
@@ -188,4 +253,61 @@ where
         let rand_usize = rand_f.to_unique_u32() as usize;
         rand_usize & ((1 << bits) - 1)
     }
+
+    fn sample_uniform_bits(&mut self, bits: usize) -> usize {
+        debug_assert!(bits < (usize::BITS as usize));
+        debug_assert!((1 << bits) < F::ORDER_U32);
+        // The largest multiple of `2^bits` which is at most the order of the field.
+        let bound = F::ORDER_U32 - F::ORDER_U32 % (1 << bits);
+        loop {
+            let rand_f: F = self.sample();
+            let rand_u32 = rand_f.to_unique_u32();
+            if rand_u32 < bound {
+                return (rand_u32 & ((1 << bits) - 1)) as usize;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_bn254_fr::{Bn254Fr, Poseidon2Bn254};
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::FieldAlgebra;
+    use p3_symmetric::Permutation;
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+    type EF = BinomialExtensionField<F, 4>;
+    type Perm = Poseidon2Bn254<3>;
+    type Chal = MultiField32Challenger<F, Bn254Fr, Perm, 3, 2>;
+
+    #[test]
+    fn low_bias_samples_reduce_whole_state() {
+        let perm = Perm::new_from_rng(8, 22, &mut thread_rng());
+        let mut challenger = Chal::new_low_bias(perm.clone()).unwrap();
+        let mut default_challenger = Chal::new(perm.clone()).unwrap();
+
+        challenger.observe(F::ONE);
+        default_challenger.observe(F::ONE);
+        let challenge: EF = challenger.sample();
+        let default_challenge: EF = default_challenger.sample();
+        assert_ne!(challenge, default_challenge);
+
+        // Samples are popped from the end of the reduced sponge state.
+        let mut state = [Bn254Fr::ZERO; 3];
+        state[0] = Bn254Fr::ONE;
+        perm.permute_mut(&mut state);
+        let expected = EF::from_base_fn(|i| match i {
+            0..=2 => reduce_mod_32(state[2 - i]),
+            _ => {
+                perm.permute_mut(&mut state);
+                reduce_mod_32(state[2])
+            }
+        });
+        assert_eq!(challenge, expected);
+    }
 }
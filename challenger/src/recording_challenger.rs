@@ -0,0 +1,460 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt::Debug;
+
+use p3_field::Field;
+use serde::{Deserialize, Serialize};
+
+use crate::{CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger};
+
+/// The kind of interaction with a challenger recorded in a transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscriptOp {
+    Observe,
+    Sample,
+    SampleBits { bits: usize },
+    SampleUniformBits { bits: usize },
+    DomainSeparator,
+    Grind { bits: usize },
+}
+
+/// A single recorded interaction with a challenger.
+///
+/// Values are stored in their `Debug` representation, so that interactions of any type can be
+/// recorded and compared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    /// The label which was active when the interaction happened.
+    pub label: &'static str,
+    pub op: TranscriptOp,
+    /// The type of the value, as given by `core::any::type_name`.
+    pub ty: &'static str,
+    /// The observed value, or the value that was sampled.
+    pub value: String,
+}
+
+/// An interaction in a `TranscriptSchema`: a `TranscriptEntry` without its value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaEntry {
+    pub label: String,
+    pub op: TranscriptOp,
+    pub ty: String,
+}
+
+impl SchemaEntry {
+    /// Whether `entry` is this interaction, with any value.
+    pub fn matches(&self, entry: &TranscriptEntry) -> bool {
+        self.label == entry.label && self.op == entry.op && self.ty == entry.ty
+    }
+}
+
+/// The exact order, kind and type of every interaction of a protocol with its challenger, without
+/// the values.
+///
+/// A schema is recorded by proving or verifying with a `RecordingChallenger`. All proofs of the
+/// same shape, e.g. of one AIR over traces of one height under one config, follow the same schema,
+/// so it can be published alongside a verifying key for verifiers written in other languages to be
+/// generated from, or audited against, mechanically.
+///
+/// Type names are those reported by `core::any::type_name`, which are meant to be descriptive and
+/// may change between compiler versions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptSchema {
+    pub entries: Vec<SchemaEntry>,
+}
+
+impl TranscriptSchema {
+    pub fn from_transcript(transcript: &[TranscriptEntry]) -> Self {
+        let entries = transcript
+            .iter()
+            .map(|entry| SchemaEntry {
+                label: entry.label.into(),
+                op: entry.op,
+                ty: entry.ty.into(),
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The position of the first entry of `transcript` which does not follow this schema, or
+    /// `None` if it follows it exactly.
+    ///
+    /// If one of them is a strict prefix of the other, this is the length of the shorter one.
+    pub fn first_divergence(&self, transcript: &[TranscriptEntry]) -> Option<usize> {
+        self.entries
+            .iter()
+            .zip(transcript)
+            .position(|(expected, actual)| !expected.matches(actual))
+            .or_else(|| {
+                (self.entries.len() != transcript.len())
+                    .then(|| self.entries.len().min(transcript.len()))
+            })
+    }
+}
+
+/// A challenger wrapper which records every observation and sample made through it.
+///
+/// Comparing the transcripts recorded on the prover and verifier sides, e.g. with
+/// `first_divergence`, pinpoints where they stop agreeing. A recorded transcript can also be
+/// replayed with `ReplayChallenger`.
+#[derive(Clone, Debug)]
+pub struct RecordingChallenger<C> {
+    inner: C,
+    label: &'static str,
+    transcript: Vec<TranscriptEntry>,
+}
+
+impl<C> RecordingChallenger<C> {
+    pub const fn new(inner: C) -> Self {
+        Self {
+            inner,
+            label: "",
+            transcript: Vec::new(),
+        }
+    }
+
+    /// Set the label attached to all subsequently recorded interactions.
+    pub fn set_label(&mut self, label: &'static str) {
+        self.label = label;
+    }
+
+    pub fn transcript(&self) -> &[TranscriptEntry] {
+        &self.transcript
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The schema of the transcript recorded so far.
+    pub fn schema(&self) -> TranscriptSchema {
+        TranscriptSchema::from_transcript(&self.transcript)
+    }
+
+    /// Consume the wrapper, returning the inner challenger and the recorded transcript.
+    pub fn into_parts(self) -> (C, Vec<TranscriptEntry>) {
+        (self.inner, self.transcript)
+    }
+
+    fn record<T: Debug>(&mut self, op: TranscriptOp, value: &T) {
+        self.transcript.push(TranscriptEntry {
+            label: self.label,
+            op,
+            ty: type_name::<T>(),
+            value: format!("{value:?}"),
+        });
+    }
+}
+
+/// The position of the first entry at which two transcripts differ, or `None` if they are equal.
+///
+/// If one transcript is a strict prefix of the other, this is the length of the shorter one.
+pub fn first_divergence(a: &[TranscriptEntry], b: &[TranscriptEntry]) -> Option<usize> {
+    a.iter()
+        .zip(b)
+        .position(|(x, y)| x != y)
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))
+}
+
+/// A challenger wrapper which re-executes a transcript recorded by a `RecordingChallenger`.
+///
+/// Every interaction is checked against the next entry of the expected transcript, and the first
+/// interaction which differs causes a panic describing both entries. Running e.g. a verifier with
+/// the transcript recorded by the prover thus stops exactly at the first disagreeing call site.
+#[derive(Clone, Debug)]
+pub struct ReplayChallenger<C> {
+    inner: RecordingChallenger<C>,
+    expected: Vec<TranscriptEntry>,
+}
+
+impl<C> ReplayChallenger<C> {
+    pub const fn new(inner: C, expected: Vec<TranscriptEntry>) -> Self {
+        Self {
+            inner: RecordingChallenger::new(inner),
+            expected,
+        }
+    }
+
+    /// Set the label attached to all subsequently replayed interactions.
+    pub fn set_label(&mut self, label: &'static str) {
+        self.inner.set_label(label);
+    }
+
+    /// The number of expected entries which have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.expected.len() - self.inner.transcript.len()
+    }
+
+    /// Consume the wrapper, returning the inner challenger.
+    ///
+    /// Panics if part of the expected transcript was never replayed.
+    pub fn finish(self) -> C {
+        assert_eq!(
+            self.remaining(),
+            0,
+            "transcript ended early; next expected entry: {:?}",
+            self.expected[self.inner.transcript.len()]
+        );
+        self.inner.inner
+    }
+
+    fn check_last(&self) {
+        let index = self.inner.transcript.len() - 1;
+        let actual = &self.inner.transcript[index];
+        match self.expected.get(index) {
+            Some(expected) => assert_eq!(actual, expected, "transcript diverged at entry {index}"),
+            None => panic!("transcript has extra entry {index}: {actual:?}"),
+        }
+    }
+}
+
+impl<C, T> CanObserve<T> for RecordingChallenger<C>
+where
+    C: CanObserve<T>,
+    T: Debug,
+{
+    fn observe(&mut self, value: T) {
+        self.record(TranscriptOp::Observe, &value);
+        self.inner.observe(value);
+    }
+}
+
+impl<C, T> CanSample<T> for RecordingChallenger<C>
+where
+    C: CanSample<T>,
+    T: Debug,
+{
+    fn sample(&mut self) -> T {
+        let value = self.inner.sample();
+        self.record(TranscriptOp::Sample, &value);
+        value
+    }
+}
+
+impl<C, T> CanSampleBits<T> for RecordingChallenger<C>
+where
+    C: CanSampleBits<T>,
+    T: Debug,
+{
+    fn sample_bits(&mut self, bits: usize) -> T {
+        let value = self.inner.sample_bits(bits);
+        self.record(TranscriptOp::SampleBits { bits }, &value);
+        value
+    }
+
+    fn sample_uniform_bits(&mut self, bits: usize) -> T {
+        let value = self.inner.sample_uniform_bits(bits);
+        self.record(TranscriptOp::SampleUniformBits { bits }, &value);
+        value
+    }
+}
+
+impl<C, F> FieldChallenger<F> for RecordingChallenger<C>
+where
+    C: FieldChallenger<F>,
+    F: Field,
+{
+    /// Also makes `label` the label of subsequently recorded interactions.
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        self.label = label;
+        self.record(TranscriptOp::DomainSeparator, &label);
+        self.inner.observe_domain_separator(label);
+    }
+}
+
+impl<C> GrindingChallenger for RecordingChallenger<C>
+where
+    C: GrindingChallenger,
+{
+    type Witness = C::Witness;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        let witness = self.inner.grind(bits);
+        self.record(TranscriptOp::Grind { bits }, &witness);
+        witness
+    }
+}
+
+impl<C, T> CanObserve<T> for ReplayChallenger<C>
+where
+    C: CanObserve<T>,
+    T: Debug,
+{
+    fn observe(&mut self, value: T) {
+        self.inner.observe(value);
+        self.check_last();
+    }
+}
+
+impl<C, T> CanSample<T> for ReplayChallenger<C>
+where
+    C: CanSample<T>,
+    T: Debug,
+{
+    fn sample(&mut self) -> T {
+        let value = self.inner.sample();
+        self.check_last();
+        value
+    }
+}
+
+impl<C, T> CanSampleBits<T> for ReplayChallenger<C>
+where
+    C: CanSampleBits<T>,
+    T: Debug,
+{
+    fn sample_bits(&mut self, bits: usize) -> T {
+        let value = self.inner.sample_bits(bits);
+        self.check_last();
+        value
+    }
+
+    fn sample_uniform_bits(&mut self, bits: usize) -> T {
+        let value = self.inner.sample_uniform_bits(bits);
+        self.check_last();
+        value
+    }
+}
+
+impl<C, F> FieldChallenger<F> for ReplayChallenger<C>
+where
+    C: FieldChallenger<F>,
+    F: Field,
+{
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        FieldChallenger::<F>::observe_domain_separator(&mut self.inner, label);
+        self.check_last();
+    }
+}
+
+impl<C> GrindingChallenger for ReplayChallenger<C>
+where
+    C: GrindingChallenger,
+{
+    type Witness = C::Witness;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        let witness = self.inner.grind(bits);
+        self.check_last();
+        witness
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::Goldilocks;
+    use p3_symmetric::{CryptographicPermutation, Permutation};
+
+    use super::*;
+    use crate::DuplexChallenger;
+
+    type F = Goldilocks;
+
+    #[derive(Clone)]
+    struct TestPermutation {}
+
+    impl Permutation<[F; 8]> for TestPermutation {
+        fn permute_mut(&self, input: &mut [F; 8]) {
+            input.reverse();
+            input[0] += F::ONE;
+        }
+    }
+
+    impl CryptographicPermutation<[F; 8]> for TestPermutation {}
+
+    type Chal = DuplexChallenger<F, TestPermutation, 8, 4>;
+
+    fn run_protocol<C: FieldChallenger<F>>(challenger: &mut C, input: u8) -> (F, usize) {
+        challenger.observe(F::from_canonical_u8(input));
+        challenger.observe(F::TWO);
+        let alpha: F = challenger.sample();
+        let index = challenger.sample_bits(3);
+        (alpha, index)
+    }
+
+    #[test]
+    fn records_and_detects_divergence() {
+        let mut prover = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        prover.set_label("round 1");
+        let (alpha, _) = run_protocol(&mut prover, 5);
+
+        let transcript = prover.transcript();
+        assert_eq!(transcript.len(), 4);
+        assert_eq!(transcript[0].label, "round 1");
+        assert_eq!(transcript[1].op, TranscriptOp::Observe);
+        assert_eq!(transcript[2].op, TranscriptOp::Sample);
+        assert_eq!(transcript[2].value, format!("{alpha:?}"));
+        assert_eq!(transcript[3].op, TranscriptOp::SampleBits { bits: 3 });
+
+        let mut verifier = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        verifier.set_label("round 1");
+        run_protocol(&mut verifier, 6);
+        assert_eq!(
+            first_divergence(prover.transcript(), verifier.transcript()),
+            Some(0)
+        );
+        assert_eq!(
+            first_divergence(prover.transcript(), prover.transcript()),
+            None
+        );
+        assert_eq!(
+            first_divergence(prover.transcript(), &prover.transcript()[..2]),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn schema_ignores_values() {
+        let mut prover = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        FieldChallenger::<F>::observe_domain_separator(&mut prover, "round 1");
+        run_protocol(&mut prover, 5);
+        let schema = prover.schema();
+        assert_eq!(schema.entries.len(), 5);
+        assert_eq!(schema.entries[0].op, TranscriptOp::DomainSeparator);
+        assert_eq!(schema.entries[1].label, "round 1");
+        assert_eq!(schema.entries[1].ty, type_name::<F>());
+        assert_eq!(schema.entries[4].op, TranscriptOp::SampleBits { bits: 3 });
+        assert_eq!(schema.entries[4].ty, type_name::<usize>());
+
+        // Other inputs give other values, but the same schema.
+        let mut verifier = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        FieldChallenger::<F>::observe_domain_separator(&mut verifier, "round 1");
+        run_protocol(&mut verifier, 6);
+        assert_eq!(verifier.schema(), schema);
+        assert_eq!(schema.first_divergence(verifier.transcript()), None);
+        assert_eq!(
+            schema.first_divergence(&verifier.transcript()[..3]),
+            Some(3)
+        );
+
+        let mut other = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        FieldChallenger::<F>::observe_domain_separator(&mut other, "round 2");
+        run_protocol(&mut other, 5);
+        assert_eq!(schema.first_divergence(other.transcript()), Some(0));
+    }
+
+    #[test]
+    fn replay_matching_transcript() {
+        let mut prover = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        let expected = run_protocol(&mut prover, 5);
+        let (_, transcript) = prover.into_parts();
+
+        let mut replay = ReplayChallenger::new(Chal::new(TestPermutation {}), transcript);
+        assert_eq!(run_protocol(&mut replay, 5), expected);
+        replay.finish();
+    }
+
+    #[test]
+    #[should_panic(expected = "transcript diverged at entry 0")]
+    fn replay_panics_on_divergence() {
+        let mut prover = RecordingChallenger::new(Chal::new(TestPermutation {}));
+        run_protocol(&mut prover, 5);
+        let (_, transcript) = prover.into_parts();
+
+        let mut replay = ReplayChallenger::new(Chal::new(TestPermutation {}), transcript);
+        run_protocol(&mut replay, 6);
+    }
+}
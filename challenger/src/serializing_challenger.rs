@@ -3,12 +3,13 @@ use core::marker::PhantomData;
 
 use p3_field::{ExtensionField, PrimeField32, PrimeField64};
 use p3_maybe_rayon::prelude::*;
-use p3_symmetric::{CryptographicHasher, Hash};
+use p3_symmetric::{CryptographicHasher, Hash, MerkleCap};
 use p3_util::log2_ceil_u64;
 use tracing::instrument;
 
 use crate::{
-    CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger, HashChallenger,
+    CanObserve, CanSample, CanSampleBits, CanSnapshot, FieldChallenger, GrindingChallenger,
+    HashChallenger,
 };
 
 /// Given a challenger that can observe and sample bytes, produces a challenger that is able to
@@ -41,6 +42,18 @@ pub struct SerializingChallenger64<F, Inner> {
     _marker: PhantomData<F>,
 }
 
+impl<F, Inner: CanSnapshot> CanSnapshot for SerializingChallenger32<F, Inner> {
+    type Snapshot = Inner::Snapshot;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.inner.snapshot()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.inner.restore(snapshot);
+    }
+}
+
 impl<F: PrimeField32, Inner: CanObserve<u8>> SerializingChallenger32<F, Inner> {
     pub const fn new(inner: Inner) -> Self {
         Self {
@@ -87,6 +100,26 @@ impl<F: PrimeField32, const N: usize, Inner: CanObserve<u8>> CanObserve<Hash<F,
     }
 }
 
+impl<F: PrimeField32, const N: usize, Inner: CanObserve<u8>> CanObserve<MerkleCap<F, u8, N>>
+    for SerializingChallenger32<F, Inner>
+{
+    fn observe(&mut self, cap: MerkleCap<F, u8, N>) {
+        for digest in cap.hashes() {
+            self.observe(digest);
+        }
+    }
+}
+
+impl<F: PrimeField32, const N: usize, Inner: CanObserve<u8>> CanObserve<MerkleCap<F, u64, N>>
+    for SerializingChallenger32<F, Inner>
+{
+    fn observe(&mut self, cap: MerkleCap<F, u64, N>) {
+        for digest in cap.hashes() {
+            self.observe(digest);
+        }
+    }
+}
+
 impl<F, EF, Inner> CanSample<EF> for SerializingChallenger32<F, Inner>
 where
     F: PrimeField32,
@@ -136,7 +169,7 @@ where
         let witness = (0..F::ORDER_U64)
             .into_par_iter()
             .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
+            .find_first(|witness| self.clone().check_witness(bits, *witness))
             .expect("failed to find witness");
         assert!(self.check_witness(bits, witness));
         witness
@@ -148,6 +181,23 @@ where
     F: PrimeField32,
     Inner: CanSample<u8> + CanObserve<u8> + Clone + Send + Sync,
 {
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        self.inner
+            .observe_slice(&(label.len() as u64).to_le_bytes());
+        self.inner.observe_slice(label.as_bytes());
+    }
+}
+
+impl<F, Inner: CanSnapshot> CanSnapshot for SerializingChallenger64<F, Inner> {
+    type Snapshot = Inner::Snapshot;
+
+    fn snapshot(&self) -> Self::Snapshot {
+        self.inner.snapshot()
+    }
+
+    fn restore(&mut self, snapshot: Self::Snapshot) {
+        self.inner.restore(snapshot);
+    }
 }
 
 impl<F: PrimeField64, Inner: CanObserve<u8>> SerializingChallenger64<F, Inner> {
@@ -196,6 +246,26 @@ impl<F: PrimeField64, const N: usize, Inner: CanObserve<u8>> CanObserve<Hash<F,
     }
 }
 
+impl<F: PrimeField64, const N: usize, Inner: CanObserve<u8>> CanObserve<MerkleCap<F, u8, N>>
+    for SerializingChallenger64<F, Inner>
+{
+    fn observe(&mut self, cap: MerkleCap<F, u8, N>) {
+        for digest in cap.hashes() {
+            self.observe(digest);
+        }
+    }
+}
+
+impl<F: PrimeField64, const N: usize, Inner: CanObserve<u8>> CanObserve<MerkleCap<F, u64, N>>
+    for SerializingChallenger64<F, Inner>
+{
+    fn observe(&mut self, cap: MerkleCap<F, u64, N>) {
+        for digest in cap.hashes() {
+            self.observe(digest);
+        }
+    }
+}
+
 impl<F, EF, Inner> CanSample<EF> for SerializingChallenger64<F, Inner>
 where
     F: PrimeField64,
@@ -246,7 +316,7 @@ where
         let witness = (0..F::ORDER_U64)
             .into_par_iter()
             .map(|i| F::from_canonical_u64(i))
-            .find_any(|witness| self.clone().check_witness(bits, *witness))
+            .find_first(|witness| self.clone().check_witness(bits, *witness))
             .expect("failed to find witness");
         assert!(self.check_witness(bits, witness));
         witness
@@ -258,4 +328,9 @@ where
     F: PrimeField64,
     Inner: CanSample<u8> + CanObserve<u8> + Clone + Send + Sync,
 {
+    fn observe_domain_separator(&mut self, label: &'static str) {
+        self.inner
+            .observe_slice(&(label.len() as u64).to_le_bytes());
+        self.inner.observe_slice(label.as_bytes());
+    }
 }
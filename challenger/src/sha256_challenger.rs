@@ -0,0 +1,128 @@
+//! Challengers over SHA-256, whose transcripts can be regenerated by any environment with a
+//! SHA-256 implementation, such as enclaves, secure elements or on-chain verifiers.
+//!
+//! The transcript is fully specified as follows.
+//!
+//! **State**: a byte string `input`, initially the domain separator passed on construction, and a
+//! byte string `output`, initially empty.
+//!
+//! **Observing** a field element appends its canonical representative to `input`, as 4
+//! little-endian bytes for `Sha256Challenger32` and 8 little-endian bytes for
+//! `Sha256Challenger64`, and clears `output`. Digests are appended to `input` as is.
+//!
+//! **Sampling a byte**: if `output` is empty, compute `d = SHA-256(input)`, then set both `input`
+//! and `output` to `d`. Remove and return the last byte of `output`, so a digest is consumed from
+//! its last byte to its first.
+//!
+//! **Sampling a field element** of a field of order `p`: sample 4 (resp. 8) bytes and interpret
+//! them, in the order they were sampled, as a little-endian `u32` (resp. `u64`). Keep the low
+//! `ceil(log2(p))` bits, and return the result if it is less than `p`; otherwise start over.
+//! Extension field elements are sampled one coefficient at a time.
+//!
+//! **Sampling `bits` bits**: sample 4 (resp. 8) bytes as above and keep the low `bits` bits.
+
+use p3_field::{PrimeField32, PrimeField64};
+use p3_sha256::Sha256;
+
+use crate::{HashChallenger, SerializingChallenger32, SerializingChallenger64};
+
+/// A challenger over a `PrimeField32`, following the SHA-256 transcript specified above.
+pub type Sha256Challenger32<F> = SerializingChallenger32<F, HashChallenger<u8, Sha256, 32>>;
+
+/// A challenger over a `PrimeField64`, following the SHA-256 transcript specified above.
+pub type Sha256Challenger64<F> = SerializingChallenger64<F, HashChallenger<u8, Sha256, 32>>;
+
+impl<F: PrimeField32> SerializingChallenger32<F, HashChallenger<u8, Sha256, 32>> {
+    pub fn new_sha256(domain_separator: &[u8]) -> Self {
+        Self::from_hasher(domain_separator.to_vec(), Sha256)
+    }
+}
+
+impl<F: PrimeField64> SerializingChallenger64<F, HashChallenger<u8, Sha256, 32>> {
+    pub fn new_sha256(domain_separator: &[u8]) -> Self {
+        Self::from_hasher(domain_separator.to_vec(), Sha256)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::FieldAlgebra;
+    use p3_goldilocks::Goldilocks;
+    use p3_symmetric::CryptographicHasher;
+
+    use super::*;
+    use crate::{CanObserve, CanSample, CanSampleBits};
+
+    /// An independent implementation of byte sampling as specified above, used to check that the
+    /// challengers follow the specification.
+    struct ReferenceTranscript {
+        input: Vec<u8>,
+        output: Vec<u8>,
+    }
+
+    impl ReferenceTranscript {
+        fn sample_bytes<const N: usize>(&mut self) -> [u8; N] {
+            core::array::from_fn(|_| {
+                if self.output.is_empty() {
+                    let digest = Sha256.hash_iter_slices([self.input.as_slice()]);
+                    self.input = digest.to_vec();
+                    self.output = digest.to_vec();
+                }
+                self.output.pop().unwrap()
+            })
+        }
+    }
+
+    #[test]
+    fn sha256_challenger32_follows_spec() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<F, 4>;
+
+        let mut challenger = Sha256Challenger32::<F>::new_sha256(b"test");
+        challenger.observe(F::from_canonical_u32(0x1234_5678));
+        let challenge: EF = challenger.sample();
+        let bits = challenger.sample_bits(10);
+
+        let mut input = b"test".to_vec();
+        input.extend(0x1234_5678u32.to_le_bytes());
+        let mut reference = ReferenceTranscript {
+            input,
+            output: vec![],
+        };
+        let expected: EF = EF::from_base_fn(|_| loop {
+            let value = u32::from_le_bytes(reference.sample_bytes()) & ((1 << 31) - 1);
+            if value < F::ORDER_U32 {
+                return F::from_canonical_u32(value);
+            }
+        });
+        assert_eq!(challenge, expected);
+        let expected_bits = u32::from_le_bytes(reference.sample_bytes()) as usize & 0x3ff;
+        assert_eq!(bits, expected_bits);
+    }
+
+    #[test]
+    fn sha256_challenger64_follows_spec() {
+        type F = Goldilocks;
+
+        let mut challenger = Sha256Challenger64::<F>::new_sha256(b"");
+        challenger.observe(F::from_canonical_u64(42));
+        let challenge: F = challenger.sample();
+
+        let mut reference = ReferenceTranscript {
+            input: 42u64.to_le_bytes().to_vec(),
+            output: vec![],
+        };
+        let expected = loop {
+            let value = u64::from_le_bytes(reference.sample_bytes());
+            if value < F::ORDER_U64 {
+                break F::from_canonical_u64(value);
+            }
+        };
+        assert_eq!(challenge, expected);
+    }
+}
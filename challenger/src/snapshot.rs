@@ -0,0 +1,27 @@
+use alloc::vec::Vec;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// A challenger whose transcript state can be exported as plain data and restored later, e.g. to
+/// write it to disk along with a checkpoint of a long-running prover.
+///
+/// Unlike the states saved by `ForkingChallenger`, a snapshot does not include the permutation or
+/// hash of the challenger, so it must be restored into a challenger built the same way.
+pub trait CanSnapshot {
+    type Snapshot: Clone + Serialize + DeserializeOwned;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Continue the transcript from `snapshot`, discarding the current state.
+    fn restore(&mut self, snapshot: Self::Snapshot);
+}
+
+/// The state of a sponge-based challenger: the sponge itself, if any, and its input and output
+/// buffers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChallengerSnapshot<S, T> {
+    pub sponge_state: Vec<S>,
+    pub input_buffer: Vec<T>,
+    pub output_buffer: Vec<T>,
+}
@@ -11,6 +11,7 @@ use p3_util::{log2_ceil_usize, log2_strict_usize};
 use tracing::instrument;
 
 use crate::point::Point;
+use crate::CircleEvaluations;
 
 /// A twin-coset of the circle group on F. It has a power-of-two size and an arbitrary shift.
 ///
@@ -80,17 +81,51 @@ impl<F: ComplexExtendable> CircleDomain<F> {
         }
     }
 
-    pub(crate) fn zeroifier<EF: ExtensionField<F>>(&self, at: Point<EF>) -> EF {
+    /// The point by which the standard twin-coset of this domain is shifted, which is also the
+    /// first point of the domain.
+    pub const fn shift(&self) -> Point<F> {
+        self.shift
+    }
+
+    /// Evaluate at `at` the vanishing polynomial of this domain, `v_n(at) - v_n(shift)`.
+    pub fn zeroifier<EF: ExtensionField<F>>(&self, at: Point<EF>) -> EF {
         at.v_n(self.log_n) - self.shift.v_n(self.log_n)
     }
 
-    pub(crate) fn s_p<EF: ExtensionField<F>>(&self, p: Point<F>, at: Point<EF>) -> EF {
+    /// Evaluate at `at` the selector of the point `p` of this domain, `Z(at) / ṽ_p(at)`, which
+    /// vanishes on the domain except at `p`.
+    /// Circle STARKs, Section 5.1, Lemma 11 (page 21 of the first revision PDF)
+    pub fn s_p<EF: ExtensionField<F>>(&self, p: Point<F>, at: Point<EF>) -> EF {
         self.zeroifier(at) / p.v_tilde_p(at)
     }
 
-    pub(crate) fn s_p_normalized<EF: ExtensionField<F>>(&self, p: Point<F>, at: Point<EF>) -> EF {
+    /// Evaluate at `at` the selector of the point `p` of this domain, normalized by
+    /// [`Point::s_p_at_p`] so that it is one at `p` and zero elsewhere on the domain.
+    pub fn s_p_normalized<EF: ExtensionField<F>>(&self, p: Point<F>, at: Point<EF>) -> EF {
         self.zeroifier(at) / (p.v_tilde_p(at) * p.s_p_at_p(self.log_n))
     }
+
+    /// Evaluate at `at` the polynomial taking the value `values[i % values.len()]` at the `i`-th
+    /// point of this domain.
+    fn periodic_at<EF: ExtensionField<F>>(&self, values: &[F], mut at: Point<EF>) -> EF {
+        assert!(self.is_standard());
+        let log_period = log2_strict_usize(values.len());
+        assert!(log_period <= self.log_n);
+        if log_period == 0 {
+            return EF::from_base(values[0]);
+        }
+        // The `i`-th point of the standard position coset of size `n` is `(2i + 1)` times the
+        // generator of order `2n`, so doubling maps it to the `i % (n / 2)`-th point of the
+        // standard position coset of size `n / 2`.
+        for _ in log_period..self.log_n {
+            at = at.double();
+        }
+        CircleEvaluations::from_natural_order(
+            Self::standard(log_period),
+            RowMajorMatrix::new_col(values.to_vec()),
+        )
+        .evaluate_at_point(at)[0]
+    }
 }
 
 impl<F: ComplexExtendable> PolynomialSpace for CircleDomain<F> {
@@ -184,6 +219,54 @@ impl<F: ComplexExtendable> PolynomialSpace for CircleDomain<F> {
         }
     }
 
+    fn periodic_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        values: &[Self::Val],
+        point: Ext,
+    ) -> Ext {
+        self.periodic_at(values, Point::from_projective_line(point))
+    }
+
+    fn periodic_on_coset(&self, values: &[Self::Val], coset: Self) -> Vec<Self::Val> {
+        coset
+            .points()
+            .map(|point| self.periodic_at(values, point))
+            .collect()
+    }
+
+    fn selector_at_point<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext {
+        assert!(row < self.size());
+        self.s_p_normalized(self.nth_point(row), Point::from_projective_line(point))
+    }
+
+    fn selector_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val> {
+        assert!(row < self.size());
+        let p = self.nth_point(row);
+        coset
+            .points()
+            .map(|at| self.s_p_normalized(p, at))
+            .collect()
+    }
+
+    fn sub_coset_zp_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        num_chunks: usize,
+        chunk: usize,
+        point: Ext,
+    ) -> Ext {
+        self.split_domains(num_chunks)[chunk].zeroifier(Point::from_projective_line(point))
+    }
+
+    fn sub_coset_zp_on_coset(
+        &self,
+        num_chunks: usize,
+        chunk: usize,
+        coset: Self,
+    ) -> Vec<Self::Val> {
+        let sub_domain = self.split_domains(num_chunks)[chunk];
+        coset.points().map(|at| sub_domain.zeroifier(at)).collect()
+    }
+
     /*
     chunks=2:
 
@@ -235,10 +318,9 @@ mod tests {
     use itertools::izip;
     use p3_field::{batch_multiplicative_inverse, FieldAlgebra};
     use p3_mersenne_31::Mersenne31;
-    use rand::thread_rng;
+    use rand::{thread_rng, Rng};
 
     use super::*;
-    use crate::CircleEvaluations;
 
     fn assert_is_twin_coset<F: ComplexExtendable>(d: CircleDomain<F>) {
         let pts = d.points().collect_vec();
@@ -396,6 +478,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalized_selectors() {
+        type F = Mersenne31;
+        let log_n = 5;
+        let n = 1 << log_n;
+
+        let d = CircleDomain::<F>::standard(log_n);
+        let coset = d.create_disjoint_domain(n);
+
+        let coset_to_d = |evals: Vec<F>| {
+            let coeffs =
+                CircleEvaluations::from_natural_order(coset, RowMajorMatrix::new_col(evals))
+                    .interpolate()
+                    .to_row_major_matrix();
+            let (lo, _) = coeffs.split_rows(n);
+            CircleEvaluations::evaluate(d, lo.to_row_major_matrix())
+                .to_natural_order()
+                .to_row_major_matrix()
+                .values
+        };
+
+        for (i, p) in [(0, d.shift()), (n - 1, -d.shift())] {
+            // The unnormalized selector takes the value `s_p_at_p` at `p`.
+            let s_p = coset_to_d(coset.points().map(|at| d.s_p(p, at)).collect());
+            let mut expected = vec![F::ZERO; n];
+            expected[i] = p.s_p_at_p(log_n);
+            assert_eq!(s_p, expected);
+
+            let s_p_normalized =
+                coset_to_d(coset.points().map(|at| d.s_p_normalized(p, at)).collect());
+            expected[i] = F::ONE;
+            assert_eq!(s_p_normalized, expected);
+        }
+    }
+
+    #[test]
+    fn selectors_at_arbitrary_rows() {
+        type F = Mersenne31;
+        let log_n = 5;
+        let n = 1 << log_n;
+
+        let d = CircleDomain::<F>::standard(log_n);
+        let coset = d.create_disjoint_domain(n);
+        for row in [0, 1, 6, 13, n - 1] {
+            let evals = d.selector_on_coset(row, coset);
+            for (&eval, p) in evals.iter().zip(coset.points()) {
+                assert_eq!(
+                    eval,
+                    d.selector_at_point(row, p.to_projective_line().unwrap())
+                );
+            }
+
+            // One at the row's point and zero at the others.
+            let coeffs =
+                CircleEvaluations::from_natural_order(coset, RowMajorMatrix::new_col(evals))
+                    .interpolate()
+                    .to_row_major_matrix();
+            let (lo, hi) = coeffs.split_rows(n);
+            assert_eq!(hi.values, vec![F::ZERO; n]);
+            let on_d = CircleEvaluations::evaluate(d, lo.to_row_major_matrix())
+                .to_natural_order()
+                .to_row_major_matrix()
+                .values;
+            let mut expected = vec![F::ZERO; n];
+            expected[row] = F::ONE;
+            assert_eq!(on_d, expected);
+        }
+    }
+
+    #[test]
+    fn sub_coset_vanishing_polynomials() {
+        type F = Mersenne31;
+        let log_n = 5;
+        let n = 1 << log_n;
+
+        let d = CircleDomain::<F>::standard(log_n);
+        let coset = d.create_disjoint_domain(n);
+        let num_chunks = 4;
+        for (chunk, sub_domain) in d.split_domains(num_chunks).into_iter().enumerate() {
+            // Zero exactly at the points of the sub-domain.
+            let sub_points: HashSet<_> = sub_domain.points().collect();
+            for p in d.points() {
+                let zp =
+                    d.sub_coset_zp_at_point(num_chunks, chunk, p.to_projective_line().unwrap());
+                assert_eq!(zp == F::ZERO, sub_points.contains(&p));
+            }
+
+            let evals = d.sub_coset_zp_on_coset(num_chunks, chunk, coset);
+            for (&eval, p) in evals.iter().zip(coset.points()) {
+                assert_eq!(
+                    eval,
+                    d.sub_coset_zp_at_point(num_chunks, chunk, p.to_projective_line().unwrap())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn periodic() {
+        type F = Mersenne31;
+        let log_n = 6;
+        let n = 1 << log_n;
+
+        let d = CircleDomain::<F>::standard(log_n);
+        let coset = d.create_disjoint_domain(2 * n);
+        for period in [1, 2, 8, n] {
+            let values: Vec<F> = (0..period).map(|_| thread_rng().gen()).collect();
+
+            // periodic_at_point repeats the values on the domain
+            for (i, p) in d.points().enumerate() {
+                assert_eq!(
+                    d.periodic_at_point(&values, p.to_projective_line().unwrap()),
+                    values[i % period]
+                );
+            }
+
+            // periodic_on_coset matches periodic_at_point, and has degree less than the domain
+            let evals = d.periodic_on_coset(&values, coset);
+            for (&eval, p) in evals.iter().zip(coset.points()) {
+                assert_eq!(
+                    eval,
+                    d.periodic_at_point(&values, p.to_projective_line().unwrap())
+                );
+            }
+            let coeffs =
+                CircleEvaluations::from_natural_order(coset, RowMajorMatrix::new_col(evals))
+                    .interpolate()
+                    .values;
+            assert_eq!(&coeffs[n..], &vec![F::ZERO; coset.size() - n]);
+        }
+    }
+
     #[test]
     fn test_circle_domain() {
         do_test_circle_domain(4, 8);
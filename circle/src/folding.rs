@@ -7,15 +7,28 @@ use p3_commit::Mmcs;
 use p3_field::extension::ComplexExtendable;
 use p3_field::{batch_multiplicative_inverse, ExtensionField};
 use p3_fri::FriGenericConfig;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::Matrix;
 use p3_util::{log2_strict_usize, reverse_bits_len};
 
 use crate::domain::CircleDomain;
 use crate::{CircleInputProof, InputError};
 
-pub(crate) struct CircleFriGenericConfig<F, InputProof, InputError>(
-    pub(crate) PhantomData<(F, InputProof, InputError)>,
-);
+pub(crate) struct CircleFriGenericConfig<F, InputProof, InputError> {
+    /// The log of the arity of the first layer fold, done outside of p3-fri, whose bits of the
+    /// query index p3-fri must sample for us.
+    pub(crate) first_layer_log_arity: usize,
+    pub(crate) _phantom: PhantomData<(F, InputProof, InputError)>,
+}
+
+impl<F, InputProof, InputError> CircleFriGenericConfig<F, InputProof, InputError> {
+    pub(crate) const fn new(first_layer_log_arity: usize) -> Self {
+        Self {
+            first_layer_log_arity,
+            _phantom: PhantomData,
+        }
+    }
+}
 
 pub(crate) type CircleFriConfig<Val, Challenge, InputMmcs, FriMmcs> = CircleFriGenericConfig<
     Val,
@@ -30,7 +43,7 @@ impl<F: ComplexExtendable, EF: ExtensionField<F>, InputProof, InputError: Debug>
     type InputError = InputError;
 
     fn extra_query_index_bits(&self) -> usize {
-        1
+        self.first_layer_log_arity
     }
 
     fn fold_row(
@@ -127,6 +140,73 @@ pub(crate) fn fold_x_row<F: ComplexExtendable, EF: ExtensionField<F>>(
     (sum + beta * diff).halve()
 }
 
+/// Fold each row of `evals`, of width `2^log_arity`, into one value: first fold the y coordinate
+/// at `beta`, then fold the x coordinate `log_arity - 1` times, at `beta^2`, `beta^4`, etc.
+///
+/// With a `log_arity` of 1 this is `fold_y`, and each x fold is the one p3-fri would otherwise do
+/// in its own layer.
+pub(crate) fn fold_first_layer<F: ComplexExtendable, EF: ExtensionField<F>>(
+    beta: EF,
+    log_arity: usize,
+    evals: &RowMajorMatrix<EF>,
+) -> Vec<EF> {
+    assert!(log_arity >= 1);
+    assert_eq!(evals.width(), 1 << log_arity);
+    let mut folded = fold_y::<F, EF>(beta, RowMajorMatrixView::new(&evals.values, 2));
+    let mut beta = beta;
+    for _ in 1..log_arity {
+        beta = beta.square();
+        folded = fold_x::<F, EF>(beta, RowMajorMatrix::new(folded, 2));
+    }
+    folded
+}
+
+/// Fold a single row of the first layer, as `fold_first_layer` does, where `index` is the index
+/// of the row and `log_folded_height` the log of the height of the folded column.
+pub(crate) fn fold_first_layer_row<F: ComplexExtendable, EF: ExtensionField<F>>(
+    index: usize,
+    log_folded_height: usize,
+    log_arity: usize,
+    beta: EF,
+    evals: impl Iterator<Item = EF>,
+) -> EF {
+    let evals = evals.collect_vec();
+    assert!(log_arity >= 1);
+    assert_eq!(evals.len(), 1 << log_arity);
+
+    let mut log_height = log_folded_height + log_arity - 1;
+    let mut folded = evals
+        .chunks_exact(2)
+        .enumerate()
+        .map(|(j, pair)| {
+            fold_y_row::<F, EF>(
+                (index << (log_arity - 1)) + j,
+                log_height,
+                beta,
+                pair.iter().copied(),
+            )
+        })
+        .collect_vec();
+    let mut beta = beta;
+    for i in 1..log_arity {
+        beta = beta.square();
+        log_height -= 1;
+        folded = folded
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(j, pair)| {
+                fold_x_row::<F, EF>(
+                    (index << (log_arity - 1 - i)) + j,
+                    log_height,
+                    beta,
+                    pair.iter().copied(),
+                )
+            })
+            .collect_vec();
+    }
+    folded[0]
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::iproduct;
@@ -160,6 +240,36 @@ mod tests {
         assert_eq!(mat_x_folded, row_x_folded);
     }
 
+    #[test]
+    fn first_layer_fold_matrix_same_as_row() {
+        for log_arity in 1..4 {
+            let log_folded_height = 4;
+            let m = RowMajorMatrix::<EF>::rand(
+                &mut thread_rng(),
+                1 << log_folded_height,
+                1 << log_arity,
+            );
+            let beta: EF = random();
+
+            let mat_folded = fold_first_layer::<F, EF>(beta, log_arity, &m);
+            let row_folded = (0..(1 << log_folded_height))
+                .map(|i| {
+                    fold_first_layer_row::<F, EF>(i, log_folded_height, log_arity, beta, m.row(i))
+                })
+                .collect_vec();
+            assert_eq!(mat_folded, row_folded);
+        }
+
+        // An arity of 4 is a y fold followed by an x fold at the square of the challenge.
+        let m = RowMajorMatrix::<EF>::rand(&mut thread_rng(), 1 << 4, 4);
+        let beta: EF = random();
+        let y_folded = fold_y::<F, EF>(beta, RowMajorMatrix::new(m.values.clone(), 2));
+        assert_eq!(
+            fold_first_layer::<F, EF>(beta, 2, &m),
+            fold_x::<F, EF>(beta.square(), RowMajorMatrix::new(y_folded, 2))
+        );
+    }
+
     #[test]
     fn folded_matrix_remains_low_degree() {
         let vec_dim = |evals: &[F]| {
@@ -178,6 +288,10 @@ mod tests {
             .to_cfft_order()
             .values;
 
+            let first_layer =
+                fold_first_layer(random(), 2, &RowMajorMatrix::new(values.clone(), 4));
+            assert_eq!(vec_dim(&first_layer), first_layer.len() >> log_blowup);
+
             values = fold_y(random(), RowMajorMatrix::new(values, 2));
             assert_eq!(vec_dim(&values), values.len() >> log_blowup);
             for _ in 0..(log_n - 1) {
@@ -20,4 +20,5 @@ pub use cfft::*;
 pub use domain::*;
 pub use ordering::*;
 pub use pcs::*;
+pub use point::*;
 pub use proof::*;
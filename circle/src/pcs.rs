@@ -19,7 +19,9 @@ use tracing::info_span;
 
 use crate::deep_quotient::{deep_quotient_reduce_row, extract_lambda};
 use crate::domain::CircleDomain;
-use crate::folding::{fold_y, fold_y_row, CircleFriConfig, CircleFriGenericConfig};
+use crate::folding::{
+    fold_first_layer, fold_first_layer_row, CircleFriConfig, CircleFriGenericConfig,
+};
 use crate::point::Point;
 use crate::prover::prove;
 use crate::verifier::verify;
@@ -29,9 +31,35 @@ use crate::{cfft_permute_index, CfftPermutable, CircleEvaluations, CircleFriProo
 pub struct CirclePcs<Val: Field, InputMmcs, FriMmcs> {
     pub mmcs: InputMmcs,
     pub fri_config: FriConfig<FriMmcs>,
+    /// The log of the arity of the first layer fold, which folds the y coordinate and then, for
+    /// arities above 2, the x coordinate as many times as needed. Proofs with an arity of 2 are
+    /// those of the original protocol.
+    pub first_layer_log_arity: usize,
     pub _phantom: PhantomData<Val>,
 }
 
+impl<Val: Field, InputMmcs, FriMmcs> CirclePcs<Val, InputMmcs, FriMmcs> {
+    pub const fn new(mmcs: InputMmcs, fri_config: FriConfig<FriMmcs>) -> Self {
+        Self {
+            mmcs,
+            fri_config,
+            first_layer_log_arity: 1,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Fold the first layer with an arity of `2^log_arity`, e.g. 2 for an arity of 4, which
+    /// saves `log_arity - 1` FRI layers.
+    pub fn with_first_layer_log_arity(mut self, log_arity: usize) -> Self {
+        assert!(
+            log_arity >= 1,
+            "the first layer must fold at least the y coordinate"
+        );
+        self.first_layer_log_arity = log_arity;
+        self
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
 pub struct BatchOpening<Val: Field, InputMmcs: Mmcs<Val>> {
@@ -56,6 +84,9 @@ pub struct CircleInputProof<
 pub enum InputError<InputMmcsError, FriMmcsError> {
     InputMmcsError(InputMmcsError),
     FirstLayerMmcsError(FriMmcsError),
+    /// The number of first layer siblings does not match the number of first layer columns and
+    /// the arity of the first layer.
+    InvalidFirstLayerSiblings,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -107,9 +138,10 @@ where
             .into_iter()
             .map(|(domain, evals)| {
                 assert!(
-                    domain.log_n >= 2,
-                    "CirclePcs cannot commit to a matrix with fewer than 4 rows.",
-                    // (because we bivariate fold one bit, and fri needs one more bit)
+                    domain.log_n > self.first_layer_log_arity,
+                    "CirclePcs cannot commit to a matrix with fewer than {} rows.",
+                    2 << self.first_layer_log_arity,
+                    // (because we fold log_arity bits in the first layer, and fri needs one more bit)
                 );
                 CircleEvaluations::from_natural_order(domain, evals)
                     .extrapolate(CircleDomain::standard(
@@ -122,6 +154,17 @@ where
         (comm, mmcs_data)
     }
 
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        let ldes = dimensions
+            .iter()
+            .map(|dim| Dimensions {
+                width: dim.width,
+                height: dim.height << self.fri_config.log_blowup,
+            })
+            .collect_vec();
+        self.mmcs.prover_data_size_hint(&ldes)
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         data: &'a Self::ProverData,
@@ -234,8 +277,8 @@ where
                 log_heights.push(log_height);
                 let lambda = extract_lambda(&mut ro, self.fri_config.log_blowup);
                 lambdas.push(lambda);
-                // Prepare for first layer fold with 2 siblings per leaf.
-                RowMajorMatrix::new(ro, 2)
+                // Prepare for first layer fold with `arity` siblings per leaf.
+                RowMajorMatrix::new(ro, 1 << self.first_layer_log_arity)
             })
             .collect();
         let log_max_height = log_heights.iter().max().copied().unwrap();
@@ -257,13 +300,14 @@ where
             .mmcs
             .get_matrices(&first_layer_data)
             .into_iter()
-            .map(|m| fold_y(bivariate_beta, m.as_view()))
+            .map(|m| fold_first_layer(bivariate_beta, self.first_layer_log_arity, m))
             // Reverse, because FRI expects descending by height
             .rev()
             .collect();
 
+        let log_arity = self.first_layer_log_arity;
         let g: CircleFriConfig<Val, Challenge, InputMmcs, FriMmcs> =
-            CircleFriGenericConfig(PhantomData);
+            CircleFriGenericConfig::new(log_arity);
 
         let fri_proof = prove(&g, &self.fri_config, fri_input, challenger, |index| {
             // CircleFriFolder asks for an extra query index bit, so we use that here to index
@@ -283,17 +327,20 @@ where
                 })
                 .collect();
 
-            // We committed to first_layer in pairs, so open the reduced index and include the sibling
-            // as part of the input proof.
+            // We committed to first_layer in rows of `arity`, so open the reduced index and include
+            // the siblings, in order, as part of the input proof.
             let (first_layer_values, first_layer_proof) = self
                 .fri_config
                 .mmcs
-                .open_batch(index >> 1, &first_layer_data);
+                .open_batch(index >> log_arity, &first_layer_data);
             let first_layer_siblings = izip!(&first_layer_values, &log_heights)
-                .map(|(v, log_height)| {
+                .flat_map(|(v, log_height)| {
                     let reduced_index = index >> (log_max_height - log_height);
-                    let sibling_index = (reduced_index & 1) ^ 1;
-                    v[sibling_index]
+                    let own_index = reduced_index & ((1 << log_arity) - 1);
+                    v.iter()
+                        .enumerate()
+                        .filter(move |&(i, _)| i != own_index)
+                        .map(|(_, &sibling)| sibling)
                 })
                 .collect();
             CircleInputProof {
@@ -339,12 +386,13 @@ where
         challenger.observe(proof.first_layer_commitment.clone());
         let bivariate_beta: Challenge = challenger.sample_ext_element();
 
-        // +1 to account for first layer
+        // + log_arity to account for first layer
+        let log_arity = self.first_layer_log_arity;
         let log_global_max_height =
-            proof.fri_proof.commit_phase_commits.len() + self.fri_config.log_blowup + 1;
+            proof.fri_proof.commit_phase_commits.len() + self.fri_config.log_blowup + log_arity;
 
         let g: CircleFriConfig<Val, Challenge, InputMmcs, FriMmcs> =
-            CircleFriGenericConfig(PhantomData);
+            CircleFriGenericConfig::new(log_arity);
 
         verify(
             &g,
@@ -413,43 +461,52 @@ where
 
                 // Verify bivariate fold and lambda correction
 
-                let (mut fri_input, fl_dims, fl_leaves): (Vec<_>, Vec<_>, Vec<_>) =
-                    izip!(reduced_openings, first_layer_siblings, &proof.lambdas)
-                        .map(|((log_height, (_, ro)), &fl_sib, &lambda)| {
-                            assert!(log_height > 0);
-
-                            let orig_size = log_height - self.fri_config.log_blowup;
-                            let bits_reduced = log_global_max_height - log_height;
-                            let orig_idx = cfft_permute_index(index >> bits_reduced, log_height);
-
-                            let lde_domain = CircleDomain::standard(log_height);
-                            let p: Point<Val> = lde_domain.nth_point(orig_idx);
-
-                            let lambda_corrected = ro - lambda * p.v_n(orig_size);
-
-                            let mut fl_values = vec![lambda_corrected; 2];
-                            fl_values[((index >> bits_reduced) & 1) ^ 1] = fl_sib;
-
-                            let fri_input = (
-                                // - 1 here is because we have already folded a layer.
-                                log_height - 1,
-                                fold_y_row(
-                                    index >> (bits_reduced + 1),
-                                    // - 1 here is log_arity.
-                                    log_height - 1,
-                                    bivariate_beta,
-                                    fl_values.iter().cloned(),
-                                ),
-                            );
-
-                            let fl_dims = Dimensions {
-                                width: 0,
-                                height: 1 << (log_height - 1),
-                            };
+                let num_siblings = (1 << log_arity) - 1;
+                if first_layer_siblings.len() != reduced_openings.len() * num_siblings {
+                    return Err(InputError::InvalidFirstLayerSiblings);
+                }
 
-                            (fri_input, fl_dims, fl_values)
-                        })
-                        .multiunzip();
+                let (mut fri_input, fl_dims, fl_leaves): (Vec<_>, Vec<_>, Vec<_>) = izip!(
+                    reduced_openings,
+                    first_layer_siblings.chunks_exact(num_siblings),
+                    &proof.lambdas
+                )
+                .map(|((log_height, (_, ro)), fl_sibs, &lambda)| {
+                    assert!(log_height > 0);
+
+                    let orig_size = log_height - self.fri_config.log_blowup;
+                    let bits_reduced = log_global_max_height - log_height;
+                    let orig_idx = cfft_permute_index(index >> bits_reduced, log_height);
+
+                    let lde_domain = CircleDomain::standard(log_height);
+                    let p: Point<Val> = lde_domain.nth_point(orig_idx);
+
+                    let lambda_corrected = ro - lambda * p.v_n(orig_size);
+
+                    let mut fl_values = fl_sibs.to_vec();
+                    let own_index = (index >> bits_reduced) & num_siblings;
+                    fl_values.insert(own_index, lambda_corrected);
+
+                    let fri_input = (
+                        // - log_arity here is because we have already folded a layer.
+                        log_height - log_arity,
+                        fold_first_layer_row(
+                            index >> (bits_reduced + log_arity),
+                            log_height - log_arity,
+                            log_arity,
+                            bivariate_beta,
+                            fl_values.iter().cloned(),
+                        ),
+                    );
+
+                    let fl_dims = Dimensions {
+                        width: 0,
+                        height: 1 << (log_height - log_arity),
+                    };
+
+                    (fri_input, fl_dims, fl_values)
+                })
+                .multiunzip();
 
                 // sort descending
                 fri_input.reverse();
@@ -459,7 +516,7 @@ where
                     .verify_batch(
                         &proof.first_layer_commitment,
                         &fl_dims,
-                        index >> 1,
+                        index >> log_arity,
                         &fl_leaves,
                         first_layer_proof,
                     )
@@ -486,8 +543,7 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn circle_pcs() {
+    fn do_test_circle_pcs(first_layer_log_arity: usize) {
         // Very simple pcs test. More rigorous tests in p3_fri/tests/pcs.
 
         let mut rng = ChaCha8Rng::from_seed([0; 32]);
@@ -514,35 +570,49 @@ mod tests {
         let fri_config = create_test_fri_config(challenge_mmcs);
 
         type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
-        let pcs = Pcs {
-            mmcs: val_mmcs,
-            fri_config,
-            _phantom: PhantomData,
-        };
-
-        let log_n = 10;
-
-        let d = <Pcs as p3_commit::Pcs<Challenge, Challenger>>::natural_domain_for_degree(
-            &pcs,
-            1 << log_n,
-        );
-
-        let evals = RowMajorMatrix::rand(&mut rng, 1 << log_n, 1);
+        let pcs = Pcs::new(val_mmcs, fri_config).with_first_layer_log_arity(first_layer_log_arity);
+
+        // Matrices of different heights are folded separately in the first layer.
+        let domains_and_evals = [10, 8]
+            .map(|log_n| {
+                let d = <Pcs as p3_commit::Pcs<Challenge, Challenger>>::natural_domain_for_degree(
+                    &pcs,
+                    1 << log_n,
+                );
+                (d, RowMajorMatrix::rand(&mut rng, 1 << log_n, 1))
+            })
+            .to_vec();
+        let domains = domains_and_evals.iter().map(|(d, _)| *d).collect_vec();
 
         let (comm, data) =
-            <Pcs as p3_commit::Pcs<Challenge, Challenger>>::commit(&pcs, vec![(d, evals)]);
+            <Pcs as p3_commit::Pcs<Challenge, Challenger>>::commit(&pcs, domains_and_evals);
 
         let zeta: Challenge = rng.gen();
 
         let mut chal = Challenger::from_hasher(vec![], byte_hash);
-        let (values, proof) = pcs.open(vec![(&data, vec![vec![zeta]])], &mut chal);
+        let (values, proof) = pcs.open(vec![(&data, vec![vec![zeta]; 2])], &mut chal);
 
         let mut chal = Challenger::from_hasher(vec![], byte_hash);
         pcs.verify(
-            vec![(comm, vec![(d, vec![(zeta, values[0][0][0].clone())])])],
+            vec![(
+                comm,
+                izip!(domains, &values[0])
+                    .map(|(d, mat_values)| (d, vec![(zeta, mat_values[0].clone())]))
+                    .collect(),
+            )],
             &proof,
             &mut chal,
         )
         .expect("verify err");
     }
+
+    #[test]
+    fn circle_pcs() {
+        do_test_circle_pcs(1);
+    }
+
+    #[test]
+    fn circle_pcs_first_layer_arity_4() {
+        do_test_circle_pcs(2);
+    }
 }
@@ -62,6 +62,10 @@ impl<F: Field> Point<F> {
     /// Evaluate the vanishing polynomial for the standard position coset of size 2^log_n
     /// at this point
     /// Circle STARKs, Section 3.3, Equation 8 (page 10 of the first revision PDF)
+    ///
+    /// This is the x-coordinate of the point doubled `log_n - 1` times, so `log_n` must be at
+    /// least 1. The vanishing polynomial of any other twin-coset of that size is
+    /// `v_n(at) - v_n(shift)`, see `CircleDomain::zeroifier`.
     pub fn v_n(mut self, log_n: usize) -> F {
         for _ in 0..(log_n - 1) {
             self.x = self.x.square().double() - F::ONE; // TODO: replace this by a custom field impl.
@@ -69,7 +73,7 @@ impl<F: Field> Point<F> {
         self.x
     }
 
-    /// Compute a product of successive `v_n`'s.
+    /// Compute a product of successive `v_n`'s, for `log_n` at least 2.
     ///
     /// More explicitly this computes `(1..log_n).map(|i| self.v_n(i)).product()`
     /// but uses far fewer `self.x.square().double() - F::ONE` steps compared to the naive implementation.
@@ -92,6 +96,8 @@ impl<F: Field> Point<F> {
 
     /// The concrete value of the selector s_P = v_n / (v_0 . T_p⁻¹) at P=self, used for normalization.
     /// Circle STARKs, Section 5.1, Remark 16 (page 22 of the first revision PDF)
+    ///
+    /// This holds when `self` is a point of a twin-coset of size 2^log_n, for `log_n` at least 2.
     pub fn s_p_at_p(self, log_n: usize) -> F {
         -self.v_n_prod(log_n).mul_2exp_u64((2 * log_n - 1) as u64) * self.y
     }
@@ -216,4 +222,28 @@ mod tests {
         let vn_prod_gen = (1..log_n).map(|i| gen.v_n(i)).product();
         assert_eq!(gen.v_n_prod(log_n), vn_prod_gen);
     }
+
+    #[test]
+    fn test_vanishing() {
+        // The standard position coset of size 2^log_n is the coset of the subgroup of that size
+        // by the generator of the subgroup of twice its size.
+        let log_n = 5;
+        let shift = Pt::generator(log_n + 1);
+        let gen = Pt::generator(log_n);
+        let coset: Vec<Pt> = (0..1 << log_n).map(|i| shift + gen * i).collect();
+        for &p in &coset {
+            assert_eq!(p.v_n(log_n), F::ZERO);
+        }
+        assert_ne!(Pt::ZERO.v_n(log_n), F::ZERO);
+        assert_ne!(gen.v_n(log_n), F::ZERO);
+
+        // ṽ_p vanishes at p alone, and has a pole at the antipode of p.
+        let p = coset[3];
+        let antipode = p + Pt::generator(1);
+        for (i, &at) in coset.iter().enumerate() {
+            if at != antipode {
+                assert_eq!(p.v_tilde_p(at) == F::ZERO, i == 3);
+            }
+        }
+    }
 }
@@ -1,12 +1,11 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use core::iter;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
 use p3_field::{ExtensionField, Field};
-use p3_fri::{FriConfig, FriGenericConfig};
+use p3_fri::{sample_query_indices, FriConfig, FriGenericConfig};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_util::log2_strict_usize;
 use tracing::{info_span, instrument};
@@ -41,17 +40,21 @@ where
     let pow_witness = challenger.grind(config.proof_of_work_bits);
 
     let query_proofs = info_span!("query phase").in_scope(|| {
-        iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
-            .take(config.num_queries)
-            .map(|index| CircleQueryProof {
-                input_proof: open_input(index),
-                commit_phase_openings: answer_query(
-                    config,
-                    &commit_phase_result.data,
-                    index >> g.extra_query_index_bits(),
-                ),
-            })
-            .collect()
+        sample_query_indices(
+            config,
+            challenger,
+            log_max_height + g.extra_query_index_bits(),
+        )
+        .into_iter()
+        .map(|index| CircleQueryProof {
+            input_proof: open_input(index),
+            commit_phase_openings: answer_query(
+                config,
+                &commit_phase_result.data,
+                index >> g.extra_query_index_bits(),
+            ),
+        })
+        .collect()
     });
 
     CircleFriProof {
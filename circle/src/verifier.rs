@@ -6,7 +6,7 @@ use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
 use p3_commit::Mmcs;
 use p3_field::{ExtensionField, Field};
 use p3_fri::verifier::FriError;
-use p3_fri::{FriConfig, FriGenericConfig};
+use p3_fri::{sample_query_indices, FriConfig, FriGenericConfig};
 use p3_matrix::Dimensions;
 
 use crate::{CircleCommitPhaseProofStep, CircleFriProof};
@@ -35,10 +35,6 @@ where
         .collect();
     challenger.observe_ext_element(proof.final_poly);
 
-    if proof.query_proofs.len() != config.num_queries {
-        return Err(FriError::InvalidProofShape);
-    }
-
     // Check PoW.
     if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
         return Err(FriError::InvalidPowWitness);
@@ -46,8 +42,17 @@ where
 
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
 
-    for qp in &proof.query_proofs {
-        let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
+    let indices = sample_query_indices(
+        config,
+        challenger,
+        log_max_height + g.extra_query_index_bits(),
+    );
+    // With deduplication, the number of queries depends on the indices sampled.
+    if proof.query_proofs.len() != indices.len() {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    for (qp, index) in proof.query_proofs.iter().zip(indices) {
         let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
 
         debug_assert!(
@@ -0,0 +1,268 @@
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use hashbrown::DefaultHashBuilder;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
+
+use crate::{Mmcs, ProofSizeStats};
+
+/// An MMCS which remembers the batches of matrices committed through it, and reuses the prover
+/// data when an identical batch is committed again.
+///
+/// This is meant for preprocessed or otherwise constant tables, which are committed in every proof
+/// with the same contents. Batches are identified by a hash of their contents; a hit is confirmed
+/// by comparing the contents with the cached matrices.
+///
+/// Since most batches, such as traces, are never repeated, a batch is only cached the second time
+/// it is seen. The first time, it is committed by the inner MMCS as usual, and only its hash is
+/// remembered. Cached batches are stored as copies in row-major form, in addition to the matrices
+/// passed to each commit.
+///
+/// At most `capacity` batches are remembered, evicting the oldest first.
+pub struct CachingMmcs<T, InnerMmcs: Mmcs<T>>
+where
+    T: Send + Sync,
+{
+    inner: InnerMmcs,
+    capacity: usize,
+    hash_builder: DefaultHashBuilder,
+    cache: RefCell<VecDeque<CacheEntry<T, InnerMmcs>>>,
+}
+
+struct CacheEntry<T: Send + Sync, InnerMmcs: Mmcs<T>> {
+    key: u64,
+    /// `None` if the batch has only been seen once.
+    committed: Option<CachedCommitment<T, InnerMmcs>>,
+}
+
+struct CachedCommitment<T: Send + Sync, InnerMmcs: Mmcs<T>> {
+    commitment: InnerMmcs::Commitment,
+    prover_data: Arc<InnerMmcs::ProverData<RowMajorMatrix<T>>>,
+}
+
+impl<T: Send + Sync, InnerMmcs: Mmcs<T>> Clone for CacheEntry<T, InnerMmcs> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key,
+            committed: self.committed.as_ref().map(|committed| CachedCommitment {
+                commitment: committed.commitment.clone(),
+                prover_data: committed.prover_data.clone(),
+            }),
+        }
+    }
+}
+
+impl<T, InnerMmcs> CachingMmcs<T, InnerMmcs>
+where
+    T: Send + Sync,
+    InnerMmcs: Mmcs<T>,
+{
+    pub fn new(inner: InnerMmcs, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            hash_builder: DefaultHashBuilder::default(),
+            cache: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    pub const fn inner(&self) -> &InnerMmcs {
+        &self.inner
+    }
+
+    /// The number of batches whose prover data is currently cached.
+    pub fn num_cached(&self) -> usize {
+        self.cache
+            .borrow()
+            .iter()
+            .filter(|entry| entry.committed.is_some())
+            .count()
+    }
+
+    /// Forget all batches seen so far.
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+    }
+}
+
+impl<T, InnerMmcs> Clone for CachingMmcs<T, InnerMmcs>
+where
+    T: Send + Sync,
+    InnerMmcs: Mmcs<T>,
+{
+    /// The clone starts with a copy of the cache; the cached prover data itself is shared.
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            capacity: self.capacity,
+            hash_builder: self.hash_builder.clone(),
+            cache: RefCell::new(self.cache.borrow().clone()),
+        }
+    }
+}
+
+/// The prover data of a `CachingMmcs`.
+pub enum CachingProverData<T: Send + Sync, InnerMmcs: Mmcs<T>, M> {
+    /// A batch committed by the inner MMCS directly.
+    Uncached(InnerMmcs::ProverData<M>),
+    /// A batch whose prover data is shared with earlier commits of the same contents.
+    Cached {
+        inputs: Vec<M>,
+        prover_data: Arc<InnerMmcs::ProverData<RowMajorMatrix<T>>>,
+    },
+}
+
+impl<T: Send + Sync, InnerMmcs: Mmcs<T>, M> CachingProverData<T, InnerMmcs, M> {
+    pub const fn is_cached(&self) -> bool {
+        matches!(self, Self::Cached { .. })
+    }
+}
+
+impl<T, InnerMmcs> Mmcs<T> for CachingMmcs<T, InnerMmcs>
+where
+    T: Clone + Eq + Hash + Send + Sync,
+    InnerMmcs: Mmcs<T>,
+{
+    type ProverData<M> = CachingProverData<T, InnerMmcs, M>;
+    type Commitment = InnerMmcs::Commitment;
+    type Proof = InnerMmcs::Proof;
+    type Error = InnerMmcs::Error;
+
+    fn commit<M: Matrix<T>>(&self, inputs: Vec<M>) -> (Self::Commitment, Self::ProverData<M>) {
+        let key = self.content_hash(&inputs);
+        let mut cache = self.cache.borrow_mut();
+
+        match cache.iter_mut().find(|entry| entry.key == key) {
+            Some(CacheEntry {
+                committed: Some(committed),
+                ..
+            }) => {
+                let cached_inputs = self.inner.get_matrices(&*committed.prover_data);
+                if same_contents(&inputs, &cached_inputs) {
+                    let commitment = committed.commitment.clone();
+                    let prover_data = CachingProverData::Cached {
+                        inputs,
+                        prover_data: committed.prover_data.clone(),
+                    };
+                    return (commitment, prover_data);
+                }
+            }
+            Some(entry) => {
+                // The second time this batch is seen, so we commit to a copy we can keep.
+                let copies = inputs
+                    .iter()
+                    .map(|mat| RowMajorMatrix::new(mat.rows().flatten().collect(), mat.width()))
+                    .collect();
+                let (commitment, prover_data) = self.inner.commit(copies);
+                let prover_data = Arc::new(prover_data);
+                entry.committed = Some(CachedCommitment {
+                    commitment: commitment.clone(),
+                    prover_data: prover_data.clone(),
+                });
+                return (
+                    commitment,
+                    CachingProverData::Cached {
+                        inputs,
+                        prover_data,
+                    },
+                );
+            }
+            None => {
+                if cache.len() == self.capacity {
+                    cache.pop_front();
+                }
+                if self.capacity > 0 {
+                    cache.push_back(CacheEntry {
+                        key,
+                        committed: None,
+                    });
+                }
+            }
+        }
+
+        let (commitment, prover_data) = self.inner.commit(inputs);
+        (commitment, CachingProverData::Uncached(prover_data))
+    }
+
+    fn open_batch<M: Matrix<T>>(
+        &self,
+        index: usize,
+        prover_data: &Self::ProverData<M>,
+    ) -> (Vec<Vec<T>>, Self::Proof) {
+        match prover_data {
+            CachingProverData::Uncached(prover_data) => self.inner.open_batch(index, prover_data),
+            CachingProverData::Cached { prover_data, .. } => {
+                self.inner.open_batch(index, &**prover_data)
+            }
+        }
+    }
+
+    fn get_matrices<'a, M: Matrix<T>>(&self, prover_data: &'a Self::ProverData<M>) -> Vec<&'a M> {
+        match prover_data {
+            CachingProverData::Uncached(prover_data) => self.inner.get_matrices(prover_data),
+            CachingProverData::Cached { inputs, .. } => inputs.iter().collect(),
+        }
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<T>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .verify_batch(commit, dimensions, index, opened_values, proof)
+    }
+
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        self.inner.proof_size_hint(dimensions)
+    }
+
+    fn verification_hash_count(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner.verification_hash_count(dimensions)
+    }
+
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner.prover_data_size_hint(dimensions)
+    }
+}
+
+impl<T, InnerMmcs> CachingMmcs<T, InnerMmcs>
+where
+    T: Hash + Send + Sync,
+    InnerMmcs: Mmcs<T>,
+{
+    /// A hash of the dimensions and contents of a batch of matrices.
+    fn content_hash<M: Matrix<T>>(&self, inputs: &[M]) -> u64 {
+        let mut hasher = self.hash_builder.build_hasher();
+        inputs.len().hash(&mut hasher);
+        for mat in inputs {
+            mat.width().hash(&mut hasher);
+            mat.height().hash(&mut hasher);
+            for row in mat.rows() {
+                row.for_each(|x| x.hash(&mut hasher));
+            }
+        }
+        hasher.finish()
+    }
+}
+
+fn same_contents<T, M, N>(inputs: &[M], cached: &[&N]) -> bool
+where
+    T: Eq + Send + Sync,
+    M: Matrix<T>,
+    N: Matrix<T>,
+{
+    inputs.len() == cached.len()
+        && inputs.iter().zip(cached).all(|(mat, cached_mat)| {
+            mat.width() == cached_mat.width()
+                && mat.height() == cached_mat.height()
+                && mat.rows().zip(cached_mat.rows()).all(|(a, b)| a.eq(b))
+        })
+}
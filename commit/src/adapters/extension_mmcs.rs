@@ -2,11 +2,13 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 use core::ops::Deref;
 
+use itertools::izip;
 use p3_field::{ExtensionField, Field};
-use p3_matrix::extension::FlatMatrixView;
+use p3_matrix::extension::{FlatIter, FlatMatrixView};
+use p3_matrix::stack::EitherRow;
 use p3_matrix::{Dimensions, Matrix};
 
-use crate::Mmcs;
+use crate::{HidingMmcs, Mmcs, ProofSizeStats};
 
 #[derive(Clone, Debug)]
 pub struct ExtensionMmcs<F, EF, InnerMmcs> {
@@ -77,14 +79,263 @@ where
                     .collect()
             })
             .collect();
-        let base_dimensions = dimensions
-            .iter()
-            .map(|dim| Dimensions {
-                width: dim.width * EF::D,
-                height: dim.height,
+        self.inner.verify_batch(
+            commit,
+            &base_dimensions::<F, EF>(dimensions),
+            index,
+            &opened_base_values,
+            proof,
+        )
+    }
+
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        let base_stats = self
+            .inner
+            .proof_size_hint(&base_dimensions::<F, EF>(dimensions));
+        ProofSizeStats {
+            num_opened_values: base_stats.num_opened_values / EF::D,
+            ..base_stats
+        }
+    }
+
+    fn verification_hash_count(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner
+            .verification_hash_count(&base_dimensions::<F, EF>(dimensions))
+    }
+
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner
+            .prover_data_size_hint(&base_dimensions::<F, EF>(dimensions))
+    }
+}
+
+impl<F, EF, InnerMmcs> HidingMmcs<EF> for ExtensionMmcs<F, EF, InnerMmcs>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    InnerMmcs: HidingMmcs<F>,
+{
+}
+
+/// The dimensions of extension field matrices once flattened into base field matrices.
+fn base_dimensions<F: Field, EF: ExtensionField<F>>(dimensions: &[Dimensions]) -> Vec<Dimensions> {
+    dimensions
+        .iter()
+        .map(|dim| Dimensions {
+            width: dim.width * EF::D,
+            height: dim.height,
+        })
+        .collect()
+}
+
+/// A matrix committed with `ExtensionMmcs::commit_mixed`, holding either base field values or
+/// extension field values. Extension field matrices are flattened into base field columns.
+#[derive(Debug)]
+pub enum MixedMatrix<F, EF, BaseM, ExtM> {
+    Base(BaseM),
+    Extension(FlatMatrixView<F, EF, ExtM>),
+}
+
+impl<F, EF, BaseM, ExtM> MixedMatrix<F, EF, BaseM, ExtM> {
+    pub const fn is_extension(&self) -> bool {
+        matches!(self, Self::Extension(_))
+    }
+
+    pub const fn kind(&self) -> MixedMatrixKind {
+        match self {
+            Self::Base(_) => MixedMatrixKind::Base,
+            Self::Extension(_) => MixedMatrixKind::Extension,
+        }
+    }
+}
+
+/// The field a matrix committed with `ExtensionMmcs::commit_mixed` holds its values in.
+///
+/// Base field and extension field rows hash to the same flattened leaves, so the verifier must know
+/// the kind of each matrix in advance rather than trust the kind of its opening.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MixedMatrixKind {
+    Base,
+    Extension,
+}
+
+impl<F, EF, BaseM, ExtM> Matrix<F> for MixedMatrix<F, EF, BaseM, ExtM>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    BaseM: Matrix<F>,
+    ExtM: Matrix<EF>,
+{
+    fn width(&self) -> usize {
+        match self {
+            Self::Base(mat) => mat.width(),
+            Self::Extension(mat) => mat.width(),
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Self::Base(mat) => mat.height(),
+            Self::Extension(mat) => mat.height(),
+        }
+    }
+
+    type Row<'a>
+        = EitherRow<BaseM::Row<'a>, FlatIter<F, ExtM::Row<'a>>>
+    where
+        Self: 'a;
+
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        match self {
+            Self::Base(mat) => EitherRow::Left(mat.row(r)),
+            Self::Extension(mat) => EitherRow::Right(mat.row(r)),
+        }
+    }
+}
+
+/// An opened row of a `MixedMatrix`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MixedOpening<F, EF> {
+    Base(Vec<F>),
+    Extension(Vec<EF>),
+}
+
+impl<F, EF> MixedOpening<F, EF> {
+    pub const fn kind(&self) -> MixedMatrixKind {
+        match self {
+            Self::Base(_) => MixedMatrixKind::Base,
+            Self::Extension(_) => MixedMatrixKind::Extension,
+        }
+    }
+
+    /// The opened row, if it belongs to a base field matrix.
+    pub fn as_base(&self) -> Option<&[F]> {
+        match self {
+            Self::Base(row) => Some(row),
+            Self::Extension(_) => None,
+        }
+    }
+
+    /// The opened row, if it belongs to an extension field matrix.
+    pub fn as_extension(&self) -> Option<&[EF]> {
+        match self {
+            Self::Base(_) => None,
+            Self::Extension(row) => Some(row),
+        }
+    }
+}
+
+/// An error from `ExtensionMmcs::verify_mixed`.
+#[derive(Debug)]
+pub enum MixedOpeningError<E> {
+    /// The numbers of kinds, dimensions and openings did not all agree.
+    WrongBatchSize,
+    /// The opening of the matrix at this position is of a different kind than expected, or has the
+    /// wrong width.
+    WrongShape(usize),
+    /// The inner MMCS rejected the flattened opening.
+    Inner(E),
+}
+
+impl<F, EF, InnerMmcs> ExtensionMmcs<F, EF, InnerMmcs>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    InnerMmcs: Mmcs<F>,
+{
+    /// Commit to base field and extension field matrices in a single batch.
+    ///
+    /// The committed matrices are ordered with all of `base_inputs` first, followed by all of
+    /// `ext_inputs`; openings from `open_mixed` follow the same order.
+    #[allow(clippy::type_complexity)]
+    pub fn commit_mixed<BaseM: Matrix<F>, ExtM: Matrix<EF>>(
+        &self,
+        base_inputs: Vec<BaseM>,
+        ext_inputs: Vec<ExtM>,
+    ) -> (
+        InnerMmcs::Commitment,
+        InnerMmcs::ProverData<MixedMatrix<F, EF, BaseM, ExtM>>,
+    ) {
+        let inputs = base_inputs
+            .into_iter()
+            .map(MixedMatrix::Base)
+            .chain(
+                ext_inputs
+                    .into_iter()
+                    .map(|mat| MixedMatrix::Extension(FlatMatrixView::new(mat))),
+            )
+            .collect();
+        self.inner.commit(inputs)
+    }
+
+    /// Open a row of each matrix committed with `commit_mixed`, following the index semantics of
+    /// `Mmcs::open_batch`. Extension field rows are returned unflattened.
+    #[allow(clippy::type_complexity)]
+    pub fn open_mixed<BaseM: Matrix<F>, ExtM: Matrix<EF>>(
+        &self,
+        index: usize,
+        prover_data: &InnerMmcs::ProverData<MixedMatrix<F, EF, BaseM, ExtM>>,
+    ) -> (Vec<MixedOpening<F, EF>>, InnerMmcs::Proof) {
+        let (opened_base_values, proof) = self.inner.open_batch(index, prover_data);
+        let openings = self
+            .inner
+            .get_matrices(prover_data)
+            .into_iter()
+            .zip(opened_base_values)
+            .map(|(mat, row)| {
+                if mat.is_extension() {
+                    MixedOpening::Extension(row.chunks(EF::D).map(EF::from_base_slice).collect())
+                } else {
+                    MixedOpening::Base(row)
+                }
             })
-            .collect::<Vec<_>>();
+            .collect();
+        (openings, proof)
+    }
+
+    /// Verify an opening produced by `open_mixed`.
+    ///
+    /// `kinds` gives the field of each committed matrix, as known to the verifier; openings of
+    /// another kind are rejected, so the kinds of accepted openings can be relied upon.
+    /// `dimensions` gives the dimensions of each matrix in its own field, i.e. the width of an
+    /// extension field matrix counts extension field elements.
+    pub fn verify_mixed(
+        &self,
+        commit: &InnerMmcs::Commitment,
+        kinds: &[MixedMatrixKind],
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[MixedOpening<F, EF>],
+        proof: &InnerMmcs::Proof,
+    ) -> Result<(), MixedOpeningError<InnerMmcs::Error>> {
+        if kinds.len() != dimensions.len() || opened_values.len() != dimensions.len() {
+            return Err(MixedOpeningError::WrongBatchSize);
+        }
+        let mut base_dimensions = Vec::with_capacity(dimensions.len());
+        let mut opened_base_values = Vec::with_capacity(dimensions.len());
+        for (i, (&kind, dim, opening)) in izip!(kinds, dimensions, opened_values).enumerate() {
+            let (width, row) = match (kind, opening) {
+                (MixedMatrixKind::Base, MixedOpening::Base(row)) => (row.len(), row.clone()),
+                (MixedMatrixKind::Extension, MixedOpening::Extension(row)) => (
+                    row.len(),
+                    row.iter()
+                        .flat_map(|el| el.as_base_slice())
+                        .copied()
+                        .collect(),
+                ),
+                _ => return Err(MixedOpeningError::WrongShape(i)),
+            };
+            if width != dim.width {
+                return Err(MixedOpeningError::WrongShape(i));
+            }
+            base_dimensions.push(Dimensions {
+                width: row.len(),
+                height: dim.height,
+            });
+            opened_base_values.push(row);
+        }
         self.inner
             .verify_batch(commit, &base_dimensions, index, &opened_base_values, proof)
+            .map_err(MixedOpeningError::Inner)
     }
 }
@@ -1,4 +1,7 @@
 //! Adapters for converting between different types of commitment schemes.
 
+mod caching_mmcs;
 mod extension_mmcs;
+
+pub use caching_mmcs::*;
 pub use extension_mmcs::*;
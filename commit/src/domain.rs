@@ -52,6 +52,40 @@ pub trait PolynomialSpace: Copy {
 
     // Unnormalized
     fn selectors_on_coset(&self, coset: Self) -> LagrangeSelectors<Vec<Self::Val>>;
+
+    /// Evaluate at `point` the polynomial of degree less than the size of this domain which takes
+    /// the value `values[i % values.len()]` at the `i`-th point. The number of values must be a
+    /// power of two no larger than the domain.
+    fn periodic_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        values: &[Self::Val],
+        point: Ext,
+    ) -> Ext;
+
+    /// Evaluate the polynomial of `periodic_at_point` over `coset`.
+    fn periodic_on_coset(&self, values: &[Self::Val], coset: Self) -> Vec<Self::Val>;
+
+    /// Evaluate at `point` the Lagrange selector of the `row`-th point of this domain, i.e. the
+    /// polynomial of degree less than the size of this domain which is one at that point and zero
+    /// at the others. Unlike `selectors_at_point`, it is normalized.
+    fn selector_at_point<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext;
+
+    /// Evaluate the selector of `selector_at_point` over `coset`, which must be disjoint from this
+    /// domain.
+    fn selector_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val>;
+
+    /// Evaluate at `point` the vanishing polynomial of the `chunk`-th domain of
+    /// `split_domains(num_chunks)`, which is a sub-coset of this domain.
+    fn sub_coset_zp_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        num_chunks: usize,
+        chunk: usize,
+        point: Ext,
+    ) -> Ext;
+
+    /// Evaluate the polynomial of `sub_coset_zp_at_point` over `coset`.
+    fn sub_coset_zp_on_coset(&self, num_chunks: usize, chunk: usize, coset: Self)
+        -> Vec<Self::Val>;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -66,6 +100,25 @@ impl<Val: TwoAdicField> TwoAdicMultiplicativeCoset<Val> {
     }
 }
 
+/// Evaluate at `point` the polynomial which takes the value `values[k]` at `h^k`, where `h`
+/// generates the subgroup of order `values.len()`.
+fn interpolate_subgroup_at_point<Val: TwoAdicField, Ext: ExtensionField<Val>>(
+    values: &[Val],
+    point: Ext,
+) -> Ext {
+    let log_n = log2_strict_usize(values.len());
+    // L_k(x) = h^k (x^n - 1) / (n (x - h^k))
+    let z_h = point.exp_power_of_2(log_n) - Ext::ONE;
+    let n_inv = Val::from_canonical_usize(values.len()).inverse();
+    Val::two_adic_generator(log_n)
+        .powers()
+        .zip(values)
+        .map(|(h_k, &value)| (point - h_k).inverse() * (h_k * value))
+        .sum::<Ext>()
+        * z_h
+        * n_inv
+}
+
 impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
     type Val = Val;
 
@@ -126,6 +179,88 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         }
     }
 
+    fn periodic_at_point<Ext: ExtensionField<Val>>(&self, values: &[Val], point: Ext) -> Ext {
+        let log_period = log2_strict_usize(values.len());
+        assert!(log_period <= self.log_n);
+        // The `i`-th point `shift g^i`, raised to the power `n / period`, is the `i % period`-th
+        // point of the subgroup of order `period`.
+        let unshifted_point = point * self.shift.inverse();
+        interpolate_subgroup_at_point(
+            values,
+            unshifted_point.exp_power_of_2(self.log_n - log_period),
+        )
+    }
+
+    fn periodic_on_coset(&self, values: &[Val], coset: Self) -> Vec<Val> {
+        let log_period = log2_strict_usize(values.len());
+        assert!(log_period <= self.log_n);
+        assert!(coset.log_n >= self.log_n);
+        // The polynomial is a function of `x^(n / period)`, which repeats itself on the coset
+        // every `coset.size() * period / n` points.
+        let cycle = cyclic_subgroup_coset_known_order(
+            coset.gen(),
+            coset.shift * self.shift.inverse(),
+            1 << (coset.log_n - self.log_n + log_period),
+        )
+        .map(|x| interpolate_subgroup_at_point(values, x.exp_power_of_2(self.log_n - log_period)))
+        .collect_vec();
+        cycle.into_iter().cycle().take(coset.size()).collect()
+    }
+
+    fn selector_at_point<Ext: ExtensionField<Val>>(&self, row: usize, point: Ext) -> Ext {
+        assert!(row < self.size());
+        // L_i(x) = h_i (x^n - 1) / (n (x - h_i)), in terms of the unshifted point x.
+        let unshifted_point = point * self.shift.inverse();
+        let h_i = self.gen().exp_u64(row as u64);
+        let z_h = unshifted_point.exp_power_of_2(self.log_n) - Ext::ONE;
+        z_h * (unshifted_point - h_i).inverse()
+            * (h_i * Val::from_canonical_usize(self.size()).inverse())
+    }
+
+    fn selector_on_coset(&self, row: usize, coset: Self) -> Vec<Val> {
+        assert!(row < self.size());
+        let unshifted_shift = coset.shift * self.shift.inverse();
+        let h_i = self.gen().exp_u64(row as u64);
+        let scale = h_i * Val::from_canonical_usize(self.size()).inverse();
+
+        let denoms = cyclic_subgroup_coset_known_order(coset.gen(), unshifted_shift, coset.size())
+            .map(|x| x - h_i)
+            .collect_vec();
+        // The `j`-th point raised to the power `n` is `(s g^j)^n = s^n (g^n)^j`.
+        let z_hs = coset
+            .gen()
+            .exp_power_of_2(self.log_n)
+            .shifted_powers(unshifted_shift.exp_power_of_2(self.log_n));
+        batch_multiplicative_inverse(&denoms)
+            .into_iter()
+            .zip(z_hs)
+            .map(|(inv, x_pow_n)| (x_pow_n - Val::ONE) * inv * scale)
+            .collect()
+    }
+
+    fn sub_coset_zp_at_point<Ext: ExtensionField<Val>>(
+        &self,
+        num_chunks: usize,
+        chunk: usize,
+        point: Ext,
+    ) -> Ext {
+        self.split_domains(num_chunks)[chunk].zp_at_point(point)
+    }
+
+    fn sub_coset_zp_on_coset(&self, num_chunks: usize, chunk: usize, coset: Self) -> Vec<Val> {
+        let sub_coset = self.split_domains(num_chunks)[chunk];
+        // As in `selector_on_coset`, the `j`-th point of `coset`, unshifted by the sub-coset's
+        // shift, raised to the power of its size is `s^m (g^m)^j`.
+        let unshifted_shift = coset.shift * sub_coset.shift.inverse();
+        coset
+            .gen()
+            .exp_power_of_2(sub_coset.log_n)
+            .shifted_powers(unshifted_shift.exp_power_of_2(sub_coset.log_n))
+            .take(coset.size())
+            .map(|x_pow_m| x_pow_m - Val::ONE)
+            .collect()
+    }
+
     fn selectors_on_coset(&self, coset: Self) -> LagrangeSelectors<Vec<Val>> {
         assert_eq!(self.shift, Val::ONE);
         assert_ne!(coset.shift, Val::ONE);
@@ -169,3 +304,76 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::FieldAlgebra;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn selectors_at_arbitrary_rows() {
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 4,
+            shift: F::from_canonical_u32(7),
+        };
+        let coset = domain.create_disjoint_domain(4 * domain.size());
+        let points = cyclic_subgroup_coset_known_order(domain.gen(), domain.shift, domain.size())
+            .collect_vec();
+        let coset_points =
+            cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, coset.size()).collect_vec();
+
+        // Each selector vanishes at the other points of the domain, and they sum to one, so each
+        // is one at its own point.
+        for row in 0..domain.size() {
+            for (i, &x) in points.iter().enumerate() {
+                if i != row {
+                    assert_eq!(domain.selector_at_point(row, x), F::ZERO);
+                }
+            }
+        }
+        for &x in coset_points.iter().take(8) {
+            let sum: F = (0..domain.size())
+                .map(|row| domain.selector_at_point(row, x))
+                .sum();
+            assert_eq!(sum, F::ONE);
+        }
+
+        for row in [0, 5, domain.size() - 1] {
+            let evals = domain.selector_on_coset(row, coset);
+            assert_eq!(evals.len(), coset.size());
+            for (&eval, &x) in evals.iter().zip(&coset_points) {
+                assert_eq!(eval, domain.selector_at_point(row, x));
+            }
+        }
+    }
+
+    #[test]
+    fn sub_coset_vanishing_polynomials() {
+        let domain = TwoAdicMultiplicativeCoset {
+            log_n: 5,
+            shift: F::ONE,
+        };
+        let coset = domain.create_disjoint_domain(2 * domain.size());
+        let points = cyclic_subgroup_coset_known_order(domain.gen(), domain.shift, domain.size())
+            .collect_vec();
+        let coset_points =
+            cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, coset.size()).collect_vec();
+
+        let num_chunks = 4;
+        for chunk in 0..num_chunks {
+            // Zero exactly at the rows congruent to `chunk`.
+            for (i, &x) in points.iter().enumerate() {
+                let zp = domain.sub_coset_zp_at_point(num_chunks, chunk, x);
+                assert_eq!(zp.is_zero(), i % num_chunks == chunk);
+            }
+            let evals = domain.sub_coset_zp_on_coset(num_chunks, chunk, coset);
+            for (&eval, &x) in evals.iter().zip(&coset_points) {
+                assert_eq!(eval, domain.sub_coset_zp_at_point(num_chunks, chunk, x));
+            }
+        }
+    }
+}
@@ -8,6 +8,10 @@ mod adapters;
 mod domain;
 mod mmcs;
 mod pcs;
+mod rng;
+mod schedule;
+mod stats;
+mod store;
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod testing;
@@ -16,3 +20,7 @@ pub use adapters::*;
 pub use domain::*;
 pub use mmcs::*;
 pub use pcs::*;
+pub use rng::*;
+pub use schedule::*;
+pub use stats::*;
+pub use store::*;
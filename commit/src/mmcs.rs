@@ -1,8 +1,9 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::mem::size_of;
 
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::{Dimensions, Matrix};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -35,6 +36,26 @@ pub trait Mmcs<T: Send + Sync>: Clone {
         self.commit_matrix(RowMajorMatrix::new_col(input))
     }
 
+    /// Like `commit`, but the matrices are computed as part of the commitment, so that an MMCS can
+    /// consume each chunk of rows as it is produced, e.g. hash it into leaves while it is still in
+    /// cache, rather than read the whole matrix back from memory afterwards.
+    ///
+    /// By default, the matrices are computed first and then committed with `commit`.
+    fn commit_chunked<S: RowChunkSource<T>>(
+        &self,
+        inputs: Vec<S>,
+    ) -> (Self::Commitment, Self::ProverData<RowMajorMatrix<T>>)
+    where
+        T: Clone,
+    {
+        self.commit(
+            inputs
+                .into_iter()
+                .map(|input| input.produce(|_| ()).0)
+                .collect(),
+        )
+    }
+
     /// Opens a batch of rows from committed matrices
     /// returns `(openings, proof)`
     /// where `openings` is a vector whose `i`th element is the `j`th row of the ith matrix `M[i]`,
@@ -76,4 +97,145 @@ pub trait Mmcs<T: Send + Sync>: Clone {
         opened_values: &[Vec<T>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error>;
+
+    /// Estimate the size of a batch opening proof for matrices with the given dimensions, without
+    /// producing one.
+    ///
+    /// By default, only the opened values are counted, as if the proof were empty; implementations
+    /// whose proofs hold digests or other values should override this.
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        ProofSizeStats::new::<T, T>(dimensions, 0, 0, 0)
+    }
+
+    /// The number of hash or compression function invocations needed by `verify_batch` for
+    /// matrices with the given dimensions.
+    ///
+    /// By default this is 0; implementations which hash should override this.
+    fn verification_hash_count(&self, _dimensions: &[Dimensions]) -> usize {
+        0
+    }
+
+    /// Estimate the size in bytes of the prover data of a commitment to matrices with the given
+    /// dimensions, e.g. the matrices and the Merkle tree over them.
+    ///
+    /// By default, only the matrices are counted.
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        dimensions
+            .iter()
+            .map(|dim| dim.width * dim.height)
+            .sum::<usize>()
+            * size_of::<T>()
+    }
+}
+
+/// An `Mmcs` whose commitments and opening proofs reveal nothing about the committed matrices
+/// beyond the opened rows, e.g. because it salts its leaves with random values.
+pub trait HidingMmcs<T: Send + Sync>: Mmcs<T> {}
+
+/// A matrix which is yet to be computed, and whose rows are produced in contiguous chunks, such as
+/// an LDE computed by a DFT. See `Mmcs::commit_chunked`.
+pub trait RowChunkSource<T: Send + Sync> {
+    fn dimensions(&self) -> Dimensions;
+
+    /// Compute the matrix, calling `on_chunk` on each chunk of its rows as soon as the chunk is
+    /// final. Returns the matrix along with the results of `on_chunk` in the order of the chunks,
+    /// which cover the matrix.
+    fn produce<R: Send>(
+        self,
+        on_chunk: impl Fn(RowMajorMatrixView<'_, T>) -> R + Sync,
+    ) -> (RowMajorMatrix<T>, Vec<R>);
+}
+
+impl<T: Clone + Send + Sync> RowChunkSource<T> for RowMajorMatrix<T> {
+    fn dimensions(&self) -> Dimensions {
+        Matrix::dimensions(self)
+    }
+
+    fn produce<R: Send>(
+        self,
+        on_chunk: impl Fn(RowMajorMatrixView<'_, T>) -> R + Sync,
+    ) -> (RowMajorMatrix<T>, Vec<R>) {
+        let chunks = vec![on_chunk(self.as_view())];
+        (self, chunks)
+    }
+}
+
+/// The size of a batch opening, as estimated by `Mmcs::proof_size_hint`.
+///
+/// This describes the shape of the openings and proof rather than any encoding of them, so that
+/// the length of an encoding can be estimated by whoever chooses it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProofSizeStats {
+    /// The number of values in the opened rows.
+    pub num_opened_values: usize,
+    /// The number of digests in the opening proof, e.g. Merkle siblings.
+    pub num_digests: usize,
+    /// The number of other values in the opening proof, e.g. salts of a hiding commitment.
+    pub num_proof_values: usize,
+    /// The number of elements of each digest.
+    pub digest_elems: usize,
+    /// The number of variable-length sequences in the openings and proof, e.g. the opened rows,
+    /// which an encoding may prefix with their lengths.
+    pub num_sequences: usize,
+    /// The in-memory size in bytes of an opened value or other proof value.
+    pub value_size: usize,
+    /// The in-memory size in bytes of an element of a digest.
+    pub digest_elem_size: usize,
+}
+
+impl ProofSizeStats {
+    /// Stats for opening one row of each matrix in `dimensions` with a proof made of `num_digests`
+    /// digests, each of `digest_elems` elements of type `W`, and `num_proof_values` values of type
+    /// `T`, with the rows, the proof values and the digests each in a sequence of their own.
+    pub fn new<T, W>(
+        dimensions: &[Dimensions],
+        num_digests: usize,
+        digest_elems: usize,
+        num_proof_values: usize,
+    ) -> Self {
+        Self {
+            num_opened_values: dimensions.iter().map(|dim| dim.width).sum(),
+            num_digests,
+            num_proof_values,
+            digest_elems,
+            // The sequence of rows, each row, the proof values and the digests.
+            num_sequences: 1 + dimensions.len() + 2,
+            value_size: size_of::<T>(),
+            digest_elem_size: size_of::<W>(),
+        }
+    }
+
+    /// The in-memory size of the openings and proof in bytes, which approximates their encoded
+    /// size.
+    pub const fn num_bytes(&self) -> usize {
+        (self.num_opened_values + self.num_proof_values) * self.value_size
+            + self.num_digests * self.digest_elems * self.digest_elem_size
+    }
+}
+
+/// An `Mmcs` whose commitment to a batch can be assembled from commitments to shards of its rows,
+/// so that the shards can be committed, and later opened, on different machines.
+///
+/// A batch is split into `2^k` shards of consecutive rows, the same rows of every matrix, so all
+/// matrices of a sharded batch must have the same height.
+pub trait ShardedMmcs<T: Send + Sync>: Mmcs<T> {
+    /// What is kept of a batch once its shards are merged, to turn openings of a shard into
+    /// openings of the batch, e.g. the top layers of a Merkle tree.
+    type MergedShards: Clone + Serialize + DeserializeOwned;
+
+    /// Merge the commitments to the shards of a batch, given in order of their rows, into a
+    /// commitment to the batch. The number of shards must be a power of two.
+    fn merge_shards(
+        &self,
+        shard_commits: Vec<Self::Commitment>,
+    ) -> (Self::Commitment, Self::MergedShards);
+
+    /// Extend a proof made by `open_batch` for a row of the shard `shard`, from its own prover
+    /// data, into a proof of the same row against the commitment to the batch.
+    fn extend_shard_proof(
+        &self,
+        merged: &Self::MergedShards,
+        shard: usize,
+        proof: Self::Proof,
+    ) -> Self::Proof;
 }
@@ -1,15 +1,20 @@
 //! Traits for polynomial commitment schemes.
 
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::mem::size_of;
 
 use p3_field::ExtensionField;
 use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::Matrix;
+use p3_matrix::{Dimensions, Matrix};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::PolynomialSpace;
+use crate::schedule::expand_opened_values;
+use crate::{
+    encoded_len, OpeningProofStats, OpeningSchedule, PolynomialSpace, ScheduledOpeningError,
+};
 
 pub type Val<D> = <D as PolynomialSpace>::Val;
 
@@ -41,6 +46,18 @@ where
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
     ) -> (Self::Commitment, Self::ProverData);
 
+    /// Estimate the size in bytes of the prover data of a commitment to evaluations with the given
+    /// dimensions, e.g. their low-degree extensions and the Merkle trees over them.
+    ///
+    /// By default, only the evaluations are counted.
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        dimensions
+            .iter()
+            .map(|dim| dim.width * dim.height)
+            .sum::<usize>()
+            * size_of::<Val<Self::Domain>>()
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         prover_data: &'a Self::ProverData,
@@ -84,6 +101,151 @@ where
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error>;
+
+    /// A breakdown of the size of `proof`, and of the work of verifying it.
+    ///
+    /// By default, the whole proof is counted in `other_bytes`, with no estimate of the work.
+    fn proof_stats(&self, proof: &Self::Proof) -> OpeningProofStats {
+        OpeningProofStats {
+            other_bytes: encoded_len(proof),
+            ..Default::default()
+        }
+    }
+
+    /// Open each matrix at the points of its schedule around `zeta`, as `open` does.
+    ///
+    /// Each distinct point of a schedule is opened once, and its values are repeated for every
+    /// shift of the schedule which gives that point.
+    fn open_scheduled(
+        &self,
+        // For each round,
+        rounds: Vec<(
+            &Self::ProverData,
+            // for each matrix, its domain and schedule
+            Vec<(Self::Domain, OpeningSchedule)>,
+        )>,
+        zeta: Challenge,
+        challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        let (rounds, indices): (Vec<_>, Vec<_>) = rounds
+            .into_iter()
+            .map(|(data, schedules)| {
+                let (points, indices): (Vec<_>, Vec<_>) = schedules
+                    .into_iter()
+                    .map(|(domain, schedule)| schedule.distinct_points(domain, zeta))
+                    .unzip();
+                ((data, points), indices)
+            })
+            .unzip();
+        let (opened_values, proof) = self.open(rounds, challenger);
+        let opened_values = opened_values
+            .into_iter()
+            .zip(indices)
+            .map(|(round, indices)| {
+                round
+                    .into_iter()
+                    .zip(indices)
+                    .map(|(mat, indices)| expand_opened_values(mat, &indices))
+                    .collect()
+            })
+            .collect();
+        (opened_values, proof)
+    }
+
+    /// Verify openings made by `open_scheduled`, with the claimed values at each shift of the
+    /// schedule of each matrix.
+    #[allow(clippy::type_complexity)]
+    fn verify_scheduled(
+        &self,
+        // For each round:
+        rounds: Vec<(
+            Self::Commitment,
+            // for each matrix: its domain, its schedule, and the values at each of its shifts
+            Vec<(Self::Domain, OpeningSchedule, Vec<Vec<Challenge>>)>,
+        )>,
+        zeta: Challenge,
+        proof: &Self::Proof,
+        challenger: &mut Challenger,
+    ) -> Result<(), ScheduledOpeningError<Self::Error>> {
+        let mut claims = Vec::with_capacity(rounds.len());
+        for (round, (commit, mats)) in rounds.into_iter().enumerate() {
+            let mut mat_claims = Vec::with_capacity(mats.len());
+            for (matrix, (domain, schedule, values)) in mats.into_iter().enumerate() {
+                if values.len() != schedule.shifts.len() {
+                    return Err(ScheduledOpeningError::WrongNumberOfPoints { round, matrix });
+                }
+                let (points, indices) = schedule.distinct_points(domain, zeta);
+                let mut distinct_values: Vec<Option<Vec<Challenge>>> = vec![None; points.len()];
+                for (i, value) in indices.into_iter().zip(values) {
+                    match &distinct_values[i] {
+                        Some(v) if *v != value => {
+                            return Err(ScheduledOpeningError::InconsistentDuplicate {
+                                round,
+                                matrix,
+                            })
+                        }
+                        Some(_) => {}
+                        None => distinct_values[i] = Some(value),
+                    }
+                }
+                let point_claims = points
+                    .into_iter()
+                    .zip(distinct_values)
+                    .map(|(point, value)| (point, value.unwrap()))
+                    .collect();
+                mat_claims.push((domain, point_claims));
+            }
+            claims.push((commit, mat_claims));
+        }
+        self.verify(claims, proof, challenger)
+            .map_err(ScheduledOpeningError::Pcs)
+    }
+}
+
+/// A PCS whose commitments and opening proofs reveal nothing about the committed polynomials beyond
+/// their opened values, e.g. a FRI PCS over hiding MMCSs which also blinds its batch with random
+/// codewords.
+pub trait HidingPcs<Challenge, Challenger>: Pcs<Challenge, Challenger>
+where
+    Challenge: ExtensionField<Val<Self::Domain>>,
+{
+    /// Commit to `evaluations` as `commit` does, but with each polynomial `p` over a domain `H`
+    /// masked as `p(X) + h(X) Z_H(X)`, for a random `h` of degree less than `|H|`, so that its
+    /// openings outside `H` reveal nothing about `p`.
+    ///
+    /// The masked polynomials agree with `p` on `H`, but have twice its degree, so they are opened
+    /// and verified over the returned domains, of twice the size, which contain the domains `H`.
+    #[allow(clippy::type_complexity)]
+    fn commit_masked(
+        &self,
+        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
+    ) -> (Self::Commitment, Self::ProverData, Vec<Self::Domain>);
+
+    /// Commit to the chunks of each of `quotients`, given by its domain, its evaluations over that
+    /// domain, and its number of chunks, which are split as by `PolynomialSpace::split_evals`.
+    ///
+    /// Each chunk `q_i` over the `i`-th domain `H_i` of `split_domains` is masked as
+    /// `q_i(X) + h_i(X) Z_{H_i}(X)`, for random `h_i` of degree less than `|H_i|`, chosen so that the
+    /// masks cancel when the chunks are recombined into the quotient as
+    /// `sum_i q_i(X) prod_{j != i} Z_{H_j}(X) / Z_{H_j}(x_i)`, where `x_i` is the first point of
+    /// `H_i`. The openings of the chunks then reveal nothing beyond the value of the quotient.
+    ///
+    /// The masked chunks agree with the chunks on the domains `H_i`, but have twice their degree,
+    /// so they are committed over the domains of `masked_quotient_domains`.
+    #[allow(clippy::type_complexity)]
+    fn commit_quotients(
+        &self,
+        quotients: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>, usize)>,
+    ) -> (Self::Commitment, Self::ProverData);
+
+    /// The domains over which `commit_quotients` commits the chunks of a quotient over
+    /// `quotient_domain`. They have twice the size of, and contain, the domains of
+    /// `quotient_domain.split_domains(num_chunks)`.
+    fn masked_quotient_domains(
+        &self,
+        quotient_domain: Self::Domain,
+        num_chunks: usize,
+    ) -> Vec<Self::Domain>;
 }
 
 pub type OpenedValues<F> = Vec<OpenedValuesForRound<F>>;
@@ -0,0 +1,32 @@
+use rand::{RngCore, SeedableRng};
+
+/// The source of all the randomness of a prover, from which each randomized component, such as a
+/// `MerkleTreeHidingMmcs`, a `HidingFriPcs` or a `ZkStarkConfig`, takes its own RNG with `fork`.
+///
+/// Each forked RNG is seeded from the stream of this one. A prover whose components are forked in
+/// the same order from a `ProverRng` with the same seed thus makes the same proofs, bit for bit,
+/// which allows comparing proofs across versions and backends. The seed must be kept secret for
+/// the proofs to be hiding.
+#[derive(Clone, Debug)]
+pub struct ProverRng<R> {
+    rng: R,
+}
+
+impl<R: RngCore + SeedableRng> ProverRng<R> {
+    pub const fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    pub fn from_seed(seed: R::Seed) -> Self {
+        Self::new(R::from_seed(seed))
+    }
+
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self::new(R::seed_from_u64(seed))
+    }
+
+    /// An RNG for the next randomized component, independent of those already forked.
+    pub fn fork(&mut self) -> R {
+        R::from_rng(&mut self.rng).expect("failed to seed a forked RNG")
+    }
+}
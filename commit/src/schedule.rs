@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+
+use p3_field::ExtensionField;
+
+use crate::{OpenedValuesForMatrix, PolynomialSpace};
+
+/// A named set of points at which to open the polynomials of a matrix, relative to an
+/// out-of-domain point `zeta`: the points `zeta * g^k` for each `k` in `shifts`, where `g` is the
+/// generator of the domain of the matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpeningSchedule {
+    pub name: &'static str,
+    pub shifts: &'static [usize],
+}
+
+impl OpeningSchedule {
+    /// Open at `zeta` alone, as for quotient chunks.
+    pub const ZETA: Self = Self {
+        name: "zeta",
+        shifts: &[0],
+    };
+
+    /// Open at `zeta` and at the next point, `zeta * g`, as for traces.
+    pub const ZETA_AND_NEXT: Self = Self {
+        name: "zeta and next",
+        shifts: &[0, 1],
+    };
+
+    pub const fn new(name: &'static str, shifts: &'static [usize]) -> Self {
+        Self { name, shifts }
+    }
+
+    /// The points of this schedule over `domain`, in the order of `shifts`.
+    pub fn points<D: PolynomialSpace, EF: ExtensionField<D::Val>>(
+        &self,
+        domain: D,
+        zeta: EF,
+    ) -> Vec<EF> {
+        let max_shift = self.shifts.iter().copied().max().unwrap_or(0);
+        let mut powers = Vec::with_capacity(max_shift + 1);
+        powers.push(zeta);
+        for _ in 0..max_shift {
+            let next = domain
+                .next_point(*powers.last().unwrap())
+                .expect("the schedule needs a domain with next points");
+            powers.push(next);
+        }
+        self.shifts.iter().map(|&k| powers[k]).collect()
+    }
+
+    /// The distinct points of this schedule over `domain`, and for each shift, the index of its
+    /// point among them. Points coincide when a shift wraps around the domain, e.g. `zeta * g`
+    /// is `zeta` over a domain of size one.
+    pub fn distinct_points<D: PolynomialSpace, EF: ExtensionField<D::Val>>(
+        &self,
+        domain: D,
+        zeta: EF,
+    ) -> (Vec<EF>, Vec<usize>) {
+        let mut distinct: Vec<EF> = Vec::new();
+        let indices = self
+            .points(domain, zeta)
+            .into_iter()
+            .map(|point| {
+                distinct
+                    .iter()
+                    .position(|&p| p == point)
+                    .unwrap_or_else(|| {
+                        distinct.push(point);
+                        distinct.len() - 1
+                    })
+            })
+            .collect();
+        (distinct, indices)
+    }
+}
+
+/// Expand the values opened at the distinct points of a schedule to one entry per shift.
+pub(crate) fn expand_opened_values<EF: Clone>(
+    distinct_values: OpenedValuesForMatrix<EF>,
+    indices: &[usize],
+) -> OpenedValuesForMatrix<EF> {
+    indices
+        .iter()
+        .map(|&i| distinct_values[i].clone())
+        .collect()
+}
+
+/// An error in verifying openings given by schedules.
+#[derive(Debug)]
+pub enum ScheduledOpeningError<E> {
+    /// Two shifts of the schedule of a matrix give the same point, but different values were
+    /// claimed at them.
+    InconsistentDuplicate { round: usize, matrix: usize },
+    /// The number of values claimed for a matrix does not match the number of shifts of its
+    /// schedule.
+    WrongNumberOfPoints { round: usize, matrix: usize },
+    /// The openings of the distinct points were rejected by the PCS.
+    Pcs(E),
+}
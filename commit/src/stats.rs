@@ -0,0 +1,45 @@
+//! Breakdowns of the size of opening proofs, for seeing where the bytes of a proof go.
+
+use serde::Serialize;
+
+/// The number of bytes of `value` in the `postcard` wire format, in which the STARK crates encode
+/// their proofs.
+pub fn encoded_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    postcard::to_allocvec(value)
+        .expect("proofs are always serializable")
+        .len()
+}
+
+/// A breakdown of an opening proof, as given by `Pcs::proof_stats`, with an estimate of the work
+/// of verifying it.
+///
+/// The byte counts are of the encoding given by `encoded_len`, and add up to that of the whole
+/// opening proof.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpeningProofStats {
+    /// The commitments made while opening, e.g. to the folded codewords of FRI.
+    pub commitment_bytes: usize,
+    /// The values opened at the queried locations, including the siblings of FRI's folds.
+    pub query_value_bytes: usize,
+    /// The Merkle paths, or other batch opening proofs, of the queries.
+    pub path_bytes: usize,
+    /// The proof of work witness.
+    pub pow_bytes: usize,
+    /// Everything else, e.g. the final polynomial of FRI and the lengths of sequences.
+    pub other_bytes: usize,
+    /// The hash and compression calls made by the verifier to check the queries.
+    pub verifier_hashes: usize,
+    /// The field additions, multiplications and inversions made by the verifier to combine and
+    /// fold the queried values, as estimated by the PCS.
+    pub verifier_field_ops: usize,
+}
+
+impl OpeningProofStats {
+    pub const fn total_bytes(&self) -> usize {
+        self.commitment_bytes
+            + self.query_value_bytes
+            + self.path_bytes
+            + self.pow_bytes
+            + self.other_bytes
+    }
+}
@@ -0,0 +1,188 @@
+//! Storage of prover data in an object store, so that the phases of a proof can run on different
+//! machines.
+//!
+//! A distributed prover commits on one machine, writes the prover data of the commitment, i.e. the
+//! Merkle tree together with the LDE matrices at its leaves, with `put_chunked`, and opens it on
+//! another after reading it back with `get_chunked`. The data is streamed into chunks of bounded
+//! size as it is encoded, so writing it needs no second copy in memory.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::fmt::Debug;
+
+use postcard::ser_flavors::Flavor;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// An object store, such as a bucket or a shared filesystem, holding chunks of bytes under string
+/// keys.
+pub trait ChunkStore {
+    type Error: Debug;
+
+    /// Store `chunk` under `key`, replacing any chunk already there.
+    fn put(&self, key: &str, chunk: &[u8]) -> Result<(), Self::Error>;
+
+    /// The chunk stored under `key`, or `None` if there is none.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Self::Error>;
+}
+
+/// A `ChunkStore` in memory, e.g. for tests.
+#[derive(Debug, Default)]
+pub struct MemoryChunkStore {
+    chunks: RefCell<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryChunkStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of chunks stored, including the headers of values.
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+}
+
+impl ChunkStore for MemoryChunkStore {
+    type Error = core::convert::Infallible;
+
+    fn put(&self, key: &str, chunk: &[u8]) -> Result<(), Self::Error> {
+        self.chunks.borrow_mut().insert(key.into(), chunk.to_vec());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.chunks.borrow().get(key).cloned())
+    }
+}
+
+/// An error from `put_chunked` or `get_chunked`.
+#[derive(Debug)]
+pub enum ChunkStoreError<E> {
+    /// The store failed.
+    Store(E),
+    /// No value, or one of its chunks, is stored under this key.
+    NotFound(String),
+    /// The value could not be encoded, or the stored bytes could not be decoded.
+    Malformed(postcard::Error),
+    /// The chunks hold a different number of bytes than their header records.
+    WrongLength { expected: usize, actual: usize },
+}
+
+/// Encode `value` in the `postcard` format and write it to `store` in chunks of at most
+/// `chunk_len` bytes, returning the number of chunks.
+///
+/// The chunks are stored under `{key}/0`, `{key}/1`, and so on, and then a header under `key`
+/// itself. As the header is written last, a reader never sees a value which was only partly
+/// written, while a value being replaced may be read with some of its new chunks.
+pub fn put_chunked<S, T>(
+    store: &S,
+    key: &str,
+    value: &T,
+    chunk_len: usize,
+) -> Result<usize, ChunkStoreError<S::Error>>
+where
+    S: ChunkStore,
+    T: Serialize + ?Sized,
+{
+    assert!(chunk_len > 0, "chunks must hold at least one byte");
+    let mut writer = ChunkWriter {
+        store,
+        key,
+        chunk_len,
+        buf: Vec::with_capacity(chunk_len),
+        num_chunks: 0,
+        len: 0,
+        error: None,
+    };
+    let result = postcard::serialize_with_flavor(value, &mut writer);
+    if let Some(err) = writer.error.take() {
+        return Err(ChunkStoreError::Store(err));
+    }
+    result.map_err(ChunkStoreError::Malformed)?;
+    if !writer.buf.is_empty() {
+        writer.flush().map_err(ChunkStoreError::Store)?;
+    }
+    let header = postcard::to_allocvec(&(writer.num_chunks, writer.len))
+        .expect("the header is always serializable");
+    store.put(key, &header).map_err(ChunkStoreError::Store)?;
+    Ok(writer.num_chunks)
+}
+
+/// Read a value written by `put_chunked` from `store`.
+pub fn get_chunked<S, T>(store: &S, key: &str) -> Result<T, ChunkStoreError<S::Error>>
+where
+    S: ChunkStore,
+    T: DeserializeOwned,
+{
+    let header = get_chunk(store, key)?;
+    let (num_chunks, len): (usize, usize) =
+        postcard::from_bytes(&header).map_err(ChunkStoreError::Malformed)?;
+    let mut bytes = Vec::with_capacity(len);
+    for i in 0..num_chunks {
+        bytes.extend(get_chunk(store, &chunk_key(key, i))?);
+    }
+    if bytes.len() != len {
+        return Err(ChunkStoreError::WrongLength {
+            expected: len,
+            actual: bytes.len(),
+        });
+    }
+    postcard::from_bytes(&bytes).map_err(ChunkStoreError::Malformed)
+}
+
+fn get_chunk<S: ChunkStore>(store: &S, key: &str) -> Result<Vec<u8>, ChunkStoreError<S::Error>> {
+    store
+        .get(key)
+        .map_err(ChunkStoreError::Store)?
+        .ok_or_else(|| ChunkStoreError::NotFound(key.into()))
+}
+
+fn chunk_key(key: &str, index: usize) -> String {
+    format!("{key}/{index}")
+}
+
+/// A `postcard` flavor writing the encoding to a `ChunkStore` whenever a chunk fills up.
+struct ChunkWriter<'a, S: ChunkStore> {
+    store: &'a S,
+    key: &'a str,
+    chunk_len: usize,
+    buf: Vec<u8>,
+    num_chunks: usize,
+    len: usize,
+    /// The error of the store, if it failed, which `postcard` cannot carry.
+    error: Option<S::Error>,
+}
+
+impl<S: ChunkStore> ChunkWriter<'_, S> {
+    fn flush(&mut self) -> Result<(), S::Error> {
+        self.store
+            .put(&chunk_key(self.key, self.num_chunks), &self.buf)?;
+        self.num_chunks += 1;
+        self.len += self.buf.len();
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+impl<S: ChunkStore> Flavor for &mut ChunkWriter<'_, S> {
+    type Output = ();
+
+    fn try_push(&mut self, data: u8) -> postcard::Result<()> {
+        self.buf.push(data);
+        if self.buf.len() == self.chunk_len {
+            if let Err(err) = self.flush() {
+                self.error = Some(err);
+                return Err(postcard::Error::SerializeBufferFull);
+            }
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> postcard::Result<()> {
+        Ok(())
+    }
+}
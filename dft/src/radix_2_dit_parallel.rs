@@ -1,5 +1,6 @@
 use alloc::collections::BTreeMap;
 use alloc::slice;
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::mem::{transmute, MaybeUninit};
@@ -125,75 +126,109 @@ impl<F: TwoAdicField + Ord> TwoAdicSubgroupDft<F> for Radix2DitParallel<F> {
         mat.bit_reverse_rows()
     }
 
-    #[instrument(skip_all, fields(dims = %mat.dimensions(), added_bits = added_bits))]
     fn coset_lde_batch(
         &self,
-        mut mat: RowMajorMatrix<F>,
+        mat: RowMajorMatrix<F>,
         added_bits: usize,
         shift: F,
     ) -> Self::Evaluations {
-        let w = mat.width;
-        let h = mat.height();
-        let log_h = log2_strict_usize(h);
-        let mid = log_h.div_ceil(2);
+        let (lde, _) = coset_lde_with(self, mat, added_bits, shift, |_| ());
+        BitReversalPerm::new_view(lde)
+    }
 
-        let mut inverse_twiddles_ref_mut = self.inverse_twiddles.borrow_mut();
-        let inverse_twiddles = inverse_twiddles_ref_mut
-            .entry(log_h)
-            .or_insert_with(|| compute_inverse_twiddles(log_h));
+    /// Each chunk is a block of the second half of the butterfly network of one coset, which is
+    /// final as soon as the block's last layer is done.
+    fn coset_lde_batch_bit_reversed_with<R: Send>(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+        on_chunk: impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync,
+    ) -> (RowMajorMatrix<F>, Vec<R>) {
+        coset_lde_with(self, mat, added_bits, shift, on_chunk)
+    }
+}
 
-        // The first half looks like a normal DIT.
-        reverse_matrix_index_bits(&mut mat);
-        first_half(&mut mat, mid, &inverse_twiddles.twiddles);
+/// The coset LDE of `mat`, with its rows in bit-reversed order, along with the results of
+/// `on_chunk` on each chunk of rows in order.
+#[instrument(skip_all, fields(dims = %mat.dimensions(), added_bits = added_bits))]
+fn coset_lde_with<F: TwoAdicField + Ord, R: Send>(
+    dft: &Radix2DitParallel<F>,
+    mut mat: RowMajorMatrix<F>,
+    added_bits: usize,
+    shift: F,
+    on_chunk: impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync,
+) -> (RowMajorMatrix<F>, Vec<R>) {
+    let w = mat.width;
+    let h = mat.height();
+    let log_h = log2_strict_usize(h);
+    let mid = log_h.div_ceil(2);
 
-        // For the second half, we flip the DIT, working in bit-reversed order.
-        reverse_matrix_index_bits(&mut mat);
-        // We'll also scale by 1/h, as per the usual inverse DFT algorithm.
-        let scale = Some(F::from_canonical_usize(h).inverse());
-        second_half(&mut mat, mid, &inverse_twiddles.bitrev_twiddles, scale);
-        // We skip the final bit-reversal, since the next FFT expects bit-reversed input.
-
-        let lde_elems = w * (h << added_bits);
-        let elems_to_add = lde_elems - w * h;
-        debug_span!("reserve_exact").in_scope(|| mat.values.reserve_exact(elems_to_add));
-
-        let g_big = F::two_adic_generator(log_h + added_bits);
-
-        let mat_ptr = mat.values.as_mut_ptr();
-        let rest_ptr = unsafe { (mat_ptr as *mut MaybeUninit<F>).add(w * h) };
-        let first_slice: &mut [F] = unsafe { slice::from_raw_parts_mut(mat_ptr, w * h) };
-        let rest_slice: &mut [MaybeUninit<F>] =
-            unsafe { slice::from_raw_parts_mut(rest_ptr, lde_elems - w * h) };
-        let mut first_coset_mat = RowMajorMatrixViewMut::new(first_slice, w);
-        let mut rest_cosets_mat = rest_slice
-            .chunks_exact_mut(w * h)
-            .map(|slice| RowMajorMatrixViewMut::new(slice, w))
-            .collect_vec();
-
-        for coset_idx in 1..(1 << added_bits) {
-            let total_shift = g_big.exp_u64(coset_idx as u64) * shift;
-            let coset_idx = reverse_bits_len(coset_idx, added_bits);
-            let dest = &mut rest_cosets_mat[coset_idx - 1]; // - 1 because we removed the first matrix.
-            coset_dft_oop(self, &first_coset_mat.as_view(), dest, total_shift);
-        }
+    let mut inverse_twiddles_ref_mut = dft.inverse_twiddles.borrow_mut();
+    let inverse_twiddles = inverse_twiddles_ref_mut
+        .entry(log_h)
+        .or_insert_with(|| compute_inverse_twiddles(log_h));
 
-        // Now run a forward DFT on the very first coset, this time in-place.
-        coset_dft(self, &mut first_coset_mat.as_view_mut(), shift);
+    // The first half looks like a normal DIT.
+    reverse_matrix_index_bits(&mut mat);
+    first_half(&mut mat, mid, &inverse_twiddles.twiddles);
 
-        // SAFETY: We wrote all values above.
-        unsafe {
-            mat.values.set_len(lde_elems);
-        }
-        BitReversalPerm::new_view(mat)
+    // For the second half, we flip the DIT, working in bit-reversed order.
+    reverse_matrix_index_bits(&mut mat);
+    // We'll also scale by 1/h, as per the usual inverse DFT algorithm.
+    let scale = Some(F::from_canonical_usize(h).inverse());
+    second_half(&mut mat, mid, &inverse_twiddles.bitrev_twiddles, scale);
+    // We skip the final bit-reversal, since the next FFT expects bit-reversed input.
+
+    let lde_elems = w * (h << added_bits);
+    let elems_to_add = lde_elems - w * h;
+    debug_span!("reserve_exact").in_scope(|| mat.values.reserve_exact(elems_to_add));
+
+    let g_big = F::two_adic_generator(log_h + added_bits);
+
+    let mat_ptr = mat.values.as_mut_ptr();
+    let rest_ptr = unsafe { (mat_ptr as *mut MaybeUninit<F>).add(w * h) };
+    let first_slice: &mut [F] = unsafe { slice::from_raw_parts_mut(mat_ptr, w * h) };
+    let rest_slice: &mut [MaybeUninit<F>] =
+        unsafe { slice::from_raw_parts_mut(rest_ptr, lde_elems - w * h) };
+    let mut first_coset_mat = RowMajorMatrixViewMut::new(first_slice, w);
+    let mut rest_cosets_mat = rest_slice
+        .chunks_exact_mut(w * h)
+        .map(|slice| RowMajorMatrixViewMut::new(slice, w))
+        .collect_vec();
+
+    // The chunks of each coset, indexed by its position in the LDE.
+    let mut coset_chunks: Vec<Vec<R>> = (0..1 << added_bits).map(|_| Vec::new()).collect();
+    for coset_idx in 1..(1 << added_bits) {
+        let total_shift = g_big.exp_u64(coset_idx as u64) * shift;
+        let coset_idx = reverse_bits_len(coset_idx, added_bits);
+        let dest = &mut rest_cosets_mat[coset_idx - 1]; // - 1 because we removed the first matrix.
+        coset_chunks[coset_idx] = coset_dft_oop(
+            dft,
+            &first_coset_mat.as_view(),
+            dest,
+            total_shift,
+            &on_chunk,
+        );
+    }
+
+    // Now run a forward DFT on the very first coset, this time in-place.
+    coset_chunks[0] = coset_dft(dft, &mut first_coset_mat.as_view_mut(), shift, &on_chunk);
+
+    // SAFETY: We wrote all values above.
+    unsafe {
+        mat.values.set_len(lde_elems);
     }
+    (mat, coset_chunks.into_iter().flatten().collect())
 }
 
 #[instrument(level = "debug", skip_all)]
-fn coset_dft<F: TwoAdicField + Ord>(
+fn coset_dft<F: TwoAdicField + Ord, R: Send>(
     dft: &Radix2DitParallel<F>,
     mat: &mut RowMajorMatrixViewMut<F>,
     shift: F,
-) {
+    on_chunk: &(impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync),
+) -> Vec<R> {
     let log_h = log2_strict_usize(mat.height());
     let mid = log_h.div_ceil(2);
 
@@ -208,17 +243,18 @@ fn coset_dft<F: TwoAdicField + Ord>(
     // For the second half, we flip the DIT, working in bit-reversed order.
     reverse_matrix_index_bits(mat);
 
-    second_half_general(mat, mid, twiddles);
+    second_half_general(mat, mid, twiddles, on_chunk)
 }
 
 /// Like `coset_dft`, except out-of-place.
 #[instrument(level = "debug", skip_all)]
-fn coset_dft_oop<F: TwoAdicField + Ord>(
+fn coset_dft_oop<F: TwoAdicField + Ord, R: Send>(
     dft: &Radix2DitParallel<F>,
     src: &RowMajorMatrixView<F>,
     dst_maybe: &mut RowMajorMatrixViewMut<MaybeUninit<F>>,
     shift: F,
-) {
+    on_chunk: &(impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync),
+) -> Vec<R> {
     assert_eq!(src.dimensions(), dst_maybe.dimensions());
 
     let log_h = log2_strict_usize(dst_maybe.height());
@@ -230,7 +266,7 @@ fn coset_dft_oop<F: TwoAdicField + Ord>(
             transmute::<&RowMajorMatrixView<F>, &RowMajorMatrixView<MaybeUninit<F>>>(src)
         };
         dst_maybe.copy_from(src_maybe);
-        return;
+        return vec![on_chunk(src.as_view())];
     }
 
     let mid = log_h.div_ceil(2);
@@ -253,7 +289,7 @@ fn coset_dft_oop<F: TwoAdicField + Ord>(
     // For the second half, we flip the DIT, working in bit-reversed order.
     reverse_matrix_index_bits(dst);
 
-    second_half_general(dst, mid, twiddles);
+    second_half_general(dst, mid, twiddles, on_chunk)
 }
 
 /// This can be used as the first half of a DIT butterfly network.
@@ -392,16 +428,20 @@ fn second_half<F: Field>(
 
 /// Like `second_half`, except supporting different twiddle factors per layer, enabling coset shifts
 /// to be baked into them.
+///
+/// Each block is final once its layers are done, so `on_chunk` is called on it right away, and the
+/// results are returned in the order of the blocks.
 #[instrument(level = "debug", skip_all)]
-fn second_half_general<F: Field>(
+fn second_half_general<F: Field, R: Send>(
     mat: &mut RowMajorMatrixViewMut<F>,
     mid: usize,
     twiddles_rev: &[Vec<F>],
-) {
+    on_chunk: &(impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync),
+) -> Vec<R> {
     let log_h = log2_strict_usize(mat.height());
     mat.par_row_chunks_exact_mut(1 << (log_h - mid))
         .enumerate()
-        .for_each(|(thread, mut submat)| {
+        .map(|(thread, mut submat)| {
             let mut backwards = false;
             for layer in mid..log_h {
                 let layer_rev = log_h - 1 - layer;
@@ -415,7 +455,9 @@ fn second_half_general<F: Field>(
                 );
                 backwards = !backwards;
             }
-        });
+            on_chunk(submat.as_view())
+        })
+        .collect()
 }
 
 /// One layer of a DIT butterfly network.
@@ -1,8 +1,9 @@
+use alloc::vec;
 use alloc::vec::Vec;
 
 use p3_field::TwoAdicField;
 use p3_matrix::bitrev::BitReversableMatrix;
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::util::swap_rows;
 use p3_matrix::Matrix;
 
@@ -123,4 +124,25 @@ pub trait TwoAdicSubgroupDft<F: TwoAdicField>: Clone + Default {
         );
         self.coset_dft_batch(coeffs, shift)
     }
+
+    /// Like `coset_lde_batch`, but returns the LDE with its rows in bit-reversed order, and calls
+    /// `on_chunk` on contiguous chunks of those rows as soon as they are final, while they are
+    /// likely still in cache. Returns the results of `on_chunk` in the order of the chunks, which
+    /// cover the LDE.
+    ///
+    /// By default, `on_chunk` is called once, on the whole LDE.
+    fn coset_lde_batch_bit_reversed_with<R: Send>(
+        &self,
+        mat: RowMajorMatrix<F>,
+        added_bits: usize,
+        shift: F,
+        on_chunk: impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync,
+    ) -> (RowMajorMatrix<F>, Vec<R>) {
+        let lde = self
+            .coset_lde_batch(mat, added_bits, shift)
+            .bit_reverse_rows()
+            .to_row_major_matrix();
+        let chunks = vec![on_chunk(lde.as_view())];
+        (lde, chunks)
+    }
 }
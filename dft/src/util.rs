@@ -1,10 +1,12 @@
 use core::borrow::BorrowMut;
 
-use p3_field::Field;
+use p3_field::{Field, TwoAdicField};
 use p3_matrix::dense::{DenseMatrix, DenseStorage, RowMajorMatrix};
 use p3_matrix::Matrix;
 use tracing::instrument;
 
+use crate::{Radix2Dit, TwoAdicSubgroupDft};
+
 /// Divide each coefficient of the given matrix by its height.
 #[instrument(skip_all, fields(dims = %mat.dimensions()))]
 pub fn divide_by_height<F: Field, S: DenseStorage<F> + BorrowMut<[F]>>(
@@ -23,3 +25,66 @@ pub(crate) fn coset_shift_cols<F: Field>(mat: &mut RowMajorMatrix<F>, shift: F)
             })
         });
 }
+
+/// Assert that each column of `evals`, given in natural order over a coset of a power-of-two
+/// subgroup, is the evaluation of a polynomial of degree less than `2^log_degree_bound`.
+///
+/// The shift of the coset does not change the degree of the interpolant, so it is not needed.
+/// This is a debugging aid which costs an inverse DFT of `evals`; it is meant to catch corrupted
+/// LDEs or mismatched blowups early, rather than through a failed low-degree test.
+#[instrument(skip_all, fields(dims = %evals.dimensions()))]
+pub fn assert_low_degree<F: TwoAdicField>(evals: &RowMajorMatrix<F>, log_degree_bound: usize) {
+    let degree_bound = 1 << log_degree_bound;
+    if evals.height() <= degree_bound {
+        return;
+    }
+    let coeffs = Radix2Dit::default().idft_batch(evals.clone());
+    for (r, row) in coeffs.rows().enumerate().skip(degree_bound).rev() {
+        if let Some(c) = row.into_iter().position(|coeff| !coeff.is_zero()) {
+            panic!(
+                "column {c} has degree {r}, but the degree bound is 2^{log_degree_bound} = {degree_bound}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::FieldAlgebra;
+    use p3_matrix::dense::RowMajorMatrix;
+    use rand::{thread_rng, Rng};
+
+    use super::assert_low_degree;
+    use crate::{Radix2Dit, TwoAdicSubgroupDft};
+
+    type F = BabyBear;
+
+    #[test]
+    fn low_degree_lde() {
+        let mut rng = thread_rng();
+        let evals = RowMajorMatrix::<F>::rand(&mut rng, 1 << 4, 3);
+        let lde = Radix2Dit::default().coset_lde_batch(evals, 2, F::GENERATOR);
+        assert_low_degree(&lde, 4);
+        assert_low_degree(&lde, 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "column 1 has degree")]
+    fn corrupted_lde() {
+        let mut rng = thread_rng();
+        let evals = RowMajorMatrix::<F>::rand(&mut rng, 1 << 4, 3);
+        let mut lde = Radix2Dit::default().coset_lde_batch(evals, 2, F::GENERATOR);
+        lde.values[3 * 7 + 1] += rng.gen::<F>();
+        assert_low_degree(&lde, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "the degree bound is 2^3")]
+    fn wrong_blowup() {
+        let mut rng = thread_rng();
+        let evals = RowMajorMatrix::<F>::rand(&mut rng, 1 << 4, 3);
+        let lde = Radix2Dit::default().coset_lde_batch(evals, 2, F::GENERATOR);
+        assert_low_degree(&lde, 3);
+    }
+}
@@ -0,0 +1,14 @@
+//! A verifier of `uni-stark` proofs for `no_std` targets with only `alloc`, such as the guest
+//! programs of other zkVMs, which can then check Plonky3 proofs within their own.
+//!
+//! `verify_proof` checks a proof made with the `BabyBearPoseidon2Config` of `p3-pinned-config`,
+//! as by the default BabyBear engine of `p3-examples`, against its verifying key, so the guest
+//! needs neither the AIR's code nor any randomness or threads; it only provides a global allocator
+//! and a panic handler. CI builds this crate for `riscv32imac-unknown-none-elf`, which keeps the
+//! verifier path of the workspace free of `std`.
+
+#![no_std]
+
+mod verify;
+
+pub use verify::*;
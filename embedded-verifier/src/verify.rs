@@ -0,0 +1,25 @@
+use p3_pinned_config::baby_bear_poseidon2::{
+    baby_bear_poseidon2_challenger, baby_bear_poseidon2_config, BabyBearChallenge,
+    BabyBearPoseidon2Config,
+};
+use p3_pinned_config::{verify_encoded, VerifyEncodedError};
+use p3_uni_stark::PcsError;
+
+pub type EmbeddedVerifyError =
+    VerifyEncodedError<PcsError<BabyBearPoseidon2Config>, BabyBearChallenge>;
+
+/// Verify a proof made with the `BabyBearPoseidon2Config`, against a verifying key, each in the
+/// binary encoding of `p3-uni-stark`, and public values given as canonical field elements.
+pub fn verify_proof(
+    proof: &[u8],
+    vk: &[u8],
+    public_values: &[u32],
+) -> Result<(), EmbeddedVerifyError> {
+    verify_encoded(
+        &baby_bear_poseidon2_config(),
+        &mut baby_bear_poseidon2_challenger(),
+        proof,
+        vk,
+        public_values,
+    )
+}
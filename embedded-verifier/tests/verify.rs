@@ -0,0 +1,51 @@
+use p3_baby_bear::BabyBear;
+use p3_embedded_verifier::{verify_proof, EmbeddedVerifyError};
+use p3_examples::engine::default_babybear_poseidon2;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_uni_stark::testing::{fibonacci_trace, FibonacciAir};
+use p3_uni_stark::{prove_with_key, setup_keys};
+
+/// The encoded proof and verifying key of a Fibonacci sequence of `n` rows, and its public values,
+/// proven on the host by the default BabyBear engine of `p3-examples`.
+fn prove_fibonacci(n: usize) -> (Vec<u8>, Vec<u8>, Vec<u32>) {
+    let (trace, pis) = fibonacci_trace(BabyBear::ZERO, BabyBear::ONE, n);
+
+    let engine = default_babybear_poseidon2();
+    let (pk, vk) = setup_keys(engine.config(), &FibonacciAir, pis.len());
+    let proof = prove_with_key(
+        engine.config(),
+        &pk,
+        &FibonacciAir,
+        &mut engine.challenger(),
+        trace,
+        &pis,
+    );
+    let pis = pis.iter().map(|x| x.as_canonical_u32()).collect();
+    (proof.to_bytes(), vk.to_bytes(), pis)
+}
+
+#[test]
+fn test_verify_engine_proof() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 4);
+    verify_proof(&proof, &vk, &pis).expect("verification failed");
+
+    let mut wrong_pis = pis.clone();
+    wrong_pis[2] += 1;
+    assert!(matches!(
+        verify_proof(&proof, &vk, &wrong_pis),
+        Err(EmbeddedVerifyError::Verification(_))
+    ));
+}
+
+#[test]
+fn test_verify_proof_malformed() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 3);
+    assert!(matches!(
+        verify_proof(&proof[..proof.len() - 1], &vk, &pis),
+        Err(EmbeddedVerifyError::Proof(_))
+    ));
+    assert!(matches!(
+        verify_proof(&proof, &vk, &[pis[0], u32::MAX, pis[2]]),
+        Err(EmbeddedVerifyError::PublicValue(1))
+    ));
+}
@@ -0,0 +1,40 @@
+use std::fmt::Debug;
+
+use p3_examples::circle::{circle_challenge_mmcs, circle_challenger, circle_stark_config};
+use p3_fri::create_benchmark_fri_config;
+use p3_keccak_air::{generate_trace_rows, KeccakAir};
+use p3_mersenne_31::Mersenne31;
+use p3_uni_stark::{prove, verify};
+use rand::random;
+use tracing_forest::util::LevelFilter;
+use tracing_forest::ForestLayer;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry};
+
+const NUM_HASHES: usize = 1365;
+
+fn main() -> Result<(), impl Debug> {
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+
+    Registry::default()
+        .with(env_filter)
+        .with(ForestLayer::default())
+        .init();
+
+    let config = circle_stark_config(create_benchmark_fri_config(circle_challenge_mmcs()));
+
+    let inputs = (0..NUM_HASHES).map(|_| random()).collect::<Vec<_>>();
+    let trace = generate_trace_rows::<Mersenne31>(inputs);
+
+    let proof = prove(&config, &KeccakAir {}, &mut circle_challenger(), trace, &[]);
+    verify(
+        &config,
+        &KeccakAir {},
+        &mut circle_challenger(),
+        &proof,
+        &[],
+    )
+}
@@ -58,13 +58,21 @@ impl<
         VECTOR_LEN,
     >
 {
+    /// Generates a trace for `num_hashes` random hashes. Where supported, the trace is allocated
+    /// with room for `extra_capacity_bits` more bits of height, for its low degree extension.
     #[inline]
-    pub fn generate_trace_rows(&self, num_hashes: usize) -> RowMajorMatrix<F>
+    pub fn generate_trace_rows(
+        &self,
+        num_hashes: usize,
+        extra_capacity_bits: usize,
+    ) -> RowMajorMatrix<F>
     where
         Standard: Distribution<F>,
     {
         match self {
-            ProofObjective::Blake3(b3_air) => b3_air.generate_trace_rows(num_hashes),
+            ProofObjective::Blake3(b3_air) => {
+                b3_air.generate_trace_rows(num_hashes, extra_capacity_bits)
+            }
             ProofObjective::Poseidon2(p2_air) => p2_air.generate_vectorized_trace_rows(num_hashes),
             ProofObjective::Keccak(k_air) => k_air.generate_trace_rows(num_hashes),
         }
@@ -0,0 +1,66 @@
+//! A ready-made configuration for proving over Mersenne31 with the circle PCS.
+//!
+//! `p3_uni_stark` is generic over its PCS, so a Circle STARK is just a `StarkConfig` whose PCS is a
+//! `CirclePcs`. The quotient splitting and the selectors over the circle domain are handled by the
+//! domains of the PCS, so any AIR proven with `prove` can be proven with this configuration.
+//!
+//! ```ignore
+//! let config = circle_stark_config(create_benchmark_fri_config(circle_challenge_mmcs()));
+//! let proof = prove(&config, &air, &mut circle_challenger(), trace, &public_values);
+//! verify(&config, &air, &mut circle_challenger(), &proof, &public_values)?;
+//! ```
+
+use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_circle::CirclePcs;
+use p3_commit::ExtensionMmcs;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::FriConfig;
+use p3_keccak::Keccak256Hash;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_mersenne_31::Mersenne31;
+use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+use p3_uni_stark::StarkConfig;
+
+pub type CircleFieldHash = SerializingHasher32<Keccak256Hash>;
+
+pub type CircleCompress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+
+/// The MMCS committing to the traces, hashing with Keccak.
+pub type CircleValMmcs<Val = Mersenne31> =
+    MerkleTreeMmcs<Val, u8, CircleFieldHash, CircleCompress, 32>;
+
+/// The MMCS committing to the FRI layers, over the challenge field.
+pub type CircleChallengeMmcs<Val = Mersenne31, Challenge = BinomialExtensionField<Val, 3>> =
+    ExtensionMmcs<Val, Challenge, CircleValMmcs<Val>>;
+
+pub type CircleChallenger<Val = Mersenne31> =
+    SerializingChallenger32<Val, HashChallenger<u8, Keccak256Hash, 32>>;
+
+pub type CircleStarkPcs<Val = Mersenne31, Challenge = BinomialExtensionField<Val, 3>> =
+    CirclePcs<Val, CircleValMmcs<Val>, CircleChallengeMmcs<Val, Challenge>>;
+
+/// A Circle STARK over Mersenne31, with Keccak Merkle trees and Fiat-Shamir.
+pub type CircleStarkConfig<Val = Mersenne31, Challenge = BinomialExtensionField<Val, 3>> =
+    StarkConfig<CircleStarkPcs<Val, Challenge>, Challenge, CircleChallenger<Val>>;
+
+pub fn circle_val_mmcs() -> CircleValMmcs {
+    CircleValMmcs::new(
+        CircleFieldHash::new(Keccak256Hash {}),
+        CircleCompress::new(Keccak256Hash {}),
+    )
+}
+
+pub fn circle_challenge_mmcs() -> CircleChallengeMmcs {
+    CircleChallengeMmcs::new(circle_val_mmcs())
+}
+
+/// A `CircleStarkConfig` whose FRI is configured by `fri_config`, which commits with
+/// `circle_challenge_mmcs()`.
+pub fn circle_stark_config(fri_config: FriConfig<CircleChallengeMmcs>) -> CircleStarkConfig {
+    CircleStarkConfig::new(CirclePcs::new(circle_val_mmcs(), fri_config))
+}
+
+/// A fresh challenger for proving or verifying with a `CircleStarkConfig`.
+pub fn circle_challenger() -> CircleChallenger {
+    CircleChallenger::from_hasher(vec![], Keccak256Hash {})
+}
@@ -0,0 +1,191 @@
+//! Ready-made, fully wired configurations, for producing a first proof without choosing each
+//! component of a `StarkConfig`.
+//!
+//! Each `default_*` constructor returns a `StarkEngine`, which holds the configuration together
+//! with the initial state of its challenger:
+//!
+//! ```ignore
+//! let engine = default_babybear_poseidon2();
+//! let trace = generate_trace_rows::<BabyBear>(inputs);
+//! let proof = engine.prove(&KeccakAir {}, trace, &[]);
+//! engine.verify(&KeccakAir {}, &proof, &[])?;
+//! ```
+//!
+//! All engines use FRI with `DEFAULT_LOG_BLOWUP`, `DEFAULT_NUM_QUERIES` and
+//! `DEFAULT_PROOF_OF_WORK_BITS`, for 100 bits of conjectured security. The blowup supports
+//! constraints of degree up to 3; AIRs of higher degree need a `FriConfig` of their own.
+//!
+//! Any constants, such as those of Poseidon2, are derived from a fixed seed, so engines built by
+//! the prover and the verifier agree. The BabyBear engine is that of `p3-pinned-config`, whose
+//! proofs the embedded verifier accepts.
+
+use p3_air::Air;
+use p3_challenger::{HashChallenger, SerializingChallenger64};
+use p3_circle::CirclePcs;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_goldilocks::Goldilocks;
+use p3_keccak::{Keccak256Hash, KeccakCompression, KeccakF, VECTOR_LEN};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+pub use p3_pinned_config::baby_bear_poseidon2::BabyBearPoseidon2Config;
+use p3_pinned_config::baby_bear_poseidon2::{
+    self, baby_bear_poseidon2_challenger, baby_bear_poseidon2_config,
+};
+use p3_symmetric::{PaddingFreeSponge, SerializingHasher64};
+use p3_uni_stark::{
+    prove, verify, PcsError, Proof, ProverConstraintFolder, StarkConfig, StarkGenericConfig,
+    SymbolicAirBuilder, Val, VerificationError, VerifierConstraintFolder,
+};
+
+use crate::circle::{circle_challenge_mmcs, circle_challenger, circle_val_mmcs, CircleStarkConfig};
+
+/// The FRI parameters of all the engines, which are those of the pinned `BabyBearPoseidon2Config`.
+pub const DEFAULT_LOG_BLOWUP: usize = baby_bear_poseidon2::LOG_BLOWUP;
+pub const DEFAULT_NUM_QUERIES: usize = baby_bear_poseidon2::NUM_QUERIES;
+pub const DEFAULT_PROOF_OF_WORK_BITS: usize = baby_bear_poseidon2::PROOF_OF_WORK_BITS;
+
+/// A `FriConfig` with the default parameters of this module, committing with `mmcs`.
+pub const fn default_fri_config<Mmcs>(mmcs: Mmcs) -> FriConfig<Mmcs> {
+    FriConfig {
+        log_blowup: DEFAULT_LOG_BLOWUP,
+        log_final_poly_len: 0,
+        num_queries: DEFAULT_NUM_QUERIES,
+        proof_of_work_bits: DEFAULT_PROOF_OF_WORK_BITS,
+        deduplicate_queries: false,
+        mmcs,
+    }
+}
+
+/// A STARK configuration together with the initial state of its challenger, which is everything
+/// needed to prove and verify.
+pub struct StarkEngine<SC: StarkGenericConfig> {
+    config: SC,
+    challenger: SC::Challenger,
+}
+
+impl<SC> StarkEngine<SC>
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: Clone,
+{
+    pub const fn new(config: SC, challenger: SC::Challenger) -> Self {
+        Self { config, challenger }
+    }
+
+    pub const fn config(&self) -> &SC {
+        &self.config
+    }
+
+    /// A challenger in its initial state, e.g. to call the functions of `p3_uni_stark` directly.
+    pub fn challenger(&self) -> SC::Challenger {
+        self.challenger.clone()
+    }
+
+    /// Prove that `trace` satisfies `air` with the given public values.
+    #[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+    pub fn prove<
+        #[cfg(debug_assertions)] A: for<'a> Air<p3_uni_stark::DebugConstraintBuilder<'a, Val<SC>>>,
+        #[cfg(not(debug_assertions))] A,
+    >(
+        &self,
+        air: &A,
+        trace: RowMajorMatrix<Val<SC>>,
+        public_values: &[Val<SC>],
+    ) -> Proof<SC>
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    {
+        prove(
+            &self.config,
+            air,
+            &mut self.challenger(),
+            trace,
+            public_values,
+        )
+    }
+
+    /// Verify a proof made by `prove` with an engine built the same way.
+    pub fn verify<A>(
+        &self,
+        air: &A,
+        proof: &Proof<SC>,
+        public_values: &[Val<SC>],
+    ) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    {
+        verify(
+            &self.config,
+            air,
+            &mut self.challenger(),
+            proof,
+            public_values,
+        )
+    }
+}
+
+/// A `StarkEngine` over BabyBear hashing with Poseidon2, the fastest of the defaults to prove.
+///
+/// Its config is the `BabyBearPoseidon2Config` pinned by `p3-pinned-config`, so the embedded
+/// verifier accepts its proofs.
+pub fn default_babybear_poseidon2() -> StarkEngine<BabyBearPoseidon2Config> {
+    StarkEngine::new(
+        baby_bear_poseidon2_config(),
+        baby_bear_poseidon2_challenger(),
+    )
+}
+
+/// A `StarkEngine` for a Circle STARK over Mersenne31, hashing with Keccak.
+///
+/// NB: The challenges are drawn from the cubic extension of Mersenne31, of about 93 bits, which
+/// bounds the security of the configuration below the 100 bits targeted by its FRI parameters.
+pub fn default_m31_circle() -> StarkEngine<CircleStarkConfig> {
+    let fri_config = default_fri_config(circle_challenge_mmcs());
+    StarkEngine::new(
+        CircleStarkConfig::new(CirclePcs::new(circle_val_mmcs(), fri_config)),
+        circle_challenger(),
+    )
+}
+
+type GoldilocksU64Hash = PaddingFreeSponge<KeccakF, 25, 17, 4>;
+type GoldilocksHash = SerializingHasher64<GoldilocksU64Hash>;
+type GoldilocksValMmcs = MerkleTreeMmcs<
+    [Goldilocks; VECTOR_LEN],
+    [u64; VECTOR_LEN],
+    GoldilocksHash,
+    KeccakCompression,
+    4,
+>;
+type GoldilocksChallenge = BinomialExtensionField<Goldilocks, 2>;
+type GoldilocksChallengeMmcs = ExtensionMmcs<Goldilocks, GoldilocksChallenge, GoldilocksValMmcs>;
+type GoldilocksPcs = TwoAdicFriPcs<
+    Goldilocks,
+    Radix2DitParallel<Goldilocks>,
+    GoldilocksValMmcs,
+    GoldilocksChallengeMmcs,
+>;
+type GoldilocksChallenger =
+    SerializingChallenger64<Goldilocks, HashChallenger<u8, Keccak256Hash, 32>>;
+
+/// Goldilocks, with a quadratic extension for challenges, and Keccak for the Merkle trees and the
+/// transcript.
+pub type GoldilocksKeccakConfig =
+    StarkConfig<GoldilocksPcs, GoldilocksChallenge, GoldilocksChallenger>;
+
+/// A `StarkEngine` over Goldilocks hashing with Keccak, whose Merkle trees hash `VECTOR_LEN`
+/// leaves at a time with the vectorized Keccak permutation.
+pub fn default_goldilocks_keccak() -> StarkEngine<GoldilocksKeccakConfig> {
+    let val_mmcs = GoldilocksValMmcs::new(
+        GoldilocksHash::new(GoldilocksU64Hash::new(KeccakF)),
+        KeccakCompression::new(KeccakF),
+    );
+    let fri_config = default_fri_config(GoldilocksChallengeMmcs::new(val_mmcs.clone()));
+    let pcs = GoldilocksPcs::new(Radix2DitParallel::default(), val_mmcs, fri_config);
+    StarkEngine::new(
+        GoldilocksKeccakConfig::new(pcs),
+        GoldilocksChallenger::from_hasher(vec![], Keccak256Hash),
+    )
+}
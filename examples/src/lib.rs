@@ -1,4 +1,6 @@
 pub mod airs;
+pub mod circle;
 pub mod dfts;
+pub mod engine;
 pub mod parsers;
 pub mod proofs;
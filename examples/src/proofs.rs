@@ -71,10 +71,10 @@ where
 
     let challenge_mmcs = ExtensionMmcs::<F, EF, _>::new(val_mmcs.clone());
 
-    let trace = proof_goal.generate_trace_rows(num_hashes);
-
     let fri_config = create_benchmark_fri_config(challenge_mmcs);
 
+    let trace = proof_goal.generate_trace_rows(num_hashes, fri_config.log_blowup);
+
     let pcs = TwoAdicFriPcs::new(dft, val_mmcs, fri_config);
 
     let config = StarkConfig::new(pcs);
@@ -137,10 +137,10 @@ where
 
     let challenge_mmcs = ExtensionMmcs::<F, EF, _>::new(val_mmcs.clone());
 
-    let trace = proof_goal.generate_trace_rows(num_hashes);
-
     let fri_config = create_benchmark_fri_config(challenge_mmcs);
 
+    let trace = proof_goal.generate_trace_rows(num_hashes, fri_config.log_blowup);
+
     let pcs = TwoAdicFriPcs::new(dft, val_mmcs, fri_config);
 
     let config = StarkConfig::new(pcs);
@@ -0,0 +1,39 @@
+use p3_challenger::CanObserve;
+use p3_examples::circle::{circle_challenge_mmcs, circle_challenger, circle_stark_config};
+use p3_field::FieldAlgebra;
+use p3_fri::create_test_fri_config;
+use p3_keccak_air::{generate_trace_rows, KeccakAir};
+use p3_mersenne_31::Mersenne31;
+use p3_uni_stark::{prove, verify};
+use rand::random;
+
+#[test]
+fn test_circle_keccak() {
+    let config = circle_stark_config(create_test_fri_config(circle_challenge_mmcs()));
+    // The Keccak constraints have degree 3, so the quotient is split into two chunks.
+    let inputs = (0..4).map(|_| random()).collect::<Vec<_>>();
+    let trace = generate_trace_rows::<Mersenne31>(inputs);
+
+    let proof = prove(&config, &KeccakAir {}, &mut circle_challenger(), trace, &[]);
+    verify(
+        &config,
+        &KeccakAir {},
+        &mut circle_challenger(),
+        &proof,
+        &[],
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_circle_keccak_wrong_challenger() {
+    let config = circle_stark_config(create_test_fri_config(circle_challenge_mmcs()));
+    let inputs = (0..4).map(|_| random()).collect::<Vec<_>>();
+    let trace = generate_trace_rows::<Mersenne31>(inputs);
+
+    let proof = prove(&config, &KeccakAir {}, &mut circle_challenger(), trace, &[]);
+    let mut challenger = circle_challenger();
+    challenger.observe(Mersenne31::ZERO);
+    verify(&config, &KeccakAir {}, &mut challenger, &proof, &[])
+        .expect_err("verification should fail with a different transcript");
+}
@@ -0,0 +1,63 @@
+use p3_baby_bear::BabyBear;
+use p3_examples::engine::{
+    default_babybear_poseidon2, default_goldilocks_keccak, default_m31_circle, DEFAULT_LOG_BLOWUP,
+    DEFAULT_NUM_QUERIES, DEFAULT_PROOF_OF_WORK_BITS,
+};
+use p3_fri::FriConfig;
+use p3_goldilocks::Goldilocks;
+use p3_keccak_air::{generate_trace_rows, KeccakAir};
+use p3_mersenne_31::Mersenne31;
+use rand::random;
+
+#[test]
+fn test_default_fri_parameters() {
+    let fri_config = FriConfig {
+        log_blowup: DEFAULT_LOG_BLOWUP,
+        log_final_poly_len: 0,
+        num_queries: DEFAULT_NUM_QUERIES,
+        proof_of_work_bits: DEFAULT_PROOF_OF_WORK_BITS,
+        deduplicate_queries: false,
+        mmcs: (),
+    };
+    assert!(fri_config.conjectured_soundness_bits() >= 100);
+}
+
+#[test]
+fn test_babybear_poseidon2_engine() {
+    let engine = default_babybear_poseidon2();
+    let inputs = (0..4).map(|_| random()).collect::<Vec<_>>();
+    let proof = engine.prove(&KeccakAir {}, generate_trace_rows::<BabyBear>(inputs), &[]);
+
+    // The engine of a verifier derives the same Poseidon2 constants.
+    default_babybear_poseidon2()
+        .verify(&KeccakAir {}, &proof, &[])
+        .expect("verification failed");
+}
+
+#[test]
+fn test_m31_circle_engine() {
+    let engine = default_m31_circle();
+    let inputs = (0..4).map(|_| random()).collect::<Vec<_>>();
+    let proof = engine.prove(
+        &KeccakAir {},
+        generate_trace_rows::<Mersenne31>(inputs),
+        &[],
+    );
+    engine
+        .verify(&KeccakAir {}, &proof, &[])
+        .expect("verification failed");
+}
+
+#[test]
+fn test_goldilocks_keccak_engine() {
+    let engine = default_goldilocks_keccak();
+    let inputs = (0..4).map(|_| random()).collect::<Vec<_>>();
+    let proof = engine.prove(
+        &KeccakAir {},
+        generate_trace_rows::<Goldilocks>(inputs),
+        &[],
+    );
+    engine
+        .verify(&KeccakAir {}, &proof, &[])
+        .expect("verification failed");
+}
@@ -0,0 +1,48 @@
+/* C declarations for p3-ffi, a verifier of Plonky3 proofs made with pinned configs. */
+
+#ifndef P3_FFI_H
+#define P3_FFI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The version of the ABI declared here, to compare with p3_ffi_abi_version() when loading. */
+#define P3_FFI_ABI_VERSION 1
+
+/* Status codes, which are never renumbered. Unknown codes should be treated as errors. */
+typedef uint32_t p3_status_t;
+#define P3_STATUS_OK 0
+#define P3_STATUS_NULL_POINTER 1
+#define P3_STATUS_UNKNOWN_CONFIG 2
+#define P3_STATUS_INVALID_PROOF 3
+#define P3_STATUS_INVALID_VERIFYING_KEY 4
+#define P3_STATUS_INVALID_PUBLIC_VALUE 5
+#define P3_STATUS_REJECTED 6
+#define P3_STATUS_PANIC 7
+
+/* Pinned configs. */
+#define P3_CONFIG_BABY_BEAR_KECCAK 1
+#define P3_CONFIG_KOALA_BEAR_KECCAK 2
+
+uint32_t p3_ffi_abi_version(void);
+
+/* A static, NUL-terminated description of a status, or NULL if it is unknown. */
+const char *p3_status_message(uint32_t status);
+
+/* Checks that a proof decodes under the given config, without verifying it. */
+p3_status_t p3_decode_proof(uint32_t config, const uint8_t *proof, size_t proof_len);
+
+/* Verifies a proof against a verifying key, with public values as canonical field elements. */
+p3_status_t p3_verify(uint32_t config, const uint8_t *proof, size_t proof_len,
+                      const uint8_t *vk, size_t vk_len, const uint32_t *public_values,
+                      size_t num_public_values);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* P3_FFI_H */
@@ -0,0 +1,7 @@
+pub use p3_pinned_config::keccak::*;
+
+/// The pinned config of BabyBear proofs, hashed with Keccak, as made by `baby_bear_keccak_config`.
+pub const P3_CONFIG_BABY_BEAR_KECCAK: u32 = 1;
+/// The pinned config of KoalaBear proofs, hashed with Keccak, as made by
+/// `koala_bear_keccak_config`.
+pub const P3_CONFIG_KOALA_BEAR_KECCAK: u32 = 2;
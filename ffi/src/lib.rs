@@ -0,0 +1,28 @@
+//! A C ABI for the verifier, for embedding in node software written in other languages, e.g.
+//! through cgo or from C++, with the declarations in `include/p3_ffi.h`.
+//!
+//! Proofs and verifying keys are passed in the binary encoding of `p3-uni-stark`, and must have
+//! been made with one of the pinned configs, identified by the `P3_CONFIG_*` constants, whose
+//! parameters never change. Each function returns a `P3Status`, whose codes are stable.
+//!
+//! The ABI is versioned by `P3_FFI_ABI_VERSION`, which callers should compare against the value
+//! returned by `p3_ffi_abi_version` when loading the library. It is bumped whenever an exported
+//! function changes its signature or meaning, or a pinned config changes; adding functions,
+//! status codes or configs does not bump it.
+
+mod config;
+mod status;
+mod verify;
+
+pub use config::*;
+pub use status::*;
+pub use verify::*;
+
+/// The version of the ABI described by `include/p3_ffi.h`.
+pub const P3_FFI_ABI_VERSION: u32 = 1;
+
+/// The version of the ABI implemented by this library.
+#[no_mangle]
+pub extern "C" fn p3_ffi_abi_version() -> u32 {
+    P3_FFI_ABI_VERSION
+}
@@ -0,0 +1,56 @@
+use core::ffi::c_char;
+
+/// The outcome of a call, whose codes are stable: they are never renumbered or reused, so that
+/// callers built against any version of the ABI can interpret them. Callers should treat codes
+/// they do not know as errors.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum P3Status {
+    Ok = 0,
+    /// A pointer was null, though its length was nonzero.
+    NullPointer = 1,
+    /// The config identifier is not one of the pinned configs.
+    UnknownConfig = 2,
+    /// The proof could not be decoded.
+    InvalidProof = 3,
+    /// The verifying key could not be decoded.
+    InvalidVerifyingKey = 4,
+    /// A public value is not a canonical field element.
+    InvalidPublicValue = 5,
+    /// The proof was decoded, but rejected by the verifier.
+    Rejected = 6,
+    /// The verifier panicked, which indicates a bug.
+    Panic = 7,
+}
+
+impl P3Status {
+    pub const fn message(self) -> &'static [u8] {
+        match self {
+            Self::Ok => b"ok\0",
+            Self::NullPointer => b"null pointer\0",
+            Self::UnknownConfig => b"unknown config\0",
+            Self::InvalidProof => b"invalid proof encoding\0",
+            Self::InvalidVerifyingKey => b"invalid verifying key encoding\0",
+            Self::InvalidPublicValue => b"invalid public value\0",
+            Self::Rejected => b"proof rejected\0",
+            Self::Panic => b"internal error\0",
+        }
+    }
+}
+
+/// A static, NUL-terminated description of `status`, or null if it is not a known status code.
+#[no_mangle]
+pub extern "C" fn p3_status_message(status: u32) -> *const c_char {
+    let status = match status {
+        0 => P3Status::Ok,
+        1 => P3Status::NullPointer,
+        2 => P3Status::UnknownConfig,
+        3 => P3Status::InvalidProof,
+        4 => P3Status::InvalidVerifyingKey,
+        5 => P3Status::InvalidPublicValue,
+        6 => P3Status::Rejected,
+        7 => P3Status::Panic,
+        _ => return core::ptr::null(),
+    };
+    status.message().as_ptr().cast()
+}
@@ -0,0 +1,121 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField32;
+use p3_koala_bear::KoalaBear;
+use p3_pinned_config::{verify_encoded, VerifyEncodedError};
+use p3_uni_stark::{Proof, StarkGenericConfig, Val};
+
+use crate::{
+    baby_bear_keccak_config, keccak_challenger, koala_bear_keccak_config, KeccakConfig, P3Status,
+    P3_CONFIG_BABY_BEAR_KECCAK, P3_CONFIG_KOALA_BEAR_KECCAK,
+};
+
+/// The `len` elements at `ptr`, which may only be null if `len` is zero.
+///
+/// # Safety
+/// If `len` is nonzero and `ptr` is not null, `ptr` must point to `len` initialized elements,
+/// which are not mutated for the lifetime `'a`.
+unsafe fn to_slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], P3Status> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(P3Status::NullPointer)
+    } else {
+        Ok(slice::from_raw_parts(ptr, len))
+    }
+}
+
+fn decode_proof<SC: StarkGenericConfig>(proof: &[u8]) -> Result<Proof<SC>, P3Status> {
+    Proof::from_bytes(proof).map_err(|_| P3Status::InvalidProof)
+}
+
+fn verify<SC>(
+    config: &SC,
+    mut challenger: SC::Challenger,
+    proof: &[u8],
+    vk: &[u8],
+    public_values: &[u32],
+) -> Result<(), P3Status>
+where
+    SC: StarkGenericConfig,
+    Val<SC>: PrimeField32,
+{
+    verify_encoded(config, &mut challenger, proof, vk, public_values).map_err(|err| match err {
+        VerifyEncodedError::Proof(_) => P3Status::InvalidProof,
+        VerifyEncodedError::VerifyingKey(_) => P3Status::InvalidVerifyingKey,
+        VerifyEncodedError::PublicValue(_) => P3Status::InvalidPublicValue,
+        VerifyEncodedError::Verification(_) => P3Status::Rejected,
+    })
+}
+
+/// Run `f`, turning its error or a panic into a status.
+fn run(f: impl FnOnce() -> Result<(), P3Status>) -> P3Status {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => P3Status::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => P3Status::Panic,
+    }
+}
+
+/// Checks that the `proof_len` bytes at `proof` decode to a proof under the pinned `config`,
+/// without verifying it.
+///
+/// # Safety
+/// `proof` must point to `proof_len` readable bytes, or be null if `proof_len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn p3_decode_proof(
+    config: u32,
+    proof: *const u8,
+    proof_len: usize,
+) -> P3Status {
+    run(|| {
+        let proof = unsafe { to_slice(proof, proof_len)? };
+        match config {
+            P3_CONFIG_BABY_BEAR_KECCAK => decode_proof::<KeccakConfig<BabyBear>>(proof).map(drop),
+            P3_CONFIG_KOALA_BEAR_KECCAK => decode_proof::<KeccakConfig<KoalaBear>>(proof).map(drop),
+            _ => Err(P3Status::UnknownConfig),
+        }
+    })
+}
+
+/// Verifies a proof under the pinned `config`, against a verifying key, each in the binary
+/// encoding of `p3-uni-stark`, with public values given as canonical field elements.
+///
+/// # Safety
+/// Each pointer must point to as many readable elements as its length, or be null if its length
+/// is zero.
+#[no_mangle]
+pub unsafe extern "C" fn p3_verify(
+    config: u32,
+    proof: *const u8,
+    proof_len: usize,
+    vk: *const u8,
+    vk_len: usize,
+    public_values: *const u32,
+    num_public_values: usize,
+) -> P3Status {
+    run(|| {
+        let proof = unsafe { to_slice(proof, proof_len)? };
+        let vk = unsafe { to_slice(vk, vk_len)? };
+        let public_values = unsafe { to_slice(public_values, num_public_values)? };
+        match config {
+            P3_CONFIG_BABY_BEAR_KECCAK => verify(
+                &baby_bear_keccak_config(),
+                keccak_challenger(),
+                proof,
+                vk,
+                public_values,
+            ),
+            P3_CONFIG_KOALA_BEAR_KECCAK => verify(
+                &koala_bear_keccak_config(),
+                keccak_challenger(),
+                proof,
+                vk,
+                public_values,
+            ),
+            _ => Err(P3Status::UnknownConfig),
+        }
+    })
+}
@@ -0,0 +1,152 @@
+use core::ffi::CStr;
+use core::ptr;
+
+use p3_baby_bear::BabyBear;
+use p3_ffi::{
+    baby_bear_keccak_config, keccak_challenger, p3_decode_proof, p3_ffi_abi_version,
+    p3_status_message, p3_verify, KeccakConfig, P3Status, P3_CONFIG_BABY_BEAR_KECCAK,
+    P3_CONFIG_KOALA_BEAR_KECCAK, P3_FFI_ABI_VERSION,
+};
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_uni_stark::testing::{fibonacci_trace, FibonacciAir};
+use p3_uni_stark::{prove_with_key, setup_keys, Proof};
+
+/// The encoded proof and verifying key of a Fibonacci sequence of `n` rows, and its public values.
+fn prove_fibonacci(n: usize) -> (Vec<u8>, Vec<u8>, Vec<u32>) {
+    let (trace, pis) = fibonacci_trace(BabyBear::ZERO, BabyBear::ONE, n);
+
+    let config = baby_bear_keccak_config();
+    let (pk, vk) = setup_keys(&config, &FibonacciAir, pis.len());
+    let proof = prove_with_key(
+        &config,
+        &pk,
+        &FibonacciAir,
+        &mut keccak_challenger(),
+        trace,
+        &pis,
+    );
+    let pis = pis.iter().map(|x| x.as_canonical_u32()).collect();
+    (proof.to_bytes(), vk.to_bytes(), pis)
+}
+
+/// `proof` with the first value opened at the first FRI query changed, so that it no longer matches
+/// the trace commitment.
+fn tamper_opening(proof: &[u8]) -> Vec<u8> {
+    let proof = Proof::<KeccakConfig<BabyBear>>::from_bytes(proof).unwrap();
+    let mut json: serde_json::Value = serde_json::from_str(&proof.to_json().unwrap()).unwrap();
+    let value = &mut json["proof"]["opening_proof"]["query_proofs"][0]["input_proof"][0]
+        ["opened_values"][0][0];
+    *value = ((value.as_u64().unwrap() + 1) % u64::from(BabyBear::ORDER_U32)).into();
+    Proof::<KeccakConfig<BabyBear>>::from_json(&json.to_string())
+        .unwrap()
+        .to_bytes()
+}
+
+fn verify(config: u32, proof: &[u8], vk: &[u8], pis: &[u32]) -> P3Status {
+    unsafe {
+        p3_verify(
+            config,
+            proof.as_ptr(),
+            proof.len(),
+            vk.as_ptr(),
+            vk.len(),
+            pis.as_ptr(),
+            pis.len(),
+        )
+    }
+}
+
+#[test]
+fn test_verify() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 4);
+    assert_eq!(
+        verify(P3_CONFIG_BABY_BEAR_KECCAK, &proof, &vk, &pis),
+        P3Status::Ok
+    );
+
+    let mut wrong_pis = pis.clone();
+    wrong_pis[2] += 1;
+    assert_eq!(
+        verify(P3_CONFIG_BABY_BEAR_KECCAK, &proof, &vk, &wrong_pis),
+        P3Status::Rejected
+    );
+    wrong_pis[2] = BabyBear::ORDER_U32;
+    assert_eq!(
+        verify(P3_CONFIG_BABY_BEAR_KECCAK, &proof, &vk, &wrong_pis),
+        P3Status::InvalidPublicValue
+    );
+}
+
+#[test]
+fn test_verify_tampered_opening() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 3);
+    assert_eq!(
+        verify(
+            P3_CONFIG_BABY_BEAR_KECCAK,
+            &tamper_opening(&proof),
+            &vk,
+            &pis
+        ),
+        P3Status::Rejected
+    );
+}
+
+#[test]
+fn test_verify_malformed() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 3);
+    assert_eq!(
+        verify(
+            P3_CONFIG_BABY_BEAR_KECCAK,
+            &proof[..proof.len() - 1],
+            &vk,
+            &pis
+        ),
+        P3Status::InvalidProof
+    );
+    assert_eq!(
+        verify(P3_CONFIG_BABY_BEAR_KECCAK, &proof, &vk[1..], &pis),
+        P3Status::InvalidVerifyingKey
+    );
+    assert_eq!(verify(99, &proof, &vk, &pis), P3Status::UnknownConfig);
+    // A BabyBear proof cannot be verified as a KoalaBear one.
+    assert_ne!(
+        verify(P3_CONFIG_KOALA_BEAR_KECCAK, &proof, &vk, &pis),
+        P3Status::Ok
+    );
+    let status = unsafe {
+        p3_verify(
+            P3_CONFIG_BABY_BEAR_KECCAK,
+            ptr::null(),
+            proof.len(),
+            vk.as_ptr(),
+            vk.len(),
+            pis.as_ptr(),
+            pis.len(),
+        )
+    };
+    assert_eq!(status, P3Status::NullPointer);
+}
+
+#[test]
+fn test_decode_proof() {
+    let (proof, _, _) = prove_fibonacci(1 << 3);
+    let decode =
+        |config, proof: &[u8]| unsafe { p3_decode_proof(config, proof.as_ptr(), proof.len()) };
+    assert_eq!(decode(P3_CONFIG_BABY_BEAR_KECCAK, &proof), P3Status::Ok);
+    assert_eq!(
+        decode(P3_CONFIG_BABY_BEAR_KECCAK, &proof[..proof.len() / 2]),
+        P3Status::InvalidProof
+    );
+    assert_eq!(
+        unsafe { p3_decode_proof(P3_CONFIG_BABY_BEAR_KECCAK, ptr::null(), 0) },
+        P3Status::InvalidProof
+    );
+}
+
+#[test]
+fn test_abi() {
+    assert_eq!(p3_ffi_abi_version(), P3_FFI_ABI_VERSION);
+    let message = unsafe { CStr::from_ptr(p3_status_message(P3Status::Rejected as u32)) };
+    assert_eq!(message.to_str(), Ok("proof rejected"));
+    assert!(p3_status_message(100).is_null());
+}
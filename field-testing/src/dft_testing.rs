@@ -1,5 +1,6 @@
 use p3_dft::{NaiveDft, TwoAdicSubgroupDft};
 use p3_field::TwoAdicField;
+use p3_matrix::bitrev::BitReversableMatrix;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use rand::distributions::{Distribution, Standard};
@@ -110,6 +111,33 @@ where
     }
 }
 
+pub fn test_coset_lde_bit_reversed_with_matches_naive<F, Dft>()
+where
+    F: TwoAdicField,
+    Standard: Distribution<F>,
+    Dft: TwoAdicSubgroupDft<F>,
+{
+    let dft = Dft::default();
+    let mut rng = thread_rng();
+    for log_h in 0..5 {
+        for added_bits in 0..3 {
+            let h = 1 << log_h;
+            let mat = RowMajorMatrix::<F>::rand(&mut rng, h, 3);
+            let shift = F::GENERATOR;
+            let coset_lde_naive = NaiveDft
+                .coset_lde_batch(mat.clone(), added_bits, shift)
+                .bit_reverse_rows()
+                .to_row_major_matrix();
+            let (coset_lde_result, chunks) =
+                dft.coset_lde_batch_bit_reversed_with(mat, added_bits, shift, |chunk| {
+                    chunk.values.to_vec()
+                });
+            assert_eq!(coset_lde_naive, coset_lde_result);
+            assert_eq!(coset_lde_naive.values, chunks.concat());
+        }
+    }
+}
+
 pub fn test_dft_idft_consistency<F, Dft>()
 where
     F: TwoAdicField,
@@ -161,6 +189,11 @@ macro_rules! test_field_dft {
                 $crate::test_coset_lde_matches_naive::<$field, $dft>();
             }
 
+            #[test]
+            fn coset_lde_bit_reversed_with_matches_naive() {
+                $crate::test_coset_lde_bit_reversed_with_matches_naive::<$field, $dft>();
+            }
+
             #[test]
             fn dft_idft_consistency() {
                 $crate::test_dft_idft_consistency::<$field, $dft>();
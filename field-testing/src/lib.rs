@@ -6,6 +6,7 @@ extern crate alloc;
 
 pub mod bench_func;
 pub mod dft_testing;
+pub mod packed_audit;
 pub mod packedfield_testing;
 
 pub use bench_func::*;
@@ -16,6 +17,7 @@ use p3_field::{
     cyclic_subgroup_coset_known_order, cyclic_subgroup_known_order, two_adic_coset_zerofier,
     two_adic_subgroup_zerofier, ExtensionField, Field, TwoAdicField,
 };
+pub use packed_audit::*;
 pub use packedfield_testing::*;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
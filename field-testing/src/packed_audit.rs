@@ -0,0 +1,269 @@
+//! Differential testing of packed field arithmetic against the scalar reference.
+//!
+//! Each architecture-specific packing (AVX2, AVX-512, NEON) reimplements the field arithmetic,
+//! so every packed operation is recomputed lane by lane with the scalar field on random inputs,
+//! mixed with special values. Rather than stopping at the first mismatch, every divergence is
+//! collected with the operation, lane and inputs, which makes lane-dependent bugs easy to spot.
+//!
+//! By default only a few trials are run with the other packed field tests. Enabling the
+//! `packed-audit` feature of this crate runs many more, e.g.
+//! `cargo test -p p3-baby-bear --features p3-field-testing/packed-audit`.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use p3_field::{Field, FieldAlgebra, PackedField, PackedValue};
+use rand::distributions::{Distribution, Standard};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// The number of random trials run by `test_packed_matches_scalar`.
+pub const PACKED_AUDIT_TRIALS: usize = if cfg!(feature = "packed-audit") {
+    1 << 16
+} else {
+    1 << 6
+};
+
+/// The number of divergences listed when `test_packed_matches_scalar` fails.
+const MAX_REPORTED: usize = 16;
+
+/// A lane on which a packed operation disagreed with the scalar reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence<F> {
+    pub op: &'static str,
+    pub trial: usize,
+    pub lane: usize,
+    /// The values of the packed inputs in this lane.
+    pub inputs: Vec<F>,
+    pub packed: F,
+    pub scalar: F,
+}
+
+impl<F: Display> Display for Divergence<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} differs in lane {} of trial {}: inputs [",
+            self.op, self.lane, self.trial
+        )?;
+        for (i, input) in self.inputs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{input}")?;
+        }
+        write!(f, "], packed {}, scalar {}", self.packed, self.scalar)
+    }
+}
+
+/// Run `trials` random trials of every packed operation, returning all lanes on which the result
+/// differs from the scalar reference.
+///
+/// Each lane of each input is either random, or with probability 1/4 one of `specials` or of a
+/// few values which tend to hit edge cases of reductions, like `0` and `-1`.
+pub fn find_packed_divergences<PF>(
+    trials: usize,
+    specials: &[PF::Scalar],
+    seed: u64,
+) -> Vec<Divergence<PF::Scalar>>
+where
+    PF: PackedField,
+    Standard: Distribution<PF::Scalar>,
+{
+    let mut specials = specials.to_vec();
+    specials.extend([
+        PF::Scalar::ZERO,
+        PF::Scalar::ONE,
+        PF::Scalar::TWO,
+        PF::Scalar::NEG_ONE,
+        -PF::Scalar::TWO,
+        PF::Scalar::TWO.inverse(),
+    ]);
+
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut divergences = Vec::new();
+    for trial in 0..trials {
+        let mut sample = || {
+            PF::from_fn(|_| {
+                if rng.gen_ratio(1, 4) {
+                    specials[rng.gen_range(0..specials.len())]
+                } else {
+                    rng.gen()
+                }
+            })
+        };
+        let (a, b, c) = (sample(), sample(), sample());
+        let s: PF::Scalar = rng.gen();
+        let nonzero_s = if s.is_zero() { PF::Scalar::ONE } else { s };
+        let power: u64 = rng.gen();
+
+        let mut audit = Audit {
+            divergences: &mut divergences,
+            trial,
+        };
+
+        audit.check("from", &[], |_| PF::from(s), |_| s);
+
+        audit.check("add", &[a, b], |x| x[0] + x[1], |x| x[0] + x[1]);
+        audit.check("sub", &[a, b], |x| x[0] - x[1], |x| x[0] - x[1]);
+        audit.check("mul", &[a, b], |x| x[0] * x[1], |x| x[0] * x[1]);
+        audit.check("neg", &[a], |x| -x[0], |x| -x[0]);
+        audit.check("double", &[a], |x| x[0].double(), |x| x[0].double());
+        audit.check("square", &[a], |x| x[0].square(), |x| x[0].square());
+        audit.check("cube", &[a], |x| x[0].cube(), |x| x[0].cube());
+        audit.check(
+            "exp_const_u64::<3>",
+            &[a],
+            |x| x[0].exp_const_u64::<3>(),
+            |x| x[0].exp_const_u64::<3>(),
+        );
+        audit.check(
+            "exp_const_u64::<5>",
+            &[a],
+            |x| x[0].exp_const_u64::<5>(),
+            |x| x[0].exp_const_u64::<5>(),
+        );
+        audit.check(
+            "exp_const_u64::<7>",
+            &[a],
+            |x| x[0].exp_const_u64::<7>(),
+            |x| x[0].exp_const_u64::<7>(),
+        );
+        audit.check(
+            "exp_u64",
+            &[a],
+            |x| x[0].exp_u64(power),
+            |x| x[0].exp_u64(power),
+        );
+        audit.check(
+            "exp_power_of_2",
+            &[a],
+            |x| x[0].exp_power_of_2(5),
+            |x| x[0].exp_power_of_2(5),
+        );
+        audit.check(
+            "mul_2exp_u64",
+            &[a],
+            |x| x[0].mul_2exp_u64(power % 64),
+            |x| x[0].mul_2exp_u64(power % 64),
+        );
+
+        audit.check("add scalar", &[a], |x| x[0] + s, |x| x[0] + s);
+        audit.check("sub scalar", &[a], |x| x[0] - s, |x| x[0] - s);
+        audit.check("mul scalar", &[a], |x| x[0] * s, |x| x[0] * s);
+        audit.check(
+            "div scalar",
+            &[a],
+            |x| x[0] / nonzero_s,
+            |x| x[0] / nonzero_s,
+        );
+
+        audit.check(
+            "mul add",
+            &[a, b, c],
+            |x| x[0] * x[1] + x[2],
+            |x| x[0] * x[1] + x[2],
+        );
+        audit.check(
+            "sum",
+            &[a, b, c],
+            |x| x.iter().copied().sum(),
+            |x| x.iter().copied().sum(),
+        );
+        audit.check(
+            "product",
+            &[a, b, c],
+            |x| x.iter().copied().product(),
+            |x| x.iter().copied().product(),
+        );
+        audit.check(
+            "dot_product",
+            &[a, b, c],
+            |x| PF::dot_product(&[x[0], x[1], x[2]], &[x[1], x[2], x[0]]),
+            |x| PF::Scalar::dot_product(&[x[0], x[1], x[2]], &[x[1], x[2], x[0]]),
+        );
+    }
+    divergences
+}
+
+/// Check that packed arithmetic on `PF` agrees with the scalar reference on every lane, running
+/// `PACKED_AUDIT_TRIALS` random trials, and list the divergences found otherwise.
+pub fn test_packed_matches_scalar<PF>(specials: PF)
+where
+    PF: PackedField,
+    Standard: Distribution<PF::Scalar>,
+{
+    let divergences = find_packed_divergences::<PF>(
+        PACKED_AUDIT_TRIALS,
+        specials.as_slice(),
+        0x7cd5_0e4f_a1b2_93c6,
+    );
+    if divergences.is_empty() {
+        return;
+    }
+    let mut report = String::new();
+    for divergence in divergences.iter().take(MAX_REPORTED) {
+        report += &format!("\n  {divergence}");
+    }
+    if divergences.len() > MAX_REPORTED {
+        report += &format!("\n  ... and {} more", divergences.len() - MAX_REPORTED);
+    }
+    panic!(
+        "{} divergences between packed and scalar arithmetic:{report}",
+        divergences.len()
+    );
+}
+
+struct Audit<'a, F> {
+    divergences: &'a mut Vec<Divergence<F>>,
+    trial: usize,
+}
+
+impl<F: Field> Audit<'_, F> {
+    /// Compare `packed` applied to `inputs` with `scalar` applied to each lane of `inputs`.
+    fn check<PF>(
+        &mut self,
+        op: &'static str,
+        inputs: &[PF],
+        packed: impl Fn(&[PF]) -> PF,
+        scalar: impl Fn(&[F]) -> F,
+    ) where
+        PF: PackedField<Scalar = F>,
+    {
+        let result = packed(inputs);
+        for lane in 0..PF::WIDTH {
+            let lane_inputs: Vec<F> = inputs.iter().map(|x| x.as_slice()[lane]).collect();
+            let expected = scalar(&lane_inputs);
+            let actual = result.as_slice()[lane];
+            if actual != expected {
+                self.divergences.push(Divergence {
+                    op,
+                    trial: self.trial,
+                    lane,
+                    inputs: lane_inputs,
+                    packed: actual,
+                    scalar: expected,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{Field, FieldAlgebra};
+
+    use super::{find_packed_divergences, test_packed_matches_scalar};
+
+    type F = BabyBear;
+    type P = <F as Field>::Packing;
+
+    #[test]
+    fn packing_matches_scalar() {
+        test_packed_matches_scalar::<P>(P::ZERO);
+        assert!(find_packed_divergences::<F>(100, &[F::NEG_ONE], 1).is_empty());
+    }
+}
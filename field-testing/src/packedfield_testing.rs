@@ -464,6 +464,10 @@ macro_rules! test_packed_field {
             fn test_multiplicative_inverse() {
                 $crate::test_multiplicative_inverse::<$packedfield>();
             }
+            #[test]
+            fn test_packed_matches_scalar() {
+                $crate::test_packed_matches_scalar::<$packedfield>($specials);
+            }
         }
     };
 }
@@ -0,0 +1,103 @@
+//! Packed kernels for sums and dot products of slices.
+
+use p3_maybe_rayon::prelude::*;
+
+use crate::field::Field;
+use crate::{ExtensionField, FieldAlgebra, FieldExtensionAlgebra, PackedValue};
+
+/// The number of elements each parallel task of the kernels below accumulates. This is a multiple
+/// of the width of any packing, so that only the last block has an unpacked suffix.
+const BLOCK_LEN: usize = 1 << 12;
+
+/// The sum of `values`, accumulated in packed form, in parallel over large blocks.
+pub fn packed_sum<F: Field>(values: &[F]) -> F {
+    values.par_chunks(BLOCK_LEN).map(packed_sum_block).sum()
+}
+
+fn packed_sum_block<F: Field>(values: &[F]) -> F {
+    let (packed, suffix) = F::Packing::pack_slice_with_suffix(values);
+    let packed_sum = packed.iter().fold(F::Packing::ZERO, |acc, &x| acc + x);
+    packed_sum.as_slice().iter().copied().sum::<F>() + suffix.iter().copied().sum::<F>()
+}
+
+/// The dot product of `base` and `ext`, which must have the same length.
+///
+/// The products are accumulated in packed form, in parallel over large blocks. This is e.g. how a
+/// row of base field values is combined with powers of an extension challenge.
+pub fn dot_product_base_ext<F, EF>(base: &[F], ext: &[EF]) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    assert_eq!(
+        base.len(),
+        ext.len(),
+        "dot product of slices of different lengths"
+    );
+    base.par_chunks(BLOCK_LEN)
+        .zip(ext.par_chunks(BLOCK_LEN))
+        .map(|(base, ext)| dot_product_base_ext_block(base, ext))
+        .sum()
+}
+
+fn dot_product_base_ext_block<F, EF>(base: &[F], ext: &[EF]) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    let (packed, suffix) = F::Packing::pack_slice_with_suffix(base);
+    let split = packed.len() * F::Packing::WIDTH;
+    let packed_dot = packed
+        .iter()
+        .zip(ext[..split].chunks_exact(F::Packing::WIDTH))
+        .fold(EF::ExtensionPacking::ZERO, |acc, (&b, e)| {
+            acc + pack_ext_slice::<F, EF>(e) * b
+        });
+    sum_ext_lanes::<F, EF>(&packed_dot)
+        + suffix
+            .iter()
+            .zip(&ext[split..])
+            .map(|(&b, &e)| e * b)
+            .sum::<EF>()
+}
+
+/// The packed extension element with `x` in every lane.
+#[inline]
+pub fn broadcast_ext<F, EF>(x: EF) -> EF::ExtensionPacking
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    EF::ExtensionPacking::from_base_fn(|i| F::Packing::from(x.as_base_slice()[i]))
+}
+
+/// The packed extension element whose lanes hold `xs`, which must have the width of the packing.
+#[inline]
+pub fn pack_ext_slice<F, EF>(xs: &[EF]) -> EF::ExtensionPacking
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    debug_assert_eq!(xs.len(), F::Packing::WIDTH);
+    EF::ExtensionPacking::from_base_fn(|i| F::Packing::from_fn(|j| xs[j].as_base_slice()[i]))
+}
+
+/// The extension element in lane `lane` of `x`.
+#[inline]
+pub fn unpack_ext_lane<F, EF>(x: &EF::ExtensionPacking, lane: usize) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    EF::from_base_fn(|i| x.as_base_slice()[i].as_slice()[lane])
+}
+
+/// The sum of the lanes of `x`.
+#[inline]
+pub fn sum_ext_lanes<F, EF>(x: &EF::ExtensionPacking) -> EF
+where
+    F: Field,
+    EF: ExtensionField<F>,
+{
+    EF::from_base_fn(|i| x.as_base_slice()[i].as_slice().iter().copied().sum())
+}
@@ -13,6 +13,7 @@ use nums::{Factorizer, FactorizerFromSplitter, MillerRabin, PollardRho};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
+use crate::dot::{broadcast_ext, pack_ext_slice};
 use crate::exponentiation::exp_u64_by_squaring;
 use crate::packed::{PackedField, PackedValue};
 use crate::Packable;
@@ -494,13 +495,9 @@ pub trait ExtensionField<Base: Field>: Field + FieldExtensionAlgebra<Base> {
     fn ext_powers_packed(&self) -> Powers<Self::ExtensionPacking> {
         let powers = self.powers().take(Base::Packing::WIDTH + 1).collect_vec();
         // Transpose first WIDTH powers
-        let current = Self::ExtensionPacking::from_base_fn(|i| {
-            Base::Packing::from_fn(|j| powers[j].as_base_slice()[i])
-        });
+        let current = pack_ext_slice::<Base, Self>(&powers[..Base::Packing::WIDTH]);
         // Broadcast self^WIDTH
-        let multiplier = Self::ExtensionPacking::from_base_fn(|i| {
-            Base::Packing::from(powers[Base::Packing::WIDTH].as_base_slice()[i])
-        });
+        let multiplier = broadcast_ext::<Base, Self>(powers[Base::Packing::WIDTH]);
 
         Powers {
             base: multiplier,
@@ -234,6 +234,15 @@ pub fn split_32<SF: PrimeField, TF: PrimeField32>(val: SF, n: usize) -> Vec<TF>
     result
 }
 
+/// Given an SF element, reduce it modulo the order of TF.
+///
+/// If `val` is uniformly distributed, the result is within statistical distance `|TF| / |SF|` of
+/// uniform, which is negligible when SF is much larger than TF.
+pub fn reduce_mod_32<SF: PrimeField, TF: PrimeField32>(val: SF) -> TF {
+    let reduced = val.as_canonical_biguint() % BigUint::from(TF::ORDER_U32);
+    TF::from_canonical_u32(reduced.to_u32_digits().first().copied().unwrap_or(0))
+}
+
 /// Maximally generic dot product.
 pub fn dot_product<S, LI, RI>(li: LI, ri: RI) -> S
 where
@@ -11,6 +11,9 @@ pub struct FriConfig<M> {
     pub log_final_poly_len: usize,
     pub num_queries: usize,
     pub proof_of_work_bits: usize,
+    /// Whether to skip the proofs of query indices which were already sampled; see
+    /// `sample_query_indices`.
+    pub deduplicate_queries: bool,
     pub mmcs: M,
 }
 
@@ -28,6 +31,9 @@ impl<M> FriConfig<M> {
     ///
     /// Certain users may instead want to look at proven soundness, a more complex calculation which
     /// isn't currently supported by this crate.
+    ///
+    /// Deduplicating queries does not change this: a repeated query adds nothing to the check of
+    /// its first occurrence, so only the proof gets smaller.
     pub fn conjectured_soundness_bits(&self) -> usize {
         self.log_blowup * self.num_queries + self.proof_of_work_bits
     }
@@ -66,6 +72,7 @@ pub fn create_test_fri_config<Mmcs>(mmcs: Mmcs) -> FriConfig<Mmcs> {
         log_final_poly_len: 0,
         num_queries: 2,
         proof_of_work_bits: 1,
+        deduplicate_queries: false,
         mmcs,
     }
 }
@@ -78,6 +85,7 @@ pub fn create_benchmark_fri_config<Mmcs>(mmcs: Mmcs) -> FriConfig<Mmcs> {
         log_final_poly_len: 0,
         num_queries: 100,
         proof_of_work_bits: 16,
+        deduplicate_queries: false,
         mmcs,
     }
 }
@@ -0,0 +1,441 @@
+//! Committing to, and opening, batches whose LDEs are too large for one machine.
+//!
+//! The rows of each LDE, in the bit-reversed order in which they are committed, are split into
+//! `2^k` shards of consecutive rows. Each shard is a coset of a smaller two-adic subgroup, so a
+//! worker computes the LDE of its shard from the coefficients of the polynomials alone, and builds
+//! the Merkle tree over it, with `TwoAdicFriPcs::commit_shard`. The coordinator merges the roots of
+//! the shards into the commitment to the batch with `TwoAdicFriPcs::merge_shard_commits`.
+//!
+//! To open, the coordinator runs `TwoAdicFriPcs::open_sharded`. The workers evaluate the
+//! polynomials at the opening points and reduce their rows into their part of the input of FRI,
+//! while the coordinator sums these parts, runs the commit phase of FRI, and completes the Merkle
+//! proofs of the workers' openings for each query. The proof is the same as `Pcs::open` would make
+//! from the whole batch, and is checked by `Pcs::verify`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use itertools::{izip, Itertools};
+use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
+use p3_commit::{Mmcs, OpenedValues, ShardedMmcs, TwoAdicMultiplicativeCoset};
+use p3_dft::TwoAdicSubgroupDft;
+use p3_field::{
+    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, ExtensionField,
+    Field, FieldAlgebra, TwoAdicField,
+};
+use p3_matrix::bitrev::BitReversableMatrix;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
+use p3_maybe_rayon::prelude::*;
+use p3_util::linear_map::LinearMap;
+use p3_util::{log2_strict_usize, reverse_bits_len, reverse_slice_index_bits};
+use serde::{Deserialize, Serialize};
+use tracing::{info_span, instrument};
+
+use crate::{
+    prover, BatchOpening, FriProof, TwoAdicFriGenericConfig, TwoAdicFriGenericConfigForMmcs,
+    TwoAdicFriPcs,
+};
+
+/// What the coordinator keeps of a batch committed in shards.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ShardedBatch<Val: Send + Sync, InputMmcs: ShardedMmcs<Val>> {
+    merged: InputMmcs::MergedShards,
+    log_num_shards: usize,
+    /// The log of the height of the LDEs, which is the same for every matrix of the batch.
+    log_height: usize,
+    widths: Vec<usize>,
+    _phantom: PhantomData<Val>,
+}
+
+impl<Val: Send + Sync, InputMmcs: ShardedMmcs<Val>> ShardedBatch<Val, InputMmcs> {
+    pub const fn log_num_shards(&self) -> usize {
+        self.log_num_shards
+    }
+
+    pub const fn log_height(&self) -> usize {
+        self.log_height
+    }
+
+    const fn log_shard_height(&self) -> usize {
+        self.log_height - self.log_num_shards
+    }
+}
+
+/// An opening of a matrix at a point, as needed by a worker to fold it into its part of the input
+/// of FRI.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ShardOpening<Challenge> {
+    pub point: Challenge,
+    /// The power of the batch combination challenge by which the columns are offset.
+    pub alpha_pow_offset: Challenge,
+    /// The values of the columns at the point, combined by powers of the challenge.
+    pub reduced_value: Challenge,
+}
+
+/// The coordinator's handle on the workers holding the shards of a batch, e.g. a client for the
+/// RPC service they run.
+///
+/// Each method runs the `TwoAdicFriPcs` method of the same name, with the `_shard` suffix, on the
+/// prover data of `shard`.
+pub trait ShardWorkers<Val: Field, Challenge, InputMmcs: Mmcs<Val>> {
+    fn evaluate(&self, shard: usize, points: &[Vec<Challenge>]) -> Vec<Vec<Vec<Challenge>>>;
+
+    fn reduce(
+        &self,
+        shard: usize,
+        alpha: Challenge,
+        openings: &[Vec<ShardOpening<Challenge>>],
+    ) -> Vec<Challenge>;
+
+    fn open(&self, shard: usize, index: usize) -> BatchOpening<Val, InputMmcs>;
+}
+
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+    InputMmcs: Mmcs<Val>,
+{
+    /// The shift of the coset of the LDE domain at the rows of `shard`, out of `2^log_num_shards`
+    /// shards of an LDE of height `2^log_height`.
+    fn shard_shift(&self, log_height: usize, log_num_shards: usize, shard: usize) -> Val {
+        // Row `shard * 2^(log_height - log_num_shards) + i` of the bit-reversed LDE holds the point
+        // of index `bitrev(shard) + 2^log_num_shards * bitrev(i)`.
+        self.lde_shift
+            * Val::two_adic_generator(log_height)
+                .exp_u64(reverse_bits_len(shard, log_num_shards) as u64)
+    }
+
+    /// The points of the LDE domain at the rows of `shard`, in the order of the rows.
+    fn shard_points(&self, log_height: usize, log_num_shards: usize, shard: usize) -> Vec<Val> {
+        let log_shard_height = log_height - log_num_shards;
+        let mut points = cyclic_subgroup_coset_known_order(
+            Val::two_adic_generator(log_shard_height),
+            self.shard_shift(log_height, log_num_shards, shard),
+            1 << log_shard_height,
+        )
+        .collect_vec();
+        reverse_slice_index_bits(&mut points);
+        points
+    }
+
+    /// Commit to the rows of `shard`, out of `2^log_num_shards` shards, of the LDEs that
+    /// `Pcs::commit` would commit to for the given polynomials.
+    ///
+    /// Each polynomial is given by its coefficients over its domain, i.e. the result of
+    /// `idft_batch` on its evaluations over the domain. All domains must have the same size, and
+    /// the LDEs must have at least one row per shard.
+    #[instrument(name = "commit to shard", skip_all)]
+    pub fn commit_shard(
+        &self,
+        coeffs: Vec<(TwoAdicMultiplicativeCoset<Val>, RowMajorMatrix<Val>)>,
+        log_num_shards: usize,
+        shard: usize,
+    ) -> (
+        InputMmcs::Commitment,
+        InputMmcs::ProverData<RowMajorMatrix<Val>>,
+    ) {
+        let log_n = coeffs[0].0.log_n;
+        assert!(
+            coeffs.iter().all(|(domain, _)| domain.log_n == log_n),
+            "all matrices of a sharded batch must have the same height"
+        );
+        let log_height = log_n + self.fri.log_blowup;
+        assert!(log_num_shards <= log_height, "more shards than rows");
+        assert!(shard < 1 << log_num_shards);
+        let log_shard_height = log_height - log_num_shards;
+        let shard_shift = self.shard_shift(log_height, log_num_shards, shard);
+
+        let ldes = coeffs
+            .into_iter()
+            .map(|(domain, coeffs)| {
+                assert_eq!(domain.size(), coeffs.height());
+                let shift = shard_shift / domain.shift;
+                // Reduce the polynomials modulo `X^h - shift^h`, which vanishes over the coset of
+                // the shard, so that an LDE of height `h` evaluates them.
+                let width = coeffs.width();
+                let mut reduced =
+                    RowMajorMatrix::new(vec![Val::ZERO; width << log_shard_height], width);
+                let chunk_len = reduced.values.len();
+                for (chunk, scale) in izip!(
+                    coeffs.values.chunks(chunk_len),
+                    shift.exp_power_of_2(log_shard_height).powers()
+                ) {
+                    reduced
+                        .values
+                        .par_iter_mut()
+                        .zip(chunk)
+                        .for_each(|(r, &c)| *r += scale * c);
+                }
+                self.dft
+                    .coset_dft_batch(reduced, shift)
+                    .bit_reverse_rows()
+                    .to_row_major_matrix()
+            })
+            .collect();
+
+        self.mmcs.commit(ldes)
+    }
+
+    /// Merge the commitments to the shards of a batch into the commitment `Pcs::commit` would
+    /// make to it. `dimensions` are those of the evaluations of the committed polynomials over
+    /// their domains.
+    pub fn merge_shard_commits(
+        &self,
+        dimensions: &[Dimensions],
+        shard_commits: Vec<InputMmcs::Commitment>,
+    ) -> (InputMmcs::Commitment, ShardedBatch<Val, InputMmcs>)
+    where
+        InputMmcs: ShardedMmcs<Val>,
+    {
+        let log_num_shards = log2_strict_usize(shard_commits.len());
+        let log_height = log2_strict_usize(dimensions[0].height) + self.fri.log_blowup;
+        let (commit, merged) = self.mmcs.merge_shards(shard_commits);
+        let batch = ShardedBatch {
+            merged,
+            log_num_shards,
+            log_height,
+            widths: dimensions.iter().map(|dims| dims.width).collect(),
+            _phantom: PhantomData,
+        };
+        (commit, batch)
+    }
+
+    /// The part of the rows of `shard` in the values of the polynomials of each matrix at each
+    /// of its points, which the coordinator sums over the shards.
+    ///
+    /// The values are interpolated over the rows which form a coset of the size of the domains of
+    /// the polynomials, i.e. the first `2^-log_blowup` of the rows, so the shards past those
+    /// contribute nothing.
+    #[instrument(name = "evaluate shard", skip_all)]
+    pub fn evaluate_shard<Challenge>(
+        &self,
+        data: &InputMmcs::ProverData<RowMajorMatrix<Val>>,
+        log_num_shards: usize,
+        shard: usize,
+        points: &[Vec<Challenge>],
+    ) -> Vec<Vec<Vec<Challenge>>>
+    where
+        Challenge: ExtensionField<Val>,
+    {
+        let mats = self.mmcs.get_matrices(data);
+        let shard_height = mats[0].height();
+        let log_height = log2_strict_usize(shard_height) + log_num_shards;
+        let first_row = shard * shard_height;
+        let num_rows = (1usize << (log_height - self.fri.log_blowup))
+            .saturating_sub(first_row)
+            .min(shard_height);
+        let xs = self.shard_points(log_height, log_num_shards, shard);
+
+        izip!(mats, points)
+            .map(|(mat, points_for_mat)| {
+                points_for_mat
+                    .iter()
+                    .map(|&point| {
+                        // The barycentric weight of `x` over the coset of `lde_shift` is
+                        // `x / (n lde_shift^n)`, which the coordinator factors out.
+                        let diff_invs = batch_multiplicative_inverse(
+                            &xs[..num_rows].iter().map(|&x| point - x).collect_vec(),
+                        );
+                        let mut scales = izip!(&xs, diff_invs)
+                            .map(|(&x, diff_inv)| diff_inv * x)
+                            .collect_vec();
+                        scales.resize(shard_height, Challenge::ZERO);
+                        mat.columnwise_dot_product(&scales)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The part of the rows of `shard` in the input of FRI, i.e. the sum over `openings` of the
+    /// quotients `alpha_pow_offset * (reduced_value - sum_i alpha^i p_i(X)) / (point - X)`.
+    #[instrument(name = "reduce shard", skip_all)]
+    pub fn reduce_shard<Challenge>(
+        &self,
+        data: &InputMmcs::ProverData<RowMajorMatrix<Val>>,
+        log_num_shards: usize,
+        shard: usize,
+        alpha: Challenge,
+        openings: &[Vec<ShardOpening<Challenge>>],
+    ) -> Vec<Challenge>
+    where
+        Challenge: ExtensionField<Val>,
+    {
+        let mats = self.mmcs.get_matrices(data);
+        let shard_height = mats[0].height();
+        let log_height = log2_strict_usize(shard_height) + log_num_shards;
+        let xs = self.shard_points(log_height, log_num_shards, shard);
+
+        let mut inv_denoms: LinearMap<Challenge, Vec<Challenge>> = LinearMap::new();
+        let mut reduced = vec![Challenge::ZERO; shard_height];
+        for (mat, openings_for_mat) in izip!(mats, openings) {
+            for opening in openings_for_mat {
+                let inv_denoms = inv_denoms.get_or_insert_with(opening.point, || {
+                    batch_multiplicative_inverse(
+                        &xs.iter().map(|&x| opening.point - x).collect_vec(),
+                    )
+                });
+                mat.dot_ext_powers(alpha)
+                    .zip(reduced.par_iter_mut())
+                    .zip(inv_denoms.par_iter())
+                    .for_each(|((reduced_row, ro), &inv_denom)| {
+                        *ro += opening.alpha_pow_offset
+                            * (opening.reduced_value - reduced_row)
+                            * inv_denom
+                    });
+            }
+        }
+        reduced
+    }
+
+    /// Open row `index` of the shard, for the coordinator to complete the proof with
+    /// `ShardedMmcs::extend_shard_proof`.
+    pub fn open_shard(
+        &self,
+        data: &InputMmcs::ProverData<RowMajorMatrix<Val>>,
+        index: usize,
+    ) -> BatchOpening<Val, InputMmcs> {
+        let (opened_values, opening_proof) = self.mmcs.open_batch(index, data);
+        BatchOpening {
+            opened_values,
+            opening_proof,
+        }
+    }
+
+    /// Open batches committed in shards as `Pcs::open` opens whole batches, with the help of the
+    /// workers holding their shards.
+    ///
+    /// Only the input of FRI, one extension element per row of the tallest LDE, is held here.
+    #[allow(clippy::type_complexity)]
+    #[instrument(name = "open sharded batches", skip_all)]
+    pub fn open_sharded<Challenge, Challenger, W>(
+        &self,
+        // For each round, the batch, its workers, and the points to open each matrix at.
+        rounds: Vec<(&ShardedBatch<Val, InputMmcs>, &W, Vec<Vec<Challenge>>)>,
+        challenger: &mut Challenger,
+    ) -> (
+        OpenedValues<Challenge>,
+        FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>,
+    )
+    where
+        InputMmcs: ShardedMmcs<Val>,
+        FriMmcs: Mmcs<Challenge>,
+        Challenge: TwoAdicField + ExtensionField<Val>,
+        Challenger: FieldChallenger<Val>
+            + CanObserve<FriMmcs::Commitment>
+            + GrindingChallenger<Witness = Val>,
+        W: ShardWorkers<Val, Challenge, InputMmcs>,
+    {
+        // Batch combination challenge
+        let alpha: Challenge = challenger.sample_ext_element();
+
+        let log_global_max_height = rounds
+            .iter()
+            .map(|(batch, _, _)| batch.log_height)
+            .max()
+            .unwrap()
+            .max(self.fri.log_blowup + self.fri.log_final_poly_len);
+
+        let mut all_opened_values: OpenedValues<Challenge> = vec![];
+
+        let mut reduced_openings: [_; 32] = core::array::from_fn(|_| None);
+        let mut num_reduced = [0; 32];
+
+        for (batch, workers, points) in &rounds {
+            assert_eq!(points.len(), batch.widths.len());
+            let log_height = batch.log_height;
+            let log_n = log_height - self.fri.log_blowup;
+            let shard_height = 1 << batch.log_shard_height();
+
+            // Sum the parts of the shards holding the coset the values are interpolated over, and
+            // scale them by `(z^n - lde_shift^n) / (n lde_shift^n)`.
+            let num_interpolating_shards = ((1 << log_n) / shard_height).max(1);
+            let sums = info_span!("compute opened values").in_scope(|| {
+                (1..num_interpolating_shards).fold(workers.evaluate(0, points), |sums, shard| {
+                    izip!(sums, workers.evaluate(shard, points))
+                        .map(|(l, r)| {
+                            izip!(l, r)
+                                .map(|(l, r)| izip!(l, r).map(|(l, r)| l + r).collect())
+                                .collect()
+                        })
+                        .collect()
+                })
+            });
+            let shift_pow = self.lde_shift.exp_power_of_2(log_n);
+            let denominator_inv = (Val::from_canonical_usize(1 << log_n) * shift_pow).inverse();
+            let opened_values_for_round: Vec<Vec<Vec<Challenge>>> = izip!(sums, points)
+                .map(|(sums_for_mat, points_for_mat)| {
+                    izip!(sums_for_mat, points_for_mat)
+                        .map(|(sum, &point)| {
+                            let scale = (point.exp_power_of_2(log_n) - shift_pow) * denominator_inv;
+                            sum.into_iter().map(|s| s * scale).collect()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let mut openings = vec![];
+            for (&width, points_for_mat, values_for_mat) in
+                izip!(&batch.widths, points, &opened_values_for_round)
+            {
+                let openings_for_mat: Vec<_> = izip!(points_for_mat, values_for_mat)
+                    .map(|(&point, ys)| {
+                        let opening = ShardOpening {
+                            point,
+                            alpha_pow_offset: alpha.exp_u64(num_reduced[log_height] as u64),
+                            reduced_value: dot_product(alpha.powers(), ys.iter().copied()),
+                        };
+                        num_reduced[log_height] += width;
+                        opening
+                    })
+                    .collect();
+                openings.push(openings_for_mat);
+            }
+
+            let reduced_opening_for_log_height = reduced_openings[log_height]
+                .get_or_insert_with(|| vec![Challenge::ZERO; 1 << log_height]);
+            info_span!("reduce shards").in_scope(|| {
+                for (shard, ro) in reduced_opening_for_log_height
+                    .chunks_mut(shard_height)
+                    .enumerate()
+                {
+                    let part = workers.reduce(shard, alpha, &openings);
+                    izip!(ro, part).for_each(|(ro, part)| *ro += part);
+                }
+            });
+
+            all_opened_values.push(opened_values_for_round);
+        }
+
+        let fri_input = reduced_openings.into_iter().rev().flatten().collect_vec();
+
+        let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
+            TwoAdicFriGenericConfig(PhantomData);
+
+        let fri_proof = prover::prove(&g, &self.fri, fri_input, challenger, |index| {
+            rounds
+                .iter()
+                .map(|(batch, workers, _)| {
+                    let reduced_index = index >> (log_global_max_height - batch.log_height);
+                    let shard = reduced_index >> batch.log_shard_height();
+                    let local_index = reduced_index & ((1 << batch.log_shard_height()) - 1);
+                    let opening = workers.open(shard, local_index);
+                    BatchOpening {
+                        opened_values: opening.opened_values,
+                        opening_proof: self.mmcs.extend_shard_proof(
+                            &batch.merged,
+                            shard,
+                            opening.opening_proof,
+                        ),
+                    }
+                })
+                .collect()
+        });
+
+        (all_opened_values, fri_proof)
+    }
+}
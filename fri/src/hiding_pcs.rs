@@ -2,13 +2,17 @@ use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::fmt::Debug;
 
+use itertools::izip;
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    encoded_len, HidingMmcs, HidingPcs, Mmcs, OpenedValues, OpeningProofStats, Pcs,
+    PolynomialSpace, TwoAdicMultiplicativeCoset,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::horizontally_truncated::HorizontallyTruncated;
-use p3_matrix::Matrix;
+use p3_matrix::{Dimensions, Matrix};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 use tracing::instrument;
@@ -16,8 +20,8 @@ use tracing::instrument;
 use crate::verifier::FriError;
 use crate::{BatchOpening, FriConfig, FriProof, TwoAdicFriPcs};
 
-/// A hiding FRI PCS. Both MMCSs must also be hiding; this is not enforced at compile time so it's
-/// the user's responsibility to configure.
+/// A hiding FRI PCS. Both MMCSs must also be hiding for its commitments to be, so it only
+/// implements `HidingPcs` over MMCSs implementing `HidingMmcs`.
 #[derive(Debug)]
 pub struct HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R> {
     inner: TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>,
@@ -25,7 +29,7 @@ pub struct HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R> {
     rng: RefCell<R>,
 }
 
-impl<Val, Dft, InputMmcs, FriMmcs, R> HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R> {
+impl<Val: Field, Dft, InputMmcs, FriMmcs, R> HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R> {
     pub fn new(
         dft: Dft,
         mmcs: InputMmcs,
@@ -90,6 +94,21 @@ where
         )
     }
 
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        // The random codewords are committed as extra columns.
+        let randomized = dimensions
+            .iter()
+            .map(|dim| Dimensions {
+                width: dim.width + self.num_random_codewords,
+                height: dim.height,
+            })
+            .collect::<Vec<_>>();
+        <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<Challenge, Challenger>>::prover_data_size_hint(
+            &self.inner,
+            &randomized,
+        )
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         prover_data: &'a Self::ProverData,
@@ -180,6 +199,140 @@ where
         }
         self.inner.verify(rounds, inner_proof, challenger)
     }
+
+    /// The openings of the random polynomials, which are made out of domain rather than at the
+    /// queries, are counted in `other_bytes`.
+    fn proof_stats(&self, proof: &Self::Proof) -> OpeningProofStats {
+        let (opened_values_for_rand_cws, inner_proof) = proof;
+        let mut stats = <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<
+            Challenge,
+            Challenger,
+        >>::proof_stats(&self.inner, inner_proof);
+        stats.other_bytes += encoded_len(opened_values_for_rand_cws);
+        stats
+    }
+}
+
+impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger, R> HidingPcs<Challenge, Challenger>
+    for HidingFriPcs<Val, Dft, InputMmcs, FriMmcs, R>
+where
+    Val: TwoAdicField,
+    Standard: Distribution<Val>,
+    Dft: TwoAdicSubgroupDft<Val>,
+    InputMmcs: HidingMmcs<Val>,
+    FriMmcs: HidingMmcs<Challenge>,
+    Challenge: TwoAdicField + ExtensionField<Val>,
+    Challenger:
+        FieldChallenger<Val> + CanObserve<FriMmcs::Commitment> + GrindingChallenger<Witness = Val>,
+    R: Rng + Send + Sync,
+{
+    fn commit_masked(
+        &self,
+        evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
+    ) -> (Self::Commitment, Self::ProverData, Vec<Self::Domain>) {
+        let (masked_domains, masked_evaluations): (Vec<_>, Vec<_>) = evaluations
+            .into_iter()
+            .map(|(domain, mat)| {
+                let masked_domain = double_domain(domain);
+                let masked = add_random_rows(mat, &mut *self.rng.borrow_mut());
+                (masked_domain, (masked_domain, masked))
+            })
+            .unzip();
+        let (commit, data) = <Self as Pcs<Challenge, Challenger>>::commit(self, masked_evaluations);
+        (commit, data, masked_domains)
+    }
+
+    fn commit_quotients(
+        &self,
+        quotients: Vec<(Self::Domain, RowMajorMatrix<Val>, usize)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let masked_chunks = quotients
+            .into_iter()
+            .flat_map(|(quotient_domain, evals, num_chunks)| {
+                mask_quotient_chunks(
+                    &self.inner.dft,
+                    quotient_domain.split_domains(num_chunks),
+                    quotient_domain.split_evals(num_chunks, evals),
+                    &mut *self.rng.borrow_mut(),
+                )
+            })
+            .collect();
+        <Self as Pcs<Challenge, Challenger>>::commit(self, masked_chunks)
+    }
+
+    fn masked_quotient_domains(
+        &self,
+        quotient_domain: Self::Domain,
+        num_chunks: usize,
+    ) -> Vec<Self::Domain> {
+        quotient_domain
+            .split_domains(num_chunks)
+            .into_iter()
+            .map(double_domain)
+            .collect()
+    }
+}
+
+/// The coset of twice the size of `domain`, with the same shift, which contains it.
+const fn double_domain<Val: TwoAdicField>(
+    domain: TwoAdicMultiplicativeCoset<Val>,
+) -> TwoAdicMultiplicativeCoset<Val> {
+    TwoAdicMultiplicativeCoset {
+        log_n: domain.log_n + 1,
+        shift: domain.shift,
+    }
+}
+
+/// Mask the chunks `q_i` of a quotient, evaluated over the domains `H_i`, as `q_i + h_i Z_{H_i}`,
+/// and evaluate the masked chunks over the domains of twice the size containing the `H_i`.
+///
+/// The masks are `h_i = d_i (t_i - t_{i-1})`, for uniformly random `t_i` with indices taken modulo
+/// the number of chunks, where `d_i = prod_{j != i} Z_{H_j}(x_i)` is the inverse of the weight of
+/// the `i`-th chunk in the recombination. Their recombination is then
+/// `prod_j Z_{H_j}(X) sum_i (t_i - t_{i-1})`, which is zero.
+#[instrument(level = "debug", skip_all)]
+fn mask_quotient_chunks<Val, Dft, R>(
+    dft: &Dft,
+    domains: Vec<TwoAdicMultiplicativeCoset<Val>>,
+    chunks: Vec<RowMajorMatrix<Val>>,
+    rng: &mut R,
+) -> Vec<(TwoAdicMultiplicativeCoset<Val>, RowMajorMatrix<Val>)>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+    R: Rng,
+    Standard: Distribution<Val>,
+{
+    let num_chunks = chunks.len();
+    let (height, width) = (chunks[0].height(), chunks[0].width());
+    let randoms = (0..num_chunks)
+        .map(|_| RowMajorMatrix::<Val>::rand(rng, height, width))
+        .collect::<Vec<_>>();
+    izip!(0.., &domains, chunks)
+        .map(|(i, domain, chunk)| {
+            let first_point = domain.first_point();
+            let weight_inv = domains
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, other)| other.zp_at_point(first_point))
+                .product::<Val>();
+            let previous = &randoms[(i + num_chunks - 1) % num_chunks];
+            let mask = izip!(&randoms[i].values, &previous.values)
+                .map(|(&t, &t_prev)| weight_inv * (t - t_prev))
+                .collect::<Vec<_>>();
+            // As `Z_{H_i}(X) = X^n / x_i^n - 1`, adding `h_i Z_{H_i}` subtracts the coefficients of
+            // `h_i` from those of `q_i`, and appends those of `h_i / x_i^n` above degree `n`.
+            let mut coeffs = dft.coset_idft_batch(chunk, first_point);
+            izip!(&mut coeffs.values, &mask).for_each(|(c, &h)| *c -= h);
+            let high_scale = first_point.exp_power_of_2(domain.log_n).inverse();
+            coeffs.values.extend(mask.iter().map(|&h| h * high_scale));
+            let evals = dft
+                .coset_dft_batch(coeffs, first_point)
+                .to_row_major_matrix();
+            (double_domain(*domain), evals)
+        })
+        .collect()
 }
 
 #[instrument(level = "debug", skip_all)]
@@ -210,3 +363,30 @@ where
         });
     result
 }
+
+/// Interleave the rows of `mat` with random rows.
+///
+/// The even rows lie on the original domain, so the polynomial over the domain of twice the size
+/// agrees with `mat` there, and differs from its interpolant by `h(X) Z_H(X)` for a uniformly random
+/// `h` of degree less than the height of `mat`.
+#[instrument(level = "debug", skip_all)]
+fn add_random_rows<Val, R>(mat: RowMajorMatrix<Val>, mut rng: R) -> RowMajorMatrix<Val>
+where
+    Val: Field,
+    R: Rng,
+    Standard: Distribution<Val>,
+{
+    let width = mat.width();
+    if width == 0 {
+        return mat;
+    }
+    let values = mat
+        .values
+        .chunks_exact(width)
+        .flat_map(|row| {
+            let random_row: Vec<Val> = (0..width).map(|_| rng.gen()).collect();
+            row.iter().copied().chain(random_row)
+        })
+        .collect();
+    RowMajorMatrix::new(values, width)
+}
@@ -5,15 +5,19 @@
 extern crate alloc;
 
 mod config;
+mod distributed;
 mod fold_even_odd;
 mod hiding_pcs;
 mod proof;
 pub mod prover;
+mod queries;
 mod two_adic_pcs;
 pub mod verifier;
 
 pub use config::*;
+pub use distributed::*;
 pub use fold_even_odd::*;
 pub use hiding_pcs::*;
 pub use proof::*;
+pub use queries::*;
 pub use two_adic_pcs::*;
@@ -1,6 +1,5 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use core::iter;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
@@ -11,7 +10,9 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_util::{log2_strict_usize, reverse_slice_index_bits};
 use tracing::{debug_span, info_span, instrument};
 
-use crate::{CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof, QueryProof};
+use crate::{
+    sample_query_indices, CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof, QueryProof,
+};
 
 #[instrument(name = "FRI prover", skip_all)]
 pub fn prove<G, Val, Challenge, M, Challenger>(
@@ -37,28 +38,31 @@ where
         "Inputs are not sorted in descending order of length."
     );
 
-    let log_max_height = log2_strict_usize(inputs[0].len());
-    let log_min_height = log2_strict_usize(inputs.last().unwrap().len());
-    if config.log_final_poly_len > 0 {
-        assert!(log_min_height > config.log_final_poly_len + config.log_blowup);
-    }
+    // Inputs shorter than the domain of the final polynomial are rolled into it, so the queries
+    // range over at least that domain.
+    let log_max_height =
+        log2_strict_usize(inputs[0].len()).max(config.log_blowup + config.log_final_poly_len);
 
     let commit_phase_result = commit_phase(g, config, inputs, challenger);
 
     let pow_witness = challenger.grind(config.proof_of_work_bits);
 
     let query_proofs = info_span!("query phase").in_scope(|| {
-        iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
-            .take(config.num_queries)
-            .map(|index| QueryProof {
-                input_proof: open_input(index),
-                commit_phase_openings: answer_query(
-                    config,
-                    &commit_phase_result.data,
-                    index >> g.extra_query_index_bits(),
-                ),
-            })
-            .collect()
+        sample_query_indices(
+            config,
+            challenger,
+            log_max_height + g.extra_query_index_bits(),
+        )
+        .into_iter()
+        .map(|index| QueryProof {
+            input_proof: open_input(index),
+            commit_phase_openings: answer_query(
+                config,
+                &commit_phase_result.data,
+                index >> g.extra_query_index_bits(),
+            ),
+        })
+        .collect()
     });
 
     FriProof {
@@ -89,12 +93,18 @@ where
     Challenger: FieldChallenger<Val> + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
+    let final_height = config.blowup() * config.final_poly_len();
     let mut inputs_iter = inputs.into_iter().peekable();
     let mut folded = inputs_iter.next().unwrap();
+    if folded.len() < final_height {
+        let input = folded;
+        folded = Challenge::zero_vec(final_height);
+        roll_in_lifted(&mut folded, &input);
+    }
     let mut commits = vec![];
     let mut data = vec![];
 
-    while folded.len() > config.blowup() * config.final_poly_len() {
+    while folded.len() > final_height {
         let leaves = RowMajorMatrix::new(folded, 2);
         let (commit, prover_data) = config.mmcs.commit_matrix(leaves);
         challenger.observe(commit.clone());
@@ -112,6 +122,12 @@ where
         }
     }
 
+    // Inputs which are no taller than the final polynomial's domain, e.g. those of traces of
+    // height 1, are never reached by folding, so they are rolled into the final polynomial.
+    for input in inputs_iter {
+        roll_in_lifted(&mut folded, &input);
+    }
+
     // After repeated folding steps, we end up working over a coset hJ instead of the original
     // domain. The IDFT we apply operates over a subgroup J, not hJ. This means the polynomial we
     // recover is G(x), where G(x) = F(hx), and F is the polynomial whose evaluations we actually
@@ -146,6 +162,20 @@ where
     }
 }
 
+/// Add `input` to `folded`, after lifting it to the larger domain of `folded`.
+///
+/// Both are evaluations over power-of-two subgroups in bit-reversed order, so the `i`-th point of
+/// the larger domain, raised to the power `2^s` of the ratio of their sizes, is the `(i >> s)`-th
+/// point of the smaller one. An input of degree less than `d` thus lifts to the evaluations of a
+/// polynomial of degree less than `2^s d`.
+fn roll_in_lifted<F: Field>(folded: &mut [F], input: &[F]) {
+    let log_ratio = log2_strict_usize(folded.len() / input.len());
+    folded
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, c)| *c += input[i >> log_ratio]);
+}
+
 fn answer_query<F, M>(
     config: &FriConfig<M>,
     commit_phase_commits: &[M::ProverData<RowMajorMatrix<F>>],
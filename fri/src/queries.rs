@@ -0,0 +1,32 @@
+use alloc::vec::Vec;
+
+use p3_challenger::CanSampleBits;
+
+use crate::FriConfig;
+
+/// Sample the indices of the FRI queries, each of `index_bits` uniformly distributed bits.
+///
+/// Both the prover and the verifier sample their indices with this function, so they cannot
+/// disagree on how many indices are drawn or which of them are kept.
+///
+/// If `config.deduplicate_queries` is set, only the first occurrence of each index is kept, so
+/// the number of query proofs may be less than `config.num_queries`. This costs no soundness: a
+/// repeated query checks exactly what its first occurrence already checked, so the conjectured
+/// soundness is still that of `config.num_queries` independent queries.
+pub fn sample_query_indices<M, Challenger>(
+    config: &FriConfig<M>,
+    challenger: &mut Challenger,
+    index_bits: usize,
+) -> Vec<usize>
+where
+    Challenger: CanSampleBits<usize>,
+{
+    let mut indices = Vec::with_capacity(config.num_queries);
+    for _ in 0..config.num_queries {
+        let index = challenger.sample_uniform_bits(index_bits);
+        if !(config.deduplicate_queries && indices.contains(&index)) {
+            indices.push(index);
+        }
+    }
+    indices
+}
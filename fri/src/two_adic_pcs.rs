@@ -1,3 +1,4 @@
+use alloc::borrow::Cow;
 use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
@@ -6,15 +7,20 @@ use core::marker::PhantomData;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    encoded_len, Mmcs, OpenedValues, OpeningProofStats, Pcs, PolynomialSpace, RowChunkSource,
+    TwoAdicMultiplicativeCoset,
+};
+#[cfg(debug_assertions)]
+use p3_dft::assert_low_degree;
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
-    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, ExtensionField,
-    Field, TwoAdicField,
+    batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product,
+    dot_product_base_ext, ExtensionField, Field, TwoAdicField,
 };
-use p3_interpolation::interpolate_coset;
+use p3_interpolation::BarycentricEvaluator;
 use p3_matrix::bitrev::{BitReversableMatrix, BitReversalPerm};
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{DenseMatrix, RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::{Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
 use p3_util::linear_map::LinearMap;
@@ -27,21 +33,169 @@ use crate::{prover, FriConfig, FriGenericConfig, FriProof};
 
 #[derive(Debug)]
 pub struct TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
-    dft: Dft,
-    mmcs: InputMmcs,
-    fri: FriConfig<FriMmcs>,
-    _phantom: PhantomData<Val>,
+    pub(crate) dft: Dft,
+    pub(crate) mmcs: InputMmcs,
+    pub(crate) fri: FriConfig<FriMmcs>,
+    /// The shift of the cosets over which the LDEs of committed matrices are evaluated.
+    pub(crate) lde_shift: Val,
 }
 
-impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
+impl<Val: Field, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
+    /// A PCS evaluating LDEs over cosets shifted by `Val::GENERATOR`.
     pub const fn new(dft: Dft, mmcs: InputMmcs, fri: FriConfig<FriMmcs>) -> Self {
         Self {
             dft,
             mmcs,
             fri,
-            _phantom: PhantomData,
+            lde_shift: Val::GENERATOR,
         }
     }
+
+    /// Evaluate LDEs over cosets shifted by `lde_shift` instead of `Val::GENERATOR`.
+    ///
+    /// Panics if `lde_shift` fails `check_lde_shift`. Note that `get_evaluations_on_domain` then
+    /// extrapolates over the disjoint domains of `PolynomialSpace::create_disjoint_domain`, which
+    /// are shifted by `Val::GENERATOR`, rather than reading them off the LDEs.
+    pub fn with_lde_shift(mut self, lde_shift: Val) -> Self
+    where
+        Val: TwoAdicField,
+    {
+        check_lde_shift(lde_shift).expect("unsafe LDE shift");
+        self.lde_shift = lde_shift;
+        self
+    }
+
+    pub const fn lde_shift(&self) -> Val {
+        self.lde_shift
+    }
+}
+
+/// A reason for which a shift cannot be used for the cosets of LDEs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LdeShiftError {
+    /// The shift is zero, so it does not define a coset.
+    Zero,
+    /// The shift lies in the largest two-adic subgroup, so the coset it defines meets the trace
+    /// domains, which are two-adic subgroups, and their vanishing polynomials cannot be divided
+    /// by over it.
+    InTwoAdicSubgroup,
+}
+
+/// Check that the cosets of LDEs shifted by `shift` are disjoint from every two-adic subgroup,
+/// and so from every trace domain.
+pub fn check_lde_shift<Val: TwoAdicField>(shift: Val) -> Result<(), LdeShiftError> {
+    if shift.is_zero() {
+        return Err(LdeShiftError::Zero);
+    }
+    // `shift` lies in the subgroup of order `2^TWO_ADICITY` exactly when this power is one.
+    if shift.exp_power_of_2(Val::TWO_ADICITY).is_one() {
+        return Err(LdeShiftError::InTwoAdicSubgroup);
+    }
+    Ok(())
+}
+
+/// Derive a shift for the cosets of LDEs from the transcript, so that protocols can separate the
+/// domains of different instances. The first sample which passes `check_lde_shift` is used.
+pub fn sample_lde_shift<Val, Challenger>(challenger: &mut Challenger) -> Val
+where
+    Val: TwoAdicField,
+    Challenger: FieldChallenger<Val>,
+{
+    loop {
+        let shift: Val = challenger.sample_labelled("lde shift");
+        if check_lde_shift(shift).is_ok() {
+            return shift;
+        }
+    }
+}
+
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+    InputMmcs: Mmcs<Val>,
+{
+    /// Commit to a batch of evaluations as `Pcs::commit` does, but with a blowup of
+    /// `2^log_blowup`, which must be at least that of the FRI config.
+    ///
+    /// Batches committed with different blowups can be opened together by `Pcs::open`, and their
+    /// openings are checked by `verify_with_log_blowups`. Each matrix is folded in at the layer of
+    /// its LDE height, so a larger blowup can be kept for the batches which need a larger domain,
+    /// such as a trace evaluated over the quotient domain, while the others use the cheaper rate.
+    /// FRI checks the degrees of all matrices at the rate of the FRI config, so a matrix committed
+    /// with an extra blowup of `2^k` is only known to have degree less than `2^k` times the size
+    /// of its domain.
+    pub fn commit_with_log_blowup(
+        &self,
+        evaluations: Vec<(TwoAdicMultiplicativeCoset<Val>, RowMajorMatrix<Val>)>,
+        log_blowup: usize,
+    ) -> (
+        InputMmcs::Commitment,
+        InputMmcs::ProverData<RowMajorMatrix<Val>>,
+    ) {
+        assert!(
+            log_blowup >= self.fri.log_blowup,
+            "the blowup of a batch must be at least that of FRI"
+        );
+        // The LDEs are computed as they are committed, so that the MMCS can hash each chunk of
+        // rows while it is still in cache.
+        let ldes = evaluations
+            .into_iter()
+            .map(|(domain, evals)| {
+                assert_eq!(domain.size(), evals.height());
+                Lde {
+                    dft: &self.dft,
+                    log_n: domain.log_n,
+                    evals,
+                    log_blowup,
+                    shift: self.lde_shift / domain.shift,
+                }
+            })
+            .collect();
+
+        self.mmcs.commit_chunked(ldes)
+    }
+}
+
+/// The bit-reversed LDE of a matrix, computed when it is committed.
+struct Lde<'a, Val, Dft> {
+    dft: &'a Dft,
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    log_n: usize,
+    evals: RowMajorMatrix<Val>,
+    log_blowup: usize,
+    shift: Val,
+}
+
+impl<Val, Dft> RowChunkSource<Val> for Lde<'_, Val, Dft>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+{
+    fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            width: self.evals.width(),
+            height: self.evals.height() << self.log_blowup,
+        }
+    }
+
+    fn produce<R: Send>(
+        self,
+        on_chunk: impl Fn(RowMajorMatrixView<'_, Val>) -> R + Sync,
+    ) -> (RowMajorMatrix<Val>, Vec<R>) {
+        let (lde, chunks) = self.dft.coset_lde_batch_bit_reversed_with(
+            self.evals,
+            self.log_blowup,
+            self.shift,
+            on_chunk,
+        );
+        #[cfg(debug_assertions)]
+        assert_low_degree(
+            &lde.as_view().bit_reverse_rows().to_row_major_matrix(),
+            self.log_n,
+        );
+        (lde, chunks)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -158,20 +312,18 @@ where
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
     ) -> (Self::Commitment, Self::ProverData) {
-        let ldes: Vec<_> = evaluations
-            .into_iter()
-            .map(|(domain, evals)| {
-                assert_eq!(domain.size(), evals.height());
-                let shift = Val::GENERATOR / domain.shift;
-                // Commit to the bit-reversed LDE.
-                self.dft
-                    .coset_lde_batch(evals, self.fri.log_blowup, shift)
-                    .bit_reverse_rows()
-                    .to_row_major_matrix()
-            })
-            .collect();
+        self.commit_with_log_blowup(evaluations, self.fri.log_blowup)
+    }
 
-        self.mmcs.commit(ldes)
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        let ldes = dimensions
+            .iter()
+            .map(|dim| Dimensions {
+                width: dim.width,
+                height: dim.height << self.fri.log_blowup,
+            })
+            .collect_vec();
+        self.mmcs.prover_data_size_hint(&ldes)
     }
 
     fn get_evaluations_on_domain<'a>(
@@ -180,11 +332,41 @@ where
         idx: usize,
         domain: Self::Domain,
     ) -> impl Matrix<Val> + 'a {
-        // todo: handle extrapolation for LDEs we don't have
-        assert_eq!(domain.shift, Val::GENERATOR);
         let lde = self.mmcs.get_matrices(prover_data)[idx];
-        assert!(lde.height() >= domain.size());
-        lde.split_rows(domain.size()).0.bit_reverse_rows()
+        if domain.shift == self.lde_shift && lde.height() >= domain.size() {
+            // The domain is a subgroup coset of the LDE's, so its evaluations are a prefix of the
+            // bit-reversed LDE.
+            let prefix = lde.split_rows(domain.size()).0;
+            return DenseMatrix::new(Cow::Borrowed(prefix.values), prefix.width())
+                .bit_reverse_rows();
+        }
+
+        // The domain is larger than the LDE, e.g. the quotient domain of an AIR whose quotient
+        // degree exceeds the blowup, so we extrapolate. Every batch is committed with a blowup of
+        // at least that of FRI, so the first `lde.height() >> log_blowup` rows already determine
+        // the polynomial.
+        let height = lde.height() >> self.fri.log_blowup;
+        let width = lde.width();
+        let evals = lde
+            .split_rows(height)
+            .0
+            .bit_reverse_rows()
+            .to_row_major_matrix();
+        let mut coeffs = self.dft.coset_idft_batch(evals, self.lde_shift);
+        assert!(
+            height <= domain.size()
+                || coeffs.values[domain.size() * width..]
+                    .iter()
+                    .all(Field::is_zero),
+            "the polynomial does not fit in the domain"
+        );
+        coeffs.values.resize(domain.size() * width, Val::ZERO);
+        let extrapolated = self
+            .dft
+            .coset_dft_batch(coeffs, domain.shift)
+            .bit_reverse_rows()
+            .to_row_major_matrix();
+        DenseMatrix::new(Cow::Owned(extrapolated.values), width).bit_reverse_rows()
     }
 
     fn open(
@@ -258,12 +440,18 @@ where
             .flat_map(|(mats, _)| mats)
             .collect_vec();
 
+        // FRI queries range over at least the domain of its final polynomial, even if every
+        // matrix is shorter, as for traces of height 1 with a final polynomial of degree above 0.
         let global_max_height = mats.iter().map(|m| m.height()).max().unwrap();
-        let log_global_max_height = log2_strict_usize(global_max_height);
+        let log_global_max_height = log2_strict_usize(global_max_height)
+            .max(self.fri.log_blowup + self.fri.log_final_poly_len);
 
         // For each unique opening point z, we will find the largest degree bound
         // for that point, and precompute 1/(z - X) for the largest subgroup (in bitrev order).
-        let inv_denoms = compute_inverse_denominators(&mats_and_points, Val::GENERATOR);
+        let inv_denoms = compute_inverse_denominators(&mats_and_points, self.lde_shift);
+
+        // The barycentric weights of each coset we interpolate over, computed once per height.
+        let mut evaluators = BTreeMap::new();
 
         let mut all_opened_values: OpenedValues<Challenge> = vec![];
 
@@ -284,18 +472,25 @@ where
                         info_span!("reduce matrix quotient", dims = %mat.dimensions()).entered();
 
                     // Use Barycentric interpolation to evaluate the matrix at the given point.
+                    // A batch may have been committed with a larger blowup than that of FRI, in
+                    // which case the polynomial still has degree less than `h`, so the rows of
+                    // the coset of size `h` are enough to interpolate it.
                     let ys = info_span!("compute opened values with Lagrange interpolation")
                         .in_scope(|| {
                             let h = mat.height() >> self.fri.log_blowup;
                             let (low_coset, _) = mat.split_rows(h);
                             let mut inv_denoms = inv_denoms.get(&point).unwrap()[..h].to_vec();
                             reverse_slice_index_bits(&mut inv_denoms);
-                            interpolate_coset(
-                                &BitReversalPerm::new_view(low_coset),
-                                Val::GENERATOR,
-                                point,
-                                Some(&inv_denoms),
-                            )
+                            evaluators
+                                .entry(log2_strict_usize(h))
+                                .or_insert_with(|| {
+                                    BarycentricEvaluator::new(log2_strict_usize(h), self.lde_shift)
+                                })
+                                .evaluate_with_inverse_differences(
+                                    &BitReversalPerm::new_view(low_coset),
+                                    point,
+                                    &inv_denoms,
+                                )
                         });
 
                     let alpha_pow_offset = alpha.exp_u64(num_reduced[log_height] as u64);
@@ -363,89 +558,256 @@ where
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
-        // Batch combination challenge
-        let alpha: Challenge = challenger.sample_ext_element();
+        let log_blowups = vec![self.fri.log_blowup; rounds.len()];
+        self.verify_with_log_blowups(rounds, &log_blowups, proof, challenger)
+    }
+
+    /// The verifier's hashes are counted as if every committed matrix had the height of the
+    /// tallest, as is the case for a single AIR. Its field operations are those spent combining the
+    /// opened values, folding, and evaluating the final polynomial at each query.
+    fn proof_stats(&self, proof: &Self::Proof) -> OpeningProofStats {
+        let log_max_height =
+            proof.commit_phase_commits.len() + self.fri.log_blowup + self.fri.log_final_poly_len;
+        let mut stats = OpeningProofStats {
+            commitment_bytes: encoded_len(&proof.commit_phase_commits),
+            pow_bytes: encoded_len(&proof.pow_witness),
+            ..Default::default()
+        };
+        for query_proof in &proof.query_proofs {
+            for batch_opening in &query_proof.input_proof {
+                stats.query_value_bytes += encoded_len(&batch_opening.opened_values);
+                stats.path_bytes += encoded_len(&batch_opening.opening_proof);
+                let dims = batch_opening
+                    .opened_values
+                    .iter()
+                    .map(|values| Dimensions {
+                        width: values.len(),
+                        height: 1 << log_max_height,
+                    })
+                    .collect_vec();
+                stats.verifier_hashes += self.mmcs.verification_hash_count(&dims);
+                // A multiplication and an addition to combine each value with a power of alpha.
+                stats.verifier_field_ops += 2 * batch_opening
+                    .opened_values
+                    .iter()
+                    .map(Vec::len)
+                    .sum::<usize>();
+            }
+            for (log_folded_height, step) in izip!(
+                (0..log_max_height).rev(),
+                &query_proof.commit_phase_openings
+            ) {
+                stats.query_value_bytes += encoded_len(&step.sibling_value);
+                stats.path_bytes += encoded_len(&step.opening_proof);
+                stats.verifier_hashes += self.fri.mmcs.verification_hash_count(&[Dimensions {
+                    width: 2,
+                    height: 1 << log_folded_height,
+                }]);
+                stats.verifier_field_ops += FOLD_FIELD_OPS;
+            }
+            stats.verifier_field_ops += 2 * proof.final_poly.len();
+        }
+        stats.other_bytes = encoded_len(proof) - stats.total_bytes();
+        stats
+    }
+}
+
+/// The field operations of a fold of arity 2 by the verifier: adding the reduced opening of the
+/// height, and the four additions, a multiplication and an inversion of `fold_row`.
+const FOLD_FIELD_OPS: usize = 7;
+
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+    InputMmcs: Mmcs<Val>,
+{
+    /// Verify openings as `Pcs::verify` does, for rounds whose batches were committed with
+    /// `commit_with_log_blowup`, with the blowup of each round given by `log_blowups`.
+    #[allow(clippy::type_complexity)]
+    pub fn verify_with_log_blowups<Challenge, Challenger>(
+        &self,
+        // For each round:
+        rounds: Vec<(
+            InputMmcs::Commitment,
+            // for each matrix:
+            Vec<(
+                // its domain,
+                TwoAdicMultiplicativeCoset<Val>,
+                // for each point:
+                Vec<(
+                    // the point,
+                    Challenge,
+                    // values at the point
+                    Vec<Challenge>,
+                )>,
+            )>,
+        )>,
+        log_blowups: &[usize],
+        proof: &FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>,
+        challenger: &mut Challenger,
+    ) -> Result<(), FriError<FriMmcs::Error, InputMmcs::Error>>
+    where
+        FriMmcs: Mmcs<Challenge>,
+        Challenge: TwoAdicField + ExtensionField<Val>,
+        Challenger: FieldChallenger<Val>
+            + CanObserve<FriMmcs::Commitment>
+            + GrindingChallenger<Witness = Val>,
+    {
+        assert_eq!(
+            log_blowups.len(),
+            rounds.len(),
+            "each round must have a blowup"
+        );
+        assert!(
+            log_blowups.iter().all(|&b| b >= self.fri.log_blowup),
+            "the blowup of a batch must be at least that of FRI"
+        );
+
+        // Each query opens every round.
+        if proof
+            .query_proofs
+            .iter()
+            .any(|qp| qp.input_proof.len() != rounds.len())
+        {
+            return Err(FriError::InvalidProofShape);
+        }
 
         let log_global_max_height =
             proof.commit_phase_commits.len() + self.fri.log_blowup + self.fri.log_final_poly_len;
 
+        // No LDE may be taller than the first FRI layer, which is as tall as the tallest.
+        if izip!(&rounds, log_blowups).any(|((_, mats), &log_blowup)| {
+            mats.iter().any(|(domain, _)| {
+                log2_strict_usize(domain.size()) + log_blowup > log_global_max_height
+            })
+        }) {
+            return Err(FriError::InvalidProofShape);
+        }
+
+        // Batch combination challenge
+        let alpha: Challenge = challenger.sample_ext_element();
+
         let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
             TwoAdicFriGenericConfig(PhantomData);
 
         verifier::verify(&g, &self.fri, proof, challenger, |index, input_proof| {
-            // TODO: separate this out into functions
+            verify_opened_values(
+                &self.mmcs,
+                &rounds,
+                log_blowups,
+                input_proof,
+                index,
+                log_global_max_height,
+                self.lde_shift,
+                alpha,
+            )
+        })?;
 
-            // log_height -> (alpha_pow, reduced_opening)
-            let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
+        Ok(())
+    }
+}
 
-            for (batch_opening, (batch_commit, mats)) in izip!(input_proof, &rounds) {
-                let batch_heights = mats
-                    .iter()
-                    .map(|(domain, _)| domain.size() << self.fri.log_blowup)
-                    .collect_vec();
-                let batch_dims = batch_heights
-                    .iter()
-                    // TODO: MMCS doesn't really need width; we put 0 for now.
-                    .map(|&height| Dimensions { width: 0, height })
-                    .collect_vec();
+/// Check the values opened at a FRI query against the commitments of their rounds, and reduce
+/// them against the claimed evaluations, as the verifier of `TwoAdicFriPcs` does.
+///
+/// For each round, `rounds` holds its commitment and, for each matrix, its domain and its claimed
+/// evaluations at each point, and `log_blowups` the blowup it was committed with. The openings at
+/// the query `index`, into a domain of size `2^log_global_max_height`, are combined with powers of
+/// `alpha`, in order of rounds, matrices, points and columns, into a reduced opening for each
+/// height of LDE: the sum of `alpha^i (p_i(x) - p_i(z)) / (x - z)` over the columns `p_i` of the
+/// matrices of that height. The reduced openings are returned in descending order of height, to
+/// be checked by the FRI verifier.
+///
+/// The LDEs are evaluated over cosets shifted by `lde_shift`.
+///
+/// This is exposed so that other verifiers can follow exactly the same reduction.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn verify_opened_values<Val, Challenge, InputMmcs>(
+    mmcs: &InputMmcs,
+    rounds: &[(
+        InputMmcs::Commitment,
+        Vec<(
+            TwoAdicMultiplicativeCoset<Val>,
+            Vec<(Challenge, Vec<Challenge>)>,
+        )>,
+    )],
+    log_blowups: &[usize],
+    batch_openings: &[BatchOpening<Val, InputMmcs>],
+    index: usize,
+    log_global_max_height: usize,
+    lde_shift: Val,
+    alpha: Challenge,
+) -> Result<Vec<(usize, Challenge)>, InputMmcs::Error>
+where
+    Val: TwoAdicField,
+    Challenge: TwoAdicField + ExtensionField<Val>,
+    InputMmcs: Mmcs<Val>,
+{
+    // log_height -> (alpha_pow, reduced_opening)
+    let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
 
-                let batch_max_height = batch_heights.iter().max().expect("Empty batch?");
-                let log_batch_max_height = log2_strict_usize(*batch_max_height);
-                let bits_reduced = log_global_max_height - log_batch_max_height;
-                let reduced_index = index >> bits_reduced;
-
-                self.mmcs.verify_batch(
-                    batch_commit,
-                    &batch_dims,
-                    reduced_index,
-                    &batch_opening.opened_values,
-                    &batch_opening.opening_proof,
-                )?;
-                for (mat_opening, (mat_domain, mat_points_and_values)) in
-                    izip!(&batch_opening.opened_values, mats)
-                {
-                    let log_height = log2_strict_usize(mat_domain.size()) + self.fri.log_blowup;
-
-                    let bits_reduced = log_global_max_height - log_height;
-                    let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
-
-                    // todo: this can be nicer with domain methods?
-
-                    let x = Val::GENERATOR
-                        * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
-
-                    let (alpha_pow, ro) = reduced_openings
-                        .entry(log_height)
-                        .or_insert((Challenge::ONE, Challenge::ZERO));
-
-                    for (z, ps_at_z) in mat_points_and_values {
-                        for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
-                            let quotient = (-p_at_z + p_at_x) / (-*z + x);
-                            *ro += *alpha_pow * quotient;
-                            *alpha_pow *= alpha;
-                        }
-                    }
-                }
-            }
+    for (batch_opening, (batch_commit, mats), &log_blowup) in
+        izip!(batch_openings, rounds, log_blowups)
+    {
+        let batch_heights = mats
+            .iter()
+            .map(|(domain, _)| domain.size() << log_blowup)
+            .collect_vec();
+        let batch_dims = batch_heights
+            .iter()
+            // TODO: MMCS doesn't really need width; we put 0 for now.
+            .map(|&height| Dimensions { width: 0, height })
+            .collect_vec();
 
-            // `reduced_openings` would have a log_height = log_blowup entry only if there was a
-            // trace matrix of height 1. In this case the reduced opening can be skipped as it will
-            // not be checked against any commit phase commit.
-            if let Some((_alpha_pow, ro)) = reduced_openings.remove(&self.fri.log_blowup) {
-                debug_assert!(ro.is_zero());
+        let batch_max_height = batch_heights.iter().max().expect("Empty batch?");
+        let log_batch_max_height = log2_strict_usize(*batch_max_height);
+        let bits_reduced = log_global_max_height - log_batch_max_height;
+        let reduced_index = index >> bits_reduced;
+
+        mmcs.verify_batch(
+            batch_commit,
+            &batch_dims,
+            reduced_index,
+            &batch_opening.opened_values,
+            &batch_opening.opening_proof,
+        )?;
+        for (mat_opening, (mat_domain, mat_points_and_values)) in
+            izip!(&batch_opening.opened_values, mats)
+        {
+            let log_height = log2_strict_usize(mat_domain.size()) + log_blowup;
+
+            let bits_reduced = log_global_max_height - log_height;
+            let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
+
+            // todo: this can be nicer with domain methods?
+
+            let x =
+                lde_shift * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
+
+            let (alpha_pow, ro) = reduced_openings
+                .entry(log_height)
+                .or_insert((Challenge::ONE, Challenge::ZERO));
+
+            // The sum of `alpha^i (p_i(x) - p_i(z)) / (x - z)` over the columns, for each point
+            // `z`, with `p_i(x)` combined once for all points.
+            let alpha_powers = alpha.powers().take(mat_opening.len()).collect_vec();
+            let alpha_pow_width = alpha.exp_u64(mat_opening.len() as u64);
+            let reduced_p_at_x: Challenge = dot_product_base_ext(mat_opening, &alpha_powers);
+            for (z, ps_at_z) in mat_points_and_values {
+                let reduced_p_at_z: Challenge =
+                    dot_product(alpha_powers.iter().copied(), ps_at_z.iter().copied());
+                *ro += *alpha_pow * (reduced_p_at_x - reduced_p_at_z) / (-*z + x);
+                *alpha_pow *= alpha_pow_width;
             }
-
-            // Return reduced openings descending by log_height.
-            Ok(reduced_openings
-                .into_iter()
-                .rev()
-                .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
-                .collect())
-        })
-        .expect("fri err");
-
-        Ok(())
+        }
     }
+
+    // Return reduced openings descending by log_height.
+    Ok(reduced_openings
+        .into_iter()
+        .rev()
+        .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
+        .collect())
 }
 
 #[instrument(skip_all)]
@@ -8,7 +8,7 @@ use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
 use p3_util::reverse_bits_len;
 
-use crate::{CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof};
+use crate::{sample_query_indices, CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof};
 
 #[derive(Debug)]
 pub enum FriError<CommitMmcsErr, InputError> {
@@ -48,7 +48,11 @@ where
         .iter()
         .for_each(|x| challenger.observe_ext_element(*x));
 
-    if proof.query_proofs.len() != config.num_queries {
+    if proof
+        .query_proofs
+        .iter()
+        .any(|qp| qp.commit_phase_openings.len() != proof.commit_phase_commits.len())
+    {
         return Err(FriError::InvalidProofShape);
     }
 
@@ -60,8 +64,17 @@ where
     let log_max_height =
         proof.commit_phase_commits.len() + config.log_blowup + config.log_final_poly_len;
 
-    for qp in &proof.query_proofs {
-        let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
+    let indices = sample_query_indices(
+        config,
+        challenger,
+        log_max_height + g.extra_query_index_bits(),
+    );
+    // With deduplication, the number of queries depends on the indices sampled.
+    if proof.query_proofs.len() != indices.len() {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    for (qp, index) in proof.query_proofs.iter().zip(indices) {
         let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
 
         debug_assert!(
@@ -165,10 +178,12 @@ where
         "index was {}",
         index,
     );
-    debug_assert!(
-        ro_iter.next().is_none(),
-        "verifier reduced_openings were not in descending order?"
-    );
+
+    // The remaining inputs are no taller than the final polynomial's domain, and are rolled into
+    // it, as the prover does. The index of each into its own domain was already accounted for.
+    for (_, ro) in ro_iter {
+        folded_eval += ro;
+    }
 
     Ok(folded_eval)
 }
@@ -1,13 +1,14 @@
 use core::cmp::Reverse;
 use std::marker::PhantomData;
 
+use itertools::Itertools;
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
 use p3_challenger::{CanSampleBits, DuplexChallenger, FieldChallenger};
 use p3_commit::ExtensionMmcs;
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{Field, FieldAlgebra};
-use p3_fri::{prover, verifier, FriConfig, TwoAdicFriGenericConfig};
+use p3_fri::{prover, sample_query_indices, verifier, FriConfig, TwoAdicFriGenericConfig};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::Matrix;
@@ -29,7 +30,11 @@ type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
 type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
 type MyFriConfig = FriConfig<ChallengeMmcs>;
 
-fn get_ldt_for_testing<R: Rng>(rng: &mut R, log_final_poly_len: usize) -> (Perm, MyFriConfig) {
+fn get_ldt_for_testing<R: Rng>(
+    rng: &mut R,
+    log_final_poly_len: usize,
+    deduplicate_queries: bool,
+) -> (Perm, MyFriConfig) {
     let perm = Perm::new_from_rng_128(rng);
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
@@ -39,13 +44,14 @@ fn get_ldt_for_testing<R: Rng>(rng: &mut R, log_final_poly_len: usize) -> (Perm,
         log_final_poly_len,
         num_queries: 10,
         proof_of_work_bits: 8,
+        deduplicate_queries,
         mmcs,
     };
     (perm, fri_config)
 }
 
-fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize) {
-    let (perm, fc) = get_ldt_for_testing(rng, log_final_poly_len);
+fn do_test_fri_ldt<R: Rng>(rng: &mut R, log_final_poly_len: usize, deduplicate_queries: bool) {
+    let (perm, fc) = get_ldt_for_testing(rng, log_final_poly_len, deduplicate_queries);
     let dft = Radix2Dit::default();
 
     let shift = Val::GENERATOR;
@@ -132,10 +138,36 @@ fn test_fri_ldt() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i as u64);
-        do_test_fri_ldt(&mut rng, i + 1);
+        do_test_fri_ldt(&mut rng, i + 1, false);
     }
 }
 
+#[test]
+fn test_fri_ldt_deduplicated_queries() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    do_test_fri_ldt(&mut rng, 1, true);
+}
+
+#[test]
+fn test_sample_query_indices() {
+    let perm = Perm::new_from_rng_128(&mut ChaCha20Rng::seed_from_u64(0));
+    let config = |deduplicate_queries| FriConfig {
+        log_blowup: 1,
+        log_final_poly_len: 0,
+        num_queries: 10,
+        proof_of_work_bits: 0,
+        deduplicate_queries,
+        mmcs: (),
+    };
+
+    // With only 4 possible indices, 10 queries must repeat some of them.
+    let all = sample_query_indices(&config(false), &mut Challenger::new(perm.clone()), 2);
+    let deduplicated = sample_query_indices(&config(true), &mut Challenger::new(perm), 2);
+    assert_eq!(all.len(), 10);
+    assert!(deduplicated.len() <= 4);
+    assert_eq!(deduplicated, all.into_iter().unique().collect::<Vec<_>>());
+}
+
 // This test is expected to panic because the polynomial degree is less than the final_poly_degree in the config.
 #[test]
 #[should_panic]
@@ -143,6 +175,6 @@ fn test_fri_ldt_should_panic() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i);
-        do_test_fri_ldt(&mut rng, 5);
+        do_test_fri_ldt(&mut rng, 5, false);
     }
 }
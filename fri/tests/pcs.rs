@@ -1,12 +1,21 @@
 use itertools::{izip, Itertools};
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger};
-use p3_commit::{ExtensionMmcs, Pcs, PolynomialSpace};
-use p3_dft::Radix2DitParallel;
+use p3_challenger::{
+    CanObserve, CanSampleBits, DuplexChallenger, FieldChallenger, GrindingChallenger,
+};
+use p3_commit::{
+    get_chunked, put_chunked, ChunkStoreError, ExtensionMmcs, MemoryChunkStore, OpeningSchedule,
+    Pcs, PolynomialSpace, ScheduledOpeningError,
+};
+use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
-use p3_field::{ExtensionField, Field};
-use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_field::{ExtensionField, Field, FieldAlgebra, TwoAdicField};
+use p3_fri::{
+    check_lde_shift, sample_lde_shift, verify_opened_values, BatchOpening, FriConfig,
+    LdeShiftError, ShardOpening, ShardWorkers, TwoAdicFriPcs,
+};
 use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
 use p3_merkle_tree::MerkleTreeMmcs;
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
 use rand::distributions::{Distribution, Standard};
@@ -181,6 +190,7 @@ mod babybear_fri_pcs {
             log_final_poly_len: 0,
             num_queries: 10,
             proof_of_work_bits: 8,
+            deduplicate_queries: false,
             mmcs: challenge_mmcs,
         };
 
@@ -194,11 +204,464 @@ mod babybear_fri_pcs {
     mod blowup_2 {
         make_tests_for_pcs!(super::get_pcs(2));
     }
+
+    /// Commit each round with its own blowup, open them together, and verify the openings with
+    /// the claimed blowups of the rounds.
+    fn do_test_mixed_blowups(
+        (pcs, challenger): &(MyPcs, Challenger),
+        rounds: &[(usize, &[usize])],
+        claimed_log_blowups: &[usize],
+    ) -> Result<(), <MyPcs as Pcs<Challenge, Challenger>>::Error> {
+        let mut rng = seeded_rng();
+        let mut p_challenger = challenger.clone();
+
+        let domains_and_polys_by_round = rounds
+            .iter()
+            .map(|(_, log_degrees)| {
+                log_degrees
+                    .iter()
+                    .map(|&log_degree| {
+                        let d = 1 << log_degree;
+                        (
+                            <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(
+                                pcs, d,
+                            ),
+                            RowMajorMatrix::<Val>::rand(&mut rng, d, 5),
+                        )
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let (commits_by_round, data_by_round): (Vec<_>, Vec<_>) =
+            izip!(rounds, &domains_and_polys_by_round)
+                .map(|(&(log_blowup, _), domains_and_polys)| {
+                    pcs.commit_with_log_blowup(domains_and_polys.clone(), log_blowup)
+                })
+                .unzip();
+        p_challenger.observe_slice(&commits_by_round);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+
+        let data_and_points = data_by_round
+            .iter()
+            .zip(rounds)
+            .map(|(data, (_, log_degrees))| (data, vec![vec![zeta]; log_degrees.len()]))
+            .collect();
+        let (opening_by_round, proof) = pcs.open(data_and_points, &mut p_challenger);
+
+        let mut v_challenger = challenger.clone();
+        v_challenger.observe_slice(&commits_by_round);
+        let verifier_zeta: Challenge = v_challenger.sample_ext_element();
+        assert_eq!(verifier_zeta, zeta);
+
+        let commits_and_claims_by_round = izip!(
+            commits_by_round,
+            domains_and_polys_by_round,
+            opening_by_round
+        )
+        .map(|(commit, domains_and_polys, openings)| {
+            let claims = domains_and_polys
+                .iter()
+                .zip(openings)
+                .map(|((domain, _), mat_openings)| (*domain, vec![(zeta, mat_openings[0].clone())]))
+                .collect_vec();
+            (commit, claims)
+        })
+        .collect_vec();
+        pcs.verify_with_log_blowups(
+            commits_and_claims_by_round,
+            claimed_log_blowups,
+            &proof,
+            &mut v_challenger,
+        )
+    }
+
+    #[test]
+    fn mixed_blowups() {
+        let p = get_pcs(1);
+        do_test_mixed_blowups(&p, &[(2, &[3, 4]), (1, &[3, 4])], &[2, 1]).unwrap();
+        do_test_mixed_blowups(&p, &[(1, &[5]), (2, &[4]), (3, &[3])], &[1, 2, 3]).unwrap();
+        do_test_mixed_blowups(&p, &[(1, &[4, 2]), (2, &[4, 2])], &[1, 2]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn mixed_blowups_wrong_claimed_blowup() {
+        let p = get_pcs(1);
+        do_test_mixed_blowups(&p, &[(2, &[4]), (1, &[4])], &[1, 1]).unwrap();
+    }
+
+    #[test]
+    fn opened_values_follow_verifier_reduction() {
+        let (pcs, challenger) = get_pcs(1);
+        let perm = Perm::new_from_rng_128(&mut seeded_rng());
+        let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+        let mut rng = seeded_rng();
+
+        let domains_and_polys = [3, 4]
+            .map(|log_degree| {
+                let d = 1 << log_degree;
+                (
+                    <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, d),
+                    RowMajorMatrix::<Val>::rand(&mut rng, d, 5),
+                )
+            })
+            .to_vec();
+        let (commit, data) =
+            <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, domains_and_polys.clone());
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe(commit);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let (openings, proof) = pcs.open(vec![(&data, vec![vec![zeta]; 2])], &mut p_challenger);
+
+        // Replay the transcript of the verifier up to the first query.
+        let mut v_challenger = challenger.clone();
+        v_challenger.observe(commit);
+        assert_eq!(v_challenger.sample_ext_element::<Challenge>(), zeta);
+        let alpha: Challenge = v_challenger.sample_ext_element();
+        for comm in &proof.commit_phase_commits {
+            v_challenger.observe(*comm);
+            let _beta: Challenge = v_challenger.sample_ext_element();
+        }
+        for &x in &proof.final_poly {
+            v_challenger.observe_ext_element(x);
+        }
+        assert!(v_challenger.check_witness(8, proof.pow_witness));
+        let log_global_max_height = proof.commit_phase_commits.len() + 1;
+        let index = v_challenger.sample_uniform_bits(log_global_max_height);
+
+        let claims = domains_and_polys
+            .iter()
+            .zip(&openings[0])
+            .map(|((domain, _), mat_openings)| (*domain, vec![(zeta, mat_openings[0].clone())]))
+            .collect_vec();
+        let rounds = vec![(commit, claims)];
+        let mut input_proof = proof.query_proofs[0].input_proof.clone();
+
+        let reduced = verify_opened_values(
+            &val_mmcs,
+            &rounds,
+            &[1],
+            &input_proof,
+            index,
+            log_global_max_height,
+            Val::GENERATOR,
+            alpha,
+        )
+        .unwrap();
+        assert_eq!(
+            reduced
+                .iter()
+                .map(|&(log_height, _)| log_height)
+                .collect_vec(),
+            [5, 4]
+        );
+
+        // The opened values are bound to the commitment.
+        input_proof[0].opened_values[0][0] += Val::ONE;
+        assert!(verify_opened_values(
+            &val_mmcs,
+            &rounds,
+            &[1],
+            &input_proof,
+            index,
+            log_global_max_height,
+            Val::GENERATOR,
+            alpha,
+        )
+        .is_err());
+    }
+
+    /// Commit on one "machine", move the prover data through a chunk store, and open on another.
+    #[test]
+    fn open_prover_data_from_chunk_store() {
+        let (pcs, challenger) = get_pcs(1);
+        let domain = <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << 5);
+        let evaluations = RowMajorMatrix::<Val>::rand(&mut seeded_rng(), 1 << 5, 7);
+        let (commit, data) =
+            <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, vec![(domain, evaluations)]);
+
+        let store = MemoryChunkStore::new();
+        let num_chunks = put_chunked(&store, "trace", &data, 1 << 10).unwrap();
+        assert!(num_chunks > 1);
+        assert_eq!(store.num_chunks(), num_chunks + 1);
+        drop(data);
+        let data = get_chunked(&store, "trace").unwrap();
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe(commit);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let (opened_values, proof) = pcs.open(vec![(&data, vec![vec![zeta]])], &mut p_challenger);
+
+        let mut v_challenger = challenger;
+        v_challenger.observe(commit);
+        assert_eq!(v_challenger.sample_ext_element::<Challenge>(), zeta);
+        let claims = vec![(domain, vec![(zeta, opened_values[0][0][0].clone())])];
+        pcs.verify(vec![(commit, claims)], &proof, &mut v_challenger)
+            .expect("verification failed");
+
+        assert!(matches!(
+            get_chunked::<_, <MyPcs as Pcs<Challenge, Challenger>>::ProverData>(&store, "quotient"),
+            Err(ChunkStoreError::NotFound(_))
+        ));
+    }
+
+    /// Workers holding their shards in memory, standing in for separate machines.
+    struct LocalWorkers<'a> {
+        pcs: &'a MyPcs,
+        log_num_shards: usize,
+        shards: Vec<<MyPcs as Pcs<Challenge, Challenger>>::ProverData>,
+    }
+
+    impl ShardWorkers<Val, Challenge, ValMmcs> for LocalWorkers<'_> {
+        fn evaluate(&self, shard: usize, points: &[Vec<Challenge>]) -> Vec<Vec<Vec<Challenge>>> {
+            self.pcs
+                .evaluate_shard(&self.shards[shard], self.log_num_shards, shard, points)
+        }
+
+        fn reduce(
+            &self,
+            shard: usize,
+            alpha: Challenge,
+            openings: &[Vec<ShardOpening<Challenge>>],
+        ) -> Vec<Challenge> {
+            self.pcs.reduce_shard(
+                &self.shards[shard],
+                self.log_num_shards,
+                shard,
+                alpha,
+                openings,
+            )
+        }
+
+        fn open(&self, shard: usize, index: usize) -> BatchOpening<Val, ValMmcs> {
+            self.pcs.open_shard(&self.shards[shard], index)
+        }
+    }
+
+    /// Commit to a trace and to a batch over a shifted domain in shards, open them with the help
+    /// of the workers, and check the result against committing and opening the whole batches.
+    fn do_test_sharded_commit(log_blowup: usize, log_n: usize, log_num_shards: usize) {
+        let (pcs, challenger) = get_pcs(log_blowup);
+        let mut rng = seeded_rng();
+        let domain =
+            <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, 1 << log_n);
+        let rounds = [
+            vec![(domain, 3), (domain, 5)],
+            vec![(domain.create_disjoint_domain(1 << log_n), 2)],
+        ]
+        .map(|mats| {
+            mats.into_iter()
+                .map(|(domain, width)| {
+                    (
+                        domain,
+                        RowMajorMatrix::<Val>::rand(&mut rng, 1 << log_n, width),
+                    )
+                })
+                .collect_vec()
+        });
+
+        let (commits, data): (Vec<_>, Vec<_>) = rounds
+            .iter()
+            .map(|evals| <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, evals.clone()))
+            .unzip();
+
+        let (sharded_commits, batches): (Vec<_>, Vec<_>) = rounds
+            .iter()
+            .map(|evals| {
+                let (shard_commits, shards): (Vec<_>, Vec<_>) = (0..1 << log_num_shards)
+                    .map(|shard| {
+                        let coeffs = evals
+                            .iter()
+                            .map(|(domain, evals)| {
+                                (*domain, Dft::default().idft_batch(evals.clone()))
+                            })
+                            .collect();
+                        pcs.commit_shard(coeffs, log_num_shards, shard)
+                    })
+                    .unzip();
+                let dimensions = evals
+                    .iter()
+                    .map(|(_, evals)| evals.dimensions())
+                    .collect_vec();
+                let (commit, batch) = pcs.merge_shard_commits(&dimensions, shard_commits);
+                let workers = LocalWorkers {
+                    pcs: &pcs,
+                    log_num_shards,
+                    shards,
+                };
+                (commit, (batch, workers))
+            })
+            .unzip();
+        assert_eq!(sharded_commits, commits);
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe_slice(&commits);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let zeta_next = domain.next_point(zeta).unwrap();
+        let points = [vec![vec![zeta, zeta_next], vec![zeta]], vec![vec![zeta]]];
+
+        let (opened_values, _) = pcs.open(
+            izip!(&data, points.clone()).collect(),
+            &mut p_challenger.clone(),
+        );
+        let (sharded_opened_values, proof) = pcs.open_sharded(
+            izip!(&batches, points.clone())
+                .map(|((batch, workers), points)| (batch, workers, points))
+                .collect(),
+            &mut p_challenger,
+        );
+        assert_eq!(sharded_opened_values, opened_values);
+
+        let mut v_challenger = challenger;
+        v_challenger.observe_slice(&commits);
+        assert_eq!(v_challenger.sample_ext_element::<Challenge>(), zeta);
+        let claims = izip!(&commits, &rounds, &points, &opened_values)
+            .map(|(&commit, evals, points, values)| {
+                let mats = izip!(evals, points, values)
+                    .map(|((domain, _), points, values)| {
+                        (*domain, izip!(points.clone(), values.clone()).collect())
+                    })
+                    .collect();
+                (commit, mats)
+            })
+            .collect();
+        pcs.verify(claims, &proof, &mut v_challenger)
+            .expect("verification failed");
+    }
+
+    #[test]
+    fn sharded_commit() {
+        do_test_sharded_commit(1, 5, 2);
+    }
+
+    #[test]
+    fn sharded_commit_single_shard() {
+        do_test_sharded_commit(1, 4, 0);
+    }
+
+    #[test]
+    fn sharded_commit_with_row_shards() {
+        do_test_sharded_commit(2, 3, 5);
+    }
+
+    #[test]
+    fn lde_shift_from_transcript() {
+        let (pcs, challenger) = get_pcs(1);
+        let shift: Val = sample_lde_shift(&mut challenger.clone());
+        assert_eq!(shift, sample_lde_shift(&mut challenger.clone()));
+        assert_ne!(shift, Val::GENERATOR);
+
+        let p = (pcs.with_lde_shift(shift), challenger);
+        assert_eq!(p.0.lde_shift(), shift);
+        do_test_fri_pcs(&p, &[&[3, 4], &[2]]);
+        do_test_fri_pcs(&p, &[&[5], &[3, 3]]);
+    }
+
+    #[test]
+    fn lde_shift_must_be_disjoint_from_trace_domains() {
+        assert_eq!(check_lde_shift(Val::GENERATOR), Ok(()));
+        assert_eq!(check_lde_shift(Val::ZERO), Err(LdeShiftError::Zero));
+        assert_eq!(
+            check_lde_shift(Val::ONE),
+            Err(LdeShiftError::InTwoAdicSubgroup)
+        );
+        assert_eq!(
+            check_lde_shift(Val::two_adic_generator(5)),
+            Err(LdeShiftError::InTwoAdicSubgroup)
+        );
+        assert_eq!(
+            check_lde_shift(Val::GENERATOR * Val::two_adic_generator(Val::TWO_ADICITY)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unsafe LDE shift")]
+    fn lde_shift_in_trace_domain_is_rejected() {
+        let (pcs, _) = get_pcs(1);
+        let _ = pcs.with_lde_shift(Val::two_adic_generator(3));
+    }
+
+    #[test]
+    fn scheduled_openings() {
+        let (pcs, challenger) = get_pcs(1);
+        let mut rng = seeded_rng();
+        let schedules = [
+            (3, OpeningSchedule::ZETA_AND_NEXT),
+            // Over a domain of size one, the next point is zeta itself.
+            (0, OpeningSchedule::ZETA_AND_NEXT),
+            (4, OpeningSchedule::new("zeta and two next", &[0, 2])),
+            (3, OpeningSchedule::ZETA),
+        ];
+        let domains_and_polys = schedules
+            .iter()
+            .map(|&(log_degree, _)| {
+                let d = 1 << log_degree;
+                (
+                    <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, d),
+                    RowMajorMatrix::<Val>::rand(&mut rng, d, 3),
+                )
+            })
+            .collect_vec();
+        let (commit, data) =
+            <MyPcs as Pcs<Challenge, Challenger>>::commit(&pcs, domains_and_polys.clone());
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe(commit);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let mat_schedules = domains_and_polys
+            .iter()
+            .zip(&schedules)
+            .map(|((domain, _), &(_, schedule))| (*domain, schedule))
+            .collect_vec();
+        let (opened_values, proof) =
+            pcs.open_scheduled(vec![(&data, mat_schedules)], zeta, &mut p_challenger);
+        for (values, &(_, schedule)) in opened_values[0].iter().zip(&schedules) {
+            assert_eq!(values.len(), schedule.shifts.len());
+        }
+        assert_eq!(opened_values[0][1][0], opened_values[0][1][1]);
+
+        let claims = |opened_values: &Vec<Vec<Vec<Challenge>>>| {
+            let mats = domains_and_polys
+                .iter()
+                .zip(&schedules)
+                .zip(opened_values)
+                .map(|(((domain, _), &(_, schedule)), values)| (*domain, schedule, values.clone()))
+                .collect_vec();
+            vec![(commit, mats)]
+        };
+        let verify = |opened_values: &Vec<Vec<Vec<Challenge>>>| {
+            let mut v_challenger = challenger.clone();
+            v_challenger.observe(commit);
+            let zeta: Challenge = v_challenger.sample_ext_element();
+            pcs.verify_scheduled(claims(opened_values), zeta, &proof, &mut v_challenger)
+        };
+        verify(&opened_values[0]).unwrap();
+
+        let mut inconsistent = opened_values[0].clone();
+        inconsistent[1][1][0] += Challenge::ONE;
+        assert!(matches!(
+            verify(&inconsistent),
+            Err(ScheduledOpeningError::InconsistentDuplicate {
+                round: 0,
+                matrix: 1
+            })
+        ));
+
+        let mut missing = opened_values[0].clone();
+        missing[2].pop();
+        assert!(matches!(
+            verify(&missing),
+            Err(ScheduledOpeningError::WrongNumberOfPoints {
+                round: 0,
+                matrix: 2
+            })
+        ));
+    }
 }
 
 mod m31_fri_pcs {
-    use std::marker::PhantomData;
-
     use p3_challenger::{HashChallenger, SerializingChallenger32};
     use p3_circle::CirclePcs;
     use p3_keccak::Keccak256Hash;
@@ -234,13 +697,10 @@ mod m31_fri_pcs {
             log_final_poly_len: 0,
             num_queries: 10,
             proof_of_work_bits: 8,
+            deduplicate_queries: false,
             mmcs: challenge_mmcs,
         };
-        let pcs = Pcs {
-            mmcs: val_mmcs,
-            fri_config,
-            _phantom: PhantomData,
-        };
+        let pcs = Pcs::new(val_mmcs, fri_config);
         (pcs, Challenger::from_hasher(vec![], byte_hash))
     }
 
@@ -251,3 +711,201 @@ mod m31_fri_pcs {
         make_tests_for_pcs!(super::get_pcs(2));
     }
 }
+
+mod babybear_hiding_fri_pcs {
+    use p3_commit::{HidingPcs, TwoAdicMultiplicativeCoset};
+    use p3_fri::HidingFriPcs;
+    use p3_interpolation::interpolate_coset;
+    use p3_matrix::Matrix;
+    use p3_merkle_tree::MerkleTreeHidingMmcs;
+
+    use super::*;
+
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+
+    type ValMmcs = MerkleTreeHidingMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        MyHash,
+        MyCompress,
+        ChaCha20Rng,
+        8,
+        4,
+    >;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+
+    type Dft = Radix2DitParallel<Val>;
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+    type MyPcs = HidingFriPcs<Val, Dft, ValMmcs, ChallengeMmcs, ChaCha20Rng>;
+
+    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+        let perm = Perm::new_from_rng_128(&mut seeded_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm.clone());
+
+        let val_mmcs = ValMmcs::new(hash, compress, ChaCha20Rng::seed_from_u64(1));
+        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+        let fri_config = FriConfig {
+            log_blowup,
+            log_final_poly_len: 0,
+            num_queries: 10,
+            proof_of_work_bits: 8,
+            deduplicate_queries: false,
+            mmcs: challenge_mmcs,
+        };
+
+        let pcs = MyPcs::new(
+            Dft::default(),
+            val_mmcs,
+            fri_config,
+            4,
+            ChaCha20Rng::seed_from_u64(2),
+        );
+        (pcs, Challenger::new(perm.clone()))
+    }
+
+    /// Commit to masked polynomials, check their openings, and verify them over the masked domains,
+    /// or over the domains of the committed evaluations.
+    fn do_test_masked_commitment(verify_over_masked_domains: bool) {
+        let (pcs, challenger) = get_pcs(1);
+        let mut rng = seeded_rng();
+
+        let domains_and_polys = [3, 4]
+            .map(|log_degree| {
+                let d = 1 << log_degree;
+                (
+                    <MyPcs as Pcs<Challenge, Challenger>>::natural_domain_for_degree(&pcs, d),
+                    RowMajorMatrix::<Val>::rand(&mut rng, d, 5),
+                )
+            })
+            .to_vec();
+        let (commit, data, masked_domains) =
+            <MyPcs as HidingPcs<Challenge, Challenger>>::commit_masked(
+                &pcs,
+                domains_and_polys.clone(),
+            );
+        for ((domain, _), masked_domain) in domains_and_polys.iter().zip(&masked_domains) {
+            assert_eq!(masked_domain.log_n, domain.log_n + 1);
+            assert_eq!(masked_domain.shift, domain.shift);
+        }
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe(commit);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        // Open each polynomial at zeta, and at the second point of the domain of its evaluations.
+        let points = domains_and_polys
+            .iter()
+            .map(|(domain, _)| {
+                let g = domain.next_point(domain.first_point()).unwrap();
+                vec![zeta, Challenge::from(g)]
+            })
+            .collect_vec();
+        let (openings, proof) = pcs.open(vec![(&data, points.clone())], &mut p_challenger);
+
+        for ((domain, mat), mat_openings) in domains_and_polys.iter().zip(&openings[0]) {
+            // There, the masked polynomial agrees with the committed evaluations.
+            let row = mat.row(1).map(Challenge::from).collect_vec();
+            assert_eq!(mat_openings[1], row);
+            // Elsewhere, it does not.
+            let unmasked: Vec<Challenge> = interpolate_coset(mat, domain.shift, zeta, None);
+            assert_ne!(mat_openings[0], unmasked);
+        }
+
+        let domains = if verify_over_masked_domains {
+            masked_domains
+        } else {
+            domains_and_polys
+                .iter()
+                .map(|(domain, _)| *domain)
+                .collect()
+        };
+        let claims = izip!(domains, points, openings.into_iter().next().unwrap())
+            .map(|(domain, points, values)| (domain, points.into_iter().zip(values).collect()))
+            .collect();
+        let mut v_challenger = challenger.clone();
+        v_challenger.observe(commit);
+        assert_eq!(v_challenger.sample_ext_element::<Challenge>(), zeta);
+        pcs.verify(vec![(commit, claims)], &proof, &mut v_challenger)
+            .unwrap();
+    }
+
+    #[test]
+    fn masked_commitment() {
+        do_test_masked_commitment(true);
+    }
+
+    /// The masked polynomials have twice the degree of the committed evaluations.
+    #[test]
+    #[should_panic]
+    fn masked_commitment_over_unmasked_domains() {
+        do_test_masked_commitment(false);
+    }
+
+    /// The masks of the chunks of a quotient hide each chunk, but cancel when they are recombined.
+    #[test]
+    fn masked_quotient_chunks_recombine() {
+        let (pcs, challenger) = get_pcs(1);
+        let mut rng = seeded_rng();
+
+        let num_chunks = 4;
+        let quotient_domain = TwoAdicMultiplicativeCoset {
+            log_n: 5,
+            shift: Val::GENERATOR,
+        };
+        let evals = RowMajorMatrix::<Val>::rand(&mut rng, quotient_domain.size(), 2);
+        let (commit, data) = <MyPcs as HidingPcs<Challenge, Challenger>>::commit_quotients(
+            &pcs,
+            vec![(quotient_domain, evals.clone(), num_chunks)],
+        );
+        let masked_domains = <MyPcs as HidingPcs<Challenge, Challenger>>::masked_quotient_domains(
+            &pcs,
+            quotient_domain,
+            num_chunks,
+        );
+
+        let mut p_challenger = challenger.clone();
+        p_challenger.observe(commit);
+        let zeta: Challenge = p_challenger.sample_ext_element();
+        let points = vec![vec![zeta]; num_chunks];
+        let (openings, proof) = pcs.open(vec![(&data, points)], &mut p_challenger);
+        let chunk_openings = openings[0].iter().map(|o| o[0].clone()).collect_vec();
+
+        let chunk_domains = quotient_domain.split_domains(num_chunks);
+        let chunks = quotient_domain.split_evals(num_chunks, evals.clone());
+        let mut recombined = vec![Challenge::ZERO; evals.width()];
+        for (i, (domain, chunk, opening)) in
+            izip!(&chunk_domains, &chunks, &chunk_openings).enumerate()
+        {
+            let unmasked: Vec<Challenge> = interpolate_coset(chunk, domain.shift, zeta, None);
+            assert_ne!(*opening, unmasked);
+            let weight = chunk_domains
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, other)| {
+                    other.zp_at_point(zeta) * other.zp_at_point(domain.first_point()).inverse()
+                })
+                .product::<Challenge>();
+            for (r, &v) in recombined.iter_mut().zip(opening) {
+                *r += weight * v;
+            }
+        }
+        let quotient: Vec<Challenge> = interpolate_coset(&evals, quotient_domain.shift, zeta, None);
+        assert_eq!(recombined, quotient);
+
+        let claims = izip!(masked_domains, chunk_openings)
+            .map(|(domain, values)| (domain, vec![(zeta, values)]))
+            .collect();
+        let mut v_challenger = challenger.clone();
+        v_challenger.observe(commit);
+        assert_eq!(v_challenger.sample_ext_element::<Challenge>(), zeta);
+        pcs.verify(vec![(commit, claims)], &proof, &mut v_challenger)
+            .unwrap();
+    }
+}
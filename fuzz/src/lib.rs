@@ -0,0 +1,17 @@
+//! Structured fuzzing of the verifier, which must reject every proof that differs from an honest
+//! one, and every transcript that differs from the prover's.
+//!
+//! `ProofFuzzer` mutates the JSON encoding of a proof, so that each mutation is located by its
+//! path in the proof, e.g. in the FRI proof or in the Merkle openings of a query, and can be
+//! targeted at it. `TamperedChallenger` skips or repeats an observation of the verifier's
+//! challenger. Both collect the outcome of each mutation in a `Report`.
+
+mod mutation;
+mod proof;
+mod report;
+mod transcript;
+
+pub use mutation::*;
+pub use proof::*;
+pub use report::*;
+pub use transcript::*;
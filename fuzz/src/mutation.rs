@@ -0,0 +1,120 @@
+use core::fmt;
+
+use serde_json::Value;
+
+/// A step in the path from the root of a proof to one of its parts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+/// The change made by a `Mutation` to the part of a proof at its path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MutationKind {
+    /// Flip the lowest bit of a number.
+    FlipBit,
+    /// Remove the last element of a sequence.
+    Truncate,
+    /// Repeat the last element of a sequence.
+    Duplicate,
+    /// Reverse a sequence whose first and last elements differ.
+    Reverse,
+}
+
+/// A change to a single part of a proof, located by its path in the JSON encoding of the proof.
+///
+/// Every mutation changes the proof, so an honest proof is never mutated into itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mutation {
+    pub path: Vec<PathSegment>,
+    pub kind: MutationKind,
+}
+
+impl Mutation {
+    /// Every mutation applicable to `value`, in depth-first order.
+    pub fn all(value: &Value) -> Vec<Self> {
+        let mut mutations = Vec::new();
+        collect(value, &mut Vec::new(), &mut mutations);
+        mutations
+    }
+
+    /// Whether the path of the mutation passes through the field `name`, e.g. `query_proofs`.
+    pub fn touches(&self, name: &str) -> bool {
+        self.path
+            .iter()
+            .any(|segment| matches!(segment, PathSegment::Field(field) if field == name))
+    }
+
+    /// Apply the mutation to `value`, which must be the value it was found in.
+    pub fn apply(&self, value: &mut Value) {
+        let target = self
+            .path
+            .iter()
+            .fold(value, |value, segment| match segment {
+                PathSegment::Field(field) => &mut value[field.as_str()],
+                PathSegment::Index(index) => &mut value[*index],
+            });
+        match (self.kind, target) {
+            (MutationKind::FlipBit, Value::Number(number)) => {
+                let n = number.as_u64().expect("only unsigned integers are mutated");
+                *number = (n ^ 1).into();
+            }
+            (MutationKind::Truncate, Value::Array(values)) => {
+                values.pop();
+            }
+            (MutationKind::Duplicate, Value::Array(values)) => {
+                values.push(values.last().expect("empty sequence").clone());
+            }
+            (MutationKind::Reverse, Value::Array(values)) => values.reverse(),
+            (kind, target) => panic!("cannot apply {kind:?} to {target}"),
+        }
+    }
+}
+
+fn collect(value: &Value, path: &mut Vec<PathSegment>, mutations: &mut Vec<Mutation>) {
+    let mut push = |kind| {
+        mutations.push(Mutation {
+            path: path.clone(),
+            kind,
+        })
+    };
+    match value {
+        Value::Number(number) if number.is_u64() => push(MutationKind::FlipBit),
+        Value::Array(values) => {
+            if let (Some(first), Some(last)) = (values.first(), values.last()) {
+                push(MutationKind::Truncate);
+                push(MutationKind::Duplicate);
+                if first != last {
+                    push(MutationKind::Reverse);
+                }
+            }
+            for (i, value) in values.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                collect(value, path, mutations);
+                path.pop();
+            }
+        }
+        Value::Object(fields) => {
+            for (field, value) in fields {
+                path.push(PathSegment::Field(field.clone()));
+                collect(value, path, mutations);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.path.iter().enumerate() {
+            match segment {
+                PathSegment::Field(field) if i == 0 => write!(f, "{field}")?,
+                PathSegment::Field(field) => write!(f, ".{field}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        write!(f, ": {:?}", self.kind)
+    }
+}
@@ -0,0 +1,54 @@
+use p3_uni_stark::{Proof, StarkGenericConfig};
+use serde_json::Value;
+
+use crate::{Mutation, Outcome, Report};
+
+/// Mutates an honest proof, for checking that the verifier rejects every mutation.
+#[derive(Debug)]
+pub struct ProofFuzzer {
+    /// The JSON encoding of the proof, along with its format version.
+    json: Value,
+}
+
+impl ProofFuzzer {
+    pub fn new<SC: StarkGenericConfig>(proof: &Proof<SC>) -> Self {
+        let json = proof.to_json().expect("proofs are always serializable");
+        Self {
+            json: serde_json::from_str(&json).expect("the encoding is valid JSON"),
+        }
+    }
+
+    /// Every mutation of the proof, in depth-first order.
+    ///
+    /// The paths of the mutations start at the fields of `Proof`, so that e.g. those of the FRI
+    /// proof of a `TwoAdicFriPcs` start with `opening_proof`.
+    pub fn mutations(&self) -> Vec<Mutation> {
+        Mutation::all(&self.json["proof"])
+    }
+
+    /// The proof with `mutation` applied, or `None` if it no longer decodes.
+    pub fn mutate<SC: StarkGenericConfig>(&self, mutation: &Mutation) -> Option<Proof<SC>> {
+        let mut json = self.json.clone();
+        mutation.apply(&mut json["proof"]);
+        Proof::from_json(&json.to_string()).ok()
+    }
+
+    /// Verify the proof with each of `mutations` applied.
+    pub fn run<SC: StarkGenericConfig, E>(
+        &self,
+        mutations: impl IntoIterator<Item = Mutation>,
+        verify: impl Fn(&Proof<SC>) -> Result<(), E>,
+    ) -> Report<Mutation> {
+        let outcomes = mutations
+            .into_iter()
+            .map(|mutation| {
+                let outcome = match self.mutate::<SC>(&mutation) {
+                    Some(proof) => Outcome::of_verification(|| verify(&proof)),
+                    None => Outcome::Undecodable,
+                };
+                (mutation, outcome)
+            })
+            .collect();
+        Report { outcomes }
+    }
+}
@@ -0,0 +1,72 @@
+use core::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+/// What became of a mutated proof or transcript.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The mutated proof could not be decoded.
+    Undecodable,
+    /// The verifier returned an error.
+    Rejected,
+    /// The verifier panicked rather than returning an error.
+    Panicked,
+    /// The verifier accepted, so it is malleable.
+    Accepted,
+}
+
+impl Outcome {
+    /// Run `verify`, catching panics.
+    pub fn of_verification<E>(verify: impl FnOnce() -> Result<(), E>) -> Self {
+        match panic::catch_unwind(AssertUnwindSafe(verify)) {
+            Ok(Ok(())) => Self::Accepted,
+            Ok(Err(_)) => Self::Rejected,
+            Err(_) => Self::Panicked,
+        }
+    }
+}
+
+/// The outcome of each mutation of a fuzzing run.
+#[derive(Clone, Debug)]
+pub struct Report<M> {
+    pub outcomes: Vec<(M, Outcome)>,
+}
+
+impl<M: Debug> Report<M> {
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.outcomes.iter().filter(|(_, o)| *o == outcome).count()
+    }
+
+    pub fn accepted(&self) -> impl Iterator<Item = &M> {
+        self.with_outcome(Outcome::Accepted)
+    }
+
+    pub fn panicked(&self) -> impl Iterator<Item = &M> {
+        self.with_outcome(Outcome::Panicked)
+    }
+
+    fn with_outcome(&self, outcome: Outcome) -> impl Iterator<Item = &M> {
+        self.outcomes
+            .iter()
+            .filter(move |(_, o)| *o == outcome)
+            .map(|(mutation, _)| mutation)
+    }
+
+    /// Panics, listing the offending mutations, unless the decoder or the verifier returned an
+    /// error for every mutation.
+    pub fn assert_all_rejected(&self) {
+        let accepted: Vec<_> = self.accepted().collect();
+        assert!(
+            accepted.is_empty(),
+            "{} of {} mutations were accepted: {accepted:?}",
+            accepted.len(),
+            self.outcomes.len()
+        );
+        let panicked: Vec<_> = self.panicked().collect();
+        assert!(
+            panicked.is_empty(),
+            "{} of {} mutations made the verifier panic: {panicked:?}",
+            panicked.len(),
+            self.outcomes.len()
+        );
+    }
+}
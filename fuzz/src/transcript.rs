@@ -0,0 +1,139 @@
+use p3_challenger::{CanObserve, CanSample, CanSampleBits, FieldChallenger, GrindingChallenger};
+use p3_field::Field;
+
+use crate::{Outcome, Report};
+
+/// A deviation of a transcript, at the observation with the given index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptMutation {
+    Skip(usize),
+    Repeat(usize),
+}
+
+impl TranscriptMutation {
+    /// Every mutation of the first `num_observations` observations of a transcript.
+    pub fn all(num_observations: usize) -> impl Iterator<Item = Self> {
+        (0..num_observations).flat_map(|i| [Self::Skip(i), Self::Repeat(i)])
+    }
+}
+
+/// A challenger wrapper which skips or repeats one observation, to check that the verifier
+/// rejects any transcript differing from the prover's.
+///
+/// Without a mutation, it behaves as the inner challenger, and only counts observations. To make
+/// it the challenger of a verifier, it must be the challenger of its config, so the prover uses it
+/// as well.
+#[derive(Clone, Debug)]
+pub struct TamperedChallenger<C> {
+    inner: C,
+    mutation: Option<TranscriptMutation>,
+    num_observed: usize,
+    /// The number of observations made before the last sample.
+    num_sampled_over: usize,
+}
+
+impl<C> TamperedChallenger<C> {
+    pub const fn new(inner: C) -> Self {
+        Self {
+            inner,
+            mutation: None,
+            num_observed: 0,
+            num_sampled_over: 0,
+        }
+    }
+
+    pub const fn with_mutation(inner: C, mutation: TranscriptMutation) -> Self {
+        Self {
+            inner,
+            mutation: Some(mutation),
+            num_observed: 0,
+            num_sampled_over: 0,
+        }
+    }
+
+    pub const fn num_observed(&self) -> usize {
+        self.num_observed
+    }
+
+    /// The number of observations followed by a sample, which is thus bound to them. Mutating any
+    /// of them must lead the verifier to reject.
+    pub const fn num_binding_observations(&self) -> usize {
+        self.num_sampled_over
+    }
+}
+
+/// Verify with the first `num_observations` observations of the transcript mutated in turn.
+///
+/// `verify` is given each mutation, for verifying with a `TamperedChallenger` applying it.
+/// `num_observations` is usually the `num_binding_observations` of an honest verification.
+pub fn fuzz_transcript<E>(
+    num_observations: usize,
+    verify: impl Fn(TranscriptMutation) -> Result<(), E>,
+) -> Report<TranscriptMutation> {
+    let outcomes = TranscriptMutation::all(num_observations)
+        .map(|mutation| (mutation, Outcome::of_verification(|| verify(mutation))))
+        .collect();
+    Report { outcomes }
+}
+
+impl<C, T> CanObserve<T> for TamperedChallenger<C>
+where
+    C: CanObserve<T>,
+    T: Clone,
+{
+    fn observe(&mut self, value: T) {
+        let index = self.num_observed;
+        self.num_observed += 1;
+        match self.mutation {
+            Some(TranscriptMutation::Skip(i)) if i == index => {}
+            Some(TranscriptMutation::Repeat(i)) if i == index => {
+                self.inner.observe(value.clone());
+                self.inner.observe(value);
+            }
+            _ => self.inner.observe(value),
+        }
+    }
+}
+
+impl<C, T> CanSample<T> for TamperedChallenger<C>
+where
+    C: CanSample<T>,
+{
+    fn sample(&mut self) -> T {
+        self.num_sampled_over = self.num_observed;
+        self.inner.sample()
+    }
+}
+
+impl<C, T> CanSampleBits<T> for TamperedChallenger<C>
+where
+    C: CanSampleBits<T>,
+{
+    fn sample_bits(&mut self, bits: usize) -> T {
+        self.num_sampled_over = self.num_observed;
+        self.inner.sample_bits(bits)
+    }
+
+    fn sample_uniform_bits(&mut self, bits: usize) -> T {
+        self.num_sampled_over = self.num_observed;
+        self.inner.sample_uniform_bits(bits)
+    }
+}
+
+impl<C, F> FieldChallenger<F> for TamperedChallenger<C>
+where
+    C: FieldChallenger<F>,
+    F: Field,
+{
+}
+
+impl<C> GrindingChallenger for TamperedChallenger<C>
+where
+    C: GrindingChallenger,
+{
+    type Witness = C::Witness;
+
+    fn grind(&mut self, bits: usize) -> Self::Witness {
+        self.inner.grind(bits)
+    }
+}
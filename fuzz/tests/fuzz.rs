@@ -0,0 +1,105 @@
+use p3_field::FieldAlgebra;
+use p3_fuzz::{
+    fuzz_transcript, MutationKind, Outcome, ProofFuzzer, TamperedChallenger, TranscriptMutation,
+};
+use p3_uni_stark::testing::{
+    fibonacci_trace, test_pcs, Challenge, Challenger as InnerChallenger, FibonacciAir, Pcs, Perm,
+    Val,
+};
+use p3_uni_stark::{prove, verify, Proof, StarkConfig};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+type Challenger = TamperedChallenger<InnerChallenger>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+/// The test config of `p3-uni-stark`, with a challenger which can skip or repeat observations.
+/// Its permutation is sampled from a seeded generator, so that the proofs, and thus the tests, are
+/// deterministic.
+fn setup() -> (MyConfig, Perm) {
+    let (pcs, perm) = test_pcs(&mut ChaCha20Rng::seed_from_u64(1));
+    (MyConfig::new(pcs), perm)
+}
+
+fn prove_fibonacci(config: &MyConfig, perm: &Perm) -> (Proof<MyConfig>, Vec<Val>) {
+    let (trace, pis) = fibonacci_trace(Val::ZERO, Val::ONE, 1 << 4);
+    let mut challenger = Challenger::new(InnerChallenger::new(perm.clone()));
+    let proof = prove(config, &FibonacciAir, &mut challenger, trace, &pis);
+    (proof, pis)
+}
+
+#[test]
+fn test_structural_mutations() {
+    let (config, perm) = setup();
+    let (proof, pis) = prove_fibonacci(&config, &perm);
+    let fuzzer = ProofFuzzer::new(&proof);
+    let mutations = fuzzer
+        .mutations()
+        .into_iter()
+        .filter(|mutation| mutation.kind != MutationKind::FlipBit);
+
+    let report = fuzzer.run(mutations, |proof| {
+        let mut challenger = Challenger::new(InnerChallenger::new(perm.clone()));
+        verify(&config, &FibonacciAir, &mut challenger, proof, &pis)
+    });
+    report.assert_all_rejected();
+    // Some mutations still decode, so that the shape checks of the verifier are exercised.
+    assert!(report.count(Outcome::Rejected) > 0);
+}
+
+#[test]
+fn test_sampled_mutations() {
+    let (config, perm) = setup();
+    let (proof, pis) = prove_fibonacci(&config, &perm);
+    let fuzzer = ProofFuzzer::new(&proof);
+    let all_mutations = fuzzer.mutations();
+
+    // Sample mutations from each part of the proof, including the FRI proof and the openings of
+    // its queries.
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let mut mutations = vec![];
+    for part in [
+        "commitments",
+        "opened_values",
+        "degree_bits",
+        "commit_phase_commits",
+        "final_poly",
+        "pow_witness",
+        "input_proof",
+        "commit_phase_openings",
+    ] {
+        let candidates: Vec<_> = all_mutations
+            .iter()
+            .filter(|mutation| mutation.touches(part))
+            .collect();
+        assert!(!candidates.is_empty(), "no mutations of {part}");
+        mutations.extend(candidates.choose_multiple(&mut rng, 20).map(|&m| m.clone()));
+    }
+
+    let report = fuzzer.run(mutations, |proof| {
+        let mut challenger = Challenger::new(InnerChallenger::new(perm.clone()));
+        verify(&config, &FibonacciAir, &mut challenger, proof, &pis)
+    });
+    report.assert_all_rejected();
+}
+
+#[test]
+fn test_transcript_mutations() {
+    let (config, perm) = setup();
+    let (proof, pis) = prove_fibonacci(&config, &perm);
+
+    let mut challenger = Challenger::new(InnerChallenger::new(perm.clone()));
+    verify(&config, &FibonacciAir, &mut challenger, &proof, &pis).expect("verification failed");
+    let num_observations = challenger.num_binding_observations();
+    assert!(num_observations > 0);
+
+    let report = fuzz_transcript(num_observations, |mutation| {
+        let mut challenger =
+            Challenger::with_mutation(InnerChallenger::new(perm.clone()), mutation);
+        verify(&config, &FibonacciAir, &mut challenger, &proof, &pis)
+    });
+    report.assert_all_rejected();
+    assert_eq!(report.outcomes.len(), 2 * num_observations);
+    assert_eq!(report.outcomes[0].0, TranscriptMutation::Skip(0));
+}
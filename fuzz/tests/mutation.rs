@@ -0,0 +1,45 @@
+use p3_fuzz::{Mutation, MutationKind, PathSegment};
+use serde_json::json;
+
+#[test]
+fn test_mutations() {
+    let value = json!({ "a": [1, 2], "b": { "c": [7, 7] } });
+    let mutations = Mutation::all(&value);
+    let descriptions: Vec<_> = mutations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        descriptions,
+        [
+            "a: Truncate",
+            "a: Duplicate",
+            "a: Reverse",
+            "a[0]: FlipBit",
+            "a[1]: FlipBit",
+            "b.c: Truncate",
+            "b.c: Duplicate",
+            "b.c[0]: FlipBit",
+            "b.c[1]: FlipBit",
+        ]
+    );
+    assert!(mutations[5].touches("c") && !mutations[5].touches("a"));
+
+    for (mutation, expected) in mutations.iter().zip([
+        json!({ "a": [1], "b": { "c": [7, 7] } }),
+        json!({ "a": [1, 2, 2], "b": { "c": [7, 7] } }),
+        json!({ "a": [2, 1], "b": { "c": [7, 7] } }),
+        json!({ "a": [0, 2], "b": { "c": [7, 7] } }),
+        json!({ "a": [1, 3], "b": { "c": [7, 7] } }),
+    ]) {
+        let mut mutated = value.clone();
+        mutation.apply(&mut mutated);
+        assert_eq!(mutated, expected, "{mutation}");
+    }
+
+    let mutation = Mutation {
+        path: vec![
+            PathSegment::Field("b".into()),
+            PathSegment::Field("c".into()),
+        ],
+        kind: MutationKind::Reverse,
+    };
+    assert!(!mutations.contains(&mutation));
+}
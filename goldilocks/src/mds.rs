@@ -59,6 +59,83 @@ impl Convolve<Goldilocks, i128, i64, i128> for SmallConvolveGoldilocks {
     }
 }
 
+/// Instantiate convolution for "large" RHS vectors over Goldilocks.
+///
+/// Here "large" means N = len(rhs) <= 32 and the elements of the RHS
+/// are less than 2^32. The entries of MDS matrices with arbitrary
+/// Goldilocks entries are split into their low and high 32 bits, see
+/// `apply_large_circulant`.
+#[derive(Debug)]
+struct LargeConvolveGoldilocks;
+impl Convolve<Goldilocks, i128, i64, i128> for LargeConvolveGoldilocks {
+    /// Return the lift of a Goldilocks element, 0 <= input.value <= P
+    /// < 2^64.
+    #[inline(always)]
+    fn read(input: Goldilocks) -> i128 {
+        input.value as i128
+    }
+
+    /// For a convolution of size N, |x| < N * 2^64 and |y| < N *
+    /// 2^32, so the product is at most N^2 * 2^96 <= 2^106 when N <=
+    /// 32, and the dot product does not overflow. We widen `y` at
+    /// this point to perform the multiplication.
+    #[inline(always)]
+    fn parity_dot<const N: usize>(u: [i128; N], v: [i64; N]) -> i128 {
+        let mut s = 0i128;
+        for i in 0..N {
+            s += u[i] * v[i] as i128;
+        }
+        s
+    }
+
+    /// The recombination steps only grow the dot products by a small
+    /// factor, so intermediate values stay well below 2^127.
+    ///
+    /// NB: As for `SmallConvolveGoldilocks`, the output must be
+    /// non-negative since the inputs were non-negative.
+    #[inline(always)]
+    fn reduce(z: i128) -> Goldilocks {
+        debug_assert!(z >= 0);
+        reduce128(z as u128)
+    }
+}
+
+/// Split the first column of a circulant matrix into the low and high 32
+/// bits of its entries, for use with `LargeConvolveGoldilocks`.
+const fn split_first_col<const N: usize>(col: &[u64; N]) -> ([i64; N], [i64; N]) {
+    let mut low = [0; N];
+    let mut high = [0; N];
+    let mut i = 0;
+    while i < N {
+        low[i] = (col[i] & 0xFFFF_FFFF) as i64;
+        high[i] = (col[i] >> 32) as i64;
+        i += 1;
+    }
+    (low, high)
+}
+
+/// Multiply `input` by the circulant matrix whose first column has low
+/// and high 32 bits `col_low` and `col_high`, using `conv` to convolve
+/// with each half.
+#[inline(always)]
+fn apply_large_circulant<const N: usize, C: Fn([i128; N], [i64; N], &mut [i128])>(
+    input: [Goldilocks; N],
+    col_low: [i64; N],
+    col_high: [i64; N],
+    conv: C,
+) -> [Goldilocks; N] {
+    const TWO_POW_32: Goldilocks = Goldilocks::new(1 << 32);
+    let lhs = input.map(LargeConvolveGoldilocks::read);
+    let mut low = [0i128; N];
+    let mut high = [0i128; N];
+    conv(lhs, col_low, &mut low);
+    conv(lhs, col_high, &mut high);
+    core::array::from_fn(|i| {
+        LargeConvolveGoldilocks::reduce(low[i])
+            + LargeConvolveGoldilocks::reduce(high[i]) * TWO_POW_32
+    })
+}
+
 const FFT_ALGO: Radix2Bowers = Radix2Bowers;
 
 pub(crate) const MATRIX_CIRC_MDS_8_SML_ROW: [i64; 8] = [7, 1, 3, 8, 8, 3, 4, 9];
@@ -131,7 +208,9 @@ pub(crate) const MATRIX_CIRC_MDS_24_GOLDILOCKS: [u64; 24] = [
 
 impl Permutation<[Goldilocks; 24]> for MdsMatrixGoldilocks {
     fn permute(&self, input: [Goldilocks; 24]) -> [Goldilocks; 24] {
-        apply_circulant(&MATRIX_CIRC_MDS_24_GOLDILOCKS, input)
+        const ENTRIES: ([i64; 24], [i64; 24]) =
+            split_first_col(&first_row_to_first_col(&MATRIX_CIRC_MDS_24_GOLDILOCKS));
+        apply_large_circulant(input, ENTRIES.0, ENTRIES.1, LargeConvolveGoldilocks::conv24)
     }
 
     fn permute_mut(&self, input: &mut [Goldilocks; 24]) {
@@ -141,7 +220,7 @@ impl Permutation<[Goldilocks; 24]> for MdsMatrixGoldilocks {
 impl MdsPermutation<Goldilocks, 24> for MdsMatrixGoldilocks {}
 
 #[rustfmt::skip]
-const MATRIX_CIRC_MDS_32_GOLDILOCKS: [u64; 32] = [
+pub(crate) const MATRIX_CIRC_MDS_32_GOLDILOCKS: [u64; 32] = [
     0x0800000000000000, 0x69249248B4924925, 0x3ABD5EAF15EAF57B, 0x294A5294739CE73A,
     0x59E2D2CEB4B3C5A6, 0x087FBE00FF7C0220, 0xA554AA94A554AA96, 0xF00080FEFFDF8005,
     0x64CCCCCC6666699A, 0x5B13AD8973B139D9, 0xAD4A55ACA54AD5AA, 0xDA496DA3B492DB8A,
@@ -154,8 +233,9 @@ const MATRIX_CIRC_MDS_32_GOLDILOCKS: [u64; 32] = [
 
 impl Permutation<[Goldilocks; 32]> for MdsMatrixGoldilocks {
     fn permute(&self, input: [Goldilocks; 32]) -> [Goldilocks; 32] {
-        const ENTRIES: [u64; 32] = first_row_to_first_col(&MATRIX_CIRC_MDS_32_GOLDILOCKS);
-        apply_circulant_fft(FFT_ALGO, ENTRIES, &input)
+        const ENTRIES: ([i64; 32], [i64; 32]) =
+            split_first_col(&first_row_to_first_col(&MATRIX_CIRC_MDS_32_GOLDILOCKS));
+        apply_large_circulant(input, ENTRIES.0, ENTRIES.1, LargeConvolveGoldilocks::conv32)
     }
 
     fn permute_mut(&self, input: &mut [Goldilocks; 32]) {
@@ -231,9 +311,13 @@ impl MdsPermutation<Goldilocks, 68> for MdsMatrixGoldilocks {}
 #[cfg(test)]
 mod tests {
     use p3_field::FieldAlgebra;
+    use p3_mds::util::apply_circulant;
     use p3_symmetric::Permutation;
 
-    use super::{Goldilocks, MdsMatrixGoldilocks};
+    use super::{
+        Goldilocks, MdsMatrixGoldilocks, MATRIX_CIRC_MDS_24_GOLDILOCKS,
+        MATRIX_CIRC_MDS_32_GOLDILOCKS,
+    };
 
     #[test]
     fn goldilocks8() {
@@ -494,6 +578,22 @@ mod tests {
         assert_eq!(output, expected);
     }
 
+    #[test]
+    fn goldilocks_large_entries_max_input() {
+        // The largest inputs give the largest intermediate values in the convolutions.
+        let input = [Goldilocks::NEG_ONE; 24];
+        assert_eq!(
+            MdsMatrixGoldilocks.permute(input),
+            apply_circulant(&MATRIX_CIRC_MDS_24_GOLDILOCKS, input)
+        );
+
+        let input = [Goldilocks::NEG_ONE; 32];
+        assert_eq!(
+            MdsMatrixGoldilocks.permute(input),
+            apply_circulant(&MATRIX_CIRC_MDS_32_GOLDILOCKS, input)
+        );
+    }
+
     #[test]
     fn goldilocks64() {
         let input: [Goldilocks; 64] = [
@@ -12,23 +12,26 @@ use alloc::vec::Vec;
 use p3_field::{Field, FieldAlgebra};
 use p3_poseidon2::{
     add_rc_and_sbox_generic, external_initial_permute_state, external_terminal_permute_state,
-    internal_permute_state, matmul_internal, ExternalLayer, ExternalLayerConstants,
-    ExternalLayerConstructor, HLMDSMat4, InternalLayer, InternalLayerConstructor, MDSMat4,
-    Poseidon2,
+    internal_permute_state, matmul_internal, poseidon2_round_numbers_128, ExternalLayer,
+    ExternalLayerConstants, ExternalLayerConstructor, HLMDSMat4, InternalLayer,
+    InternalLayerConstructor, MDSMat4, Poseidon2,
 };
+use rand::distributions::Standard;
+use rand::Rng;
 
 use crate::{to_goldilocks_array, Goldilocks};
 
 /// Degree of the chosen permutation polynomial for Goldilocks, used as the Poseidon2 S-Box.
 ///
 /// As p - 1 = 2^32 * 3 * 5 * 17 * ... the smallest choice for a degree D satisfying gcd(p - 1, D) = 1 is 7.
-const GOLDILOCKS_S_BOX_DEGREE: u64 = 7;
+pub(crate) const GOLDILOCKS_S_BOX_DEGREE: u64 = 7;
 
 /// An implementation of the Poseidon2 hash function for the Goldilocks field.
 ///
 /// It acts on arrays of the form `[Goldilocks; WIDTH]`.
 /// Currently the internal layers are unoptimized. These could be sped up in a similar way to
-/// how it was done for Monty31 fields.
+/// how it was done for Monty31 fields. To permute one state at a time with widths 8 or 12,
+/// `Poseidon2GoldilocksHorizontal` is faster.
 pub type Poseidon2Goldilocks<const WIDTH: usize> = Poseidon2<
     <Goldilocks as Field>::Packing,
     Poseidon2ExternalLayerGoldilocks<WIDTH>,
@@ -262,6 +265,108 @@ impl<FA: FieldAlgebra<F = Goldilocks>, const WIDTH: usize>
     }
 }
 
+/// The same permutation as `Poseidon2Goldilocks<WIDTH>`, for widths 8 and 12, optimized for
+/// permuting a single state rather than many at once.
+///
+/// `Poseidon2Goldilocks` vectorizes by permuting one state per lane of `Goldilocks::Packing`, so
+/// a single `[Goldilocks; WIDTH]` is permuted with scalar arithmetic. Here, the state is instead
+/// packed horizontally, with consecutive elements in the lanes of an AVX2 or AVX-512 vector, so
+/// that the S-boxes of the full rounds and both linear layers are vectorized. On other targets,
+/// this falls back to the scalar permutation.
+#[derive(Clone, Debug)]
+pub struct Poseidon2GoldilocksHorizontal<const WIDTH: usize> {
+    pub(crate) external_constants: ExternalLayerConstants<Goldilocks, WIDTH>,
+    pub(crate) internal_constants: Vec<Goldilocks>,
+}
+
+impl<const WIDTH: usize> Poseidon2GoldilocksHorizontal<WIDTH> {
+    pub fn new(
+        external_constants: ExternalLayerConstants<Goldilocks, WIDTH>,
+        internal_constants: Vec<Goldilocks>,
+    ) -> Self {
+        assert!(
+            WIDTH == 8 || WIDTH == 12,
+            "only widths 8 and 12 are supported"
+        );
+        Self {
+            external_constants,
+            internal_constants,
+        }
+    }
+
+    /// Create a new instance with 128 bit security and random round constants, which are the same
+    /// as those of `Poseidon2Goldilocks::new_from_rng_128` given the same randomness.
+    pub fn new_from_rng_128<R: Rng>(rng: &mut R) -> Self {
+        let (rounds_f, rounds_p) =
+            poseidon2_round_numbers_128::<Goldilocks>(WIDTH, GOLDILOCKS_S_BOX_DEGREE);
+        let external_constants = ExternalLayerConstants::new_from_rng(rounds_f, rng);
+        let internal_constants = rng.sample_iter(Standard).take(rounds_p).collect();
+        Self::new(external_constants, internal_constants)
+    }
+
+    /// The diagonal of the internal matrix, minus the identity.
+    pub(crate) fn internal_diag() -> &'static [Goldilocks] {
+        match WIDTH {
+            8 => &MATRIX_DIAG_8_GOLDILOCKS,
+            12 => &MATRIX_DIAG_12_GOLDILOCKS,
+            _ => unreachable!("only widths 8 and 12 are supported"),
+        }
+    }
+}
+
+#[cfg(not(any(
+    all(
+        target_arch = "x86_64",
+        target_feature = "avx2",
+        not(all(feature = "nightly-features", target_feature = "avx512f"))
+    ),
+    all(
+        feature = "nightly-features",
+        target_arch = "x86_64",
+        target_feature = "avx512f"
+    )
+)))]
+impl<const WIDTH: usize> p3_symmetric::Permutation<[Goldilocks; WIDTH]>
+    for Poseidon2GoldilocksHorizontal<WIDTH>
+{
+    fn permute_mut(&self, state: &mut [Goldilocks; WIDTH]) {
+        let diag: [Goldilocks; WIDTH] = Self::internal_diag().try_into().unwrap();
+        external_initial_permute_state(
+            state,
+            self.external_constants.get_initial_constants(),
+            add_rc_and_sbox_generic::<_, GOLDILOCKS_S_BOX_DEGREE>,
+            &MDSMat4,
+        );
+        for &rc in &self.internal_constants {
+            add_rc_and_sbox_generic::<_, GOLDILOCKS_S_BOX_DEGREE>(&mut state[0], rc);
+            matmul_internal(state, diag);
+        }
+        external_terminal_permute_state(
+            state,
+            self.external_constants.get_terminal_constants(),
+            add_rc_and_sbox_generic::<_, GOLDILOCKS_S_BOX_DEGREE>,
+            &MDSMat4,
+        );
+    }
+}
+
+#[cfg(not(any(
+    all(
+        target_arch = "x86_64",
+        target_feature = "avx2",
+        not(all(feature = "nightly-features", target_feature = "avx512f"))
+    ),
+    all(
+        feature = "nightly-features",
+        target_arch = "x86_64",
+        target_feature = "avx512f"
+    )
+)))]
+impl<const WIDTH: usize> p3_symmetric::CryptographicPermutation<[Goldilocks; WIDTH]>
+    for Poseidon2GoldilocksHorizontal<WIDTH>
+{
+}
+
 pub const HL_GOLDILOCKS_8_EXTERNAL_ROUND_CONSTANTS: [[[u64; 8]; 4]; 2] = [
     [
         [
@@ -380,6 +485,8 @@ mod tests {
     use p3_field::FieldAlgebra;
     use p3_poseidon2::Poseidon2;
     use p3_symmetric::Permutation;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     use super::*;
 
@@ -475,4 +582,29 @@ mod tests {
         hl_poseidon2_goldilocks_width_8(&mut input);
         assert_eq!(input, expected);
     }
+
+    fn test_horizontal_matches<const WIDTH: usize>()
+    where
+        Poseidon2Goldilocks<WIDTH>: Permutation<[F; WIDTH]>,
+        Poseidon2GoldilocksHorizontal<WIDTH>: Permutation<[F; WIDTH]>,
+    {
+        let mut rng = StdRng::seed_from_u64(1);
+        let poseidon2 = Poseidon2Goldilocks::<WIDTH>::new_from_rng_128(&mut rng.clone());
+        let horizontal = Poseidon2GoldilocksHorizontal::<WIDTH>::new_from_rng_128(&mut rng);
+
+        for _ in 0..10 {
+            let input: [F; WIDTH] = array::from_fn(|_| rng.gen());
+            assert_eq!(horizontal.permute(input), poseidon2.permute(input));
+        }
+    }
+
+    #[test]
+    fn test_poseidon2_horizontal_width_8() {
+        test_horizontal_matches::<8>();
+    }
+
+    #[test]
+    fn test_poseidon2_horizontal_width_12() {
+        test_horizontal_matches::<12>();
+    }
 }
@@ -28,11 +28,11 @@ pub struct PackedGoldilocksAVX2(pub [Goldilocks; WIDTH]);
 
 impl PackedGoldilocksAVX2 {
     #[inline]
-    fn new(x: __m256i) -> Self {
+    pub(crate) fn new(x: __m256i) -> Self {
         unsafe { transmute(x) }
     }
     #[inline]
-    fn get(&self) -> __m256i {
+    pub(crate) fn get(&self) -> __m256i {
         unsafe { transmute(*self) }
     }
 }
@@ -0,0 +1,100 @@
+//! Poseidon2 for Goldilocks with a single state packed horizontally into AVX2 vectors.
+
+use core::arch::x86_64::*;
+
+use p3_field::{FieldAlgebra, PackedValue};
+use p3_symmetric::{CryptographicPermutation, Permutation};
+
+use crate::{
+    Goldilocks, PackedGoldilocksAVX2, Poseidon2GoldilocksHorizontal, GOLDILOCKS_S_BOX_DEGREE,
+};
+
+/// Each lane `i` of the output is lane `i + 1` of the input, cyclically.
+#[inline(always)]
+fn rotate_1(x: PackedGoldilocksAVX2) -> PackedGoldilocksAVX2 {
+    unsafe { PackedGoldilocksAVX2::new(_mm256_permute4x64_epi64::<0b00_11_10_01>(x.get())) }
+}
+
+/// Each lane `i` of the output is lane `i + 2` of the input, cyclically.
+#[inline(always)]
+fn rotate_2(x: PackedGoldilocksAVX2) -> PackedGoldilocksAVX2 {
+    unsafe { PackedGoldilocksAVX2::new(_mm256_permute4x64_epi64::<0b01_00_11_10>(x.get())) }
+}
+
+/// The sum of the four lanes of `x`, in every lane.
+#[inline(always)]
+fn lane_sum(x: PackedGoldilocksAVX2) -> PackedGoldilocksAVX2 {
+    let x = x + rotate_2(x);
+    x + rotate_1(x)
+}
+
+/// Multiply the four lanes of `x` by `MDSMat4`.
+///
+/// `MDSMat4` is the circulant matrix with first row `[2, 3, 1, 1]`, so lane `i` of the output is
+/// `2 x_i + 3 x_{i + 1} + x_{i + 2} + x_{i + 3}`, i.e. the sum of the lanes plus
+/// `x_i + 2 x_{i + 1}`.
+#[inline(always)]
+fn mat4(x: PackedGoldilocksAVX2) -> PackedGoldilocksAVX2 {
+    lane_sum(x) + x + rotate_1(x).double()
+}
+
+/// Multiply the state by the matrix of the external layer, `[[2M M ... M], ..., [M M ... 2M]]`
+/// for `M = MDSMat4`.
+#[inline(always)]
+fn external_linear_layer(state: &mut [PackedGoldilocksAVX2]) {
+    for x in state.iter_mut() {
+        *x = mat4(*x);
+    }
+    let sum: PackedGoldilocksAVX2 = state.iter().copied().sum();
+    for x in state.iter_mut() {
+        *x += sum;
+    }
+}
+
+#[inline(always)]
+fn full_round(state: &mut [PackedGoldilocksAVX2], round_constants: &[Goldilocks]) {
+    for (x, &rc) in state
+        .iter_mut()
+        .zip(PackedGoldilocksAVX2::pack_slice(round_constants))
+    {
+        *x = (*x + rc).exp_const_u64::<GOLDILOCKS_S_BOX_DEGREE>();
+    }
+    external_linear_layer(state);
+}
+
+#[inline(always)]
+fn partial_round(
+    state: &mut [PackedGoldilocksAVX2],
+    round_constant: Goldilocks,
+    diag: &[PackedGoldilocksAVX2],
+) {
+    let s0 = &mut state[0].0[0];
+    *s0 = (*s0 + round_constant).exp_const_u64::<GOLDILOCKS_S_BOX_DEGREE>();
+    let sum = lane_sum(state.iter().copied().sum());
+    for (x, &d) in state.iter_mut().zip(diag) {
+        *x = *x * d + sum;
+    }
+}
+
+impl<const WIDTH: usize> Permutation<[Goldilocks; WIDTH]> for Poseidon2GoldilocksHorizontal<WIDTH> {
+    fn permute_mut(&self, state: &mut [Goldilocks; WIDTH]) {
+        let diag = PackedGoldilocksAVX2::pack_slice(Self::internal_diag());
+        let state = PackedGoldilocksAVX2::pack_slice_mut(state);
+
+        external_linear_layer(state);
+        for round_constants in self.external_constants.get_initial_constants() {
+            full_round(state, round_constants);
+        }
+        for &round_constant in &self.internal_constants {
+            partial_round(state, round_constant, diag);
+        }
+        for round_constants in self.external_constants.get_terminal_constants() {
+            full_round(state, round_constants);
+        }
+    }
+}
+
+impl<const WIDTH: usize> CryptographicPermutation<[Goldilocks; WIDTH]>
+    for Poseidon2GoldilocksHorizontal<WIDTH>
+{
+}
@@ -27,11 +27,11 @@ pub struct PackedGoldilocksAVX512(pub [Goldilocks; WIDTH]);
 
 impl PackedGoldilocksAVX512 {
     #[inline]
-    fn new(x: __m512i) -> Self {
+    pub(crate) fn new(x: __m512i) -> Self {
         unsafe { transmute(x) }
     }
     #[inline]
-    fn get(&self) -> __m512i {
+    pub(crate) fn get(&self) -> __m512i {
         unsafe { transmute(*self) }
     }
 }
@@ -0,0 +1,146 @@
+//! Poseidon2 for Goldilocks with a single state packed horizontally into AVX-512 vectors.
+
+use core::arch::x86_64::*;
+
+use p3_field::{FieldAlgebra, PackedValue};
+use p3_symmetric::{CryptographicPermutation, Permutation};
+
+use crate::{
+    Goldilocks, PackedGoldilocksAVX512, Poseidon2GoldilocksHorizontal, GOLDILOCKS_S_BOX_DEGREE,
+};
+
+/// The number of vectors holding a state of the largest supported width, 12, whose last four
+/// lanes are always zero.
+const MAX_VECTORS: usize = 2;
+
+/// Each lane `i` of the output is lane `i + 1` of the input, cyclically within each half.
+#[inline(always)]
+fn rotate_1(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    unsafe { PackedGoldilocksAVX512::new(_mm512_permutex_epi64::<0b00_11_10_01>(x.get())) }
+}
+
+/// Each lane `i` of the output is lane `i + 2` of the input, cyclically within each half.
+#[inline(always)]
+fn rotate_2(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    unsafe { PackedGoldilocksAVX512::new(_mm512_permutex_epi64::<0b01_00_11_10>(x.get())) }
+}
+
+/// Swap the two halves of `x`.
+#[inline(always)]
+fn swap_halves(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    unsafe {
+        let x = x.get();
+        PackedGoldilocksAVX512::new(_mm512_shuffle_i64x2::<0b01_00_11_10>(x, x))
+    }
+}
+
+/// Zero the upper half of `x`.
+#[inline(always)]
+fn zero_upper_half(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    unsafe { PackedGoldilocksAVX512::new(_mm512_maskz_mov_epi64(0x0f, x.get())) }
+}
+
+/// The sum of the four lanes of each half of `x`, in every lane of that half.
+#[inline(always)]
+fn half_sum(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    let x = x + rotate_2(x);
+    x + rotate_1(x)
+}
+
+/// Multiply the four lanes of each half of `x` by `MDSMat4`.
+///
+/// `MDSMat4` is the circulant matrix with first row `[2, 3, 1, 1]`, so lane `i` of the output is
+/// `2 x_i + 3 x_{i + 1} + x_{i + 2} + x_{i + 3}`, i.e. the sum of the lanes plus
+/// `x_i + 2 x_{i + 1}`.
+#[inline(always)]
+fn mat4(x: PackedGoldilocksAVX512) -> PackedGoldilocksAVX512 {
+    half_sum(x) + x + rotate_1(x).double()
+}
+
+/// Zero the padding of a state of width 12.
+#[inline(always)]
+fn clear_padding<const WIDTH: usize>(state: &mut [PackedGoldilocksAVX512]) {
+    if WIDTH % 8 != 0 {
+        let last = state.last_mut().unwrap();
+        *last = zero_upper_half(*last);
+    }
+}
+
+/// Multiply the state by the matrix of the external layer, `[[2M M ... M], ..., [M M ... 2M]]`
+/// for `M = MDSMat4`.
+#[inline(always)]
+fn external_linear_layer<const WIDTH: usize>(state: &mut [PackedGoldilocksAVX512]) {
+    for x in state.iter_mut() {
+        *x = mat4(*x);
+    }
+    // The padding is zero, so does not affect the sum of the chunks of four.
+    let sum: PackedGoldilocksAVX512 = state.iter().copied().sum();
+    let sum = sum + swap_halves(sum);
+    for x in state.iter_mut() {
+        *x += sum;
+    }
+    clear_padding::<WIDTH>(state);
+}
+
+#[inline(always)]
+fn full_round<const WIDTH: usize>(
+    state: &mut [PackedGoldilocksAVX512],
+    round_constants: &[Goldilocks],
+) {
+    let mut padded = [Goldilocks::ZERO; 8 * MAX_VECTORS];
+    padded[..WIDTH].copy_from_slice(round_constants);
+    for (x, &rc) in state
+        .iter_mut()
+        .zip(PackedGoldilocksAVX512::pack_slice(&padded))
+    {
+        *x = (*x + rc).exp_const_u64::<GOLDILOCKS_S_BOX_DEGREE>();
+    }
+    external_linear_layer::<WIDTH>(state);
+}
+
+#[inline(always)]
+fn partial_round<const WIDTH: usize>(
+    state: &mut [PackedGoldilocksAVX512],
+    round_constant: Goldilocks,
+    diag: &[PackedGoldilocksAVX512],
+) {
+    let s0 = &mut state[0].0[0];
+    *s0 = (*s0 + round_constant).exp_const_u64::<GOLDILOCKS_S_BOX_DEGREE>();
+    let sum = half_sum(state.iter().copied().sum());
+    let sum = sum + swap_halves(sum);
+    for (x, &d) in state.iter_mut().zip(diag) {
+        *x = *x * d + sum;
+    }
+    clear_padding::<WIDTH>(state);
+}
+
+impl<const WIDTH: usize> Permutation<[Goldilocks; WIDTH]> for Poseidon2GoldilocksHorizontal<WIDTH> {
+    fn permute_mut(&self, state: &mut [Goldilocks; WIDTH]) {
+        // A state of width 12 is padded with zeros to fill two vectors.
+        let num_vectors = WIDTH.div_ceil(8);
+        let mut padded_diag = [Goldilocks::ZERO; 8 * MAX_VECTORS];
+        padded_diag[..WIDTH].copy_from_slice(Self::internal_diag());
+        let diag = PackedGoldilocksAVX512::pack_slice(&padded_diag[..8 * num_vectors]);
+        let mut padded_state = [Goldilocks::ZERO; 8 * MAX_VECTORS];
+        padded_state[..WIDTH].copy_from_slice(state);
+        let packed = PackedGoldilocksAVX512::pack_slice_mut(&mut padded_state[..8 * num_vectors]);
+
+        external_linear_layer::<WIDTH>(packed);
+        for round_constants in self.external_constants.get_initial_constants() {
+            full_round::<WIDTH>(packed, round_constants);
+        }
+        for &round_constant in &self.internal_constants {
+            partial_round::<WIDTH>(packed, round_constant, diag);
+        }
+        for round_constants in self.external_constants.get_terminal_constants() {
+            full_round::<WIDTH>(packed, round_constants);
+        }
+
+        state.copy_from_slice(&padded_state[..WIDTH]);
+    }
+}
+
+impl<const WIDTH: usize> CryptographicPermutation<[Goldilocks; WIDTH]>
+    for Poseidon2GoldilocksHorizontal<WIDTH>
+{
+}
@@ -0,0 +1,172 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::{
+    batch_multiplicative_inverse, scale_vec, ExtensionField, FieldAlgebra, FieldExtensionAlgebra,
+    PackedValue, TwoAdicField,
+};
+use p3_matrix::Matrix;
+use p3_maybe_rayon::prelude::*;
+
+/// Evaluates polynomials, given by their evaluations over a fixed coset of a power-of-two
+/// subgroup, at points outside of the coset.
+///
+/// The barycentric weights of the coset are computed once, so that evaluating many batches of
+/// polynomials, or a batch at many points, only costs the inversions of the differences between
+/// each point and the coset.
+#[derive(Clone, Debug)]
+pub struct BarycentricEvaluator<F> {
+    log_height: usize,
+    shift: F,
+    /// The points of the coset, `shift * g^i`.
+    coset: Vec<F>,
+    /// The barycentric weights of the coset, `g^i / (n shift^(n - 1))`.
+    weights: Vec<F>,
+}
+
+impl<F: TwoAdicField> BarycentricEvaluator<F> {
+    /// Precompute the weights of the coset of size `2^log_height` shifted by `shift`.
+    pub fn new(log_height: usize, shift: F) -> Self {
+        let height = 1 << log_height;
+        let subgroup: Vec<F> = F::two_adic_generator(log_height)
+            .powers()
+            .take(height)
+            .collect();
+        let coset = subgroup.iter().map(|&x| x * shift).collect();
+        let denominator_inv =
+            (F::from_canonical_usize(height) * shift.exp_u64(height as u64 - 1)).inverse();
+        let weights = subgroup.iter().map(|&x| x * denominator_inv).collect();
+        Self {
+            log_height,
+            shift,
+            coset,
+            weights,
+        }
+    }
+
+    pub const fn log_height(&self) -> usize {
+        self.log_height
+    }
+
+    pub const fn shift(&self) -> F {
+        self.shift
+    }
+
+    /// Evaluate at `point` the polynomials whose evaluations over the coset are the columns of
+    /// `evals`, in the natural order of the coset.
+    ///
+    /// This assumes the point is not in the coset, otherwise the behavior is undefined.
+    pub fn evaluate<EF, Mat>(&self, evals: &Mat, point: EF) -> Vec<EF>
+    where
+        EF: ExtensionField<F>,
+        Mat: Matrix<F>,
+    {
+        let diffs: Vec<EF> = self.coset.par_iter().map(|&x| point - x).collect();
+        let diff_invs = batch_multiplicative_inverse(&diffs);
+        self.evaluate_with_inverse_differences(evals, point, &diff_invs)
+    }
+
+    /// Evaluate as `evaluate` does, given the inverses `1 / (point - x_i)` of the differences
+    /// between `point` and each point `x_i` of the coset, e.g. when they are shared with other
+    /// computations.
+    pub fn evaluate_with_inverse_differences<EF, Mat>(
+        &self,
+        evals: &Mat,
+        point: EF,
+        diff_invs: &[EF],
+    ) -> Vec<EF>
+    where
+        EF: ExtensionField<F>,
+        Mat: Matrix<F>,
+    {
+        debug_assert_eq!(evals.height(), self.coset.len());
+        let col_scale: Vec<EF> = self
+            .weights
+            .par_iter()
+            .zip(diff_invs)
+            .map(|(&w, &diff_inv)| diff_inv * w)
+            .collect();
+        let sum = evals.columnwise_dot_product(&col_scale);
+        scale_vec(self.zerofier(point), sum)
+    }
+
+    /// Evaluate the polynomials whose evaluations over the coset are the columns of `evals` at
+    /// each of `points`, in a single pass over the rows of `evals`.
+    ///
+    /// This assumes no point is in the coset, otherwise the behavior is undefined.
+    pub fn evaluate_batch<EF, Mat>(&self, evals: &Mat, points: &[EF]) -> Vec<Vec<EF>>
+    where
+        EF: ExtensionField<F>,
+        Mat: Matrix<F>,
+    {
+        debug_assert_eq!(evals.height(), self.coset.len());
+        if points.is_empty() {
+            return Vec::new();
+        }
+        let num_points = points.len();
+
+        // Invert all differences at once, and scale them by the weights. The scales of row `r`
+        // are at `r * num_points..(r + 1) * num_points`.
+        let diffs: Vec<EF> = self
+            .coset
+            .par_iter()
+            .flat_map_iter(|&x| points.iter().map(move |&point| point - x))
+            .collect();
+        let mut col_scales = batch_multiplicative_inverse(&diffs);
+        col_scales
+            .par_chunks_exact_mut(num_points)
+            .zip(&self.weights)
+            .for_each(|(scales, &w)| scales.iter_mut().for_each(|s| *s *= w));
+
+        let packed_width = evals.width().div_ceil(F::Packing::WIDTH);
+        if packed_width == 0 {
+            return vec![Vec::new(); num_points];
+        }
+        let packed_sums = evals
+            .par_padded_horizontally_packed_rows::<F::Packing>()
+            .zip(col_scales.par_chunks_exact(num_points))
+            .par_fold_reduce(
+                || EF::ExtensionPacking::zero_vec(num_points * packed_width),
+                |mut acc, (row, scales)| {
+                    // The row is read once and reused for every point.
+                    let row: Vec<F::Packing> = row.collect();
+                    for (acc, &scale) in acc.chunks_exact_mut(packed_width).zip(scales) {
+                        let scale = EF::ExtensionPacking::from_base_fn(|i| {
+                            F::Packing::from(scale.as_base_slice()[i])
+                        });
+                        for (l, &r) in acc.iter_mut().zip(&row) {
+                            *l += scale * r;
+                        }
+                    }
+                    acc
+                },
+                |mut acc_l, acc_r| {
+                    for (l, r) in acc_l.iter_mut().zip(acc_r) {
+                        *l += r;
+                    }
+                    acc_l
+                },
+            );
+
+        points
+            .iter()
+            .zip(packed_sums.chunks_exact(packed_width))
+            .map(|(&point, packed_sum)| {
+                let sum = packed_sum
+                    .iter()
+                    .flat_map(|p| {
+                        (0..F::Packing::WIDTH)
+                            .map(move |i| EF::from_base_fn(|j| p.as_base_slice()[j].as_slice()[i]))
+                    })
+                    .take(evals.width())
+                    .collect();
+                scale_vec(self.zerofier(point), sum)
+            })
+            .collect()
+    }
+
+    /// The vanishing polynomial of the coset, evaluated at `point`.
+    fn zerofier<EF: ExtensionField<F>>(&self, point: EF) -> EF {
+        point.exp_power_of_2(self.log_height) - self.shift.exp_power_of_2(self.log_height)
+    }
+}
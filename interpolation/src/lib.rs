@@ -4,13 +4,13 @@
 
 extern crate alloc;
 
+mod barycentric;
+
 use alloc::vec::Vec;
 
-use p3_field::{
-    batch_multiplicative_inverse, scale_vec, two_adic_coset_zerofier, ExtensionField, TwoAdicField,
-};
+pub use barycentric::*;
+use p3_field::{ExtensionField, TwoAdicField};
 use p3_matrix::Matrix;
-use p3_maybe_rayon::prelude::*;
 use p3_util::log2_strict_usize;
 
 /// Given evaluations of a batch of polynomials over the canonical power-of-two subgroup, evaluate
@@ -31,6 +31,9 @@ where
 ///
 /// This assumes the point is not in the coset, otherwise the behavior is undefined.
 /// If available, reuse denominator diffs that is `1 / (x_i-z)` to avoid batch inversion.
+///
+/// This recomputes the barycentric weights of the coset on every call; to evaluate several
+/// batches or points over the same coset, use a [`BarycentricEvaluator`].
 pub fn interpolate_coset<F, EF, Mat>(
     coset_evals: &Mat,
     shift: F,
@@ -43,33 +46,13 @@ where
     Mat: Matrix<F>,
 {
     // Slight variation of this approach: https://hackmd.io/@vbuterin/barycentric_evaluation
-
-    let height = coset_evals.height();
-    let log_height = log2_strict_usize(height);
-
-    let g = F::two_adic_generator(log_height).powers().take(height);
-    let col_scale: Vec<_> = if let Some(diff_invs) = diff_invs {
-        g.zip(diff_invs)
-            .map(|(sg, &diff_inv)| diff_inv * sg)
-            .collect()
-    } else {
-        let subgroup = g.collect::<Vec<_>>();
-        let diffs: Vec<EF> = subgroup
-            .par_iter()
-            .map(|&subgroup_i| point - subgroup_i * shift)
-            .collect();
-        let diff_invs = batch_multiplicative_inverse(&diffs);
-        subgroup
-            .par_iter()
-            .zip(diff_invs)
-            .map(|(&sg, diff_inv)| diff_inv * sg)
-            .collect()
-    };
-    let sum = coset_evals.columnwise_dot_product(&col_scale);
-
-    let zerofier = two_adic_coset_zerofier::<EF>(log_height, EF::from_base(shift), point);
-    let denominator = F::from_canonical_usize(height) * shift.exp_u64(height as u64 - 1);
-    scale_vec(zerofier * denominator.inverse(), sum)
+    let evaluator = BarycentricEvaluator::new(log2_strict_usize(coset_evals.height()), shift);
+    match diff_invs {
+        Some(diff_invs) => {
+            evaluator.evaluate_with_inverse_differences(coset_evals, point, diff_invs)
+        }
+        None => evaluator.evaluate(coset_evals, point),
+    }
 }
 
 #[cfg(test)]
@@ -78,11 +61,12 @@ mod tests {
     use alloc::vec::Vec;
 
     use p3_baby_bear::BabyBear;
+    use p3_field::extension::BinomialExtensionField;
     use p3_field::{batch_multiplicative_inverse, Field, FieldAlgebra};
     use p3_matrix::dense::RowMajorMatrix;
     use p3_util::log2_strict_usize;
 
-    use crate::{interpolate_coset, interpolate_subgroup};
+    use crate::{interpolate_coset, interpolate_subgroup, BarycentricEvaluator};
 
     #[test]
     fn test_interpolate_subgroup() {
@@ -126,4 +110,41 @@ mod tests {
         let result = interpolate_coset(&evals_mat, shift, point, Some(&denom));
         assert_eq!(result, vec![F::from_canonical_u32(10203)]);
     }
+
+    #[test]
+    fn test_barycentric_evaluator() {
+        // x^2 + 2 x + 3, and 3 x^2 + x + 7 over the same coset
+        type F = BabyBear;
+        type EF = BinomialExtensionField<F, 4>;
+        let shift = F::GENERATOR;
+        let log_height = 3;
+        let first = |x: F| x * x + x.double() + F::from_canonical_u32(3);
+        let second = |x: F| x * x * F::from_canonical_u32(3) + x + F::from_canonical_u32(7);
+        let evals = F::two_adic_generator(log_height)
+            .shifted_powers(shift)
+            .take(1 << log_height)
+            .flat_map(|x| [first(x), second(x)])
+            .collect();
+        let evals_mat = RowMajorMatrix::new(evals, 2);
+
+        let evaluator = BarycentricEvaluator::new(log_height, shift);
+        let point = F::from_canonical_u32(100);
+        assert_eq!(
+            evaluator.evaluate(&evals_mat, point),
+            vec![F::from_canonical_u32(10203), F::from_canonical_u32(30107)]
+        );
+
+        let points: Vec<EF> = (0..5)
+            .map(|i| EF::from_base_fn(|j| F::from_canonical_usize(100 * i + j + 1)))
+            .collect();
+        let batch = evaluator.evaluate_batch(&evals_mat, &points);
+        assert_eq!(batch.len(), points.len());
+        for (&point, values) in points.iter().zip(batch) {
+            assert_eq!(values, evaluator.evaluate(&evals_mat, point));
+            assert_eq!(values, interpolate_coset(&evals_mat, shift, point, None));
+        }
+        assert!(evaluator
+            .evaluate_batch::<EF, _>(&evals_mat, &[])
+            .is_empty());
+    }
 }
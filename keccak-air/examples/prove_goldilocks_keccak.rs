@@ -6,10 +6,10 @@ use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
 use p3_fri::{create_benchmark_fri_config, TwoAdicFriPcs};
 use p3_goldilocks::Goldilocks;
-use p3_keccak::{Keccak256Hash, KeccakF};
+use p3_keccak::{Keccak256Hash, KeccakCompression, KeccakF};
 use p3_keccak_air::{generate_trace_rows, KeccakAir};
 use p3_merkle_tree::MerkleTreeMmcs;
-use p3_symmetric::{CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher64};
+use p3_symmetric::{PaddingFreeSponge, SerializingHasher64};
 use p3_uni_stark::{prove, verify, StarkConfig};
 use rand::random;
 use tracing_forest::util::LevelFilter;
@@ -40,8 +40,8 @@ fn main() -> Result<(), impl Debug> {
     let u64_hash = U64Hash::new(KeccakF {});
     let field_hash = FieldHash::new(u64_hash);
 
-    type MyCompress = CompressionFunctionFromHasher<U64Hash, 2, 4>;
-    let compress = MyCompress::new(u64_hash);
+    type MyCompress = KeccakCompression;
+    let compress = MyCompress::new(KeccakF);
 
     type ValMmcs = MerkleTreeMmcs<
         [Val; p3_keccak::VECTOR_LEN],
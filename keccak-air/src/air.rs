@@ -33,12 +33,24 @@ impl<F> BaseAir<F> for KeccakAir {
 impl<AB: AirBuilder> Air<AB> for KeccakAir {
     #[inline]
     fn eval(&self, builder: &mut AB) {
-        eval_round_flags(builder);
-
         let main = builder.main();
         let (local, next) = (main.row_slice(0), main.row_slice(1));
         let local: &KeccakCols<AB::Var> = (*local).borrow();
         let next: &KeccakCols<AB::Var> = (*next).borrow();
+        self.eval_permutation(builder, local, next);
+    }
+}
+
+impl KeccakAir {
+    /// Verify that `local` is a correct round of a permutation, whose next round, unless `local`
+    /// is its final round, is `next`.
+    pub(crate) fn eval_permutation<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &KeccakCols<AB::Var>,
+        next: &KeccakCols<AB::Var>,
+    ) {
+        eval_round_flags(builder, local, next);
 
         let first_step = local.step_flags[0];
         let final_step = local.step_flags[NUM_ROUNDS - 1];
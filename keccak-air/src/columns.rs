@@ -4,7 +4,7 @@ use core::mem::{size_of, transmute};
 use p3_util::indices_arr;
 
 use crate::constants::R;
-use crate::{NUM_ROUNDS, RATE_LIMBS, U64_LIMBS};
+use crate::{NUM_ROUNDS, RATE_BYTES, RATE_LANES, RATE_LIMBS, U64_LIMBS};
 
 /// Note: The ordering of each array is based on the input mapping. As the spec says,
 ///
@@ -146,3 +146,150 @@ impl<T> BorrowMut<KeccakCols<T>> for [T] {
         &mut shorts[0]
     }
 }
+
+/// Columns for a Keccak sponge AIR, in which consecutive permutations may chain their states, with
+/// the rate of each absorbing a block of the input.
+///
+/// Blocks are given as limbs in the same order as the rate of the state. As the `KeccakCols` come
+/// first, `input_limb` and `output_limb` give the columns of the preimage and output here too.
+#[derive(Debug)]
+#[repr(C)]
+pub struct KeccakSpongeCols<T> {
+    pub keccak: KeccakCols<T>,
+
+    /// Whether this permutation absorbs a block of an input, rather than padding the trace.
+    pub is_real: T,
+
+    /// Set to 1 in the first step of a real permutation, when its block may be imported.
+    pub import: T,
+
+    /// The block absorbed by this permutation.
+    pub block: [[T; U64_LIMBS]; RATE_LANES],
+
+    /// Set to 1 in the final step of a permutation if the next permutation continues the same
+    /// sponge, rather than starting a new one.
+    pub chain: T,
+
+    /// The bits of the rate of the output of this round.
+    pub output_bits: [[T; 64]; RATE_LANES],
+
+    /// In the final step of a permutation which chains, the bits of the next block.
+    pub next_block_bits: [[T; 64]; RATE_LANES],
+
+    /// The xor of the rate of the output of this round with `next_block_bits`, which is the rate
+    /// of the next preimage when this permutation chains.
+    pub absorbed: [[T; U64_LIMBS]; RATE_LANES],
+}
+
+pub const NUM_KECCAK_SPONGE_COLS: usize = size_of::<KeccakSpongeCols<u8>>();
+pub(crate) const KECCAK_SPONGE_COL_MAP: KeccakSpongeCols<usize> = make_sponge_col_map();
+
+const fn make_sponge_col_map() -> KeccakSpongeCols<usize> {
+    let indices_arr = indices_arr::<NUM_KECCAK_SPONGE_COLS>();
+    unsafe { transmute::<[usize; NUM_KECCAK_SPONGE_COLS], KeccakSpongeCols<usize>>(indices_arr) }
+}
+
+/// The column of the `i`th limb of the block absorbed by a permutation of a `KeccakSpongeAir`.
+pub fn sponge_block_limb(i: usize) -> usize {
+    debug_assert!(i < RATE_LIMBS);
+    KECCAK_SPONGE_COL_MAP.block[i / U64_LIMBS][i % U64_LIMBS]
+}
+
+impl<T> Borrow<KeccakCols<T>> for KeccakSpongeCols<T> {
+    fn borrow(&self) -> &KeccakCols<T> {
+        &self.keccak
+    }
+}
+
+impl<T> BorrowMut<KeccakCols<T>> for KeccakSpongeCols<T> {
+    fn borrow_mut(&mut self) -> &mut KeccakCols<T> {
+        &mut self.keccak
+    }
+}
+
+impl<T> Borrow<KeccakSpongeCols<T>> for [T] {
+    fn borrow(&self) -> &KeccakSpongeCols<T> {
+        debug_assert_eq!(self.len(), NUM_KECCAK_SPONGE_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<KeccakSpongeCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T> BorrowMut<KeccakSpongeCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut KeccakSpongeCols<T> {
+        debug_assert_eq!(self.len(), NUM_KECCAK_SPONGE_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<KeccakSpongeCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
+
+/// Columns for a Keccak-256 AIR, which extend those of the sponge with the bits of each block and
+/// the position of its padding.
+#[derive(Debug)]
+#[repr(C)]
+pub struct Keccak256HashCols<T> {
+    pub sponge: KeccakSpongeCols<T>,
+
+    /// Whether this permutation absorbs the last block of a message, which holds its padding.
+    pub is_last_block: T,
+
+    /// The bits of the block absorbed by this permutation.
+    pub block_bits: [[T; 64]; RATE_LANES],
+
+    /// Whether each byte of the block is padding rather than part of the message.
+    pub is_padding: [T; RATE_BYTES],
+}
+
+pub const NUM_KECCAK_256_HASH_COLS: usize = size_of::<Keccak256HashCols<u8>>();
+
+impl<T> Borrow<KeccakCols<T>> for Keccak256HashCols<T> {
+    fn borrow(&self) -> &KeccakCols<T> {
+        &self.sponge.keccak
+    }
+}
+
+impl<T> BorrowMut<KeccakCols<T>> for Keccak256HashCols<T> {
+    fn borrow_mut(&mut self) -> &mut KeccakCols<T> {
+        &mut self.sponge.keccak
+    }
+}
+
+impl<T> Borrow<KeccakSpongeCols<T>> for Keccak256HashCols<T> {
+    fn borrow(&self) -> &KeccakSpongeCols<T> {
+        &self.sponge
+    }
+}
+
+impl<T> BorrowMut<KeccakSpongeCols<T>> for Keccak256HashCols<T> {
+    fn borrow_mut(&mut self) -> &mut KeccakSpongeCols<T> {
+        &mut self.sponge
+    }
+}
+
+impl<T> Borrow<Keccak256HashCols<T>> for [T] {
+    fn borrow(&self) -> &Keccak256HashCols<T> {
+        debug_assert_eq!(self.len(), NUM_KECCAK_256_HASH_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Keccak256HashCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T> BorrowMut<Keccak256HashCols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut Keccak256HashCols<T> {
+        debug_assert_eq!(self.len(), NUM_KECCAK_256_HASH_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Keccak256HashCols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
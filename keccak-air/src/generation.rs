@@ -1,4 +1,6 @@
 use alloc::vec::Vec;
+use core::array;
+use core::borrow::{Borrow, BorrowMut};
 
 use p3_air::utils::{checked_andn, checked_xor};
 use p3_field::PrimeField64;
@@ -7,9 +9,12 @@ use p3_maybe_rayon::iter::repeat;
 use p3_maybe_rayon::prelude::*;
 use tracing::instrument;
 
-use crate::columns::{KeccakCols, NUM_KECCAK_COLS};
+use crate::columns::{
+    Keccak256HashCols, KeccakCols, KeccakSpongeCols, NUM_KECCAK_256_HASH_COLS, NUM_KECCAK_COLS,
+    NUM_KECCAK_SPONGE_COLS,
+};
 use crate::constants::rc_value_limb;
-use crate::{BITS_PER_LIMB, NUM_ROUNDS, U64_LIMBS};
+use crate::{BITS_PER_LIMB, NUM_ROUNDS, RATE_BYTES, RATE_LANES, U64_LIMBS};
 
 // TODO: Take generic iterable
 #[instrument(name = "generate Keccak trace", skip_all)]
@@ -29,16 +34,216 @@ pub fn generate_trace_rows<F: PrimeField64>(inputs: Vec<[u64; 25]>) -> RowMajorM
     rows.par_chunks_mut(NUM_ROUNDS)
         .zip(padded_inputs)
         .for_each(|(row, input)| {
-            generate_trace_rows_for_perm(row, input);
+            generate_trace_rows_for_perm::<F, _>(row, input);
         });
 
     trace
 }
 
+/// Generates a trace for `KeccakSpongeAir` absorbing each of `inputs`, a non-empty sequence of
+/// blocks which the caller has already padded, and exporting the output of its final permutation.
+/// The trace is padded to a power of two height with permutations of zero.
+#[instrument(name = "generate Keccak sponge trace", skip_all)]
+pub fn generate_sponge_trace_rows<F: PrimeField64>(
+    inputs: &[Vec<[u64; RATE_LANES]>],
+) -> RowMajorMatrix<F> {
+    let num_real_perms: usize = inputs.iter().map(Vec::len).sum();
+    let num_rows = (num_real_perms * NUM_ROUNDS).next_power_of_two();
+    let mut trace = RowMajorMatrix::new(
+        F::zero_vec(num_rows * NUM_KECCAK_SPONGE_COLS),
+        NUM_KECCAK_SPONGE_COLS,
+    );
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<KeccakSpongeCols<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), num_rows);
+
+    let mut perms = rows.chunks_mut(NUM_ROUNDS);
+    for blocks in inputs {
+        assert!(
+            !blocks.is_empty(),
+            "each input must have at least one block"
+        );
+        let mut state = [0; 25];
+        for (i, block) in blocks.iter().enumerate() {
+            for (lane, &word) in state.iter_mut().zip(block) {
+                *lane ^= word;
+            }
+            let rows = perms.next().unwrap();
+            let next_block = blocks.get(i + 1);
+            state = generate_sponge_rows_for_perm::<F, _>(rows, state, block, next_block, true);
+            if next_block.is_none() {
+                rows[NUM_ROUNDS - 1].keccak.export = F::ONE;
+            }
+        }
+    }
+
+    perms.collect::<Vec<_>>().into_par_iter().for_each(|rows| {
+        generate_sponge_rows_for_perm::<F, _>(rows, [0; 25], &[0; RATE_LANES], None, false);
+    });
+
+    trace
+}
+
+/// Pads `message` as in Keccak-256, with a `0x01` byte, then zeros up to the end of a block, with
+/// the high bit of its last byte set, and splits it into blocks of little-endian lanes.
+pub fn keccak_256_blocks(message: &[u8]) -> Vec<[u64; RATE_LANES]> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x01);
+    bytes.resize(bytes.len().next_multiple_of(RATE_BYTES), 0);
+    *bytes.last_mut().unwrap() |= 0x80;
+    bytes
+        .chunks_exact(RATE_BYTES)
+        .map(|block| array::from_fn(|i| u64::from_le_bytes(array::from_fn(|j| block[8 * i + j]))))
+        .collect()
+}
+
+/// Generates a trace for `Keccak256HashAir` hashing each of `messages`, with the hashes of the
+/// first `num_public_digests` messages public. The trace is padded to a power of two height with
+/// permutations of zero.
+#[instrument(name = "generate Keccak-256 trace", skip_all)]
+pub fn generate_hash_trace_rows<F: PrimeField64>(
+    messages: &[&[u8]],
+    num_public_digests: usize,
+) -> RowMajorMatrix<F> {
+    assert!(
+        num_public_digests <= messages.len(),
+        "each public digest must be the hash of a message"
+    );
+    let inputs: Vec<_> = messages
+        .iter()
+        .map(|message| keccak_256_blocks(message))
+        .collect();
+    let num_real_perms: usize = inputs.iter().map(Vec::len).sum();
+    let num_rows = (num_real_perms * NUM_ROUNDS).next_power_of_two();
+    let mut hash_trace = RowMajorMatrix::new(
+        F::zero_vec(num_rows * NUM_KECCAK_256_HASH_COLS),
+        NUM_KECCAK_256_HASH_COLS,
+    );
+    let (prefix, rows, suffix) =
+        unsafe { hash_trace.values.align_to_mut::<Keccak256HashCols<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), num_rows);
+
+    let mut perms = rows.chunks_mut(NUM_ROUNDS);
+    for (message, blocks) in messages.iter().zip(&inputs) {
+        let mut state = [0; 25];
+        for (i, block) in blocks.iter().enumerate() {
+            for (lane, &word) in state.iter_mut().zip(block) {
+                *lane ^= word;
+            }
+            let rows = perms.next().unwrap();
+            let next_block = blocks.get(i + 1);
+            state = generate_sponge_rows_for_perm::<F, _>(rows, state, block, next_block, true);
+
+            let is_last_block = next_block.is_none();
+            let num_padding_bytes = if is_last_block {
+                blocks.len() * RATE_BYTES - message.len()
+            } else {
+                0
+            };
+            for row in rows.iter_mut() {
+                row.is_last_block = F::from_bool(is_last_block);
+                row.block_bits =
+                    block.map(|lane| array::from_fn(|z| F::from_bool((lane >> z) & 1 != 0)));
+                for is_padding in &mut row.is_padding[RATE_BYTES - num_padding_bytes..] {
+                    *is_padding = F::ONE;
+                }
+            }
+            if is_last_block {
+                rows[NUM_ROUNDS - 1].sponge.keccak.export = F::ONE;
+            }
+        }
+    }
+
+    perms.collect::<Vec<_>>().into_par_iter().for_each(|rows| {
+        generate_sponge_rows_for_perm::<F, _>(rows, [0; 25], &[0; RATE_LANES], None, false);
+    });
+
+    if num_public_digests == 0 {
+        return hash_trace;
+    }
+
+    // Append the one-hot index of the message of each row among those with public digests.
+    let width = NUM_KECCAK_256_HASH_COLS + num_public_digests;
+    let mut trace = RowMajorMatrix::new(F::zero_vec(num_rows * width), width);
+    for (row, hash_row) in trace
+        .values
+        .chunks_exact_mut(width)
+        .zip(hash_trace.values.chunks_exact(NUM_KECCAK_256_HASH_COLS))
+    {
+        row[..NUM_KECCAK_256_HASH_COLS].copy_from_slice(hash_row);
+    }
+    let mut start = 0;
+    for (message_index, blocks) in inputs[..num_public_digests].iter().enumerate() {
+        let end = start + blocks.len() * NUM_ROUNDS;
+        for row in start..end {
+            trace.values[row * width + NUM_KECCAK_256_HASH_COLS + message_index] = F::ONE;
+        }
+        start = end;
+    }
+
+    trace
+}
+
+/// Fills in the rows of a permutation of `preimage`, which absorbed `block`, in a sponge
+/// continuing with `next_block` if any, and returns its output.
+fn generate_sponge_rows_for_perm<F, R>(
+    rows: &mut [R],
+    preimage: [u64; 25],
+    block: &[u64; RATE_LANES],
+    next_block: Option<&[u64; RATE_LANES]>,
+    is_real: bool,
+) -> [u64; 25]
+where
+    F: PrimeField64,
+    R: BorrowMut<KeccakSpongeCols<F>> + BorrowMut<KeccakCols<F>>,
+{
+    generate_trace_rows_for_perm::<F, _>(rows, preimage);
+
+    for (step, row) in rows.iter_mut().enumerate() {
+        let row: &mut KeccakSpongeCols<F> = row.borrow_mut();
+        let is_final_step = step == NUM_ROUNDS - 1;
+        let next_block = next_block.filter(|_| is_final_step);
+        row.is_real = F::from_bool(is_real);
+        row.import = F::from_bool(is_real && step == 0);
+        row.chain = F::from_bool(next_block.is_some());
+        let next_block = next_block.copied().unwrap_or([0; RATE_LANES]);
+        for lane in 0..RATE_LANES {
+            let output = output_lane(&row.keccak, lane);
+            row.block[lane] = u64_to_limbs(block[lane]);
+            row.output_bits[lane] = array::from_fn(|z| F::from_bool((output >> z) & 1 != 0));
+            row.next_block_bits[lane] =
+                array::from_fn(|z| F::from_bool((next_block[lane] >> z) & 1 != 0));
+            row.absorbed[lane] = u64_to_limbs(output ^ next_block[lane]);
+        }
+    }
+
+    let last_row: &KeccakCols<F> = rows.last().unwrap().borrow();
+    array::from_fn(|lane| output_lane(last_row, lane))
+}
+
+/// The `lane`th word of the output of the round in `row`.
+fn output_lane<F: PrimeField64>(row: &KeccakCols<F>, lane: usize) -> u64 {
+    (0..U64_LIMBS).fold(0, |acc, limb| {
+        let limb_value = row.a_prime_prime_prime(lane / 5, lane % 5, limb);
+        acc | (limb_value.as_canonical_u64() << (limb * BITS_PER_LIMB))
+    })
+}
+
+fn u64_to_limbs<F: PrimeField64>(value: u64) -> [F; U64_LIMBS] {
+    array::from_fn(|limb| F::from_canonical_u16((value >> (limb * BITS_PER_LIMB)) as u16))
+}
+
 /// `rows` will normally consist of 24 rows, with an exception for the final row.
-fn generate_trace_rows_for_perm<F: PrimeField64>(rows: &mut [KeccakCols<F>], input: [u64; 25]) {
+fn generate_trace_rows_for_perm<F: PrimeField64, R: BorrowMut<KeccakCols<F>>>(
+    rows: &mut [R],
+    input: [u64; 25],
+) {
     // Populate the preimage for each row.
     for row in rows.iter_mut() {
+        let row = row.borrow_mut();
         for y in 0..5 {
             for x in 0..5 {
                 let input_xy = input[y * 5 + x];
@@ -51,28 +256,28 @@ fn generate_trace_rows_for_perm<F: PrimeField64>(rows: &mut [KeccakCols<F>], inp
     }
 
     // Populate the round input for the first round.
+    let first_row = rows[0].borrow_mut();
     for y in 0..5 {
         for x in 0..5 {
             let input_xy = input[y * 5 + x];
             for limb in 0..U64_LIMBS {
-                rows[0].a[y][x][limb] = F::from_canonical_u64((input_xy >> (16 * limb)) & 0xFFFF);
+                first_row.a[y][x][limb] = F::from_canonical_u64((input_xy >> (16 * limb)) & 0xFFFF);
             }
         }
     }
 
-    generate_trace_row_for_round(&mut rows[0], 0);
+    generate_trace_row_for_round(first_row, 0);
 
     for round in 1..rows.len() {
         // Copy previous row's output to next row's input.
-        for y in 0..5 {
-            for x in 0..5 {
-                for limb in 0..U64_LIMBS {
-                    rows[round].a[y][x][limb] = rows[round - 1].a_prime_prime_prime(y, x, limb);
-                }
-            }
-        }
+        let previous_row: &KeccakCols<F> = rows[round - 1].borrow();
+        let a = array::from_fn(|y| {
+            array::from_fn(|x| array::from_fn(|limb| previous_row.a_prime_prime_prime(y, x, limb)))
+        });
+        let row = rows[round].borrow_mut();
+        row.a = a;
 
-        generate_trace_row_for_round(&mut rows[round], round);
+        generate_trace_row_for_round(row, round);
     }
 }
 
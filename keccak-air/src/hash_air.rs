@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues};
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+
+use crate::columns::{Keccak256HashCols, NUM_KECCAK_256_HASH_COLS};
+use crate::sponge_air::eval_sponge;
+use crate::{BITS_PER_LIMB, DIGEST_BYTES, NUM_ROUNDS, RATE_BYTES, U64_LIMBS};
+
+/// An AIR for the Keccak-256 hashes of messages of any length.
+///
+/// Each message is padded as in Keccak-256, with a `0x01` byte, then zeros up to the end of a
+/// block, with the high bit of its last byte set, and absorbed one block per permutation, as in
+/// `KeccakSpongeAir`. The padding is checked here, so that only the last block of a message holds
+/// padding, and the output of its final step, which is exported, begins with the hash of the
+/// message.
+///
+/// The hashes of the first `num_public_digests` messages are public values, each given as its
+/// `DIGEST_BYTES` bytes. To locate them, the trace has `num_public_digests` columns after the
+/// `Keccak256HashCols`, which one-hot encode the index of the message of each row among those
+/// whose hashes are public.
+///
+/// Assumes the field size is at least 16 bits.
+#[derive(Debug, Default)]
+pub struct Keccak256HashAir {
+    pub num_public_digests: usize,
+}
+
+impl Keccak256HashAir {
+    pub const fn new(num_public_digests: usize) -> Self {
+        Self { num_public_digests }
+    }
+
+    /// The public values proving that the first messages of the trace have the given hashes.
+    pub fn public_values<F: FieldAlgebra>(digests: &[[u8; DIGEST_BYTES]]) -> Vec<F> {
+        digests
+            .iter()
+            .flatten()
+            .map(|&byte| F::from_canonical_u8(byte))
+            .collect()
+    }
+}
+
+impl<F> BaseAir<F> for Keccak256HashAir {
+    fn width(&self) -> usize {
+        NUM_KECCAK_256_HASH_COLS + self.num_public_digests
+    }
+}
+
+impl<F> BaseAirWithPublicValues<F> for Keccak256HashAir {
+    fn num_public_values(&self) -> usize {
+        self.num_public_digests * DIGEST_BYTES
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Keccak256HashAir {
+    #[inline]
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (local, local_digest_index) = local.split_at(NUM_KECCAK_256_HASH_COLS);
+        let (next, next_digest_index) = next.split_at(NUM_KECCAK_256_HASH_COLS);
+        let local: &Keccak256HashCols<AB::Var> = local.borrow();
+        let next: &Keccak256HashCols<AB::Var> = next.borrow();
+        let sponge = &local.sponge;
+
+        eval_sponge(builder, sponge, &next.sponge);
+
+        let final_step = sponge.keccak.step_flags[NUM_ROUNDS - 1];
+        let export = sponge.keccak.export;
+
+        // A message ends with its last block, which is exported, and the sponge continues
+        // otherwise. The last permutation of the trace must end, so that every message does.
+        builder.assert_bool(local.is_last_block);
+        builder.when(local.is_last_block).assert_one(sponge.is_real);
+        builder
+            .when_transition()
+            .when(AB::Expr::ONE - final_step)
+            .assert_eq(next.is_last_block, local.is_last_block);
+        builder
+            .when(final_step)
+            .assert_eq(sponge.chain, sponge.is_real - local.is_last_block);
+        builder.assert_eq(export, final_step * local.is_last_block);
+        let mut when_last_row = builder.when_last_row();
+        when_last_row.assert_zero(sponge.chain);
+        when_last_row.when(sponge.is_real).assert_one(final_step);
+
+        // The block is given by its bits.
+        for (lane, bits) in local.block_bits.iter().enumerate() {
+            for &bit in bits {
+                builder.assert_bool(bit);
+            }
+            for limb in 0..U64_LIMBS {
+                let packed = bits[limb * BITS_PER_LIMB..(limb + 1) * BITS_PER_LIMB]
+                    .iter()
+                    .rev()
+                    .fold(AB::Expr::ZERO, |acc, &bit| acc.double() + bit);
+                builder.assert_eq(sponge.block[lane][limb], packed);
+            }
+        }
+
+        // The padding is a suffix of the block, which is not empty exactly in the last block.
+        for &is_padding in &local.is_padding {
+            builder.assert_bool(is_padding);
+        }
+        for pair in local.is_padding.windows(2) {
+            builder.when(pair[0]).assert_one(pair[1]);
+        }
+        builder.assert_eq(local.is_padding[RATE_BYTES - 1], local.is_last_block);
+
+        // The first byte of padding is `0x01`, and the others zero, but for the high bit of the
+        // last byte, which is set.
+        for (byte, &is_padding) in local.is_padding.iter().enumerate() {
+            let bits = &local.block_bits[byte / 8][8 * (byte % 8)..8 * (byte % 8 + 1)];
+            let is_first_padding: AB::Expr = if byte == 0 {
+                is_padding.into()
+            } else {
+                is_padding - local.is_padding[byte - 1]
+            };
+            let mut when_padding = builder.when(is_padding);
+            when_padding.assert_eq(bits[0], is_first_padding);
+            for &bit in &bits[1..7] {
+                when_padding.assert_zero(bit);
+            }
+            if byte == RATE_BYTES - 1 {
+                when_padding.assert_one(bits[7]);
+            } else {
+                when_padding.assert_zero(bits[7]);
+            }
+        }
+
+        if self.num_public_digests == 0 {
+            return;
+        }
+
+        // The digest index starts at the first message, and moves to the next at the end of each
+        // message, until it passes the last public digest. It must have reached the last public
+        // digest by the end of the trace, and only be set for real permutations, so each public
+        // digest is the hash of some message.
+        let n = self.num_public_digests;
+        for &is_index in local_digest_index {
+            builder.assert_bool(is_index);
+            builder
+                .when(AB::Expr::ONE - sponge.is_real)
+                .assert_zero(is_index);
+        }
+        builder.when_first_row().assert_one(local_digest_index[0]);
+        for &is_index in &local_digest_index[..n - 1] {
+            builder.when_last_row().assert_zero(is_index);
+        }
+        let message_ends = final_step - sponge.chain;
+        let mut when_transition = builder.when_transition();
+        let mut when_message_continues = when_transition.when(AB::Expr::ONE - message_ends.clone());
+        for (&next_is_index, &is_index) in next_digest_index.iter().zip(local_digest_index) {
+            when_message_continues.assert_eq(next_is_index, is_index);
+        }
+        let mut when_message_ends = when_transition.when(message_ends);
+        when_message_ends.assert_zero(next_digest_index[0]);
+        for i in 0..n - 1 {
+            when_message_ends.assert_eq(next_digest_index[i + 1], local_digest_index[i]);
+        }
+
+        // The output of the last block of each message with a public digest begins with that
+        // digest, each limb of which holds two bytes.
+        let public_values = builder.public_values();
+        let digests: Vec<AB::Expr> = public_values.iter().map(|&x| x.into()).collect();
+        let base = AB::Expr::from_canonical_u32(1 << 8);
+        for (i, &is_index) in local_digest_index.iter().enumerate() {
+            let digest = &digests[i * DIGEST_BYTES..(i + 1) * DIGEST_BYTES];
+            let mut when_digest = builder.when(is_index * export);
+            for (j, bytes) in digest.chunks_exact(2).enumerate() {
+                let (lane, limb) = (j / U64_LIMBS, j % U64_LIMBS);
+                when_digest.assert_eq(
+                    sponge.keccak.a_prime_prime_prime(0, lane, limb),
+                    bytes[0].clone() + bytes[1].clone() * base.clone(),
+                );
+            }
+        }
+    }
+}
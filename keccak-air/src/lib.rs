@@ -1,4 +1,5 @@
-//! An AIR for the Keccak-f permutation. Assumes the field size is between 2^16 and 2^32.
+//! AIRs for the Keccak-f permutation, the Keccak sponge and Keccak-256. Assumes the field size is between 2^16
+//! and 2^32.
 
 #![no_std]
 
@@ -8,15 +9,25 @@ mod air;
 mod columns;
 mod constants;
 mod generation;
+mod hash_air;
 mod round_flags;
+mod sponge_air;
 
 pub use air::*;
 pub use columns::*;
 pub use constants::*;
 pub use generation::*;
+pub use hash_air::*;
+pub use sponge_air::*;
 
 pub const NUM_ROUNDS: usize = 24;
 const BITS_PER_LIMB: usize = 16;
 pub const U64_LIMBS: usize = 64 / BITS_PER_LIMB;
 const RATE_BITS: usize = 1088;
+/// The number of lanes, i.e. 64-bit words, in the rate of the sponge.
+pub const RATE_LANES: usize = RATE_BITS / 64;
 const RATE_LIMBS: usize = RATE_BITS / BITS_PER_LIMB;
+/// The number of bytes in the rate of the sponge, i.e. in a block of a message.
+pub const RATE_BYTES: usize = RATE_BITS / 8;
+/// The number of bytes in a Keccak-256 hash.
+pub const DIGEST_BYTES: usize = 32;
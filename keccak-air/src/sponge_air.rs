@@ -0,0 +1,195 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::utils::{andn, xor};
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::Matrix;
+
+use crate::columns::{
+    output_limb, sponge_block_limb, KeccakSpongeCols, KECCAK_COL_MAP, KECCAK_SPONGE_COL_MAP,
+    NUM_KECCAK_SPONGE_COLS,
+};
+use crate::constants::rc_value_bit;
+use crate::{KeccakAir, BITS_PER_LIMB, NUM_ROUNDS, RATE_LANES, RATE_LIMBS, U64_LIMBS};
+
+/// An AIR for the Keccak sponge, absorbing inputs of any number of blocks.
+///
+/// Each permutation takes `NUM_ROUNDS` rows, as in `KeccakAir`. A permutation either starts a new
+/// sponge, with its block as the rate of its preimage and a zero capacity, or continues the
+/// previous one, with the xor of its block and the rate of the previous output as the rate of its
+/// preimage, and the capacity of the previous output as its capacity. Padding the input into
+/// blocks is left to the caller.
+///
+/// The blocks and outputs can be connected to another AIR by lookups: the `import` rows send their
+/// block on `block_bus`, and the `export` rows send the rate of their output on `output_bus`.
+///
+/// Assumes the field size is at least 16 bits.
+#[derive(Debug)]
+pub struct KeccakSpongeAir {
+    pub block_bus: usize,
+    pub output_bus: usize,
+}
+
+impl<F> BaseAir<F> for KeccakSpongeAir {
+    fn width(&self) -> usize {
+        NUM_KECCAK_SPONGE_COLS
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for KeccakSpongeAir {
+    #[inline]
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &KeccakSpongeCols<AB::Var> = (*local).borrow();
+        let next: &KeccakSpongeCols<AB::Var> = (*next).borrow();
+        eval_sponge(builder, local, next);
+    }
+}
+
+/// Verify that `local` is a correct round of a sponge, whose next round is `next`.
+pub(crate) fn eval_sponge<AB: AirBuilder>(
+    builder: &mut AB,
+    local: &KeccakSpongeCols<AB::Var>,
+    next: &KeccakSpongeCols<AB::Var>,
+) {
+    KeccakAir {}.eval_permutation(builder, &local.keccak, &next.keccak);
+
+    let first_step = local.keccak.step_flags[0];
+    let final_step = local.keccak.step_flags[NUM_ROUNDS - 1];
+    let not_final_step = AB::Expr::ONE - final_step;
+
+    // The flags are boolean, and the permutation, its reality and its block do not change
+    // between steps.
+    builder.assert_bool(local.is_real);
+    builder.assert_eq(local.import, first_step * local.is_real);
+    builder.assert_bool(local.chain);
+    builder
+        .when(not_final_step.clone())
+        .assert_zero(local.chain);
+    builder.when(local.chain).assert_one(local.is_real);
+    builder.when(local.keccak.export).assert_one(local.is_real);
+    builder.when(local.keccak.export).assert_zero(local.chain);
+    let mut when_same_permutation = builder.when_transition();
+    let mut when_same_permutation = when_same_permutation.when(not_final_step);
+    when_same_permutation.assert_eq(next.is_real, local.is_real);
+    for (next_limbs, limbs) in next.block.iter().zip(&local.block) {
+        for (&next_limb, &limb) in next_limbs.iter().zip(limbs) {
+            when_same_permutation.assert_eq(next_limb, limb);
+        }
+    }
+
+    // The output bits are those of the rate of this round's output.
+    for (lane, bits) in local.output_bits.iter().enumerate() {
+        let (y, x) = (lane / 5, lane % 5);
+        for (z, &bit) in bits.iter().enumerate() {
+            let computed_bit = if (y, x) == (0, 0) {
+                let rc_bit = (0..NUM_ROUNDS).fold(AB::Expr::ZERO, |acc, r| {
+                    acc + local.keccak.step_flags[r]
+                        * AB::Expr::from_canonical_u8(rc_value_bit(r, z))
+                });
+                xor::<AB::Expr>(local.keccak.a_prime_prime_0_0_bits[z].into(), rc_bit)
+            } else {
+                let andn = andn::<AB::Expr>(
+                    local.keccak.b((x + 1) % 5, y, z).into(),
+                    local.keccak.b((x + 2) % 5, y, z).into(),
+                );
+                xor::<AB::Expr>(local.keccak.b(x, y, z).into(), andn)
+            };
+            builder.assert_eq(bit, computed_bit);
+        }
+    }
+
+    // The absorbed limbs are the xor of the output bits with the next block.
+    for (lane, limbs) in local.absorbed.iter().enumerate() {
+        for (limb, &absorbed_limb) in limbs.iter().enumerate() {
+            let computed_limb = (limb * BITS_PER_LIMB..(limb + 1) * BITS_PER_LIMB)
+                .rev()
+                .fold(AB::Expr::ZERO, |acc, z| {
+                    let next_block_bit = local.next_block_bits[lane][z];
+                    builder.assert_bool(next_block_bit);
+                    acc.double()
+                        + xor::<AB::Expr>(local.output_bits[lane][z].into(), next_block_bit.into())
+                });
+            builder.assert_eq(absorbed_limb, computed_limb);
+        }
+    }
+
+    // A new sponge starts from its block and a zero capacity.
+    let is_first_row = builder.is_first_row();
+    eval_sponge_start(builder, is_first_row, &local.keccak.preimage, &local.block);
+    let next_starts_sponge = builder.is_transition() * (final_step - local.chain);
+    eval_sponge_start(
+        builder,
+        next_starts_sponge,
+        &next.keccak.preimage,
+        &next.block,
+    );
+
+    // A permutation which chains absorbs the next block into its output, which becomes the
+    // preimage of the next permutation.
+    let mut when_chain = builder.when_transition();
+    let mut when_chain = when_chain.when(local.chain);
+    for lane in 0..25 {
+        let (y, x) = (lane / 5, lane % 5);
+        for limb in 0..U64_LIMBS {
+            let next_preimage_limb = next.keccak.preimage[y][x][limb];
+            if lane < RATE_LANES {
+                when_chain.assert_eq(next_preimage_limb, local.absorbed[lane][limb]);
+                let next_block_limb = (limb * BITS_PER_LIMB..(limb + 1) * BITS_PER_LIMB)
+                    .rev()
+                    .fold(AB::Expr::ZERO, |acc, z| {
+                        acc.double() + local.next_block_bits[lane][z]
+                    });
+                when_chain.assert_eq(next.block[lane][limb], next_block_limb);
+            } else {
+                when_chain.assert_eq(
+                    next_preimage_limb,
+                    local.keccak.a_prime_prime_prime(y, x, limb),
+                );
+            }
+        }
+    }
+}
+
+/// Verify that, if `condition` holds, `preimage` starts a new sponge absorbing `block`.
+fn eval_sponge_start<AB: AirBuilder>(
+    builder: &mut AB,
+    condition: AB::Expr,
+    preimage: &[[[AB::Var; U64_LIMBS]; 5]; 5],
+    block: &[[AB::Var; U64_LIMBS]; RATE_LANES],
+) {
+    let mut builder = builder.when(condition);
+    for (lane, limbs) in preimage.iter().flatten().enumerate() {
+        for (limb, &preimage_limb) in limbs.iter().enumerate() {
+            if lane < RATE_LANES {
+                builder.assert_eq(preimage_limb, block[lane][limb]);
+            } else {
+                builder.assert_zero(preimage_limb);
+            }
+        }
+    }
+}
+
+impl<F: Field> LookupAir<F> for KeccakSpongeAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        vec![
+            Interaction::send(
+                (0..RATE_LIMBS)
+                    .map(|i| VirtualPairCol::single_main(sponge_block_limb(i)))
+                    .collect(),
+                VirtualPairCol::single_main(KECCAK_SPONGE_COL_MAP.import),
+                self.block_bus,
+            ),
+            Interaction::send(
+                (0..RATE_LIMBS)
+                    .map(|i| VirtualPairCol::single_main(output_limb(i)))
+                    .collect(),
+                VirtualPairCol::single_main(KECCAK_COL_MAP.export),
+                self.output_bus,
+            ),
+        ]
+    }
+}
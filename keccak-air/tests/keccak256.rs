@@ -0,0 +1,73 @@
+use core::borrow::BorrowMut;
+
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_keccak::Keccak256Hash;
+use p3_keccak_air::{
+    generate_hash_trace_rows, keccak_256_blocks, output_limb, Keccak256HashAir, Keccak256HashCols,
+    NUM_KECCAK_256_HASH_COLS, NUM_ROUNDS,
+};
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{debug_constraints, prove, verify};
+use rand::thread_rng;
+
+/// Messages whose padding is a whole block, a single byte, a whole block after a full block, and
+/// neither.
+fn messages() -> Vec<Vec<u8>> {
+    [0, 135, 136, 300]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (i * 5 + len) as u8).collect())
+        .collect()
+}
+
+#[test]
+fn test_keccak256_hash_air() {
+    let messages = messages();
+    let message_slices = messages.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let air = Keccak256HashAir::new(3);
+    let trace = generate_hash_trace_rows::<Val>(&message_slices, 3);
+
+    // The output of the final permutation of each message begins with its hash.
+    let digests = messages
+        .iter()
+        .map(|message| Keccak256Hash.hash_iter(message.iter().copied()))
+        .collect::<Vec<_>>();
+    let mut num_perms = 0;
+    for (message, digest) in messages.iter().zip(&digests) {
+        num_perms += keccak_256_blocks(message).len();
+        let row = trace.row_slice(num_perms * NUM_ROUNDS - 1);
+        let output = (0..16)
+            .flat_map(|i| (row[output_limb(i)].as_canonical_u32() as u16).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(&output, digest);
+    }
+    let public_values = Keccak256HashAir::public_values::<Val>(&digests[..3]);
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+
+    let mut wrong_digest = public_values;
+    wrong_digest[2 * 32 + 7] += Val::ONE;
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &wrong_digest)
+        .expect_err("verification should fail with the wrong digest");
+}
+
+#[test]
+fn test_keccak256_hash_air_wrong_padding() {
+    let message = [1, 2, 3, 0x01, 0, 0];
+    let mut trace = generate_hash_trace_rows::<Val>(&[&message], 0);
+    // Claim the message ends after its first three bytes, as though its last three were padding.
+    let row = &mut trace.row_mut(0)[..NUM_KECCAK_256_HASH_COLS];
+    let row: &mut Keccak256HashCols<Val> = row.borrow_mut();
+    for is_padding in &mut row.is_padding[3..6] {
+        *is_padding = Val::ONE;
+    }
+    debug_constraints(&Keccak256HashAir::default(), &trace, &[])
+        .expect_err("the message bytes should not be accepted as padding");
+}
@@ -0,0 +1,58 @@
+use core::borrow::BorrowMut;
+
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_keccak::Keccak256Hash;
+use p3_keccak_air::{
+    generate_sponge_trace_rows, keccak_256_blocks, output_limb, KeccakSpongeAir, KeccakSpongeCols,
+    NUM_ROUNDS,
+};
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{debug_constraints, prove, verify};
+use rand::thread_rng;
+
+const AIR: KeccakSpongeAir = KeccakSpongeAir {
+    block_bus: 0,
+    output_bus: 1,
+};
+
+#[test]
+fn test_keccak_sponge_air() {
+    let messages: Vec<Vec<u8>> = [0, 200, 300]
+        .into_iter()
+        .map(|len| (0..len).map(|i| (i * 3 + len) as u8).collect())
+        .collect();
+    let inputs = messages
+        .iter()
+        .map(|message| keccak_256_blocks(message))
+        .collect::<Vec<_>>();
+    let trace = generate_sponge_trace_rows::<Val>(&inputs);
+
+    // The output of the final permutation of each input begins with its hash.
+    let mut num_perms = 0;
+    for (message, blocks) in messages.iter().zip(&inputs) {
+        num_perms += blocks.len();
+        let row = trace.row_slice(num_perms * NUM_ROUNDS - 1);
+        let digest = (0..16)
+            .flat_map(|i| (row[output_limb(i)].as_canonical_u32() as u16).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(digest, Keccak256Hash.hash_iter(message.iter().copied()));
+    }
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &AIR, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &AIR, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_keccak_sponge_air_broken_chain() {
+    let mut trace = generate_sponge_trace_rows::<Val>(&[keccak_256_blocks(&[7; 200])]);
+    // Claim the second permutation starts a new sponge, though its preimage is chained.
+    let row: &mut KeccakSpongeCols<Val> = trace.row_mut(NUM_ROUNDS - 1).borrow_mut();
+    row.chain = Val::ZERO;
+    debug_constraints(&AIR, &trace, &[])
+        .expect_err("a chained preimage should not start a new sponge");
+}
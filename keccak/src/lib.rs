@@ -10,7 +10,11 @@
     feature(stdarch_x86_avx512)
 )]
 
-use p3_symmetric::{CryptographicHasher, CryptographicPermutation, Permutation};
+extern crate alloc;
+
+use p3_symmetric::{
+    CryptographicHasher, CryptographicPermutation, Permutation, TruncatedPermutation,
+};
 use tiny_keccak::{keccakf, Hasher, Keccak};
 
 #[cfg(all(
@@ -109,6 +113,16 @@ impl Permutation<[u8; 200]> for KeccakF {
 
 impl CryptographicPermutation<[u8; 200]> for KeccakF {}
 
+/// A 2-to-1 compression function on `[u64; 4]` digests, built directly on the Keccak-f
+/// permutation, for use as the node compression function of a Merkle tree.
+///
+/// The two inputs are written to the first 8 lanes of an otherwise zero state, which is then
+/// permuted; the first 4 lanes are the output. This skips the SHA-3 padding and sponge machinery,
+/// which are unnecessary for fixed-size node hashing. It is also implemented for the packed inputs
+/// `[[u64; VECTOR_LEN]; 4]`, so Merkle trees can compress `VECTOR_LEN` nodes at once using the
+/// vectorized permutation for the target architecture.
+pub type KeccakCompression = TruncatedPermutation<KeccakF, 2, 4, 25>;
+
 /// The `Keccak` hash functions defined in
 /// [Keccak SHA3 submission](https://keccak.team/files/Keccak-submission-3.pdf).
 #[derive(Copy, Clone, Debug)]
@@ -142,3 +156,108 @@ impl CryptographicHasher<u8, [u8; 32]> for Keccak256Hash {
         output
     }
 }
+
+/// Keccak-256 of the little-endian bytes of a sequence of `u32`s, with the digest read as
+/// little-endian `u32`s.
+///
+/// Together with the packed implementation below, this lets `SerializingHasher32<Keccak256Hash>`
+/// hash Merkle tree leaves `VECTOR_LEN` at a time with digests of type `[u32; 8]`.
+impl CryptographicHasher<u32, [u32; 8]> for Keccak256Hash {
+    fn hash_iter<I>(&self, input: I) -> [u32; 8]
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let bytes: [u8; 32] = self.hash_iter(input.into_iter().flat_map(u32::to_le_bytes));
+        core::array::from_fn(|i| u32::from_le_bytes(bytes[i * 4..][..4].try_into().unwrap()))
+    }
+}
+
+/// Keccak-256 of `VECTOR_LEN` independent messages at once, using the vectorized Keccak-f
+/// permutation. Each lane agrees with the scalar `CryptographicHasher<u32, [u32; 8]>`.
+impl CryptographicHasher<[u32; VECTOR_LEN], [[u32; VECTOR_LEN]; 8]> for Keccak256Hash {
+    fn hash_iter<I>(&self, input: I) -> [[u32; VECTOR_LEN]; 8]
+    where
+        I: IntoIterator<Item = [u32; VECTOR_LEN]>,
+    {
+        /// The rate of Keccak-256, in `u32` words.
+        const RATE: usize = 2 * 17;
+
+        let mut state = [[0u64; VECTOR_LEN]; 25];
+        // The number of words absorbed into the current block.
+        let mut pos = 0;
+        for words in input {
+            xor_words(&mut state[pos / 2], words, pos % 2);
+            pos += 1;
+            if pos == RATE {
+                KeccakF.permute_mut(&mut state);
+                pos = 0;
+            }
+        }
+
+        // The original Keccak padding: a 0x01 byte after the message, and 0x80 in the last byte
+        // of the block.
+        xor_words(&mut state[pos / 2], [1; VECTOR_LEN], pos % 2);
+        xor_words(&mut state[RATE / 2 - 1], [0x80 << 24; VECTOR_LEN], 1);
+        KeccakF.permute_mut(&mut state);
+
+        core::array::from_fn(|i| state[i / 2].map(|lane| (lane >> (32 * (i % 2))) as u32))
+    }
+}
+
+/// XOR `words` into the low (`half == 0`) or high (`half == 1`) halves of a packed lane.
+#[inline]
+fn xor_words(lane: &mut [u64; VECTOR_LEN], words: [u32; VECTOR_LEN], half: usize) {
+    for (l, w) in lane.iter_mut().zip(words) {
+        *l ^= (w as u64) << (32 * half);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_symmetric::{
+        CompressionFunctionFromHasher, PaddingFreeSponge, PseudoCompressionFunction,
+    };
+
+    use super::*;
+
+    #[test]
+    fn keccak_compression_matches_sponge() {
+        type U64Hash = PaddingFreeSponge<KeccakF, 25, 17, 4>;
+        let sponge_compress =
+            CompressionFunctionFromHasher::<U64Hash, 2, 4>::new(U64Hash::new(KeccakF));
+        let compress = KeccakCompression::new(KeccakF);
+
+        let left = [1, 2, 3, 0xdead_beef_u64];
+        let right = [u64::MAX, 5, 0, 7];
+        let expected = sponge_compress.compress([left, right]);
+        assert_eq!(compress.compress([left, right]), expected);
+
+        let packed_left = left.map(|x| [x; VECTOR_LEN]);
+        let packed_right = right.map(|x| [x; VECTOR_LEN]);
+        let packed = compress.compress([packed_left, packed_right]);
+        assert_eq!(packed, expected.map(|x| [x; VECTOR_LEN]));
+    }
+
+    #[test]
+    fn packed_keccak256_matches_scalar() {
+        // Lengths around the rate of 34 words, where padding spills into a new block.
+        for len in [0, 1, 2, 33, 34, 35, 67, 68, 100] {
+            let input: Vec<[u32; VECTOR_LEN]> = (0..len)
+                .map(|i| core::array::from_fn(|lane| (i * 0x9e37_79b9 + lane * 0x7f4a_7c15) as u32))
+                .collect();
+            let packed: [[u32; VECTOR_LEN]; 8] = Keccak256Hash.hash_slice(&input);
+            for lane in 0..VECTOR_LEN {
+                let expected: [u32; 8] = Keccak256Hash.hash_iter(input.iter().map(|w| w[lane]));
+                assert_eq!(packed.map(|w| w[lane]), expected);
+            }
+        }
+
+        // Keccak-256 of the empty message.
+        let empty: [u8; 32] = Keccak256Hash.hash_iter(core::iter::empty::<u8>());
+        let empty_words: [u32; 8] = Keccak256Hash.hash_iter(core::iter::empty::<u32>());
+        assert_eq!(empty[..4], [0xc5, 0xd2, 0x46, 0x01]);
+        assert_eq!(empty_words[0], u32::from_le_bytes([0xc5, 0xd2, 0x46, 0x01]));
+    }
+}
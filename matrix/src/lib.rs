@@ -10,7 +10,8 @@ use core::ops::Deref;
 
 use itertools::{izip, Itertools};
 use p3_field::{
-    dot_product, ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra, PackedValue,
+    broadcast_ext, dot_product, sum_ext_lanes, unpack_ext_lane, ExtensionField, Field,
+    FieldAlgebra, PackedValue,
 };
 use p3_maybe_rayon::prelude::*;
 use strided::{VerticallyStridedMatrixView, VerticallyStridedRowIndexMap};
@@ -22,11 +23,13 @@ pub mod bitrev;
 pub mod dense;
 pub mod extension;
 pub mod horizontally_truncated;
+pub mod linalg;
 pub mod mul;
 pub mod row_index_mapped;
 pub mod sparse;
 pub mod stack;
 pub mod strided;
+pub mod tracked;
 pub mod util;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -233,9 +236,7 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
             .par_fold_reduce(
                 || EF::ExtensionPacking::zero_vec(packed_width),
                 |mut acc, (row, &scale)| {
-                    let scale = EF::ExtensionPacking::from_base_fn(|i| {
-                        T::Packing::from(scale.as_base_slice()[i])
-                    });
+                    let scale = broadcast_ext::<T, EF>(scale);
                     izip!(&mut acc, row).for_each(|(l, r)| *l += scale * r);
                     acc
                 },
@@ -247,10 +248,7 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
 
         packed_result
             .into_iter()
-            .flat_map(|p| {
-                (0..T::Packing::WIDTH)
-                    .map(move |i| EF::from_base_fn(|j| p.as_base_slice()[j].as_slice()[i]))
-            })
+            .flat_map(|p| (0..T::Packing::WIDTH).map(move |i| unpack_ext_lane::<T, EF>(&p, i)))
             .take(self.width())
             .collect()
     }
@@ -269,14 +267,7 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
             .map(move |row_packed| {
                 let packed_sum_of_packed: EF::ExtensionPacking =
                     dot_product(powers_packed.iter().copied(), row_packed);
-                let sum_of_packed: EF = EF::from_base_fn(|i| {
-                    packed_sum_of_packed.as_base_slice()[i]
-                        .as_slice()
-                        .iter()
-                        .copied()
-                        .sum()
-                });
-                sum_of_packed
+                sum_ext_lanes::<T, EF>(&packed_sum_of_packed)
             })
     }
 }
@@ -289,8 +280,8 @@ mod tests {
     use itertools::izip;
     use p3_baby_bear::BabyBear;
     use p3_field::extension::BinomialExtensionField;
-    use p3_field::FieldAlgebra;
-    use rand::thread_rng;
+    use p3_field::{dot_product_base_ext, packed_sum, FieldAlgebra};
+    use rand::{thread_rng, Rng};
 
     use super::*;
 
@@ -312,6 +303,24 @@ mod tests {
         assert_eq!(m.columnwise_dot_product(&v), expected);
     }
 
+    #[test]
+    fn test_packed_kernels() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<BabyBear, 4>;
+
+        let mut rng = thread_rng();
+        // Lengths around the packing width, and spanning several parallel blocks.
+        for len in [0, 1, 7, 8, 9, 33, 10_000] {
+            let base: Vec<F> = (0..len).map(|_| rng.gen()).collect();
+            let ext: Vec<EF> = (0..len).map(|_| rng.gen()).collect();
+            assert_eq!(packed_sum(&base), base.iter().copied().sum::<F>());
+            assert_eq!(
+                dot_product_base_ext(&base, &ext),
+                izip!(&base, &ext).map(|(&b, &e)| e * b).sum::<EF>()
+            );
+        }
+    }
+
     // Mock implementation for testing purposes
     struct MockMatrix {
         data: Vec<Vec<u32>>,
@@ -0,0 +1,253 @@
+//! Dense linear algebra over a field: solving linear systems, and computing determinants and
+//! inverses of square matrices, by Gaussian elimination.
+//!
+//! These are meant for the small matrices of setup and preprocessing steps, such as checking that
+//! a matrix is MDS or interpolating over an arbitrary set of points, and run in `O(n^3)` time.
+
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use p3_field::Field;
+
+use crate::dense::RowMajorMatrix;
+use crate::util::swap_rows;
+use crate::Matrix;
+
+/// An LU decomposition `P A = L U` of an invertible square matrix `A`, computed with partial
+/// pivoting, where `P` is a permutation matrix, `L` is lower triangular with ones on its diagonal,
+/// and `U` is upper triangular.
+#[derive(Clone, Debug)]
+pub struct LuDecomposition<F> {
+    /// `L` below the diagonal, whose ones are left implicit, and `U` on and above it.
+    lu: RowMajorMatrix<F>,
+    /// Row `i` of `P A` is row `perm[i]` of `A`.
+    perm: Vec<usize>,
+    /// Whether `P` is an odd permutation.
+    odd: bool,
+}
+
+impl<F: Field> LuDecomposition<F> {
+    /// Decompose `matrix`, or return `None` if it is singular.
+    ///
+    /// # Panics
+    /// Panics if `matrix` is not square.
+    pub fn new<M: Matrix<F>>(matrix: &M) -> Option<Self> {
+        let n = matrix.height();
+        assert_eq!(matrix.width(), n, "the matrix must be square");
+        let mut lu = RowMajorMatrix::new((0..n).flat_map(|r| matrix.row(r)).collect(), n);
+        let mut perm = (0..n).collect_vec();
+        let mut odd = false;
+
+        for col in 0..n {
+            // Over a field, any nonzero pivot will do.
+            let pivot = (col..n).find(|&r| !lu.values[r * n + col].is_zero())?;
+            if pivot != col {
+                swap_rows(&mut lu, col, pivot);
+                perm.swap(col, pivot);
+                odd = !odd;
+            }
+
+            let (upper, lower) = lu.values.split_at_mut((col + 1) * n);
+            let pivot_row = &upper[col * n..];
+            let pivot_inv = pivot_row[col].inverse();
+            for row in lower.chunks_exact_mut(n) {
+                let factor = row[col] * pivot_inv;
+                row[col] = factor;
+                if factor.is_zero() {
+                    continue;
+                }
+                for (x, &p) in row[col + 1..].iter_mut().zip(&pivot_row[col + 1..]) {
+                    *x -= factor * p;
+                }
+            }
+        }
+
+        Some(Self { lu, perm, odd })
+    }
+
+    /// The number of rows, and of columns, of the decomposed matrix.
+    pub fn size(&self) -> usize {
+        self.perm.len()
+    }
+
+    pub fn determinant(&self) -> F {
+        let n = self.size();
+        let det = (0..n).map(|i| self.lu.values[i * n + i]).product();
+        if self.odd {
+            -det
+        } else {
+            det
+        }
+    }
+
+    /// The `x` with `A x = b`.
+    ///
+    /// # Panics
+    /// Panics if `b` does not have one entry per row of `A`.
+    pub fn solve(&self, b: &[F]) -> Vec<F> {
+        self.solve_matrix(&RowMajorMatrix::new_col(b.to_vec()))
+            .values
+    }
+
+    /// The `X` with `A X = B`, i.e. the solutions for each column of `B`.
+    ///
+    /// # Panics
+    /// Panics if `b` does not have one row per row of `A`.
+    pub fn solve_matrix<M: Matrix<F>>(&self, b: &M) -> RowMajorMatrix<F> {
+        let n = self.size();
+        assert_eq!(
+            b.height(),
+            n,
+            "the right-hand side must have one row per row of the matrix"
+        );
+        let width = b.width();
+        let mut x = RowMajorMatrix::new(self.perm.iter().flat_map(|&r| b.row(r)).collect(), width);
+
+        // Solve `L Y = P B`, top to bottom.
+        for i in 1..n {
+            let (solved, rest) = x.values.split_at_mut(i * width);
+            let row = &mut rest[..width];
+            for (j, solved_row) in solved.chunks_exact(width).enumerate() {
+                let l = self.lu.values[i * n + j];
+                for (x, &y) in row.iter_mut().zip(solved_row) {
+                    *x -= l * y;
+                }
+            }
+        }
+
+        // Solve `U X = Y`, bottom to top.
+        for i in (0..n).rev() {
+            let (rest, solved) = x.values.split_at_mut((i + 1) * width);
+            let row = &mut rest[i * width..];
+            for (j, solved_row) in solved.chunks_exact(width).enumerate() {
+                let u = self.lu.values[i * n + i + 1 + j];
+                for (x, &y) in row.iter_mut().zip(solved_row) {
+                    *x -= u * y;
+                }
+            }
+            let diag_inv = self.lu.values[i * n + i].inverse();
+            row.iter_mut().for_each(|x| *x *= diag_inv);
+        }
+
+        x
+    }
+
+    /// The inverse of the decomposed matrix.
+    pub fn inverse(&self) -> RowMajorMatrix<F> {
+        let n = self.size();
+        let mut identity = RowMajorMatrix::new(F::zero_vec(n * n), n);
+        for i in 0..n {
+            identity.values[i * n + i] = F::ONE;
+        }
+        self.solve_matrix(&identity)
+    }
+}
+
+/// The determinant of the square matrix `matrix`.
+///
+/// # Panics
+/// Panics if `matrix` is not square.
+pub fn determinant<F: Field, M: Matrix<F>>(matrix: &M) -> F {
+    LuDecomposition::new(matrix).map_or(F::ZERO, |lu| lu.determinant())
+}
+
+/// Whether the square matrix `matrix` is invertible.
+///
+/// # Panics
+/// Panics if `matrix` is not square.
+pub fn is_invertible<F: Field, M: Matrix<F>>(matrix: &M) -> bool {
+    LuDecomposition::new(matrix).is_some()
+}
+
+/// The inverse of the square matrix `matrix`, or `None` if it is singular.
+///
+/// # Panics
+/// Panics if `matrix` is not square.
+pub fn inverse<F: Field, M: Matrix<F>>(matrix: &M) -> Option<RowMajorMatrix<F>> {
+    LuDecomposition::new(matrix).map(|lu| lu.inverse())
+}
+
+/// The `x` with `matrix * x = b`, or `None` if `matrix` is singular.
+///
+/// # Panics
+/// Panics if `matrix` is not square, or `b` does not have one entry per row of it.
+pub fn solve<F: Field, M: Matrix<F>>(matrix: &M, b: &[F]) -> Option<Vec<F>> {
+    LuDecomposition::new(matrix).map(|lu| lu.solve(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::FieldAlgebra;
+    use rand::thread_rng;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn from_u32s(values: &[u32], width: usize) -> RowMajorMatrix<F> {
+        RowMajorMatrix::new(
+            values.iter().map(|&v| F::from_canonical_u32(v)).collect(),
+            width,
+        )
+    }
+
+    /// The product `a * b` of two square matrices.
+    fn mul(a: &RowMajorMatrix<F>, b: &RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        let n = a.width();
+        let values = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (0..n).map(|k| a.get(i, k) * b.get(k, j)).sum()))
+            .collect();
+        RowMajorMatrix::new(values, n)
+    }
+
+    #[test]
+    fn determinant_small() {
+        // Needs a row swap, which flips the sign.
+        let m = from_u32s(&[0, 2, 3, 4], 2);
+        assert_eq!(determinant(&m), -F::from_canonical_u32(6));
+
+        let m = from_u32s(&[2, 0, 1, 1, 3, 2, 1, 1, 2], 3);
+        assert_eq!(determinant(&m), F::from_canonical_u32(6));
+
+        let singular = from_u32s(&[1, 2, 3, 2, 4, 6, 0, 1, 1], 3);
+        assert_eq!(determinant(&singular), F::ZERO);
+        assert!(!is_invertible(&singular));
+        assert!(inverse(&singular).is_none());
+        assert!(solve(&singular, &[F::ONE; 3]).is_none());
+    }
+
+    #[test]
+    fn empty_matrix() {
+        let m = RowMajorMatrix::<F>::new(vec![], 0);
+        assert_eq!(determinant(&m), F::ONE);
+        assert_eq!(inverse(&m).unwrap().height(), 0);
+    }
+
+    #[test]
+    fn inverse_and_solve_random() {
+        let mut rng = thread_rng();
+        for n in 1..10 {
+            let m = RowMajorMatrix::<F>::rand(&mut rng, n, n);
+            let lu = LuDecomposition::new(&m).expect("a random matrix is almost surely invertible");
+
+            let mut identity = RowMajorMatrix::new(F::zero_vec(n * n), n);
+            for i in 0..n {
+                identity.values[i * n + i] = F::ONE;
+            }
+            let inv = lu.inverse();
+            assert_eq!(mul(&m, &inv), identity);
+            assert_eq!(mul(&inv, &m), identity);
+            assert_eq!(lu.determinant() * determinant(&inv), F::ONE);
+
+            let b = RowMajorMatrix::<F>::rand(&mut rng, n, 1).values;
+            let x = lu.solve(&b);
+            let mx = (0..n)
+                .map(|i| m.row(i).zip(&x).map(|(a, &x)| a * x).sum())
+                .collect::<Vec<F>>();
+            assert_eq!(mx, b);
+        }
+    }
+}
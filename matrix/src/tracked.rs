@@ -0,0 +1,143 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Deref;
+use core::panic::Location;
+
+use crate::dense::RowMajorMatrix;
+use crate::Matrix;
+
+/// A row-major matrix which records the code location which last wrote each of its cells.
+///
+/// This is meant for debugging trace generation: a cell which is never written keeps its initial
+/// value, typically zero, which often satisfies the constraints it appears in and so goes unnoticed.
+/// Writing the trace through `set` and `set_row` instead of directly makes such cells visible via
+/// `unwritten_cells`, and lets a failed constraint be traced back to the code which wrote its
+/// inputs. The locations are those of the callers, so helpers which write to the matrix should be
+/// `#[track_caller]` to attribute writes to their own callers.
+#[derive(Clone, Debug)]
+pub struct TrackedMatrix<T: Send + Sync> {
+    values: RowMajorMatrix<T>,
+    writers: Vec<Option<&'static Location<'static>>>,
+}
+
+impl<T: Clone + Send + Sync + Default> TrackedMatrix<T> {
+    /// A matrix of default values, none of which counts as written.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::from_matrix(RowMajorMatrix::default(width, height))
+    }
+}
+
+impl<T: Clone + Send + Sync> TrackedMatrix<T> {
+    /// Tracks writes to `values`, none of which counts as written yet.
+    pub fn from_matrix(values: RowMajorMatrix<T>) -> Self {
+        let writers = vec![None; values.values.len()];
+        Self { values, writers }
+    }
+
+    #[track_caller]
+    pub fn set(&mut self, r: usize, c: usize, value: T) {
+        let width = self.width();
+        assert!(c < width, "column {c} is out of bounds for width {width}");
+        self.values.row_mut(r)[c] = value;
+        self.writers[r * width + c] = Some(Location::caller());
+    }
+
+    /// Writes `values` to row `r`, starting at column `start`.
+    #[track_caller]
+    pub fn set_row(&mut self, r: usize, start: usize, values: &[T]) {
+        let width = self.width();
+        assert!(
+            start + values.len() <= width,
+            "columns {start}..{} are out of bounds for width {width}",
+            start + values.len()
+        );
+        self.values.row_mut(r)[start..start + values.len()].clone_from_slice(values);
+        self.writers[r * width + start..r * width + start + values.len()]
+            .fill(Some(Location::caller()));
+    }
+
+    /// The location of the last write to the given cell, or `None` if it was never written.
+    pub fn writer(&self, r: usize, c: usize) -> Option<&'static Location<'static>> {
+        assert!(r < self.height() && c < self.width());
+        self.writers[r * self.width() + c]
+    }
+
+    /// The `(row, column)` of each cell which was never written, in row-major order.
+    pub fn unwritten_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let width = self.width();
+        self.writers
+            .iter()
+            .enumerate()
+            .filter(|(_, writer)| writer.is_none())
+            .map(move |(i, _)| (i / width, i % width))
+    }
+
+    pub const fn values(&self) -> &RowMajorMatrix<T> {
+        &self.values
+    }
+
+    /// Discards the recorded locations.
+    pub fn into_inner(self) -> RowMajorMatrix<T> {
+        self.values
+    }
+}
+
+impl<T: Clone + Send + Sync> Matrix<T> for TrackedMatrix<T> {
+    fn width(&self) -> usize {
+        self.values.width()
+    }
+
+    fn height(&self) -> usize {
+        self.values.height()
+    }
+
+    fn get(&self, r: usize, c: usize) -> T {
+        self.values.get(r, c)
+    }
+
+    type Row<'a>
+        = <RowMajorMatrix<T> as Matrix<T>>::Row<'a>
+    where
+        Self: 'a;
+
+    fn row(&self, r: usize) -> Self::Row<'_> {
+        self.values.row(r)
+    }
+
+    fn row_slice(&self, r: usize) -> impl Deref<Target = [T]> {
+        self.values.row_slice(r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwritten_cells() {
+        let mut matrix = TrackedMatrix::<u32>::new(3, 2);
+        matrix.set(0, 1, 5);
+        matrix.set_row(1, 1, &[6, 7]);
+
+        assert_eq!(
+            matrix.unwritten_cells().collect::<Vec<_>>(),
+            vec![(0, 0), (0, 2), (1, 0)]
+        );
+        assert_eq!(matrix.into_inner().values, vec![0, 5, 0, 0, 6, 7]);
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut matrix = TrackedMatrix::from_matrix(RowMajorMatrix::new(vec![1, 2, 3, 4], 2));
+        assert_eq!(matrix.writer(1, 0), None);
+
+        let line = line!() + 1;
+        matrix.set(1, 0, 5);
+        matrix.set(1, 0, 6);
+        let writer = matrix.writer(1, 0).unwrap();
+        assert_eq!(writer.file(), file!());
+        assert_eq!(writer.line(), line + 1);
+        assert_eq!(matrix.get(1, 0), 6);
+        assert_eq!(matrix.writer(1, 1), None);
+    }
+}
@@ -1,3 +1,9 @@
+#![no_std]
+
+mod resources;
+
+pub use resources::*;
+
 #[cfg(feature = "parallel")]
 pub mod prelude {
     pub use rayon::prelude::*;
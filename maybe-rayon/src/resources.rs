@@ -0,0 +1,52 @@
+/// Limits on the resources a computation, such as a proof, may use.
+///
+/// This lets embedders running several computations at once, e.g. a proving service with many
+/// tenants, give each of them a share of the machine, rather than letting all of them compete for
+/// the global rayon pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceConfig {
+    /// The number of threads to run on, or `None` to use the global rayon pool.
+    pub max_threads: Option<usize>,
+    /// The number of bytes the computation may allocate, or `None` for no limit.
+    ///
+    /// This crate does not track allocations; callers check the budget against their own
+    /// estimates, e.g. before starting a proof.
+    pub max_memory_bytes: Option<usize>,
+}
+
+impl ResourceConfig {
+    /// No limits, running on the global rayon pool.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_threads: None,
+            max_memory_bytes: None,
+        }
+    }
+
+    pub const fn with_max_threads(mut self, max_threads: usize) -> Self {
+        self.max_threads = Some(max_threads);
+        self
+    }
+
+    pub const fn with_max_memory_bytes(mut self, max_memory_bytes: usize) -> Self {
+        self.max_memory_bytes = Some(max_memory_bytes);
+        self
+    }
+
+    /// Run `op` with at most `max_threads` threads.
+    ///
+    /// All parallel iterators and joins within `op` run on a pool of `max_threads` threads, which
+    /// is created for this call and shut down once `op` returns. Without the `parallel` feature,
+    /// `op` runs on the current thread.
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "parallel")]
+        if let Some(max_threads) = self.max_threads {
+            return rayon::ThreadPoolBuilder::new()
+                .num_threads(max_threads.max(1))
+                .build()
+                .expect("failed to build the thread pool")
+                .install(op);
+        }
+        op()
+    }
+}
@@ -27,6 +27,7 @@ fn bench_all_mds(c: &mut Criterion) {
     bench_mds::<Goldilocks, MdsMatrixGoldilocks, 8>(c);
     bench_mds::<Goldilocks, MdsMatrixGoldilocks, 12>(c);
     bench_mds::<Goldilocks, MdsMatrixGoldilocks, 16>(c);
+    bench_mds::<Goldilocks, MdsMatrixGoldilocks, 24>(c);
     bench_mds::<Goldilocks, MdsMatrixGoldilocks, 32>(c);
     bench_mds::<Goldilocks, MdsMatrixGoldilocks, 64>(c);
 
@@ -0,0 +1,219 @@
+//! Constructing MDS matrices over arbitrary fields, and verifying the MDS property.
+//!
+//! The MDS matrices in this crate and the field crates were found for specific fields, and are
+//! not in general MDS over other fields. The functions here let users of other fields generate
+//! and check their own matrices instead of copying constants.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use itertools::Itertools;
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{linalg, Matrix};
+use p3_symmetric::Permutation;
+use rand::Rng;
+
+use crate::util::apply_circulant;
+use crate::MdsPermutation;
+
+/// Returns whether `matrix` is MDS, i.e. whether each of its square submatrices is invertible.
+///
+/// NB: Every square submatrix is checked, so the cost grows exponentially with the size of the
+/// matrix. This is practical for widths up to about 12.
+pub fn is_mds<F: Field>(matrix: &RowMajorMatrix<F>) -> bool {
+    let (height, width) = (matrix.height(), matrix.width());
+    (1..=height.min(width)).all(|size| {
+        (0..height)
+            .combinations(size)
+            .all(|rows| all_minors_nonzero(matrix, &rows))
+    })
+}
+
+/// Returns whether the circulant matrix with first row `first_row` is MDS.
+///
+/// This is equivalent to `is_mds` on the full matrix, but faster: shifting a submatrix of a
+/// circulant matrix along its diagonal gives the same submatrix up to a permutation, so only
+/// submatrices containing the first row are checked.
+pub fn is_mds_circulant<F: Field>(first_row: &[F]) -> bool {
+    let matrix = circulant_matrix(first_row);
+    let width = first_row.len();
+    (1..=width).all(|size| {
+        (1..width).combinations(size - 1).all(|other_rows| {
+            let rows: Vec<usize> = core::iter::once(0).chain(other_rows).collect();
+            all_minors_nonzero(&matrix, &rows)
+        })
+    })
+}
+
+/// Returns the Cauchy matrix with entries `1 / (xs[i] - ys[j])`, or `None` if the elements of
+/// `xs` and `ys` are not all distinct.
+///
+/// Cauchy matrices are MDS by construction, so unlike circulant matrices they need not be
+/// searched for, but their entries are arbitrary field elements.
+pub fn cauchy_matrix<F: Field>(xs: &[F], ys: &[F]) -> Option<RowMajorMatrix<F>> {
+    // A quadratic check, as `F` need not be `Ord` and `Itertools::all_unique` needs `std`.
+    let elems = xs.iter().chain(ys).collect_vec();
+    if (0..elems.len()).any(|i| elems[..i].contains(&elems[i])) {
+        return None;
+    }
+    let values = xs
+        .iter()
+        .flat_map(|&x| ys.iter().map(move |&y| (x - y).inverse()))
+        .collect();
+    Some(RowMajorMatrix::new(values, ys.len()))
+}
+
+/// Search for a circulant MDS matrix over `F` of size `WIDTH`, whose entries are at most
+/// `max_entry`, trying up to `attempts` random first rows.
+///
+/// Small entries keep multiplication by the matrix cheap, e.g. with the convolutions in
+/// `karatsuba_convolution`. `max_entry` must be smaller than the characteristic of `F`.
+pub fn find_mds_circulant<F, R, const WIDTH: usize>(
+    max_entry: u64,
+    attempts: usize,
+    rng: &mut R,
+) -> Option<CirculantMds<F, WIDTH>>
+where
+    F: Field,
+    R: Rng,
+{
+    (0..attempts).find_map(|_| {
+        let first_row = core::array::from_fn(|_| rng.gen_range(1..=max_entry));
+        CirculantMds::new(first_row)
+    })
+}
+
+/// A circulant MDS matrix over `F`, with small entries given by its first row.
+#[derive(Clone, Debug)]
+pub struct CirculantMds<F, const WIDTH: usize> {
+    first_row: [u64; WIDTH],
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Field, const WIDTH: usize> CirculantMds<F, WIDTH> {
+    /// Returns the circulant matrix with first row `first_row`, or `None` if it is not MDS over
+    /// `F`.
+    pub fn new(first_row: [u64; WIDTH]) -> Option<Self> {
+        is_mds_circulant(&first_row.map(F::from_canonical_u64)).then_some(Self {
+            first_row,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// The first row of the matrix, e.g. to be copied into a constant.
+    pub const fn first_row(&self) -> [u64; WIDTH] {
+        self.first_row
+    }
+
+    pub fn to_matrix(&self) -> RowMajorMatrix<F> {
+        circulant_matrix(&self.first_row.map(F::from_canonical_u64))
+    }
+}
+
+impl<FA, const WIDTH: usize> Permutation<[FA; WIDTH]> for CirculantMds<FA::F, WIDTH>
+where
+    FA: FieldAlgebra,
+{
+    fn permute(&self, input: [FA; WIDTH]) -> [FA; WIDTH] {
+        apply_circulant(&self.first_row, input)
+    }
+
+    fn permute_mut(&self, input: &mut [FA; WIDTH]) {
+        *input = self.permute(input.clone());
+    }
+}
+
+impl<FA, const WIDTH: usize> MdsPermutation<FA, WIDTH> for CirculantMds<FA::F, WIDTH> where
+    FA: FieldAlgebra
+{
+}
+
+/// The circulant matrix whose rows are `first_row` rotated to the right by their index.
+fn circulant_matrix<F: Field>(first_row: &[F]) -> RowMajorMatrix<F> {
+    let width = first_row.len();
+    let values = (0..width)
+        .flat_map(|i| (0..width).map(move |j| first_row[(j + width - i) % width]))
+        .collect();
+    RowMajorMatrix::new(values, width)
+}
+
+/// Returns whether each square submatrix of `matrix` with the given rows is invertible.
+fn all_minors_nonzero<F: Field>(matrix: &RowMajorMatrix<F>, rows: &[usize]) -> bool {
+    (0..matrix.width())
+        .combinations(rows.len())
+        .all(|cols| is_invertible(matrix, rows, &cols))
+}
+
+/// Returns whether the square submatrix of `matrix` with the given rows and columns is
+/// invertible.
+fn is_invertible<F: Field>(matrix: &RowMajorMatrix<F>, rows: &[usize], cols: &[usize]) -> bool {
+    let submatrix = RowMajorMatrix::new(
+        rows.iter()
+            .flat_map(|&r| cols.iter().map(move |&c| matrix.get(r, c)))
+            .collect(),
+        cols.len(),
+    );
+    linalg::is_invertible(&submatrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::FieldAlgebra;
+    use p3_matrix::Matrix;
+    use p3_symmetric::Permutation;
+    use rand::SeedableRng;
+    use rand_xoshiro::Xoroshiro128Plus;
+
+    use super::{
+        cauchy_matrix, circulant_matrix, find_mds_circulant, is_mds, is_mds_circulant, CirculantMds,
+    };
+
+    type F = BabyBear;
+
+    #[test]
+    fn known_circulant_is_mds() {
+        let first_row = [7, 1, 3, 8, 8, 3, 4, 9].map(F::from_canonical_u64);
+        assert!(is_mds_circulant(&first_row));
+        assert!(is_mds(&circulant_matrix(&first_row)));
+    }
+
+    #[test]
+    fn singular_minors_are_found() {
+        // Every 2x2 submatrix of an all-ones matrix is singular.
+        assert!(!is_mds_circulant(&[F::ONE; 4]));
+        // The submatrix of the first two rows and columns 1 and 2 is [[2, 4], [1, 2]].
+        let first_row = [1, 2, 4, 8].map(F::from_canonical_u64);
+        assert!(!is_mds(&circulant_matrix(&first_row)));
+        assert!(!is_mds_circulant(&first_row));
+        assert!(CirculantMds::<F, 4>::new([1, 2, 4, 8]).is_none());
+    }
+
+    #[test]
+    fn cauchy() {
+        let xs = [1, 2, 3, 4, 5, 6].map(F::from_canonical_u64);
+        let ys = [7, 8, 9, 10, 11, 12].map(F::from_canonical_u64);
+        let matrix = cauchy_matrix(&xs, &ys).unwrap();
+        assert!(is_mds(&matrix));
+        assert!(cauchy_matrix(&xs, &xs).is_none());
+    }
+
+    #[test]
+    fn find_circulant() {
+        let mut rng = Xoroshiro128Plus::seed_from_u64(1);
+        let mds = find_mds_circulant::<F, _, 6>(16, 100, &mut rng).unwrap();
+        assert!(mds.first_row().iter().all(|&x| (1..=16).contains(&x)));
+        assert!(is_mds(&mds.to_matrix()));
+
+        let input = [1, 2, 3, 4, 5, 6].map(F::from_canonical_u64);
+        let expected: Vec<F> = mds
+            .to_matrix()
+            .rows()
+            .map(|row| row.zip(input).map(|(x, y)| x * y).sum())
+            .collect();
+        assert_eq!(mds.permute(input).to_vec(), expected);
+    }
+}
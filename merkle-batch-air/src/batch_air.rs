@@ -0,0 +1,220 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, BaseAirWithPublicValues, Interaction,
+    LookupAir, VirtualPairCol,
+};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::Matrix;
+
+use crate::columns::{num_merkle_batch_cols, MerkleBatchCols};
+
+/// Checks a batch of Merkle paths, from leaf digests to roots, under a compression function whose
+/// evaluations are looked up in another table, such as `Poseidon2CompressionAir` or the
+/// `Blake3CompressionAir` of `p3-blake3-air`.
+///
+/// Each row holds one step of a path, and sends its two children followed by their compression on
+/// `bus`, so the paths take as many rows as they have siblings in total, padded to a power of two.
+/// The paths may have different lengths, but each leaf index must be less than the characteristic
+/// of the field.
+///
+/// For each of the `num_paths` paths, in order, the public values are its root, its leaf index and
+/// its leaf digest. To locate them, the trace has `num_paths` columns after the
+/// `MerkleBatchCols`, which one-hot encode the path of each row.
+#[derive(Debug)]
+pub struct MerkleBatchAir<const DIGEST_ELEMS: usize> {
+    pub num_paths: usize,
+    pub bus: usize,
+}
+
+impl<const DIGEST_ELEMS: usize> MerkleBatchAir<DIGEST_ELEMS> {
+    pub fn new(num_paths: usize, bus: usize) -> Self {
+        assert!(num_paths > 0, "the batch must not be empty");
+        Self { num_paths, bus }
+    }
+
+    /// The number of public values for each path, namely its root, leaf index and leaf digest.
+    pub const fn public_values_per_path() -> usize {
+        2 * DIGEST_ELEMS + 1
+    }
+}
+
+impl<F, const DIGEST_ELEMS: usize> BaseAir<F> for MerkleBatchAir<DIGEST_ELEMS> {
+    fn width(&self) -> usize {
+        num_merkle_batch_cols::<DIGEST_ELEMS>() + self.num_paths
+    }
+}
+
+impl<F, const DIGEST_ELEMS: usize> BaseAirWithPublicValues<F> for MerkleBatchAir<DIGEST_ELEMS> {
+    fn num_public_values(&self) -> usize {
+        self.num_paths * Self::public_values_per_path()
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues, const DIGEST_ELEMS: usize> Air<AB>
+    for MerkleBatchAir<DIGEST_ELEMS>
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (local, local_path) = local.split_at(num_merkle_batch_cols::<DIGEST_ELEMS>());
+        let (next, next_path) = next.split_at(num_merkle_batch_cols::<DIGEST_ELEMS>());
+        let local: &MerkleBatchCols<AB::Var, DIGEST_ELEMS> = local.borrow();
+        let next: &MerkleBatchCols<AB::Var, DIGEST_ELEMS> = next.borrow();
+
+        let n = self.num_paths;
+        let public_values: Vec<AB::Expr> =
+            builder.public_values().iter().map(|&x| x.into()).collect();
+        // The `offset`-th public value of the path encoded by `path`, or zero for padding.
+        let select = |path: &[AB::Var], offset: usize| {
+            path.iter()
+                .enumerate()
+                .fold(AB::Expr::ZERO, |acc, (p, &is_path)| {
+                    acc + public_values[p * Self::public_values_per_path() + offset].clone()
+                        * is_path
+                })
+        };
+
+        builder.assert_bool(local.is_real);
+        builder.assert_bool(local.is_last);
+        builder.assert_bool(local.is_right);
+        let is_padding = AB::Expr::ONE - local.is_real;
+        builder.when(is_padding.clone()).assert_zero(local.is_last);
+        builder.when(is_padding).assert_zero(local.is_right);
+
+        // The path is one-hot on real rows, and zero on padding.
+        for &is_path in local_path {
+            builder.assert_bool(is_path);
+        }
+        builder.assert_eq(
+            local_path
+                .iter()
+                .fold(AB::Expr::ZERO, |sum, &is_path| sum + is_path),
+            local.is_real,
+        );
+
+        // The children are the node and its sibling, in order.
+        for i in 0..DIGEST_ELEMS {
+            let (node, sibling) = (local.node[i], local.sibling[i]);
+            builder.assert_eq(local.left[i], node + local.is_right * (sibling - node));
+            builder.assert_eq(local.right[i], sibling + local.is_right * (node - sibling));
+        }
+
+        // The first path starts at the first row.
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_one(local.is_real);
+        when_first_row.assert_one(local_path[0]);
+        when_first_row.assert_one(local.power_of_two);
+        when_first_row.assert_eq(local.index, local.is_right);
+        for (&node, leaf) in local
+            .node
+            .iter()
+            .zip(&public_values[DIGEST_ELEMS + 1..Self::public_values_per_path()])
+        {
+            when_first_row.assert_eq(node, leaf.clone());
+        }
+
+        // Padding is only followed by padding, and a path which has not ended continues.
+        let mut when_transition = builder.when_transition();
+        when_transition
+            .when(AB::Expr::ONE - local.is_real)
+            .assert_zero(next.is_real);
+        let mut when_path_continues = when_transition.when(local.is_real - local.is_last);
+        when_path_continues.assert_one(next.is_real);
+        when_path_continues.assert_eq(next.power_of_two, local.power_of_two * AB::Expr::TWO);
+        when_path_continues.assert_eq(next.index, local.index + next.is_right * next.power_of_two);
+        for (&next_node, &output) in next.node.iter().zip(&local.output) {
+            when_path_continues.assert_eq(next_node, output);
+        }
+        for (&next_is_path, &is_path) in next_path.iter().zip(local_path) {
+            when_path_continues.assert_eq(next_is_path, is_path);
+        }
+
+        // After the end of a path, the next one starts from its leaf, or padding starts if it was
+        // the last path. Either way the next row belongs to the next path, so that every path
+        // appears, in order.
+        let mut when_path_ends = when_transition.when(local.is_last);
+        when_path_ends.assert_eq(next.power_of_two, next.is_real);
+        when_path_ends.assert_eq(next.index, next.is_right);
+        for (i, &node) in next.node.iter().enumerate() {
+            when_path_ends.assert_eq(node, select(next_path, DIGEST_ELEMS + 1 + i));
+        }
+        when_path_ends.assert_zero(next_path[0]);
+        for i in 0..n - 1 {
+            when_path_ends.assert_eq(next_path[i + 1], local_path[i]);
+        }
+
+        // The last real row ends the last path, even if there is no padding.
+        let mut when_last_row = builder.when_last_row();
+        when_last_row.assert_eq(local.is_last, local.is_real);
+        when_last_row.assert_eq(local_path[n - 1], local.is_real);
+
+        // The end of each path is its root, at its leaf index.
+        let mut when_last = builder.when(local.is_last);
+        for (i, &output) in local.output.iter().enumerate() {
+            when_last.assert_eq(output, select(local_path, i));
+        }
+        when_last.assert_eq(local.index, select(local_path, DIGEST_ELEMS));
+    }
+}
+
+impl<F: Field, const DIGEST_ELEMS: usize> LookupAir<F> for MerkleBatchAir<DIGEST_ELEMS> {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let indices: Vec<usize> = (0..num_merkle_batch_cols::<DIGEST_ELEMS>()).collect();
+        let cols: &MerkleBatchCols<usize, DIGEST_ELEMS> = indices[..].borrow();
+
+        let values = cols
+            .left
+            .iter()
+            .chain(&cols.right)
+            .chain(&cols.output)
+            .map(|&col| VirtualPairCol::single_main(col))
+            .collect();
+        vec![Interaction::send(
+            values,
+            VirtualPairCol::single_main(cols.is_real),
+            self.bus,
+        )]
+    }
+}
+
+/// A `MerkleBatchAir` together with the table of compressions its rows look up, for proving both
+/// with `prove_multiple_with_lookups`.
+#[derive(Debug)]
+pub enum MerkleBatchTable<C, const DIGEST_ELEMS: usize> {
+    Paths(MerkleBatchAir<DIGEST_ELEMS>),
+    Compressions(C),
+}
+
+impl<F, C: BaseAir<F>, const DIGEST_ELEMS: usize> BaseAir<F> for MerkleBatchTable<C, DIGEST_ELEMS> {
+    fn width(&self) -> usize {
+        match self {
+            Self::Paths(air) => BaseAir::<F>::width(air),
+            Self::Compressions(air) => air.width(),
+        }
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues, C: Air<AB>, const DIGEST_ELEMS: usize> Air<AB>
+    for MerkleBatchTable<C, DIGEST_ELEMS>
+{
+    fn eval(&self, builder: &mut AB) {
+        match self {
+            Self::Paths(air) => air.eval(builder),
+            Self::Compressions(air) => air.eval(builder),
+        }
+    }
+}
+
+impl<F: Field, C: LookupAir<F>, const DIGEST_ELEMS: usize> LookupAir<F>
+    for MerkleBatchTable<C, DIGEST_ELEMS>
+{
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        match self {
+            Self::Paths(air) => air.interactions(),
+            Self::Compressions(air) => air.interactions(),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+/// The columns of a `MerkleBatchAir`, which are followed by one column per path, together a one-hot
+/// encoding of the path to which a row belongs.
+///
+/// Each row compresses `left` with `right` into `output`, the node of the next row of the same
+/// path. The rows of each path are consecutive, and the rows after the last path are padding, for
+/// which `is_real` is zero.
+#[repr(C)]
+pub struct MerkleBatchCols<T, const DIGEST_ELEMS: usize> {
+    /// Whether this row is a step of a path rather than padding.
+    pub is_real: T,
+
+    /// Whether this row is the last step of its path, i.e. whether it outputs the root.
+    pub is_last: T,
+
+    /// Whether `node` is the right child, i.e. the bit of the leaf index at this height.
+    pub is_right: T,
+
+    pub node: [T; DIGEST_ELEMS],
+
+    pub sibling: [T; DIGEST_ELEMS],
+
+    /// The node and its sibling, in order.
+    pub left: [T; DIGEST_ELEMS],
+    pub right: [T; DIGEST_ELEMS],
+
+    /// The compression of `left` and `right`, as looked up in a table of compressions.
+    pub output: [T; DIGEST_ELEMS],
+
+    /// `2^i` in the `i`-th row of a path.
+    pub power_of_two: T,
+
+    /// The bits of the leaf index seen so far.
+    pub index: T,
+}
+
+pub const fn num_merkle_batch_cols<const DIGEST_ELEMS: usize>() -> usize {
+    size_of::<MerkleBatchCols<u8, DIGEST_ELEMS>>()
+}
+
+impl<T, const DIGEST_ELEMS: usize> Borrow<MerkleBatchCols<T, DIGEST_ELEMS>> for [T] {
+    fn borrow(&self) -> &MerkleBatchCols<T, DIGEST_ELEMS> {
+        debug_assert_eq!(self.len(), num_merkle_batch_cols::<DIGEST_ELEMS>());
+        let (prefix, shorts, suffix) =
+            unsafe { self.align_to::<MerkleBatchCols<T, DIGEST_ELEMS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T, const DIGEST_ELEMS: usize> BorrowMut<MerkleBatchCols<T, DIGEST_ELEMS>> for [T] {
+    fn borrow_mut(&mut self) -> &mut MerkleBatchCols<T, DIGEST_ELEMS> {
+        debug_assert_eq!(self.len(), num_merkle_batch_cols::<DIGEST_ELEMS>());
+        let (prefix, shorts, suffix) =
+            unsafe { self.align_to_mut::<MerkleBatchCols<T, DIGEST_ELEMS>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
@@ -0,0 +1,200 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_poseidon2::GenericPoseidon2LinearLayers;
+use p3_poseidon2_air::{eval, num_cols, Poseidon2Air, Poseidon2Cols, RoundConstants};
+
+/// A table of compressions of pairs of digests under the compression function which permutes the
+/// concatenated digests with Poseidon2 and keeps the first `DIGEST_ELEMS` elements, as
+/// `TruncatedPermutation` does, for use as a lookup table by other AIRs.
+///
+/// Each row is a Poseidon2 permutation, followed by a multiplicity column. It receives the two
+/// digests followed by the output on `bus`, as many times as its multiplicity.
+#[derive(Debug)]
+pub struct Poseidon2CompressionAir<
+    F: Field,
+    LinearLayers,
+    const WIDTH: usize,
+    const SBOX_DEGREE: u64,
+    const SBOX_REGISTERS: usize,
+    const HALF_FULL_ROUNDS: usize,
+    const PARTIAL_ROUNDS: usize,
+    const DIGEST_ELEMS: usize,
+> {
+    pub(crate) constants: RoundConstants<F, WIDTH, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+    poseidon2: Poseidon2Air<
+        F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >,
+    pub bus: usize,
+}
+
+impl<
+        F: Field,
+        LinearLayers,
+        const WIDTH: usize,
+        const SBOX_DEGREE: u64,
+        const SBOX_REGISTERS: usize,
+        const HALF_FULL_ROUNDS: usize,
+        const PARTIAL_ROUNDS: usize,
+        const DIGEST_ELEMS: usize,
+    >
+    Poseidon2CompressionAir<
+        F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+        DIGEST_ELEMS,
+    >
+{
+    pub fn new(
+        constants: RoundConstants<F, WIDTH, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>,
+        bus: usize,
+    ) -> Self {
+        assert_eq!(
+            WIDTH,
+            2 * DIGEST_ELEMS,
+            "the permutation must absorb exactly two digests"
+        );
+        assert!(HALF_FULL_ROUNDS > 0);
+        Self {
+            constants: constants.clone(),
+            poseidon2: Poseidon2Air::new(constants),
+            bus,
+        }
+    }
+
+    pub(crate) const fn perm_width() -> usize {
+        num_cols::<WIDTH, SBOX_DEGREE, SBOX_REGISTERS, HALF_FULL_ROUNDS, PARTIAL_ROUNDS>()
+    }
+}
+
+impl<
+        F: Field,
+        LinearLayers: Sync,
+        const WIDTH: usize,
+        const SBOX_DEGREE: u64,
+        const SBOX_REGISTERS: usize,
+        const HALF_FULL_ROUNDS: usize,
+        const PARTIAL_ROUNDS: usize,
+        const DIGEST_ELEMS: usize,
+    > BaseAir<F>
+    for Poseidon2CompressionAir<
+        F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+        DIGEST_ELEMS,
+    >
+{
+    fn width(&self) -> usize {
+        Self::perm_width() + 1
+    }
+}
+
+impl<
+        AB: AirBuilder,
+        LinearLayers: GenericPoseidon2LinearLayers<AB::Expr, WIDTH>,
+        const WIDTH: usize,
+        const SBOX_DEGREE: u64,
+        const SBOX_REGISTERS: usize,
+        const HALF_FULL_ROUNDS: usize,
+        const PARTIAL_ROUNDS: usize,
+        const DIGEST_ELEMS: usize,
+    > Air<AB>
+    for Poseidon2CompressionAir<
+        AB::F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+        DIGEST_ELEMS,
+    >
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let perm: &Poseidon2Cols<
+            AB::Var,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        > = local[..Self::perm_width()].borrow();
+
+        eval::<
+            AB,
+            LinearLayers,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        >(&self.poseidon2, builder, perm);
+    }
+}
+
+impl<
+        F: Field,
+        LinearLayers: Sync,
+        const WIDTH: usize,
+        const SBOX_DEGREE: u64,
+        const SBOX_REGISTERS: usize,
+        const HALF_FULL_ROUNDS: usize,
+        const PARTIAL_ROUNDS: usize,
+        const DIGEST_ELEMS: usize,
+    > LookupAir<F>
+    for Poseidon2CompressionAir<
+        F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+        DIGEST_ELEMS,
+    >
+{
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        let perm_width = Self::perm_width();
+        let indices: Vec<usize> = (0..perm_width).collect();
+        let perm: &Poseidon2Cols<
+            usize,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        > = indices[..].borrow();
+
+        let values = perm
+            .inputs
+            .iter()
+            .chain(&perm.ending_full_rounds[HALF_FULL_ROUNDS - 1].post[..DIGEST_ELEMS])
+            .map(|&col| VirtualPairCol::single_main(col))
+            .collect();
+        vec![Interaction::receive(
+            values,
+            VirtualPairCol::single_main(perm_width),
+            self.bus,
+        )]
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::vec::Vec;
+use core::array;
+use core::borrow::{Borrow, BorrowMut};
+
+use p3_field::{Field, PrimeField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_poseidon2::GenericPoseidon2LinearLayers;
+use p3_poseidon2_air::{generate_trace_rows, Poseidon2Cols};
+use tracing::instrument;
+
+use crate::columns::{num_merkle_batch_cols, MerkleBatchCols};
+use crate::{MerkleBatchAir, Poseidon2CompressionAir};
+
+/// A Merkle path from a leaf digest, at `index`, to its root, with the siblings ordered from the
+/// leaf up.
+#[derive(Clone, Debug)]
+pub struct MerklePath<F, const DIGEST_ELEMS: usize> {
+    pub leaf: [F; DIGEST_ELEMS],
+    pub index: usize,
+    pub siblings: Vec<[F; DIGEST_ELEMS]>,
+}
+
+impl<F: Copy, const DIGEST_ELEMS: usize> MerklePath<F, DIGEST_ELEMS> {
+    /// The root of the path under the given compression function.
+    pub fn root<C>(&self, compress: C) -> [F; DIGEST_ELEMS]
+    where
+        C: Fn([F; DIGEST_ELEMS], [F; DIGEST_ELEMS]) -> [F; DIGEST_ELEMS],
+    {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(self.leaf, |node, (i, &sibling)| {
+                if (self.index >> i) & 1 == 1 {
+                    compress(sibling, node)
+                } else {
+                    compress(node, sibling)
+                }
+            })
+    }
+}
+
+impl<const DIGEST_ELEMS: usize> MerkleBatchAir<DIGEST_ELEMS> {
+    /// The public values proving that `paths` lead to their roots under the given compression
+    /// function.
+    pub fn public_values<F, C>(paths: &[MerklePath<F, DIGEST_ELEMS>], compress: C) -> Vec<F>
+    where
+        F: Field,
+        C: Fn([F; DIGEST_ELEMS], [F; DIGEST_ELEMS]) -> [F; DIGEST_ELEMS],
+    {
+        paths
+            .iter()
+            .flat_map(|path| {
+                let root = path.root(&compress);
+                root.into_iter()
+                    .chain([F::from_canonical_usize(path.index)])
+                    .chain(path.leaf)
+            })
+            .collect()
+    }
+
+    /// Generates the trace proving that `paths`, of which there must be `num_paths`, lead to their
+    /// roots under the given compression function. Also returns the pairs of children compressed
+    /// by the rows, which the table of compressions must contain.
+    #[instrument(name = "generate Merkle batch trace", skip_all)]
+    pub fn generate_trace_rows<F, C>(
+        &self,
+        paths: &[MerklePath<F, DIGEST_ELEMS>],
+        compress: C,
+    ) -> (RowMajorMatrix<F>, Vec<[[F; DIGEST_ELEMS]; 2]>)
+    where
+        F: Field,
+        C: Fn([F; DIGEST_ELEMS], [F; DIGEST_ELEMS]) -> [F; DIGEST_ELEMS],
+    {
+        assert_eq!(paths.len(), self.num_paths, "wrong number of paths");
+        let num_real_rows: usize = paths.iter().map(|path| path.siblings.len()).sum();
+        let height = num_real_rows.next_power_of_two();
+        let cols_width = num_merkle_batch_cols::<DIGEST_ELEMS>();
+        let width = cols_width + self.num_paths;
+
+        let mut trace = RowMajorMatrix::new(F::zero_vec(height * width), width);
+        let mut compressions = Vec::with_capacity(num_real_rows);
+        let mut rows = trace.rows_mut();
+        for (p, path) in paths.iter().enumerate() {
+            assert!(!path.siblings.is_empty(), "the paths must not be empty");
+            let mut node = path.leaf;
+            let mut index_so_far = 0;
+            for (i, (&sibling, row)) in path.siblings.iter().zip(rows.by_ref()).enumerate() {
+                let (row, path_index) = row.split_at_mut(cols_width);
+                let cols: &mut MerkleBatchCols<F, DIGEST_ELEMS> = row.borrow_mut();
+                let is_right = (path.index >> i) & 1 == 1;
+                let (left, right) = if is_right {
+                    (sibling, node)
+                } else {
+                    (node, sibling)
+                };
+                index_so_far |= usize::from(is_right) << i;
+
+                cols.is_real = F::ONE;
+                cols.is_last = F::from_bool(i + 1 == path.siblings.len());
+                cols.is_right = F::from_bool(is_right);
+                cols.node = node;
+                cols.sibling = sibling;
+                cols.left = left;
+                cols.right = right;
+                cols.output = compress(left, right);
+                cols.power_of_two = F::TWO.exp_u64(i as u64);
+                cols.index = F::from_canonical_usize(index_so_far);
+                path_index[p] = F::ONE;
+
+                compressions.push([left, right]);
+                node = cols.output;
+            }
+        }
+
+        (trace, compressions)
+    }
+}
+
+impl<
+        F: PrimeField,
+        LinearLayers: GenericPoseidon2LinearLayers<F, WIDTH>,
+        const WIDTH: usize,
+        const SBOX_DEGREE: u64,
+        const SBOX_REGISTERS: usize,
+        const HALF_FULL_ROUNDS: usize,
+        const PARTIAL_ROUNDS: usize,
+        const DIGEST_ELEMS: usize,
+    >
+    Poseidon2CompressionAir<
+        F,
+        LinearLayers,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+        DIGEST_ELEMS,
+    >
+{
+    /// The compression function of the table.
+    pub fn compress(&self, left: [F; DIGEST_ELEMS], right: [F; DIGEST_ELEMS]) -> [F; DIGEST_ELEMS] {
+        let trace = self.generate_trace_rows(&[[left, right]]);
+        let perm: &Poseidon2Cols<
+            F,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        > = trace.values[..Self::perm_width()].borrow();
+        array::from_fn(|i| perm.ending_full_rounds[HALF_FULL_ROUNDS - 1].post[i])
+    }
+
+    /// Generates the trace with a row for each pair of digests in `inputs`, each with multiplicity
+    /// one. The trace is padded to a power of two height with rows of multiplicity zero.
+    #[instrument(name = "generate Poseidon2 compression trace", skip_all)]
+    pub fn generate_trace_rows(&self, inputs: &[[[F; DIGEST_ELEMS]; 2]]) -> RowMajorMatrix<F> {
+        let height = inputs.len().next_power_of_two();
+        let mut perm_inputs: Vec<[F; WIDTH]> = inputs
+            .iter()
+            .map(|[left, right]| {
+                array::from_fn(|i| {
+                    if i < DIGEST_ELEMS {
+                        left[i]
+                    } else {
+                        right[i - DIGEST_ELEMS]
+                    }
+                })
+            })
+            .collect();
+        perm_inputs.resize(height, [F::ZERO; WIDTH]);
+        let perms = generate_trace_rows::<
+            F,
+            LinearLayers,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        >(perm_inputs, &self.constants);
+
+        let values = perms
+            .rows()
+            .enumerate()
+            .flat_map(|(r, row)| row.chain([F::from_bool(r < inputs.len())]))
+            .collect();
+        RowMajorMatrix::new(values, Self::perm_width() + 1)
+    }
+}
@@ -0,0 +1,19 @@
+//! AIRs for checking batches of Merkle openings.
+//!
+//! This provides `MerkleBatchAir`, which checks a batch of openings against public roots with the
+//! compressions looked up in a separate table, such as `Poseidon2CompressionAir`, along with trace
+//! generators for both.
+
+#![no_std]
+
+extern crate alloc;
+
+mod batch_air;
+mod columns;
+mod compression_air;
+mod generation;
+
+pub use batch_air::*;
+pub use columns::*;
+pub use compression_air::*;
+pub use generation::*;
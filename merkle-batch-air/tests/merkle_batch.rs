@@ -0,0 +1,172 @@
+use p3_baby_bear::GenericPoseidon2LinearLayersBabyBear;
+use p3_blake3::Blake3;
+use p3_blake3_air::{generate_compression_trace_rows, Blake3CompressionAir, DIGEST_LEN};
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_merkle_batch_air::{MerkleBatchAir, MerkleBatchTable, MerklePath, Poseidon2CompressionAir};
+use p3_poseidon2_air::RoundConstants;
+use p3_symmetric::{CompressionFunctionFromHasher, PseudoCompressionFunction};
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{prove_multiple_with_lookups, verify_multiple_with_lookups};
+use rand::{thread_rng, Rng};
+
+const WIDTH: usize = 16;
+const SBOX_DEGREE: u64 = 7;
+const SBOX_REGISTERS: usize = 1;
+const HALF_FULL_ROUNDS: usize = 4;
+const PARTIAL_ROUNDS: usize = 13;
+const DIGEST_ELEMS: usize = 8;
+const BUS: usize = 0;
+
+type Poseidon2Table = Poseidon2CompressionAir<
+    Val,
+    GenericPoseidon2LinearLayersBabyBear,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+    DIGEST_ELEMS,
+>;
+
+/// Random paths with the given depths, whose digests are generated by `digest`.
+fn random_paths<const D: usize>(
+    depths: &[usize],
+    mut digest: impl FnMut() -> [Val; D],
+) -> Vec<MerklePath<Val, D>> {
+    let mut rng = thread_rng();
+    depths
+        .iter()
+        .map(|&depth| MerklePath {
+            leaf: digest(),
+            index: rng.gen_range(0..1 << depth),
+            siblings: (0..depth).map(|_| digest()).collect(),
+        })
+        .collect()
+}
+
+#[test]
+fn test_merkle_batch_poseidon2() {
+    let mut rng = thread_rng();
+    let compressions_air = Poseidon2Table::new(RoundConstants::from_rng(&mut rng), BUS);
+    let compress = |left, right| compressions_air.compress(left, right);
+
+    let paths = random_paths::<DIGEST_ELEMS>(&[3, 1, 4], || rng.gen());
+    let paths_air = MerkleBatchAir::new(paths.len(), BUS);
+    let public_values = MerkleBatchAir::public_values(&paths, compress);
+    let (paths_trace, compressions) = paths_air.generate_trace_rows(&paths, compress);
+    let compressions_trace = compressions_air.generate_trace_rows(&compressions);
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let airs_and_traces = [
+        (MerkleBatchTable::Paths(paths_air), paths_trace),
+        (
+            MerkleBatchTable::Compressions(compressions_air),
+            compressions_trace,
+        ),
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple_with_lookups(
+        &config,
+        &airs_and_traces,
+        &mut challenger,
+        &public_values,
+        None,
+    );
+
+    let airs = airs_and_traces.map(|(air, _)| air);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple_with_lookups(
+        &config,
+        &airs,
+        &mut challenger,
+        &proof,
+        &public_values,
+        None,
+    )
+    .expect("verification failed");
+
+    let mut wrong_root = public_values;
+    wrong_root[0] += Val::ONE;
+    let mut challenger = Challenger::new(perm);
+    verify_multiple_with_lookups(&config, &airs, &mut challenger, &proof, &wrong_root, None)
+        .expect_err("verification should fail with the wrong root");
+}
+
+#[test]
+fn test_merkle_batch_blake3() {
+    let to_bytes = |digest: [Val; DIGEST_LEN]| digest.map(|x| x.as_canonical_u32() as u8);
+    let blake3 = CompressionFunctionFromHasher::<Blake3, 2, DIGEST_LEN>::new(Blake3);
+    let compress = |left, right| {
+        blake3
+            .compress([to_bytes(left), to_bytes(right)])
+            .map(Val::from_canonical_u8)
+    };
+
+    let mut rng = thread_rng();
+    let paths = random_paths::<DIGEST_LEN>(&[2, 3], || {
+        rng.gen::<[u8; DIGEST_LEN]>().map(Val::from_canonical_u8)
+    });
+    let paths_air = MerkleBatchAir::new(paths.len(), BUS);
+    let public_values = MerkleBatchAir::public_values(&paths, compress);
+    let (paths_trace, compressions) = paths_air.generate_trace_rows(&paths, compress);
+    let compressions: Vec<_> = compressions
+        .into_iter()
+        .map(|children| children.map(to_bytes))
+        .collect();
+    let compressions_air = Blake3CompressionAir::new(BUS);
+    let compressions_trace = generate_compression_trace_rows(&compressions);
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let airs_and_traces = [
+        (MerkleBatchTable::Paths(paths_air), paths_trace),
+        (
+            MerkleBatchTable::Compressions(compressions_air),
+            compressions_trace,
+        ),
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple_with_lookups(
+        &config,
+        &airs_and_traces,
+        &mut challenger,
+        &public_values,
+        None,
+    );
+
+    let airs = airs_and_traces.map(|(air, _)| air);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple_with_lookups(
+        &config,
+        &airs,
+        &mut challenger,
+        &proof,
+        &public_values,
+        None,
+    )
+    .expect("verification failed");
+
+    let mut wrong_root = public_values;
+    wrong_root[0] += Val::ONE;
+    let mut challenger = Challenger::new(perm);
+    verify_multiple_with_lookups(&config, &airs, &mut challenger, &proof, &wrong_root, None)
+        .expect_err("verification should fail with the wrong root");
+}
+
+#[test]
+fn test_merkle_batch_wrong_leaf() {
+    let mut rng = thread_rng();
+    let compressions_air = Poseidon2Table::new(RoundConstants::from_rng(&mut rng), BUS);
+    let compress = |left, right| compressions_air.compress(left, right);
+
+    let paths = random_paths::<DIGEST_ELEMS>(&[2, 2], || rng.gen());
+    let paths_air = MerkleBatchAir::new(paths.len(), BUS);
+    let mut public_values = MerkleBatchAir::public_values(&paths, compress);
+    let (paths_trace, _) = paths_air.generate_trace_rows(&paths, compress);
+
+    // The leaf of the second path.
+    public_values[MerkleBatchAir::<DIGEST_ELEMS>::public_values_per_path() + DIGEST_ELEMS + 1] +=
+        Val::ONE;
+    assert!(p3_uni_stark::debug_constraints(&paths_air, &paths_trace, &public_values).is_err());
+}
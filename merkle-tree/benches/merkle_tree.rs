@@ -4,8 +4,8 @@ use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
 use p3_blake3::Blake3;
 use p3_commit::Mmcs;
-use p3_field::{Field, PackedField, PackedValue};
-use p3_keccak::Keccak256Hash;
+use p3_field::{Field, PackedValue};
+use p3_keccak::{Keccak256Hash, VECTOR_LEN};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_mds::integrated_coset_mds::IntegratedCosetMds;
@@ -25,6 +25,7 @@ fn bench_merkle_trees(criterion: &mut Criterion) {
     bench_bb_rescue(criterion);
     bench_bb_blake3(criterion);
     bench_bb_keccak(criterion);
+    bench_bb_keccak_vectorized(criterion);
 }
 
 fn bench_bb_poseidon2(criterion: &mut Criterion) {
@@ -99,23 +100,38 @@ fn bench_bb_keccak(criterion: &mut Criterion) {
     bench_merkle_tree::<F, u8, H, C, 32>(criterion, h, c);
 }
 
+fn bench_bb_keccak_vectorized(criterion: &mut Criterion) {
+    type F = BabyBear;
+
+    // Leaves and nodes are hashed `VECTOR_LEN` at a time, with digests read as `[u32; 8]`.
+    type H = SerializingHasher32<Keccak256Hash>;
+    let k = Keccak256Hash {};
+    let h = H::new(k);
+
+    type C = CompressionFunctionFromHasher<Keccak256Hash, 2, 8>;
+    let c = C::new(k);
+
+    bench_mmcs::<[F; VECTOR_LEN], [u32; VECTOR_LEN], H, C, 8>(criterion, h, c.clone());
+    bench_merkle_tree::<[F; VECTOR_LEN], [u32; VECTOR_LEN], H, C, 8>(criterion, h, c);
+}
+
 fn bench_merkle_tree<P, PW, H, C, const DIGEST_ELEMS: usize>(criterion: &mut Criterion, h: H, c: C)
 where
-    P: PackedField,
+    P: PackedValue,
     PW: PackedValue,
-    H: CryptographicHasher<P::Scalar, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
     H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
     H: Sync,
     C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
     C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
     C: Sync,
     [PW::Value; DIGEST_ELEMS]: Serialize + DeserializeOwned,
-    Standard: Distribution<P::Scalar>,
+    Standard: Distribution<P::Value>,
 {
     const ROWS: usize = 1 << 15;
     const COLS: usize = 135;
 
-    let matrix = RowMajorMatrix::<P::Scalar>::rand(&mut thread_rng(), ROWS, COLS);
+    let matrix = RowMajorMatrix::<P::Value>::rand(&mut thread_rng(), ROWS, COLS);
     let dims = matrix.dimensions();
     let leaves = vec![matrix];
 
@@ -137,22 +153,22 @@ where
 
 fn bench_mmcs<P, PW, H, C, const DIGEST_ELEMS: usize>(criterion: &mut Criterion, h: H, c: C)
 where
-    P: PackedField,
+    P: PackedValue,
     PW: PackedValue,
-    H: CryptographicHasher<P::Scalar, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
     H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
     H: Sync,
     C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
     C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
     C: Sync,
     [PW::Value; DIGEST_ELEMS]: Serialize + DeserializeOwned,
-    Standard: Distribution<P::Scalar>,
+    Standard: Distribution<P::Value>,
 {
     const ROWS: usize = 1 << 15;
     const COLS: usize = 135;
 
-    let matrix_1 = RowMajorMatrix::<P::Scalar>::rand(&mut thread_rng(), ROWS + 1, COLS);
-    let matrix_2 = RowMajorMatrix::<P::Scalar>::rand(&mut thread_rng(), ROWS / 2 + 1, COLS);
+    let matrix_1 = RowMajorMatrix::<P::Value>::rand(&mut thread_rng(), ROWS + 1, COLS);
+    let matrix_2 = RowMajorMatrix::<P::Value>::rand(&mut thread_rng(), ROWS / 2 + 1, COLS);
     let dims = vec![matrix_1.dimensions(), matrix_2.dimensions()];
     let leaves = vec![matrix_1, matrix_2];
 
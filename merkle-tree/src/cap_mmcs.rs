@@ -0,0 +1,365 @@
+use alloc::vec::Vec;
+
+use p3_commit::{Mmcs, ProofSizeStats, RowChunkSource};
+use p3_field::PackedValue;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
+use p3_symmetric::{CryptographicHasher, MerkleCap, PseudoCompressionFunction};
+use p3_util::log2_ceil_usize;
+use serde::{Deserialize, Serialize};
+
+use crate::mmcs::{path_hash_count, tree_size};
+use crate::MerkleTreeError::{RootMismatch, WrongBatchSize, WrongCapLength, WrongHeight};
+use crate::{MerkleTree, MerkleTreeError, MerkleTreeMmcs};
+
+/// A vector commitment scheme backed by a `MerkleTree`, which commits to the top `2^cap_height`
+/// digests of the tree (the "cap") rather than to its root.
+///
+/// Each opening proof is `cap_height` digests shorter than the corresponding `MerkleTreeMmcs`
+/// proof. Since a matrix must be hashed into the tree below the cap for its openings to be
+/// checkable, the cap height actually used for a batch is clamped to `log2_ceil` of the smallest
+/// committed height.
+///
+/// Generics:
+/// - `P`: a leaf value
+/// - `PW`: an element of a digest
+/// - `H`: the leaf hasher
+/// - `C`: the digest compression function
+#[derive(Copy, Clone, Debug)]
+pub struct MerkleCapMmcs<P, PW, H, C, const DIGEST_ELEMS: usize> {
+    inner: MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>,
+    cap_height: usize,
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleCapMmcs<P, PW, H, C, DIGEST_ELEMS> {
+    pub const fn new(hash: H, compress: C, cap_height: usize) -> Self {
+        Self {
+            inner: MerkleTreeMmcs::new(hash, compress),
+            cap_height,
+        }
+    }
+
+    pub const fn cap_height(&self) -> usize {
+        self.cap_height
+    }
+
+    /// The cap height used for a batch of matrices whose smallest height is `min_height`.
+    fn effective_cap_height(&self, min_height: usize) -> usize {
+        self.cap_height.min(log2_ceil_usize(min_height))
+    }
+
+    /// The number of siblings in an opening proof for matrices of the given dimensions.
+    fn path_len(&self, dimensions: &[Dimensions]) -> usize {
+        let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap_or(1);
+        let min_height = dimensions.iter().map(|dim| dim.height).min().unwrap_or(1);
+        log2_ceil_usize(max_height) - self.effective_cap_height(min_height)
+    }
+
+    /// The number of digests in the cap of matrices of the given dimensions, i.e. in the layer of
+    /// the tree `path_len` layers above the leaves, following the padding of `MerkleTree`.
+    fn cap_len(&self, dimensions: &[Dimensions]) -> usize {
+        let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap_or(1);
+        let mut len = if max_height == 1 {
+            1
+        } else {
+            max_height + max_height % 2
+        };
+        for _ in 0..self.path_len(dimensions) {
+            len = if len == 2 { 1 } else { (len / 2 + 1) & !1 };
+        }
+        len
+    }
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> Mmcs<P::Value>
+    for MerkleCapMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    type ProverData<M> = MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>;
+    type Commitment = MerkleCap<P::Value, PW::Value, DIGEST_ELEMS>;
+    type Proof = Vec<[PW::Value; DIGEST_ELEMS]>;
+    type Error = MerkleTreeError;
+
+    fn commit<M: Matrix<P::Value>>(
+        &self,
+        inputs: Vec<M>,
+    ) -> (Self::Commitment, Self::ProverData<M>) {
+        let (_, tree) = self.inner.commit(inputs);
+        let min_height = tree.leaves.iter().map(|m| m.height()).min().unwrap();
+        let cap = tree.cap(self.effective_cap_height(min_height));
+        (cap, tree)
+    }
+
+    fn commit_chunked<S: RowChunkSource<P::Value>>(
+        &self,
+        inputs: Vec<S>,
+    ) -> (Self::Commitment, Self::ProverData<RowMajorMatrix<P::Value>>) {
+        let (_, tree) = self.inner.commit_chunked(inputs);
+        let min_height = tree.leaves.iter().map(|m| m.height()).min().unwrap();
+        let cap = tree.cap(self.effective_cap_height(min_height));
+        (cap, tree)
+    }
+
+    fn open_batch<M: Matrix<P::Value>>(
+        &self,
+        index: usize,
+        prover_data: &MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>,
+    ) -> (Vec<Vec<P::Value>>, Vec<[PW::Value; DIGEST_ELEMS]>) {
+        let (openings, mut proof) = self.inner.open_batch(index, prover_data);
+        let min_height = prover_data.leaves.iter().map(|m| m.height()).min().unwrap();
+        proof.truncate(proof.len() - self.effective_cap_height(min_height));
+        (openings, proof)
+    }
+
+    fn get_matrices<'a, M: Matrix<P::Value>>(
+        &self,
+        prover_data: &'a Self::ProverData<M>,
+    ) -> Vec<&'a M> {
+        self.inner.get_matrices(prover_data)
+    }
+
+    fn verify_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        index: usize,
+        opened_values: &[Vec<P::Value>],
+        proof: &Self::Proof,
+    ) -> Result<(), Self::Error> {
+        // Check that the openings have the correct shape.
+        if dimensions.is_empty() || dimensions.len() != opened_values.len() {
+            return Err(WrongBatchSize);
+        }
+
+        // A cap with extra digests would let the prover change the commitment after it has been
+        // observed.
+        let cap_len = self.cap_len(dimensions);
+        if commit.len() != cap_len {
+            return Err(WrongCapLength {
+                expected: cap_len,
+                actual: commit.len(),
+            });
+        }
+
+        let max_height = dimensions
+            .iter()
+            .map(|dim| dim.height)
+            .max()
+            .unwrap_or_default();
+        let path_len = self.path_len(dimensions);
+        if proof.len() != path_len {
+            return Err(WrongHeight {
+                max_height,
+                num_siblings: proof.len(),
+            });
+        }
+
+        let node = self
+            .inner
+            .compress_path(dimensions, index, opened_values, proof);
+
+        match commit.get(index >> path_len) {
+            Some(cap_node) if cap_node == &node => Ok(()),
+            _ => Err(RootMismatch),
+        }
+    }
+
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        ProofSizeStats::new::<P::Value, PW::Value>(
+            dimensions,
+            self.path_len(dimensions),
+            DIGEST_ELEMS,
+            0,
+        )
+    }
+
+    fn verification_hash_count(&self, dimensions: &[Dimensions]) -> usize {
+        path_hash_count(dimensions, self.path_len(dimensions))
+    }
+
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        tree_size::<P::Value, PW::Value, DIGEST_ELEMS>(dimensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use itertools::Itertools;
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_commit::Mmcs;
+    use p3_field::{Field, FieldAlgebra};
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::Matrix;
+    use p3_symmetric::{MerkleCap, PaddingFreeSponge, TruncatedPermutation};
+    use rand::thread_rng;
+
+    use super::MerkleCapMmcs;
+    use crate::MerkleTreeError::{WrongBatchSize, WrongCapLength};
+    use crate::MerkleTreeMmcs;
+
+    type F = BabyBear;
+
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    type MyMmcs =
+        MerkleTreeMmcs<<F as Field>::Packing, <F as Field>::Packing, MyHash, MyCompress, 8>;
+    type MyCapMmcs =
+        MerkleCapMmcs<<F as Field>::Packing, <F as Field>::Packing, MyHash, MyCompress, 8>;
+
+    #[test]
+    fn zero_cap_height_is_root() {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash.clone(), compress.clone());
+        let cap_mmcs = MyCapMmcs::new(hash, compress, 0);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 32, 3);
+        let (root, _) = mmcs.commit(vec![mat.clone()]);
+        let (cap, _) = cap_mmcs.commit(vec![mat]);
+
+        let root: [F; 8] = root.into();
+        assert_eq!(cap.as_slice(), &[root]);
+    }
+
+    #[test]
+    fn open_and_verify_with_cap() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 3);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 1000, 8),
+            RowMajorMatrix::<F>::rand(&mut rng, 70, 5),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (commit, prover_data) = mmcs.commit(mats);
+        assert_eq!(commit.len(), 8);
+
+        for index in [0, 6, 511, 999] {
+            let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+            assert_eq!(proof.len(), 10 - 3);
+            mmcs.verify_batch(&commit, &dims, index, &opened_values, &proof)
+                .expect("expected verification to succeed");
+        }
+    }
+
+    #[test]
+    fn cap_height_clamped_by_smallest_matrix() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 4);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 64, 4),
+            RowMajorMatrix::<F>::rand(&mut rng, 4, 4),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (commit, prover_data) = mmcs.commit(mats);
+        assert_eq!(commit.len(), 4);
+
+        let (opened_values, proof) = mmcs.open_batch(37, &prover_data);
+        assert_eq!(proof.len(), 4);
+        mmcs.verify_batch(&commit, &dims, 37, &opened_values, &proof)
+            .expect("expected verification to succeed");
+    }
+
+    #[test]
+    fn cap_len_matches_commitment() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 3);
+
+        for heights in [vec![16], vec![17, 9], vec![1000, 70, 8], vec![5], vec![1]] {
+            let mats = heights
+                .iter()
+                .map(|&h| RowMajorMatrix::<F>::rand(&mut rng, h, 2))
+                .collect_vec();
+            let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+            let (commit, _) = mmcs.commit(mats);
+            assert_eq!(commit.len(), mmcs.cap_len(&dims));
+        }
+    }
+
+    #[test]
+    fn verify_extended_cap_fails() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 2);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 16, 3);
+        let dims = vec![mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+        let (opened_values, proof) = mmcs.open_batch(5, &prover_data);
+
+        let mut digests = commit.as_slice().to_vec();
+        digests.push(digests[0]);
+        let extended: MerkleCap<F, F, 8> = digests.into();
+        assert!(matches!(
+            mmcs.verify_batch(&extended, &dims, 5, &opened_values, &proof),
+            Err(WrongCapLength {
+                expected: 4,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn verify_empty_batch_fails() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 2);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 16, 3);
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+        let (_, proof) = mmcs.open_batch(5, &prover_data);
+        assert!(matches!(
+            mmcs.verify_batch(&commit, &[], 5, &[], &proof),
+            Err(WrongBatchSize)
+        ));
+    }
+
+    #[test]
+    fn verify_tampered_opening_fails() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyCapMmcs::new(hash, compress, 2);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 16, 3);
+        let dims = vec![mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+
+        let (mut opened_values, proof) = mmcs.open_batch(5, &prover_data);
+        opened_values[0][1] += F::ONE;
+        mmcs.verify_batch(&commit, &dims, 5, &opened_values, &proof)
+            .expect_err("expected verification to fail");
+    }
+}
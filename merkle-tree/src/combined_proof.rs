@@ -0,0 +1,454 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use itertools::{izip, Itertools};
+use p3_field::PackedValue;
+use p3_matrix::{Dimensions, Matrix};
+use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_util::log2_ceil_usize;
+use serde::{Deserialize, Serialize};
+
+use crate::MerkleTreeError::{InconsistentOpenings, RootMismatch, WrongBatchSize, WrongHeight};
+use crate::{MerkleTree, MerkleTreeError, MerkleTreeMmcs};
+
+/// Authentication data for opening several indices of the same `MerkleTree`.
+///
+/// Rather than one path per index, this holds the pruned subtree spanned by all opened leaves:
+/// a sibling digest is only included if the verifier cannot compute it from the openings
+/// themselves. With many indices, paths share most of their upper layers, so this is
+/// considerably smaller than independent proofs.
+///
+/// Siblings are ordered layer by layer from the leaves upward, and by position within a layer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+#[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+pub struct CombinedOpeningProof<W, const DIGEST_ELEMS: usize> {
+    pub siblings: Vec<[W; DIGEST_ELEMS]>,
+}
+
+/// Authentication data for opening the same indices in several batches committed with the same
+/// `MerkleTreeMmcs`, e.g. the trace, quotient and permutation commitments of a multi-round STARK.
+///
+/// Each batch gets the pruned subtree of a `CombinedOpeningProof` for the indices as reduced to
+/// its height, so indices which land on the same row of a shorter batch share their path.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+#[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+pub struct MultiBatchOpeningProof<W, const DIGEST_ELEMS: usize> {
+    pub batches: Vec<CombinedOpeningProof<W, DIGEST_ELEMS>>,
+}
+
+/// The amount by which indices into the tallest of all batches are shifted right to index into
+/// each batch, given the `log2_ceil` of the tallest height of each batch.
+fn batch_index_shifts(log_max_heights: &[usize]) -> impl Iterator<Item = usize> + '_ {
+    let log_global_max_height = log_max_heights.iter().copied().max().unwrap_or(0);
+    log_max_heights
+        .iter()
+        .map(move |&log_max_height| log_global_max_height - log_max_height)
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    PW::Value: Eq,
+{
+    /// Open a batch of rows for each of `indices`, with a single proof for all of them.
+    ///
+    /// Each index follows the semantics of `Mmcs::open_batch`. Returns `(openings, proof)` where
+    /// `openings[i]` holds the rows opened at `indices[i]`.
+    #[allow(clippy::type_complexity)]
+    pub fn open_multi_index<M: Matrix<P::Value>>(
+        &self,
+        indices: &[usize],
+        prover_data: &MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>,
+    ) -> (
+        Vec<Vec<Vec<P::Value>>>,
+        CombinedOpeningProof<PW::Value, DIGEST_ELEMS>,
+    ) {
+        let max_height = prover_data.leaves.iter().map(|m| m.height()).max().unwrap();
+        let log_max_height = log2_ceil_usize(max_height);
+
+        let openings = indices
+            .iter()
+            .map(|&index| {
+                prover_data
+                    .leaves
+                    .iter()
+                    .map(|matrix| {
+                        let bits_reduced = log_max_height - log2_ceil_usize(matrix.height());
+                        matrix.row(index >> bits_reduced).collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The sibling path of each distinct index, keyed by index.
+        let paths: BTreeMap<usize, Vec<_>> = indices
+            .iter()
+            .map(|&index| {
+                (
+                    index,
+                    prover_data.siblings(&self.hash, &self.compress, index),
+                )
+            })
+            .collect();
+
+        let mut siblings = Vec::new();
+        for layer in 0..log_max_height {
+            let known = paths
+                .keys()
+                .map(|&index| index >> layer)
+                .dedup()
+                .collect_vec();
+            for &pos in &known {
+                if known.binary_search(&(pos ^ 1)).is_err() {
+                    let index = *paths.keys().find(|&&i| i >> layer == pos).unwrap();
+                    siblings.push(paths[&index][layer]);
+                }
+            }
+        }
+
+        (openings, CombinedOpeningProof { siblings })
+    }
+
+    /// Verify an opening produced by `open_multi_index`.
+    ///
+    /// `dimensions` are the dimensions of the committed matrices, and `opened_values[i]` holds
+    /// the rows opened at `indices[i]`.
+    pub fn verify_multi_index(
+        &self,
+        commit: &Hash<P::Value, PW::Value, DIGEST_ELEMS>,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<P::Value>>],
+        proof: &CombinedOpeningProof<PW::Value, DIGEST_ELEMS>,
+    ) -> Result<(), MerkleTreeError> {
+        if indices.is_empty()
+            || dimensions.is_empty()
+            || indices.len() != opened_values.len()
+            || opened_values
+                .iter()
+                .any(|openings| openings.len() != dimensions.len())
+        {
+            return Err(WrongBatchSize);
+        }
+
+        let max_height = dimensions
+            .iter()
+            .map(|dim| dim.height)
+            .max()
+            .unwrap_or_default();
+        let log_max_height = log2_ceil_usize(max_height);
+
+        let mut heights_tallest_first = dimensions
+            .iter()
+            .enumerate()
+            .sorted_by_key(|(_, dims)| Reverse(dims.height))
+            .peekable();
+        let mut curr_height_padded = max_height.next_power_of_two();
+
+        // Hash the rows of the given matrices opened at each query, checking that queries which
+        // land on the same node agree, and return the digest for each node.
+        let hash_rows =
+            |matrices: &[usize],
+             layer: usize|
+             -> Result<BTreeMap<usize, [PW::Value; DIGEST_ELEMS]>, MerkleTreeError> {
+                let mut digests = BTreeMap::new();
+                for (&index, openings) in indices.iter().zip(opened_values) {
+                    let digest = self
+                        .hash
+                        .hash_iter_slices(matrices.iter().map(|&i| openings[i].as_slice()));
+                    if let Some(existing) = digests.insert(index >> layer, digest) {
+                        if existing != digest {
+                            return Err(InconsistentOpenings);
+                        }
+                    }
+                }
+                Ok(digests)
+            };
+
+        let tallest = heights_tallest_first
+            .peeking_take_while(|(_, dims)| dims.height.next_power_of_two() == curr_height_padded)
+            .map(|(i, _)| i)
+            .collect_vec();
+        let mut nodes = hash_rows(&tallest, 0)?;
+
+        let mut siblings = proof.siblings.iter();
+        for layer in 1..=log_max_height {
+            let mut next_nodes = BTreeMap::new();
+            for (&pos, &digest) in &nodes {
+                if pos & 1 == 1 && nodes.contains_key(&(pos ^ 1)) {
+                    // Already combined with its left sibling.
+                    continue;
+                }
+                let sibling = match nodes.get(&(pos ^ 1)) {
+                    Some(&sibling) => sibling,
+                    None => *siblings.next().ok_or(WrongHeight {
+                        max_height,
+                        num_siblings: proof.siblings.len(),
+                    })?,
+                };
+                let (left, right) = if pos & 1 == 0 {
+                    (digest, sibling)
+                } else {
+                    (sibling, digest)
+                };
+                next_nodes.insert(pos >> 1, self.compress.compress([left, right]));
+            }
+            curr_height_padded >>= 1;
+
+            let next_height = heights_tallest_first
+                .peek()
+                .map(|(_, dims)| dims.height)
+                .filter(|h| h.next_power_of_two() == curr_height_padded);
+            if let Some(next_height) = next_height {
+                let injected = heights_tallest_first
+                    .peeking_take_while(|(_, dims)| dims.height == next_height)
+                    .map(|(i, _)| i)
+                    .collect_vec();
+                for (pos, rows_digest) in hash_rows(&injected, layer)? {
+                    let node = next_nodes.get_mut(&pos).unwrap();
+                    *node = self.compress.compress([*node, rows_digest]);
+                }
+            }
+
+            nodes = next_nodes;
+        }
+
+        if siblings.next().is_some() {
+            return Err(WrongHeight {
+                max_height,
+                num_siblings: proof.siblings.len(),
+            });
+        }
+
+        match nodes.get(&0) {
+            Some(root) if nodes.len() == 1 && commit == root => Ok(()),
+            _ => Err(RootMismatch),
+        }
+    }
+
+    /// Open each of `indices` in each of several batches, with a single proof for all of them.
+    ///
+    /// Indices are row indices of the tallest matrix across *all* batches, and are reduced for each
+    /// batch by the difference in `log2_ceil` heights between its tallest matrix and the global
+    /// tallest matrix. Returns `(openings, proof)` where `openings[b][i]` holds the rows of batch
+    /// `b` opened at `indices[i]`.
+    #[allow(clippy::type_complexity)]
+    pub fn open_multi_batch<M: Matrix<P::Value>>(
+        &self,
+        indices: &[usize],
+        prover_data: &[&MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>],
+    ) -> (
+        Vec<Vec<Vec<Vec<P::Value>>>>,
+        MultiBatchOpeningProof<PW::Value, DIGEST_ELEMS>,
+    ) {
+        let log_max_heights = prover_data
+            .iter()
+            .map(|data| log2_ceil_usize(data.leaves.iter().map(|m| m.height()).max().unwrap()))
+            .collect_vec();
+        let (openings, batches) = prover_data
+            .iter()
+            .zip(batch_index_shifts(&log_max_heights))
+            .map(|(data, shift)| {
+                let reduced = indices.iter().map(|&index| index >> shift).collect_vec();
+                self.open_multi_index(&reduced, data)
+            })
+            .unzip();
+        (openings, MultiBatchOpeningProof { batches })
+    }
+
+    /// Verify an opening produced by `open_multi_batch`.
+    ///
+    /// `commits`, `dimensions`, `opened_values` and the batches of `proof` must all have one entry
+    /// per batch, and `indices` follow the same semantics as in `open_multi_batch`.
+    pub fn verify_multi_batch(
+        &self,
+        commits: &[Hash<P::Value, PW::Value, DIGEST_ELEMS>],
+        dimensions: &[Vec<Dimensions>],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<Vec<P::Value>>>],
+        proof: &MultiBatchOpeningProof<PW::Value, DIGEST_ELEMS>,
+    ) -> Result<(), MerkleTreeError> {
+        let num_batches = commits.len();
+        if dimensions.len() != num_batches
+            || opened_values.len() != num_batches
+            || proof.batches.len() != num_batches
+            || dimensions.iter().any(|dims| dims.is_empty())
+        {
+            return Err(WrongBatchSize);
+        }
+
+        let log_max_heights = dimensions
+            .iter()
+            .map(|dims| log2_ceil_usize(dims.iter().map(|d| d.height).max().unwrap()))
+            .collect_vec();
+        for (commit, dims, shift, opened, batch_proof) in izip!(
+            commits,
+            dimensions,
+            batch_index_shifts(&log_max_heights),
+            opened_values,
+            &proof.batches
+        ) {
+            let reduced = indices.iter().map(|&index| index >> shift).collect_vec();
+            self.verify_multi_index(commit, dims, &reduced, opened, batch_proof)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use itertools::Itertools;
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_commit::Mmcs;
+    use p3_field::{Field, FieldAlgebra};
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::Matrix;
+    use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+    use rand::thread_rng;
+
+    use crate::MerkleTreeMmcs;
+
+    type F = BabyBear;
+
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    type MyMmcs =
+        MerkleTreeMmcs<<F as Field>::Packing, <F as Field>::Packing, MyHash, MyCompress, 8>;
+
+    fn setup() -> MyMmcs {
+        let perm = Perm::new_from_rng_128(&mut thread_rng());
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        MyMmcs::new(hash, compress)
+    }
+
+    #[test]
+    fn combined_proof_verifies_and_is_smaller() {
+        let mut rng = thread_rng();
+        let mmcs = setup();
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 1000, 4),
+            RowMajorMatrix::<F>::rand(&mut rng, 70, 2),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 3),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [3, 2, 700, 999, 3, 64, 65, 300];
+        let (opened_values, proof) = mmcs.open_multi_index(&indices, &prover_data);
+
+        for (&index, openings) in indices.iter().zip(&opened_values) {
+            assert_eq!(openings, &mmcs.open_batch(index, &prover_data).0);
+        }
+        let independent_siblings = indices.iter().sorted().dedup().count() * 10;
+        assert!(proof.siblings.len() < independent_siblings);
+
+        mmcs.verify_multi_index(&commit, &dims, &indices, &opened_values, &proof)
+            .expect("expected verification to succeed");
+    }
+
+    #[test]
+    fn combined_proof_single_index_matches_path() {
+        let mut rng = thread_rng();
+        let mmcs = setup();
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 64, 4);
+        let dims = vec![mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+
+        let (opened_values, proof) = mmcs.open_multi_index(&[37], &prover_data);
+        assert_eq!(proof.siblings, mmcs.open_batch(37, &prover_data).1);
+        mmcs.verify_multi_index(&commit, &dims, &[37], &opened_values, &proof)
+            .expect("expected verification to succeed");
+    }
+
+    #[test]
+    fn multi_batch_across_commitments() {
+        let mut rng = thread_rng();
+        let mmcs = setup();
+
+        let trace = vec![RowMajorMatrix::<F>::rand(&mut rng, 64, 4)];
+        let quotient = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 16, 8),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+        ];
+        let dims = vec![
+            trace.iter().map(|m| m.dimensions()).collect_vec(),
+            quotient.iter().map(|m| m.dimensions()).collect_vec(),
+        ];
+
+        let (trace_commit, trace_data) = mmcs.commit(trace);
+        let (quotient_commit, quotient_data) = mmcs.commit(quotient);
+        let commits = [trace_commit, quotient_commit];
+
+        // 44 and 45 land on the same row of the quotient batch, which is 4 times shorter.
+        let indices = [45, 44, 3];
+        let (opened_values, proof) =
+            mmcs.open_multi_batch(&indices, &[&trace_data, &quotient_data]);
+        assert_eq!(
+            opened_values[1][0],
+            mmcs.open_batch(45 >> 2, &quotient_data).0
+        );
+        let independent_siblings = indices.len() * 6 + indices.len() * 4;
+        let num_siblings = proof
+            .batches
+            .iter()
+            .map(|b| b.siblings.len())
+            .sum::<usize>();
+        assert!(num_siblings < independent_siblings);
+        mmcs.verify_multi_batch(&commits, &dims, &indices, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        mmcs.verify_multi_batch(&commits, &dims, &[45, 40, 3], &opened_values, &proof)
+            .expect_err("expected verification to fail");
+        mmcs.verify_multi_batch(&commits[..1], &dims, &indices, &opened_values, &proof)
+            .expect_err("expected verification to fail");
+    }
+
+    #[test]
+    fn combined_proof_rejects_tampering() {
+        let mut rng = thread_rng();
+        let mmcs = setup();
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 32, 4),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [4, 5, 20];
+        let (opened_values, proof) = mmcs.open_multi_index(&indices, &prover_data);
+
+        // Indices 4 and 5 share a row of the shorter matrix; the openings must agree.
+        let mut inconsistent = opened_values.clone();
+        inconsistent[1][1][0] += F::ONE;
+        mmcs.verify_multi_index(&commit, &dims, &indices, &inconsistent, &proof)
+            .expect_err("expected verification to fail");
+
+        let mut short_proof = proof.clone();
+        short_proof.siblings.pop();
+        mmcs.verify_multi_index(&commit, &dims, &indices, &opened_values, &short_proof)
+            .expect_err("expected verification to fail");
+
+        let mut long_proof = proof.clone();
+        long_proof.siblings.push([F::ZERO; 8]);
+        mmcs.verify_multi_index(&commit, &dims, &indices, &opened_values, &long_proof)
+            .expect_err("expected verification to fail");
+
+        // No matrices at all, with an opening of no rows at each index.
+        let empty = vec![vec![]; indices.len()];
+        mmcs.verify_multi_index(&commit, &[], &indices, &empty, &proof)
+            .expect_err("expected verification to fail");
+    }
+}
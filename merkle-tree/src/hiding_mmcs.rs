@@ -2,14 +2,14 @@ use alloc::vec::Vec;
 use core::cell::RefCell;
 
 use itertools::Itertools;
-use p3_commit::Mmcs;
+use p3_commit::{HidingMmcs, Mmcs, ProofSizeStats};
 use p3_field::PackedValue;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::stack::HorizontalPair;
 use p3_matrix::{Dimensions, Matrix};
 use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
 use rand::distributions::{Distribution, Standard};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
@@ -53,6 +53,20 @@ impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize>
     }
 }
 
+impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize>
+    MerkleTreeHidingMmcs<P, PW, H, C, R, DIGEST_ELEMS, SALT_ELEMS>
+where
+    R: SeedableRng,
+{
+    /// Create a hiding MMCS whose salts are drawn from an `R` seeded with `seed`.
+    ///
+    /// Commitments made by two such MMCS instances created from the same seed are identical, which
+    /// makes proofs reproducible. The seed must be kept secret for the commitments to be hiding.
+    pub fn from_seed(hash: H, compress: C, seed: R::Seed) -> Self {
+        Self::new(hash, compress, R::from_seed(seed))
+    }
+}
+
 impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize> Mmcs<P::Value>
     for MerkleTreeHidingMmcs<P, PW, H, C, R, DIGEST_ELEMS, SALT_ELEMS>
 where
@@ -137,6 +151,54 @@ where
         self.inner
             .verify_batch(commit, dimensions, index, &opened_salted_values, siblings)
     }
+
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        let num_digests = self.inner.proof_size_hint(dimensions).num_digests;
+        let mut stats = ProofSizeStats::new::<P::Value, PW::Value>(
+            dimensions,
+            num_digests,
+            DIGEST_ELEMS,
+            dimensions.len() * SALT_ELEMS,
+        );
+        // Each matrix's salt is a sequence of its own.
+        stats.num_sequences += dimensions.len();
+        stats
+    }
+
+    fn verification_hash_count(&self, dimensions: &[Dimensions]) -> usize {
+        self.inner.verification_hash_count(dimensions)
+    }
+
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        // The salts are committed as extra columns.
+        let salted_dimensions = dimensions
+            .iter()
+            .map(|dim| Dimensions {
+                width: dim.width + SALT_ELEMS,
+                height: dim.height,
+            })
+            .collect_vec();
+        self.inner.prover_data_size_hint(&salted_dimensions)
+    }
+}
+
+impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize> HidingMmcs<P::Value>
+    for MerkleTreeHidingMmcs<P, PW, H, C, R, DIGEST_ELEMS, SALT_ELEMS>
+where
+    P: PackedValue,
+    P::Value: Serialize + DeserializeOwned,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    R: Rng + Clone,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+    Standard: Distribution<P::Value>,
+{
 }
 
 #[cfg(test)]
@@ -151,6 +213,7 @@ mod tests {
     use p3_matrix::Matrix;
     use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
     use rand::prelude::*;
+    use rand_chacha::ChaCha20Rng;
 
     use super::MerkleTreeHidingMmcs;
     use crate::MerkleTreeError;
@@ -190,6 +253,46 @@ mod tests {
         let _ = mmcs.commit(vec![large_mat, small_mat]);
     }
 
+    #[test]
+    fn salts_derived_from_seed() {
+        type SeededMmcs = MerkleTreeHidingMmcs<
+            <F as Field>::Packing,
+            <F as Field>::Packing,
+            MyHash,
+            MyCompress,
+            ChaCha20Rng,
+            8,
+            SALT_ELEMS,
+        >;
+
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs_a = SeededMmcs::from_seed(hash.clone(), compress.clone(), [1; 32]);
+        let mmcs_b = SeededMmcs::from_seed(hash.clone(), compress.clone(), [1; 32]);
+        let mmcs_c = SeededMmcs::from_seed(hash, compress, [2; 32]);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut rng, 16, 4);
+        let dims = vec![mat.dimensions()];
+        let (commit_a, prover_data) = mmcs_a.commit(vec![mat.clone()]);
+        let (commit_b, _) = mmcs_b.commit(vec![mat.clone()]);
+        let (commit_c, _) = mmcs_c.commit(vec![mat]);
+        assert_eq!(commit_a, commit_b);
+        assert_ne!(commit_a, commit_c);
+
+        let (opened_values, mut proof) = mmcs_a.open_batch(3, &prover_data);
+        assert_eq!(proof.0[0].len(), SALT_ELEMS);
+        mmcs_a
+            .verify_batch(&commit_a, &dims, 3, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        proof.0[0][0] += F::ONE;
+        mmcs_a
+            .verify_batch(&commit_a, &dims, 3, &opened_values, &proof)
+            .expect_err("expected verification to fail with a tampered salt");
+    }
+
     #[test]
     fn different_widths() -> Result<(), MerkleTreeError> {
         let mut rng = thread_rng();
@@ -2,10 +2,16 @@
 
 extern crate alloc;
 
+mod cap_mmcs;
+mod combined_proof;
 mod hiding_mmcs;
 mod merkle_tree;
 mod mmcs;
+mod sharded;
 
+pub use cap_mmcs::*;
+pub use combined_proof::*;
 pub use hiding_mmcs::*;
 pub use merkle_tree::*;
 pub use mmcs::*;
+pub use sharded::*;
@@ -8,7 +8,7 @@ use itertools::Itertools;
 use p3_field::PackedValue;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, Hash, MerkleCap, PseudoCompressionFunction};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
@@ -25,6 +25,9 @@ pub struct MerkleTree<F, W, M, const DIGEST_ELEMS: usize> {
     // Enable deserialization for this type whenever the underlying array type supports it (len 1-32).
     #[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
     pub(crate) digest_layers: Vec<Vec<[W; DIGEST_ELEMS]>>,
+    /// The number of lowest digest layers which have been dropped, and must be recomputed from
+    /// the leaves when needed.
+    pub(crate) pruned_layers: usize,
     _phantom: PhantomData<F>,
 }
 
@@ -33,9 +36,49 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
 {
     /// Matrix heights need not be powers of two. However, if the heights of two given matrices
     /// round up to the same power of two, they must be equal.
+    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<M>) -> Self
+    where
+        P: PackedValue<Value = F>,
+        PW: PackedValue<Value = W>,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        Self::build::<P, PW, H, C>(h, c, leaves, None)
+    }
+
+    /// Like `new`, but with the first digest layer, which hashes the rows of the tallest matrices,
+    /// given, e.g. because it was hashed while those matrices were being computed.
+    pub fn new_with_first_digest_layer<P, PW, H, C>(
+        h: &H,
+        c: &C,
+        leaves: Vec<M>,
+        first_layer: Vec<[W; DIGEST_ELEMS]>,
+    ) -> Self
+    where
+        P: PackedValue<Value = F>,
+        PW: PackedValue<Value = W>,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+        H: Sync,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+        C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+        C: Sync,
+    {
+        Self::build::<P, PW, H, C>(h, c, leaves, Some(first_layer))
+    }
+
     #[instrument(name = "build merkle tree", level = "debug", skip_all,
                  fields(dimensions = alloc::format!("{:?}", leaves.iter().map(|l| l.dimensions()).collect::<Vec<_>>())))]
-    pub fn new<P, PW, H, C>(h: &H, c: &C, leaves: Vec<M>) -> Self
+    fn build<P, PW, H, C>(
+        h: &H,
+        c: &C,
+        leaves: Vec<M>,
+        first_layer: Option<Vec<[W; DIGEST_ELEMS]>>,
+    ) -> Self
     where
         P: PackedValue<Value = F>,
         PW: PackedValue<Value = W>,
@@ -71,10 +114,10 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
             .peeking_take_while(|m| m.height() == max_height)
             .collect_vec();
 
-        let mut digest_layers = vec![first_digest_layer::<P, PW, H, M, DIGEST_ELEMS>(
-            h,
-            tallest_matrices,
-        )];
+        let first_layer = first_layer.unwrap_or_else(|| {
+            first_digest_layer::<P, PW, H, M, DIGEST_ELEMS>(h, tallest_matrices)
+        });
+        let mut digest_layers = vec![first_layer];
         loop {
             let prev_layer = digest_layers.last().unwrap().as_slice();
             if prev_layer.len() == 1 {
@@ -99,6 +142,7 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
         Self {
             leaves,
             digest_layers,
+            pruned_layers: 0,
             _phantom: PhantomData,
         }
     }
@@ -110,10 +154,149 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
     {
         self.digest_layers.last().unwrap()[0].into()
     }
+
+    /// Drop the lowest `num_layers` digest layers, which will be recomputed from the leaves
+    /// whenever an opening needs them.
+    ///
+    /// The first digest layer alone accounts for about half of the digest memory, so pruning a
+    /// single layer roughly halves it. In exchange, each opening rehashes `2^num_layers` rows.
+    /// The root layer is never pruned.
+    pub fn prune_digest_layers(&mut self, num_layers: usize) {
+        let num_layers = num_layers.min(self.digest_layers.len() - 1);
+        for layer in &mut self.digest_layers[..num_layers] {
+            *layer = Vec::new();
+        }
+        self.pruned_layers = self.pruned_layers.max(num_layers);
+    }
+
+    #[must_use]
+    pub const fn num_pruned_layers(&self) -> usize {
+        self.pruned_layers
+    }
+
+    /// The sibling digests on the path from leaf `index` to the root, recomputing any pruned
+    /// layers from the leaves.
+    pub(crate) fn siblings<H, C>(&self, h: &H, c: &C, index: usize) -> Vec<[W; DIGEST_ELEMS]>
+    where
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        let log_max_height = self.digest_layers.len() - 1;
+        let node = index >> self.pruned_layers;
+        let subtree = self.recompute_subtree(h, c, node);
+
+        (0..log_max_height)
+            .map(|i| {
+                let sibling = (index >> i) ^ 1;
+                if i < self.pruned_layers {
+                    subtree[i][sibling - (node << (self.pruned_layers - i))]
+                } else {
+                    self.digest_layers[i][sibling]
+                }
+            })
+            .collect()
+    }
+
+    /// Recompute the pruned digest layers of the subtree below `node`, where `node` is an index
+    /// into the lowest unpruned layer. The `i`th returned layer holds the `2^(pruned_layers - i)`
+    /// digests of digest layer `i` which lie below `node`.
+    fn recompute_subtree<H, C>(&self, h: &H, c: &C, node: usize) -> Vec<Vec<[W; DIGEST_ELEMS]>>
+    where
+        W: Copy + Default,
+        H: CryptographicHasher<F, [W; DIGEST_ELEMS]>,
+        C: PseudoCompressionFunction<[W; DIGEST_ELEMS], 2>,
+    {
+        if self.pruned_layers == 0 {
+            return vec![];
+        }
+
+        let default_digest = [W::default(); DIGEST_ELEMS];
+        let max_height = self.leaves.iter().map(|m| m.height()).max().unwrap();
+        let tallest_matrices = self
+            .leaves
+            .iter()
+            .filter(|m| m.height() == max_height)
+            .collect_vec();
+
+        // The padded length of the current layer, mirroring the construction in `new`.
+        let mut layer_len = if max_height == 1 {
+            1
+        } else {
+            max_height + max_height % 2
+        };
+
+        let first_row = node << self.pruned_layers;
+        let mut layer = (first_row..first_row + (1 << self.pruned_layers))
+            .map(|r| {
+                if r < max_height {
+                    h.hash_iter(tallest_matrices.iter().flat_map(|m| m.row(r)))
+                } else {
+                    default_digest
+                }
+            })
+            .collect_vec();
+
+        let mut layers = Vec::with_capacity(self.pruned_layers);
+        for i in 1..self.pruned_layers {
+            let next_len = layer_len / 2;
+            let matrices_to_inject = self
+                .leaves
+                .iter()
+                .filter(|m| {
+                    m.height() != max_height
+                        && m.height().next_power_of_two() == next_len.next_power_of_two()
+                })
+                .collect_vec();
+
+            let first_pos = node << (self.pruned_layers - i);
+            let next_layer = layer
+                .chunks_exact(2)
+                .zip(first_pos..)
+                .map(|(pair, pos)| {
+                    if pos >= next_len {
+                        return default_digest;
+                    }
+                    let digest = c.compress([pair[0], pair[1]]);
+                    if matrices_to_inject.is_empty() {
+                        return digest;
+                    }
+                    let rows_digest = if pos < matrices_to_inject[0].height() {
+                        h.hash_iter(matrices_to_inject.iter().flat_map(|m| m.row(pos)))
+                    } else {
+                        default_digest
+                    };
+                    c.compress([digest, rows_digest])
+                })
+                .collect();
+            layers.push(core::mem::replace(&mut layer, next_layer));
+
+            layer_len = if layer_len == 2 {
+                1
+            } else {
+                (layer_len / 2 + 1) & !1
+            };
+        }
+        layers.push(layer);
+
+        layers
+    }
+
+    /// The digests of the layer `cap_height` levels below the root.
+    ///
+    /// `cap_height` is clamped to the height of the tree, so a cap of height `0` is just the root
+    /// and a sufficiently large cap is the first digest layer.
+    #[must_use]
+    pub fn cap(&self, cap_height: usize) -> MerkleCap<F, W, DIGEST_ELEMS> {
+        let num_layers = self.digest_layers.len();
+        let layer = num_layers - 1 - cap_height.min(num_layers - 1);
+        assert!(layer >= self.pruned_layers, "cap layer has been pruned");
+        self.digest_layers[layer].clone().into()
+    }
 }
 
 #[instrument(name = "first digest layer", level = "debug", skip_all)]
-fn first_digest_layer<P, PW, H, M, const DIGEST_ELEMS: usize>(
+pub(crate) fn first_digest_layer<P, PW, H, M, const DIGEST_ELEMS: usize>(
     h: &H,
     tallest_matrices: Vec<&M>,
 ) -> Vec<[PW::Value; DIGEST_ELEMS]>
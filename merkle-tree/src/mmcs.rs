@@ -1,15 +1,19 @@
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Reverse;
 use core::marker::PhantomData;
+use core::mem::size_of;
 
 use itertools::Itertools;
-use p3_commit::Mmcs;
+use p3_commit::{Mmcs, ProofSizeStats, RowChunkSource};
 use p3_field::PackedValue;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Dimensions, Matrix};
 use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
 use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 
+use crate::merkle_tree::first_digest_layer;
 use crate::MerkleTree;
 use crate::MerkleTreeError::{RootMismatch, WrongBatchSize, WrongHeight};
 
@@ -22,8 +26,11 @@ use crate::MerkleTreeError::{RootMismatch, WrongBatchSize, WrongHeight};
 /// - `C`: the digest compression function
 #[derive(Copy, Clone, Debug)]
 pub struct MerkleTreeMmcs<P, PW, H, C, const DIGEST_ELEMS: usize> {
-    hash: H,
-    compress: C,
+    pub(crate) hash: H,
+    pub(crate) compress: C,
+    /// The number of lowest digest layers to drop from committed trees. See
+    /// `MerkleTree::prune_digest_layers`.
+    pruned_layers: usize,
     _phantom: PhantomData<(P, PW)>,
 }
 
@@ -36,6 +43,13 @@ pub enum MerkleTreeError {
         num_siblings: usize,
     },
     RootMismatch,
+    /// The commitment has a different number of cap digests than the committed dimensions imply.
+    WrongCapLength {
+        expected: usize,
+        actual: usize,
+    },
+    /// Two opened indices which share a row of some matrix disagree on that row's values.
+    InconsistentOpenings,
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS> {
@@ -43,9 +57,88 @@ impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_
         Self {
             hash,
             compress,
+            pruned_layers: 0,
             _phantom: PhantomData,
         }
     }
+
+    /// Keep only the digest layers above the lowest `num_layers` in committed trees, trading
+    /// prover memory for rehashing `2^num_layers` rows per opening.
+    #[must_use]
+    pub fn with_pruned_layers(mut self, num_layers: usize) -> Self {
+        self.pruned_layers = num_layers;
+        self
+    }
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+{
+    /// Hash the opened rows and walk up the tree along `siblings`, returning the digest of the
+    /// node `siblings.len()` layers above the leaves.
+    ///
+    /// Matrices are injected at the layer matching their padded height, so every matrix must be
+    /// injected at or below the returned node; callers are responsible for checking this.
+    pub(crate) fn compress_path(
+        &self,
+        dimensions: &[Dimensions],
+        mut index: usize,
+        opened_values: &[Vec<P::Value>],
+        siblings: &[[PW::Value; DIGEST_ELEMS]],
+    ) -> [PW::Value; DIGEST_ELEMS] {
+        let mut heights_tallest_first = dimensions
+            .iter()
+            .enumerate()
+            .sorted_by_key(|(_, dims)| Reverse(dims.height))
+            .peekable();
+
+        let mut curr_height_padded = heights_tallest_first
+            .peek()
+            .unwrap()
+            .1
+            .height
+            .next_power_of_two();
+
+        let mut root = self.hash.hash_iter_slices(
+            heights_tallest_first
+                .peeking_take_while(|(_, dims)| {
+                    dims.height.next_power_of_two() == curr_height_padded
+                })
+                .map(|(i, _)| opened_values[i].as_slice()),
+        );
+
+        for &sibling in siblings.iter() {
+            let (left, right) = if index & 1 == 0 {
+                (root, sibling)
+            } else {
+                (sibling, root)
+            };
+
+            root = self.compress.compress([left, right]);
+            index >>= 1;
+            curr_height_padded >>= 1;
+
+            let next_height = heights_tallest_first
+                .peek()
+                .map(|(_, dims)| dims.height)
+                .filter(|h| h.next_power_of_two() == curr_height_padded);
+            if let Some(next_height) = next_height {
+                let next_height_openings_digest = self.hash.hash_iter_slices(
+                    heights_tallest_first
+                        .peeking_take_while(|(_, dims)| dims.height == next_height)
+                        .map(|(i, _)| opened_values[i].as_slice()),
+                );
+
+                root = self.compress.compress([root, next_height_openings_digest]);
+            }
+        }
+
+        root
+    }
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> Mmcs<P::Value>
@@ -71,7 +164,66 @@ where
         &self,
         inputs: Vec<M>,
     ) -> (Self::Commitment, Self::ProverData<M>) {
-        let tree = MerkleTree::new::<P, PW, H, C>(&self.hash, &self.compress, inputs);
+        let mut tree = MerkleTree::new::<P, PW, H, C>(&self.hash, &self.compress, inputs);
+        tree.prune_digest_layers(self.pruned_layers);
+        let root = tree.root();
+        (root, tree)
+    }
+
+    /// If a single matrix is the tallest, its rows are hashed into the first digest layer chunk by
+    /// chunk as it is produced. Otherwise a leaf spans the rows of several matrices, which are
+    /// produced one after another, so the matrices are produced first and then committed.
+    fn commit_chunked<S: RowChunkSource<P::Value>>(
+        &self,
+        inputs: Vec<S>,
+    ) -> (Self::Commitment, Self::ProverData<RowMajorMatrix<P::Value>>) {
+        let max_height = inputs
+            .iter()
+            .map(|input| input.dimensions().height)
+            .max()
+            .expect("No matrices given?");
+        let mut tallest = inputs
+            .iter()
+            .positions(|input| input.dimensions().height == max_height);
+        let (Some(tallest), None) = (tallest.next(), tallest.next()) else {
+            return self.commit(
+                inputs
+                    .into_iter()
+                    .map(|input| input.produce(|_| ()).0)
+                    .collect(),
+            );
+        };
+
+        let mut first_layer = Vec::new();
+        let leaves = inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, input)| {
+                if i != tallest {
+                    return input.produce(|_| ()).0;
+                }
+                let (leaf, chunk_digests) = input.produce(|chunk| {
+                    let mut digests =
+                        first_digest_layer::<P, PW, H, _, DIGEST_ELEMS>(&self.hash, vec![&chunk]);
+                    // Drop the padding to an even number of digests.
+                    digests.truncate(chunk.height());
+                    digests
+                });
+                first_layer = chunk_digests.concat();
+                leaf
+            })
+            .collect();
+        if max_height > 1 && max_height % 2 == 1 {
+            first_layer.push([PW::Value::default(); DIGEST_ELEMS]);
+        }
+
+        let mut tree = MerkleTree::new_with_first_digest_layer::<P, PW, H, C>(
+            &self.hash,
+            &self.compress,
+            leaves,
+            first_layer,
+        );
+        tree.prune_digest_layers(self.pruned_layers);
         let root = tree.root();
         (root, tree)
     }
@@ -95,9 +247,7 @@ where
             })
             .collect_vec();
 
-        let proof: Vec<_> = (0..log_max_height)
-            .map(|i| prover_data.digest_layers[i][(index >> i) ^ 1])
-            .collect();
+        let proof = prover_data.siblings(&self.hash, &self.compress, index);
 
         (openings, proof)
     }
@@ -113,7 +263,7 @@ where
         &self,
         commit: &Self::Commitment,
         dimensions: &[Dimensions],
-        mut index: usize,
+        index: usize,
         opened_values: &[Vec<P::Value>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
@@ -139,52 +289,7 @@ where
             });
         }
 
-        let mut heights_tallest_first = dimensions
-            .iter()
-            .enumerate()
-            .sorted_by_key(|(_, dims)| Reverse(dims.height))
-            .peekable();
-
-        let mut curr_height_padded = heights_tallest_first
-            .peek()
-            .unwrap()
-            .1
-            .height
-            .next_power_of_two();
-
-        let mut root = self.hash.hash_iter_slices(
-            heights_tallest_first
-                .peeking_take_while(|(_, dims)| {
-                    dims.height.next_power_of_two() == curr_height_padded
-                })
-                .map(|(i, _)| opened_values[i].as_slice()),
-        );
-
-        for &sibling in proof.iter() {
-            let (left, right) = if index & 1 == 0 {
-                (root, sibling)
-            } else {
-                (sibling, root)
-            };
-
-            root = self.compress.compress([left, right]);
-            index >>= 1;
-            curr_height_padded >>= 1;
-
-            let next_height = heights_tallest_first
-                .peek()
-                .map(|(_, dims)| dims.height)
-                .filter(|h| h.next_power_of_two() == curr_height_padded);
-            if let Some(next_height) = next_height {
-                let next_height_openings_digest = self.hash.hash_iter_slices(
-                    heights_tallest_first
-                        .peeking_take_while(|(_, dims)| dims.height == next_height)
-                        .map(|(i, _)| opened_values[i].as_slice()),
-                );
-
-                root = self.compress.compress([root, next_height_openings_digest]);
-            }
-        }
+        let root = self.compress_path(dimensions, index, opened_values, proof);
 
         if commit == &root {
             Ok(())
@@ -192,17 +297,66 @@ where
             Err(RootMismatch)
         }
     }
+
+    fn proof_size_hint(&self, dimensions: &[Dimensions]) -> ProofSizeStats {
+        let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap_or(1);
+        ProofSizeStats::new::<P::Value, PW::Value>(
+            dimensions,
+            log2_ceil_usize(max_height),
+            DIGEST_ELEMS,
+            0,
+        )
+    }
+
+    fn verification_hash_count(&self, dimensions: &[Dimensions]) -> usize {
+        let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap_or(1);
+        path_hash_count(dimensions, log2_ceil_usize(max_height))
+    }
+
+    fn prover_data_size_hint(&self, dimensions: &[Dimensions]) -> usize {
+        tree_size::<P::Value, PW::Value, DIGEST_ELEMS>(dimensions)
+    }
+}
+
+/// The size in bytes of a Merkle tree over matrices with the given dimensions: the matrices, and
+/// digest layers of halving lengths from the largest padded height down to the root.
+pub(crate) fn tree_size<T, W, const DIGEST_ELEMS: usize>(dimensions: &[Dimensions]) -> usize {
+    let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap_or(1);
+    let num_digests = 2 * max_height.next_power_of_two() - 1;
+    let leaves = dimensions
+        .iter()
+        .map(|dim| dim.width * dim.height)
+        .sum::<usize>();
+    leaves * size_of::<T>() + num_digests * size_of::<[W; DIGEST_ELEMS]>()
+}
+
+/// The number of hash and compression calls made when walking `num_siblings` layers up from the
+/// leaves: one hash per distinct padded height, one compression per sibling, and one compression
+/// per layer where shorter matrices are injected.
+pub(crate) fn path_hash_count(dimensions: &[Dimensions], num_siblings: usize) -> usize {
+    let num_heights = dimensions
+        .iter()
+        .map(|dim| dim.height.next_power_of_two())
+        .sorted_unstable()
+        .dedup()
+        .count();
+    (num_siblings + 2 * num_heights).saturating_sub(1)
 }
 
 #[cfg(test)]
 mod tests {
     use alloc::vec;
+    use alloc::vec::Vec;
 
     use itertools::Itertools;
     use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-    use p3_commit::Mmcs;
-    use p3_field::{Field, FieldAlgebra};
-    use p3_matrix::dense::RowMajorMatrix;
+    use p3_commit::{
+        CachingMmcs, ExtensionMmcs, MixedMatrixKind, MixedOpening, MixedOpeningError, Mmcs,
+        RowChunkSource,
+    };
+    use p3_field::extension::BinomialExtensionField;
+    use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
+    use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
     use p3_matrix::{Dimensions, Matrix};
     use p3_symmetric::{
         CryptographicHasher, PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
@@ -539,6 +693,162 @@ mod tests {
         .expect("expected verification to succeed");
     }
 
+    #[test]
+    fn pruned_layers_match_full_tree() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash.clone(), compress.clone());
+        let pruned_mmcs = MyMmcs::new(hash, compress).with_pruned_layers(4);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 1000, 8),
+            RowMajorMatrix::<F>::rand(&mut rng, 70, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 30, 5),
+            RowMajorMatrix::<F>::rand(&mut rng, 8, 2),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (commit, prover_data) = mmcs.commit(mats.clone());
+        let (pruned_commit, pruned_prover_data) = pruned_mmcs.commit(mats);
+        assert_eq!(commit, pruned_commit);
+        assert_eq!(pruned_prover_data.num_pruned_layers(), 4);
+
+        for index in [0, 1, 17, 500, 999] {
+            let opening = mmcs.open_batch(index, &prover_data);
+            let (opened_values, proof) = pruned_mmcs.open_batch(index, &pruned_prover_data);
+            assert_eq!(opening, (opened_values.clone(), proof.clone()));
+            pruned_mmcs
+                .verify_batch(&pruned_commit, &dims, index, &opened_values, &proof)
+                .expect("expected verification to succeed");
+        }
+    }
+
+    #[test]
+    fn extension_mmcs_mixed_commit() {
+        type EF = BinomialExtensionField<F, 4>;
+
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = ExtensionMmcs::<F, EF, _>::new(MyMmcs::new(hash, compress));
+
+        let trace = RowMajorMatrix::<F>::rand(&mut rng, 32, 5);
+        let quotient = RowMajorMatrix::<EF>::rand(&mut rng, 16, 2);
+        let dims = vec![trace.dimensions(), quotient.dimensions()];
+
+        let (commit, prover_data) = mmcs.commit_mixed(vec![trace.clone()], vec![quotient.clone()]);
+        let (opened_values, proof) = mmcs.open_mixed(21, &prover_data);
+
+        assert_eq!(opened_values[0].as_base(), Some(&*trace.row_slice(21)));
+        assert_eq!(
+            opened_values[1].as_extension(),
+            Some(&*quotient.row_slice(10))
+        );
+        assert_eq!(opened_values[1].as_base(), None);
+        let kinds = [MixedMatrixKind::Base, MixedMatrixKind::Extension];
+        mmcs.verify_mixed(&commit, &kinds, &dims, 21, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        // The flattened extension row hashes to the same leaf, but is not of the expected kind.
+        let mut flipped = opened_values.clone();
+        flipped[1] = MixedOpening::Base(
+            quotient
+                .row_slice(10)
+                .iter()
+                .flat_map(|el| el.as_base_slice())
+                .copied()
+                .collect(),
+        );
+        assert!(matches!(
+            mmcs.verify_mixed(&commit, &kinds, &dims, 21, &flipped, &proof),
+            Err(MixedOpeningError::WrongShape(1))
+        ));
+        assert!(matches!(
+            mmcs.verify_mixed(&commit, &kinds[..1], &dims, 21, &opened_values, &proof),
+            Err(MixedOpeningError::WrongBatchSize)
+        ));
+    }
+
+    #[test]
+    fn caching_mmcs_reuses_repeated_commits() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = CachingMmcs::new(MyMmcs::new(hash, compress), 4);
+
+        let preprocessed = RowMajorMatrix::<F>::rand(&mut rng, 32, 3);
+        let dims = vec![preprocessed.dimensions()];
+        let (expected_commit, _) = mmcs.inner().commit_matrix(preprocessed.clone());
+
+        // Cached from the second commit on.
+        for i in 0..3 {
+            let (commit, prover_data) = mmcs.commit_matrix(preprocessed.clone());
+            assert_eq!(commit, expected_commit);
+            assert_eq!(prover_data.is_cached(), i > 0);
+            assert_eq!(mmcs.get_matrices(&prover_data), vec![&preprocessed]);
+
+            let (opened_values, proof) = mmcs.open_batch(13, &prover_data);
+            assert_eq!(opened_values, vec![preprocessed.row_slice(13).to_vec()]);
+            mmcs.verify_batch(&commit, &dims, 13, &opened_values, &proof)
+                .expect("expected verification to succeed");
+        }
+        assert_eq!(mmcs.num_cached(), 1);
+
+        // A batch with different contents is not confused with the cached one.
+        let mut changed = preprocessed.clone();
+        changed.values[0] += F::ONE;
+        let (commit, prover_data) = mmcs.commit_matrix(changed);
+        assert_ne!(commit, expected_commit);
+        assert!(!prover_data.is_cached());
+
+        mmcs.clear();
+        assert!(!mmcs.commit_matrix(preprocessed).1.is_cached());
+    }
+
+    #[test]
+    fn proof_size_hint_matches_proof() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mats = vec![
+            RowMajorMatrix::<F>::rand(&mut rng, 100, 8),
+            RowMajorMatrix::<F>::rand(&mut rng, 100, 3),
+            RowMajorMatrix::<F>::rand(&mut rng, 12, 5),
+        ];
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+        let (_, prover_data) = mmcs.commit(mats);
+        let (opened_values, proof) = mmcs.open_batch(42, &prover_data);
+
+        let stats = mmcs.proof_size_hint(&dims);
+        assert_eq!(stats.num_digests, proof.len());
+        assert_eq!(
+            stats.num_opened_values,
+            opened_values.iter().map(|row| row.len()).sum::<usize>()
+        );
+        assert_eq!(stats.num_proof_values, 0);
+        assert_eq!(stats.digest_elems, 8);
+        // The sequence of rows, three rows, the proof values and the digests.
+        assert_eq!(stats.num_sequences, 6);
+        assert_eq!(stats.num_bytes(), (16 + 7 * 8) * 4);
+
+        // 2 leaf hashes, 7 sibling compressions and 1 injection.
+        assert_eq!(mmcs.verification_hash_count(&dims), 10);
+
+        // The leaves, and 128 + 64 + ... + 1 digests of 8 values.
+        assert_eq!(
+            mmcs.prover_data_size_hint(&dims),
+            (100 * 8 + 100 * 3 + 12 * 5) * 4 + 255 * 8 * 4
+        );
+    }
+
     #[test]
     fn different_widths() {
         let mut rng = thread_rng();
@@ -558,4 +868,58 @@ mod tests {
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
             .expect("expected verification to succeed");
     }
+
+    /// Produces a matrix in chunks of `chunk_rows` rows, the last of which may be shorter.
+    struct InChunks {
+        mat: RowMajorMatrix<F>,
+        chunk_rows: usize,
+    }
+
+    impl RowChunkSource<F> for InChunks {
+        fn dimensions(&self) -> Dimensions {
+            self.mat.dimensions()
+        }
+
+        fn produce<R: Send>(
+            self,
+            on_chunk: impl Fn(RowMajorMatrixView<'_, F>) -> R + Sync,
+        ) -> (RowMajorMatrix<F>, Vec<R>) {
+            let chunks = self
+                .mat
+                .values
+                .chunks(self.chunk_rows * self.mat.width())
+                .map(|chunk| on_chunk(RowMajorMatrixView::new(chunk, self.mat.width())))
+                .collect();
+            (self.mat, chunks)
+        }
+    }
+
+    #[test]
+    fn commit_chunked_matches_commit() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        // A single tallest matrix, whose leaves are hashed chunk by chunk, and two tallest
+        // matrices, which are committed as a whole.
+        for heights in [vec![13, 4, 13 * 2 + 1], vec![32, 32, 8]] {
+            let mats = heights
+                .iter()
+                .map(|&height| RowMajorMatrix::<F>::rand(&mut rng, height, 3))
+                .collect_vec();
+            let (commit, prover_data) = mmcs.commit(mats.clone());
+            let (chunked_commit, chunked_prover_data) = mmcs.commit_chunked(
+                mats.into_iter()
+                    .map(|mat| InChunks { mat, chunk_rows: 4 })
+                    .collect(),
+            );
+            assert_eq!(commit, chunked_commit);
+            assert_eq!(
+                mmcs.get_matrices(&prover_data),
+                mmcs.get_matrices(&chunked_prover_data)
+            );
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use p3_commit::ShardedMmcs;
+use p3_field::PackedValue;
+use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use serde::{Deserialize, Serialize};
+
+use crate::MerkleTreeMmcs;
+
+/// The layers of a Merkle tree from the roots of its shards up to its root, which is all a
+/// coordinator needs of a tree whose shards are held by workers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerkleShardLayers<W, const DIGEST_ELEMS: usize> {
+    // Enable serialization for this type whenever the underlying array type supports it (len 1-32).
+    #[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+    // Enable deserialization for this type whenever the underlying array type supports it (len 1-32).
+    #[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+    layers: Vec<Vec<[W; DIGEST_ELEMS]>>,
+}
+
+impl<W, const DIGEST_ELEMS: usize> MerkleShardLayers<W, DIGEST_ELEMS> {
+    /// The number of shards, as a power of two.
+    pub fn log_num_shards(&self) -> usize {
+        self.layers.len() - 1
+    }
+}
+
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> ShardedMmcs<P::Value>
+    for MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    type MergedShards = MerkleShardLayers<PW::Value, DIGEST_ELEMS>;
+
+    fn merge_shards(
+        &self,
+        shard_commits: Vec<Self::Commitment>,
+    ) -> (Self::Commitment, Self::MergedShards) {
+        assert!(
+            shard_commits.len().is_power_of_two(),
+            "the number of shards must be a power of two"
+        );
+        let mut layers = vec![shard_commits.into_iter().map(Into::into).collect_vec()];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| self.compress.compress([pair[0], pair[1]]))
+                .collect();
+            layers.push(next);
+        }
+        let root: Hash<P::Value, PW::Value, DIGEST_ELEMS> = layers.last().unwrap()[0].into();
+        (root, MerkleShardLayers { layers })
+    }
+
+    fn extend_shard_proof(
+        &self,
+        merged: &Self::MergedShards,
+        shard: usize,
+        mut proof: Self::Proof,
+    ) -> Self::Proof {
+        let top = merged.layers.len() - 1;
+        proof.extend(
+            merged.layers[..top]
+                .iter()
+                .enumerate()
+                .map(|(i, layer)| layer[(shard >> i) ^ 1]),
+        );
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+    use p3_commit::{Mmcs, ShardedMmcs};
+    use p3_field::Field;
+    use p3_matrix::dense::RowMajorMatrix;
+    use p3_matrix::Matrix;
+    use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+    use rand::thread_rng;
+
+    use crate::MerkleTreeMmcs;
+
+    type F = BabyBear;
+    type Perm = Poseidon2BabyBear<16>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+    type MyMmcs =
+        MerkleTreeMmcs<<F as Field>::Packing, <F as Field>::Packing, MyHash, MyCompress, 8>;
+
+    #[test]
+    fn merged_shards_match_full_tree() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let mmcs = MyMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm));
+
+        let mats = [3, 5].map(|width| RowMajorMatrix::<F>::rand(&mut rng, 64, width));
+        let (commit, full) = mmcs.commit(mats.to_vec());
+
+        let log_num_shards = 2;
+        let shard_height = 64 >> log_num_shards;
+        let shards = (0..1 << log_num_shards)
+            .map(|shard| {
+                let rows = shard * shard_height..(shard + 1) * shard_height;
+                let mats = mats
+                    .iter()
+                    .map(|mat| {
+                        let values = rows
+                            .clone()
+                            .flat_map(|r| mat.row(r).collect::<Vec<_>>())
+                            .collect();
+                        RowMajorMatrix::new(values, mat.width())
+                    })
+                    .collect();
+                mmcs.commit(mats)
+            })
+            .collect::<Vec<_>>();
+        let (merged_commit, merged) =
+            mmcs.merge_shards(shards.iter().map(|(commit, _)| commit.clone()).collect());
+        assert_eq!(merged_commit, commit);
+        assert_eq!(merged.log_num_shards(), log_num_shards);
+
+        for index in [0, 17, 63] {
+            let shard = index / shard_height;
+            let (values, proof) = mmcs.open_batch(index % shard_height, &shards[shard].1);
+            let proof = mmcs.extend_shard_proof(&merged, shard, proof);
+            assert_eq!((values, proof), mmcs.open_batch(index, &full));
+        }
+    }
+}
@@ -12,7 +12,7 @@ use rand::Rng;
 use crate::Mersenne31;
 
 const WIDTH: usize = 4;
-const P: uint32x4_t = unsafe { transmute::<[u32; WIDTH], _>([0x7fffffff; WIDTH]) };
+pub(crate) const P: uint32x4_t = unsafe { transmute::<[u32; WIDTH], _>([0x7fffffff; WIDTH]) };
 
 /// Vectorized NEON implementation of `Mersenne31` arithmetic.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -23,7 +23,7 @@ impl PackedMersenne31Neon {
     #[inline]
     #[must_use]
     /// Get an arch-specific vector representing the packed values.
-    fn to_vector(self) -> uint32x4_t {
+    pub(crate) fn to_vector(self) -> uint32x4_t {
         unsafe {
             // Safety: `Mersenne31` is `repr(transparent)` so it can be transmuted to `u32`. It
             // follows that `[Mersenne31; WIDTH]` can be transmuted to `[u32; WIDTH]`, which can be
@@ -41,7 +41,7 @@ impl PackedMersenne31Neon {
     /// SAFETY: The caller must ensure that each element of `vector` represents a valid
     /// `Mersenne31`.  In particular, each element of vector must be in `0..=P` (i.e. it fits in 31
     /// bits).
-    unsafe fn from_vector(vector: uint32x4_t) -> Self {
+    pub(crate) unsafe fn from_vector(vector: uint32x4_t) -> Self {
         // Safety: It is up to the user to ensure that elements of `vector` represent valid
         // `Mersenne31` values. We must only reason about memory representations. `uint32x4_t` can
         // be transmuted to `[u32; WIDTH]` (since arrays elements are contiguous in memory), which
@@ -253,6 +253,60 @@ fn sub(lhs: uint32x4_t, rhs: uint32x4_t) -> uint32x4_t {
     }
 }
 
+/// Square a vector of Mersenne-31 field elements represented as signed values in {-P, ..., P}.
+/// The output is represented as a value in 0, ..., 2 P - 1, which has not been reduced.
+/// If the inputs do not conform to this representation, the result is undefined.
+#[inline]
+#[must_use]
+fn square_unred(val: uint32x4_t) -> uint32x4_t {
+    // This is `mul` without the final `reduce_sum`. As val^2 is non-negative and at most P^2
+    // whatever the sign of val, the analysis in `mul` goes through unchanged: `vqdmulhq_s32`
+    // gives the high 31 bits of val^2 and `vmulq_u32` the low 32, so t is in 0, ..., 2 P - 1.
+
+    unsafe {
+        // Safety: If this code got compiled then NEON intrinsics are available.
+        let prod_hi31 = mul_31x31_to_hi_31(val, val);
+        let prod_lo32 = aarch64::vmulq_u32(val, val);
+        aarch64::vmlsq_u32(prod_lo32, prod_hi31, P)
+    }
+}
+
+/// Compute the permutation x -> x^5 on Mersenne-31 field elements represented as signed values
+/// in {-P, ..., P}. If the inputs do not conform to this representation, the result is undefined.
+/// The output is represented as a value in 0, ..., P.
+#[inline]
+#[must_use]
+pub(crate) fn exp5(val: uint32x4_t) -> uint32x4_t {
+    // We want this to compile to:
+    //      sqdmulh  hi.4s, val.4s, val.4s
+    //      mul      t.4s, val.4s, val.4s
+    //      mls      t.4s, hi.4s, P.4s
+    //      sub      x2.4s, t.4s, P.4s
+    //      sqdmulh  hi.4s, x2.4s, x2.4s
+    //      mul      t.4s, x2.4s, x2.4s
+    //      mls      t.4s, hi.4s, P.4s
+    //      sub      u.4s, t.4s, P.4s
+    //      umin     x4.4s, t.4s, u.4s
+    //      add      v.4s, val.4s, P.4s
+    //      umin     x.4s, val.4s, v.4s
+    // followed by a `mul` of x4 and x.
+
+    // The square of val is in 0, ..., 2 P - 1, so subtracting P rather than reducing leaves x^2
+    // in -P, ..., P - 1, which is a valid input for the next squaring. Only x^4 needs to be
+    // reduced before the last multiplication.
+    // To bring val into 0, ..., P, note that if val is negative then, as an unsigned integer, it
+    // is at least 2^32 - P > 2 P, and val + P is in 0, ..., P - 1, so the unsigned minimum of val
+    // and val + P is val + P. Otherwise val + P is in P, ..., 2 P, so the minimum is val.
+
+    unsafe {
+        // Safety: If this code got compiled then NEON intrinsics are available.
+        let x2 = aarch64::vsubq_u32(square_unred(val), P);
+        let x4 = reduce_sum(square_unred(x2));
+        let x = aarch64::vminq_u32(val, aarch64::vaddq_u32(val, P));
+        mul(x4, x)
+    }
+}
+
 impl From<Mersenne31> for PackedMersenne31Neon {
     #[inline]
     fn from(value: Mersenne31) -> Self {
@@ -355,6 +409,32 @@ impl FieldAlgebra for PackedMersenne31Neon {
         Mersenne31::from_wrapped_u64(n).into()
     }
 
+    #[must_use]
+    #[inline(always)]
+    fn exp_const_u64<const POWER: u64>(&self) -> Self {
+        // We provide specialised code for power 5 as this turns up regularly.
+        // The other powers could be specialised similarly but we ignore this for now.
+        match POWER {
+            0 => Self::ONE,
+            1 => *self,
+            2 => self.square(),
+            3 => self.cube(),
+            4 => self.square().square(),
+            5 => unsafe {
+                let val = self.to_vector();
+                Self::from_vector(exp5(val))
+            },
+            6 => self.square().cube(),
+            7 => {
+                let x2 = self.square();
+                let x3 = x2 * *self;
+                let x4 = x2.square();
+                x3 * x4
+            }
+            _ => self.exp_u64(POWER),
+        }
+    }
+
     #[inline(always)]
     fn zero_vec(len: usize) -> Vec<Self> {
         // SAFETY: this is a repr(transparent) wrapper around an array.
@@ -568,9 +648,11 @@ unsafe impl PackedFieldPow2 for PackedMersenne31Neon {
 
 #[cfg(test)]
 mod tests {
+    use p3_field::FieldAlgebra;
     use p3_field_testing::test_packed_field;
+    use rand::Rng;
 
-    use super::{Mersenne31, WIDTH};
+    use super::{Mersenne31, PackedMersenne31Neon, WIDTH};
     use crate::to_mersenne31_array;
 
     /// Zero has a redundant representation, so let's test both.
@@ -585,4 +667,13 @@ mod tests {
         crate::PackedMersenne31Neon(super::ZEROS),
         crate::PackedMersenne31Neon(super::SPECIAL_VALS)
     );
+
+    #[test]
+    fn test_exp5_matches_scalar() {
+        let mut rng = rand::thread_rng();
+        for vals in [ZEROS, SPECIAL_VALS, rng.gen()] {
+            let packed = PackedMersenne31Neon(vals).exp_const_u64::<5>();
+            assert_eq!(packed.0, vals.map(|x| x.exp_const_u64::<5>()));
+        }
+    }
 }
@@ -29,9 +29,7 @@ use crate::{
 /// Degree of the chosen permutation polynomial for Mersenne31, used as the Poseidon2 S-Box.
 ///
 /// As p - 1 = 2×3^2×7×11×... the smallest choice for a degree D satisfying gcd(p - 1, D) = 1 is 5.
-/// Currently pub(crate) as it is used in the default neon implementation. Once that is optimized
-/// this should no longer be public.
-pub(crate) const MERSENNE31_S_BOX_DEGREE: u64 = 5;
+const MERSENNE31_S_BOX_DEGREE: u64 = 5;
 
 /// An implementation of the Poseidon2 hash function specialised to run on the current architecture.
 ///
@@ -0,0 +1,77 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::StarkConfig;
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128Plus;
+
+pub type BabyBearChallenge = BinomialExtensionField<BabyBear, 4>;
+
+type Perm16 = Poseidon2BabyBear<16>;
+
+type Perm24 = Poseidon2BabyBear<24>;
+
+type Hash = PaddingFreeSponge<Perm24, 24, 16, 8>;
+
+type Compress = TruncatedPermutation<Perm16, 2, 8, 16>;
+
+/// The MMCS committing to the traces, hashing with Poseidon2.
+pub type BabyBearValMmcs =
+    MerkleTreeMmcs<<BabyBear as Field>::Packing, <BabyBear as Field>::Packing, Hash, Compress, 8>;
+
+/// The MMCS committing to the FRI layers, over the challenge field.
+pub type BabyBearChallengeMmcs = ExtensionMmcs<BabyBear, BabyBearChallenge, BabyBearValMmcs>;
+
+pub type BabyBearChallenger = DuplexChallenger<BabyBear, Perm24, 24, 16>;
+
+pub type BabyBearPcs =
+    TwoAdicFriPcs<BabyBear, Radix2DitParallel<BabyBear>, BabyBearValMmcs, BabyBearChallengeMmcs>;
+
+/// BabyBear, with a quartic extension for challenges, and Poseidon2 for the Merkle trees and the
+/// transcript.
+pub type BabyBearPoseidon2Config = StarkConfig<BabyBearPcs, BabyBearChallenge, BabyBearChallenger>;
+
+pub const LOG_BLOWUP: usize = 1;
+pub const NUM_QUERIES: usize = 84;
+pub const PROOF_OF_WORK_BITS: usize = 16;
+
+/// The seed from which the Poseidon2 constants are derived.
+const CONSTANTS_SEED: u64 = 1;
+
+fn permutations() -> (Perm16, Perm24) {
+    let mut rng = Xoroshiro128Plus::seed_from_u64(CONSTANTS_SEED);
+    let perm16 = Perm16::new_from_rng_128(&mut rng);
+    let perm24 = Perm24::new_from_rng_128(&mut rng);
+    (perm16, perm24)
+}
+
+/// The `BabyBearPoseidon2Config`, with the FRI parameters given by the constants of this module.
+pub fn baby_bear_poseidon2_config() -> BabyBearPoseidon2Config {
+    let (perm16, perm24) = permutations();
+    let val_mmcs = BabyBearValMmcs::new(Hash::new(perm24), Compress::new(perm16));
+    let fri_config = FriConfig {
+        log_blowup: LOG_BLOWUP,
+        log_final_poly_len: 0,
+        num_queries: NUM_QUERIES,
+        proof_of_work_bits: PROOF_OF_WORK_BITS,
+        deduplicate_queries: false,
+        mmcs: BabyBearChallengeMmcs::new(val_mmcs.clone()),
+    };
+    BabyBearPoseidon2Config::new(BabyBearPcs::new(
+        Radix2DitParallel::default(),
+        val_mmcs,
+        fri_config,
+    ))
+}
+
+/// A fresh challenger for proving or verifying with the `BabyBearPoseidon2Config`.
+pub fn baby_bear_poseidon2_challenger() -> BabyBearChallenger {
+    let (_, perm24) = permutations();
+    BabyBearChallenger::new(perm24)
+}
@@ -0,0 +1,78 @@
+use alloc::vec::Vec;
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::{HashChallenger, SerializingChallenger32};
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{PrimeField32, TwoAdicField};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_koala_bear::KoalaBear;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
+use p3_uni_stark::StarkConfig;
+
+/// The FRI parameters shared by the configs of this module.
+pub const LOG_BLOWUP: usize = 1;
+pub const NUM_QUERIES: usize = 100;
+pub const PROOF_OF_WORK_BITS: usize = 16;
+
+type FieldHash = SerializingHasher32<Keccak256Hash>;
+
+type Compress = CompressionFunctionFromHasher<Keccak256Hash, 2, 32>;
+
+pub type KeccakValMmcs<Val> = MerkleTreeMmcs<Val, u8, FieldHash, Compress, 32>;
+
+pub type KeccakChallenge<Val> = BinomialExtensionField<Val, 4>;
+
+pub type KeccakChallengeMmcs<Val> = ExtensionMmcs<Val, KeccakChallenge<Val>, KeccakValMmcs<Val>>;
+
+pub type KeccakChallenger<Val> =
+    SerializingChallenger32<Val, HashChallenger<u8, Keccak256Hash, 32>>;
+
+/// A STARK over `Val`, with a quartic extension for challenges and Keccak for the Merkle trees and
+/// the transcript, which need no constants to set up.
+pub type KeccakConfig<Val> = StarkConfig<
+    TwoAdicFriPcs<Val, Radix2DitParallel<Val>, KeccakValMmcs<Val>, KeccakChallengeMmcs<Val>>,
+    KeccakChallenge<Val>,
+    KeccakChallenger<Val>,
+>;
+
+fn keccak_val_mmcs<Val>() -> KeccakValMmcs<Val> {
+    KeccakValMmcs::new(
+        FieldHash::new(Keccak256Hash {}),
+        Compress::new(Keccak256Hash {}),
+    )
+}
+
+/// The `KeccakConfig` over `Val`, with the FRI parameters given by the constants of this module.
+pub fn keccak_config<Val: TwoAdicField>() -> KeccakConfig<Val> {
+    let fri_config = FriConfig {
+        log_blowup: LOG_BLOWUP,
+        log_final_poly_len: 0,
+        num_queries: NUM_QUERIES,
+        proof_of_work_bits: PROOF_OF_WORK_BITS,
+        deduplicate_queries: false,
+        mmcs: KeccakChallengeMmcs::new(keccak_val_mmcs()),
+    };
+    KeccakConfig::new(TwoAdicFriPcs::new(
+        Radix2DitParallel::default(),
+        keccak_val_mmcs(),
+        fri_config,
+    ))
+}
+
+/// The `KeccakConfig` over BabyBear, that of the WebAssembly verifier.
+pub fn baby_bear_keccak_config() -> KeccakConfig<BabyBear> {
+    keccak_config()
+}
+
+pub fn koala_bear_keccak_config() -> KeccakConfig<KoalaBear> {
+    keccak_config()
+}
+
+/// A fresh challenger for proving or verifying with a `KeccakConfig`.
+pub fn keccak_challenger<Val: PrimeField32>() -> KeccakChallenger<Val> {
+    KeccakChallenger::from_hasher(Vec::new(), Keccak256Hash {})
+}
@@ -0,0 +1,19 @@
+//! Configurations pinned down to every parameter, so that proofs made with one can be checked by a
+//! verifier built elsewhere, such as the embedded, WebAssembly and C verifiers, and one function,
+//! `verify_encoded`, with which those verifiers check encoded proofs.
+//!
+//! `baby_bear_poseidon2`, behind the `poseidon2` feature, is the configuration of the embedded
+//! verifier. Its Poseidon2 constants are derived from a seeded `rand` generator, which the
+//! WebAssembly and C verifiers leave out by using only `keccak`, whose configurations need no
+//! constants to set up.
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "poseidon2")]
+pub mod baby_bear_poseidon2;
+pub mod keccak;
+mod verify;
+
+pub use verify::*;
@@ -0,0 +1,52 @@
+use alloc::vec::Vec;
+
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_uni_stark::{
+    verify_with_key, PcsError, Proof, ProofDecodingError, StarkGenericConfig, Val,
+    VerificationError, VerifyingKey,
+};
+
+#[derive(Debug)]
+pub enum VerifyEncodedError<PcsErr, Challenge> {
+    /// The proof could not be decoded.
+    Proof(ProofDecodingError),
+    /// The verifying key could not be decoded.
+    VerifyingKey(ProofDecodingError),
+    /// The public value at this index is not a canonical field element.
+    PublicValue(usize),
+    /// The proof was rejected.
+    Verification(VerificationError<PcsErr, Challenge>),
+}
+
+/// Verify a proof made with `config`, against a verifying key, each in the binary encoding of
+/// `p3-uni-stark`, and public values given as canonical field elements.
+///
+/// `challenger` must be in the state the prover's challenger started in.
+#[allow(clippy::type_complexity)]
+pub fn verify_encoded<SC>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    proof: &[u8],
+    vk: &[u8],
+    public_values: &[u32],
+) -> Result<(), VerifyEncodedError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    Val<SC>: PrimeField32,
+{
+    let proof = Proof::<SC>::from_bytes(proof).map_err(VerifyEncodedError::Proof)?;
+    let vk = VerifyingKey::<SC>::from_bytes(vk).map_err(VerifyEncodedError::VerifyingKey)?;
+    let public_values = public_values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            if value < Val::<SC>::ORDER_U32 {
+                Ok(Val::<SC>::from_canonical_u32(value))
+            } else {
+                Err(VerifyEncodedError::PublicValue(i))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    verify_with_key(config, &vk, challenger, &proof, &public_values)
+        .map_err(VerifyEncodedError::Verification)
+}
@@ -76,7 +76,9 @@ impl<
     }
 }
 
-pub(crate) fn eval<
+/// Evaluates the Poseidon2 constraints on `local`, so that other AIRs can embed the permutation in
+/// their rows.
+pub fn eval<
     AB: AirBuilder,
     LinearLayers: GenericPoseidon2LinearLayers<AB::Expr, WIDTH>,
     const WIDTH: usize,
@@ -1,6 +1,7 @@
 use alloc::vec::Vec;
 
 use p3_field::Field;
+use p3_poseidon2::ExternalLayerConstants;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 
@@ -32,6 +33,33 @@ impl<F: Field, const WIDTH: usize, const HALF_FULL_ROUNDS: usize, const PARTIAL_
         }
     }
 
+    /// The round constants of the permutation built by `Poseidon2::new` from the same constants,
+    /// such as the tables in the field crates, so that the AIR proves exactly that permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numbers of constants do not match the numbers of rounds.
+    pub fn from_poseidon2_constants(
+        external_constants: &ExternalLayerConstants<F, WIDTH>,
+        internal_constants: &[F],
+    ) -> Self {
+        Self {
+            beginning_full_round_constants: external_constants
+                .get_initial_constants()
+                .as_slice()
+                .try_into()
+                .expect("wrong number of initial external round constants"),
+            partial_round_constants: internal_constants
+                .try_into()
+                .expect("wrong number of internal round constants"),
+            ending_full_round_constants: external_constants
+                .get_terminal_constants()
+                .as_slice()
+                .try_into()
+                .expect("wrong number of terminal external round constants"),
+        }
+    }
+
     pub fn from_rng<R: Rng>(rng: &mut R) -> Self
     where
         Standard: Distribution<F> + Distribution<[F; WIDTH]>,
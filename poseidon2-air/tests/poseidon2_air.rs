@@ -0,0 +1,74 @@
+use core::borrow::Borrow;
+
+use p3_baby_bear::GenericPoseidon2LinearLayersBabyBear;
+use p3_matrix::Matrix;
+use p3_poseidon2::ExternalLayerConstants;
+use p3_poseidon2_air::{generate_trace_rows, Poseidon2Air, Poseidon2Cols, RoundConstants};
+use p3_symmetric::Permutation;
+use p3_uni_stark::testing::{test_config, Challenger, Perm, Val};
+use p3_uni_stark::{prove, verify};
+use rand::distributions::Standard;
+use rand::{random, thread_rng, Rng};
+
+const WIDTH: usize = 16;
+const SBOX_DEGREE: u64 = 7;
+const SBOX_REGISTERS: usize = 1;
+const HALF_FULL_ROUNDS: usize = 4;
+const PARTIAL_ROUNDS: usize = 13;
+
+type MyAir = Poseidon2Air<
+    Val,
+    GenericPoseidon2LinearLayersBabyBear,
+    WIDTH,
+    SBOX_DEGREE,
+    SBOX_REGISTERS,
+    HALF_FULL_ROUNDS,
+    PARTIAL_ROUNDS,
+>;
+
+#[test]
+fn test_poseidon2_air_matches_permutation() {
+    let mut rng = thread_rng();
+    let external_constants = ExternalLayerConstants::new_from_rng(2 * HALF_FULL_ROUNDS, &mut rng);
+    let internal_constants: Vec<Val> = (&mut rng)
+        .sample_iter(Standard)
+        .take(PARTIAL_ROUNDS)
+        .collect();
+    let permutation = Perm::new(external_constants.clone(), internal_constants.clone());
+    let constants =
+        RoundConstants::from_poseidon2_constants(&external_constants, &internal_constants);
+
+    let inputs: Vec<[Val; WIDTH]> = (0..8).map(|_| random()).collect();
+    let trace = generate_trace_rows::<
+        Val,
+        GenericPoseidon2LinearLayersBabyBear,
+        WIDTH,
+        SBOX_DEGREE,
+        SBOX_REGISTERS,
+        HALF_FULL_ROUNDS,
+        PARTIAL_ROUNDS,
+    >(inputs.clone(), &constants);
+
+    for (i, input) in inputs.into_iter().enumerate() {
+        let row = trace.row_slice(i);
+        let row: &Poseidon2Cols<
+            Val,
+            WIDTH,
+            SBOX_DEGREE,
+            SBOX_REGISTERS,
+            HALF_FULL_ROUNDS,
+            PARTIAL_ROUNDS,
+        > = (*row).borrow();
+        assert_eq!(
+            row.ending_full_rounds[HALF_FULL_ROUNDS - 1].post,
+            permutation.permute(input)
+        );
+    }
+
+    let air = MyAir::new(constants);
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+}
@@ -0,0 +1,246 @@
+//! Construction of the matrix of the internal layer from its diagonal, with a check of the
+//! conditions listed in `internal.rs`.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::{Field, FieldAlgebra, PrimeField64};
+use p3_symmetric::Permutation;
+
+use crate::matmul_internal;
+
+/// A reason for which `1 + Diag(diag)` is not a safe matrix for the internal layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffusionMatrixError {
+    /// The diagonal entry of the matrix at `index` is zero, i.e. `diag[index] = -1`.
+    ZeroEntry { index: usize },
+    /// `diag[index]` is zero.
+    ZeroDiagonal { index: usize },
+    /// The matrix is not invertible.
+    Singular,
+    /// The characteristic polynomial of the matrix raised to `power` is reducible, so the matrix
+    /// may admit an invariant subspace trail.
+    ReducibleCharacteristicPolynomial { power: usize },
+}
+
+/// Check that `1 + Diag(diag)`, where `1` is the matrix whose entries are all one, is a safe
+/// matrix for the internal layer of Poseidon2.
+///
+/// The matrix must have no zero entries, `diag` must have no zero entries, and the matrix must be
+/// invertible. To rule out arbitrarily long subspace trails, the characteristic polynomials of
+/// the matrix and of its powers up to `2 * WIDTH` must also be irreducible.
+pub fn check_diffusion_diagonal<F: PrimeField64, const WIDTH: usize>(
+    diag: &[F; WIDTH],
+) -> Result<(), DiffusionMatrixError> {
+    if let Some(index) = diag.iter().position(|&d| d == F::NEG_ONE) {
+        return Err(DiffusionMatrixError::ZeroEntry { index });
+    }
+    if let Some(index) = diag.iter().position(|d| d.is_zero()) {
+        return Err(DiffusionMatrixError::ZeroDiagonal { index });
+    }
+
+    let mut matrix = vec![F::ONE; WIDTH * WIDTH];
+    for (i, &d) in diag.iter().enumerate() {
+        matrix[i * WIDTH + i] += d;
+    }
+    if characteristic_polynomial(matrix.clone(), WIDTH)[0].is_zero() {
+        return Err(DiffusionMatrixError::Singular);
+    }
+
+    let mut power = matrix.clone();
+    for k in 1..=2 * WIDTH {
+        if !is_irreducible(&characteristic_polynomial(power.clone(), WIDTH)) {
+            return Err(DiffusionMatrixError::ReducibleCharacteristicPolynomial { power: k });
+        }
+        power = matrix_mul(&power, &matrix, WIDTH);
+    }
+    Ok(())
+}
+
+/// The matrix `1 + Diag(diag)` of the internal layer of Poseidon2, where `1` is the matrix whose
+/// entries are all one, as applied by `matmul_internal`.
+///
+/// Fields with their own internal layers can construct it with `new_checked` to make sure their
+/// choice of diagonal is safe, and apply it in their implementation of `InternalLayer`.
+#[derive(Clone, Debug)]
+pub struct DiffusionMatrixFromDiagonal<F, const WIDTH: usize> {
+    diag: [F; WIDTH],
+}
+
+impl<F: PrimeField64, const WIDTH: usize> DiffusionMatrixFromDiagonal<F, WIDTH> {
+    /// Construct the matrix, after checking it with `check_diffusion_diagonal`.
+    pub fn new_checked(diag: [F; WIDTH]) -> Result<Self, DiffusionMatrixError> {
+        check_diffusion_diagonal(&diag)?;
+        Ok(Self { diag })
+    }
+}
+
+impl<F, const WIDTH: usize> DiffusionMatrixFromDiagonal<F, WIDTH> {
+    /// Construct the matrix without checking that it is safe, e.g. for a diagonal which is already
+    /// known to be.
+    pub const fn new_unchecked(diag: [F; WIDTH]) -> Self {
+        Self { diag }
+    }
+
+    pub const fn diag(&self) -> &[F; WIDTH] {
+        &self.diag
+    }
+}
+
+impl<F: Field, FA: FieldAlgebra<F = F>, const WIDTH: usize> Permutation<[FA; WIDTH]>
+    for DiffusionMatrixFromDiagonal<F, WIDTH>
+{
+    fn permute_mut(&self, state: &mut [FA; WIDTH]) {
+        matmul_internal(state, self.diag);
+    }
+}
+
+/// The product of two `n x n` row-major matrices.
+fn matrix_mul<F: Field>(a: &[F], b: &[F], n: usize) -> Vec<F> {
+    let mut product = vec![F::ZERO; n * n];
+    for i in 0..n {
+        for k in 0..n {
+            let a_ik = a[i * n + k];
+            for j in 0..n {
+                product[i * n + j] += a_ik * b[k * n + j];
+            }
+        }
+    }
+    product
+}
+
+/// The characteristic polynomial of an `n x n` row-major matrix, with coefficients from the
+/// constant term up.
+///
+/// The matrix is first reduced to a similar upper Hessenberg matrix, whose characteristic
+/// polynomial then follows from a recurrence on its leading principal minors.
+fn characteristic_polynomial<F: Field>(mut a: Vec<F>, n: usize) -> Vec<F> {
+    for m in 1..n.saturating_sub(1) {
+        let Some(pivot) = (m..n).find(|&i| !a[i * n + m - 1].is_zero()) else {
+            continue;
+        };
+        if pivot != m {
+            for j in 0..n {
+                a.swap(pivot * n + j, m * n + j);
+            }
+            for i in 0..n {
+                a.swap(i * n + pivot, i * n + m);
+            }
+        }
+        let inverse = a[m * n + m - 1].inverse();
+        for i in m + 1..n {
+            let u = a[i * n + m - 1] * inverse;
+            if u.is_zero() {
+                continue;
+            }
+            for j in 0..n {
+                let a_mj = a[m * n + j];
+                a[i * n + j] -= u * a_mj;
+            }
+            for j in 0..n {
+                let a_ji = a[j * n + i];
+                a[j * n + m] += u * a_ji;
+            }
+        }
+    }
+
+    // `minors[m]` is the characteristic polynomial of the leading `m x m` submatrix.
+    let mut minors = vec![vec![F::ONE]];
+    for m in 1..=n {
+        let mut p = poly_mul(&minors[m - 1], &[-a[(m - 1) * n + m - 1], F::ONE]);
+        let mut t = F::ONE;
+        for i in 1..m {
+            t *= a[(m - i) * n + m - i - 1];
+            let c = t * a[(m - i - 1) * n + m - 1];
+            for (coeff, &q) in p.iter_mut().zip(&minors[m - i - 1]) {
+                *coeff -= c * q;
+            }
+        }
+        minors.push(p);
+    }
+    minors.pop().unwrap()
+}
+
+/// Whether a monic polynomial over a prime field is irreducible, by Ben-Or's test: a polynomial
+/// of degree `n` is reducible exactly when it has a factor of some degree `i <= n / 2`, which then
+/// divides `x^{p^i} - x`.
+fn is_irreducible<F: PrimeField64>(f: &[F]) -> bool {
+    let n = f.len() - 1;
+    let x = poly_rem(&[F::ZERO, F::ONE], f);
+    let mut x_power = x.clone();
+    for _ in 1..=n / 2 {
+        x_power = poly_pow_mod(&x_power, F::ORDER_U64, f);
+        let mut difference = x_power.clone();
+        difference.resize(difference.len().max(x.len()), F::ZERO);
+        for (d, &x) in difference.iter_mut().zip(&x) {
+            *d -= x;
+        }
+        if poly_gcd(f.to_vec(), difference).len() > 1 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Remove the leading zero coefficients of `p`, so that the zero polynomial is empty.
+fn poly_trim<F: Field>(p: &mut Vec<F>) {
+    while p.last().is_some_and(|c| c.is_zero()) {
+        p.pop();
+    }
+}
+
+fn poly_mul<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![F::ZERO; a.len() + b.len() - 1];
+    for (i, &a) in a.iter().enumerate() {
+        for (j, &b) in b.iter().enumerate() {
+            product[i + j] += a * b;
+        }
+    }
+    product
+}
+
+/// The remainder of `a` modulo the nonzero polynomial `b`.
+fn poly_rem<F: Field>(a: &[F], b: &[F]) -> Vec<F> {
+    let mut b = b.to_vec();
+    poly_trim(&mut b);
+    let lead_inverse = b.last().expect("division by zero polynomial").inverse();
+    let mut r = a.to_vec();
+    poly_trim(&mut r);
+    while r.len() >= b.len() {
+        let shift = r.len() - b.len();
+        let c = *r.last().unwrap() * lead_inverse;
+        for (i, &b) in b.iter().enumerate() {
+            r[shift + i] -= c * b;
+        }
+        poly_trim(&mut r);
+    }
+    r
+}
+
+fn poly_pow_mod<F: Field>(base: &[F], mut exponent: u64, modulus: &[F]) -> Vec<F> {
+    let mut base = poly_rem(base, modulus);
+    let mut result = poly_rem(&[F::ONE], modulus);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = poly_rem(&poly_mul(&result, &base), modulus);
+        }
+        base = poly_rem(&poly_mul(&base, &base), modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// A greatest common divisor of `a` and `b`, which is constant exactly when they are coprime.
+fn poly_gcd<F: Field>(mut a: Vec<F>, mut b: Vec<F>) -> Vec<F> {
+    poly_trim(&mut a);
+    poly_trim(&mut b);
+    while !b.is_empty() {
+        let r = poly_rem(&a, &b);
+        a = b;
+        b = r;
+    }
+    a
+}
@@ -0,0 +1,399 @@
+//! The parameters of a Poseidon2 permutation in a form independent of Plonky3, so that other
+//! implementations, such as circuits in circom, noir or halo2, can use exactly the same
+//! permutation.
+//!
+//! `Poseidon2Params` serializes, with any `serde` format such as JSON or TOML, to:
+//!
+//! ```text
+//! {
+//!   "field_modulus": "2013265921",
+//!   "width": 16,
+//!   "d": 7,
+//!   "rounds_f": 8,
+//!   "rounds_p": 13,
+//!   "external_mat4": [["2", "3", "1", "1"], ["1", "2", "3", "1"], ...],
+//!   "mat_internal_diag_m_1": ["<element>", ...],
+//!   "external_initial_constants": [["<element>", ...], ...],
+//!   "internal_constants": ["<element>", ...],
+//!   "external_terminal_constants": [["<element>", ...], ...]
+//! }
+//! ```
+//!
+//! Field elements are the decimal strings of their canonical representatives, in
+//! `[0, field_modulus)`. The permutation of a state of `width` elements is:
+//! - the external linear layer;
+//! - `rounds_f / 2` full rounds, the `i`th of which adds `external_initial_constants[i]` to the
+//!   state, raises every element to the power `d`, and applies the external linear layer;
+//! - `rounds_p` partial rounds, the `i`th of which adds `internal_constants[i]` to the first
+//!   element, raises it to the power `d`, and applies the internal linear layer;
+//! - `rounds_f / 2` full rounds with `external_terminal_constants`.
+//!
+//! The external linear layer multiplies the state by the block matrix with `2 M` on its diagonal
+//! and `M` elsewhere, where `M` is the 4x4 matrix `external_mat4` given by rows. For widths 2 and
+//! 3, `external_mat4` is absent and the matrix has 2 on its diagonal and 1 elsewhere. The internal
+//! linear layer multiplies the state by `1 + diag(mat_internal_diag_m_1)`, where `1` is the matrix
+//! of ones, i.e. it maps `x_i` to `x_i * mat_internal_diag_m_1[i] + sum_j x_j`.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use p3_field::{Field, FieldAlgebra, PrimeField, PrimeField64};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    poseidon2_round_numbers_128, ExternalLayerConstants, ExternalLayerConstructor,
+    GenericPoseidon2LinearLayers, InternalLayerConstructor, Poseidon2,
+};
+
+/// Everything which defines a Poseidon2 permutation over `F` of width `WIDTH`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    bound = "F: PrimeField",
+    into = "Poseidon2ParamsRepr",
+    try_from = "Poseidon2ParamsRepr"
+)]
+pub struct Poseidon2Params<F, const WIDTH: usize> {
+    /// The degree of the S-box.
+    pub d: u64,
+    /// The 4x4 matrix of the external linear layer, by rows, or `None` for widths below 4.
+    pub external_mat4: Option<[[F; 4]; 4]>,
+    /// The diagonal of the internal linear layer, minus one.
+    pub mat_internal_diag_m_1: [F; WIDTH],
+    pub external_initial_constants: Vec<[F; WIDTH]>,
+    pub internal_constants: Vec<F>,
+    pub external_terminal_constants: Vec<[F; WIDTH]>,
+}
+
+impl<F: Field, const WIDTH: usize> Poseidon2Params<F, WIDTH> {
+    /// The parameters of the permutation with S-box degree `d` and the given constants, whose
+    /// linear layers are those of `LinearLayers`.
+    ///
+    /// The matrices of the linear layers are read off their images of the unit vectors. Panics if
+    /// they do not have the structure described in the module documentation.
+    pub fn new<LinearLayers: GenericPoseidon2LinearLayers<F, WIDTH>>(
+        d: u64,
+        external_constants: ExternalLayerConstants<F, WIDTH>,
+        internal_constants: Vec<F>,
+    ) -> Self {
+        let columns = |layer: fn(&mut [F; WIDTH])| -> Vec<[F; WIDTH]> {
+            (0..WIDTH)
+                .map(|j| {
+                    let mut column = [F::ZERO; WIDTH];
+                    column[j] = F::ONE;
+                    layer(&mut column);
+                    column
+                })
+                .collect()
+        };
+
+        let internal = columns(LinearLayers::internal_linear_layer);
+        assert!(
+            (0..WIDTH).all(|j| (0..WIDTH).all(|i| i == j || internal[j][i] == F::ONE)),
+            "the internal linear layer is not of the form 1 + diag(v)"
+        );
+        let mat_internal_diag_m_1 = core::array::from_fn(|i| internal[i][i] - F::ONE);
+
+        let external = columns(LinearLayers::external_linear_layer);
+        let external_mat4: Option<[[F; 4]; 4]> = (WIDTH >= 4)
+            .then(|| core::array::from_fn(|i| core::array::from_fn(|j| external[j][i].halve())));
+        let expected_entry = |i: usize, j: usize| {
+            let entry = external_mat4.map_or(F::ONE, |mat4| mat4[i % 4][j % 4]);
+            if (WIDTH >= 4 && i / 4 == j / 4) || (WIDTH < 4 && i == j) {
+                entry.double()
+            } else {
+                entry
+            }
+        };
+        assert!(
+            (0..WIDTH).all(|j| (0..WIDTH).all(|i| external[j][i] == expected_entry(i, j))),
+            "the external linear layer is not of the form of `mds_light_permutation`"
+        );
+
+        Self {
+            d,
+            external_mat4,
+            mat_internal_diag_m_1,
+            external_initial_constants: external_constants.get_initial_constants().clone(),
+            internal_constants,
+            external_terminal_constants: external_constants.get_terminal_constants().clone(),
+        }
+    }
+
+    /// The parameters with the random constants `Poseidon2::new_from_rng_128` draws from `rng`.
+    pub fn new_from_rng_128<LinearLayers, R>(d: u64, rng: &mut R) -> Self
+    where
+        F: PrimeField64,
+        LinearLayers: GenericPoseidon2LinearLayers<F, WIDTH>,
+        R: Rng,
+        Standard: Distribution<F> + Distribution<[F; WIDTH]>,
+    {
+        let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<F>(WIDTH, d);
+        let external_constants = ExternalLayerConstants::new_from_rng(rounds_f, rng);
+        let internal_constants = rng.sample_iter(Standard).take(rounds_p).collect();
+        Self::new::<LinearLayers>(d, external_constants, internal_constants)
+    }
+
+    pub fn rounds_f(&self) -> usize {
+        self.external_initial_constants.len() + self.external_terminal_constants.len()
+    }
+
+    pub fn rounds_p(&self) -> usize {
+        self.internal_constants.len()
+    }
+
+    pub fn external_constants(&self) -> ExternalLayerConstants<F, WIDTH> {
+        ExternalLayerConstants::new(
+            self.external_initial_constants.clone(),
+            self.external_terminal_constants.clone(),
+        )
+    }
+}
+
+impl<F: PrimeField, const WIDTH: usize> Poseidon2Params<F, WIDTH> {
+    /// Encode the parameters as JSON, in the schema of the module documentation.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Decode parameters encoded by `to_json`, or by another implementation in the same schema.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl<FA, ExternalPerm, InternalPerm, const WIDTH: usize, const D: u64>
+    Poseidon2<FA, ExternalPerm, InternalPerm, WIDTH, D>
+where
+    FA: FieldAlgebra,
+    FA::F: PrimeField,
+    ExternalPerm: ExternalLayerConstructor<FA, WIDTH>,
+    InternalPerm: InternalLayerConstructor<FA>,
+{
+    /// Create the permutation with the constants of `params`, e.g. imported from another
+    /// implementation.
+    ///
+    /// The linear layers are those of `ExternalPerm` and `InternalPerm`; callers importing
+    /// parameters should check that they match those of `params`, as given by
+    /// `Poseidon2Params::new`.
+    pub fn from_params(params: &Poseidon2Params<FA::F, WIDTH>) -> Self {
+        assert_eq!(params.d, D, "the S-box degree of the parameters differs");
+        Self::new(
+            params.external_constants(),
+            params.internal_constants.clone(),
+        )
+    }
+}
+
+/// A reason for which serialized parameters cannot be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Poseidon2ParamsError {
+    /// The parameters are over another field.
+    WrongFieldModulus {
+        expected: String,
+        actual: String,
+    },
+    WrongWidth {
+        expected: usize,
+        actual: usize,
+    },
+    /// The number of full rounds is odd, so they cannot be split into initial and terminal halves.
+    OddFullRounds {
+        rounds_f: usize,
+    },
+    /// A list of field elements, named `name`, has the wrong length for the width or the numbers
+    /// of rounds.
+    WrongLength {
+        name: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// A value which is not the canonical decimal representative of a field element.
+    InvalidElement(String),
+}
+
+impl fmt::Display for Poseidon2ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongFieldModulus { expected, actual } => {
+                write!(f, "expected field modulus {expected}, got {actual}")
+            }
+            Self::WrongWidth { expected, actual } => {
+                write!(f, "expected width {expected}, got {actual}")
+            }
+            Self::OddFullRounds { rounds_f } => write!(f, "odd number of full rounds {rounds_f}"),
+            Self::WrongLength {
+                name,
+                expected,
+                actual,
+            } => write!(f, "expected {expected} values in {name}, got {actual}"),
+            Self::InvalidElement(value) => write!(f, "invalid field element {value:?}"),
+        }
+    }
+}
+
+/// The serialized form of `Poseidon2Params`.
+#[derive(Serialize, Deserialize)]
+struct Poseidon2ParamsRepr {
+    field_modulus: String,
+    width: usize,
+    d: u64,
+    rounds_f: usize,
+    rounds_p: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    external_mat4: Option<Vec<Vec<String>>>,
+    mat_internal_diag_m_1: Vec<String>,
+    external_initial_constants: Vec<Vec<String>>,
+    internal_constants: Vec<String>,
+    external_terminal_constants: Vec<Vec<String>>,
+}
+
+fn element_to_string<F: PrimeField>(x: &F) -> String {
+    x.as_canonical_biguint().to_string()
+}
+
+fn row_to_strings<F: PrimeField>(row: &[F]) -> Vec<String> {
+    row.iter().map(element_to_string).collect()
+}
+
+fn element_from_str<F: PrimeField>(value: &str) -> Result<F, Poseidon2ParamsError> {
+    let invalid = || Poseidon2ParamsError::InvalidElement(value.into());
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let ten = F::from_canonical_u8(10);
+    let x = value
+        .bytes()
+        .fold(F::ZERO, |acc, b| acc * ten + F::from_canonical_u8(b - b'0'));
+    // Values of the modulus or more were reduced, and leading zeros dropped.
+    if element_to_string(&x) != value {
+        return Err(invalid());
+    }
+    Ok(x)
+}
+
+fn elements_from_strings<F: PrimeField>(
+    name: &'static str,
+    values: &[String],
+    len: usize,
+) -> Result<Vec<F>, Poseidon2ParamsError> {
+    if values.len() != len {
+        return Err(Poseidon2ParamsError::WrongLength {
+            name,
+            expected: len,
+            actual: values.len(),
+        });
+    }
+    values.iter().map(|value| element_from_str(value)).collect()
+}
+
+fn row_from_strings<F: PrimeField, const N: usize>(
+    name: &'static str,
+    row: &[String],
+) -> Result<[F; N], Poseidon2ParamsError> {
+    let row = elements_from_strings(name, row, N)?;
+    Ok(row.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+fn rows_from_strings<F: PrimeField, const N: usize>(
+    name: &'static str,
+    rows: &[Vec<String>],
+    num_rows: usize,
+) -> Result<Vec<[F; N]>, Poseidon2ParamsError> {
+    if rows.len() != num_rows {
+        return Err(Poseidon2ParamsError::WrongLength {
+            name,
+            expected: num_rows,
+            actual: rows.len(),
+        });
+    }
+    rows.iter().map(|row| row_from_strings(name, row)).collect()
+}
+
+impl<F: PrimeField, const WIDTH: usize> From<Poseidon2Params<F, WIDTH>> for Poseidon2ParamsRepr {
+    fn from(params: Poseidon2Params<F, WIDTH>) -> Self {
+        let rows_to_strings =
+            |rows: &[[F; WIDTH]]| rows.iter().map(|row| row_to_strings(row)).collect();
+        Self {
+            field_modulus: F::order().to_string(),
+            width: WIDTH,
+            d: params.d,
+            rounds_f: params.rounds_f(),
+            rounds_p: params.rounds_p(),
+            external_mat4: params
+                .external_mat4
+                .map(|mat4| mat4.iter().map(|row| row_to_strings(row)).collect()),
+            mat_internal_diag_m_1: row_to_strings(&params.mat_internal_diag_m_1),
+            external_initial_constants: rows_to_strings(&params.external_initial_constants),
+            internal_constants: row_to_strings(&params.internal_constants),
+            external_terminal_constants: rows_to_strings(&params.external_terminal_constants),
+        }
+    }
+}
+
+impl<F: PrimeField, const WIDTH: usize> TryFrom<Poseidon2ParamsRepr> for Poseidon2Params<F, WIDTH> {
+    type Error = Poseidon2ParamsError;
+
+    fn try_from(repr: Poseidon2ParamsRepr) -> Result<Self, Self::Error> {
+        let modulus = F::order().to_string();
+        if repr.field_modulus != modulus {
+            return Err(Poseidon2ParamsError::WrongFieldModulus {
+                expected: modulus,
+                actual: repr.field_modulus,
+            });
+        }
+        if repr.width != WIDTH {
+            return Err(Poseidon2ParamsError::WrongWidth {
+                expected: WIDTH,
+                actual: repr.width,
+            });
+        }
+        if repr.rounds_f % 2 != 0 {
+            return Err(Poseidon2ParamsError::OddFullRounds {
+                rounds_f: repr.rounds_f,
+            });
+        }
+
+        let external_mat4 = match (&repr.external_mat4, WIDTH >= 4) {
+            (Some(mat4), true) => {
+                let rows = rows_from_strings::<F, 4>("external_mat4", mat4, 4)?;
+                Some(rows.try_into().unwrap_or_else(|_| unreachable!()))
+            }
+            (None, false) => None,
+            (mat4, _) => {
+                return Err(Poseidon2ParamsError::WrongLength {
+                    name: "external_mat4",
+                    expected: if WIDTH >= 4 { 4 } else { 0 },
+                    actual: mat4.as_ref().map_or(0, Vec::len),
+                })
+            }
+        };
+        Ok(Self {
+            d: repr.d,
+            external_mat4,
+            mat_internal_diag_m_1: row_from_strings(
+                "mat_internal_diag_m_1",
+                &repr.mat_internal_diag_m_1,
+            )?,
+            external_initial_constants: rows_from_strings(
+                "external_initial_constants",
+                &repr.external_initial_constants,
+                repr.rounds_f / 2,
+            )?,
+            internal_constants: elements_from_strings(
+                "internal_constants",
+                &repr.internal_constants,
+                repr.rounds_p,
+            )?,
+            external_terminal_constants: rows_from_strings(
+                "external_terminal_constants",
+                &repr.external_terminal_constants,
+                repr.rounds_f / 2,
+            )?,
+        })
+    }
+}
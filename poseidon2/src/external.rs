@@ -1,11 +1,13 @@
 use alloc::vec::Vec;
 
-use p3_field::FieldAlgebra;
+use p3_field::{FieldAlgebra, PrimeField64};
 use p3_mds::MdsPermutation;
 use p3_symmetric::Permutation;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 
+use crate::poseidon2_round_numbers_128;
+
 /// Multiply a 4-element vector x by
 /// [ 5 7 1 3 ]
 /// [ 4 6 1 1 ]
@@ -155,6 +157,32 @@ pub fn mds_light_permutation<
     }
 }
 
+/// The order in which a flat list of Poseidon2 round constants is given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundConstantsLayout {
+    /// One row of `WIDTH` constants for each round, in the order in which the rounds are applied:
+    /// the initial full rounds, the partial rounds and the terminal full rounds. The row of a
+    /// partial round holds its constant followed by zeros. This is the layout of the HorizenLabs
+    /// reference implementation.
+    HorizenLabs,
+    /// The `WIDTH` constants of each full round, initial then terminal, followed by the single
+    /// constant of each partial round. This is the layout of `ExternalLayerConstants` followed by
+    /// the internal constants, as stored by Plonky3.
+    Plonky3,
+}
+
+/// A reason for which a flat list of round constants cannot be split into rounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundConstantsError {
+    /// The number of full rounds is odd, so they cannot be split into initial and terminal halves.
+    OddFullRounds { rounds_f: usize },
+    /// The list does not hold as many constants as the rounds need.
+    WrongLength { expected: usize, actual: usize },
+    /// The row of the partial round `round`, counted among all rounds, has a non-zero constant
+    /// after its first, which suggests that the rows are not aligned with the rounds.
+    NonZeroPartialRoundPadding { round: usize },
+}
+
 /// A struct which holds the constants for the external layer.
 #[derive(Debug, Clone)]
 pub struct ExternalLayerConstants<T, const WIDTH: usize> {
@@ -201,6 +229,78 @@ impl<T, const WIDTH: usize> ExternalLayerConstants<T, WIDTH> {
         Self::new(initial_consts, terminal_consts)
     }
 
+    /// Split a flat list of round constants, laid out as in `layout`, into the constants of the
+    /// external layer and those of the internal layer, for `rounds_f` full rounds and `rounds_p`
+    /// partial rounds.
+    pub fn from_flat_constants(
+        constants: &[T],
+        rounds_f: usize,
+        rounds_p: usize,
+        layout: RoundConstantsLayout,
+    ) -> Result<(Self, Vec<T>), RoundConstantsError>
+    where
+        T: FieldAlgebra + Copy + PartialEq,
+    {
+        if rounds_f % 2 != 0 {
+            return Err(RoundConstantsError::OddFullRounds { rounds_f });
+        }
+        let half_f = rounds_f / 2;
+        let internal_len = match layout {
+            RoundConstantsLayout::HorizenLabs => rounds_p * WIDTH,
+            RoundConstantsLayout::Plonky3 => rounds_p,
+        };
+        let expected = rounds_f * WIDTH + internal_len;
+        if constants.len() != expected {
+            return Err(RoundConstantsError::WrongLength {
+                expected,
+                actual: constants.len(),
+            });
+        }
+
+        let rows = |constants: &[T]| -> Vec<[T; WIDTH]> {
+            constants
+                .chunks_exact(WIDTH)
+                .map(|row| row.try_into().unwrap())
+                .collect()
+        };
+        let (initial, internal, terminal) = match layout {
+            RoundConstantsLayout::HorizenLabs => {
+                let (initial, rest) = constants.split_at(half_f * WIDTH);
+                let (internal, terminal) = rest.split_at(internal_len);
+                let mut internal_constants = Vec::with_capacity(rounds_p);
+                for (round, row) in internal.chunks_exact(WIDTH).enumerate() {
+                    if row[1..].iter().any(|&c| c != T::ZERO) {
+                        return Err(RoundConstantsError::NonZeroPartialRoundPadding {
+                            round: half_f + round,
+                        });
+                    }
+                    internal_constants.push(row[0]);
+                }
+                (initial, internal_constants, terminal)
+            }
+            RoundConstantsLayout::Plonky3 => {
+                let (external, internal) = constants.split_at(rounds_f * WIDTH);
+                let (initial, terminal) = external.split_at(half_f * WIDTH);
+                (initial, internal.to_vec(), terminal)
+            }
+        };
+        Ok((Self::new(rows(initial), rows(terminal)), internal))
+    }
+
+    /// Split a flat list of round constants, laid out as in `layout`, as in `from_flat_constants`,
+    /// with the numbers of rounds needed for 128 bit security with an S-box of degree `d`.
+    pub fn from_flat_constants_128(
+        constants: &[T],
+        d: u64,
+        layout: RoundConstantsLayout,
+    ) -> Result<(Self, Vec<T>), RoundConstantsError>
+    where
+        T: PrimeField64,
+    {
+        let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<T>(WIDTH, d);
+        Self::from_flat_constants(constants, rounds_f, rounds_p, layout)
+    }
+
     pub fn get_initial_constants(&self) -> &Vec<[T; WIDTH]> {
         &self.initial
     }
@@ -8,6 +8,8 @@
 
 extern crate alloc;
 
+mod diffusion;
+mod export;
 mod external;
 mod generic;
 mod internal;
@@ -15,6 +17,8 @@ mod round_numbers;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
+pub use diffusion::*;
+pub use export::*;
 pub use external::*;
 pub use generic::*;
 pub use internal::*;
@@ -0,0 +1,66 @@
+use p3_field::FieldAlgebra;
+use p3_goldilocks::{Goldilocks, MATRIX_DIAG_12_GOLDILOCKS, MATRIX_DIAG_8_GOLDILOCKS};
+use p3_mersenne_31::Mersenne31;
+use p3_poseidon2::{
+    check_diffusion_diagonal, matmul_internal, DiffusionMatrixError, DiffusionMatrixFromDiagonal,
+};
+use p3_symmetric::Permutation;
+
+fn from_i32<F: FieldAlgebra>(x: i32) -> F {
+    let abs = F::from_canonical_u32(x.unsigned_abs());
+    if x < 0 {
+        -abs
+    } else {
+        abs
+    }
+}
+
+#[test]
+fn test_goldilocks_diagonals_are_safe() {
+    check_diffusion_diagonal(&MATRIX_DIAG_8_GOLDILOCKS).expect("width 8 diagonal is unsafe");
+    check_diffusion_diagonal(&MATRIX_DIAG_12_GOLDILOCKS).expect("width 12 diagonal is unsafe");
+}
+
+#[test]
+fn test_mersenne_31_diagonal_is_safe() {
+    // The vector checked by the sage code in `internal.rs`.
+    let diag: [Mersenne31; 16] = [
+        -2, 1, 2, 4, 8, 16, 32, 64, 128, 256, 1024, 4096, 8192, 16384, 32768, 65536,
+    ]
+    .map(from_i32);
+    let matrix = DiffusionMatrixFromDiagonal::new_checked(diag).expect("diagonal is unsafe");
+
+    let input: [Mersenne31; 16] = core::array::from_fn(Mersenne31::from_canonical_usize);
+    let mut expected = input;
+    matmul_internal(&mut expected, diag);
+    assert_eq!(matrix.permute(input), expected);
+}
+
+#[test]
+fn test_unsafe_diagonals() {
+    let mut diag = MATRIX_DIAG_8_GOLDILOCKS;
+    diag[3] = Goldilocks::NEG_ONE;
+    assert_eq!(
+        check_diffusion_diagonal(&diag),
+        Err(DiffusionMatrixError::ZeroEntry { index: 3 })
+    );
+    diag[3] = Goldilocks::ZERO;
+    assert_eq!(
+        check_diffusion_diagonal(&diag),
+        Err(DiffusionMatrixError::ZeroDiagonal { index: 3 })
+    );
+
+    // A constant diagonal `d` gives a matrix with the eigenvalue `d` repeated.
+    let constant = [Goldilocks::TWO; 8];
+    assert_eq!(
+        check_diffusion_diagonal(&constant),
+        Err(DiffusionMatrixError::ReducibleCharacteristicPolynomial { power: 1 })
+    );
+
+    // `1 + Diag(v)` is singular when `sum_i 1 / v_i = -1`, e.g. for `v = [-4; 4]`.
+    let singular = [from_i32::<Goldilocks>(-4); 4];
+    assert_eq!(
+        check_diffusion_diagonal(&singular),
+        Err(DiffusionMatrixError::Singular)
+    );
+}
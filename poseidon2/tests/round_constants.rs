@@ -0,0 +1,138 @@
+use p3_baby_bear::BabyBear;
+use p3_field::FieldAlgebra;
+use p3_poseidon2::{
+    poseidon2_round_numbers_128, ExternalLayerConstants, RoundConstantsError, RoundConstantsLayout,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+type F = BabyBear;
+const WIDTH: usize = 16;
+const D: u64 = 7;
+
+/// Random constants for each round, in the order in which the rounds are applied.
+fn random_rounds(rounds_f: usize, rounds_p: usize) -> (Vec<[F; WIDTH]>, Vec<F>, Vec<[F; WIDTH]>) {
+    let mut rng = StdRng::seed_from_u64(1);
+    let initial = (0..rounds_f / 2).map(|_| rng.gen()).collect();
+    let internal = (0..rounds_p).map(|_| rng.gen()).collect();
+    let terminal = (0..rounds_f / 2).map(|_| rng.gen()).collect();
+    (initial, internal, terminal)
+}
+
+fn horizen_labs_layout(initial: &[[F; WIDTH]], internal: &[F], terminal: &[[F; WIDTH]]) -> Vec<F> {
+    let internal_rows = internal.iter().map(|&c| {
+        let mut row = [F::ZERO; WIDTH];
+        row[0] = c;
+        row
+    });
+    initial
+        .iter()
+        .copied()
+        .chain(internal_rows)
+        .chain(terminal.iter().copied())
+        .flatten()
+        .collect()
+}
+
+fn plonky3_layout(initial: &[[F; WIDTH]], internal: &[F], terminal: &[[F; WIDTH]]) -> Vec<F> {
+    initial
+        .iter()
+        .chain(terminal)
+        .flatten()
+        .chain(internal)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_split_both_layouts() {
+    let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<F>(WIDTH, D);
+    let (initial, internal, terminal) = random_rounds(rounds_f, rounds_p);
+
+    for (layout, flat) in [
+        (
+            RoundConstantsLayout::HorizenLabs,
+            horizen_labs_layout(&initial, &internal, &terminal),
+        ),
+        (
+            RoundConstantsLayout::Plonky3,
+            plonky3_layout(&initial, &internal, &terminal),
+        ),
+    ] {
+        let (external, internal_constants) =
+            ExternalLayerConstants::<F, WIDTH>::from_flat_constants_128(&flat, D, layout).unwrap();
+        assert_eq!(external.get_initial_constants(), &initial);
+        assert_eq!(external.get_terminal_constants(), &terminal);
+        assert_eq!(internal_constants, internal);
+    }
+}
+
+#[test]
+fn test_wrong_length() {
+    let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<F>(WIDTH, D);
+    let (initial, internal, terminal) = random_rounds(rounds_f, rounds_p);
+
+    // Dropping the constant of the last partial round is caught, rather than shifting the
+    // terminal rounds by one.
+    let flat = plonky3_layout(&initial, &internal[1..], &terminal);
+    assert_eq!(
+        ExternalLayerConstants::<F, WIDTH>::from_flat_constants_128(
+            &flat,
+            D,
+            RoundConstantsLayout::Plonky3
+        )
+        .unwrap_err(),
+        RoundConstantsError::WrongLength {
+            expected: flat.len() + 1,
+            actual: flat.len(),
+        }
+    );
+
+    // The constants of one layout do not fit the other.
+    let flat = horizen_labs_layout(&initial, &internal, &terminal);
+    assert!(matches!(
+        ExternalLayerConstants::<F, WIDTH>::from_flat_constants(
+            &flat,
+            rounds_f,
+            rounds_p,
+            RoundConstantsLayout::Plonky3
+        ),
+        Err(RoundConstantsError::WrongLength { .. })
+    ));
+}
+
+#[test]
+fn test_odd_full_rounds() {
+    let flat = vec![F::ONE; 3 * WIDTH + 2];
+    assert_eq!(
+        ExternalLayerConstants::<F, WIDTH>::from_flat_constants(
+            &flat,
+            3,
+            2,
+            RoundConstantsLayout::Plonky3
+        )
+        .unwrap_err(),
+        RoundConstantsError::OddFullRounds { rounds_f: 3 }
+    );
+}
+
+#[test]
+fn test_misaligned_horizen_labs_rows() {
+    let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<F>(WIDTH, D);
+    let (initial, internal, terminal) = random_rounds(rounds_f, rounds_p);
+
+    // Splitting with one full round too few puts the last initial round among the partial rounds.
+    let flat = horizen_labs_layout(&initial, &internal, &terminal);
+    assert_eq!(
+        ExternalLayerConstants::<F, WIDTH>::from_flat_constants(
+            &flat,
+            rounds_f - 2,
+            rounds_p + 2,
+            RoundConstantsLayout::HorizenLabs
+        )
+        .unwrap_err(),
+        RoundConstantsError::NonZeroPartialRoundPadding {
+            round: rounds_f / 2 - 1
+        }
+    );
+}
@@ -0,0 +1,219 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_uni_stark::{Entry, SymbolicAir, SymbolicAirBuilder, SymbolicExpression, SymbolicVariable};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::Val;
+
+/// A polynomial in the columns of the current and next rows, the public values and the row
+/// selectors, built with the arithmetic operators of Python.
+#[pyclass(name = "Expr", module = "p3", unsendable)]
+#[derive(Clone, Debug)]
+pub struct PyExpr(pub(crate) SymbolicExpression<Val>);
+
+/// An operand of the arithmetic of `Expr`, where integers are taken modulo the BabyBear prime.
+#[derive(FromPyObject)]
+enum ExprLike {
+    Expr(PyExpr),
+    Int(i64),
+}
+
+impl From<ExprLike> for SymbolicExpression<Val> {
+    fn from(value: ExprLike) -> Self {
+        match value {
+            ExprLike::Expr(expr) => expr.0,
+            ExprLike::Int(n) if n >= 0 => Val::from_wrapped_u64(n as u64).into(),
+            ExprLike::Int(n) => (-Val::from_wrapped_u64(n.unsigned_abs())).into(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyExpr {
+    fn __add__(&self, other: ExprLike) -> Self {
+        Self(self.0.clone() + SymbolicExpression::from(other))
+    }
+
+    fn __radd__(&self, other: ExprLike) -> Self {
+        Self(SymbolicExpression::from(other) + self.0.clone())
+    }
+
+    fn __sub__(&self, other: ExprLike) -> Self {
+        Self(self.0.clone() - SymbolicExpression::from(other))
+    }
+
+    fn __rsub__(&self, other: ExprLike) -> Self {
+        Self(SymbolicExpression::from(other) - self.0.clone())
+    }
+
+    fn __mul__(&self, other: ExprLike) -> Self {
+        Self(self.0.clone() * SymbolicExpression::from(other))
+    }
+
+    fn __rmul__(&self, other: ExprLike) -> Self {
+        Self(SymbolicExpression::from(other) * self.0.clone())
+    }
+
+    fn __neg__(&self) -> Self {
+        Self(-self.0.clone())
+    }
+
+    /// The degree of the expression, counting each selector as degree one.
+    #[getter]
+    fn degree(&self) -> usize {
+        self.0.degree_multiple()
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The main trace column `index`, in the current row, or the next row if `offset` is one.
+#[pyfunction]
+#[pyo3(signature = (index, offset = 0))]
+pub(crate) fn main(index: usize, offset: usize) -> PyResult<PyExpr> {
+    if offset > 1 {
+        return Err(PyValueError::new_err(
+            "constraints may only refer to the current and next rows",
+        ));
+    }
+    Ok(PyExpr(
+        SymbolicVariable::new(Entry::Main { offset }, index).into(),
+    ))
+}
+
+/// The public value `index`.
+#[pyfunction]
+pub(crate) fn public(index: usize) -> PyExpr {
+    PyExpr(SymbolicVariable::new(Entry::Public, index).into())
+}
+
+#[pyfunction]
+pub(crate) fn constant(value: ExprLike) -> PyExpr {
+    PyExpr(value.into())
+}
+
+#[pyfunction]
+pub(crate) fn is_first_row() -> PyExpr {
+    PyExpr(SymbolicExpression::IsFirstRow)
+}
+
+#[pyfunction]
+pub(crate) fn is_last_row() -> PyExpr {
+    PyExpr(SymbolicExpression::IsLastRow)
+}
+
+/// The selector of every row but the last, on which the next row wraps around to the first.
+#[pyfunction]
+pub(crate) fn is_transition() -> PyExpr {
+    PyExpr(SymbolicExpression::IsTransition)
+}
+
+/// An AIR whose constraints are given as expressions, to be recorded as a `SymbolicAir`.
+struct ConstraintList {
+    width: usize,
+    constraints: Vec<SymbolicExpression<Val>>,
+}
+
+impl BaseAir<Val> for ConstraintList {
+    fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl Air<SymbolicAirBuilder<Val>> for ConstraintList {
+    fn eval(&self, builder: &mut SymbolicAirBuilder<Val>) {
+        for constraint in &self.constraints {
+            builder.assert_zero(constraint.clone());
+        }
+    }
+}
+
+/// Checks that `expr` only refers to the columns and public values of the AIR.
+fn check_variables(
+    expr: &SymbolicExpression<Val>,
+    width: usize,
+    num_public_values: usize,
+) -> PyResult<()> {
+    match expr {
+        SymbolicExpression::Variable(v) => {
+            let in_range = match v.entry {
+                Entry::Main { .. } => v.index < width,
+                Entry::Public => v.index < num_public_values,
+                _ => false,
+            };
+            if in_range {
+                Ok(())
+            } else {
+                Err(PyValueError::new_err(format!(
+                    "{v} is not a column or public value of the AIR"
+                )))
+            }
+        }
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => {
+            check_variables(x, width, num_public_values)?;
+            check_variables(y, width, num_public_values)
+        }
+        SymbolicExpression::Neg { x, .. } => check_variables(x, width, num_public_values),
+        _ => Ok(()),
+    }
+}
+
+/// An AIR with `width` main columns and `num_public_values` public values, whose constraints
+/// must vanish on every row.
+#[pyclass(name = "Air", module = "p3")]
+#[derive(Clone, Debug)]
+pub struct PyAir {
+    pub(crate) air: SymbolicAir<Val>,
+    pub(crate) num_public_values: usize,
+}
+
+#[pymethods]
+impl PyAir {
+    #[new]
+    #[pyo3(signature = (width, constraints, num_public_values = 0))]
+    fn new(width: usize, constraints: Vec<PyExpr>, num_public_values: usize) -> PyResult<Self> {
+        if width == 0 {
+            return Err(PyValueError::new_err("the AIR must have a column"));
+        }
+        for constraint in &constraints {
+            check_variables(&constraint.0, width, num_public_values)?;
+        }
+        let constraints = ConstraintList {
+            width,
+            constraints: constraints.into_iter().map(|c| c.0).collect(),
+        };
+        Ok(Self {
+            air: SymbolicAir::new(&constraints, 0, num_public_values),
+            num_public_values,
+        })
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        BaseAir::<Val>::width(&self.air)
+    }
+
+    #[getter]
+    fn num_public_values(&self) -> usize {
+        self.num_public_values
+    }
+
+    #[getter]
+    fn num_constraints(&self) -> usize {
+        self.air.num_constraints()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Air(width={}, num_constraints={}, num_public_values={})",
+            self.width(),
+            self.num_constraints(),
+            self.num_public_values
+        )
+    }
+}
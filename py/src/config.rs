@@ -0,0 +1,171 @@
+use p3_baby_bear::Poseidon2BabyBear;
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{
+    CryptographicHasher, PaddingFreeSponge, Permutation, PseudoCompressionFunction,
+    TruncatedPermutation,
+};
+use p3_uni_stark::{debug_constraints, prove, verify, Proof, StarkConfig};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use rand::SeedableRng;
+use rand_xoshiro::Xoroshiro128Plus;
+
+use crate::{from_fields, to_fields, PyAir, PyMatrix, Val};
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+/// The maximum number of failing constraints described when a trace does not satisfy its AIR.
+const MAX_REPORTED_FAILURES: usize = 5;
+
+/// A BabyBear STARK configuration, with Merkle trees and Fiat-Shamir over a Poseidon2
+/// permutation whose constants are drawn from `seed`, so that the prover and the verifier must
+/// use the same seed.
+#[pyclass(name = "Config", module = "p3")]
+pub struct PyConfig {
+    config: MyConfig,
+    perm: Perm,
+}
+
+impl PyConfig {
+    /// Checks that `trace` and `public_values` have the shape expected by `air`.
+    fn check_shape(air: &PyAir, trace: &PyMatrix, public_values: &[Val]) -> PyResult<()> {
+        if trace.inner.width() != air.width() {
+            return Err(PyValueError::new_err(
+                "the trace width differs from the AIR's",
+            ));
+        }
+        if !trace.inner.height().is_power_of_two() {
+            return Err(PyValueError::new_err(
+                "the trace height must be a power of two",
+            ));
+        }
+        if public_values.len() != air.num_public_values {
+            return Err(PyValueError::new_err(
+                "the number of public values differs from the AIR's",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyConfig {
+    #[new]
+    #[pyo3(signature = (seed = 0, log_blowup = 1, num_queries = 100, proof_of_work_bits = 16))]
+    fn new(seed: u64, log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> Self {
+        let perm = Perm::new_from_rng_128(&mut Xoroshiro128Plus::seed_from_u64(seed));
+        let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+        let fri_config = FriConfig {
+            log_blowup,
+            log_final_poly_len: 0,
+            num_queries,
+            proof_of_work_bits,
+            deduplicate_queries: false,
+            mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+        };
+        let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+        Self {
+            config: MyConfig::new(pcs),
+            perm,
+        }
+    }
+
+    /// Applies the Poseidon2 permutation to a state of 16 elements.
+    fn permute(&self, state: Vec<u32>) -> PyResult<Vec<u32>> {
+        let state: [Val; 16] = to_fields(&state)?
+            .try_into()
+            .map_err(|_| PyValueError::new_err("the state must have 16 elements"))?;
+        Ok(from_fields(&self.perm.permute(state)))
+    }
+
+    /// Hashes any number of elements into a digest of 8, as the leaves of the Merkle trees are.
+    fn hash(&self, values: Vec<u32>) -> PyResult<Vec<u32>> {
+        let hash = MyHash::new(self.perm.clone());
+        Ok(from_fields(&hash.hash_iter(to_fields(&values)?)))
+    }
+
+    /// Compresses two digests of 8 elements into one, as the nodes of the Merkle trees are.
+    fn compress(&self, left: Vec<u32>, right: Vec<u32>) -> PyResult<Vec<u32>> {
+        let digest = |values: Vec<u32>| -> PyResult<[Val; 8]> {
+            to_fields(&values)?
+                .try_into()
+                .map_err(|_| PyValueError::new_err("each digest must have 8 elements"))
+        };
+        let compress = MyCompress::new(self.perm.clone());
+        Ok(from_fields(
+            &compress.compress([digest(left)?, digest(right)?]),
+        ))
+    }
+
+    /// Proves that `trace` satisfies `air` with the given public values, and returns the encoded
+    /// proof. Raises `ValueError`, describing the failing constraints, if it does not.
+    fn prove<'py>(
+        &self,
+        py: Python<'py>,
+        air: &PyAir,
+        trace: &PyMatrix,
+        public_values: Vec<u32>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let public_values = to_fields(&public_values)?;
+        Self::check_shape(air, trace, &public_values)?;
+        if let Err(failures) = debug_constraints(&air.air, &trace.inner, &public_values) {
+            let descriptions: Vec<_> = failures
+                .iter()
+                .take(MAX_REPORTED_FAILURES)
+                .map(ToString::to_string)
+                .collect();
+            return Err(PyValueError::new_err(format!(
+                "the trace does not satisfy the AIR, with {} failures, including:\n{}",
+                failures.len(),
+                descriptions.join("\n")
+            )));
+        }
+        let mut challenger = Challenger::new(self.perm.clone());
+        let proof = prove(
+            &self.config,
+            &air.air,
+            &mut challenger,
+            trace.inner.clone(),
+            &public_values,
+        );
+        Ok(PyBytes::new_bound(py, &proof.to_bytes()))
+    }
+
+    /// Whether `proof` proves a trace satisfying `air` with the given public values. Raises
+    /// `ValueError` if the proof cannot be decoded.
+    fn verify(&self, air: &PyAir, proof: &[u8], public_values: Vec<u32>) -> PyResult<bool> {
+        let proof = Proof::<MyConfig>::from_bytes(proof)
+            .map_err(|err| PyValueError::new_err(format!("invalid proof: {err:?}")))?;
+        let public_values = to_fields(&public_values)?;
+        if public_values.len() != air.num_public_values {
+            return Ok(false);
+        }
+        let mut challenger = Challenger::new(self.perm.clone());
+        Ok(verify(
+            &self.config,
+            &air.air,
+            &mut challenger,
+            &proof,
+            &public_values,
+        )
+        .is_ok())
+    }
+}
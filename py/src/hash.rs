@@ -0,0 +1,21 @@
+use p3_blake3::Blake3;
+use p3_keccak::Keccak256Hash;
+use p3_sha256::Sha256;
+use p3_symmetric::CryptographicHasher;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+#[pyfunction]
+pub(crate) fn keccak256<'py>(py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+    PyBytes::new_bound(py, &Keccak256Hash.hash_iter(data.iter().copied()))
+}
+
+#[pyfunction]
+pub(crate) fn sha256<'py>(py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+    PyBytes::new_bound(py, &Sha256.hash_iter(data.iter().copied()))
+}
+
+#[pyfunction]
+pub(crate) fn blake3<'py>(py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+    PyBytes::new_bound(py, &Blake3.hash_iter(data.iter().copied()))
+}
@@ -0,0 +1,80 @@
+//! Python bindings for prototyping AIRs and test vectors without writing Rust.
+//!
+//! The `p3` module exposes BabyBear matrices, the byte hash functions, and AIRs whose constraints
+//! are written in Python as expressions over the columns of the current and next rows, built with
+//! `main`, `public` and the row selectors. A `Config` proves and verifies such AIRs with the usual
+//! BabyBear-Poseidon2 configuration, whose Poseidon2 constants are drawn from a seed, and exposes
+//! that Poseidon2 permutation and the sponge and compression function built from it.
+//!
+//! ```python
+//! import p3
+//!
+//! a, b = p3.main(0), p3.main(1)
+//! air = p3.Air(2, [
+//!     p3.is_first_row() * (a - p3.public(0)),
+//!     p3.is_transition() * (p3.main(0, 1) - b),
+//!     p3.is_transition() * (p3.main(1, 1) - a - b),
+//! ], num_public_values=1)
+//! config = p3.Config(seed=0)
+//! proof = config.prove(air, trace, [0])
+//! assert config.verify(air, proof, [0])
+//! ```
+//!
+//! Build the module with `maturin develop --release`, which enables the `extension-module`
+//! feature.
+
+use p3_baby_bear::BabyBear;
+use p3_field::{FieldAlgebra, PrimeField32};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+mod air;
+mod config;
+mod hash;
+mod matrix;
+
+pub use air::*;
+pub use config::*;
+pub use matrix::*;
+
+pub(crate) type Val = BabyBear;
+
+/// The field element with the canonical representative `value`.
+pub(crate) fn to_field(value: u32) -> PyResult<Val> {
+    if value < Val::ORDER_U32 {
+        Ok(Val::from_canonical_u32(value))
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{value} is not a canonical BabyBear element"
+        )))
+    }
+}
+
+pub(crate) fn to_fields(values: &[u32]) -> PyResult<Vec<Val>> {
+    values.iter().map(|&value| to_field(value)).collect()
+}
+
+pub(crate) fn from_fields(values: &[Val]) -> Vec<u32> {
+    values
+        .iter()
+        .map(|value| value.as_canonical_u32())
+        .collect()
+}
+
+#[pymodule]
+fn p3(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMatrix>()?;
+    m.add_class::<PyExpr>()?;
+    m.add_class::<PyAir>()?;
+    m.add_class::<PyConfig>()?;
+    m.add_function(wrap_pyfunction!(air::main, m)?)?;
+    m.add_function(wrap_pyfunction!(air::public, m)?)?;
+    m.add_function(wrap_pyfunction!(air::constant, m)?)?;
+    m.add_function(wrap_pyfunction!(air::is_first_row, m)?)?;
+    m.add_function(wrap_pyfunction!(air::is_last_row, m)?)?;
+    m.add_function(wrap_pyfunction!(air::is_transition, m)?)?;
+    m.add_function(wrap_pyfunction!(hash::keccak256, m)?)?;
+    m.add_function(wrap_pyfunction!(hash::sha256, m)?)?;
+    m.add_function(wrap_pyfunction!(hash::blake3, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::{from_fields, to_fields, Val};
+
+/// A row-major matrix of BabyBear elements, such as a trace, given by their canonical values.
+#[pyclass(name = "Matrix", module = "p3")]
+#[derive(Clone, Debug)]
+pub struct PyMatrix {
+    pub(crate) inner: RowMajorMatrix<Val>,
+}
+
+#[pymethods]
+impl PyMatrix {
+    #[new]
+    fn new(values: Vec<u32>, width: usize) -> PyResult<Self> {
+        if width == 0 || values.is_empty() || values.len() % width != 0 {
+            return Err(PyValueError::new_err(
+                "the number of values must be a nonzero multiple of the width",
+            ));
+        }
+        Ok(Self {
+            inner: RowMajorMatrix::new(to_fields(&values)?, width),
+        })
+    }
+
+    #[staticmethod]
+    fn from_rows(rows: Vec<Vec<u32>>) -> PyResult<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(PyValueError::new_err("the rows must have the same length"));
+        }
+        Self::new(rows.concat(), width)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.inner.height()
+    }
+
+    fn get(&self, row: usize, col: usize) -> PyResult<u32> {
+        if row >= self.height() || col >= self.width() {
+            return Err(PyIndexError::new_err("index out of range"));
+        }
+        Ok(from_fields(&[self.inner.get(row, col)])[0])
+    }
+
+    fn row(&self, row: usize) -> PyResult<Vec<u32>> {
+        if row >= self.height() {
+            return Err(PyIndexError::new_err("row out of range"));
+        }
+        Ok(from_fields(&self.inner.row_slice(row)))
+    }
+
+    fn values(&self) -> Vec<u32> {
+        from_fields(&self.inner.values)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Matrix(width={}, height={})", self.width(), self.height())
+    }
+}
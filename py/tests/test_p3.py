@@ -0,0 +1,95 @@
+"""Tests of the Python bindings, run with `pytest` after `maturin develop --release`."""
+
+import pytest
+
+import p3
+
+P = 2**31 - 2**27 + 1
+
+
+def fibonacci_air():
+    a, b = p3.main(0), p3.main(1)
+    return p3.Air(
+        2,
+        [
+            p3.is_first_row() * (a - p3.public(0)),
+            p3.is_first_row() * (b - p3.public(1)),
+            p3.is_transition() * (p3.main(0, 1) - b),
+            p3.is_transition() * (p3.main(1, 1) - a - b),
+            p3.is_last_row() * (b - p3.public(2)),
+        ],
+        num_public_values=3,
+    )
+
+
+def fibonacci_trace(n):
+    rows = [[0, 1]]
+    for _ in range(n - 1):
+        a, b = rows[-1]
+        rows.append([b, (a + b) % P])
+    return p3.Matrix.from_rows(rows), [0, 1, rows[-1][1]]
+
+
+def test_matrix():
+    m = p3.Matrix([1, 2, 3, 4, 5, 6], 2)
+    assert (m.width, m.height) == (2, 3)
+    assert m.row(1) == [3, 4]
+    assert m.get(2, 1) == 6
+    assert m.values() == [1, 2, 3, 4, 5, 6]
+    with pytest.raises(ValueError):
+        p3.Matrix([1, 2, 3], 2)
+    with pytest.raises(ValueError):
+        p3.Matrix([P], 1)
+    with pytest.raises(IndexError):
+        m.row(3)
+
+
+def test_hashes():
+    assert p3.keccak256(b"").hex().startswith("c5d2460186f7233c")
+    assert p3.sha256(b"").hex().startswith("e3b0c44298fc1c14")
+    assert p3.blake3(b"").hex().startswith("af1349b9f5f9a1a6")
+
+    config = p3.Config(seed=1)
+    assert len(config.permute(list(range(16)))) == 16
+    assert config.permute(list(range(16))) == p3.Config(seed=1).permute(list(range(16)))
+    assert config.permute(list(range(16))) != p3.Config(seed=2).permute(list(range(16)))
+    digest = config.hash([1, 2, 3])
+    assert len(digest) == 8
+    assert len(config.compress(digest, digest)) == 8
+
+
+def test_expressions():
+    expr = p3.main(0) * p3.main(1) - 3
+    assert expr.degree == 2
+    assert (p3.is_transition() * expr).degree == 2
+    assert (p3.is_first_row() * expr).degree == 3
+    with pytest.raises(ValueError):
+        p3.main(0, 2)
+    with pytest.raises(ValueError):
+        p3.Air(2, [p3.main(2)])
+    with pytest.raises(ValueError):
+        p3.Air(2, [p3.public(0)])
+
+
+def test_prove_verify():
+    air = fibonacci_air()
+    trace, public_values = fibonacci_trace(16)
+    config = p3.Config(num_queries=10, proof_of_work_bits=1)
+
+    proof = config.prove(air, trace, public_values)
+    assert config.verify(air, proof, public_values)
+    assert not config.verify(air, proof, [0, 1, public_values[2] + 1])
+    assert not p3.Config(seed=1, num_queries=10, proof_of_work_bits=1).verify(
+        air, proof, public_values
+    )
+    with pytest.raises(ValueError):
+        config.verify(air, proof[:-1], public_values)
+
+
+def test_prove_unsatisfied():
+    air = fibonacci_air()
+    trace, public_values = fibonacci_trace(16)
+    with pytest.raises(ValueError, match="does not satisfy"):
+        p3.Config().prove(air, trace, [0, 1, public_values[2] + 1])
+    with pytest.raises(ValueError, match="power of two"):
+        p3.Config().prove(air, fibonacci_trace(12)[0], public_values)
@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+use core::array;
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{FieldAlgebra, PrimeField64};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_u32_air_gadgets::{eval_sum, pack_limbs, rotr, shr, u32_to_limbs, xor_words, U32_LIMBS};
+use rand::random;
+
+use crate::columns::{Sha256Cols, NUM_SHA256_COLS};
+use crate::constants::{
+    BIG_SIGMA0_ROTATIONS, BIG_SIGMA1_ROTATIONS, BLOCK_WORDS, K, NUM_ROUNDS, SMALL_SIGMA0_ROTATIONS,
+    SMALL_SIGMA1_ROTATIONS,
+};
+use crate::generate_trace_rows;
+
+/// Assumes the field size is at least 20 bits.
+#[derive(Debug)]
+pub struct Sha256Air {}
+
+impl Sha256Air {
+    pub fn generate_trace_rows<F: PrimeField64>(
+        &self,
+        num_hashes: usize,
+        extra_capacity_bits: usize,
+    ) -> RowMajorMatrix<F> {
+        let inputs = (0..num_hashes).map(|_| random()).collect::<Vec<_>>();
+        generate_trace_rows(inputs, extra_capacity_bits)
+    }
+}
+
+impl<F> BaseAir<F> for Sha256Air {
+    fn width(&self) -> usize {
+        NUM_SHA256_COLS
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for Sha256Air {
+    #[inline]
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Sha256Cols<AB::Var> = (*local).borrow();
+        self.eval_compression(builder, local);
+    }
+}
+
+impl Sha256Air {
+    /// Verify that the outputs of `local` are the SHA-256 compression of its inputs.
+    pub(crate) fn eval_compression<AB: AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Sha256Cols<AB::Var>,
+    ) {
+        // The remaining bits are range checked by the sums which compute them.
+        local
+            .inputs
+            .iter()
+            .chain(local.chaining_values.iter())
+            .for_each(|bits| bits.iter().for_each(|&bit| builder.assert_bool(bit)));
+
+        // First we verify the message schedule.
+        let w = |t: usize| {
+            if t < BLOCK_WORDS {
+                &local.inputs[t]
+            } else {
+                &local.schedule[t - BLOCK_WORDS].bits
+            }
+        };
+        for t in BLOCK_WORDS..NUM_ROUNDS {
+            let word = &local.schedule[t - BLOCK_WORDS];
+            let summands = [
+                pack_limbs(&small_sigma::<AB>(w(t - 2), SMALL_SIGMA1_ROTATIONS)),
+                pack_limbs(w(t - 7)),
+                pack_limbs(&small_sigma::<AB>(w(t - 15), SMALL_SIGMA0_ROTATIONS)),
+                pack_limbs(w(t - 16)),
+            ];
+            eval_sum(builder, word, &summands);
+        }
+
+        // The words `a` and `e` taken by the state, oldest first. The state before round `t` is
+        // `a = a_words[t + 3], b = a_words[t + 2], ..., h = e_words[t]`.
+        let cv = &local.chaining_values;
+        let a_words: Vec<_> = [&cv[3], &cv[2], &cv[1], &cv[0]]
+            .into_iter()
+            .chain(local.rounds.iter().map(|round| &round.a.bits))
+            .collect();
+        let e_words: Vec<_> = [&cv[7], &cv[6], &cv[5], &cv[4]]
+            .into_iter()
+            .chain(local.rounds.iter().map(|round| &round.e.bits))
+            .collect();
+
+        for (t, round) in local.rounds.iter().enumerate() {
+            let (a, b, c, d) = (a_words[t + 3], a_words[t + 2], a_words[t + 1], a_words[t]);
+            let (e, f, g, h) = (e_words[t + 3], e_words[t + 2], e_words[t + 1], e_words[t]);
+
+            // T_1 = h + Σ1(e) + Ch(e, f, g) + K_t + W_t
+            let t_1: [[AB::Expr; U32_LIMBS]; 5] = [
+                pack_limbs(h),
+                pack_limbs(&big_sigma::<AB>(e, BIG_SIGMA1_ROTATIONS)),
+                pack_limbs(&array::from_fn(|i| ch::<AB>(e[i], f[i], g[i]))),
+                u32_to_limbs(K[t]),
+                pack_limbs(w(t)),
+            ];
+
+            // e' = d + T_1
+            let mut summands = t_1.to_vec();
+            summands.push(pack_limbs(d));
+            eval_sum(builder, &round.e, &summands);
+
+            // a' = T_1 + Σ0(a) + Maj(a, b, c)
+            let mut summands = t_1.to_vec();
+            summands.push(pack_limbs(&big_sigma::<AB>(a, BIG_SIGMA0_ROTATIONS)));
+            summands.push(pack_limbs(&array::from_fn(|i| maj::<AB>(a[i], b[i], c[i]))));
+            eval_sum(builder, &round.a, &summands);
+        }
+
+        // Finally we verify that the outputs are the sums of the chaining value and the final state.
+        for (i, output) in local.outputs.iter().enumerate() {
+            let word = if i < 4 {
+                a_words[NUM_ROUNDS + 3 - i]
+            } else {
+                e_words[NUM_ROUNDS + 7 - i]
+            };
+            eval_sum(builder, output, &[pack_limbs(&cv[i]), pack_limbs(word)]);
+        }
+    }
+}
+
+/// The bits of `Σ(x) = rotr(x, r0) ^ rotr(x, r1) ^ rotr(x, r2)`.
+#[inline]
+fn big_sigma<AB: AirBuilder>(x: &[AB::Var; 32], [r0, r1, r2]: [usize; 3]) -> [AB::Expr; 32] {
+    let x_r0 = rotr(x, r0).map(Into::into);
+    xor_words(xor_words(x_r0, &rotr(x, r1)), &rotr(x, r2))
+}
+
+/// The bits of `σ(x) = rotr(x, r0) ^ rotr(x, r1) ^ (x >> shift)`.
+#[inline]
+fn small_sigma<AB: AirBuilder>(x: &[AB::Var; 32], [r0, r1, shift]: [usize; 3]) -> [AB::Expr; 32] {
+    let x_r0 = rotr(x, r0).map(Into::into);
+    xor_words(xor_words(x_r0, &rotr(x, r1)), &shr::<AB::Expr, _>(x, shift))
+}
+
+/// `Ch(e, f, g) = (e & f) ^ (!e & g)`. The two terms are never both one, so we may add them.
+#[inline]
+fn ch<AB: AirBuilder>(e: AB::Var, f: AB::Var, g: AB::Var) -> AB::Expr {
+    e * f + (AB::Expr::ONE - e) * g
+}
+
+/// `Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)`, which is one when at least two of its inputs are.
+#[inline]
+fn maj<AB: AirBuilder>(a: AB::Var, b: AB::Var, c: AB::Var) -> AB::Expr {
+    let ab: AB::Expr = a * b;
+    ab.clone() + a * c + b * c - ab.double() * c
+}
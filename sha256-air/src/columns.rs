@@ -0,0 +1,69 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_u32_air_gadgets::U32Sum;
+
+use crate::constants::{
+    BLOCK_WORDS, NUM_ROUNDS, OUTPUT_CARRY_BITS, ROUND_CARRY_BITS, SCHEDULE_CARRY_BITS,
+};
+
+/// Columns for a SHA-256 AIR which computes one compression per row.
+///
+/// Every word is saved as `32` boolean values, as each is an input to the bitwise functions of a
+/// later round or schedule word. Sums of words are saved along with the carries out of each limb.
+#[repr(C)]
+pub struct Sha256Cols<T> {
+    /// The block being compressed.
+    pub inputs: [[T; 32]; BLOCK_WORDS],
+
+    /// The chaining value, which is also the state before the first round.
+    pub chaining_values: [[T; 32]; 8],
+
+    /// The words `W_16, ..., W_63` of the message schedule, with
+    /// `W_t = σ1(W_{t - 2}) + W_{t - 7} + σ0(W_{t - 15}) + W_{t - 16}`. The first sixteen words are
+    /// the inputs.
+    pub schedule: [U32Sum<T, SCHEDULE_CARRY_BITS>; NUM_ROUNDS - BLOCK_WORDS],
+
+    pub rounds: [Sha256Round<T>; NUM_ROUNDS],
+
+    /// The sums of the chaining value and the final state, the next chaining value.
+    pub outputs: [U32Sum<T, OUTPUT_CARRY_BITS>; 8],
+}
+
+/// Columns for a single round.
+///
+/// A round shifts the state `(a, b, c, d, e, f, g, h)` along by one word and computes new words
+/// `a` and `e`, so these are all we save. The rest of the state is given by the words computed in
+/// the three previous rounds, or by the chaining value in the first rounds.
+#[repr(C)]
+pub struct Sha256Round<T> {
+    /// `T_1 + T_2`, where `T_1 = h + Σ1(e) + Ch(e, f, g) + K_t + W_t` and `T_2 = Σ0(a) + Maj(a, b, c)`.
+    pub a: U32Sum<T, ROUND_CARRY_BITS>,
+
+    /// `d + T_1`.
+    pub e: U32Sum<T, ROUND_CARRY_BITS>,
+}
+
+pub const NUM_SHA256_COLS: usize = size_of::<Sha256Cols<u8>>();
+
+impl<T> Borrow<Sha256Cols<T>> for [T] {
+    fn borrow(&self) -> &Sha256Cols<T> {
+        debug_assert_eq!(self.len(), NUM_SHA256_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to::<Sha256Cols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &shorts[0]
+    }
+}
+
+impl<T> BorrowMut<Sha256Cols<T>> for [T] {
+    fn borrow_mut(&mut self) -> &mut Sha256Cols<T> {
+        debug_assert_eq!(self.len(), NUM_SHA256_COLS);
+        let (prefix, shorts, suffix) = unsafe { self.align_to_mut::<Sha256Cols<T>>() };
+        debug_assert!(prefix.is_empty(), "Alignment should match");
+        debug_assert!(suffix.is_empty(), "Alignment should match");
+        debug_assert_eq!(shorts.len(), 1);
+        &mut shorts[0]
+    }
+}
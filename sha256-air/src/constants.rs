@@ -0,0 +1,47 @@
+/// The number of rounds of the compression function.
+pub const NUM_ROUNDS: usize = 64;
+/// The number of words in a block, the input to a single compression.
+pub(crate) const BLOCK_WORDS: usize = 16;
+
+// The number of bits needed for the carries out of a limb of each sum. A word of the message
+// schedule is a sum of four words, each word of the state a sum of at most seven, and each output
+// a sum of two.
+pub(crate) const SCHEDULE_CARRY_BITS: usize = 2;
+pub(crate) const ROUND_CARRY_BITS: usize = 3;
+pub(crate) const OUTPUT_CARRY_BITS: usize = 1;
+
+/// The initial chaining value, used for the first block of a message.
+pub const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The round constants.
+pub(crate) const K: [u32; NUM_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// The rotations of the big sigma functions, applied to words of the state.
+pub(crate) const BIG_SIGMA0_ROTATIONS: [usize; 3] = [2, 13, 22];
+pub(crate) const BIG_SIGMA1_ROTATIONS: [usize; 3] = [6, 11, 25];
+
+// The two rotations and the shift of the small sigma functions, applied to words of the message
+// schedule.
+pub(crate) const SMALL_SIGMA0_ROTATIONS: [usize; 3] = [7, 18, 3];
+pub(crate) const SMALL_SIGMA1_ROTATIONS: [usize; 3] = [17, 19, 10];
+
+/// `rotr(x, r0) ^ rotr(x, r1) ^ rotr(x, r2)`.
+pub(crate) const fn big_sigma(x: u32, [r0, r1, r2]: [usize; 3]) -> u32 {
+    x.rotate_right(r0 as u32) ^ x.rotate_right(r1 as u32) ^ x.rotate_right(r2 as u32)
+}
+
+/// `rotr(x, r0) ^ rotr(x, r1) ^ (x >> shift)`.
+pub(crate) const fn small_sigma(x: u32, [r0, r1, shift]: [usize; 3]) -> u32 {
+    x.rotate_right(r0 as u32) ^ x.rotate_right(r1 as u32) ^ (x >> shift)
+}
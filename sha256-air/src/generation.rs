@@ -0,0 +1,92 @@
+use alloc::vec::Vec;
+use core::array;
+
+use p3_air::utils::u32_to_bits_le;
+use p3_field::{PackedValue, PrimeField64};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_maybe_rayon::prelude::*;
+use p3_u32_air_gadgets::generate_sum;
+use tracing::instrument;
+
+use crate::columns::{Sha256Cols, NUM_SHA256_COLS};
+use crate::constants::{
+    big_sigma, small_sigma, BIG_SIGMA0_ROTATIONS, BIG_SIGMA1_ROTATIONS, BITS_PER_LIMB, BLOCK_WORDS,
+    K, NUM_ROUNDS, SMALL_SIGMA0_ROTATIONS, SMALL_SIGMA1_ROTATIONS, U32_LIMBS,
+};
+
+/// Generates a trace proving the SHA-256 compressions of `inputs`, whose number must be a power of
+/// two. Each input is a block of sixteen words followed by a chaining value of eight words.
+///
+/// The trace is allocated with room for `extra_capacity_bits` more bits of height, so that a low
+/// degree extension with that log blowup can be computed in place. Rows are filled in parallel, in
+/// groups of the packing width of `F`.
+#[instrument(name = "generate SHA-256 trace", skip_all)]
+pub fn generate_trace_rows<F: PrimeField64>(
+    inputs: Vec<[u32; 24]>,
+    extra_capacity_bits: usize,
+) -> RowMajorMatrix<F> {
+    let num_rows = inputs.len();
+    assert!(
+        num_rows.is_power_of_two(),
+        "Callers expected to pad inputs to a power of two"
+    );
+
+    let trace_len = num_rows * NUM_SHA256_COLS;
+    let mut values = Vec::with_capacity(trace_len << extra_capacity_bits);
+    values.resize(trace_len, F::ZERO);
+    let mut trace = RowMajorMatrix::new(values, NUM_SHA256_COLS);
+    let (prefix, rows, suffix) = unsafe { trace.values.align_to_mut::<Sha256Cols<F>>() };
+    assert!(prefix.is_empty(), "Alignment should match");
+    assert!(suffix.is_empty(), "Alignment should match");
+    assert_eq!(rows.len(), num_rows);
+
+    let group_len = F::Packing::WIDTH;
+    rows.par_chunks_mut(group_len)
+        .zip(inputs.par_chunks(group_len))
+        .for_each(|(rows, inputs)| {
+            for (row, &input) in rows.iter_mut().zip(inputs) {
+                generate_trace_row_for_compression(row, input);
+            }
+        });
+
+    trace
+}
+
+fn generate_trace_row_for_compression<F: PrimeField64>(row: &mut Sha256Cols<F>, input: [u32; 24]) {
+    let (block, chaining_value) = input.split_at(BLOCK_WORDS);
+
+    row.inputs = array::from_fn(|i| u32_to_bits_le(block[i]));
+    row.chaining_values = array::from_fn(|i| u32_to_bits_le(chaining_value[i]));
+
+    let mut w = [0; NUM_ROUNDS];
+    w[..BLOCK_WORDS].copy_from_slice(block);
+    for t in BLOCK_WORDS..NUM_ROUNDS {
+        w[t] = generate_sum(
+            &mut row.schedule[t - BLOCK_WORDS],
+            &[
+                small_sigma(w[t - 2], SMALL_SIGMA1_ROTATIONS),
+                w[t - 7],
+                small_sigma(w[t - 15], SMALL_SIGMA0_ROTATIONS),
+                w[t - 16],
+            ],
+        );
+    }
+
+    let mut state: [u32; 8] = chaining_value.try_into().unwrap();
+    for (t, round) in row.rounds.iter_mut().enumerate() {
+        let [a, b, c, d, e, f, g, h] = state;
+        let ch = (e & f) ^ (!e & g);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let sigma_1 = big_sigma(e, BIG_SIGMA1_ROTATIONS);
+        let sigma_0 = big_sigma(a, BIG_SIGMA0_ROTATIONS);
+
+        // The summands of T_1 come first in both sums.
+        let new_e = generate_sum(&mut round.e, &[h, sigma_1, ch, K[t], w[t], d]);
+        let new_a = generate_sum(&mut round.a, &[h, sigma_1, ch, K[t], w[t], sigma_0, maj]);
+        state = [new_a, a, b, c, new_e, e, f, g];
+    }
+
+    for (i, output) in row.outputs.iter_mut().enumerate() {
+        generate_sum(output, &[chaining_value[i], state[i]]);
+    }
+}
@@ -0,0 +1,15 @@
+//! An AIR for the SHA-256 compression function. Assumes the field size is between 2^20 and 2^32.
+
+#![no_std]
+
+extern crate alloc;
+
+mod air;
+mod columns;
+mod constants;
+mod generation;
+
+pub use air::*;
+pub use columns::*;
+pub use constants::{IV, NUM_ROUNDS};
+pub use generation::*;
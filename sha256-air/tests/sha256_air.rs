@@ -0,0 +1,86 @@
+use core::array;
+use core::borrow::{Borrow, BorrowMut};
+
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_matrix::Matrix;
+use p3_sha256::{Sha256, Sha256Compress};
+use p3_sha256_air::{generate_trace_rows, Sha256Air, Sha256Cols, IV};
+use p3_symmetric::{CryptographicHasher, PseudoCompressionFunction};
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{debug_constraints, prove, verify};
+use rand::{random, thread_rng};
+
+/// The input compressing `block` with the initial chaining value.
+fn input_from_block(block: &[u8; 64]) -> [u32; 24] {
+    let mut input = [0; 24];
+    for (word, bytes) in input.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    input[16..].copy_from_slice(&IV);
+    input
+}
+
+fn random_block() -> [u8; 64] {
+    array::from_fn(|_| random())
+}
+
+/// The output of a row of the trace, as big-endian bytes.
+fn row_output(row: &Sha256Cols<Val>) -> [u8; 32] {
+    let mut output = [0; 32];
+    for (bytes, word) in output.chunks_exact_mut(4).zip(&row.outputs) {
+        let word = word
+            .bits
+            .iter()
+            .rev()
+            .fold(0, |acc, bit| (acc << 1) | bit.as_canonical_u32());
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    output
+}
+
+#[test]
+fn test_sha256_air() {
+    // "abc" fits in a single padded block, so its hash is a single compression.
+    let mut abc = [0; 64];
+    abc[..3].copy_from_slice(b"abc");
+    abc[3] = 0x80;
+    abc[63] = 24;
+
+    let blocks: Vec<[u8; 64]> = [abc]
+        .into_iter()
+        .chain((1..8).map(|_| random_block()))
+        .collect();
+    let inputs = blocks.iter().map(input_from_block).collect();
+    let trace = generate_trace_rows::<Val>(inputs, 1);
+    assert!(trace.values.capacity() >= 2 * trace.values.len());
+
+    let row = trace.row_slice(0);
+    assert_eq!(
+        row_output((*row).borrow()),
+        Sha256.hash_iter(b"abc".iter().copied())
+    );
+    drop(row);
+    for (i, block) in blocks.iter().enumerate() {
+        let row = trace.row_slice(i);
+        let halves = [
+            block[..32].try_into().unwrap(),
+            block[32..].try_into().unwrap(),
+        ];
+        assert_eq!(row_output((*row).borrow()), Sha256Compress.compress(halves));
+    }
+
+    let (config, perm) = test_config(&mut thread_rng());
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &Sha256Air {}, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &Sha256Air {}, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_sha256_air_wrong_output() {
+    let inputs = (0..4).map(|_| input_from_block(&random_block())).collect();
+    let mut trace = generate_trace_rows::<Val>(inputs, 0);
+    let row: &mut Sha256Cols<Val> = trace.row_mut(2).borrow_mut();
+    row.outputs[5].bits[7] = Val::ONE - row.outputs[5].bits[7];
+    debug_constraints(&Sha256Air {}, &trace, &[]).expect_err("a flipped output bit should fail");
+}
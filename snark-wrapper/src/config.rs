@@ -0,0 +1,70 @@
+use p3_baby_bear::BabyBear;
+use p3_bn254_fr::{Bn254Fr, Poseidon2Bn254};
+use p3_challenger::MultiField32Challenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{PrimeField32, TwoAdicField};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::StarkConfig;
+
+/// The permutation of the sponges and the transcript, over BN254, which the wrapping circuit
+/// evaluates natively.
+pub type OuterPerm = Poseidon2Bn254<3>;
+
+/// Hashes rows of 32-bit field elements, eight to each BN254 element, into a single BN254 digest.
+pub type OuterHash<Val = BabyBear> =
+    MultiField32PaddingFreeSponge<Val, Bn254Fr, OuterPerm, 3, 16, 1>;
+
+pub type OuterCompress = TruncatedPermutation<OuterPerm, 2, 1, 3>;
+
+/// The MMCS committing to the traces, with single BN254 digests.
+pub type OuterValMmcs<Val = BabyBear> =
+    MerkleTreeMmcs<Val, Bn254Fr, OuterHash<Val>, OuterCompress, 1>;
+
+/// The MMCS committing to the FRI layers, over the challenge field.
+pub type OuterChallengeMmcs<Val = BabyBear, Challenge = BinomialExtensionField<Val, 4>> =
+    ExtensionMmcs<Val, Challenge, OuterValMmcs<Val>>;
+
+pub type OuterChallenger<Val = BabyBear> = MultiField32Challenger<Val, Bn254Fr, OuterPerm, 3, 2>;
+
+pub type OuterPcs<Val = BabyBear, Challenge = BinomialExtensionField<Val, 4>> = TwoAdicFriPcs<
+    Val,
+    Radix2DitParallel<Val>,
+    OuterValMmcs<Val>,
+    OuterChallengeMmcs<Val, Challenge>,
+>;
+
+/// A STARK whose commitments and transcript are over BN254, so that a circuit over BN254 can verify
+/// it cheaply, as the last proof before wrapping in a SNARK.
+pub type OuterConfig<Val = BabyBear, Challenge = BinomialExtensionField<Val, 4>> =
+    StarkConfig<OuterPcs<Val, Challenge>, Challenge, OuterChallenger<Val>>;
+
+pub fn outer_val_mmcs<Val: PrimeField32>(perm: OuterPerm) -> OuterValMmcs<Val> {
+    let hash = OuterHash::new(perm.clone()).expect("the field is smaller than BN254");
+    OuterValMmcs::new(hash, OuterCompress::new(perm))
+}
+
+pub fn outer_challenge_mmcs<Val: PrimeField32>(perm: OuterPerm) -> OuterChallengeMmcs<Val> {
+    OuterChallengeMmcs::new(outer_val_mmcs(perm))
+}
+
+/// An `OuterConfig` whose FRI is configured by `fri_config`, which must commit with
+/// `outer_challenge_mmcs(perm)`.
+pub fn outer_config<Val: PrimeField32 + TwoAdicField>(
+    perm: OuterPerm,
+    fri_config: FriConfig<OuterChallengeMmcs<Val>>,
+) -> OuterConfig<Val> {
+    OuterConfig::new(OuterPcs::new(
+        Radix2DitParallel::default(),
+        outer_val_mmcs(perm),
+        fri_config,
+    ))
+}
+
+/// A fresh challenger for proving or verifying with an `OuterConfig`.
+pub fn outer_challenger<Val: PrimeField32>(perm: OuterPerm) -> OuterChallenger<Val> {
+    OuterChallenger::new(perm).expect("the field is smaller than BN254")
+}
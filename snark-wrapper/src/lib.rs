@@ -0,0 +1,21 @@
+//! Export of `uni-stark` proofs to circuits over BN254, which wrap their verification in a
+//! Groth16 or Plonk proof for cheap verification on chain.
+//!
+//! The proofs to be wrapped are made with an `OuterConfig`, whose Merkle trees and Fiat-Shamir
+//! transcript use Poseidon2 over BN254, through `MultiField32PaddingFreeSponge` and
+//! `MultiField32Challenger`, so the circuit hashes natively. `WrapperWitness` then lays out a
+//! proof and its verifying key as the BN254 elements of the circuit's witness, and encodes them
+//! in the binary formats read by gnark and arkworks.
+//!
+//! The circuit itself, which must absorb the witness in the same order as the challenger, is not
+//! part of this crate.
+
+#![no_std]
+
+extern crate alloc;
+
+mod config;
+mod witness;
+
+pub use config::*;
+pub use witness::*;
@@ -0,0 +1,249 @@
+use alloc::vec::Vec;
+
+use p3_bn254_fr::Bn254Fr;
+use p3_commit::{Mmcs, Pcs};
+use p3_field::extension::{BinomialExtensionField, BinomiallyExtendable};
+use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra, PrimeField, PrimeField32};
+use p3_fri::{BatchOpening, CommitPhaseProofStep, FriProof, QueryProof};
+use p3_monty_31::{FieldParameters, MontyField31};
+use p3_symmetric::Hash;
+use p3_uni_stark::{Commitments, OpenedValues, Proof, StarkGenericConfig, Val, VerifyingKey};
+
+/// A value which a wrapping circuit over BN254 takes as a sequence of BN254 elements.
+///
+/// Each element of a smaller prime field takes a whole BN254 element, in canonical form, and an
+/// extension field element takes one for each of its coefficients. Collections are written
+/// without their lengths, which are fixed by the shape of the circuit, i.e. by the verifying key
+/// and the FRI parameters, and absent optional values are skipped for the same reason.
+pub trait Bn254Witness {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>);
+}
+
+impl Bn254Witness for Bn254Fr {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        witness.push(*self);
+    }
+}
+
+impl<FP: FieldParameters> Bn254Witness for MontyField31<FP> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        witness.push(Bn254Fr::from_canonical_u32(self.as_canonical_u32()));
+    }
+}
+
+impl<F: BinomiallyExtendable<D> + Bn254Witness, const D: usize> Bn254Witness
+    for BinomialExtensionField<F, D>
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.as_base_slice().write_witness(witness);
+    }
+}
+
+impl Bn254Witness for usize {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        witness.push(Bn254Fr::from_canonical_usize(*self));
+    }
+}
+
+impl<T: Bn254Witness> Bn254Witness for [T] {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        for value in self {
+            value.write_witness(witness);
+        }
+    }
+}
+
+impl<T: Bn254Witness, const N: usize> Bn254Witness for [T; N] {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self[..].write_witness(witness);
+    }
+}
+
+impl<T: Bn254Witness> Bn254Witness for Vec<T> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self[..].write_witness(witness);
+    }
+}
+
+impl<T: Bn254Witness> Bn254Witness for Option<T> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        if let Some(value) = self {
+            value.write_witness(witness);
+        }
+    }
+}
+
+impl<F, W: Bn254Witness, const DIGEST_ELEMS: usize> Bn254Witness for Hash<F, W, DIGEST_ELEMS> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.as_ref().write_witness(witness);
+    }
+}
+
+impl<Val, InputMmcs> Bn254Witness for BatchOpening<Val, InputMmcs>
+where
+    Val: Field + Bn254Witness,
+    InputMmcs: Mmcs<Val>,
+    InputMmcs::Proof: Bn254Witness,
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.opened_values.write_witness(witness);
+        self.opening_proof.write_witness(witness);
+    }
+}
+
+impl<F, M> Bn254Witness for CommitPhaseProofStep<F, M>
+where
+    F: Field + Bn254Witness,
+    M: Mmcs<F>,
+    M::Proof: Bn254Witness,
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.sibling_value.write_witness(witness);
+        self.opening_proof.write_witness(witness);
+    }
+}
+
+impl<F, M, InputProof> Bn254Witness for QueryProof<F, M, InputProof>
+where
+    F: Field + Bn254Witness,
+    M: Mmcs<F>,
+    M::Proof: Bn254Witness,
+    InputProof: Bn254Witness,
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.input_proof.write_witness(witness);
+        self.commit_phase_openings.write_witness(witness);
+    }
+}
+
+impl<F, M, Witness, InputProof> Bn254Witness for FriProof<F, M, Witness, InputProof>
+where
+    F: Field + Bn254Witness,
+    M: Mmcs<F>,
+    M::Commitment: Bn254Witness,
+    M::Proof: Bn254Witness,
+    Witness: Bn254Witness,
+    InputProof: Bn254Witness,
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.commit_phase_commits.write_witness(witness);
+        self.query_proofs.write_witness(witness);
+        self.final_poly.write_witness(witness);
+        self.pow_witness.write_witness(witness);
+    }
+}
+
+impl<Com: Bn254Witness> Bn254Witness for Commitments<Com> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.trace().write_witness(witness);
+        if let Some(permutation) = self.permutation() {
+            permutation.write_witness(witness);
+        }
+        self.quotient_chunks().write_witness(witness);
+    }
+}
+
+impl<Challenge: Bn254Witness> Bn254Witness for OpenedValues<Challenge> {
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        for values in [
+            self.preprocessed_local(),
+            self.preprocessed_next(),
+            Some(self.trace_local()),
+            Some(self.trace_next()),
+            self.permutation_local(),
+            self.permutation_next(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            values.write_witness(witness);
+        }
+        self.preprocessed_after_next().write_witness(witness);
+        self.trace_after_next().write_witness(witness);
+        self.quotient_chunks().write_witness(witness);
+    }
+}
+
+impl<SC> Bn254Witness for Proof<SC>
+where
+    SC: StarkGenericConfig,
+    SC::Challenge: Bn254Witness,
+    <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::Commitment: Bn254Witness,
+    <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::Proof: Bn254Witness,
+{
+    fn write_witness(&self, witness: &mut Vec<Bn254Fr>) {
+        self.degree_bits().write_witness(witness);
+        self.commitments().write_witness(witness);
+        self.opened_values().write_witness(witness);
+        self.opening_proof().write_witness(witness);
+    }
+}
+
+/// The witness of a circuit wrapping the verification of a `uni-stark` proof.
+///
+/// The public inputs are the commitment to the preprocessed trace, if any, followed by the public
+/// values of the proof, so that the verifier of the wrapping proof checks them against the ones it
+/// expects. The constraints of the AIR are fixed by the circuit. The rest of the proof is the
+/// secret witness, written by `Bn254Witness`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WrapperWitness {
+    pub public: Vec<Bn254Fr>,
+    pub secret: Vec<Bn254Fr>,
+}
+
+impl WrapperWitness {
+    pub fn new<SC>(vk: &VerifyingKey<SC>, proof: &Proof<SC>, public_values: &[Val<SC>]) -> Self
+    where
+        SC: StarkGenericConfig,
+        Val<SC>: Bn254Witness,
+        Proof<SC>: Bn254Witness,
+        <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::Commitment: Bn254Witness,
+    {
+        let mut public = Vec::new();
+        if let Some(preprocessed) = vk.preprocessed() {
+            preprocessed.commitment().write_witness(&mut public);
+        }
+        public_values.write_witness(&mut public);
+        let mut secret = Vec::new();
+        proof.write_witness(&mut secret);
+        Self { public, secret }
+    }
+
+    /// Encodes the witness as gnark's `witness.MarshalBinary` does: the numbers of public and
+    /// secret elements and the total, as big-endian `u32`s, followed by each element, public
+    /// inputs first, as 32 big-endian bytes.
+    pub fn to_gnark_bytes(&self) -> Vec<u8> {
+        let len = |n: usize| u32::try_from(n).expect("witness too long");
+        let mut bytes = Vec::with_capacity(12 + 32 * (self.public.len() + self.secret.len()));
+        bytes.extend(len(self.public.len()).to_be_bytes());
+        bytes.extend(len(self.secret.len()).to_be_bytes());
+        bytes.extend(len(self.public.len() + self.secret.len()).to_be_bytes());
+        for element in self.public.iter().chain(&self.secret) {
+            bytes.extend(to_bytes_be(element));
+        }
+        bytes
+    }
+
+    /// Encodes the witness as arkworks' `CanonicalSerialize` does for the vector of all its
+    /// elements, public inputs first: the number of elements as a little-endian `u64`, followed by
+    /// each element as 32 little-endian bytes.
+    pub fn to_arkworks_bytes(&self) -> Vec<u8> {
+        let len = self.public.len() + self.secret.len();
+        let mut bytes = Vec::with_capacity(8 + 32 * len);
+        bytes.extend((len as u64).to_le_bytes());
+        for element in self.public.iter().chain(&self.secret) {
+            let mut element_bytes = to_bytes_be(element);
+            element_bytes.reverse();
+            bytes.extend(element_bytes);
+        }
+        bytes
+    }
+}
+
+/// The canonical form of `element` as 32 big-endian bytes.
+fn to_bytes_be(element: &Bn254Fr) -> [u8; 32] {
+    let digits = element.as_canonical_biguint().to_bytes_be();
+    let mut bytes = [0; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
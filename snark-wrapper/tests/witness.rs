@@ -0,0 +1,83 @@
+use p3_baby_bear::BabyBear;
+use p3_bn254_fr::Bn254Fr;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_fri::create_test_fri_config;
+use p3_snark_wrapper::{
+    outer_challenge_mmcs, outer_challenger, outer_config, OuterConfig, OuterPerm, WrapperWitness,
+};
+use p3_uni_stark::testing::{fibonacci_trace, FibonacciAir};
+use p3_uni_stark::{prove_with_key, setup_keys, verify_with_key, Proof, VerifyingKey};
+use rand::thread_rng;
+
+type Val = BabyBear;
+
+fn setup() -> (OuterConfig, OuterPerm) {
+    let perm = OuterPerm::new_from_rng(8, 22, &mut thread_rng());
+    let fri_config = create_test_fri_config(outer_challenge_mmcs(perm.clone()));
+    (outer_config(perm.clone(), fri_config), perm)
+}
+
+fn prove_fibonacci(
+    config: &OuterConfig,
+    perm: &OuterPerm,
+    a: u32,
+    b: u32,
+) -> (VerifyingKey<OuterConfig>, Proof<OuterConfig>, Vec<Val>) {
+    let (trace, pis) = fibonacci_trace(Val::from_canonical_u32(a), Val::from_canonical_u32(b), 8);
+    let (pk, vk) = setup_keys(config, &FibonacciAir, pis.len());
+    let mut challenger = outer_challenger(perm.clone());
+    let proof = prove_with_key(config, &pk, &FibonacciAir, &mut challenger, trace, &pis);
+    let mut challenger = outer_challenger(perm.clone());
+    verify_with_key(config, &vk, &mut challenger, &proof, &pis).expect("verification failed");
+    (vk, proof, pis)
+}
+
+#[test]
+fn test_wrapper_witness() {
+    let (config, perm) = setup();
+    let (vk, proof, pis) = prove_fibonacci(&config, &perm, 0, 1);
+    let witness = WrapperWitness::new(&vk, &proof, &pis);
+
+    let expected_public: Vec<_> = pis
+        .iter()
+        .map(|x| Bn254Fr::from_canonical_u32(x.as_canonical_u32()))
+        .collect();
+    assert_eq!(witness.public, expected_public);
+    assert_eq!(witness.secret[0], Bn254Fr::from_canonical_usize(3));
+    assert_eq!(witness.secret[1], proof.commitments().trace().as_ref()[0]);
+
+    // The shape of the witness only depends on the AIR and the FRI parameters.
+    let (vk, proof, pis) = prove_fibonacci(&config, &perm, 2, 7);
+    let other_witness = WrapperWitness::new(&vk, &proof, &pis);
+    assert_eq!(other_witness.public.len(), witness.public.len());
+    assert_eq!(other_witness.secret.len(), witness.secret.len());
+    assert_ne!(other_witness, witness);
+}
+
+#[test]
+fn test_wrapper_witness_encodings() {
+    let (config, perm) = setup();
+    let (vk, proof, pis) = prove_fibonacci(&config, &perm, 1, 1);
+    let witness = WrapperWitness::new(&vk, &proof, &pis);
+    let (num_public, num_secret) = (witness.public.len(), witness.secret.len());
+    let total = num_public + num_secret;
+
+    let gnark = witness.to_gnark_bytes();
+    assert_eq!(gnark.len(), 12 + 32 * total);
+    assert_eq!(gnark[..4], (num_public as u32).to_be_bytes());
+    assert_eq!(gnark[4..8], (num_secret as u32).to_be_bytes());
+    assert_eq!(gnark[8..12], (total as u32).to_be_bytes());
+    // The first public value is 1.
+    let mut one = [0; 32];
+    one[31] = 1;
+    assert_eq!(gnark[12..44], one);
+
+    let arkworks = witness.to_arkworks_bytes();
+    assert_eq!(arkworks.len(), 8 + 32 * total);
+    assert_eq!(arkworks[..8], (total as u64).to_le_bytes());
+    one.reverse();
+    assert_eq!(arkworks[8..40], one);
+    for (gnark_element, arkworks_element) in gnark[12..].chunks(32).zip(arkworks[8..].chunks(32)) {
+        assert!(gnark_element.iter().eq(arkworks_element.iter().rev()));
+    }
+}
@@ -0,0 +1,68 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Hash;
+
+/// The top layer of a Merkle tree, used as a commitment in place of a single root.
+///
+/// A cap of height `h` consists of (at most) `2^h` digests. Committing to a cap rather than a root
+/// shortens every opening proof by `h` sibling digests, at the cost of a larger commitment.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(serialize = "[W; DIGEST_ELEMS]: Serialize"))]
+#[serde(bound(deserialize = "[W; DIGEST_ELEMS]: Deserialize<'de>"))]
+pub struct MerkleCap<F, W, const DIGEST_ELEMS: usize> {
+    cap: Vec<[W; DIGEST_ELEMS]>,
+    _marker: PhantomData<F>,
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> MerkleCap<F, W, DIGEST_ELEMS> {
+    pub const fn new(cap: Vec<[W; DIGEST_ELEMS]>) -> Self {
+        Self {
+            cap,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cap.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&[W; DIGEST_ELEMS]> {
+        self.cap.get(index)
+    }
+
+    pub fn as_slice(&self) -> &[[W; DIGEST_ELEMS]] {
+        &self.cap
+    }
+
+    /// Iterate over the digests of the cap, each wrapped as a `Hash`.
+    pub fn hashes(&self) -> impl Iterator<Item = Hash<F, W, DIGEST_ELEMS>> + '_
+    where
+        W: Copy,
+    {
+        self.cap.iter().map(|&digest| digest.into())
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> From<Vec<[W; DIGEST_ELEMS]>>
+    for MerkleCap<F, W, DIGEST_ELEMS>
+{
+    fn from(cap: Vec<[W; DIGEST_ELEMS]>) -> Self {
+        Self::new(cap)
+    }
+}
+
+impl<F, W, const DIGEST_ELEMS: usize> IntoIterator for MerkleCap<F, W, DIGEST_ELEMS> {
+    type Item = [W; DIGEST_ELEMS];
+    type IntoIter = alloc::vec::IntoIter<[W; DIGEST_ELEMS]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cap.into_iter()
+    }
+}
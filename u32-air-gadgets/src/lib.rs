@@ -0,0 +1,16 @@
+//! Constraints and trace generation for 32 bit words, shared by AIRs for hash functions.
+//!
+//! Words are saved either as `32` boolean values or as `2, 16` bit limbs. Bitwise operations are
+//! evaluated on bits and sums on limbs, so the gadgets here convert between the two. Assumes the
+//! field size is at least 2^20.
+
+#![no_std]
+
+mod sum;
+mod word;
+
+pub use sum::*;
+pub use word::*;
+
+pub const BITS_PER_LIMB: usize = 16;
+pub const U32_LIMBS: usize = 32 / BITS_PER_LIMB;
@@ -0,0 +1,83 @@
+use core::array;
+
+use p3_air::utils::{pack_bits_le, u32_to_bits_le};
+use p3_air::AirBuilder;
+use p3_field::{Field, FieldAlgebra};
+
+use crate::{pack_limbs, BITS_PER_LIMB, U32_LIMBS};
+
+/// Columns for a sum of words mod `2^32`, with room for the carries of up to `2^CARRY_BITS`
+/// summands.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct U32Sum<T, const CARRY_BITS: usize> {
+    /// The little-endian bits of the sum.
+    pub bits: [T; 32],
+
+    /// The little-endian bits of the carry out of each limb.
+    pub carries: [[T; CARRY_BITS]; U32_LIMBS],
+}
+
+/// Verify that `sum` is `summands[0] + ... + summands[n - 1] mod 2^32`.
+///
+/// Each summand is given as `2, 16` bit limbs, which we assume have been range checked. The bits of
+/// `sum` and of its carries are range checked here. We check, over the field,
+///
+/// (1) `low_sum = sum[0] + 2^16 carry[0]`,
+/// (2) `high_sum + carry[0] = sum[1] + 2^16 carry[1]`,
+///
+/// where `low_sum` and `high_sum` are the sums of the low and high limbs of the summands. Both sides
+/// of each lie in `[0, 2^(16 + CARRY_BITS))`, so as long as the characteristic is larger than that,
+/// they hold over the integers, and hence `sum` is the sum of the summands mod `2^32`.
+///
+/// The constraints have the degree of the summands.
+pub fn eval_sum<AB: AirBuilder, const CARRY_BITS: usize>(
+    builder: &mut AB,
+    sum: &U32Sum<AB::Var, CARRY_BITS>,
+    summands: &[[AB::Expr; U32_LIMBS]],
+) {
+    debug_assert!(summands.len() <= 1 << CARRY_BITS);
+
+    sum.bits
+        .iter()
+        .chain(sum.carries.iter().flatten())
+        .for_each(|&bit| builder.assert_bool(bit));
+
+    let two_16 = AB::Expr::from_canonical_u32(1 << BITS_PER_LIMB);
+    let limbs = pack_limbs::<AB::Expr, _>(&sum.bits);
+    let carries = sum
+        .carries
+        .map(|bits| pack_bits_le::<AB::Expr, _, _>(bits.into_iter()));
+
+    let low_sum = summands.iter().map(|s| s[0].clone()).sum::<AB::Expr>();
+    builder.assert_eq(
+        low_sum,
+        limbs[0].clone() + two_16.clone() * carries[0].clone(),
+    );
+
+    let high_sum = summands.iter().map(|s| s[1].clone()).sum::<AB::Expr>() + carries[0].clone();
+    builder.assert_eq(high_sum, limbs[1].clone() + two_16 * carries[1].clone());
+}
+
+/// Fill in `sum` with the sum of `summands` mod `2^32`, which is returned.
+pub fn generate_sum<F: Field, const CARRY_BITS: usize>(
+    sum: &mut U32Sum<F, CARRY_BITS>,
+    summands: &[u32],
+) -> u32 {
+    debug_assert!(summands.len() <= 1 << CARRY_BITS);
+
+    let limb_sum = |limb: usize| {
+        summands
+            .iter()
+            .map(|&x| (x >> (limb * BITS_PER_LIMB)) & 0xFFFF)
+            .sum::<u32>()
+    };
+    let low_carry = limb_sum(0) >> BITS_PER_LIMB;
+    let high_carry = (limb_sum(1) + low_carry) >> BITS_PER_LIMB;
+    sum.carries =
+        [low_carry, high_carry].map(|carry| array::from_fn(|i| F::from_bool(carry >> i & 1 == 1)));
+
+    let value = summands.iter().fold(0, |acc: u32, &x| acc.wrapping_add(x));
+    sum.bits = u32_to_bits_le(value);
+    value
+}
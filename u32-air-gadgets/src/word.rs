@@ -0,0 +1,67 @@
+use core::array;
+
+use p3_air::utils::{pack_bits_le, xor};
+use p3_field::FieldAlgebra;
+
+use crate::{BITS_PER_LIMB, U32_LIMBS};
+
+/// Pack the little-endian bits of a word into `2, 16` bit limbs.
+#[inline]
+pub fn pack_limbs<FA, Var>(bits: &[Var; 32]) -> [FA; U32_LIMBS]
+where
+    FA: FieldAlgebra,
+    Var: Into<FA> + Clone,
+{
+    array::from_fn(|i| {
+        pack_bits_le(
+            bits[i * BITS_PER_LIMB..(i + 1) * BITS_PER_LIMB]
+                .iter()
+                .cloned(),
+        )
+    })
+}
+
+/// The `2, 16` bit limbs of a constant word.
+#[inline]
+pub fn u32_to_limbs<FA: FieldAlgebra>(val: u32) -> [FA; U32_LIMBS] {
+    [
+        FA::from_canonical_u32(val & 0xFFFF),
+        FA::from_canonical_u32(val >> BITS_PER_LIMB),
+    ]
+}
+
+/// The little-endian bits of `x.rotate_right(n)`, given those of `x`.
+#[inline]
+pub fn rotr<T: Clone>(bits: &[T; 32], n: usize) -> [T; 32] {
+    array::from_fn(|i| bits[(i + n) % 32].clone())
+}
+
+/// The little-endian bits of `x >> n`, given those of `x`.
+#[inline]
+pub fn shr<FA, Var>(bits: &[Var; 32], n: usize) -> [FA; 32]
+where
+    FA: FieldAlgebra,
+    Var: Into<FA> + Clone,
+{
+    array::from_fn(|i| {
+        if i + n < 32 {
+            bits[i + n].clone().into()
+        } else {
+            FA::ZERO
+        }
+    })
+}
+
+/// The bits of `x ^ y`, given the bits of `x` and `y`.
+///
+/// This has degree `2` in the inputs, so at most two of these may be chained in a constraint of
+/// degree `3`.
+#[inline]
+pub fn xor_words<FA, Var>(x: [FA; 32], y: &[Var; 32]) -> [FA; 32]
+where
+    FA: FieldAlgebra,
+    Var: Into<FA> + Clone,
+{
+    let mut y = y.iter();
+    x.map(|x| xor(x, y.next().unwrap().clone().into()))
+}
@@ -0,0 +1,49 @@
+use p3_baby_bear::BabyBear;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_u32_air_gadgets::{generate_sum, pack_limbs, U32Sum};
+use rand::random;
+
+type F = BabyBear;
+
+fn to_u32(bits: &[F]) -> u32 {
+    bits.iter()
+        .rev()
+        .fold(0, |acc, bit| (acc << 1) | bit.as_canonical_u32())
+}
+
+#[test]
+fn test_generate_sum() {
+    let summands: [u32; 7] = random();
+    let mut sum = U32Sum::<F, 3> {
+        bits: [F::ZERO; 32],
+        carries: [[F::ZERO; 3]; 2],
+    };
+    let value = generate_sum(&mut sum, &summands);
+    assert_eq!(
+        value,
+        summands.iter().fold(0, |acc, &x| acc.wrapping_add(x))
+    );
+    assert_eq!(to_u32(&sum.bits), value);
+
+    // The limb equations checked by `eval_sum` hold over the integers.
+    let limbs = pack_limbs::<F, _>(&sum.bits).map(|limb| limb.as_canonical_u32() as u64);
+    let carries = sum.carries.map(|bits| to_u32(&bits) as u64);
+    let limb_sum = |i: usize| -> u64 {
+        summands
+            .iter()
+            .map(|&x| ((x >> (16 * i)) & 0xFFFF) as u64)
+            .sum()
+    };
+    assert_eq!(limb_sum(0), limbs[0] + (carries[0] << 16));
+    assert_eq!(limb_sum(1) + carries[0], limbs[1] + (carries[1] << 16));
+}
+
+#[test]
+fn test_generate_sum_max_carry() {
+    let mut sum = U32Sum::<F, 1> {
+        bits: [F::ZERO; 32],
+        carries: [[F::ZERO; 1]; 2],
+    };
+    assert_eq!(generate_sum(&mut sum, &[u32::MAX, u32::MAX]), u32::MAX - 1);
+    assert_eq!(sum.carries, [[F::ONE]; 2]);
+}
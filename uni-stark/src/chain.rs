@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+use p3_air::Air;
+use p3_challenger::FieldChallenger;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use tracing::{info_span, instrument};
+
+use crate::{
+    prove, verify, PcsError, Proof, ProverConstraintFolder, ShapeError, StarkGenericConfig,
+    SymbolicAirBuilder, Val, VerificationError, VerifierConstraintFolder,
+};
+
+/// Prove a sequence of statements about `air`, each given by a trace and its public values, on a
+/// single challenger, so that each proof is bound to every statement and proof before it.
+///
+/// Before each proof, the challenger observes the proof's index in the chain, under a
+/// domain-separation tag, and the proof's
+/// transcript then continues from the challenger's state at the end of the previous proof. After
+/// each proof, a digest of the chain so far is sampled and observed, so that the next transcript
+/// is seeded from it. Returns the proofs, to be checked with `verify_chain`, and the digest of the
+/// whole chain, which is zero for an empty chain.
+///
+/// A chain may be extended by proving another chain on the same challenger, but its proofs are
+/// then indexed from zero again, so a verifier must split the chain the same way.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_chain<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    statements: impl IntoIterator<Item = (RowMajorMatrix<Val<SC>>, Vec<Val<SC>>)>,
+) -> (Vec<Proof<SC>>, SC::Challenge)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let mut digest = SC::Challenge::ZERO;
+    let proofs = statements
+        .into_iter()
+        .enumerate()
+        .map(|(index, (trace, public_values))| {
+            info_span!("prove link", index).in_scope(|| {
+                challenger.observe_labelled("chain link", Val::<SC>::from_canonical_usize(index));
+                let proof = prove(config, air, challenger, trace, &public_values);
+                digest = link_digest::<SC>(challenger);
+                proof
+            })
+        })
+        .collect();
+    (proofs, digest)
+}
+
+/// Verify a chain of proofs produced by `prove_chain`, given the public values of each proof in
+/// order, and return the digest of the whole chain.
+///
+/// Every proof is checked on the same challenger, so a proof is only accepted after the same
+/// statements and proofs as when it was proven.
+#[instrument(skip_all)]
+pub fn verify_chain<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proofs: &[Proof<SC>],
+    public_values: &[Vec<Val<SC>>],
+) -> Result<SC::Challenge, ChainVerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    if proofs.len() != public_values.len() {
+        return Err(ChainVerificationError {
+            index: proofs.len().min(public_values.len()),
+            error: VerificationError::InvalidProofShape(ShapeError::NumProofs),
+        });
+    }
+
+    let mut digest = SC::Challenge::ZERO;
+    for (index, (proof, public_values)) in proofs.iter().zip(public_values).enumerate() {
+        challenger.observe_labelled("chain link", Val::<SC>::from_canonical_usize(index));
+        verify(config, air, challenger, proof, public_values)
+            .map_err(|error| ChainVerificationError { index, error })?;
+        digest = link_digest::<SC>(challenger);
+    }
+    Ok(digest)
+}
+
+/// Sample the digest of the chain so far, and observe it to seed the next proof's transcript.
+fn link_digest<SC: StarkGenericConfig>(challenger: &mut SC::Challenger) -> SC::Challenge {
+    let digest: SC::Challenge = challenger.sample_ext_element_labelled("chain digest");
+    challenger.observe_ext_element(digest);
+    digest
+}
+
+/// The failure of the proof at `index` in a chain.
+#[derive(Debug)]
+pub struct ChainVerificationError<PcsErr, Challenge> {
+    pub index: usize,
+    pub error: VerificationError<PcsErr, Challenge>,
+}
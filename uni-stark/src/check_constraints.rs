@@ -1,37 +1,48 @@
 use alloc::vec::Vec;
 
-use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, PairBuilder, PeriodicAirBuilder};
 use p3_field::Field;
 use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::stack::VerticalPair;
 use p3_matrix::Matrix;
 use tracing::instrument;
 
 #[instrument(name = "check constraints", skip_all)]
-pub(crate) fn check_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>, public_values: &Vec<F>)
-where
+pub(crate) fn check_constraints<F, A>(
+    air: &A,
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    main: &RowMajorMatrix<F>,
+    public_values: &[F],
+) where
     F: Field,
     A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
 {
     let height = main.height();
+    let window_size = air.window_size();
+    let preprocessed_width = preprocessed.map_or(0, |preprocessed| preprocessed.width());
+    let periodic_columns = air.periodic_columns();
 
     (0..height).for_each(|i| {
-        let i_next = (i + 1) % height;
-
-        let local = main.row_slice(i);
-        let next = main.row_slice(i_next);
-        let main = VerticalPair::new(
-            RowMajorMatrixView::new_row(&*local),
-            RowMajorMatrixView::new_row(&*next),
-        );
+        let window = |mat: &RowMajorMatrix<F>| {
+            (0..window_size)
+                .flat_map(|offset| mat.row((i + offset) % height))
+                .collect::<Vec<_>>()
+        };
+        let main_window = window(main);
+        let preprocessed_window = preprocessed.map_or_else(Vec::new, window);
+        let periodic_values = periodic_columns
+            .iter()
+            .map(|column| column[i % column.len()])
+            .collect::<Vec<_>>();
 
         let mut builder = DebugConstraintBuilder {
             row_index: i,
-            main,
+            preprocessed: RowMajorMatrixView::new(&preprocessed_window, preprocessed_width),
+            main: RowMajorMatrixView::new(&main_window, main.width()),
             public_values,
+            periodic_values: &periodic_values,
             is_first_row: F::from_bool(i == 0),
             is_last_row: F::from_bool(i == height - 1),
-            is_transition: F::from_bool(i != height - 1),
+            height,
         };
 
         air.eval(&mut builder);
@@ -43,11 +54,13 @@ where
 #[derive(Debug)]
 pub struct DebugConstraintBuilder<'a, F: Field> {
     row_index: usize,
-    main: VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>,
+    preprocessed: RowMajorMatrixView<'a, F>,
+    main: RowMajorMatrixView<'a, F>,
     public_values: &'a [F],
+    periodic_values: &'a [F],
     is_first_row: F,
     is_last_row: F,
-    is_transition: F,
+    height: usize,
 }
 
 impl<'a, F> AirBuilder for DebugConstraintBuilder<'a, F>
@@ -57,7 +70,7 @@ where
     type F = F;
     type Expr = F;
     type Var = F;
-    type M = VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>;
+    type M = RowMajorMatrixView<'a, F>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -72,11 +85,12 @@ where
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("only supports a window size of 2")
-        }
+        assert!(
+            (2..=self.main.height()).contains(&size),
+            "transition windows must have between 2 and {} rows",
+            self.main.height()
+        );
+        F::from_bool(self.row_index + size <= self.height)
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -106,3 +120,17 @@ impl<F: Field> AirBuilderWithPublicValues for DebugConstraintBuilder<'_, F> {
         self.public_values
     }
 }
+
+impl<F: Field> PeriodicAirBuilder for DebugConstraintBuilder<'_, F> {
+    type PeriodicVar = Self::F;
+
+    fn periodic_values(&self) -> &[Self::F] {
+        self.periodic_values
+    }
+}
+
+impl<F: Field> PairBuilder for DebugConstraintBuilder<'_, F> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
@@ -0,0 +1,188 @@
+//! Checkpoints of the prover, from which an interrupted proof can be resumed.
+//!
+//! `prove_with_checkpoints` hands a `ProverCheckpoint` to a `ProverCheckpoints` store after each
+//! commitment stage, and asks the store for the latest checkpoint before it starts. Resuming
+//! skips the trace generation and every stage up to the checkpoint, including the LDEs and Merkle
+//! trees, which are restored from the prover data of the PCS.
+//!
+//! The opening stage, which includes the commit phase of FRI, runs inside `Pcs::open` and is not
+//! checkpointed; it ends with the finished proof.
+
+use alloc::vec::Vec;
+
+use p3_challenger::CanSnapshot;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::serialization::{decode, encode};
+use crate::{Com, PcsProverData, ProofDecodingError, StarkGenericConfig, Val};
+
+const CHECKPOINT_MAGIC: [u8; 4] = *b"P3CK";
+
+/// The last stage completed by the prover at a checkpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverStage {
+    /// The trace, and the permutation trace of an AIR with lookups, are committed.
+    TraceCommitted,
+    /// The quotient polynomial is committed.
+    QuotientCommitted,
+}
+
+/// The state of the prover after a commitment stage.
+///
+/// The transcript state is not part of this struct, since a challenger need not be serializable;
+/// stores save it alongside, e.g. with `to_bytes`.
+pub struct ProverCheckpoint<SC: StarkGenericConfig> {
+    pub degree_bits: usize,
+    /// The public values of the proof, which are checked when resuming.
+    pub public_values: Vec<Val<SC>>,
+    pub trace_commit: Com<SC>,
+    pub trace_data: PcsProverData<SC>,
+    pub permutation: Option<(Com<SC>, PcsProverData<SC>)>,
+    pub permutation_challenges: Vec<SC::Challenge>,
+    pub quotient: Option<(Com<SC>, PcsProverData<SC>)>,
+}
+
+impl<SC: StarkGenericConfig> ProverCheckpoint<SC> {
+    pub const fn stage(&self) -> ProverStage {
+        if self.quotient.is_some() {
+            ProverStage::QuotientCommitted
+        } else {
+            ProverStage::TraceCommitted
+        }
+    }
+}
+
+impl<SC> ProverCheckpoint<SC>
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: CanSnapshot,
+    PcsProverData<SC>: Serialize + DeserializeOwned,
+{
+    /// Encode this checkpoint together with the state of `challenger`, in the versioned binary
+    /// format of proofs.
+    pub fn to_bytes(&self, challenger: &SC::Challenger) -> Vec<u8> {
+        encode(
+            CHECKPOINT_MAGIC,
+            &(
+                self.degree_bits,
+                &self.public_values,
+                &self.trace_commit,
+                &self.trace_data,
+                &self.permutation,
+                &self.permutation_challenges,
+                &self.quotient,
+                challenger.snapshot(),
+            ),
+        )
+    }
+
+    /// Decode a checkpoint encoded by `to_bytes`, restoring its transcript state into
+    /// `challenger`, which must be built the same way as the one it was saved from.
+    pub fn from_bytes(
+        bytes: &[u8],
+        challenger: &mut SC::Challenger,
+    ) -> Result<Self, ProofDecodingError> {
+        let (
+            degree_bits,
+            public_values,
+            trace_commit,
+            trace_data,
+            permutation,
+            permutation_challenges,
+            quotient,
+            snapshot,
+        ) = decode(CHECKPOINT_MAGIC, bytes)?;
+        challenger.restore(snapshot);
+        Ok(Self {
+            degree_bits,
+            public_values,
+            trace_commit,
+            trace_data,
+            permutation,
+            permutation_challenges,
+            quotient,
+        })
+    }
+}
+
+/// Where the prover saves its checkpoints, and finds the one to resume from.
+///
+/// `()` is the store which saves nothing, used by the other `prove` functions.
+pub trait ProverCheckpoints<SC: StarkGenericConfig> {
+    /// The latest checkpoint saved, if any, after restoring its transcript state into
+    /// `challenger`.
+    fn latest(&mut self, challenger: &mut SC::Challenger) -> Option<ProverCheckpoint<SC>>;
+
+    /// Save `checkpoint`, with `challenger` in its state at that point, replacing any earlier
+    /// checkpoint.
+    fn save(&mut self, checkpoint: &ProverCheckpoint<SC>, challenger: &SC::Challenger);
+
+    /// Called once the proof is complete, after which the checkpoints can be discarded.
+    fn finish(&mut self) {}
+}
+
+impl<SC: StarkGenericConfig> ProverCheckpoints<SC> for () {
+    fn latest(&mut self, _challenger: &mut SC::Challenger) -> Option<ProverCheckpoint<SC>> {
+        None
+    }
+
+    fn save(&mut self, _checkpoint: &ProverCheckpoint<SC>, _challenger: &SC::Challenger) {}
+}
+
+/// A store keeping the latest checkpoint in a file, which is deleted once the proof is complete.
+///
+/// Each checkpoint is written to a temporary file first and then renamed, so a crash while saving
+/// leaves the previous checkpoint intact. The file belongs to a single proof: resuming with another
+/// trace silently proves the old one, as the trace is not regenerated.
+#[cfg(feature = "std")]
+pub struct FileCheckpoints {
+    path: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileCheckpoints {
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(feature = "std")]
+impl<SC> ProverCheckpoints<SC> for FileCheckpoints
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: CanSnapshot,
+    PcsProverData<SC>: Serialize + DeserializeOwned,
+{
+    fn latest(&mut self, challenger: &mut SC::Challenger) -> Option<ProverCheckpoint<SC>> {
+        let bytes = match std::fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(err) => panic!("failed to read checkpoint {}: {err}", self.path.display()),
+        };
+        let checkpoint = ProverCheckpoint::from_bytes(&bytes, challenger)
+            .unwrap_or_else(|err| panic!("invalid checkpoint {}: {err:?}", self.path.display()));
+        Some(checkpoint)
+    }
+
+    fn save(&mut self, checkpoint: &ProverCheckpoint<SC>, challenger: &SC::Challenger) {
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, checkpoint.to_bytes(challenger))
+            .and_then(|()| std::fs::rename(&tmp_path, &self.path))
+            .unwrap_or_else(|err| {
+                panic!("failed to save checkpoint {}: {err}", self.path.display())
+            });
+    }
+
+    fn finish(&mut self) {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => panic!("failed to remove checkpoint {}: {err}", self.path.display()),
+        }
+    }
+}
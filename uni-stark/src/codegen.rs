@@ -0,0 +1,140 @@
+//! Compilation of symbolic AIRs into straight-line Rust.
+//!
+//! Evaluating a `SymbolicAir` interprets its DAG of operations on every row. For large
+//! machine-generated AIRs it can be much faster to evaluate generated code instead, e.g. by
+//! generating it in a build script:
+//!
+//! ```ignore
+//! let air = SymbolicAir::<BabyBear>::new(&MyAir, 0, 0);
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(format!("{out_dir}/my_air.rs"), air.generate_rust("eval_my_air")).unwrap();
+//! ```
+//!
+//! and including it where the AIR is defined, with an `Air` implementation which calls it:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/my_air.rs"));
+//!
+//! impl<AB> Air<AB> for MyCompiledAir
+//! where
+//!     AB: AirBuilderWithPublicValues + PairBuilder + PeriodicAirBuilder,
+//! {
+//!     fn eval(&self, builder: &mut AB) {
+//!         eval_my_air(builder);
+//!     }
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+
+use p3_field::PrimeField64;
+
+use crate::keys::ConstraintNode;
+use crate::{Entry, SymbolicAir};
+
+impl<F: PrimeField64> SymbolicAir<F> {
+    /// Generate a Rust function named `fn_name`, which asserts the constraints of this AIR with
+    /// any builder, in the same order as evaluating this AIR does.
+    ///
+    /// The generated code refers to the `p3_air`, `p3_field` and `p3_matrix` crates, which must be
+    /// dependencies of the crate including it.
+    pub fn generate_rust(&self, fn_name: &str) -> String {
+        // The number of remaining uses of each node, so that values are moved on their last use
+        // rather than cloned.
+        let mut uses = vec![0; self.nodes.len()];
+        for node in &self.nodes {
+            match *node {
+                ConstraintNode::Add(x, y)
+                | ConstraintNode::Sub(x, y)
+                | ConstraintNode::Mul(x, y) => {
+                    uses[x] += 1;
+                    uses[y] += 1;
+                }
+                ConstraintNode::Neg(x) => uses[x] += 1,
+                _ => {}
+            }
+        }
+        for &constraint in &self.constraints {
+            uses[constraint] += 1;
+        }
+        let mut operand = |i: usize| {
+            uses[i] -= 1;
+            if uses[i] == 0 {
+                format!("v{i}")
+            } else {
+                format!("v{i}.clone()")
+            }
+        };
+
+        let uses_entry = |pred: fn(Entry) -> bool| {
+            self.nodes
+                .iter()
+                .any(|node| matches!(node, ConstraintNode::Variable { entry, .. } if pred(*entry)))
+        };
+
+        let mut code = String::new();
+        let mut line = |s: String| {
+            code.push_str(&s);
+            code.push('\n');
+        };
+        line("// Generated by `p3_uni_stark::SymbolicAir::generate_rust`.".into());
+        line(format!("pub fn {fn_name}<AB>(builder: &mut AB)"));
+        line("where".into());
+        line(
+            "    AB: p3_air::AirBuilderWithPublicValues + p3_air::PairBuilder + p3_air::PeriodicAirBuilder,"
+                .into(),
+        );
+        line("{".into());
+        if uses_entry(|entry| matches!(entry, Entry::Main { .. })) {
+            line("    let main = p3_air::AirBuilder::main(builder);".into());
+        }
+        if uses_entry(|entry| matches!(entry, Entry::Preprocessed { .. })) {
+            line("    let preprocessed = p3_air::PairBuilder::preprocessed(builder);".into());
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            let value = match *node {
+                ConstraintNode::Variable { entry, index } => match entry {
+                    Entry::Preprocessed { offset } => {
+                        format!("p3_matrix::Matrix::get(&preprocessed, {offset}, {index}).into()")
+                    }
+                    Entry::Main { offset } => {
+                        format!("p3_matrix::Matrix::get(&main, {offset}, {index}).into()")
+                    }
+                    Entry::Periodic => format!(
+                        "p3_air::PeriodicAirBuilder::periodic_values(builder)[{index}].into()"
+                    ),
+                    Entry::Public => format!(
+                        "p3_air::AirBuilderWithPublicValues::public_values(builder)[{index}].into()"
+                    ),
+                    Entry::Permutation { .. } | Entry::Challenge => {
+                        unreachable!("symbolic AIRs have no lookups")
+                    }
+                },
+                ConstraintNode::IsFirstRow => "p3_air::AirBuilder::is_first_row(builder)".into(),
+                ConstraintNode::IsLastRow => "p3_air::AirBuilder::is_last_row(builder)".into(),
+                ConstraintNode::IsTransitionWindow(size) => {
+                    format!("p3_air::AirBuilder::is_transition_window(builder, {size})")
+                }
+                ConstraintNode::Constant(c) => format!(
+                    "<AB::Expr as p3_field::FieldAlgebra>::from_canonical_u64({})",
+                    c.as_canonical_u64()
+                ),
+                ConstraintNode::Add(x, y) => format!("{} + {}", operand(x), operand(y)),
+                ConstraintNode::Sub(x, y) => format!("{} - {}", operand(x), operand(y)),
+                ConstraintNode::Neg(x) => format!("-{}", operand(x)),
+                ConstraintNode::Mul(x, y) => format!("{} * {}", operand(x), operand(y)),
+            };
+            line(format!("    let v{i}: AB::Expr = {value};"));
+        }
+        for &constraint in &self.constraints {
+            line(format!(
+                "    p3_air::AirBuilder::assert_zero(builder, {});",
+                operand(constraint)
+            ));
+        }
+        line("}".into());
+        code
+    }
+}
@@ -1,8 +1,11 @@
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
+use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
+use p3_commit::{HidingPcs, Pcs, PolynomialSpace};
 use p3_field::{ExtensionField, Field};
+use p3_matrix::dense::RowMajorMatrix;
 
 pub type PcsError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
@@ -21,6 +24,57 @@ pub type PackedVal<SC> = <Val<SC> as Field>::Packing;
 pub type PackedChallenge<SC> =
     <<SC as StarkGenericConfig>::Challenge as ExtensionField<Val<SC>>>::ExtensionPacking;
 
+/// The default for `StarkGenericConfig::quotient_chunk_size`.
+pub const DEFAULT_QUOTIENT_CHUNK_SIZE: usize = 256;
+
+/// How the constraints of an AIR are combined into the single polynomial whose quotient is
+/// committed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConstraintBatching {
+    /// Combine the constraints with the powers of a single random challenge. The probability that
+    /// an unsatisfied constraint goes unnoticed grows with the number of constraints.
+    #[default]
+    Powers,
+    /// Split the constraints into groups of `group_size` consecutive constraints, each combined
+    /// with the powers of its own random challenge. The probability that an unsatisfied constraint
+    /// goes unnoticed then only grows with the group size, which matters for AIRs with many
+    /// constraints over small challenge fields.
+    Groups { group_size: usize },
+}
+
+impl ConstraintBatching {
+    /// The number of challenges needed to combine `constraint_count` constraints.
+    pub fn num_challenges(&self, constraint_count: usize) -> usize {
+        match *self {
+            Self::Powers => 1,
+            Self::Groups { group_size } => {
+                assert!(group_size > 0, "constraint groups must not be empty");
+                constraint_count.div_ceil(group_size).max(1)
+            }
+        }
+    }
+
+    /// The coefficient of each of `constraint_count` constraints in their combination, given at
+    /// least `num_challenges(constraint_count)` challenges.
+    pub fn coefficients<EF: Field>(&self, challenges: &[EF], constraint_count: usize) -> Vec<EF> {
+        match *self {
+            Self::Powers => {
+                let mut powers = challenges[0]
+                    .powers()
+                    .take(constraint_count)
+                    .collect::<Vec<_>>();
+                powers.reverse();
+                powers
+            }
+            Self::Groups { group_size } => challenges
+                .iter()
+                .flat_map(|challenge| challenge.powers().take(group_size))
+                .take(constraint_count)
+                .collect(),
+        }
+    }
+}
+
 pub trait StarkGenericConfig {
     /// The PCS used to commit to trace polynomials.
     type Pcs: Pcs<Self::Challenge, Self::Challenger>;
@@ -34,11 +88,97 @@ pub trait StarkGenericConfig {
         + CanSample<Self::Challenge>;
 
     fn pcs(&self) -> &Self::Pcs;
+
+    /// The number of quotient domain points on which each parallel task of the prover evaluates
+    /// the constraints, rounded up to a multiple of the packing width.
+    ///
+    /// Larger chunks amortize the per-task overhead, while smaller chunks balance the load better
+    /// and keep each task's rows in cache.
+    fn quotient_chunk_size(&self) -> usize {
+        DEFAULT_QUOTIENT_CHUNK_SIZE
+    }
+
+    /// How the constraints are combined into the quotient.
+    fn constraint_batching(&self) -> ConstraintBatching {
+        ConstraintBatching::Powers
+    }
+
+    /// The log2 of the number of chunks to split every quotient into, rather than the fewest that
+    /// the degree of the AIR's constraints allows, e.g. to give proofs of different AIRs the same
+    /// shape. It must be no smaller than the fewest. It may exceed the PCS blowup, in which case the
+    /// PCS extrapolates the traces to the quotient domain.
+    fn fixed_log_quotient_degree(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the prover masks its traces, so that proofs reveal nothing about the witness.
+    ///
+    /// This only hides the trace values opened by the STARK itself. The PCS must also be hiding,
+    /// e.g. a `HidingFriPcs` over a `MerkleTreeHidingMmcs`, which salts Merkle leaves, and the
+    /// traces and quotient chunks must be masked by `commit_traces` and `commit_quotients`, as
+    /// `ZkStarkConfig` does.
+    fn is_zk(&self) -> bool {
+        false
+    }
+
+    /// Commit to the main or permutation traces `traces`, each given by its trace domain and its
+    /// evaluations over that domain.
+    ///
+    /// In zero-knowledge mode, the traces are masked by `HidingPcs::commit_masked`, and so committed over
+    /// domains of twice the size.
+    #[allow(clippy::type_complexity)]
+    fn commit_traces(
+        &self,
+        traces: Vec<(Domain<Self>, RowMajorMatrix<Val<Self>>)>,
+    ) -> (
+        <Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::Commitment,
+        <Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::ProverData,
+    ) {
+        self.pcs().commit(traces)
+    }
+
+    /// Commit to the chunks of each of `quotients`, given by its domain, its evaluations over that
+    /// domain, and its number of chunks.
+    ///
+    /// In zero-knowledge mode, the chunks are masked so that their openings reveal nothing beyond
+    /// the value of the quotient, as by `HidingPcs::commit_quotients`.
+    #[allow(clippy::type_complexity)]
+    fn commit_quotients(
+        &self,
+        quotients: Vec<(Domain<Self>, RowMajorMatrix<Val<Self>>, usize)>,
+    ) -> (
+        <Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::Commitment,
+        <Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::ProverData,
+    ) {
+        let chunks = quotients
+            .into_iter()
+            .flat_map(|(quotient_domain, evals, num_chunks)| {
+                izip!(
+                    quotient_domain.split_domains(num_chunks),
+                    quotient_domain.split_evals(num_chunks, evals)
+                )
+            })
+            .collect_vec();
+        self.pcs().commit(chunks)
+    }
+
+    /// The domains over which `commit_quotients` commits the chunks of a quotient over
+    /// `quotient_domain`.
+    fn committed_quotient_chunk_domains(
+        &self,
+        quotient_domain: Domain<Self>,
+        num_chunks: usize,
+    ) -> Vec<Domain<Self>> {
+        quotient_domain.split_domains(num_chunks)
+    }
 }
 
 #[derive(Debug)]
 pub struct StarkConfig<Pcs, Challenge, Challenger> {
     pcs: Pcs,
+    quotient_chunk_size: usize,
+    constraint_batching: ConstraintBatching,
+    fixed_log_quotient_degree: Option<usize>,
     _phantom: PhantomData<(Challenge, Challenger)>,
 }
 
@@ -46,9 +186,36 @@ impl<Pcs, Challenge, Challenger> StarkConfig<Pcs, Challenge, Challenger> {
     pub const fn new(pcs: Pcs) -> Self {
         Self {
             pcs,
+            quotient_chunk_size: DEFAULT_QUOTIENT_CHUNK_SIZE,
+            constraint_batching: ConstraintBatching::Powers,
+            fixed_log_quotient_degree: None,
             _phantom: PhantomData,
         }
     }
+
+    /// Set the number of quotient domain points each parallel task of the prover evaluates the
+    /// constraints on. See `StarkGenericConfig::quotient_chunk_size`.
+    #[must_use]
+    pub fn with_quotient_chunk_size(mut self, quotient_chunk_size: usize) -> Self {
+        self.quotient_chunk_size = quotient_chunk_size;
+        self
+    }
+
+    /// Set how the constraints are combined into the quotient. See
+    /// `StarkGenericConfig::constraint_batching`.
+    #[must_use]
+    pub fn with_constraint_batching(mut self, constraint_batching: ConstraintBatching) -> Self {
+        self.constraint_batching = constraint_batching;
+        self
+    }
+
+    /// Split every quotient into `2^log_quotient_degree` chunks. See
+    /// `StarkGenericConfig::fixed_log_quotient_degree`.
+    #[must_use]
+    pub fn with_fixed_log_quotient_degree(mut self, log_quotient_degree: usize) -> Self {
+        self.fixed_log_quotient_degree = Some(log_quotient_degree);
+        self
+    }
 }
 
 impl<Pcs, Challenge, Challenger> StarkGenericConfig for StarkConfig<Pcs, Challenge, Challenger>
@@ -66,4 +233,125 @@ where
     fn pcs(&self) -> &Self::Pcs {
         &self.pcs
     }
+
+    fn quotient_chunk_size(&self) -> usize {
+        self.quotient_chunk_size
+    }
+
+    fn constraint_batching(&self) -> ConstraintBatching {
+        self.constraint_batching
+    }
+
+    fn fixed_log_quotient_degree(&self) -> Option<usize> {
+        self.fixed_log_quotient_degree
+    }
+}
+
+/// Like `StarkConfig`, but proofs are zero-knowledge.
+///
+/// The main and permutation traces are masked by `HidingPcs::commit_masked`, which interleaves them with
+/// random rows over a domain of twice their height, so the quotient has twice the degree, and twice
+/// as many chunks. The chunks are masked by `HidingPcs::commit_quotients`. Both draw their
+/// randomness from the PCS, which is why it must be hiding, and proofs are reproducible if its
+/// randomness, and that of its MMCS, is forked from a seeded `ProverRng`.
+#[derive(Debug)]
+pub struct ZkStarkConfig<Pcs, Challenge, Challenger> {
+    pcs: Pcs,
+    quotient_chunk_size: usize,
+    constraint_batching: ConstraintBatching,
+    fixed_log_quotient_degree: Option<usize>,
+    _phantom: PhantomData<(Challenge, Challenger)>,
+}
+
+impl<Pcs, Challenge, Challenger> ZkStarkConfig<Pcs, Challenge, Challenger> {
+    pub const fn new(pcs: Pcs) -> Self {
+        Self {
+            pcs,
+            quotient_chunk_size: DEFAULT_QUOTIENT_CHUNK_SIZE,
+            constraint_batching: ConstraintBatching::Powers,
+            fixed_log_quotient_degree: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set the number of quotient domain points each parallel task of the prover evaluates the
+    /// constraints on. See `StarkGenericConfig::quotient_chunk_size`.
+    #[must_use]
+    pub fn with_quotient_chunk_size(mut self, quotient_chunk_size: usize) -> Self {
+        self.quotient_chunk_size = quotient_chunk_size;
+        self
+    }
+
+    /// Set how the constraints are combined into the quotient. See
+    /// `StarkGenericConfig::constraint_batching`.
+    #[must_use]
+    pub fn with_constraint_batching(mut self, constraint_batching: ConstraintBatching) -> Self {
+        self.constraint_batching = constraint_batching;
+        self
+    }
+
+    /// Split every quotient into `2^log_quotient_degree` chunks. See
+    /// `StarkGenericConfig::fixed_log_quotient_degree`.
+    #[must_use]
+    pub fn with_fixed_log_quotient_degree(mut self, log_quotient_degree: usize) -> Self {
+        self.fixed_log_quotient_degree = Some(log_quotient_degree);
+        self
+    }
+}
+
+impl<Pcs, Challenge, Challenger> StarkGenericConfig for ZkStarkConfig<Pcs, Challenge, Challenger>
+where
+    Challenge: ExtensionField<<Pcs::Domain as PolynomialSpace>::Val>,
+    Pcs: HidingPcs<Challenge, Challenger>,
+    Challenger: FieldChallenger<<Pcs::Domain as PolynomialSpace>::Val>
+        + CanObserve<<Pcs as p3_commit::Pcs<Challenge, Challenger>>::Commitment>
+        + CanSample<Challenge>,
+{
+    type Pcs = Pcs;
+    type Challenge = Challenge;
+    type Challenger = Challenger;
+
+    fn pcs(&self) -> &Self::Pcs {
+        &self.pcs
+    }
+
+    fn quotient_chunk_size(&self) -> usize {
+        self.quotient_chunk_size
+    }
+
+    fn constraint_batching(&self) -> ConstraintBatching {
+        self.constraint_batching
+    }
+
+    fn fixed_log_quotient_degree(&self) -> Option<usize> {
+        self.fixed_log_quotient_degree
+    }
+
+    fn is_zk(&self) -> bool {
+        true
+    }
+
+    fn commit_traces(
+        &self,
+        traces: Vec<(Domain<Self>, RowMajorMatrix<Val<Self>>)>,
+    ) -> (Pcs::Commitment, Pcs::ProverData) {
+        let (commit, data, _) = self.pcs.commit_masked(traces);
+        (commit, data)
+    }
+
+    fn commit_quotients(
+        &self,
+        quotients: Vec<(Domain<Self>, RowMajorMatrix<Val<Self>>, usize)>,
+    ) -> (Pcs::Commitment, Pcs::ProverData) {
+        self.pcs.commit_quotients(quotients)
+    }
+
+    fn committed_quotient_chunk_domains(
+        &self,
+        quotient_domain: Domain<Self>,
+        num_chunks: usize,
+    ) -> Vec<Domain<Self>> {
+        self.pcs
+            .masked_quotient_domains(quotient_domain, num_chunks)
+    }
 }
@@ -0,0 +1,364 @@
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::panic::Location;
+
+use hashbrown::HashMap;
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, Interaction, InteractionKind, PairBuilder,
+    PeriodicAirBuilder,
+};
+use p3_field::Field;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
+use p3_matrix::tracked::TrackedMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::{get_symbolic_constraints, Entry, SymbolicAirBuilder, SymbolicExpression};
+
+/// A constraint which is nonzero on some row of a trace.
+#[derive(Clone, Debug)]
+pub struct ConstraintFailure<F> {
+    /// The index of the row on which the constraint failed.
+    pub row: usize,
+    /// The index of the constraint, in the order the AIR asserts its constraints.
+    pub constraint: usize,
+    /// The value of the constraint on the row.
+    pub value: F,
+    /// The constraint, as an expression over the columns of the AIR.
+    pub expression: SymbolicExpression<F>,
+}
+
+impl<F: Field> Display for ConstraintFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "constraint {} has value {} on row {}: {}",
+            self.constraint, self.value, self.row, self.expression
+        )
+    }
+}
+
+/// Evaluates every constraint of `air` on every row of `trace`, and returns those which are nonzero.
+///
+/// Unlike the constraint check made by the prover in debug builds, this does not stop at the first
+/// failure, and describes each failure in terms of the AIR's columns, which makes it useful while
+/// writing a trace generator. It is available in release builds.
+#[instrument(name = "debug constraints", skip_all)]
+pub fn debug_constraints<F, A>(
+    air: &A,
+    trace: &RowMajorMatrix<F>,
+    public_values: &[F],
+) -> Result<(), Vec<ConstraintFailure<F>>>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>> + for<'a> Air<ConstraintEvaluator<'a, F>>,
+{
+    let preprocessed = air.preprocessed_trace();
+    let preprocessed_width = preprocessed.as_ref().map_or(0, |p| p.width());
+    let expressions = get_symbolic_constraints(air, preprocessed_width, public_values.len());
+
+    let height = trace.height();
+    let window_size = air.window_size();
+    let periodic_columns = air.periodic_columns();
+    let mut failures = Vec::new();
+    for i in 0..height {
+        let window = |mat: &RowMajorMatrix<F>| {
+            (0..window_size)
+                .flat_map(|offset| mat.row((i + offset) % height))
+                .collect::<Vec<_>>()
+        };
+        let main_window = window(trace);
+        let preprocessed_window = preprocessed.as_ref().map_or_else(Vec::new, window);
+        let periodic_values = periodic_columns
+            .iter()
+            .map(|column| column[i % column.len()])
+            .collect::<Vec<_>>();
+
+        let mut builder = ConstraintEvaluator {
+            row_index: i,
+            preprocessed: RowMajorMatrixView::new(&preprocessed_window, preprocessed_width),
+            main: RowMajorMatrixView::new(&main_window, trace.width()),
+            public_values,
+            periodic_values: &periodic_values,
+            is_first_row: F::from_bool(i == 0),
+            is_last_row: F::from_bool(i == height - 1),
+            height,
+            values: Vec::with_capacity(expressions.len()),
+        };
+        air.eval(&mut builder);
+
+        debug_assert_eq!(builder.values.len(), expressions.len());
+        failures.extend(
+            builder
+                .values
+                .into_iter()
+                .zip(&expressions)
+                .enumerate()
+                .filter(|(_, (value, _))| !value.is_zero())
+                .map(|(constraint, (value, expression))| ConstraintFailure {
+                    row: i,
+                    constraint,
+                    value,
+                    expression: expression.clone(),
+                }),
+        );
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(failures)
+    }
+}
+
+/// A cell of the main trace, and the location of the code which last wrote it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CellProvenance {
+    pub row: usize,
+    pub column: usize,
+    /// `None` if the cell was never written.
+    pub writer: Option<&'static Location<'static>>,
+}
+
+impl Display for CellProvenance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "column {} of row {} ", self.column, self.row)?;
+        match self.writer {
+            Some(writer) => write!(f, "was written at {writer}"),
+            None => write!(f, "was never written"),
+        }
+    }
+}
+
+/// A failed constraint, together with the provenance of the cells of the main trace it reads.
+#[derive(Clone, Debug)]
+pub struct TracedFailure<F> {
+    pub failure: ConstraintFailure<F>,
+    /// The cells read by the constraint on the failing row, by offset and then column.
+    pub cells: Vec<CellProvenance>,
+}
+
+impl<F: Field> Display for TracedFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.failure)?;
+        for cell in &self.cells {
+            write!(f, "\n  {cell}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The problems found in a `TrackedMatrix` trace by `debug_tracked_constraints`.
+#[derive(Clone, Debug)]
+pub struct ProvenanceReport<F> {
+    /// The constraints which are nonzero on some row.
+    pub failures: Vec<TracedFailure<F>>,
+    /// The `(row, column)` of each cell of the trace which was never written.
+    pub unwritten: Vec<(usize, usize)>,
+}
+
+/// Like `debug_constraints`, for a trace whose writes were recorded in a `TrackedMatrix`.
+///
+/// Each failed constraint comes with the locations which wrote the cells it reads, so that it can
+/// be traced back to the trace generator. Since a cell left at its initial value may well satisfy
+/// every constraint, cells which were never written are also reported, even if no constraint
+/// fails.
+pub fn debug_tracked_constraints<F, A>(
+    air: &A,
+    trace: &TrackedMatrix<F>,
+    public_values: &[F],
+) -> Result<(), ProvenanceReport<F>>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>> + for<'a> Air<ConstraintEvaluator<'a, F>>,
+{
+    let height = trace.height();
+    let failures: Vec<_> = debug_constraints(air, trace.values(), public_values)
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|failure| {
+            let cells = failure
+                .expression
+                .variables()
+                .into_iter()
+                .filter_map(|(entry, column)| match entry {
+                    Entry::Main { offset } => {
+                        let row = (failure.row + offset) % height;
+                        Some(CellProvenance {
+                            row,
+                            column,
+                            writer: trace.writer(row, column),
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+            TracedFailure { failure, cells }
+        })
+        .collect();
+    let unwritten: Vec<_> = trace.unwritten_cells().collect();
+
+    if failures.is_empty() && unwritten.is_empty() {
+        Ok(())
+    } else {
+        Err(ProvenanceReport {
+            failures,
+            unwritten,
+        })
+    }
+}
+
+/// A tuple which is sent on a bus a different number of times than it is received.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InteractionImbalance<F> {
+    pub bus: usize,
+    pub values: Vec<F>,
+    /// The multiplicity with which the tuple is sent, minus that with which it is received.
+    pub net_multiplicity: F,
+}
+
+impl<F: Field> Display for InteractionImbalance<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "tuple (")?;
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{value}")?;
+        }
+        write!(
+            f,
+            ") on bus {} is sent {} more times than it is received",
+            self.bus, self.net_multiplicity
+        )
+    }
+}
+
+/// Evaluates the interactions of AIRs proven together on their traces, and returns the tuples
+/// whose sends and receives don't balance over all of the traces, in the order they first occur.
+///
+/// Each trace is given with the interactions of its AIR and its preprocessed trace, if any. Where
+/// the prover only notices that the LogUp sum is nonzero, this names the offending tuples, which
+/// makes it useful while writing the trace generators of AIRs which communicate over buses.
+#[instrument(name = "debug interactions", skip_all)]
+pub fn debug_interactions<F: Field>(
+    traces: &[(
+        &[Interaction<F>],
+        Option<&RowMajorMatrix<F>>,
+        &RowMajorMatrix<F>,
+    )],
+) -> Result<(), Vec<InteractionImbalance<F>>> {
+    let mut indices = HashMap::new();
+    let mut tuples: Vec<InteractionImbalance<F>> = Vec::new();
+    for &(interactions, preprocessed, main) in traces {
+        for r in 0..main.height() {
+            let main_row = main.row_slice(r);
+            let preprocessed_row = preprocessed.map(|preprocessed| preprocessed.row_slice(r));
+            let preprocessed_row = preprocessed_row.as_deref().unwrap_or(&[]);
+            for interaction in interactions {
+                let values: Vec<F> = interaction
+                    .values
+                    .iter()
+                    .map(|value| value.apply::<F, F>(preprocessed_row, &main_row))
+                    .collect();
+                let multiplicity = interaction
+                    .multiplicity
+                    .apply::<F, F>(preprocessed_row, &main_row);
+                let multiplicity = match interaction.kind {
+                    InteractionKind::Send => multiplicity,
+                    InteractionKind::Receive => -multiplicity,
+                };
+                let index = *indices
+                    .entry((interaction.bus, values.clone()))
+                    .or_insert_with(|| {
+                        tuples.push(InteractionImbalance {
+                            bus: interaction.bus,
+                            values,
+                            net_multiplicity: F::ZERO,
+                        });
+                        tuples.len() - 1
+                    });
+                tuples[index].net_multiplicity += multiplicity;
+            }
+        }
+    }
+
+    let imbalances: Vec<_> = tuples
+        .into_iter()
+        .filter(|tuple| !tuple.net_multiplicity.is_zero())
+        .collect();
+    if imbalances.is_empty() {
+        Ok(())
+    } else {
+        Err(imbalances)
+    }
+}
+
+/// An `AirBuilder` which records the value of each constraint on a single row.
+#[derive(Debug)]
+pub struct ConstraintEvaluator<'a, F: Field> {
+    row_index: usize,
+    preprocessed: RowMajorMatrixView<'a, F>,
+    main: RowMajorMatrixView<'a, F>,
+    public_values: &'a [F],
+    periodic_values: &'a [F],
+    is_first_row: F,
+    is_last_row: F,
+    height: usize,
+    values: Vec<F>,
+}
+
+impl<'a, F: Field> AirBuilder for ConstraintEvaluator<'a, F> {
+    type F = F;
+    type Expr = F;
+    type Var = F;
+    type M = RowMajorMatrixView<'a, F>;
+
+    fn main(&self) -> Self::M {
+        self.main
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.is_first_row
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.is_last_row
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert!(
+            (2..=self.main.height()).contains(&size),
+            "transition windows must have between 2 and {} rows",
+            self.main.height()
+        );
+        F::from_bool(self.row_index + size <= self.height)
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.values.push(x.into());
+    }
+}
+
+impl<F: Field> AirBuilderWithPublicValues for ConstraintEvaluator<'_, F> {
+    type PublicVar = Self::F;
+
+    fn public_values(&self) -> &[Self::F] {
+        self.public_values
+    }
+}
+
+impl<F: Field> PeriodicAirBuilder for ConstraintEvaluator<'_, F> {
+    type PeriodicVar = Self::F;
+
+    fn periodic_values(&self) -> &[Self::F] {
+        self.periodic_values
+    }
+}
+
+impl<F: Field> PairBuilder for ConstraintEvaluator<'_, F> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
@@ -1,6 +1,7 @@
-use alloc::vec::Vec;
-
-use p3_air::{AirBuilder, AirBuilderWithPublicValues};
+use p3_air::{
+    AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, PairBuilder, PeriodicAirBuilder,
+    PermutationAirBuilder,
+};
 use p3_field::FieldAlgebra;
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
@@ -9,12 +10,19 @@ use crate::{PackedChallenge, PackedVal, StarkGenericConfig, Val};
 
 #[derive(Debug)]
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
+    pub preprocessed: RowMajorMatrixView<'a, PackedVal<SC>>,
     pub main: RowMajorMatrixView<'a, PackedVal<SC>>,
-    pub public_values: &'a Vec<Val<SC>>,
+    pub permutation: RowMajorMatrixView<'a, PackedChallenge<SC>>,
+    pub permutation_challenges: &'a [PackedChallenge<SC>],
+    pub public_values: &'a [Val<SC>],
+    pub periodic_values: &'a [PackedVal<SC>],
     pub is_first_row: PackedVal<SC>,
     pub is_last_row: PackedVal<SC>,
     pub is_transition: PackedVal<SC>,
-    pub alpha_powers: &'a [SC::Challenge],
+    /// The selectors for transition windows of 3 or more rows, starting with 3.
+    pub is_transition_windows: &'a [PackedVal<SC>],
+    /// The coefficient of each constraint in their combination, in the order they are evaluated.
+    pub constraint_coefficients: &'a [SC::Challenge],
     pub accumulator: PackedChallenge<SC>,
     pub constraint_index: usize,
 }
@@ -23,13 +31,21 @@ type ViewPair<'a, T> = VerticalPair<RowMajorMatrixView<'a, T>, RowMajorMatrixVie
 
 #[derive(Debug)]
 pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
-    pub main: ViewPair<'a, SC::Challenge>,
-    pub public_values: &'a Vec<Val<SC>>,
+    pub preprocessed: RowMajorMatrixView<'a, SC::Challenge>,
+    pub main: RowMajorMatrixView<'a, SC::Challenge>,
+    pub permutation: ViewPair<'a, SC::Challenge>,
+    pub permutation_challenges: &'a [SC::Challenge],
+    pub public_values: &'a [Val<SC>],
+    pub periodic_values: &'a [SC::Challenge],
     pub is_first_row: SC::Challenge,
     pub is_last_row: SC::Challenge,
     pub is_transition: SC::Challenge,
-    pub alpha: SC::Challenge,
+    /// The selectors for transition windows of 3 or more rows, starting with 3.
+    pub is_transition_windows: &'a [SC::Challenge],
+    /// The coefficient of each constraint in their combination, in the order they are evaluated.
+    pub constraint_coefficients: &'a [SC::Challenge],
     pub accumulator: SC::Challenge,
+    pub constraint_index: usize,
 }
 
 impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
@@ -55,18 +71,20 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
 
     #[inline]
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
+        match size {
+            2 => self.is_transition,
+            _ if (3..=self.is_transition_windows.len() + 2).contains(&size) => {
+                self.is_transition_windows[size - 3]
+            }
+            _ => panic!("transition windows must be no larger than the AIR's window"),
         }
     }
 
     #[inline]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
         let x: PackedVal<SC> = x.into();
-        let alpha_power = self.alpha_powers[self.constraint_index];
-        self.accumulator += PackedChallenge::<SC>::from_f(alpha_power) * x;
+        let coefficient = self.constraint_coefficients[self.constraint_index];
+        self.accumulator += PackedChallenge::<SC>::from_f(coefficient) * x;
         self.constraint_index += 1;
     }
 }
@@ -80,11 +98,59 @@ impl<SC: StarkGenericConfig> AirBuilderWithPublicValues for ProverConstraintFold
     }
 }
 
+impl<SC: StarkGenericConfig> PeriodicAirBuilder for ProverConstraintFolder<'_, SC> {
+    type PeriodicVar = PackedVal<SC>;
+
+    #[inline]
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.periodic_values
+    }
+}
+
+impl<SC: StarkGenericConfig> PairBuilder for ProverConstraintFolder<'_, SC> {
+    #[inline]
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+impl<SC: StarkGenericConfig> ExtensionBuilder for ProverConstraintFolder<'_, SC> {
+    type EF = SC::Challenge;
+    type ExprEF = PackedChallenge<SC>;
+    type VarEF = PackedChallenge<SC>;
+
+    #[inline]
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        let x: PackedChallenge<SC> = x.into();
+        let coefficient = self.constraint_coefficients[self.constraint_index];
+        self.accumulator += PackedChallenge::<SC>::from_f(coefficient) * x;
+        self.constraint_index += 1;
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> PermutationAirBuilder for ProverConstraintFolder<'a, SC> {
+    type MP = RowMajorMatrixView<'a, PackedChallenge<SC>>;
+    type RandomVar = PackedChallenge<SC>;
+
+    #[inline]
+    fn permutation(&self) -> Self::MP {
+        self.permutation
+    }
+
+    #[inline]
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.permutation_challenges
+    }
+}
+
 impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC> {
     type F = Val<SC>;
     type Expr = SC::Challenge;
     type Var = SC::Challenge;
-    type M = ViewPair<'a, SC::Challenge>;
+    type M = RowMajorMatrixView<'a, SC::Challenge>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -99,17 +165,19 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
+        match size {
+            2 => self.is_transition,
+            _ if (3..=self.is_transition_windows.len() + 2).contains(&size) => {
+                self.is_transition_windows[size - 3]
+            }
+            _ => panic!("transition windows must be no larger than the AIR's window"),
         }
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
         let x: SC::Challenge = x.into();
-        self.accumulator *= self.alpha;
-        self.accumulator += x;
+        self.accumulator += self.constraint_coefficients[self.constraint_index] * x;
+        self.constraint_index += 1;
     }
 }
 
@@ -120,3 +188,45 @@ impl<SC: StarkGenericConfig> AirBuilderWithPublicValues for VerifierConstraintFo
         self.public_values
     }
 }
+
+impl<SC: StarkGenericConfig> PeriodicAirBuilder for VerifierConstraintFolder<'_, SC> {
+    type PeriodicVar = SC::Challenge;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.periodic_values
+    }
+}
+
+impl<SC: StarkGenericConfig> PairBuilder for VerifierConstraintFolder<'_, SC> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+impl<SC: StarkGenericConfig> ExtensionBuilder for VerifierConstraintFolder<'_, SC> {
+    type EF = SC::Challenge;
+    type ExprEF = SC::Challenge;
+    type VarEF = SC::Challenge;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        let x: SC::Challenge = x.into();
+        self.accumulator += self.constraint_coefficients[self.constraint_index] * x;
+        self.constraint_index += 1;
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> PermutationAirBuilder for VerifierConstraintFolder<'a, SC> {
+    type MP = ViewPair<'a, SC::Challenge>;
+    type RandomVar = SC::Challenge;
+
+    fn permutation(&self) -> Self::MP {
+        self.permutation
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.permutation_challenges
+    }
+}
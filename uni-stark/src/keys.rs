@@ -0,0 +1,361 @@
+//! Proving and verifying keys, which capture everything about an AIR that the prover and verifier
+//! need besides the trace, so that verifiers need not run the AIR's code.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PairBuilder, PeriodicAirBuilder,
+};
+use p3_challenger::TranscriptSchema;
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+use p3_util::log2_strict_usize;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::preprocessed::commit_preprocessed;
+use crate::prover::log_quotient_degree;
+use crate::symbolic_builder::{get_max_constraint_degree, get_symbolic_constraints};
+use crate::{
+    prove_with_preprocessed, verify_with_preprocessed, Entry, PcsError, PreprocessedProverData,
+    PreprocessedVerifierKey, Proof, ProverConstraintFolder, ShapeError, StarkGenericConfig,
+    SymbolicAirBuilder, SymbolicExpression, Val, VerificationError,
+};
+
+/// An AIR given by its symbolic constraints, rather than by code.
+///
+/// The constraints are stored as a DAG of operations, in which subexpressions shared by the
+/// original AIR are evaluated once. AIRs with lookups are not supported.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolicAir<F> {
+    width: usize,
+    preprocessed_width: usize,
+    window_size: usize,
+    num_public_values: usize,
+    periodic_columns: Vec<Vec<F>>,
+    /// The operations, each of which only refers to earlier ones.
+    pub(crate) nodes: Vec<ConstraintNode<F>>,
+    /// The nodes which must evaluate to zero.
+    pub(crate) constraints: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ConstraintNode<F> {
+    Variable { entry: Entry, index: usize },
+    IsFirstRow,
+    IsLastRow,
+    IsTransitionWindow(usize),
+    Constant(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Mul(usize, usize),
+}
+
+impl<F: Field> SymbolicAir<F> {
+    /// Record the constraints of `air`, with a preprocessed trace of the given width.
+    pub fn new<A>(air: &A, preprocessed_width: usize, num_public_values: usize) -> Self
+    where
+        A: Air<SymbolicAirBuilder<F>>,
+    {
+        let mut nodes = Vec::new();
+        let mut indices = BTreeMap::new();
+        let symbolic_constraints =
+            get_symbolic_constraints(air, preprocessed_width, num_public_values);
+        let constraints = symbolic_constraints
+            .iter()
+            .map(|constraint| flatten(constraint, &mut nodes, &mut indices))
+            .collect();
+        Self {
+            width: air.width(),
+            preprocessed_width,
+            window_size: air.window_size(),
+            num_public_values,
+            periodic_columns: air.periodic_columns(),
+            nodes,
+            constraints,
+        }
+    }
+
+    pub const fn preprocessed_width(&self) -> usize {
+        self.preprocessed_width
+    }
+
+    pub const fn num_public_values(&self) -> usize {
+        self.num_public_values
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.len()
+    }
+}
+
+/// Append the node for `expr`, and those of its subexpressions, to `nodes`, unless they are
+/// already there, and return its index.
+fn flatten<F: Field>(
+    expr: &SymbolicExpression<F>,
+    nodes: &mut Vec<ConstraintNode<F>>,
+    indices: &mut BTreeMap<*const SymbolicExpression<F>, usize>,
+) -> usize {
+    let key = expr as *const SymbolicExpression<F>;
+    if let Some(&index) = indices.get(&key) {
+        return index;
+    }
+    let node = match expr {
+        SymbolicExpression::Variable(v) => ConstraintNode::Variable {
+            entry: v.entry,
+            index: v.index,
+        },
+        SymbolicExpression::IsFirstRow => ConstraintNode::IsFirstRow,
+        SymbolicExpression::IsLastRow => ConstraintNode::IsLastRow,
+        SymbolicExpression::IsTransition => ConstraintNode::IsTransitionWindow(2),
+        SymbolicExpression::IsTransitionWindow(size) => ConstraintNode::IsTransitionWindow(*size),
+        SymbolicExpression::Constant(c) => ConstraintNode::Constant(*c),
+        SymbolicExpression::Add { x, y, .. } => {
+            ConstraintNode::Add(flatten(x, nodes, indices), flatten(y, nodes, indices))
+        }
+        SymbolicExpression::Sub { x, y, .. } => {
+            ConstraintNode::Sub(flatten(x, nodes, indices), flatten(y, nodes, indices))
+        }
+        SymbolicExpression::Neg { x, .. } => ConstraintNode::Neg(flatten(x, nodes, indices)),
+        SymbolicExpression::Mul { x, y, .. } => {
+            ConstraintNode::Mul(flatten(x, nodes, indices), flatten(y, nodes, indices))
+        }
+    };
+    nodes.push(node);
+    indices.insert(key, nodes.len() - 1);
+    nodes.len() - 1
+}
+
+impl<F: Field> BaseAir<F> for SymbolicAir<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        self.periodic_columns.clone()
+    }
+}
+
+impl<AB> Air<AB> for SymbolicAir<AB::F>
+where
+    AB: AirBuilderWithPublicValues + PairBuilder + PeriodicAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let preprocessed = builder.preprocessed();
+        let mut values: Vec<AB::Expr> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let value = match *node {
+                ConstraintNode::Variable { entry, index } => match entry {
+                    Entry::Preprocessed { offset } => preprocessed.get(offset, index).into(),
+                    Entry::Main { offset } => main.get(offset, index).into(),
+                    Entry::Periodic => builder.periodic_values()[index].into(),
+                    Entry::Public => builder.public_values()[index].into(),
+                    Entry::Permutation { .. } | Entry::Challenge => {
+                        unreachable!("symbolic AIRs have no lookups")
+                    }
+                },
+                ConstraintNode::IsFirstRow => builder.is_first_row(),
+                ConstraintNode::IsLastRow => builder.is_last_row(),
+                ConstraintNode::IsTransitionWindow(size) => builder.is_transition_window(size),
+                ConstraintNode::Constant(c) => c.into(),
+                ConstraintNode::Add(x, y) => values[x].clone() + values[y].clone(),
+                ConstraintNode::Sub(x, y) => values[x].clone() - values[y].clone(),
+                ConstraintNode::Neg(x) => -values[x].clone(),
+                ConstraintNode::Mul(x, y) => values[x].clone() * values[y].clone(),
+            };
+            values.push(value);
+        }
+        for &constraint in &self.constraints {
+            builder.assert_zero(values[constraint].clone());
+        }
+    }
+}
+
+/// What the verifier needs to know about an AIR: its constraints, the commitment to its
+/// preprocessed trace, and the quotient degree they imply under the config it was created with.
+///
+/// It may also carry the schema of the transcripts of its proofs, for verifiers written in other
+/// languages.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifyingKey<SC: StarkGenericConfig> {
+    pub(crate) air: SymbolicAir<Val<SC>>,
+    pub(crate) preprocessed: Option<PreprocessedVerifierKey<SC>>,
+    pub(crate) log_quotient_degree: usize,
+    pub(crate) transcript_schema: Option<TranscriptSchema>,
+}
+
+impl<SC: StarkGenericConfig> Clone for VerifyingKey<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            air: self.air.clone(),
+            preprocessed: self.preprocessed.clone(),
+            log_quotient_degree: self.log_quotient_degree,
+            transcript_schema: self.transcript_schema.clone(),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> VerifyingKey<SC> {
+    pub const fn air(&self) -> &SymbolicAir<Val<SC>> {
+        &self.air
+    }
+
+    pub const fn preprocessed(&self) -> Option<&PreprocessedVerifierKey<SC>> {
+        self.preprocessed.as_ref()
+    }
+
+    /// The log2 of the number of chunks the quotient is split into, each the height of the trace
+    /// and committed at the blowup of the PCS. This fixes the shape of proofs: they open
+    /// `2^log_quotient_degree` quotient chunks, even when that exceeds the blowup.
+    pub const fn log_quotient_degree(&self) -> usize {
+        self.log_quotient_degree
+    }
+
+    pub const fn transcript_schema(&self) -> Option<&TranscriptSchema> {
+        self.transcript_schema.as_ref()
+    }
+
+    /// Attach the schema of the transcripts of proofs verified with this key, to be serialized
+    /// with it.
+    ///
+    /// The schema is recorded by verifying a proof with a config whose challenger is a
+    /// `RecordingChallenger`, and calling `RecordingChallenger::schema`. As the number of FRI
+    /// rounds depends on the height of the trace, it describes proofs of traces of the height of
+    /// that proof. It is not used by `verify_with_key`.
+    #[must_use]
+    pub fn with_transcript_schema(mut self, schema: TranscriptSchema) -> Self {
+        self.transcript_schema = Some(schema);
+        self
+    }
+
+    /// Hash the encoding of this key, so that keys can be compared by digest.
+    pub fn digest<H, Out>(&self, hasher: &H) -> Out
+    where
+        H: CryptographicHasher<u8, Out>,
+    {
+        hasher.hash_iter(postcard::to_allocvec(self).expect("keys are always serializable"))
+    }
+}
+
+/// What the prover needs besides the AIR itself: the verifying key, and its data for the
+/// preprocessed trace.
+pub struct ProvingKey<SC: StarkGenericConfig> {
+    pub(crate) vk: VerifyingKey<SC>,
+    pub(crate) preprocessed: Option<PreprocessedProverData<SC>>,
+}
+
+impl<SC: StarkGenericConfig> ProvingKey<SC> {
+    pub const fn vk(&self) -> &VerifyingKey<SC> {
+        &self.vk
+    }
+}
+
+/// Create the keys for proofs of `air` with `num_public_values` public values.
+///
+/// If the AIR has a preprocessed trace, it is committed to here, and its height fixes the height
+/// of every trace proven with these keys.
+#[instrument(skip_all)]
+pub fn setup_keys<SC, A>(
+    config: &SC,
+    air: &A,
+    num_public_values: usize,
+) -> (ProvingKey<SC>, VerifyingKey<SC>)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>>,
+{
+    let preprocessed = air.preprocessed_trace().map(|trace| {
+        let degree_bits = log2_strict_usize(trace.height());
+        commit_preprocessed(config, trace, degree_bits)
+    });
+    let preprocessed_width = preprocessed
+        .as_ref()
+        .map_or(0, |(_, preprocessed_vk)| preprocessed_vk.width());
+    let constraint_degree = get_max_constraint_degree(air, preprocessed_width, num_public_values);
+    let (preprocessed_prover_data, preprocessed_vk) = preprocessed.unzip();
+    let vk = VerifyingKey {
+        air: SymbolicAir::new(air, preprocessed_width, num_public_values),
+        preprocessed: preprocessed_vk,
+        log_quotient_degree: log_quotient_degree(config, constraint_degree),
+        transcript_schema: None,
+    };
+    let pk = ProvingKey {
+        vk: vk.clone(),
+        preprocessed: preprocessed_prover_data,
+    };
+    (pk, vk)
+}
+
+/// Prove `air` with a proving key created by `setup_keys`.
+///
+/// Panics if the key was created for a different AIR.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_key<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    pk: &ProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert!(
+        SymbolicAir::new(air, pk.vk.air.preprocessed_width, public_values.len()) == pk.vk.air,
+        "the proving key was created for a different AIR"
+    );
+    prove_with_preprocessed(
+        config,
+        air,
+        challenger,
+        trace,
+        public_values,
+        pk.preprocessed.as_ref(),
+    )
+}
+
+/// Verify a proof against a verifying key created by `setup_keys`, without the AIR's code.
+#[instrument(skip_all)]
+pub fn verify_with_key<SC: StarkGenericConfig>(
+    config: &SC,
+    vk: &VerifyingKey<SC>,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Val<SC>],
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>> {
+    if public_values.len() != vk.air.num_public_values {
+        return Err(VerificationError::InvalidProofShape(
+            ShapeError::NumPublicValues,
+        ));
+    }
+    let constraint_degree =
+        get_max_constraint_degree(&vk.air, vk.air.preprocessed_width, vk.air.num_public_values);
+    if log_quotient_degree(config, constraint_degree) != vk.log_quotient_degree {
+        return Err(VerificationError::KeyMismatch);
+    }
+    verify_with_preprocessed(
+        config,
+        &vk.air,
+        challenger,
+        proof,
+        public_values,
+        vk.preprocessed.as_ref(),
+    )
+}
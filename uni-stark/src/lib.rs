@@ -3,11 +3,24 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+mod chain;
+mod checkpoint;
+mod codegen;
 mod config;
+mod debug_constraints;
 mod folder;
+mod keys;
+mod metrics;
+mod preprocessed;
 mod proof;
 mod prover;
+mod resources;
+mod serialization;
+mod simplify;
+mod stats;
 mod symbolic_builder;
 mod symbolic_expression;
 mod symbolic_variable;
@@ -17,12 +30,26 @@ mod zerofier_coset;
 #[cfg(debug_assertions)]
 mod check_constraints;
 
+#[cfg(feature = "test-utils")]
+pub mod testing;
+
+pub use chain::*;
 #[cfg(debug_assertions)]
 pub use check_constraints::*;
+pub use checkpoint::*;
+pub use codegen::*;
 pub use config::*;
+pub use debug_constraints::*;
 pub use folder::*;
+pub use keys::*;
+pub use metrics::*;
+pub use preprocessed::*;
 pub use proof::*;
 pub use prover::*;
+pub use resources::*;
+pub use serialization::*;
+pub use simplify::*;
+pub use stats::*;
 pub use symbolic_builder::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
@@ -0,0 +1,51 @@
+//! Metrics collected while proving, so that the prover's performance can be tracked across
+//! versions.
+
+use core::time::Duration;
+
+/// The cost of one stage of the prover.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StageMetrics {
+    /// The size of the data the stage produced or committed to.
+    pub bytes: usize,
+    /// The wall-clock time the stage took, which is only measured with the `std` feature.
+    pub duration: Option<Duration>,
+}
+
+/// The cost of each stage of a proof, as returned by `prove_with_metrics`.
+///
+/// The PCS computes the low-degree extension of a matrix as part of committing to it, so each
+/// commitment stage includes it. Likewise, the FRI commit phase and proof of work happen as part of
+/// opening. The same stages are recorded as `tracing` spans, with their sizes as fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProverMetrics {
+    /// Committing to the main trace, including any zero-knowledge masking, though its size is that
+    /// of the trace before masking.
+    pub trace_commit: StageMetrics,
+    /// Generating and committing to the permutation trace, which is empty for AIRs without lookups.
+    pub permutation_commit: StageMetrics,
+    /// Evaluating the quotient on the quotient domain.
+    pub quotient: StageMetrics,
+    /// Committing to the quotient chunks.
+    pub quotient_commit: StageMetrics,
+    /// Opening the commitments, whose size is that of the encoded opening proof.
+    pub open: StageMetrics,
+    /// The size of the whole proof, as encoded by `Proof::to_bytes`.
+    pub proof_bytes: usize,
+}
+
+/// Run `f`, recording its duration in `stage` if the `std` feature is enabled.
+pub(crate) fn measure<T>(stage: &mut StageMetrics, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "std")]
+    {
+        let start = std::time::Instant::now();
+        let result = f();
+        stage.duration = Some(start.elapsed());
+        result
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = stage;
+        f()
+    }
+}
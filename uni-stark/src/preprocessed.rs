@@ -0,0 +1,223 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_air::BaseAir;
+use p3_commit::Pcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::{Com, PcsProverData, StarkGenericConfig, Val};
+
+/// The prover's data for the preprocessed trace of an AIR, which is committed once and can then be
+/// reused for every proof of that AIR with the same trace height.
+pub struct PreprocessedProverData<SC: StarkGenericConfig> {
+    pub(crate) width: usize,
+    pub(crate) degree_bits: usize,
+    pub(crate) commitment: Com<SC>,
+    pub(crate) prover_data: PcsProverData<SC>,
+}
+
+/// The verifier's view of the preprocessed trace of an AIR: its shape and commitment.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PreprocessedVerifierKey<SC: StarkGenericConfig> {
+    pub(crate) width: usize,
+    pub(crate) degree_bits: usize,
+    pub(crate) commitment: Com<SC>,
+}
+
+impl<SC: StarkGenericConfig> Clone for PreprocessedVerifierKey<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            degree_bits: self.degree_bits,
+            commitment: self.commitment.clone(),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> PreprocessedVerifierKey<SC> {
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+
+    pub const fn commitment(&self) -> &Com<SC> {
+        &self.commitment
+    }
+}
+
+/// Commit to the preprocessed trace of `air`, if it has one, for proofs over traces of height
+/// `2^degree_bits`.
+///
+/// Panics if the preprocessed trace does not have height `2^degree_bits`.
+pub fn setup_preprocessed<SC, A>(
+    config: &SC,
+    air: &A,
+    degree_bits: usize,
+) -> Option<(PreprocessedProverData<SC>, PreprocessedVerifierKey<SC>)>
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>>,
+{
+    let preprocessed = air.preprocessed_trace()?;
+    assert_eq!(
+        preprocessed.height(),
+        1 << degree_bits,
+        "preprocessed trace must have the same height as the main trace"
+    );
+    Some(commit_preprocessed(config, preprocessed, degree_bits))
+}
+
+pub(crate) fn commit_preprocessed<SC: StarkGenericConfig>(
+    config: &SC,
+    preprocessed: RowMajorMatrix<Val<SC>>,
+    degree_bits: usize,
+) -> (PreprocessedProverData<SC>, PreprocessedVerifierKey<SC>) {
+    let width = preprocessed.width();
+    let pcs = config.pcs();
+    let domain = pcs.natural_domain_for_degree(1 << degree_bits);
+    let (commitment, prover_data) = info_span!("commit to preprocessed trace")
+        .in_scope(|| pcs.commit(vec![(domain, preprocessed)]));
+
+    (
+        PreprocessedProverData {
+            width,
+            degree_bits,
+            commitment: commitment.clone(),
+            prover_data,
+        },
+        PreprocessedVerifierKey {
+            width,
+            degree_bits,
+            commitment,
+        },
+    )
+}
+
+/// The prover's data for the preprocessed traces of several AIRs proven together, which are
+/// committed together once and can then be reused for every proof of those AIRs with the same trace
+/// heights.
+pub struct MultiPreprocessedProverData<SC: StarkGenericConfig> {
+    /// The width of each AIR's preprocessed trace, if it has one.
+    pub(crate) widths: Vec<Option<usize>>,
+    pub(crate) degree_bits: Vec<usize>,
+    pub(crate) commitment: Com<SC>,
+    pub(crate) prover_data: PcsProverData<SC>,
+}
+
+/// The verifier's view of the preprocessed traces of several AIRs proven together: their shapes
+/// and commitment.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MultiPreprocessedVerifierKey<SC: StarkGenericConfig> {
+    /// The width of each AIR's preprocessed trace, if it has one.
+    pub(crate) widths: Vec<Option<usize>>,
+    pub(crate) degree_bits: Vec<usize>,
+    pub(crate) commitment: Com<SC>,
+}
+
+impl<SC: StarkGenericConfig> Clone for MultiPreprocessedVerifierKey<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            widths: self.widths.clone(),
+            degree_bits: self.degree_bits.clone(),
+            commitment: self.commitment.clone(),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> MultiPreprocessedVerifierKey<SC> {
+    pub fn widths(&self) -> &[Option<usize>] {
+        &self.widths
+    }
+
+    pub fn degree_bits(&self) -> &[usize] {
+        &self.degree_bits
+    }
+
+    pub const fn commitment(&self) -> &Com<SC> {
+        &self.commitment
+    }
+}
+
+/// Commit to the preprocessed traces of `airs`, to be proven together over traces of heights
+/// `2^degree_bits[i]`, in a single commitment. Returns `None` if no AIR has a preprocessed trace.
+///
+/// Panics if a preprocessed trace does not have the height of its AIR's trace.
+pub fn setup_multiple_preprocessed<SC, A>(
+    config: &SC,
+    airs: &[A],
+    degree_bits: &[usize],
+) -> Option<(
+    MultiPreprocessedProverData<SC>,
+    MultiPreprocessedVerifierKey<SC>,
+)>
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>>,
+{
+    assert_eq!(airs.len(), degree_bits.len());
+    commit_multiple_preprocessed(
+        config,
+        airs.iter()
+            .map(<A as BaseAir<Val<SC>>>::preprocessed_trace)
+            .collect(),
+        degree_bits,
+    )
+}
+
+/// Commit to the preprocessed traces of several AIRs proven together, in a single commitment.
+pub(crate) fn commit_multiple_preprocessed<SC: StarkGenericConfig>(
+    config: &SC,
+    preprocessed: Vec<Option<RowMajorMatrix<Val<SC>>>>,
+    degree_bits: &[usize],
+) -> Option<(
+    MultiPreprocessedProverData<SC>,
+    MultiPreprocessedVerifierKey<SC>,
+)> {
+    if preprocessed.iter().all(Option::is_none) {
+        return None;
+    }
+    let widths: Vec<_> = preprocessed
+        .iter()
+        .map(|trace| trace.as_ref().map(|trace| trace.width()))
+        .collect();
+    let pcs = config.pcs();
+    let (commitment, prover_data) = info_span!("commit to preprocessed traces").in_scope(|| {
+        pcs.commit(
+            preprocessed
+                .into_iter()
+                .zip(degree_bits)
+                .filter_map(|(trace, &degree_bits)| {
+                    let trace = trace?;
+                    assert_eq!(
+                        trace.height(),
+                        1 << degree_bits,
+                        "preprocessed trace must have the same height as the main trace"
+                    );
+                    Some((pcs.natural_domain_for_degree(1 << degree_bits), trace))
+                })
+                .collect(),
+        )
+    });
+
+    Some((
+        MultiPreprocessedProverData {
+            widths: widths.clone(),
+            degree_bits: degree_bits.to_vec(),
+            commitment: commitment.clone(),
+            prover_data,
+        },
+        MultiPreprocessedVerifierKey {
+            widths,
+            degree_bits: degree_bits.to_vec(),
+            commitment,
+        },
+    ))
+}
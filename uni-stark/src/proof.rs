@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::StarkGenericConfig;
 
-type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+pub(crate) type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
 >>::Commitment;
@@ -13,6 +13,10 @@ type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
 >>::Proof;
+pub(crate) type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::ProverData;
 
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -23,15 +27,111 @@ pub struct Proof<SC: StarkGenericConfig> {
     pub(crate) degree_bits: usize,
 }
 
+impl<SC: StarkGenericConfig> Proof<SC> {
+    pub const fn commitments(&self) -> &Commitments<Com<SC>> {
+        &self.commitments
+    }
+
+    pub const fn opened_values(&self) -> &OpenedValues<SC::Challenge> {
+        &self.opened_values
+    }
+
+    pub const fn opening_proof(&self) -> &PcsProof<SC> {
+        &self.opening_proof
+    }
+
+    /// The log2 height of the trace.
+    pub const fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+}
+
+/// A proof of several AIRs whose traces, and then quotients, are committed together, as produced by
+/// `prove_multiple`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MultiProof<SC: StarkGenericConfig> {
+    pub(crate) commitments: Commitments<Com<SC>>,
+    /// The opened values of each AIR, in the order the AIRs were given.
+    pub(crate) opened_values: Vec<OpenedValues<SC::Challenge>>,
+    /// The total of each AIR's lookup running sum, if any AIR has lookups.
+    pub(crate) cumulative_sums: Vec<SC::Challenge>,
+    pub(crate) opening_proof: PcsProof<SC>,
+    /// The log2 trace height of each AIR.
+    pub(crate) degree_bits: Vec<usize>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Commitments<Com> {
     pub(crate) trace: Com,
+    /// The commitment to the permutation trace, for AIRs with lookups.
+    pub(crate) permutation: Option<Com>,
     pub(crate) quotient_chunks: Com,
 }
 
+impl<Com> Commitments<Com> {
+    pub const fn trace(&self) -> &Com {
+        &self.trace
+    }
+
+    pub const fn permutation(&self) -> Option<&Com> {
+        self.permutation.as_ref()
+    }
+
+    pub const fn quotient_chunks(&self) -> &Com {
+        &self.quotient_chunks
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenedValues<Challenge> {
+    pub(crate) preprocessed_local: Option<Vec<Challenge>>,
+    pub(crate) preprocessed_next: Option<Vec<Challenge>>,
+    /// The preprocessed rows after `preprocessed_next`, for AIRs whose window has more than two rows.
+    pub(crate) preprocessed_after_next: Vec<Vec<Challenge>>,
     pub(crate) trace_local: Vec<Challenge>,
     pub(crate) trace_next: Vec<Challenge>,
+    /// The rows after `trace_next`, for AIRs whose window has more than two rows.
+    pub(crate) trace_after_next: Vec<Vec<Challenge>>,
+    pub(crate) permutation_local: Option<Vec<Challenge>>,
+    pub(crate) permutation_next: Option<Vec<Challenge>>,
     pub(crate) quotient_chunks: Vec<Vec<Challenge>>,
 }
+
+impl<Challenge> OpenedValues<Challenge> {
+    pub fn preprocessed_local(&self) -> Option<&[Challenge]> {
+        self.preprocessed_local.as_deref()
+    }
+
+    pub fn preprocessed_next(&self) -> Option<&[Challenge]> {
+        self.preprocessed_next.as_deref()
+    }
+
+    pub fn preprocessed_after_next(&self) -> &[Vec<Challenge>] {
+        &self.preprocessed_after_next
+    }
+
+    pub fn trace_local(&self) -> &[Challenge] {
+        &self.trace_local
+    }
+
+    pub fn trace_next(&self) -> &[Challenge] {
+        &self.trace_next
+    }
+
+    pub fn trace_after_next(&self) -> &[Vec<Challenge>] {
+        &self.trace_after_next
+    }
+
+    pub fn permutation_local(&self) -> Option<&[Challenge]> {
+        self.permutation_local.as_deref()
+    }
+
+    pub fn permutation_next(&self) -> Option<&[Challenge]> {
+        self.permutation_next.as_deref()
+    }
+
+    pub fn quotient_chunks(&self) -> &[Vec<Challenge>] {
+        &self.quotient_chunks
+    }
+}
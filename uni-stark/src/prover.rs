@@ -1,20 +1,28 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 
 use itertools::{izip, Itertools};
-use p3_air::Air;
+use p3_air::{
+    eval_logup, generate_logup_trace, logup_trace_width, num_logup_constraints, Air, BaseAir,
+    Interaction, LookupAir, LOGUP_CONSTRAINT_DEGREE,
+};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{FieldAlgebra, FieldExtensionAlgebra, PackedValue};
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
 use p3_util::{log2_ceil_usize, log2_strict_usize};
 use tracing::{info_span, instrument};
 
+use crate::metrics::measure;
+use crate::preprocessed::commit_multiple_preprocessed;
 use crate::{
-    get_symbolic_constraints, Commitments, Domain, OpenedValues, PackedChallenge, PackedVal, Proof,
-    ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder, SymbolicExpression, Val,
+    get_symbolic_constraints, setup_preprocessed, Commitments, Domain, MultiPreprocessedProverData,
+    MultiProof, OpenedValues, PackedChallenge, PackedVal, PreprocessedProverData, Proof,
+    ProverCheckpoint, ProverCheckpoints, ProverConstraintFolder, ProverMetrics, StarkGenericConfig,
+    SymbolicAirBuilder, SymbolicExpression, Val,
 };
 
 #[instrument(skip_all)]
@@ -28,123 +36,1038 @@ pub fn prove<
     air: &A,
     challenger: &mut SC::Challenger,
     trace: RowMajorMatrix<Val<SC>>,
-    public_values: &Vec<Val<SC>>,
+    public_values: &[Val<SC>],
 ) -> Proof<SC>
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
 {
-    #[cfg(debug_assertions)]
-    crate::check_constraints::check_constraints(air, &trace, public_values);
+    let preprocessed = setup_preprocessed(config, air, log2_strict_usize(trace.height()));
+    prove_with_preprocessed(
+        config,
+        air,
+        challenger,
+        trace,
+        public_values,
+        preprocessed.as_ref().map(|(prover_data, _)| prover_data),
+    )
+}
+
+/// Like `prove`, but also returns the size and, with the `std` feature, the duration of each stage
+/// of the prover.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_metrics<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+) -> (Proof<SC>, ProverMetrics)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let preprocessed = setup_preprocessed(config, air, log2_strict_usize(trace.height()));
+    let mut metrics = ProverMetrics::default();
+    let proof = prove_internal(
+        config,
+        air,
+        challenger,
+        ProverStart::Trace(trace),
+        public_values,
+        preprocessed.as_ref().map(|(prover_data, _)| prover_data),
+        &[],
+        &mut metrics,
+        &mut (),
+    );
+    metrics.open.bytes = postcard::to_allocvec(&proof.opening_proof)
+        .expect("failed to encode the opening proof")
+        .len();
+    metrics.proof_bytes = proof.to_bytes().len();
+    (proof, metrics)
+}
+
+/// Like `prove`, but for a trace over the challenge field, which is committed with each column
+/// flattened into `D` base field columns holding its coordinates.
+///
+/// The AIR sees the flattened trace, so its width is in base field columns, and it can reassemble
+/// extension field values with `ExtensionExpr::from_row`. The proof is verified with `verify`.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_extension_trace<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<SC::Challenge>,
+    public_values: &[Val<SC>],
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove(
+        config,
+        air,
+        challenger,
+        trace.flatten_to_base(),
+        public_values,
+    )
+}
+
+/// Like `prove`, but reuses a commitment to the AIR's preprocessed trace created with
+/// `setup_preprocessed`, rather than committing to it again.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_preprocessed<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&PreprocessedProverData<SC>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_internal(
+        config,
+        air,
+        challenger,
+        ProverStart::Trace(trace),
+        public_values,
+        preprocessed,
+        &[],
+        &mut ProverMetrics::default(),
+        &mut (),
+    )
+}
+
+/// Like `prove_with_preprocessed`, for an AIR with lookups.
+///
+/// After committing to the main trace, the prover samples the LogUp challenges and commits to the
+/// resulting permutation trace, whose constraints are checked alongside those of the AIR.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_lookups<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&PreprocessedProverData<SC>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: LookupAir<Val<SC>>
+        + Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_internal(
+        config,
+        air,
+        challenger,
+        ProverStart::Trace(trace),
+        public_values,
+        preprocessed,
+        &air.interactions(),
+        &mut ProverMetrics::default(),
+        &mut (),
+    )
+}
+
+/// Like `prove`, but saves a checkpoint to `checkpoints` after each commitment stage, and resumes
+/// from the latest checkpoint saved there if there is one.
+///
+/// `generate_trace` is only called when starting from scratch. When resuming, the challenger must
+/// be built the same way as for the interrupted proof, and its state is restored from the
+/// checkpoint.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_checkpoints<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    generate_trace: impl FnOnce() -> RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+    checkpoints: &mut impl ProverCheckpoints<SC>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let start = match checkpoints.latest(challenger) {
+        Some(checkpoint) => ProverStart::Checkpoint(checkpoint),
+        None => ProverStart::Trace(info_span!("generate trace").in_scope(generate_trace)),
+    };
+    let log_degree = match &start {
+        ProverStart::Trace(trace) => log2_strict_usize(trace.height()),
+        ProverStart::Checkpoint(checkpoint) => checkpoint.degree_bits,
+    };
+    let preprocessed = setup_preprocessed(config, air, log_degree);
+    prove_internal(
+        config,
+        air,
+        challenger,
+        start,
+        public_values,
+        preprocessed.as_ref().map(|(prover_data, _)| prover_data),
+        &[],
+        &mut ProverMetrics::default(),
+        checkpoints,
+    )
+}
 
-    let degree = trace.height();
-    let log_degree = log2_strict_usize(degree);
+/// The point from which `prove_internal` starts.
+enum ProverStart<SC: StarkGenericConfig> {
+    Trace(RowMajorMatrix<Val<SC>>),
+    Checkpoint(ProverCheckpoint<SC>),
+}
 
-    let symbolic_constraints = get_symbolic_constraints::<Val<SC>, A>(air, 0, public_values.len());
-    let constraint_count = symbolic_constraints.len();
-    let constraint_degree = symbolic_constraints
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+#[allow(clippy::too_many_arguments)]
+fn prove_internal<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    start: ProverStart<SC>,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&PreprocessedProverData<SC>>,
+    interactions: &[Interaction<Val<SC>>],
+    metrics: &mut ProverMetrics,
+    checkpoints: &mut impl ProverCheckpoints<SC>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert_window_size::<Val<SC>, _>(air);
+    let log_degree = match &start {
+        ProverStart::Trace(trace) => {
+            #[cfg(debug_assertions)]
+            crate::check_constraints::check_constraints(
+                air,
+                air.preprocessed_trace().as_ref(),
+                trace,
+                public_values,
+            );
+            log2_strict_usize(trace.height())
+        }
+        ProverStart::Checkpoint(checkpoint) => {
+            assert_eq!(
+                checkpoint.public_values, public_values,
+                "the checkpoint belongs to a proof with other public values"
+            );
+            checkpoint.degree_bits
+        }
+    };
+    let degree = 1 << log_degree;
+    if let Some(preprocessed) = preprocessed {
+        assert_eq!(
+            preprocessed.degree_bits, log_degree,
+            "preprocessed trace must have the same height as the main trace"
+        );
+    }
+    let preprocessed_width = preprocessed.map_or(0, |preprocessed| preprocessed.width);
+
+    let symbolic_constraints =
+        get_symbolic_constraints::<Val<SC>, A>(air, preprocessed_width, public_values.len());
+    let constraint_count = symbolic_constraints.len() + num_logup_constraints(interactions);
+    let mut constraint_degree = symbolic_constraints
         .iter()
         .map(SymbolicExpression::degree_multiple)
         .max()
         .unwrap_or(0);
-    let log_quotient_degree = log2_ceil_usize(constraint_degree - 1);
+    if !interactions.is_empty() {
+        constraint_degree = constraint_degree.max(LOGUP_CONSTRAINT_DEGREE);
+    }
+    let log_quotient_degree = log_quotient_degree(config, constraint_degree);
     let quotient_degree = 1 << log_quotient_degree;
 
     let pcs = config.pcs();
     let trace_domain = pcs.natural_domain_for_degree(degree);
 
-    let (trace_commit, trace_data) =
-        info_span!("commit to trace data").in_scope(|| pcs.commit(vec![(trace_domain, trace)]));
+    let mut checkpoint = match start {
+        ProverStart::Checkpoint(checkpoint) => checkpoint,
+        ProverStart::Trace(trace) => {
+            // The permutation trace is built from the main trace after the lookup challenges are
+            // sampled.
+            let lookup_trace = (!interactions.is_empty()).then(|| trace.clone());
+            metrics.trace_commit.bytes = size_of_val(trace.values.as_slice());
+            let (trace_commit, trace_data) =
+                info_span!("commit to trace data", bytes = metrics.trace_commit.bytes).in_scope(
+                    || {
+                        measure(&mut metrics.trace_commit, || {
+                            config.commit_traces(vec![(trace_domain, trace)])
+                        })
+                    },
+                );
 
-    // Observe the instance.
-    challenger.observe(Val::<SC>::from_canonical_usize(log_degree));
-    // TODO: Might be best practice to include other instance data here; see verifier comment.
+            // Observe the instance.
+            challenger.observe(Val::<SC>::from_canonical_usize(log_degree));
+            // TODO: Might be best practice to include other instance data here; see verifier
+            // comment.
+            if let Some(preprocessed) = preprocessed {
+                challenger.observe(preprocessed.commitment.clone());
+            }
 
-    challenger.observe(trace_commit.clone());
-    challenger.observe_slice(public_values);
-    let alpha: SC::Challenge = challenger.sample_ext_element();
+            challenger.observe(trace_commit.clone());
+            challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
+            challenger.observe_slice(public_values);
 
-    let quotient_domain =
-        trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
+            let mut permutation_challenges = vec![];
+            let permutation = lookup_trace.map(|trace| {
+                let beta: SC::Challenge = challenger.sample_ext_element();
+                let gamma: SC::Challenge = challenger.sample_ext_element();
+                permutation_challenges = vec![beta, gamma];
+                let (permutation_commit, permutation_data, bytes) =
+                    measure(&mut metrics.permutation_commit, || {
+                        let permutation_trace =
+                            info_span!("generate permutation trace").in_scope(|| {
+                                generate_logup_trace(
+                                    interactions,
+                                    air.preprocessed_trace().as_ref(),
+                                    &trace,
+                                    beta,
+                                    gamma,
+                                )
+                            });
+                        #[cfg(debug_assertions)]
+                        assert_eq!(
+                            permutation_trace.values.last(),
+                            Some(&SC::Challenge::ZERO),
+                            "lookup sends and receives do not balance"
+                        );
+                        let permutation_trace = permutation_trace.flatten_to_base();
+                        let bytes = size_of_val(permutation_trace.values.as_slice());
+                        let (permutation_commit, permutation_data) =
+                            info_span!("commit to permutation trace", bytes).in_scope(|| {
+                                config.commit_traces(vec![(trace_domain, permutation_trace)])
+                            });
+                        (permutation_commit, permutation_data, bytes)
+                    });
+                metrics.permutation_commit.bytes = bytes;
+                challenger.observe(permutation_commit.clone());
+                (permutation_commit, permutation_data)
+            });
 
-    let trace_on_quotient_domain = pcs.get_evaluations_on_domain(&trace_data, 0, quotient_domain);
+            let checkpoint = ProverCheckpoint {
+                degree_bits: log_degree,
+                public_values: public_values.to_vec(),
+                trace_commit,
+                trace_data,
+                permutation,
+                permutation_challenges,
+                quotient: None,
+            };
+            checkpoints.save(&checkpoint, challenger);
+            checkpoint
+        }
+    };
 
-    let quotient_values = quotient_values(
-        air,
-        public_values,
-        trace_domain,
-        quotient_domain,
-        trace_on_quotient_domain,
-        alpha,
-        constraint_count,
-    );
-    let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
-    let quotient_chunks = quotient_domain.split_evals(quotient_degree, quotient_flat);
-    let qc_domains = quotient_domain.split_domains(quotient_degree);
+    if checkpoint.quotient.is_none() {
+        let batching_challenges = sample_batching_challenges(config, challenger, constraint_count);
+        let constraint_coefficients = config
+            .constraint_batching()
+            .coefficients(&batching_challenges, constraint_count);
 
-    let (quotient_commit, quotient_data) = info_span!("commit to quotient poly chunks")
-        .in_scope(|| pcs.commit(izip!(qc_domains, quotient_chunks).collect_vec()));
-    challenger.observe(quotient_commit.clone());
+        let quotient_domain =
+            trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
 
-    let commitments = Commitments {
-        trace: trace_commit,
-        quotient_chunks: quotient_commit,
-    };
+        let trace_on_quotient_domain =
+            pcs.get_evaluations_on_domain(&checkpoint.trace_data, 0, quotient_domain);
+        let preprocessed_on_quotient_domain = preprocessed.map(|preprocessed| {
+            pcs.get_evaluations_on_domain(&preprocessed.prover_data, 0, quotient_domain)
+        });
+        let permutation_on_quotient_domain =
+            checkpoint
+                .permutation
+                .as_ref()
+                .map(|(_, permutation_data)| {
+                    pcs.get_evaluations_on_domain(permutation_data, 0, quotient_domain)
+                });
 
-    let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
+        let quotient_values = measure(&mut metrics.quotient, || {
+            quotient_values(
+                air,
+                public_values,
+                trace_domain,
+                quotient_domain,
+                air.window_size(),
+                preprocessed_on_quotient_domain,
+                trace_on_quotient_domain,
+                permutation_on_quotient_domain,
+                interactions,
+                &checkpoint.permutation_challenges,
+                SC::Challenge::ZERO,
+                &constraint_coefficients,
+                config.quotient_chunk_size(),
+            )
+        });
+        metrics.quotient.bytes = size_of_val(quotient_values.as_slice());
+        let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
 
-    let (opened_values, opening_proof) = info_span!("open").in_scope(|| {
-        pcs.open(
-            vec![
-                (&trace_data, vec![vec![zeta, zeta_next]]),
-                (
-                    &quotient_data,
-                    // open every chunk at zeta
-                    (0..quotient_degree).map(|_| vec![zeta]).collect_vec(),
-                ),
-            ],
-            challenger,
+        metrics.quotient_commit.bytes = metrics.quotient.bytes;
+        let (quotient_commit, quotient_data) = info_span!(
+            "commit to quotient poly chunks",
+            bytes = metrics.quotient_commit.bytes
         )
-    });
-    let trace_local = opened_values[0][0][0].clone();
-    let trace_next = opened_values[0][0][1].clone();
-    let quotient_chunks = opened_values[1].iter().map(|v| v[0].clone()).collect_vec();
+        .in_scope(|| {
+            measure(&mut metrics.quotient_commit, || {
+                config.commit_quotients(vec![(quotient_domain, quotient_flat, quotient_degree)])
+            })
+        });
+        challenger.observe(quotient_commit.clone());
+
+        checkpoint.quotient = Some((quotient_commit, quotient_data));
+        checkpoints.save(&checkpoint, challenger);
+    }
+    let ProverCheckpoint {
+        trace_commit,
+        trace_data,
+        permutation,
+        quotient,
+        ..
+    } = checkpoint;
+    let (quotient_commit, quotient_data) = quotient.unwrap();
+
+    let zeta: SC::Challenge = challenger.sample();
+    let window = window_points::<SC>(&trace_domain, zeta, air.window_size());
+    let zeta_next = window[1];
+
+    let mut rounds = vec![
+        (&trace_data, vec![window.clone()]),
+        (
+            &quotient_data,
+            // open every chunk at zeta
+            (0..quotient_degree).map(|_| vec![zeta]).collect_vec(),
+        ),
+    ];
+    if let Some(preprocessed) = preprocessed {
+        rounds.push((&preprocessed.prover_data, vec![window]));
+    }
+    if let Some((_, permutation_data)) = &permutation {
+        rounds.push((permutation_data, vec![vec![zeta, zeta_next]]));
+    }
+    let (opened_values, opening_proof) =
+        info_span!("open").in_scope(|| measure(&mut metrics.open, || pcs.open(rounds, challenger)));
+    let mut opened_values = opened_values.into_iter();
+    let mut trace_openings = opened_values.next().unwrap().remove(0).into_iter();
+    let quotient_chunks = opened_values
+        .next()
+        .unwrap()
+        .into_iter()
+        .map(|mut v| v.remove(0))
+        .collect_vec();
+    let mut open_window = |present: bool| {
+        if present {
+            let mut openings = opened_values.next().unwrap().remove(0).into_iter();
+            (openings.next(), openings.next(), openings.collect_vec())
+        } else {
+            (None, None, vec![])
+        }
+    };
+    let (preprocessed_local, preprocessed_next, preprocessed_after_next) =
+        open_window(preprocessed.is_some());
+    let (permutation_local, permutation_next, _) = open_window(permutation.is_some());
     let opened_values = OpenedValues {
-        trace_local,
-        trace_next,
+        preprocessed_local,
+        preprocessed_next,
+        preprocessed_after_next,
+        trace_local: trace_openings.next().unwrap(),
+        trace_next: trace_openings.next().unwrap(),
+        trace_after_next: trace_openings.collect_vec(),
+        permutation_local,
+        permutation_next,
         quotient_chunks,
     };
+    checkpoints.finish();
     Proof {
-        commitments,
+        commitments: Commitments {
+            trace: trace_commit,
+            permutation: permutation.map(|(permutation_commit, _)| permutation_commit),
+            quotient_chunks: quotient_commit,
+        },
         opened_values,
         opening_proof,
         degree_bits: log_degree,
     }
 }
 
+/// Prove several AIRs at once, e.g. the chips of a VM.
+///
+/// All traces are committed in a single round, and so are all quotient polynomials, so the proof
+/// has two commitments and a single opening proof however many AIRs there are. The traces may have
+/// different heights, e.g. as padded by `p3_air::pad_traces`. Every AIR sees the same public
+/// values, and constraints are folded with the same challenge.
+///
+/// The preprocessed traces of the AIRs which have them are committed together here, as by
+/// `setup_multiple_preprocessed`.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_multiple<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    airs_and_traces: &[(A, RowMajorMatrix<Val<SC>>)],
+    challenger: &mut SC::Challenger,
+    public_values: &[Val<SC>],
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let interactions = airs_and_traces.iter().map(|_| vec![]).collect_vec();
+    let preprocessed = commit_multiple_preprocessed(
+        config,
+        airs_and_traces
+            .iter()
+            .map(|(air, _)| air.preprocessed_trace())
+            .collect(),
+        &airs_and_traces
+            .iter()
+            .map(|(_, trace)| log2_strict_usize(trace.height()))
+            .collect_vec(),
+    );
+    prove_multiple_internal(
+        config,
+        airs_and_traces,
+        challenger,
+        public_values,
+        preprocessed.as_ref().map(|(prover_data, _)| prover_data),
+        &interactions,
+    )
+}
+
+/// Like `prove_multiple`, but reuses a commitment to the AIRs' preprocessed traces created with
+/// `setup_multiple_preprocessed`, rather than committing to them again.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_multiple_with_preprocessed<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    airs_and_traces: &[(A, RowMajorMatrix<Val<SC>>)],
+    challenger: &mut SC::Challenger,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&MultiPreprocessedProverData<SC>>,
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let interactions = airs_and_traces.iter().map(|_| vec![]).collect_vec();
+    prove_multiple_internal(
+        config,
+        airs_and_traces,
+        challenger,
+        public_values,
+        preprocessed,
+        &interactions,
+    )
+}
+
+/// Like `prove_multiple_with_preprocessed`, for AIRs with lookups.
+///
+/// Lookups may span several AIRs: sends and receives only need to balance across all of them. Each
+/// AIR's permutation trace ends with its own cumulative sum, which is included in the proof, and
+/// the verifier checks that these sum to zero.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_multiple_with_lookups<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    airs_and_traces: &[(A, RowMajorMatrix<Val<SC>>)],
+    challenger: &mut SC::Challenger,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&MultiPreprocessedProverData<SC>>,
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: LookupAir<Val<SC>>
+        + Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let interactions = airs_and_traces
+        .iter()
+        .map(|(air, _)| air.interactions())
+        .collect_vec();
+    prove_multiple_internal(
+        config,
+        airs_and_traces,
+        challenger,
+        public_values,
+        preprocessed,
+        &interactions,
+    )
+}
+
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+fn prove_multiple_internal<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    airs_and_traces: &[(A, RowMajorMatrix<Val<SC>>)],
+    challenger: &mut SC::Challenger,
+    public_values: &[Val<SC>],
+    preprocessed: Option<&MultiPreprocessedProverData<SC>>,
+    interactions: &[Vec<Interaction<Val<SC>>>],
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert!(!airs_and_traces.is_empty(), "no AIRs to prove");
+    for (air, _) in airs_and_traces {
+        assert_window_size::<Val<SC>, _>(air);
+    }
+
+    #[cfg(debug_assertions)]
+    for (air, trace) in airs_and_traces {
+        crate::check_constraints::check_constraints(
+            air,
+            air.preprocessed_trace().as_ref(),
+            trace,
+            public_values,
+        );
+    }
+
+    let pcs = config.pcs();
+    let log_degrees = airs_and_traces
+        .iter()
+        .map(|(_, trace)| log2_strict_usize(trace.height()))
+        .collect_vec();
+    if let Some(preprocessed) = preprocessed {
+        assert_eq!(
+            preprocessed.degree_bits, log_degrees,
+            "preprocessed traces must have the same heights as the main traces"
+        );
+    }
+    let preprocessed_widths = (0..airs_and_traces.len())
+        .map(|i| preprocessed.and_then(|preprocessed| preprocessed.widths[i]))
+        .collect_vec();
+    // The AIRs with preprocessed traces, whose preprocessed traces are committed together, in
+    // order.
+    let preprocessed_airs = (0..airs_and_traces.len())
+        .filter(|&i| preprocessed_widths[i].is_some())
+        .collect_vec();
+    let trace_domains = log_degrees
+        .iter()
+        .map(|&log_degree| pcs.natural_domain_for_degree(1 << log_degree))
+        .collect_vec();
+    let (constraint_counts, log_quotient_degrees): (Vec<_>, Vec<_>) =
+        izip!(airs_and_traces, &preprocessed_widths, interactions)
+            .map(|((air, _), preprocessed_width, interactions)| {
+                let symbolic_constraints = get_symbolic_constraints::<Val<SC>, A>(
+                    air,
+                    preprocessed_width.unwrap_or(0),
+                    public_values.len(),
+                );
+                let mut constraint_degree = symbolic_constraints
+                    .iter()
+                    .map(SymbolicExpression::degree_multiple)
+                    .max()
+                    .unwrap_or(0);
+                if !interactions.is_empty() {
+                    constraint_degree = constraint_degree.max(LOGUP_CONSTRAINT_DEGREE);
+                }
+                (
+                    symbolic_constraints.len() + num_logup_constraints(interactions),
+                    log_quotient_degree(config, constraint_degree),
+                )
+            })
+            .unzip();
+
+    let (trace_commit, trace_data) = info_span!("commit to trace data").in_scope(|| {
+        config.commit_traces(
+            izip!(&log_degrees, airs_and_traces)
+                .map(|(&log_degree, (_, trace))| {
+                    (
+                        pcs.natural_domain_for_degree(1 << log_degree),
+                        trace.clone(),
+                    )
+                })
+                .collect_vec(),
+        )
+    });
+
+    // Observe the instance.
+    challenger.observe(Val::<SC>::from_canonical_usize(airs_and_traces.len()));
+    for &log_degree in &log_degrees {
+        challenger.observe(Val::<SC>::from_canonical_usize(log_degree));
+    }
+    if let Some(preprocessed) = preprocessed {
+        challenger.observe(preprocessed.commitment.clone());
+    }
+
+    challenger.observe(trace_commit.clone());
+    challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
+    challenger.observe_slice(public_values);
+
+    // The AIRs with lookups, whose permutation traces are committed together, in order.
+    let lookup_airs = (0..airs_and_traces.len())
+        .filter(|&i| !interactions[i].is_empty())
+        .collect_vec();
+    let mut permutation_challenges = vec![];
+    let mut cumulative_sums = vec![SC::Challenge::ZERO; airs_and_traces.len()];
+    let permutation = (!lookup_airs.is_empty()).then(|| {
+        let beta: SC::Challenge = challenger.sample_ext_element();
+        let gamma: SC::Challenge = challenger.sample_ext_element();
+        permutation_challenges = vec![beta, gamma];
+        let permutation_traces = info_span!("generate permutation traces").in_scope(|| {
+            lookup_airs
+                .iter()
+                .map(|&i| {
+                    let (air, trace) = &airs_and_traces[i];
+                    generate_logup_trace(
+                        &interactions[i],
+                        air.preprocessed_trace().as_ref(),
+                        trace,
+                        beta,
+                        gamma,
+                    )
+                })
+                .collect_vec()
+        });
+        for (&i, permutation_trace) in izip!(&lookup_airs, &permutation_traces) {
+            cumulative_sums[i] = *permutation_trace.values.last().unwrap();
+        }
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            cumulative_sums.iter().copied().sum::<SC::Challenge>(),
+            SC::Challenge::ZERO,
+            "lookup sends and receives do not balance"
+        );
+        let (permutation_commit, permutation_data) = info_span!("commit to permutation traces")
+            .in_scope(|| {
+                config.commit_traces(
+                    izip!(&lookup_airs, permutation_traces)
+                        .map(|(&i, permutation_trace)| {
+                            (
+                                pcs.natural_domain_for_degree(1 << log_degrees[i]),
+                                permutation_trace.flatten_to_base(),
+                            )
+                        })
+                        .collect_vec(),
+                )
+            });
+        challenger.observe(permutation_commit.clone());
+        for &cumulative_sum in &cumulative_sums {
+            challenger.observe_ext_element(cumulative_sum);
+        }
+        (permutation_commit, permutation_data)
+    });
+    let batching_challenges = sample_batching_challenges(
+        config,
+        challenger,
+        constraint_counts.iter().copied().max().unwrap_or(0),
+    );
+
+    let mut quotients = vec![];
+    for (i, (air, _)) in airs_and_traces.iter().enumerate() {
+        let quotient_degree = 1 << log_quotient_degrees[i];
+        let quotient_domain = trace_domains[i]
+            .create_disjoint_domain(1 << (log_degrees[i] + log_quotient_degrees[i]));
+        let trace_on_quotient_domain =
+            pcs.get_evaluations_on_domain(&trace_data, i, quotient_domain);
+        let preprocessed_on_quotient_domain = preprocessed_airs
+            .iter()
+            .position(|&j| j == i)
+            .zip(preprocessed)
+            .map(|(j, preprocessed)| {
+                pcs.get_evaluations_on_domain(&preprocessed.prover_data, j, quotient_domain)
+            });
+        let permutation_on_quotient_domain = lookup_airs
+            .iter()
+            .position(|&j| j == i)
+            .zip(permutation.as_ref())
+            .map(|(j, (_, permutation_data))| {
+                pcs.get_evaluations_on_domain(permutation_data, j, quotient_domain)
+            });
+        let quotient_values = quotient_values(
+            air,
+            public_values,
+            trace_domains[i],
+            quotient_domain,
+            air.window_size(),
+            preprocessed_on_quotient_domain,
+            trace_on_quotient_domain,
+            permutation_on_quotient_domain,
+            &interactions[i],
+            &permutation_challenges,
+            cumulative_sums[i],
+            &config
+                .constraint_batching()
+                .coefficients(&batching_challenges, constraint_counts[i]),
+            config.quotient_chunk_size(),
+        );
+        let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
+        quotients.push((quotient_domain, quotient_flat, quotient_degree));
+    }
+    let num_quotient_chunks = quotients
+        .iter()
+        .map(|&(_, _, num_chunks)| num_chunks)
+        .sum::<usize>();
+
+    let (quotient_commit, quotient_data) = info_span!("commit to quotient poly chunks")
+        .in_scope(|| config.commit_quotients(quotients));
+    challenger.observe(quotient_commit.clone());
+
+    let zeta: SC::Challenge = challenger.sample();
+    let zeta_and_next = |domain: &Domain<SC>| window_points::<SC>(domain, zeta, 2);
+
+    let mut rounds = vec![
+        (
+            &trace_data,
+            izip!(airs_and_traces, &trace_domains)
+                .map(|((air, _), domain)| window_points::<SC>(domain, zeta, air.window_size()))
+                .collect_vec(),
+        ),
+        (
+            &quotient_data,
+            // open every chunk at zeta
+            (0..num_quotient_chunks).map(|_| vec![zeta]).collect_vec(),
+        ),
+    ];
+    if let Some(preprocessed) = preprocessed {
+        rounds.push((
+            &preprocessed.prover_data,
+            preprocessed_airs
+                .iter()
+                .map(|&i| {
+                    let window_size = airs_and_traces[i].0.window_size();
+                    window_points::<SC>(&trace_domains[i], zeta, window_size)
+                })
+                .collect_vec(),
+        ));
+    }
+    if let Some((_, permutation_data)) = &permutation {
+        rounds.push((
+            permutation_data,
+            lookup_airs
+                .iter()
+                .map(|&i| zeta_and_next(&trace_domains[i]))
+                .collect_vec(),
+        ));
+    }
+    let (opened_values, opening_proof) =
+        info_span!("open").in_scope(|| pcs.open(rounds, challenger));
+    let permutation_round = if preprocessed.is_some() { 3 } else { 2 };
+    let mut quotient_openings = opened_values[1].iter();
+    let opened_values = izip!(&opened_values[0], &log_quotient_degrees)
+        .enumerate()
+        .map(|(i, (trace_opening, &log_quotient_degree))| {
+            let preprocessed_opening = preprocessed_airs
+                .iter()
+                .position(|&j| j == i)
+                .map(|j| &opened_values[2][j]);
+            let permutation_opening = lookup_airs
+                .iter()
+                .position(|&j| j == i)
+                .map(|j| &opened_values[permutation_round][j]);
+            OpenedValues {
+                preprocessed_local: preprocessed_opening.map(|opening| opening[0].clone()),
+                preprocessed_next: preprocessed_opening.map(|opening| opening[1].clone()),
+                preprocessed_after_next: preprocessed_opening
+                    .map_or_else(Vec::new, |opening| opening[2..].to_vec()),
+                trace_local: trace_opening[0].clone(),
+                trace_next: trace_opening[1].clone(),
+                trace_after_next: trace_opening[2..].to_vec(),
+                permutation_local: permutation_opening.map(|opening| opening[0].clone()),
+                permutation_next: permutation_opening.map(|opening| opening[1].clone()),
+                quotient_chunks: quotient_openings
+                    .by_ref()
+                    .take(1 << log_quotient_degree)
+                    .map(|v| v[0].clone())
+                    .collect_vec(),
+            }
+        })
+        .collect_vec();
+    MultiProof {
+        commitments: Commitments {
+            trace: trace_commit,
+            permutation: permutation.map(|(permutation_commit, _)| permutation_commit),
+            quotient_chunks: quotient_commit,
+        },
+        opened_values,
+        cumulative_sums: if lookup_airs.is_empty() {
+            vec![]
+        } else {
+            cumulative_sums
+        },
+        opening_proof,
+        degree_bits: log_degrees,
+    }
+}
+
+/// The log2 of the number of quotient chunks for constraints of degree `constraint_degree`.
+///
+/// In zero-knowledge mode the trace polynomials have twice the degree, and so does the quotient.
+pub(crate) fn log_quotient_degree<SC: StarkGenericConfig>(
+    config: &SC,
+    constraint_degree: usize,
+) -> usize {
+    // As in `get_log_quotient_degree`, pad to at least degree 2.
+    let constraint_degree = constraint_degree.max(2);
+    let min_log_quotient_degree = if config.is_zk() {
+        log2_ceil_usize(2 * constraint_degree - 1)
+    } else {
+        log2_ceil_usize(constraint_degree - 1)
+    };
+    match config.fixed_log_quotient_degree() {
+        Some(log_quotient_degree) => {
+            assert!(
+                log_quotient_degree >= min_log_quotient_degree,
+                "the fixed quotient degree is too small for constraints of degree {constraint_degree}"
+            );
+            log_quotient_degree
+        }
+        None => min_log_quotient_degree,
+    }
+}
+
+/// Sample the challenges with which `config.constraint_batching()` combines up to
+/// `max_constraint_count` constraints.
+pub(crate) fn sample_batching_challenges<SC: StarkGenericConfig>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    max_constraint_count: usize,
+) -> Vec<SC::Challenge> {
+    (0..config
+        .constraint_batching()
+        .num_challenges(max_constraint_count))
+        .map(|_| challenger.sample_ext_element())
+        .collect()
+}
+
+/// The domain over which a trace of height `degree` is committed.
+///
+/// In zero-knowledge mode, this has twice the size of the trace domain, which it contains.
+pub(crate) fn committed_trace_domain<SC: StarkGenericConfig>(
+    config: &SC,
+    degree: usize,
+) -> Domain<SC> {
+    let pcs = config.pcs();
+    if config.is_zk() {
+        pcs.natural_domain_for_degree(2 * degree)
+    } else {
+        pcs.natural_domain_for_degree(degree)
+    }
+}
+
+/// Panic if `air` has a window of fewer than two rows, see `BaseAir::window_size`.
+fn assert_window_size<F, A: BaseAir<F>>(air: &A) {
+    assert!(
+        air.window_size() >= 2,
+        "the window of an AIR must have at least two rows"
+    );
+}
+
+/// `point` followed by its successors in `domain`, one for each row of a window of `window_size`
+/// rows.
+pub(crate) fn window_points<SC: StarkGenericConfig>(
+    domain: &Domain<SC>,
+    point: SC::Challenge,
+    window_size: usize,
+) -> Vec<SC::Challenge> {
+    iter::successors(Some(point), |&point| domain.next_point(point))
+        .take(window_size)
+        .collect()
+}
+
 #[instrument(name = "compute quotient polynomial", skip_all)]
-fn quotient_values<SC, A, Mat>(
+#[allow(clippy::too_many_arguments)]
+fn quotient_values<SC, A, PMat, Mat, PermMat>(
     air: &A,
-    public_values: &Vec<Val<SC>>,
+    public_values: &[Val<SC>],
     trace_domain: Domain<SC>,
     quotient_domain: Domain<SC>,
+    window_size: usize,
+    preprocessed_on_quotient_domain: Option<PMat>,
     trace_on_quotient_domain: Mat,
-    alpha: SC::Challenge,
-    constraint_count: usize,
+    permutation_on_quotient_domain: Option<PermMat>,
+    interactions: &[Interaction<Val<SC>>],
+    permutation_challenges: &[SC::Challenge],
+    cumulative_sum: SC::Challenge,
+    constraint_coefficients: &[SC::Challenge],
+    chunk_size: usize,
 ) -> Vec<SC::Challenge>
 where
     SC: StarkGenericConfig,
     A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    PMat: Matrix<Val<SC>> + Sync,
     Mat: Matrix<Val<SC>> + Sync,
+    PermMat: Matrix<Val<SC>> + Sync,
 {
     let quotient_size = quotient_domain.size();
     let width = trace_on_quotient_domain.width();
+    let preprocessed_width = preprocessed_on_quotient_domain
+        .as_ref()
+        .map_or(0, |preprocessed| preprocessed.width());
+    let permutation_width = logup_trace_width(interactions);
+    let permutation_challenges = permutation_challenges
+        .iter()
+        .map(|&challenge| PackedChallenge::<SC>::from_f(challenge))
+        .collect_vec();
     let mut sels = trace_domain.selectors_on_coset(quotient_domain);
 
     let qdb = log2_strict_usize(quotient_domain.size()) - log2_strict_usize(trace_domain.size());
     let next_step = 1 << qdb;
 
+    // The selector for windows of `size` rows must vanish on the last `size - 1` rows, so we take
+    // the product of `is_transition` at each of the first `size - 1` rows of the window.
+    let mut is_transition_windows: Vec<Vec<Val<SC>>> = vec![];
+    for size in 3..=window_size {
+        let smaller_window = is_transition_windows.last().unwrap_or(&sels.is_transition);
+        let is_transition_window = (0..quotient_size)
+            .map(|i| {
+                smaller_window[i] * sels.is_transition[(i + (size - 2) * next_step) % quotient_size]
+            })
+            .collect();
+        is_transition_windows.push(is_transition_window);
+    }
+
+    // Periodic columns are never committed to, so we evaluate them on the quotient domain directly.
+    let mut periodic_columns = air
+        .periodic_columns()
+        .iter()
+        .map(|column| trace_domain.periodic_on_coset(column, quotient_domain))
+        .collect_vec();
+
     // We take PackedVal::<SC>::WIDTH worth of values at a time from a quotient_size slice, so we need to
     // pad with default values in the case where quotient_size is smaller than PackedVal::<SC>::WIDTH.
     for _ in quotient_size..PackedVal::<SC>::WIDTH {
@@ -152,49 +1075,126 @@ where
         sels.is_last_row.push(Val::<SC>::default());
         sels.is_transition.push(Val::<SC>::default());
         sels.inv_zeroifier.push(Val::<SC>::default());
+        for is_transition_window in &mut is_transition_windows {
+            is_transition_window.push(Val::<SC>::default());
+        }
+        for periodic_column in &mut periodic_columns {
+            periodic_column.push(Val::<SC>::default());
+        }
     }
 
-    let mut alpha_powers = alpha.powers().take(constraint_count).collect_vec();
-    alpha_powers.reverse();
+    // Each task evaluates the constraints on a contiguous chunk of rows, one packed lane at a
+    // time, reusing its buffers for the packed rows from one lane to the next.
+    let chunk_size = chunk_size.max(1).next_multiple_of(PackedVal::<SC>::WIDTH);
+    let mut quotient_values = SC::Challenge::zero_vec(quotient_size.max(PackedVal::<SC>::WIDTH));
+    quotient_values
+        .par_chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(chunk_index, chunk)| {
+            let mut preprocessed_rows = Vec::new();
+            let mut main_rows = Vec::new();
+            let mut permutation_rows = Vec::new();
+            let mut is_transition_window_values = Vec::with_capacity(is_transition_windows.len());
+            let mut periodic_values = Vec::with_capacity(periodic_columns.len());
 
-    (0..quotient_size)
-        .into_par_iter()
-        .step_by(PackedVal::<SC>::WIDTH)
-        .flat_map_iter(|i_start| {
-            let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
+            for (lane_index, lane) in chunk
+                .chunks_exact_mut(PackedVal::<SC>::WIDTH)
+                .enumerate()
+            {
+                let i_start = chunk_index * chunk_size + lane_index * PackedVal::<SC>::WIDTH;
+                let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
+                let pack = |values: &[Val<SC>]| *PackedVal::<SC>::from_slice(&values[i_range.clone()]);
 
-            let is_first_row = *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
-            let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
-            let is_transition = *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
-            let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
+                is_transition_window_values.clear();
+                is_transition_window_values
+                    .extend(is_transition_windows.iter().map(|sel| pack(sel)));
+                periodic_values.clear();
+                periodic_values.extend(periodic_columns.iter().map(|column| pack(column)));
 
-            let main = RowMajorMatrix::new(
-                trace_on_quotient_domain.vertically_packed_row_pair(i_start, next_step),
-                width,
-            );
+                preprocessed_rows.clear();
+                if let Some(preprocessed) = &preprocessed_on_quotient_domain {
+                    extend_with_packed_row_window(
+                        &mut preprocessed_rows,
+                        preprocessed,
+                        i_start,
+                        next_step,
+                        window_size,
+                    );
+                }
+                main_rows.clear();
+                extend_with_packed_row_window(
+                    &mut main_rows,
+                    &trace_on_quotient_domain,
+                    i_start,
+                    next_step,
+                    window_size,
+                );
+                permutation_rows.clear();
+                if let Some(permutation) = &permutation_on_quotient_domain {
+                    permutation_rows.extend(
+                        permutation
+                            .vertically_packed_row_pair::<PackedVal<SC>>(i_start, next_step)
+                            .chunks_exact(<SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D)
+                            .map(|coeffs| {
+                                <PackedChallenge<SC> as FieldExtensionAlgebra<PackedVal<SC>>>::from_base_slice(coeffs)
+                            }),
+                    );
+                }
 
-            let accumulator = PackedChallenge::<SC>::ZERO;
-            let mut folder = ProverConstraintFolder {
-                main: main.as_view(),
-                public_values,
-                is_first_row,
-                is_last_row,
-                is_transition,
-                alpha_powers: &alpha_powers,
-                accumulator,
-                constraint_index: 0,
-            };
-            air.eval(&mut folder);
+                let mut folder = ProverConstraintFolder {
+                    preprocessed: RowMajorMatrixView::new(&preprocessed_rows, preprocessed_width),
+                    main: RowMajorMatrixView::new(&main_rows, width),
+                    permutation: RowMajorMatrixView::new(&permutation_rows, permutation_width),
+                    permutation_challenges: &permutation_challenges,
+                    public_values,
+                    periodic_values: &periodic_values,
+                    is_first_row: pack(&sels.is_first_row),
+                    is_last_row: pack(&sels.is_last_row),
+                    is_transition: pack(&sels.is_transition),
+                    is_transition_windows: &is_transition_window_values,
+                    constraint_coefficients,
+                    accumulator: PackedChallenge::<SC>::ZERO,
+                    constraint_index: 0,
+                };
+                air.eval(&mut folder);
+                eval_logup(
+                    &mut folder,
+                    interactions,
+                    PackedChallenge::<SC>::from_f(cumulative_sum),
+                );
 
-            // quotient(x) = constraints(x) / Z_H(x)
-            let quotient = folder.accumulator * inv_zeroifier;
+                // quotient(x) = constraints(x) / Z_H(x)
+                let quotient = folder.accumulator * pack(&sels.inv_zeroifier);
 
-            // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
-            (0..core::cmp::min(quotient_size, PackedVal::<SC>::WIDTH)).map(move |idx_in_packing| {
-                SC::Challenge::from_base_fn(|coeff_idx| {
-                    quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing]
-                })
-            })
-        })
-        .collect()
+                // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
+                for (idx_in_packing, value) in lane.iter_mut().enumerate() {
+                    *value = SC::Challenge::from_base_fn(|coeff_idx| {
+                        quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing]
+                    });
+                }
+            }
+        });
+    quotient_values.truncate(quotient_size);
+    quotient_values
+}
+
+/// Append the packed rows `r`, `r + step`, ..., `r + (window_size - 1) step` of `mat` to `buf`, as
+/// `Matrix::vertically_packed_row_pair` packs two rows.
+fn extend_with_packed_row_window<T, P>(
+    buf: &mut Vec<P>,
+    mat: &impl Matrix<T>,
+    r: usize,
+    step: usize,
+    window_size: usize,
+) where
+    T: Send + Sync + Copy,
+    P: PackedValue<Value = T>,
+{
+    if window_size == 2 {
+        buf.extend(mat.vertically_packed_row_pair::<P>(r, step));
+    } else {
+        for i in 0..window_size {
+            buf.extend(mat.vertically_packed_row::<P>(r + i * step));
+        }
+    }
 }
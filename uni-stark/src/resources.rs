@@ -0,0 +1,108 @@
+use alloc::vec;
+use core::mem::size_of;
+
+use p3_air::{Air, BaseAir};
+use p3_commit::Pcs;
+use p3_field::FieldExtensionAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::{Dimensions, Matrix};
+use p3_maybe_rayon::ResourceConfig;
+use p3_util::log2_strict_usize;
+
+use crate::prover::log_quotient_degree;
+use crate::{
+    get_symbolic_constraints, prove, Proof, ProverConstraintFolder, StarkGenericConfig,
+    SymbolicAirBuilder, SymbolicExpression, Val,
+};
+
+/// The error returned when a proof would not fit in its resource budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceError {
+    /// The estimated memory use of the prover exceeds `ResourceConfig::max_memory_bytes`.
+    MemoryBudgetExceeded { estimated: usize, budget: usize },
+}
+
+/// An estimate of the peak memory use, in bytes, of proving `air` over a trace of height
+/// `2^degree_bits`.
+///
+/// This counts the largest allocations of the prover: the trace, the prover data of the commitments
+/// to the trace, the preprocessed trace and the quotient chunks, as estimated by
+/// `Pcs::prover_data_size_hint`, e.g. their low-degree extensions and Merkle trees, and the
+/// quotient values. It assumes the PCS gives the evaluations of the traces over the quotient
+/// domain from its prover data, as FRI does when its blowup is at least the quotient degree.
+/// Permutation traces of AIRs with lookups are not included.
+pub fn estimate_prover_memory<SC, A>(config: &SC, air: &A, degree_bits: usize) -> usize
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>> + Air<SymbolicAirBuilder<Val<SC>>>,
+{
+    let width = air.width();
+    let preprocessed_width = air
+        .preprocessed_trace()
+        .map_or(0, |preprocessed| preprocessed.width());
+    let constraint_degree =
+        get_symbolic_constraints::<Val<SC>, A>(air, preprocessed_width, air.num_public_values())
+            .iter()
+            .map(SymbolicExpression::degree_multiple)
+            .max()
+            .unwrap_or(0);
+    let quotient_size = 1 << (degree_bits + log_quotient_degree(config, constraint_degree));
+    let committed_height = if config.is_zk() {
+        2 << degree_bits
+    } else {
+        1 << degree_bits
+    };
+
+    let pcs = config.pcs();
+    let committed = |width| Dimensions {
+        width,
+        height: committed_height,
+    };
+
+    let trace = (width << degree_bits) * size_of::<Val<SC>>();
+    let committed_traces = pcs.prover_data_size_hint(&[committed(width)])
+        + if preprocessed_width > 0 {
+            pcs.prover_data_size_hint(&[committed(preprocessed_width)])
+        } else {
+            0
+        };
+    let quotient = quotient_size * size_of::<SC::Challenge>();
+    // The quotient is committed in chunks of the height of the trace, flattened to the base field.
+    let num_chunks = quotient_size >> degree_bits;
+    let chunk_width = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
+    let committed_quotient = pcs.prover_data_size_hint(&vec![committed(chunk_width); num_chunks]);
+    trace + committed_traces + quotient + committed_quotient
+}
+
+/// Like `prove`, but within the limits of `resources`.
+///
+/// The prover runs on at most `resources.max_threads` threads. Before it starts, its memory use is
+/// estimated with `estimate_prover_memory`, and the proof is refused if that exceeds
+/// `resources.max_memory_bytes`.
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_resources<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &[Val<SC>],
+    resources: &ResourceConfig,
+) -> Result<Proof<SC>, ResourceError>
+where
+    SC: StarkGenericConfig + Sync,
+    SC::Challenger: Send,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>> + Sync,
+    Proof<SC>: Send,
+{
+    if let Some(budget) = resources.max_memory_bytes {
+        let estimated = estimate_prover_memory(config, air, log2_strict_usize(trace.height()));
+        if estimated > budget {
+            return Err(ResourceError::MemoryBudgetExceeded { estimated, budget });
+        }
+    }
+    Ok(resources.install(|| prove(config, air, challenger, trace, public_values)))
+}
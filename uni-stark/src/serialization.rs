@@ -0,0 +1,192 @@
+//! Stable, versioned encodings of proofs and verifying keys.
+//!
+//! The binary encoding is a four byte magic, identifying the kind of proof, and a little-endian
+//! `u16` format version, followed by the proof in the `postcard` wire format. It depends only on
+//! the serde representations of the commitments, field elements and PCS proofs, so proofs can be
+//! moved between builds and machines. With the `json` feature, proofs can also be encoded as JSON,
+//! as an object holding the format version and the proof. Verifying keys have the same binary
+//! encoding, under their own magic.
+
+use alloc::vec::Vec;
+
+use p3_commit::ProofSizeStats;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{MultiProof, Proof, StarkGenericConfig, VerifyingKey};
+
+/// The current version of the proof encodings, which is bumped whenever they change.
+pub const PROOF_FORMAT_VERSION: u16 = 2;
+
+const PROOF_MAGIC: [u8; 4] = *b"P3SP";
+const MULTI_PROOF_MAGIC: [u8; 4] = *b"P3SM";
+const VERIFYING_KEY_MAGIC: [u8; 4] = *b"P3VK";
+pub(crate) const HEADER_LEN: usize = 6;
+
+#[derive(Debug)]
+pub enum ProofDecodingError {
+    /// The bytes do not start with the magic of the expected kind of proof.
+    InvalidMagic,
+    /// The proof was encoded with a different version of the format.
+    UnsupportedVersion(u16),
+    /// The proof itself could not be decoded.
+    Malformed(postcard::Error),
+    /// The proof was followed by unexpected bytes.
+    TrailingBytes,
+}
+
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// Encode this proof in the versioned binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(PROOF_MAGIC, self)
+    }
+
+    /// Decode a proof encoded by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofDecodingError> {
+        decode(PROOF_MAGIC, bytes)
+    }
+
+    /// Encode this proof as JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Result<alloc::string::String> {
+        to_json(self)
+    }
+
+    /// Decode a proof encoded by `to_json`.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        from_json(json)
+    }
+}
+
+impl<SC: StarkGenericConfig> MultiProof<SC> {
+    /// Encode this proof in the versioned binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(MULTI_PROOF_MAGIC, self)
+    }
+
+    /// Decode a proof encoded by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofDecodingError> {
+        decode(MULTI_PROOF_MAGIC, bytes)
+    }
+
+    /// Encode this proof as JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> serde_json::Result<alloc::string::String> {
+        to_json(self)
+    }
+
+    /// Decode a proof encoded by `to_json`.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        from_json(json)
+    }
+}
+
+impl<SC: StarkGenericConfig> VerifyingKey<SC> {
+    /// Encode this key in the versioned binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(VERIFYING_KEY_MAGIC, self)
+    }
+
+    /// Decode a key encoded by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofDecodingError> {
+        decode(VERIFYING_KEY_MAGIC, bytes)
+    }
+}
+
+pub(crate) fn encode<T: Serialize>(magic: [u8; 4], proof: &T) -> Vec<u8> {
+    let mut bytes = magic.to_vec();
+    bytes.extend(PROOF_FORMAT_VERSION.to_le_bytes());
+    bytes.extend(postcard::to_allocvec(proof).expect("proofs are always serializable"));
+    bytes
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(
+    magic: [u8; 4],
+    bytes: &[u8],
+) -> Result<T, ProofDecodingError> {
+    if bytes.len() < HEADER_LEN || bytes[..4] != magic {
+        return Err(ProofDecodingError::InvalidMagic);
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != PROOF_FORMAT_VERSION {
+        return Err(ProofDecodingError::UnsupportedVersion(version));
+    }
+    let (proof, rest) =
+        postcard::take_from_bytes(&bytes[HEADER_LEN..]).map_err(ProofDecodingError::Malformed)?;
+    if !rest.is_empty() {
+        return Err(ProofDecodingError::TrailingBytes);
+    }
+    Ok(proof)
+}
+
+/// An upper bound on the length in bytes of the binary encoding of a batch opening with the given
+/// stats, as it appears in the proofs encoded by `to_bytes`.
+///
+/// Field elements serialize as a single integer, which `postcard` varint-encodes, so most take
+/// their maximum length, while small ones take less.
+pub const fn batch_opening_len_bound(stats: &ProofSizeStats) -> usize {
+    let mut longest = stats.num_sequences;
+    if stats.num_opened_values > longest {
+        longest = stats.num_opened_values;
+    }
+    if stats.num_proof_values > longest {
+        longest = stats.num_proof_values;
+    }
+    if stats.num_digests > longest {
+        longest = stats.num_digests;
+    }
+    stats.num_sequences * varint_len(longest)
+        + (stats.num_opened_values + stats.num_proof_values) * max_int_len(stats.value_size)
+        + stats.num_digests * stats.digest_elems * max_int_len(stats.digest_elem_size)
+}
+
+/// The maximum length of the `postcard` encoding of an integer of `size` bytes: bytes are written
+/// as is, and wider integers as varints of 7 bits per byte.
+const fn max_int_len(size: usize) -> usize {
+    match size {
+        1 => 1,
+        size => (size * 8).div_ceil(7),
+    }
+}
+
+/// The length of the `postcard` varint encoding of `n`, e.g. of a sequence length.
+const fn varint_len(n: usize) -> usize {
+    let bits = (usize::BITS - n.leading_zeros()) as usize;
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7)
+    }
+}
+
+/// The JSON encoding of a proof, alongside the format version.
+#[cfg(feature = "json")]
+#[derive(Serialize, serde::Deserialize)]
+struct Versioned<T> {
+    version: u16,
+    proof: T,
+}
+
+#[cfg(feature = "json")]
+fn to_json<T: Serialize>(proof: &T) -> serde_json::Result<alloc::string::String> {
+    serde_json::to_string(&Versioned {
+        version: PROOF_FORMAT_VERSION,
+        proof,
+    })
+}
+
+#[cfg(feature = "json")]
+fn from_json<T: DeserializeOwned>(json: &str) -> serde_json::Result<T> {
+    use serde::de::Error;
+
+    let versioned: Versioned<T> = serde_json::from_str(json)?;
+    if versioned.version != PROOF_FORMAT_VERSION {
+        return Err(serde_json::Error::custom(format_args!(
+            "unsupported proof format version {}, expected {PROOF_FORMAT_VERSION}",
+            versioned.version
+        )));
+    }
+    Ok(versioned.proof)
+}
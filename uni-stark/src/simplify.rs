@@ -0,0 +1,201 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cmp;
+
+use hashbrown::HashMap;
+use p3_field::Field;
+
+use crate::{Entry, SymbolicExpression, SymbolicVariable};
+
+/// Simplify `constraints`, folding constants, pruning multiplications by zero and removing
+/// additions of zero and multiplications by one, so that their degrees and evaluation costs don't
+/// count operations which have no effect.
+///
+/// Structurally equal subexpressions, within or across constraints, are shared in the result, so
+/// that they are evaluated once by e.g. `SymbolicAir`. Constraints which simplify to zero are kept,
+/// so that constraints are still identified by their index.
+pub fn simplify_constraints<F: Field>(
+    constraints: &[SymbolicExpression<F>],
+) -> Vec<SymbolicExpression<F>> {
+    let mut simplifier = Simplifier::new();
+    constraints
+        .iter()
+        .map(|constraint| {
+            let id = simplifier.simplify(constraint);
+            simplifier.exprs[id].as_ref().clone()
+        })
+        .collect()
+}
+
+impl<F: Field> SymbolicExpression<F> {
+    /// Returns an equivalent expression, simplified as in `simplify_constraints`.
+    pub fn simplify(&self) -> Self {
+        let mut simplifier = Simplifier::new();
+        let id = simplifier.simplify(self);
+        simplifier.exprs[id].as_ref().clone()
+    }
+}
+
+/// A simplified expression, whose operands are identified by their index in the simplifier.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Key<F> {
+    Variable(Entry, usize),
+    IsFirstRow,
+    IsLastRow,
+    IsTransition,
+    IsTransitionWindow(usize),
+    Constant(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Mul(usize, usize),
+}
+
+struct Simplifier<F> {
+    /// The distinct simplified expressions, so that equal expressions have equal indices.
+    exprs: Vec<Rc<SymbolicExpression<F>>>,
+    keys: Vec<Key<F>>,
+    ids: HashMap<Key<F>, usize>,
+    /// The index of the simplified form of each expression visited so far, so that subexpressions
+    /// shared by the input are only simplified once.
+    visited: HashMap<*const SymbolicExpression<F>, usize>,
+}
+
+impl<F: Field> Simplifier<F> {
+    fn new() -> Self {
+        Self {
+            exprs: Vec::new(),
+            keys: Vec::new(),
+            ids: HashMap::new(),
+            visited: HashMap::new(),
+        }
+    }
+
+    fn simplify(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        let ptr = expr as *const SymbolicExpression<F>;
+        if let Some(&id) = self.visited.get(&ptr) {
+            return id;
+        }
+        let id = match expr {
+            SymbolicExpression::Variable(v) => self.intern(Key::Variable(v.entry, v.index)),
+            SymbolicExpression::IsFirstRow => self.intern(Key::IsFirstRow),
+            SymbolicExpression::IsLastRow => self.intern(Key::IsLastRow),
+            SymbolicExpression::IsTransition => self.intern(Key::IsTransition),
+            SymbolicExpression::IsTransitionWindow(size) => {
+                self.intern(Key::IsTransitionWindow(*size))
+            }
+            SymbolicExpression::Constant(c) => self.intern(Key::Constant(*c)),
+            SymbolicExpression::Add { x, y, .. } => {
+                let (x, y) = (self.simplify(x), self.simplify(y));
+                self.add(x, y)
+            }
+            SymbolicExpression::Sub { x, y, .. } => {
+                let (x, y) = (self.simplify(x), self.simplify(y));
+                self.sub(x, y)
+            }
+            SymbolicExpression::Neg { x, .. } => {
+                let x = self.simplify(x);
+                self.neg(x)
+            }
+            SymbolicExpression::Mul { x, y, .. } => {
+                let (x, y) = (self.simplify(x), self.simplify(y));
+                self.mul(x, y)
+            }
+        };
+        self.visited.insert(ptr, id);
+        id
+    }
+
+    fn constant(&self, id: usize) -> Option<F> {
+        match self.keys[id] {
+            Key::Constant(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn add(&mut self, x: usize, y: usize) -> usize {
+        match (self.constant(x), self.constant(y)) {
+            (Some(a), Some(b)) => self.intern(Key::Constant(a + b)),
+            (Some(a), _) if a.is_zero() => y,
+            (_, Some(b)) if b.is_zero() => x,
+            _ => self.intern(Key::Add(x, y)),
+        }
+    }
+
+    fn sub(&mut self, x: usize, y: usize) -> usize {
+        match (self.constant(x), self.constant(y)) {
+            (Some(a), Some(b)) => self.intern(Key::Constant(a - b)),
+            (_, Some(b)) if b.is_zero() => x,
+            (Some(a), _) if a.is_zero() => self.neg(y),
+            _ if x == y => self.intern(Key::Constant(F::ZERO)),
+            _ => self.intern(Key::Sub(x, y)),
+        }
+    }
+
+    fn neg(&mut self, x: usize) -> usize {
+        match self.keys[x] {
+            Key::Constant(c) => self.intern(Key::Constant(-c)),
+            Key::Neg(inner) => inner,
+            _ => self.intern(Key::Neg(x)),
+        }
+    }
+
+    fn mul(&mut self, x: usize, y: usize) -> usize {
+        match (self.constant(x), self.constant(y)) {
+            (Some(a), Some(b)) => self.intern(Key::Constant(a * b)),
+            (Some(c), _) | (_, Some(c)) if c.is_zero() => self.intern(Key::Constant(F::ZERO)),
+            (Some(a), _) if a.is_one() => y,
+            (_, Some(b)) if b.is_one() => x,
+            (Some(a), _) if a == F::NEG_ONE => self.neg(y),
+            (_, Some(b)) if b == F::NEG_ONE => self.neg(x),
+            _ => self.intern(Key::Mul(x, y)),
+        }
+    }
+
+    /// Returns the index of the expression given by `key`, adding it if it is new.
+    fn intern(&mut self, key: Key<F>) -> usize {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let expr = match key {
+            Key::Variable(entry, index) => {
+                SymbolicExpression::Variable(SymbolicVariable::new(entry, index))
+            }
+            Key::IsFirstRow => SymbolicExpression::IsFirstRow,
+            Key::IsLastRow => SymbolicExpression::IsLastRow,
+            Key::IsTransition => SymbolicExpression::IsTransition,
+            Key::IsTransitionWindow(size) => SymbolicExpression::IsTransitionWindow(size),
+            Key::Constant(c) => SymbolicExpression::Constant(c),
+            Key::Add(x, y) => SymbolicExpression::Add {
+                x: self.exprs[x].clone(),
+                y: self.exprs[y].clone(),
+                degree_multiple: cmp::max(
+                    self.exprs[x].degree_multiple(),
+                    self.exprs[y].degree_multiple(),
+                ),
+            },
+            Key::Sub(x, y) => SymbolicExpression::Sub {
+                x: self.exprs[x].clone(),
+                y: self.exprs[y].clone(),
+                degree_multiple: cmp::max(
+                    self.exprs[x].degree_multiple(),
+                    self.exprs[y].degree_multiple(),
+                ),
+            },
+            Key::Neg(x) => SymbolicExpression::Neg {
+                x: self.exprs[x].clone(),
+                degree_multiple: self.exprs[x].degree_multiple(),
+            },
+            Key::Mul(x, y) => SymbolicExpression::Mul {
+                x: self.exprs[x].clone(),
+                y: self.exprs[y].clone(),
+                degree_multiple: self.exprs[x].degree_multiple() + self.exprs[y].degree_multiple(),
+            },
+        };
+        let id = self.exprs.len();
+        self.exprs.push(Rc::new(expr));
+        self.keys.push(key.clone());
+        self.ids.insert(key, id);
+        id
+    }
+}
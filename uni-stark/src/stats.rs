@@ -0,0 +1,57 @@
+//! Breakdowns of the size of proofs, and of the work of verifying them.
+
+use p3_commit::{encoded_len, OpeningProofStats, Pcs};
+
+use crate::serialization::HEADER_LEN;
+use crate::{Proof, StarkGenericConfig};
+
+/// Where the bytes of a `Proof` go, as returned by `Proof::stats`.
+///
+/// The byte counts are of the binary encoding of `Proof::to_bytes`, and add up to its length.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProofStats {
+    /// The magic and format version of the encoding, and the degree of the trace.
+    pub header_bytes: usize,
+    /// The commitments to the trace, the permutation trace and the quotient.
+    pub commitment_bytes: usize,
+    /// The values of the committed polynomials opened out of domain.
+    pub opened_value_bytes: usize,
+    /// The opening proof, broken down by the PCS, which also estimates the work of verifying it.
+    pub opening_proof: OpeningProofStats,
+}
+
+impl ProofStats {
+    pub const fn total_bytes(&self) -> usize {
+        self.header_bytes
+            + self.commitment_bytes
+            + self.opened_value_bytes
+            + self.opening_proof.total_bytes()
+    }
+
+    /// The hash and compression calls made by the verifier to check the opening proof.
+    pub const fn verifier_hashes(&self) -> usize {
+        self.opening_proof.verifier_hashes
+    }
+
+    /// The field operations made by the verifier to check the opening proof, not counting the
+    /// evaluation of the constraints, which is the same for every proof of an AIR.
+    pub const fn verifier_field_ops(&self) -> usize {
+        self.opening_proof.verifier_field_ops
+    }
+}
+
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// A breakdown of the size of this proof, and of the work of verifying it, with the opening
+    /// proof broken down by the PCS of `config`.
+    pub fn stats(&self, config: &SC) -> ProofStats {
+        ProofStats {
+            header_bytes: HEADER_LEN + encoded_len(&self.degree_bits),
+            commitment_bytes: encoded_len(&self.commitments),
+            opened_value_bytes: encoded_len(&self.opened_values),
+            opening_proof: <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::proof_stats(
+                config.pcs(),
+                &self.opening_proof,
+            ),
+        }
+    }
+}
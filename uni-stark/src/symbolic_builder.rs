@@ -1,12 +1,13 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, PairBuilder};
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, PairBuilder, PeriodicAirBuilder};
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_util::log2_ceil_usize;
 use tracing::instrument;
 
+use crate::simplify::simplify_constraints;
 use crate::symbolic_expression::SymbolicExpression;
 use crate::symbolic_variable::SymbolicVariable;
 use crate::Entry;
@@ -48,6 +49,40 @@ where
         .unwrap_or(0)
 }
 
+/// A summary of a single constraint, for finding the constraints which determine the quotient
+/// degree, and hence the blowup the PCS needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintProfile {
+    /// The degree of the constraint, as a multiple of the trace length.
+    pub degree: usize,
+    /// The number of multiplications needed to evaluate the constraint, without reusing common
+    /// subexpressions.
+    pub num_multiplications: usize,
+    /// The distinct variables referenced by the constraint, as `(entry, index)` pairs in order.
+    pub variables: Vec<(Entry, usize)>,
+}
+
+/// Returns a `ConstraintProfile` for each of the AIR's constraints, in the order they are asserted.
+#[instrument(name = "profile constraints", skip_all, level = "debug")]
+pub fn get_constraint_profile<F, A>(
+    air: &A,
+    preprocessed_width: usize,
+    num_public_values: usize,
+) -> Vec<ConstraintProfile>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    get_symbolic_constraints(air, preprocessed_width, num_public_values)
+        .iter()
+        .map(|c| ConstraintProfile {
+            degree: c.degree_multiple(),
+            num_multiplications: c.num_multiplications(),
+            variables: c.variables().into_iter().collect(),
+        })
+        .collect()
+}
+
 #[instrument(name = "evaluate constraints symbolically", skip_all, level = "debug")]
 pub fn get_symbolic_constraints<F, A>(
     air: &A,
@@ -58,9 +93,15 @@ where
     F: Field,
     A: Air<SymbolicAirBuilder<F>>,
 {
-    let mut builder = SymbolicAirBuilder::new(preprocessed_width, air.width(), num_public_values);
+    let mut builder = SymbolicAirBuilder::new(
+        preprocessed_width,
+        air.width(),
+        num_public_values,
+        air.window_size(),
+        air.periodic_columns().len(),
+    );
     air.eval(&mut builder);
-    builder.constraints()
+    simplify_constraints(&builder.constraints())
 }
 
 /// An `AirBuilder` for evaluating constraints symbolically, and recording them for later use.
@@ -69,20 +110,26 @@ pub struct SymbolicAirBuilder<F: Field> {
     preprocessed: RowMajorMatrix<SymbolicVariable<F>>,
     main: RowMajorMatrix<SymbolicVariable<F>>,
     public_values: Vec<SymbolicVariable<F>>,
+    periodic_values: Vec<SymbolicVariable<F>>,
     constraints: Vec<SymbolicExpression<F>>,
+    window_size: usize,
 }
 
 impl<F: Field> SymbolicAirBuilder<F> {
-    pub(crate) fn new(preprocessed_width: usize, width: usize, num_public_values: usize) -> Self {
-        let prep_values = [0, 1]
-            .into_iter()
+    pub(crate) fn new(
+        preprocessed_width: usize,
+        width: usize,
+        num_public_values: usize,
+        window_size: usize,
+        num_periodic_columns: usize,
+    ) -> Self {
+        let prep_values = (0..window_size)
             .flat_map(|offset| {
                 (0..preprocessed_width)
                     .map(move |index| SymbolicVariable::new(Entry::Preprocessed { offset }, index))
             })
             .collect();
-        let main_values = [0, 1]
-            .into_iter()
+        let main_values = (0..window_size)
             .flat_map(|offset| {
                 (0..width).map(move |index| SymbolicVariable::new(Entry::Main { offset }, index))
             })
@@ -90,11 +137,16 @@ impl<F: Field> SymbolicAirBuilder<F> {
         let public_values = (0..num_public_values)
             .map(move |index| SymbolicVariable::new(Entry::Public, index))
             .collect();
+        let periodic_values = (0..num_periodic_columns)
+            .map(move |index| SymbolicVariable::new(Entry::Periodic, index))
+            .collect();
         Self {
             preprocessed: RowMajorMatrix::new(prep_values, preprocessed_width),
             main: RowMajorMatrix::new(main_values, width),
             public_values,
+            periodic_values,
             constraints: vec![],
+            window_size,
         }
     }
 
@@ -122,10 +174,15 @@ impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
+        assert!(
+            (2..=self.window_size).contains(&size),
+            "transition windows must have between 2 and {} rows",
+            self.window_size
+        );
         if size == 2 {
             SymbolicExpression::IsTransition
         } else {
-            panic!("uni-stark only supports a window size of 2")
+            SymbolicExpression::IsTransitionWindow(size)
         }
     }
 
@@ -141,6 +198,14 @@ impl<F: Field> AirBuilderWithPublicValues for SymbolicAirBuilder<F> {
     }
 }
 
+impl<F: Field> PeriodicAirBuilder for SymbolicAirBuilder<F> {
+    type PeriodicVar = SymbolicVariable<F>;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        &self.periodic_values
+    }
+}
+
 impl<F: Field> PairBuilder for SymbolicAirBuilder<F> {
     fn preprocessed(&self) -> Self::M {
         self.preprocessed.clone()
@@ -1,12 +1,13 @@
+use alloc::collections::BTreeSet;
 use alloc::rc::Rc;
 use core::cmp;
-use core::fmt::Debug;
+use core::fmt::{Debug, Display, Formatter};
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
 use p3_field::{Field, FieldAlgebra};
 
-use crate::symbolic_variable::SymbolicVariable;
+use crate::symbolic_variable::{Entry, SymbolicVariable};
 
 /// An expression over `SymbolicVariable`s.
 #[derive(Clone, Debug)]
@@ -15,6 +16,9 @@ pub enum SymbolicExpression<F> {
     IsFirstRow,
     IsLastRow,
     IsTransition,
+    /// The selector for windows of the given number of rows, which vanishes on the last
+    /// `size - 1` rows.
+    IsTransitionWindow(usize),
     Constant(F),
     Add {
         x: Rc<Self>,
@@ -45,6 +49,9 @@ impl<F> SymbolicExpression<F> {
             SymbolicExpression::IsFirstRow => 1,
             SymbolicExpression::IsLastRow => 1,
             SymbolicExpression::IsTransition => 0,
+            // The actual degree is `size - 1`, which is small but may not fit in the slack left
+            // by the quotient, so count it as a full multiple of `n`.
+            SymbolicExpression::IsTransitionWindow(_) => 1,
             SymbolicExpression::Constant(_) => 0,
             SymbolicExpression::Add {
                 degree_multiple, ..
@@ -60,6 +67,84 @@ impl<F> SymbolicExpression<F> {
             } => *degree_multiple,
         }
     }
+
+    /// Returns the number of multiplications needed to evaluate this expression, without reusing
+    /// common subexpressions.
+    pub fn num_multiplications(&self) -> usize {
+        match self {
+            SymbolicExpression::Add { x, y, .. } | SymbolicExpression::Sub { x, y, .. } => {
+                x.num_multiplications() + y.num_multiplications()
+            }
+            SymbolicExpression::Neg { x, .. } => x.num_multiplications(),
+            SymbolicExpression::Mul { x, y, .. } => {
+                1 + x.num_multiplications() + y.num_multiplications()
+            }
+            _ => 0,
+        }
+    }
+
+    /// Returns the distinct variables referenced by this expression, as `(entry, index)` pairs.
+    pub fn variables(&self) -> BTreeSet<(Entry, usize)> {
+        let mut variables = BTreeSet::new();
+        self.collect_variables(&mut variables);
+        variables
+    }
+
+    fn collect_variables(&self, variables: &mut BTreeSet<(Entry, usize)>) {
+        match self {
+            SymbolicExpression::Variable(v) => {
+                variables.insert((v.entry, v.index));
+            }
+            SymbolicExpression::Add { x, y, .. }
+            | SymbolicExpression::Sub { x, y, .. }
+            | SymbolicExpression::Mul { x, y, .. } => {
+                x.collect_variables(variables);
+                y.collect_variables(variables);
+            }
+            SymbolicExpression::Neg { x, .. } => x.collect_variables(variables),
+            _ => {}
+        }
+    }
+}
+
+impl<F: Field> SymbolicExpression<F> {
+    /// Formats `self` as an operand of a product or negation, in parentheses unless it is a leaf.
+    fn fmt_operand(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Add { .. } | Self::Sub { .. } | Self::Neg { .. } => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+}
+
+impl<F: Field> Display for SymbolicExpression<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Variable(v) => write!(f, "{v}"),
+            Self::IsFirstRow => write!(f, "is_first_row"),
+            Self::IsLastRow => write!(f, "is_last_row"),
+            Self::IsTransition => write!(f, "is_transition"),
+            Self::IsTransitionWindow(size) => write!(f, "is_transition_window({size})"),
+            Self::Constant(c) => write!(f, "{c}"),
+            Self::Add { x, y, .. } => write!(f, "{x} + {y}"),
+            Self::Sub { x, y, .. } => {
+                write!(f, "{x} - ")?;
+                match **y {
+                    Self::Add { .. } | Self::Sub { .. } => write!(f, "({y})"),
+                    _ => write!(f, "{y}"),
+                }
+            }
+            Self::Neg { x, .. } => {
+                write!(f, "-")?;
+                x.fmt_operand(f)
+            }
+            Self::Mul { x, y, .. } => {
+                x.fmt_operand(f)?;
+                write!(f, " * ")?;
+                y.fmt_operand(f)
+            }
+        }
+    }
 }
 
 impl<F: Field> Default for SymbolicExpression<F> {
@@ -1,15 +1,18 @@
+use core::fmt::{Display, Formatter};
 use core::marker::PhantomData;
 use core::ops::{Add, Mul, Sub};
 
 use p3_field::Field;
+use serde::{Deserialize, Serialize};
 
 use crate::symbolic_expression::SymbolicExpression;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Entry {
     Preprocessed { offset: usize },
     Main { offset: usize },
     Permutation { offset: usize },
+    Periodic,
     Public,
     Challenge,
 }
@@ -33,12 +36,29 @@ impl<F> SymbolicVariable<F> {
 
     pub const fn degree_multiple(&self) -> usize {
         match self.entry {
-            Entry::Preprocessed { .. } | Entry::Main { .. } | Entry::Permutation { .. } => 1,
+            Entry::Preprocessed { .. }
+            | Entry::Main { .. }
+            | Entry::Permutation { .. }
+            | Entry::Periodic => 1,
             Entry::Public | Entry::Challenge => 0,
         }
     }
 }
 
+/// Formats the variable as e.g. `main[1][3]` for column 3 of the next row of the main trace.
+impl<F> Display for SymbolicVariable<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.entry {
+            Entry::Preprocessed { offset } => write!(f, "preprocessed[{offset}][{}]", self.index),
+            Entry::Main { offset } => write!(f, "main[{offset}][{}]", self.index),
+            Entry::Permutation { offset } => write!(f, "permutation[{offset}][{}]", self.index),
+            Entry::Periodic => write!(f, "periodic[{}]", self.index),
+            Entry::Public => write!(f, "public[{}]", self.index),
+            Entry::Challenge => write!(f, "challenge[{}]", self.index),
+        }
+    }
+}
+
 impl<F: Field> From<SymbolicVariable<F>> for SymbolicExpression<F> {
     fn from(value: SymbolicVariable<F>) -> Self {
         SymbolicExpression::Variable(value)
@@ -0,0 +1,93 @@
+//! A small BabyBear config shared by the tests of this crate and of crates defining AIRs, and a
+//! Fibonacci AIR for the tests of crates that only need some AIR to prove.
+//!
+//! The config hashes and samples challenges with Poseidon2, and uses the FRI parameters of
+//! `create_test_fri_config`, which keep tests fast but give no meaningful security.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{create_test_fri_config, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use rand::Rng;
+
+use crate::StarkConfig;
+
+pub type Val = BabyBear;
+pub type Perm = Poseidon2BabyBear<16>;
+pub type ValHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+pub type ValCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+pub type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, ValHash, ValCompress, 8>;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+pub type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+pub type Dft = Radix2DitParallel<Val>;
+pub type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+pub type TestConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+/// A PCS with a random Poseidon2 permutation, along with that permutation, with which challengers
+/// are created.
+pub fn test_pcs<R: Rng>(rng: &mut R) -> (Pcs, Perm) {
+    let perm = Perm::new_from_rng_128(rng);
+    let hash = ValHash::new(perm.clone());
+    let compress = ValCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    (pcs, perm)
+}
+
+/// A config over `test_pcs`, along with its permutation, with which challengers are created.
+pub fn test_config<R: Rng>(rng: &mut R) -> (TestConfig, Perm) {
+    let (pcs, perm) = test_pcs(rng);
+    (TestConfig::new(pcs), perm)
+}
+
+/// Proves that the last row of a Fibonacci sequence starting with two public values is the third.
+pub struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, x) = (pis[0], pis[1], pis[2]);
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local[0], a);
+        when_first_row.assert_eq(local[1], b);
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(next[0], local[1]);
+        when_transition.assert_eq(next[1], local[0] + local[1]);
+        builder.when_last_row().assert_eq(local[1], x);
+    }
+}
+
+/// A trace of `FibonacciAir` of `n` rows, starting with `a` and `b`, and its public values.
+pub fn fibonacci_trace<F: Field>(a: F, b: F, n: usize) -> (RowMajorMatrix<F>, Vec<F>) {
+    let mut values = vec![a, b];
+    for i in 1..n {
+        values.push(values[2 * i - 1]);
+        values.push(values[2 * i - 2] + values[2 * i - 1]);
+    }
+    let pis = vec![values[0], values[1], values[2 * n - 1]];
+    (RowMajorMatrix::new(values, 2), pis)
+}
@@ -1,17 +1,25 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use itertools::Itertools;
-use p3_air::{Air, BaseAir};
+use itertools::{izip, Itertools};
+use p3_air::{
+    eval_logup, logup_trace_width, num_logup_constraints, Air, BaseAir, Interaction, LookupAir,
+    LOGUP_CONSTRAINT_DEGREE,
+};
 use p3_challenger::{CanObserve, CanSample, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
+use p3_matrix::Matrix;
 use tracing::instrument;
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
-use crate::{PcsError, Proof, StarkGenericConfig, Val, VerifierConstraintFolder};
+use crate::prover::{self, committed_trace_domain, sample_batching_challenges, window_points};
+use crate::symbolic_builder::{get_symbolic_constraints, SymbolicAirBuilder};
+use crate::{
+    Domain, MultiPreprocessedVerifierKey, MultiProof, OpenedValues, PcsError,
+    PreprocessedVerifierKey, Proof, StarkGenericConfig, Val, VerifierConstraintFolder,
+};
 
 #[instrument(skip_all)]
 pub fn verify<SC, A>(
@@ -19,11 +27,122 @@ pub fn verify<SC, A>(
     air: &A,
     challenger: &mut SC::Challenger,
     proof: &Proof<SC>,
-    public_values: &Vec<Val<SC>>,
-) -> Result<(), VerificationError<PcsError<SC>>>
+    public_values: &[Val<SC>],
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    verify_with_report(config, air, challenger, proof, public_values).into_result()
+}
+
+/// Like `verify`, but rather than stopping at the first failure, goes on to collect every failure
+/// it can find.
+///
+/// The opening proof and the constraints are both checked even if the other fails, but nothing is
+/// checked past a proof of the wrong shape.
+#[instrument(skip_all)]
+pub fn verify_with_report<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Val<SC>],
+) -> VerificationReport<PcsError<SC>, SC::Challenge>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let mut report = VerificationReport::default();
+    verify_internal(
+        config,
+        air,
+        challenger,
+        proof,
+        public_values,
+        None,
+        &[],
+        &mut report,
+    );
+    report
+}
+
+/// Like `verify`, for an AIR with a preprocessed trace, checked against the commitment to it
+/// created with `setup_preprocessed`.
+///
+/// `verify` rejects proofs of such AIRs, as the verifier never commits to the preprocessed trace
+/// itself.
+#[instrument(skip_all)]
+pub fn verify_with_preprocessed<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&PreprocessedVerifierKey<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let mut report = VerificationReport::default();
+    verify_internal(
+        config,
+        air,
+        challenger,
+        proof,
+        public_values,
+        preprocessed_vk,
+        &[],
+        &mut report,
+    );
+    report.into_result()
+}
+
+/// Verify a proof of an AIR with lookups, produced by `prove_with_lookups`, given the verifier key
+/// of its preprocessed trace if it has one.
+#[instrument(skip_all)]
+pub fn verify_with_lookups<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&PreprocessedVerifierKey<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: LookupAir<Val<SC>>
+        + Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let mut report = VerificationReport::default();
+    verify_internal(
+        config,
+        air,
+        challenger,
+        proof,
+        public_values,
+        preprocessed_vk,
+        &air.interactions(),
+        &mut report,
+    );
+    report.into_result()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_internal<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&PreprocessedVerifierKey<SC>>,
+    interactions: &[Interaction<Val<SC>>],
+    report: &mut VerificationReport<PcsError<SC>, SC::Challenge>,
+) where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
 {
     let Proof {
         commitments,
@@ -32,26 +151,55 @@ where
         degree_bits,
     } = proof;
 
+    let window_size = <A as BaseAir<Val<SC>>>::window_size(air);
+    if window_size < 2 {
+        report.push_shape_error(ShapeError::WindowSize { air: 0 });
+        return;
+    }
+    if preprocessed_vk.is_none() && <A as BaseAir<Val<SC>>>::preprocessed_trace(air).is_some() {
+        report
+            .errors
+            .push(VerificationError::MissingPreprocessedKey);
+        return;
+    }
     let degree = 1 << degree_bits;
-    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, public_values.len());
+    let preprocessed_width = preprocessed_vk.map_or(0, |vk| vk.width);
+    let (log_quotient_degree, constraint_count) = constraint_shape::<SC, A>(
+        config,
+        air,
+        preprocessed_width,
+        public_values.len(),
+        interactions,
+    );
     let quotient_degree = 1 << log_quotient_degree;
 
     let pcs = config.pcs();
     let trace_domain = pcs.natural_domain_for_degree(degree);
+    let committed_domain = committed_trace_domain(config, degree);
     let quotient_domain =
         trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
     let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
+    let committed_quotient_chunks_domains =
+        config.committed_quotient_chunk_domains(quotient_domain, quotient_degree);
 
-    let air_width = <A as BaseAir<Val<SC>>>::width(air);
-    let valid_shape = opened_values.trace_local.len() == air_width
-        && opened_values.trace_next.len() == air_width
-        && opened_values.quotient_chunks.len() == quotient_degree
-        && opened_values
-            .quotient_chunks
-            .iter()
-            .all(|qc| qc.len() == <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D);
-    if !valid_shape {
-        return Err(VerificationError::InvalidProofShape);
+    if preprocessed_vk.is_some_and(|vk| vk.degree_bits != *degree_bits) {
+        report.push_shape_error(ShapeError::PreprocessedHeight);
+    }
+    if commitments.permutation.is_some() == interactions.is_empty() {
+        report.push_shape_error(ShapeError::PermutationCommitment);
+    }
+    check_opened_values_shape::<SC>(
+        opened_values,
+        0,
+        <A as BaseAir<Val<SC>>>::width(air),
+        window_size,
+        preprocessed_vk.map(|vk| vk.width),
+        interactions,
+        quotient_degree,
+        report,
+    );
+    if !report.is_ok() {
+        return;
     }
 
     // Observe the instance.
@@ -61,41 +209,595 @@ where
     // Practically speaking though, the only related known attack is from failing to include public
     // values. It's not clear if failing to include other instance data could enable a transcript
     // collision, since most such changes would completely change the set of satisfying witnesses.
+    if let Some(vk) = preprocessed_vk {
+        challenger.observe(vk.commitment.clone());
+    }
 
     challenger.observe(commitments.trace.clone());
+    challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
     challenger.observe_slice(public_values);
-    let alpha: SC::Challenge = challenger.sample_ext_element();
+    let mut permutation_challenges = vec![];
+    if let Some(permutation_commit) = &commitments.permutation {
+        let beta: SC::Challenge = challenger.sample_ext_element();
+        let gamma: SC::Challenge = challenger.sample_ext_element();
+        permutation_challenges = vec![beta, gamma];
+        challenger.observe(permutation_commit.clone());
+    }
+    let batching_challenges = sample_batching_challenges(config, challenger, constraint_count);
     challenger.observe(commitments.quotient_chunks.clone());
 
     let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
-
-    pcs.verify(
-        vec![
-            (
-                commitments.trace.clone(),
-                vec![(
-                    trace_domain,
-                    vec![
-                        (zeta, opened_values.trace_local.clone()),
-                        (zeta_next, opened_values.trace_next.clone()),
-                    ],
-                )],
-            ),
-            (
-                commitments.quotient_chunks.clone(),
-                quotient_chunks_domains
-                    .iter()
-                    .zip(&opened_values.quotient_chunks)
-                    .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
-                    .collect_vec(),
-            ),
-        ],
+    let window = window_points::<SC>(&trace_domain, zeta, window_size);
+    let zeta_next = window[1];
+
+    let mut rounds = vec![
+        (
+            commitments.trace.clone(),
+            vec![(
+                committed_domain,
+                izip!(
+                    window.iter().copied(),
+                    [&opened_values.trace_local, &opened_values.trace_next]
+                        .into_iter()
+                        .chain(&opened_values.trace_after_next)
+                        .cloned()
+                )
+                .collect_vec(),
+            )],
+        ),
+        (
+            commitments.quotient_chunks.clone(),
+            committed_quotient_chunks_domains
+                .iter()
+                .zip(&opened_values.quotient_chunks)
+                .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
+                .collect_vec(),
+        ),
+    ];
+    if let (Some(vk), Some(local), Some(next)) = (
+        preprocessed_vk,
+        &opened_values.preprocessed_local,
+        &opened_values.preprocessed_next,
+    ) {
+        rounds.push((
+            vk.commitment.clone(),
+            vec![(
+                trace_domain,
+                izip!(
+                    window.iter().copied(),
+                    [local, next]
+                        .into_iter()
+                        .chain(&opened_values.preprocessed_after_next)
+                        .cloned()
+                )
+                .collect_vec(),
+            )],
+        ));
+    }
+    if let (Some(permutation_commit), Some(local), Some(next)) = (
+        &commitments.permutation,
+        &opened_values.permutation_local,
+        &opened_values.permutation_next,
+    ) {
+        rounds.push((
+            permutation_commit.clone(),
+            vec![(
+                committed_domain,
+                vec![(zeta, local.clone()), (zeta_next, next.clone())],
+            )],
+        ));
+    }
+    if let Err(error) = pcs.verify(rounds, opening_proof, challenger) {
+        report
+            .errors
+            .push(VerificationError::InvalidOpeningArgument(error));
+    }
+
+    verify_constraints::<SC, A>(
+        air,
+        0,
+        public_values,
+        opened_values,
+        interactions,
+        &permutation_challenges,
+        SC::Challenge::ZERO,
+        trace_domain,
+        &quotient_chunks_domains,
+        zeta,
+        &config
+            .constraint_batching()
+            .coefficients(&batching_challenges, constraint_count),
+        report,
+    );
+}
+
+/// Verify a proof of several AIRs produced by `prove_multiple`, given the same AIRs in the same
+/// order.
+///
+/// Proofs of AIRs with preprocessed traces are rejected; they are checked with
+/// `verify_multiple_with_preprocessed` instead.
+#[instrument(skip_all)]
+pub fn verify_multiple<SC, A>(
+    config: &SC,
+    airs: &[A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Val<SC>],
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    verify_multiple_with_report(config, airs, challenger, proof, public_values).into_result()
+}
+
+/// Like `verify_multiple`, but collects every failure it can find, as `verify_with_report` does.
+#[instrument(skip_all)]
+pub fn verify_multiple_with_report<SC, A>(
+    config: &SC,
+    airs: &[A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Val<SC>],
+) -> VerificationReport<PcsError<SC>, SC::Challenge>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let interactions = airs.iter().map(|_| vec![]).collect_vec();
+    let mut report = VerificationReport::default();
+    verify_multiple_internal(
+        config,
+        airs,
+        challenger,
+        proof,
+        public_values,
+        None,
+        &interactions,
+        &mut report,
+    );
+    report
+}
+
+/// Like `verify_multiple`, for AIRs with preprocessed traces, checked against the commitment to
+/// them created with `setup_multiple_preprocessed`.
+#[instrument(skip_all)]
+pub fn verify_multiple_with_preprocessed<SC, A>(
+    config: &SC,
+    airs: &[A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&MultiPreprocessedVerifierKey<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let interactions = airs.iter().map(|_| vec![]).collect_vec();
+    let mut report = VerificationReport::default();
+    verify_multiple_internal(
+        config,
+        airs,
+        challenger,
+        proof,
+        public_values,
+        preprocessed_vk,
+        &interactions,
+        &mut report,
+    );
+    report.into_result()
+}
+
+/// Verify a proof of several AIRs with lookups, produced by `prove_multiple_with_lookups`, given
+/// the verifier key of their preprocessed traces if any of them has one.
+#[instrument(skip_all)]
+pub fn verify_multiple_with_lookups<SC, A>(
+    config: &SC,
+    airs: &[A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&MultiPreprocessedVerifierKey<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>, SC::Challenge>>
+where
+    SC: StarkGenericConfig,
+    A: LookupAir<Val<SC>>
+        + Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let interactions = airs.iter().map(LookupAir::interactions).collect_vec();
+    let mut report = VerificationReport::default();
+    verify_multiple_internal(
+        config,
+        airs,
+        challenger,
+        proof,
+        public_values,
+        preprocessed_vk,
+        &interactions,
+        &mut report,
+    );
+    report.into_result()
+}
+
+fn verify_multiple_internal<SC, A>(
+    config: &SC,
+    airs: &[A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Val<SC>],
+    preprocessed_vk: Option<&MultiPreprocessedVerifierKey<SC>>,
+    interactions: &[Vec<Interaction<Val<SC>>>],
+    report: &mut VerificationReport<PcsError<SC>, SC::Challenge>,
+) where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let MultiProof {
+        commitments,
+        opened_values,
+        cumulative_sums,
         opening_proof,
+        degree_bits,
+    } = proof;
+
+    // The AIRs with lookups, whose permutation traces are committed together, in order.
+    let lookup_airs = (0..airs.len())
+        .filter(|&i| !interactions[i].is_empty())
+        .collect_vec();
+    let has_lookups = !lookup_airs.is_empty();
+    if airs.is_empty() || opened_values.len() != airs.len() || degree_bits.len() != airs.len() {
+        report.push_shape_error(ShapeError::NumAirs);
+        return;
+    }
+    if commitments.permutation.is_some() != has_lookups {
+        report.push_shape_error(ShapeError::PermutationCommitment);
+    }
+    if cumulative_sums.len() != if has_lookups { airs.len() } else { 0 } {
+        report.push_shape_error(ShapeError::CumulativeSums);
+    }
+    let mut window_sizes_valid = true;
+    for (i, air) in airs.iter().enumerate() {
+        if <A as BaseAir<Val<SC>>>::window_size(air) < 2 {
+            report.push_shape_error(ShapeError::WindowSize { air: i });
+            window_sizes_valid = false;
+        }
+    }
+    if !window_sizes_valid {
+        return;
+    }
+    if preprocessed_vk.is_none()
+        && airs
+            .iter()
+            .any(|air| <A as BaseAir<Val<SC>>>::preprocessed_trace(air).is_some())
+    {
+        report
+            .errors
+            .push(VerificationError::MissingPreprocessedKey);
+        return;
+    }
+    if let Some(vk) = preprocessed_vk {
+        if vk.widths.len() != airs.len() {
+            report.push_shape_error(ShapeError::NumAirs);
+            return;
+        }
+        if vk.degree_bits != *degree_bits {
+            report.push_shape_error(ShapeError::PreprocessedHeight);
+        }
+    }
+    let preprocessed_widths = (0..airs.len())
+        .map(|i| preprocessed_vk.and_then(|vk| vk.widths[i]))
+        .collect_vec();
+    // The AIRs with preprocessed traces, whose preprocessed traces are committed together, in
+    // order.
+    let preprocessed_airs = (0..airs.len())
+        .filter(|&i| preprocessed_widths[i].is_some())
+        .collect_vec();
+
+    let pcs = config.pcs();
+    let mut trace_domains = Vec::with_capacity(airs.len());
+    let mut quotient_chunks_domains = Vec::with_capacity(airs.len());
+    let mut committed_quotient_chunks_domains = Vec::with_capacity(airs.len());
+    let mut constraint_counts = Vec::with_capacity(airs.len());
+    for (i, (air, opened_values, &degree_bits)) in
+        izip!(airs, opened_values, degree_bits).enumerate()
+    {
+        let (log_quotient_degree, constraint_count) = constraint_shape::<SC, A>(
+            config,
+            air,
+            preprocessed_widths[i].unwrap_or(0),
+            public_values.len(),
+            &interactions[i],
+        );
+        let quotient_degree = 1 << log_quotient_degree;
+        let trace_domain = pcs.natural_domain_for_degree(1 << degree_bits);
+        let quotient_domain =
+            trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
+
+        if interactions[i].is_empty() && cumulative_sums.get(i).is_some_and(|sum| !sum.is_zero()) {
+            report.push_shape_error(ShapeError::CumulativeSums);
+        }
+        check_opened_values_shape::<SC>(
+            opened_values,
+            i,
+            <A as BaseAir<Val<SC>>>::width(air),
+            <A as BaseAir<Val<SC>>>::window_size(air),
+            preprocessed_widths[i],
+            &interactions[i],
+            quotient_degree,
+            report,
+        );
+
+        trace_domains.push(trace_domain);
+        quotient_chunks_domains.push(quotient_domain.split_domains(quotient_degree));
+        committed_quotient_chunks_domains
+            .push(config.committed_quotient_chunk_domains(quotient_domain, quotient_degree));
+        constraint_counts.push(constraint_count);
+    }
+
+    if !report.is_ok() {
+        return;
+    }
+
+    if cumulative_sums.iter().copied().sum::<SC::Challenge>() != SC::Challenge::ZERO {
+        report.errors.push(VerificationError::UnbalancedLookups);
+    }
+
+    // Observe the instance.
+    challenger.observe(Val::<SC>::from_canonical_usize(airs.len()));
+    for &degree_bits in degree_bits {
+        challenger.observe(Val::<SC>::from_canonical_usize(degree_bits));
+    }
+    if let Some(vk) = preprocessed_vk {
+        challenger.observe(vk.commitment.clone());
+    }
+
+    challenger.observe(commitments.trace.clone());
+    challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
+    challenger.observe_slice(public_values);
+    let mut permutation_challenges = vec![];
+    if let Some(permutation_commit) = &commitments.permutation {
+        let beta: SC::Challenge = challenger.sample_ext_element();
+        let gamma: SC::Challenge = challenger.sample_ext_element();
+        permutation_challenges = vec![beta, gamma];
+        challenger.observe(permutation_commit.clone());
+        for &cumulative_sum in cumulative_sums {
+            challenger.observe_ext_element(cumulative_sum);
+        }
+    }
+    let batching_challenges = sample_batching_challenges(
+        config,
         challenger,
+        constraint_counts.iter().copied().max().unwrap_or(0),
+    );
+    challenger.observe(commitments.quotient_chunks.clone());
+
+    let zeta: SC::Challenge = challenger.sample();
+
+    let mut rounds = vec![
+        (
+            commitments.trace.clone(),
+            izip!(airs, &trace_domains, degree_bits, opened_values)
+                .map(|(air, domain, &degree_bits, values)| {
+                    let window_size = <A as BaseAir<Val<SC>>>::window_size(air);
+                    let window = window_points::<SC>(domain, zeta, window_size);
+                    (
+                        committed_trace_domain(config, 1 << degree_bits),
+                        izip!(
+                            window,
+                            [&values.trace_local, &values.trace_next]
+                                .into_iter()
+                                .chain(&values.trace_after_next)
+                                .cloned()
+                        )
+                        .collect_vec(),
+                    )
+                })
+                .collect_vec(),
+        ),
+        (
+            commitments.quotient_chunks.clone(),
+            izip!(&committed_quotient_chunks_domains, opened_values)
+                .flat_map(|(domains, values)| {
+                    izip!(domains, &values.quotient_chunks)
+                        .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
+                })
+                .collect_vec(),
+        ),
+    ];
+    if let Some(vk) = preprocessed_vk {
+        rounds.push((
+            vk.commitment.clone(),
+            preprocessed_airs
+                .iter()
+                .map(|&i| {
+                    let values = &opened_values[i];
+                    let window_size = <A as BaseAir<Val<SC>>>::window_size(&airs[i]);
+                    (
+                        trace_domains[i],
+                        izip!(
+                            window_points::<SC>(&trace_domains[i], zeta, window_size),
+                            [&values.preprocessed_local, &values.preprocessed_next]
+                                .into_iter()
+                                .map(|row| row.clone().unwrap())
+                                .chain(values.preprocessed_after_next.iter().cloned())
+                        )
+                        .collect_vec(),
+                    )
+                })
+                .collect_vec(),
+        ));
+    }
+    if let Some(permutation_commit) = &commitments.permutation {
+        rounds.push((
+            permutation_commit.clone(),
+            lookup_airs
+                .iter()
+                .map(|&i| {
+                    let domain = trace_domains[i];
+                    let values = &opened_values[i];
+                    (
+                        committed_trace_domain(config, 1 << degree_bits[i]),
+                        vec![
+                            (zeta, values.permutation_local.clone().unwrap()),
+                            (
+                                domain.next_point(zeta).unwrap(),
+                                values.permutation_next.clone().unwrap(),
+                            ),
+                        ],
+                    )
+                })
+                .collect_vec(),
+        ));
+    }
+    if let Err(error) = pcs.verify(rounds, opening_proof, challenger) {
+        report
+            .errors
+            .push(VerificationError::InvalidOpeningArgument(error));
+    }
+
+    for (i, (air, opened_values, trace_domain, quotient_chunks_domains)) in
+        izip!(airs, opened_values, trace_domains, &quotient_chunks_domains).enumerate()
+    {
+        verify_constraints::<SC, A>(
+            air,
+            i,
+            public_values,
+            opened_values,
+            &interactions[i],
+            &permutation_challenges,
+            cumulative_sums.get(i).copied().unwrap_or_default(),
+            trace_domain,
+            quotient_chunks_domains,
+            zeta,
+            &config
+                .constraint_batching()
+                .coefficients(&batching_challenges, constraint_counts[i]),
+            report,
+        );
+    }
+}
+
+/// Check the shapes of the values opened for the AIR at `air_index`, recording each opened matrix of
+/// the wrong shape.
+#[allow(clippy::too_many_arguments)]
+fn check_opened_values_shape<SC: StarkGenericConfig>(
+    opened_values: &OpenedValues<SC::Challenge>,
+    air_index: usize,
+    width: usize,
+    window_size: usize,
+    preprocessed_width: Option<usize>,
+    interactions: &[Interaction<Val<SC>>],
+    quotient_degree: usize,
+    report: &mut VerificationReport<PcsError<SC>, SC::Challenge>,
+) {
+    let d = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
+    let is_window = |local: &[SC::Challenge],
+                     next: &[SC::Challenge],
+                     after_next: &[Vec<SC::Challenge>],
+                     width: usize| {
+        local.len() == width
+            && next.len() == width
+            && after_next.len() == window_size - 2
+            && after_next.iter().all(|row| row.len() == width)
+    };
+
+    let main_valid = is_window(
+        &opened_values.trace_local,
+        &opened_values.trace_next,
+        &opened_values.trace_after_next,
+        width,
+    );
+    let preprocessed_valid = match (
+        preprocessed_width,
+        &opened_values.preprocessed_local,
+        &opened_values.preprocessed_next,
+    ) {
+        (Some(width), Some(local), Some(next)) => {
+            is_window(local, next, &opened_values.preprocessed_after_next, width)
+        }
+        (None, None, None) => opened_values.preprocessed_after_next.is_empty(),
+        _ => false,
+    };
+    let permutation_valid = match (
+        &opened_values.permutation_local,
+        &opened_values.permutation_next,
+    ) {
+        (Some(local), Some(next)) => {
+            let width = logup_trace_width(interactions) * d;
+            !interactions.is_empty() && local.len() == width && next.len() == width
+        }
+        (None, None) => interactions.is_empty(),
+        _ => false,
+    };
+    let quotient_chunks_valid = opened_values.quotient_chunks.len() == quotient_degree
+        && opened_values.quotient_chunks.iter().all(|qc| qc.len() == d);
+
+    for (valid, matrix) in [
+        (preprocessed_valid, OpenedMatrix::Preprocessed),
+        (main_valid, OpenedMatrix::Main),
+        (permutation_valid, OpenedMatrix::Permutation),
+        (quotient_chunks_valid, OpenedMatrix::QuotientChunks),
+    ] {
+        if !valid {
+            report.push_shape_error(ShapeError::OpenedMatrix {
+                air: air_index,
+                matrix,
+            });
+        }
+    }
+}
+
+/// The log2 of the number of quotient chunks of `air`, and its number of constraints, both including
+/// the constraints of its lookups.
+fn constraint_shape<SC, A>(
+    config: &SC,
+    air: &A,
+    preprocessed_width: usize,
+    num_public_values: usize,
+    interactions: &[Interaction<Val<SC>>],
+) -> (usize, usize)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>>,
+{
+    let constraints =
+        get_symbolic_constraints::<Val<SC>, A>(air, preprocessed_width, num_public_values);
+    let mut constraint_degree = constraints
+        .iter()
+        .map(|constraint| constraint.degree_multiple())
+        .max()
+        .unwrap_or(0);
+    if !interactions.is_empty() {
+        constraint_degree = constraint_degree.max(LOGUP_CONSTRAINT_DEGREE);
+    }
+    (
+        prover::log_quotient_degree(config, constraint_degree),
+        constraints.len() + num_logup_constraints(interactions),
     )
-    .map_err(VerificationError::InvalidOpeningArgument)?;
+}
 
+/// Check that the constraints of `air` and of its lookups, combined with `constraint_coefficients`
+/// and evaluated on the values opened at `zeta`, agree with the opened quotient.
+#[allow(clippy::too_many_arguments)]
+fn verify_constraints<SC, A>(
+    air: &A,
+    air_index: usize,
+    public_values: &[Val<SC>],
+    opened_values: &OpenedValues<SC::Challenge>,
+    interactions: &[Interaction<Val<SC>>],
+    permutation_challenges: &[SC::Challenge],
+    cumulative_sum: SC::Challenge,
+    trace_domain: Domain<SC>,
+    quotient_chunks_domains: &[Domain<SC>],
+    zeta: SC::Challenge,
+    constraint_coefficients: &[SC::Challenge],
+    report: &mut VerificationReport<PcsError<SC>, SC::Challenge>,
+) where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
     let zps = quotient_chunks_domains
         .iter()
         .enumerate()
@@ -125,39 +827,199 @@ where
         .sum::<SC::Challenge>();
 
     let sels = trace_domain.selectors_at_point(zeta);
+    // The selector for windows of `size` rows must vanish on the last `size - 1` rows, so we take
+    // the product of `is_transition` at each of the first `size - 1` points of the window.
+    let window_size = opened_values.trace_after_next.len() + 2;
+    let window = window_points::<SC>(&trace_domain, zeta, window_size);
+    let is_transition_windows = (3..=window_size)
+        .scan(sels.is_transition, |is_transition_window, size| {
+            *is_transition_window *= trace_domain
+                .selectors_at_point(window[size - 2])
+                .is_transition;
+            Some(*is_transition_window)
+        })
+        .collect_vec();
+
+    let periodic_columns = air.periodic_columns();
+    if let Some(column) = periodic_columns
+        .iter()
+        .position(|column| !column.len().is_power_of_two() || column.len() > trace_domain.size())
+    {
+        report.push_shape_error(ShapeError::PeriodicColumn {
+            air: air_index,
+            column,
+        });
+        return;
+    }
+    let periodic_values = periodic_columns
+        .iter()
+        .map(|column| trace_domain.periodic_at_point(column, zeta))
+        .collect_vec();
+
+    let preprocessed_width = opened_values
+        .preprocessed_local
+        .as_ref()
+        .map_or(0, Vec::len);
+    let preprocessed_values = opened_values
+        .preprocessed_local
+        .iter()
+        .chain(&opened_values.preprocessed_next)
+        .chain(&opened_values.preprocessed_after_next)
+        .flatten()
+        .copied()
+        .collect_vec();
+    let preprocessed = RowMajorMatrixView::new(&preprocessed_values, preprocessed_width);
+    let main_values = [&opened_values.trace_local, &opened_values.trace_next]
+        .into_iter()
+        .chain(&opened_values.trace_after_next)
+        .flatten()
+        .copied()
+        .collect_vec();
+    let main = RowMajorMatrixView::new(&main_values, opened_values.trace_local.len());
 
-    let main = VerticalPair::new(
-        RowMajorMatrixView::new_row(&opened_values.trace_local),
-        RowMajorMatrixView::new_row(&opened_values.trace_next),
+    // The permutation trace is committed over the base field, one coefficient per column.
+    let unflatten = |values: &Option<Vec<SC::Challenge>>| {
+        values
+            .as_deref()
+            .unwrap_or(&[])
+            .chunks_exact(<SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D)
+            .map(|coeffs| {
+                coeffs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| SC::Challenge::monomial(i) * c)
+                    .sum::<SC::Challenge>()
+            })
+            .collect_vec()
+    };
+    let (permutation_local, permutation_next) = (
+        unflatten(&opened_values.permutation_local),
+        unflatten(&opened_values.permutation_next),
+    );
+    let permutation = VerticalPair::new(
+        RowMajorMatrixView::new_row(&permutation_local),
+        RowMajorMatrixView::new_row(&permutation_next),
     );
 
     let mut folder = VerifierConstraintFolder {
+        preprocessed,
         main,
+        permutation,
+        permutation_challenges,
         public_values,
+        periodic_values: &periodic_values,
         is_first_row: sels.is_first_row,
         is_last_row: sels.is_last_row,
         is_transition: sels.is_transition,
-        alpha,
+        is_transition_windows: &is_transition_windows,
+        constraint_coefficients,
         accumulator: SC::Challenge::ZERO,
+        constraint_index: 0,
     };
     air.eval(&mut folder);
+    eval_logup(&mut folder, interactions, cumulative_sum);
     let folded_constraints = folder.accumulator;
 
     // Finally, check that
     //     folded_constraints(zeta) / Z_H(zeta) = quotient(zeta)
-    if folded_constraints * sels.inv_zeroifier != quotient {
-        return Err(VerificationError::OodEvaluationMismatch);
+    let recomputed = folded_constraints * sels.inv_zeroifier;
+    if recomputed != quotient {
+        report
+            .errors
+            .push(VerificationError::OodEvaluationMismatch {
+                air: air_index,
+                recomputed,
+                claimed: quotient,
+            });
     }
-
-    Ok(())
 }
 
 #[derive(Debug)]
-pub enum VerificationError<PcsErr> {
-    InvalidProofShape,
+pub enum VerificationError<PcsErr, Challenge> {
+    InvalidProofShape(ShapeError),
     /// An error occurred while verifying the claimed openings.
     InvalidOpeningArgument(PcsErr),
     /// Out-of-domain evaluation mismatch, i.e. `constraints(zeta)` did not match
     /// `quotient(zeta) Z_H(zeta)`.
-    OodEvaluationMismatch,
+    OodEvaluationMismatch {
+        /// The index of the AIR whose constraints failed, which is zero for single-AIR proofs.
+        air: usize,
+        /// `constraints(zeta) / Z_H(zeta)`, recomputed from the opened trace values.
+        recomputed: Challenge,
+        /// `quotient(zeta)`, as opened from the quotient chunks.
+        claimed: Challenge,
+    },
+    /// The lookup sends and receives of several AIRs do not balance.
+    UnbalancedLookups,
+    /// The verifying key was created with a config implying a different quotient degree.
+    KeyMismatch,
+    /// The AIR has a preprocessed trace, but no key for it was given. The verifier does not
+    /// commit to preprocessed traces itself, so the key must come from `setup_preprocessed` or
+    /// `setup_keys`.
+    MissingPreprocessedKey,
+}
+
+/// The part of a proof, or of the AIR it is checked against, whose shape is wrong.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShapeError {
+    /// The values opened from a matrix of the AIR at index `air` are missing, unexpected, or have
+    /// the wrong number of rows or columns.
+    OpenedMatrix { air: usize, matrix: OpenedMatrix },
+    /// The preprocessed trace does not have the height of the proof's trace.
+    PreprocessedHeight,
+    /// The proof has a permutation commitment exactly when no AIR has lookups.
+    PermutationCommitment,
+    /// The proof has the wrong number of lookup cumulative sums, or a nonzero sum for an AIR
+    /// without lookups.
+    CumulativeSums,
+    /// The proof is for a different number of AIRs.
+    NumAirs,
+    /// The number of public values differs from the verifying key's.
+    NumPublicValues,
+    /// A chain has a different number of proofs than of statements.
+    NumProofs,
+    /// The length of a periodic column of the AIR at index `air` is not a power of two no larger
+    /// than the trace.
+    PeriodicColumn { air: usize, column: usize },
+    /// The AIR at index `air` has a window of fewer than two rows.
+    WindowSize { air: usize },
+}
+
+/// A matrix whose values are opened in a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpenedMatrix {
+    Preprocessed,
+    Main,
+    Permutation,
+    QuotientChunks,
+}
+
+/// Every failure found while verifying a proof, as returned by `verify_with_report`.
+#[derive(Debug)]
+pub struct VerificationReport<PcsErr, Challenge> {
+    /// The failures, in the order they were found.
+    pub errors: Vec<VerificationError<PcsErr, Challenge>>,
+}
+
+impl<PcsErr, Challenge> VerificationReport<PcsErr, Challenge> {
+    /// Whether the proof was accepted.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The first failure, if any.
+    pub fn into_result(self) -> Result<(), VerificationError<PcsErr, Challenge>> {
+        self.errors.into_iter().next().map_or(Ok(()), Err)
+    }
+
+    fn push_shape_error(&mut self, error: ShapeError) {
+        self.errors
+            .push(VerificationError::InvalidProofShape(error));
+    }
+}
+
+impl<PcsErr, Challenge> Default for VerificationReport<PcsErr, Challenge> {
+    fn default() -> Self {
+        Self { errors: Vec::new() }
+    }
 }
@@ -0,0 +1,143 @@
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{prove_chain, verify, verify_chain, ShapeError, VerificationError};
+use rand::thread_rng;
+
+/// Proves that counting up from the public value `a`, one per row, ends at the public value `x`.
+struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (a, x) = (builder.public_values()[0], builder.public_values()[1]);
+        let (local, next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        builder.when_first_row().assert_eq(local, a);
+        builder
+            .when_transition()
+            .assert_eq(next, local + AB::Expr::ONE);
+        builder.when_last_row().assert_eq(local, x);
+    }
+}
+
+const HEIGHT: u32 = 8;
+
+/// The statement that counting up from `HEIGHT * i` ends at `HEIGHT * (i + 1) - 1`, whose end is
+/// just before the start of the next statement.
+fn statement(i: u32) -> (RowMajorMatrix<Val>, Vec<Val>) {
+    let trace = RowMajorMatrix::new_col(
+        (HEIGHT * i..HEIGHT * (i + 1))
+            .map(Val::from_canonical_u32)
+            .collect(),
+    );
+    let public_values = vec![
+        Val::from_canonical_u32(HEIGHT * i),
+        Val::from_canonical_u32(HEIGHT * (i + 1) - 1),
+    ];
+    (trace, public_values)
+}
+
+#[test]
+fn test_chain() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let statements: Vec<_> = (0..3).map(statement).collect();
+    let public_values: Vec<_> = statements.iter().map(|(_, pis)| pis.clone()).collect();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let (proofs, digest) = prove_chain(&config, &CounterAir, &mut challenger, statements);
+    assert_eq!(proofs.len(), 3);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let verified_digest = verify_chain(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proofs,
+        &public_values,
+    )
+    .expect("verification failed");
+    assert_eq!(verified_digest, digest);
+
+    // A chain which is not extended has the same digest.
+    let mut challenger = Challenger::new(perm.clone());
+    let (_, prefix_digest) = prove_chain(&config, &CounterAir, &mut challenger, [statement(0)]);
+    let mut challenger = Challenger::new(perm.clone());
+    let verified_prefix_digest = verify_chain(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proofs[..1],
+        &public_values[..1],
+    )
+    .expect("verification of a prefix failed");
+    assert_eq!(verified_prefix_digest, prefix_digest);
+    assert_ne!(prefix_digest, digest);
+}
+
+#[test]
+fn test_chain_binding() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let statements: Vec<_> = (0..3).map(statement).collect();
+    let public_values: Vec<_> = statements.iter().map(|(_, pis)| pis.clone()).collect();
+    let mut challenger = Challenger::new(perm.clone());
+    let (proofs, _) = prove_chain(&config, &CounterAir, &mut challenger, statements);
+
+    // A proof from the middle of the chain is only valid after the proofs before it.
+    let mut challenger = Challenger::new(perm.clone());
+    verify(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proofs[1],
+        &public_values[1],
+    )
+    .expect_err("a linked proof should not verify on its own");
+    let mut challenger = Challenger::new(perm.clone());
+    let error = verify_chain(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proofs[1..],
+        &public_values[1..],
+    )
+    .expect_err("a chain without its first proof should be rejected");
+    assert_eq!(error.index, 0);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let error = verify_chain(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proofs,
+        &public_values[..2],
+    )
+    .expect_err("a chain with too many proofs should be rejected");
+    assert_eq!(error.index, 2);
+    assert!(matches!(
+        error.error,
+        VerificationError::InvalidProofShape(ShapeError::NumProofs)
+    ));
+
+    let mut reordered_proofs = proofs;
+    reordered_proofs.swap(1, 2);
+    let mut reordered_public_values = public_values;
+    reordered_public_values.swap(1, 2);
+    let mut challenger = Challenger::new(perm);
+    let error = verify_chain(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &reordered_proofs,
+        &reordered_public_values,
+    )
+    .expect_err("a reordered chain should be rejected");
+    assert_eq!(error.index, 1);
+}
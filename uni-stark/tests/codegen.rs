@@ -0,0 +1,121 @@
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PairBuilder, PeriodicAirBuilder,
+};
+use p3_baby_bear::BabyBear;
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::{debug_constraints, get_symbolic_constraints, SymbolicAir};
+
+mod generated {
+    include!("generated/squares.rs");
+}
+
+/// Proves that the main column holds the squares of the preprocessed column, which counts up from
+/// `offset`, with the last square a public value.
+struct SquaresAir {
+    height: usize,
+    offset: u32,
+}
+
+impl<F: Field> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..self.height as u32)
+            .map(|i| F::from_canonical_u32(self.offset + i))
+            .collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + PairBuilder> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let last = builder.public_values()[0];
+        let c: AB::Expr = preprocessed.row_slice(0)[0].into();
+        let (x, x_next): (AB::Expr, AB::Expr) =
+            (main.row_slice(0)[0].into(), main.row_slice(1)[0].into());
+        builder.assert_eq(x.clone(), c.clone() * c.clone());
+        builder
+            .when_transition()
+            .assert_eq(x_next, x.clone() + c * AB::Expr::TWO + AB::Expr::ONE);
+        builder.when_last_row().assert_eq(x, last);
+    }
+}
+
+/// `SquaresAir`, with its constraints evaluated by generated code.
+struct CompiledSquaresAir(SquaresAir);
+
+impl<F: Field> BaseAir<F> for CompiledSquaresAir {
+    fn width(&self) -> usize {
+        BaseAir::<F>::width(&self.0)
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.0.preprocessed_trace()
+    }
+}
+
+impl<AB> Air<AB> for CompiledSquaresAir
+where
+    AB: AirBuilderWithPublicValues + PairBuilder + PeriodicAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        generated::eval_squares(builder);
+    }
+}
+
+#[test]
+fn test_generated_code_is_up_to_date() {
+    let air = SquaresAir {
+        height: 8,
+        offset: 3,
+    };
+    let code = SymbolicAir::<BabyBear>::new(&air, 1, 1).generate_rust("eval_squares");
+    assert_eq!(code, include_str!("generated/squares.rs"));
+}
+
+#[test]
+fn test_generated_code_matches_air() {
+    let air = SquaresAir {
+        height: 8,
+        offset: 3,
+    };
+    let original = get_symbolic_constraints::<BabyBear, _>(&air, 1, 1);
+    let compiled_air = CompiledSquaresAir(air);
+    let compiled = get_symbolic_constraints::<BabyBear, _>(&compiled_air, 1, 1);
+    assert_eq!(
+        original.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        compiled.iter().map(ToString::to_string).collect::<Vec<_>>()
+    );
+
+    let mut values: Vec<BabyBear> = (3..11)
+        .map(|i| BabyBear::from_canonical_u32(i).square())
+        .collect();
+    let public_values = [values[7]];
+    debug_constraints(
+        &compiled_air,
+        &RowMajorMatrix::new_col(values.clone()),
+        &public_values,
+    )
+    .expect("constraints should hold");
+
+    values[5] += BabyBear::ONE;
+    let failures = debug_constraints(
+        &compiled_air,
+        &RowMajorMatrix::new_col(values),
+        &public_values,
+    )
+    .expect_err("constraints should fail");
+    assert_eq!(
+        failures
+            .iter()
+            .map(|failure| (failure.row, failure.constraint))
+            .collect::<Vec<_>>(),
+        vec![(4, 1), (5, 0), (5, 1)]
+    );
+}
@@ -0,0 +1,155 @@
+use p3_air::{Air, AirBuilder, BaseAir, HorizontalAir, PeriodicAirBuilder, SubAirBuilder};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{prove, verify};
+use rand::thread_rng;
+
+/// A column counting up from zero.
+struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + AB::Expr::ONE);
+    }
+}
+
+/// Asserts that the second column is the square of the first.
+struct SquareAir;
+
+impl<F> BaseAir<F> for SquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for SquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let x: AB::Expr = local[0].into();
+        builder.assert_eq(local[1], x.square());
+    }
+}
+
+/// Asserts that the columns are `x`, `x^2` and `x^4`, by using `SquareAir` twice.
+struct FourthPowerAir;
+
+impl<F> BaseAir<F> for FourthPowerAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for FourthPowerAir {
+    fn eval(&self, builder: &mut AB) {
+        SubAirBuilder::new(builder, [0, 1]).eval(&SquareAir);
+        SubAirBuilder::new(builder, [1, 2]).eval(&SquareAir);
+    }
+}
+
+/// A column starting at zero and increasing by its only periodic column.
+struct PeriodicStepAir;
+
+impl<F> BaseAir<F> for PeriodicStepAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: PeriodicAirBuilder> Air<AB> for PeriodicStepAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let step: AB::Expr = builder.periodic_values()[0].into();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + step);
+    }
+}
+
+/// Two copies of `PeriodicStepAir`, each with its own periodic column.
+struct TwoStepsAir;
+
+impl<F: Field> BaseAir<F> for TwoStepsAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![vec![F::ONE, F::TWO], vec![F::from_canonical_u32(3)]]
+    }
+}
+
+impl<AB: PeriodicAirBuilder> Air<AB> for TwoStepsAir {
+    fn eval(&self, builder: &mut AB) {
+        SubAirBuilder::new(builder, [0])
+            .with_periodic_columns(0..1)
+            .eval(&PeriodicStepAir);
+        SubAirBuilder::new(builder, [1])
+            .with_periodic_columns(1..2)
+            .eval(&PeriodicStepAir);
+    }
+}
+
+#[test]
+fn test_horizontal_air() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = HorizontalAir::new(CounterAir, SquareAir);
+    let trace = RowMajorMatrix::new(
+        (0..1 << 4)
+            .flat_map(|i| [i, 3 * i + 1, (3 * i + 1) * (3 * i + 1)].map(Val::from_canonical_u32))
+            .collect(),
+        3,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_reused_sub_air() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = RowMajorMatrix::new(
+        (0..1 << 4)
+            .flat_map(|i| [i, i * i, i * i * i * i].map(Val::from_canonical_u32))
+            .collect(),
+        3,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FourthPowerAir, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FourthPowerAir, &mut challenger, &proof, &[]).expect("verification failed");
+}
+
+#[test]
+fn test_sub_air_periodic_columns() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = RowMajorMatrix::new(
+        (0..1 << 4)
+            .flat_map(|i| [3 * (i / 2) + i % 2, 3 * i].map(Val::from_canonical_u32))
+            .collect(),
+        2,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &TwoStepsAir, &mut challenger, trace, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &TwoStepsAir, &mut challenger, &proof, &[]).expect("verification failed");
+}
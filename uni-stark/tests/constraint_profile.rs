@@ -0,0 +1,134 @@
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_field::FieldAlgebra;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::tracked::TrackedMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::{
+    debug_constraints, debug_tracked_constraints, get_constraint_profile,
+    get_max_constraint_degree, CellProvenance, ConstraintProfile, Entry,
+};
+
+/// Constraints of degrees 2, 3 and 1.
+struct CubicAir;
+
+impl<F> BaseAir<F> for CubicAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CubicAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] * local[1] * local[1]);
+        builder.assert_eq(local[1], x);
+    }
+}
+
+#[test]
+fn test_constraint_profile() {
+    let air = CubicAir;
+    let profile = get_constraint_profile::<BabyBear, _>(&air, 0, 1);
+
+    let local = Entry::Main { offset: 0 };
+    let next = Entry::Main { offset: 1 };
+    assert_eq!(
+        profile,
+        vec![
+            ConstraintProfile {
+                degree: 2,
+                num_multiplications: 1,
+                variables: vec![(local, 0)],
+            },
+            ConstraintProfile {
+                degree: 3,
+                num_multiplications: 3,
+                variables: vec![(local, 0), (local, 1), (next, 0)],
+            },
+            ConstraintProfile {
+                degree: 1,
+                num_multiplications: 0,
+                variables: vec![(local, 1), (Entry::Public, 0)],
+            },
+        ]
+    );
+    assert_eq!(
+        get_max_constraint_degree::<BabyBear, _>(&air, 0, 1),
+        profile.iter().map(|c| c.degree).max().unwrap()
+    );
+}
+
+#[test]
+fn test_debug_constraints() {
+    let air = CubicAir;
+    let mut values = [0, 2, 0, 2, 0, 2, 0, 2]
+        .map(BabyBear::from_canonical_u32)
+        .to_vec();
+    let public_values = [BabyBear::TWO];
+    debug_constraints(
+        &air,
+        &RowMajorMatrix::new(values.clone(), 2),
+        &public_values,
+    )
+    .expect("constraints should hold");
+
+    values[4] = BabyBear::from_canonical_u32(5);
+    let failures = debug_constraints(&air, &RowMajorMatrix::new(values, 2), &public_values)
+        .expect_err("constraints should fail");
+    assert_eq!(
+        failures
+            .iter()
+            .map(|failure| (failure.row, failure.constraint, failure.value))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, 1, BabyBear::from_canonical_u32(5)),
+            (2, 1, -BabyBear::from_canonical_u32(20)),
+        ]
+    );
+    assert_eq!(
+        failures[0].expression.to_string(),
+        "is_transition * (main[1][0] - main[0][0] * main[0][1] * main[0][1])"
+    );
+}
+
+#[test]
+fn test_debug_tracked_constraints() {
+    let air = CubicAir;
+    let public_values = [BabyBear::TWO];
+    let mut trace = TrackedMatrix::new(2, 4);
+    for r in 0..4 {
+        trace.set(r, 1, BabyBear::TWO);
+    }
+    // Forgetting the first column goes unnoticed by the constraints, which zero satisfies.
+    debug_constraints(&air, trace.values(), &public_values).expect("constraints should hold");
+    let report = debug_tracked_constraints(&air, &trace, &public_values)
+        .expect_err("unwritten cells should be reported");
+    assert!(report.failures.is_empty());
+    assert_eq!(report.unwritten, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+
+    let line = line!() + 2;
+    for r in 0..4 {
+        trace.set(r, 0, BabyBear::from_canonical_u32(r as u32));
+    }
+    let report = debug_tracked_constraints(&air, &trace, &public_values)
+        .expect_err("constraints should fail");
+    assert!(report.unwritten.is_empty());
+    let failure = &report.failures[0];
+    assert_eq!((failure.failure.row, failure.failure.constraint), (0, 1));
+    assert_eq!(
+        failure
+            .cells
+            .iter()
+            .map(|cell| (cell.row, cell.column))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (0, 1), (1, 0)]
+    );
+    let CellProvenance { writer, .. } = failure.cells[2];
+    assert_eq!(writer.map(|writer| writer.line()), Some(line));
+}
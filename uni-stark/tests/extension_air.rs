@@ -0,0 +1,67 @@
+use p3_air::{Air, AirBuilder, BaseAir, ExtensionExpr};
+use p3_field::{FieldAlgebra, FieldExtensionAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenge, Challenger, Val};
+use p3_uni_stark::{prove_with_extension_trace, verify};
+use rand::thread_rng;
+
+/// Iterates `x -> x^2 + c` over the extension field, starting from `c`, in a single extension
+/// column.
+struct QuadraticMapAir {
+    c: Challenge,
+}
+
+impl BaseAir<Val> for QuadraticMapAir {
+    fn width(&self) -> usize {
+        <Challenge as FieldExtensionAlgebra<Val>>::D
+    }
+}
+
+impl<AB: AirBuilder<F = Val>> Air<AB> for QuadraticMapAir {
+    fn eval(&self, builder: &mut AB) {
+        type Ext<AB> = ExtensionExpr<Val, Challenge, <AB as AirBuilder>::Expr>;
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let x = Ext::<AB>::from_row(&*local, 0);
+        let x_next = Ext::<AB>::from_row(&*next, 0);
+        let c = Ext::<AB>::constant(self.c);
+        builder
+            .when_first_row()
+            .assert_eq_extension(x.clone(), c.clone());
+        builder
+            .when_transition()
+            .assert_eq_extension(x_next, x.clone() * x + c);
+    }
+}
+
+fn generate_trace(c: Challenge, height: usize) -> RowMajorMatrix<Challenge> {
+    let values = (0..height)
+        .scan(c, |x, _| {
+            let value = *x;
+            *x = x.square() + c;
+            Some(value)
+        })
+        .collect();
+    RowMajorMatrix::new_col(values)
+}
+
+#[test]
+fn test_extension_trace() {
+    let (config, perm) = test_config(&mut thread_rng());
+
+    let c = Challenge::from_base_slice(&[1, 2, 3, 4].map(Val::from_canonical_u32));
+    let air = QuadraticMapAir { c };
+    let trace = generate_trace(c, 1 << 4);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_extension_trace(&config, &air, &mut challenger, trace, &[]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &[]).expect("verification failed");
+
+    let other_air = QuadraticMapAir { c: c + Val::ONE };
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &other_air, &mut challenger, &proof, &[])
+        .expect_err("verification should fail with a different constant");
+}
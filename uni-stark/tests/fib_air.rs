@@ -10,9 +10,15 @@ use p3_field::{Field, FieldAlgebra, PrimeField64};
 use p3_fri::{create_test_fri_config, TwoAdicFriPcs};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use p3_maybe_rayon::ResourceConfig;
 use p3_merkle_tree::MerkleTreeMmcs;
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{prove, verify, StarkConfig};
+use p3_uni_stark::{
+    estimate_prover_memory, prove, prove_with_checkpoints, prove_with_metrics,
+    prove_with_resources, verify, verify_with_report, MultiProof, Proof, ProofDecodingError,
+    ProverCheckpoint, ProverCheckpoints, ProverStage, ResourceError, StarkConfig,
+    VerificationError, PROOF_FORMAT_VERSION,
+};
 use rand::thread_rng;
 
 /// For testing the public values feature
@@ -144,6 +150,386 @@ fn test_public_value() {
     test_public_value_impl(1 << 3, 21);
 }
 
+/// Keeps every checkpoint saved, encoded, and resumes from a chosen one.
+#[derive(Default)]
+struct MemoryCheckpoints {
+    saved: Vec<Vec<u8>>,
+    resume_from: Option<Vec<u8>>,
+}
+
+impl ProverCheckpoints<MyConfig> for MemoryCheckpoints {
+    fn latest(&mut self, challenger: &mut Challenger) -> Option<ProverCheckpoint<MyConfig>> {
+        self.resume_from
+            .take()
+            .map(|bytes| ProverCheckpoint::from_bytes(&bytes, challenger).unwrap())
+    }
+
+    fn save(&mut self, checkpoint: &ProverCheckpoint<MyConfig>, challenger: &Challenger) {
+        self.saved.push(checkpoint.to_bytes(challenger));
+    }
+}
+
+#[test]
+fn test_resume_from_checkpoints() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+
+    let mut checkpoints = MemoryCheckpoints::default();
+    let proof = prove_with_checkpoints(
+        &config,
+        &FibonacciAir {},
+        &mut Challenger::new(perm.clone()),
+        || generate_trace_rows::<Val>(0, 1, 1 << 3),
+        &pis,
+        &mut checkpoints,
+    );
+    assert_eq!(checkpoints.saved.len(), 2);
+
+    // Resuming from either checkpoint, without the trace, gives the same proof.
+    let stages = [ProverStage::TraceCommitted, ProverStage::QuotientCommitted];
+    for (bytes, stage) in checkpoints.saved.into_iter().zip(stages) {
+        let checkpoint =
+            ProverCheckpoint::<MyConfig>::from_bytes(&bytes, &mut Challenger::new(perm.clone()))
+                .unwrap();
+        assert_eq!(checkpoint.stage(), stage);
+
+        let mut resumed = MemoryCheckpoints {
+            resume_from: Some(bytes),
+            ..Default::default()
+        };
+        let resumed_proof = prove_with_checkpoints(
+            &config,
+            &FibonacciAir {},
+            &mut Challenger::new(perm.clone()),
+            || unreachable!("the trace is not needed to resume"),
+            &pis,
+            &mut resumed,
+        );
+        assert_eq!(resumed_proof.to_bytes(), proof.to_bytes());
+        verify(
+            &config,
+            &FibonacciAir {},
+            &mut Challenger::new(perm.clone()),
+            &resumed_proof,
+            &pis,
+        )
+        .expect("verification failed");
+    }
+}
+
+#[test]
+fn test_resource_limits() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let estimated = estimate_prover_memory(&config, &FibonacciAir {}, 3);
+
+    let resources = ResourceConfig::unlimited()
+        .with_max_threads(2)
+        .with_max_memory_bytes(estimated);
+    let proof = prove_with_resources(
+        &config,
+        &FibonacciAir {},
+        &mut Challenger::new(perm.clone()),
+        generate_trace_rows::<Val>(0, 1, 1 << 3),
+        &pis,
+        &resources,
+    )
+    .expect("the proof fits in its budget");
+    verify(
+        &config,
+        &FibonacciAir {},
+        &mut Challenger::new(perm.clone()),
+        &proof,
+        &pis,
+    )
+    .expect("verification failed");
+
+    let result = prove_with_resources(
+        &config,
+        &FibonacciAir {},
+        &mut Challenger::new(perm),
+        generate_trace_rows::<Val>(0, 1, 1 << 3),
+        &pis,
+        &resources.with_max_memory_bytes(estimated - 1),
+    );
+    assert_eq!(
+        result.err(),
+        Some(ResourceError::MemoryBudgetExceeded {
+            estimated,
+            budget: estimated - 1,
+        })
+    );
+}
+
+#[test]
+fn test_quotient_chunk_sizes() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+
+    let mut config = MyConfig::new(pcs);
+    for chunk_size in [1, 3, 16, 1 << 10] {
+        config = config.with_quotient_chunk_size(chunk_size);
+        let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+        let mut challenger = Challenger::new(perm.clone());
+        verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis)
+            .expect("verification failed");
+    }
+}
+
+#[test]
+fn test_proof_serialization() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let bytes = proof.to_bytes();
+    let decoded = Proof::<MyConfig>::from_bytes(&bytes).expect("unable to decode proof");
+    assert_eq!(decoded.to_bytes(), bytes);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &FibonacciAir {}, &mut challenger, &decoded, &pis)
+        .expect("verification failed");
+
+    #[cfg(feature = "json")]
+    {
+        let json = proof.to_json().expect("unable to encode proof");
+        let decoded = Proof::<MyConfig>::from_json(&json).expect("unable to decode proof");
+        let mut challenger = Challenger::new(perm);
+        verify(&config, &FibonacciAir {}, &mut challenger, &decoded, &pis)
+            .expect("verification failed");
+    }
+
+    assert!(matches!(
+        MultiProof::<MyConfig>::from_bytes(&bytes),
+        Err(ProofDecodingError::InvalidMagic)
+    ));
+    let mut other_version = bytes.clone();
+    other_version[4..6].copy_from_slice(&(PROOF_FORMAT_VERSION + 1).to_le_bytes());
+    assert!(matches!(
+        Proof::<MyConfig>::from_bytes(&other_version),
+        Err(ProofDecodingError::UnsupportedVersion(version)) if version == PROOF_FORMAT_VERSION + 1
+    ));
+    assert!(matches!(
+        Proof::<MyConfig>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ProofDecodingError::Malformed(_))
+    ));
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert!(matches!(
+        Proof::<MyConfig>::from_bytes(&trailing),
+        Err(ProofDecodingError::TrailingBytes)
+    ));
+}
+
+#[test]
+fn test_verify_rejects_wrong_public_values() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft::default();
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pcs = Pcs::new(dft, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm.clone());
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let mut wrong_pis = pis.clone();
+    wrong_pis[2] = BabyBear::from_canonical_u64(22);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &wrong_pis,
+    )
+    .expect_err("verification should fail with wrong public values");
+
+    // Trailing public values the AIR ignores are still bound by the transcript.
+    let mut extra_pis = pis;
+    extra_pis.push(BabyBear::ZERO);
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &extra_pis,
+    )
+    .expect_err("verification should fail with extra public values");
+}
+
+#[test]
+fn test_proof_stats() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm);
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let stats = proof.stats(&config);
+    assert_eq!(stats.total_bytes(), proof.to_bytes().len());
+    assert!(stats.commitment_bytes > 0);
+    assert!(stats.opened_value_bytes > 0);
+    let opening = stats.opening_proof;
+    assert!(opening.commitment_bytes > 0);
+    assert!(opening.pow_bytes > 0);
+    // The Merkle paths outweigh the rows they open, which are two columns wide.
+    assert!(opening.path_bytes > opening.query_value_bytes);
+    assert!(stats.verifier_hashes() > 0);
+    assert!(stats.verifier_field_ops() > 0);
+}
+
+#[test]
+fn test_prover_metrics() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft::default();
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pcs = Pcs::new(dft, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm.clone());
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let (proof, metrics) =
+        prove_with_metrics(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    assert_eq!(
+        metrics.trace_commit.bytes,
+        (1 << 3) * NUM_FIBONACCI_COLS * size_of::<Val>()
+    );
+    assert_eq!(metrics.permutation_commit.bytes, 0);
+    assert!(metrics.quotient.bytes > 0);
+    assert!(metrics.open.bytes > 0);
+    assert_eq!(metrics.proof_bytes, proof.to_bytes().len());
+    assert!(metrics.open.bytes < metrics.proof_bytes);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[test]
+fn test_verification_report() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let dft = Dft::default();
+    let fri_config = create_test_fri_config(challenge_mmcs);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pcs = Pcs::new(dft, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm.clone());
+    let pis = vec![
+        BabyBear::from_canonical_u64(0),
+        BabyBear::from_canonical_u64(1),
+        BabyBear::from_canonical_u64(21),
+    ];
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let report = verify_with_report(&config, &FibonacciAir {}, &mut challenger, &proof, &pis);
+    assert!(report.is_ok(), "{:?}", report.errors);
+
+    // The wrong public values change the transcript, so the opening proof fails as well as the
+    // constraints, and the report has both.
+    let mut wrong_pis = pis;
+    wrong_pis[2] = BabyBear::from_canonical_u64(22);
+    let mut challenger = Challenger::new(perm);
+    let report = verify_with_report(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &wrong_pis,
+    );
+    assert!(report
+        .errors
+        .iter()
+        .any(|error| matches!(error, VerificationError::InvalidOpeningArgument(_))));
+    assert!(report.errors.iter().any(|error| matches!(
+        error,
+        VerificationError::OodEvaluationMismatch {
+            air: 0,
+            recomputed,
+            claimed,
+        } if recomputed != claimed
+    )));
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
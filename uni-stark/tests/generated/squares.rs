@@ -0,0 +1,28 @@
+// Generated by `p3_uni_stark::SymbolicAir::generate_rust`.
+pub fn eval_squares<AB>(builder: &mut AB)
+where
+    AB: p3_air::AirBuilderWithPublicValues + p3_air::PairBuilder + p3_air::PeriodicAirBuilder,
+{
+    let main = p3_air::AirBuilder::main(builder);
+    let preprocessed = p3_air::PairBuilder::preprocessed(builder);
+    let v0: AB::Expr = p3_matrix::Matrix::get(&main, 0, 0).into();
+    let v1: AB::Expr = p3_matrix::Matrix::get(&preprocessed, 0, 0).into();
+    let v2: AB::Expr = v1.clone() * v1.clone();
+    let v3: AB::Expr = v0.clone() - v2;
+    let v4: AB::Expr = p3_air::AirBuilder::is_transition_window(builder, 2);
+    let v5: AB::Expr = p3_matrix::Matrix::get(&main, 1, 0).into();
+    let v6: AB::Expr = <AB::Expr as p3_field::FieldAlgebra>::from_canonical_u64(2);
+    let v7: AB::Expr = v1 * v6;
+    let v8: AB::Expr = v0.clone() + v7;
+    let v9: AB::Expr = <AB::Expr as p3_field::FieldAlgebra>::from_canonical_u64(1);
+    let v10: AB::Expr = v8 + v9;
+    let v11: AB::Expr = v5 - v10;
+    let v12: AB::Expr = v4 * v11;
+    let v13: AB::Expr = p3_air::AirBuilder::is_last_row(builder);
+    let v14: AB::Expr = p3_air::AirBuilderWithPublicValues::public_values(builder)[0].into();
+    let v15: AB::Expr = v0 - v14;
+    let v16: AB::Expr = v13 * v15;
+    p3_air::AirBuilder::assert_zero(builder, v3);
+    p3_air::AirBuilder::assert_zero(builder, v12);
+    p3_air::AirBuilder::assert_zero(builder, v16);
+}
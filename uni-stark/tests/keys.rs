@@ -0,0 +1,149 @@
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir, PairBuilder};
+use p3_challenger::{RecordingChallenger, TranscriptOp};
+use p3_field::{Field, FieldAlgebra};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{
+    test_config, test_pcs, Challenge, Challenger, Pcs, Perm, TestConfig, Val,
+};
+use p3_uni_stark::{prove_with_key, setup_keys, verify_with_key, StarkConfig, VerifyingKey};
+use rand::thread_rng;
+
+/// Proves knowledge of `(offset + i)^2` for every row `i`, where the column `offset + i` is fixed
+/// by the AIR as a preprocessed column, with the last square a public value.
+struct SquaresAir {
+    height: usize,
+    offset: u32,
+}
+
+impl<F: Field> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..self.height as u32)
+            .map(|i| F::from_canonical_u32(self.offset + i))
+            .collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + PairBuilder> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let last = builder.public_values()[0];
+        let c = preprocessed.row_slice(0)[0];
+        let x = main.row_slice(0)[0];
+        builder.assert_eq(x, c * c);
+        builder.when_last_row().assert_eq(x, last);
+    }
+}
+
+fn generate_trace(air: &SquaresAir) -> RowMajorMatrix<Val> {
+    let values = (0..air.height as u32)
+        .map(|i| Val::from_canonical_u32(air.offset + i).square())
+        .collect();
+    RowMajorMatrix::new_col(values)
+}
+
+type RecordingConfig = StarkConfig<Pcs, Challenge, RecordingChallenger<Challenger>>;
+
+#[test]
+fn test_keys() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = SquaresAir {
+        height: 1 << 4,
+        offset: 3,
+    };
+    let (pk, vk) = setup_keys(&config, &air, 1);
+    let trace = generate_trace(&air);
+    let public_values = [trace.get(trace.height() - 1, 0)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_key(&config, &pk, &air, &mut challenger, trace, &public_values);
+
+    // The verifying key can be sent to a verifier who does not have the AIR.
+    let vk_bytes = postcard::to_allocvec(&vk).expect("unable to serialize key");
+    let decoded_vk: VerifyingKey<TestConfig> =
+        postcard::from_bytes(&vk_bytes).expect("unable to deserialize key");
+    assert_eq!(decoded_vk.digest(&Keccak256Hash), vk.digest(&Keccak256Hash));
+    let decoded_vk =
+        VerifyingKey::<TestConfig>::from_bytes(&vk.to_bytes()).expect("unable to decode key");
+    assert_eq!(decoded_vk.digest(&Keccak256Hash), vk.digest(&Keccak256Hash));
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_key(
+        &config,
+        &decoded_vk,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    // The key of an AIR with a different preprocessed trace has a different digest, and rejects
+    // the proof.
+    let other_air = SquaresAir {
+        height: 1 << 4,
+        offset: 4,
+    };
+    let (_, other_vk) = setup_keys(&config, &other_air, 1);
+    assert_ne!(other_vk.digest(&Keccak256Hash), vk.digest(&Keccak256Hash));
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_key(&config, &other_vk, &mut challenger, &proof, &public_values)
+        .expect_err("verification should fail with the key of another AIR");
+
+    let mut challenger = Challenger::new(perm);
+    verify_with_key(&config, &vk, &mut challenger, &proof, &[])
+        .expect_err("verification should fail with missing public values");
+}
+
+#[test]
+fn test_transcript_schema() {
+    let (pcs, perm) = test_pcs(&mut thread_rng());
+    let config = RecordingConfig::new(pcs);
+    let air = SquaresAir {
+        height: 1 << 4,
+        offset: 3,
+    };
+    let (pk, vk) = setup_keys(&config, &air, 1);
+    assert!(vk.transcript_schema().is_none());
+    let trace = generate_trace(&air);
+    let public_values = [trace.get(trace.height() - 1, 0)];
+
+    let mut challenger = RecordingChallenger::new(Challenger::new(perm.clone()));
+    let proof = prove_with_key(
+        &config,
+        &pk,
+        &air,
+        &mut challenger,
+        trace.clone(),
+        &public_values,
+    );
+    let mut challenger = RecordingChallenger::new(Challenger::new(perm));
+    verify_with_key(&config, &vk, &mut challenger, &proof, &public_values)
+        .expect("verification failed");
+    let schema = challenger.schema();
+    assert!(schema
+        .entries
+        .iter()
+        .any(|entry| entry.op == TranscriptOp::Sample));
+
+    // The schema is serialized with the key.
+    let vk = vk.with_transcript_schema(schema.clone());
+    let decoded_vk =
+        VerifyingKey::<RecordingConfig>::from_bytes(&vk.to_bytes()).expect("unable to decode key");
+    assert_eq!(decoded_vk.transcript_schema(), Some(&schema));
+
+    // Proofs with other challenges follow the same schema.
+    let other_perm = Perm::new_from_rng_128(&mut thread_rng());
+    let mut other = RecordingChallenger::new(Challenger::new(other_perm.clone()));
+    let other_proof = prove_with_key(&config, &pk, &air, &mut other, trace, &public_values);
+    let mut other = RecordingChallenger::new(Challenger::new(other_perm));
+    verify_with_key(&config, &vk, &mut other, &other_proof, &public_values)
+        .expect("verification failed");
+    assert_ne!(other.transcript(), challenger.transcript());
+    assert_eq!(schema.first_divergence(other.transcript()), None);
+}
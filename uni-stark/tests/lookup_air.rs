@@ -0,0 +1,244 @@
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, LookupAir, VirtualPairCol};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{
+    debug_interactions, prove_multiple_with_lookups, prove_with_lookups, verify,
+    verify_multiple_with_lookups, verify_with_lookups, InteractionImbalance,
+};
+use rand::thread_rng;
+
+/// Range checks the `value` column against a `table` column counting up from zero, which receives
+/// each of its entries `multiplicity` times.
+struct RangeCheckAir;
+
+const VALUE: usize = 0;
+const TABLE: usize = 1;
+const MULTIPLICITY: usize = 2;
+
+impl<F> BaseAir<F> for RangeCheckAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for RangeCheckAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_first_row().assert_zero(local[TABLE]);
+        builder
+            .when_transition()
+            .assert_eq(next[TABLE], local[TABLE] + AB::Expr::ONE);
+    }
+}
+
+impl<F: Field> LookupAir<F> for RangeCheckAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        vec![
+            Interaction::send(
+                vec![VirtualPairCol::single_main(VALUE)],
+                VirtualPairCol::ONE,
+                0,
+            ),
+            Interaction::receive(
+                vec![VirtualPairCol::single_main(TABLE)],
+                VirtualPairCol::single_main(MULTIPLICITY),
+                0,
+            ),
+        ]
+    }
+}
+
+/// Asserts that the second column is a permutation of the first.
+struct PermutationAir;
+
+impl<F> BaseAir<F> for PermutationAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for PermutationAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+impl<F: Field> LookupAir<F> for PermutationAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        Interaction::multiset_equality(
+            vec![VirtualPairCol::single_main(0)],
+            vec![VirtualPairCol::single_main(1)],
+            0,
+        )
+        .to_vec()
+    }
+}
+
+/// `RangeCheckAir` split into two AIRs, with the values and the table in separate traces.
+#[derive(Clone, Copy)]
+enum SplitRangeCheckAir {
+    Values,
+    Table,
+}
+
+impl<F> BaseAir<F> for SplitRangeCheckAir {
+    fn width(&self) -> usize {
+        match self {
+            Self::Values => 1,
+            Self::Table => 2,
+        }
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for SplitRangeCheckAir {
+    fn eval(&self, builder: &mut AB) {
+        if let Self::Table = self {
+            let main = builder.main();
+            let (local, next) = (main.row_slice(0), main.row_slice(1));
+            builder.when_first_row().assert_zero(local[0]);
+            builder
+                .when_transition()
+                .assert_eq(next[0], local[0] + AB::Expr::ONE);
+        }
+    }
+}
+
+impl<F: Field> LookupAir<F> for SplitRangeCheckAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        match self {
+            Self::Values => vec![Interaction::send(
+                vec![VirtualPairCol::single_main(0)],
+                VirtualPairCol::ONE,
+                0,
+            )],
+            Self::Table => vec![Interaction::receive(
+                vec![VirtualPairCol::single_main(0)],
+                VirtualPairCol::single_main(1),
+                0,
+            )],
+        }
+    }
+}
+
+fn generate_trace(values: &[u32]) -> RowMajorMatrix<Val> {
+    let mut multiplicities = vec![0; values.len()];
+    for &value in values {
+        if let Some(multiplicity) = multiplicities.get_mut(value as usize) {
+            *multiplicity += 1;
+        }
+    }
+    let rows = values
+        .iter()
+        .zip(multiplicities)
+        .enumerate()
+        .flat_map(|(i, (&value, multiplicity))| {
+            [value, i as u32, multiplicity].map(Val::from_canonical_u32)
+        })
+        .collect();
+    RowMajorMatrix::new(rows, 3)
+}
+
+#[test]
+fn test_lookups() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_trace(&[3, 1, 4, 1, 5, 0, 2, 6]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_lookups(&config, &RangeCheckAir, &mut challenger, trace, &[], None);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_lookups(&config, &RangeCheckAir, &mut challenger, &proof, &[], None)
+        .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &RangeCheckAir, &mut challenger, &proof, &[])
+        .expect_err("verification should fail without the lookups");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "lookup sends and receives do not balance")]
+fn test_unbalanced_lookups() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_trace(&[3, 1, 4, 1, 5, 9, 2, 6]);
+
+    let mut challenger = Challenger::new(perm);
+    prove_with_lookups(&config, &RangeCheckAir, &mut challenger, trace, &[], None);
+}
+
+#[test]
+fn test_debug_interactions() {
+    let interactions = LookupAir::<Val>::interactions(&RangeCheckAir);
+    let trace = generate_trace(&[3, 1, 4, 1, 5, 0, 2, 6]);
+    debug_interactions(&[(&interactions[..], None, &trace)]).expect("lookups should balance");
+
+    let trace = generate_trace(&[3, 1, 4, 1, 5, 9, 2, 6]);
+    let imbalances = debug_interactions(&[(&interactions[..], None, &trace)])
+        .expect_err("lookups should not balance");
+    assert_eq!(
+        imbalances,
+        vec![InteractionImbalance {
+            bus: 0,
+            values: vec![Val::from_canonical_u32(9)],
+            net_multiplicity: Val::ONE,
+        }]
+    );
+    assert_eq!(
+        imbalances[0].to_string(),
+        "tuple (9) on bus 0 is sent 1 more times than it is received"
+    );
+}
+
+#[test]
+fn test_permutation() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let rows = [(1, 5), (5, 2), (2, 2), (2, 1)]
+        .into_iter()
+        .flat_map(|(a, b)| [a, b].map(Val::from_canonical_u32))
+        .collect();
+    let trace = RowMajorMatrix::new(rows, 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_lookups(&config, &PermutationAir, &mut challenger, trace, &[], None);
+    let mut challenger = Challenger::new(perm);
+    verify_with_lookups(&config, &PermutationAir, &mut challenger, &proof, &[], None)
+        .expect("verification failed");
+}
+
+#[test]
+fn test_lookups_across_airs() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let values = [3, 1, 4, 1, 5, 0, 2, 6, 2, 7, 1, 0, 3, 3, 6, 5];
+    let mut multiplicities = [0; 8];
+    for value in values {
+        multiplicities[value as usize] += 1;
+    }
+    let values_trace = RowMajorMatrix::new_col(values.map(Val::from_canonical_u32).to_vec());
+    let table_trace = RowMajorMatrix::new(
+        multiplicities
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &multiplicity)| [i as u32, multiplicity].map(Val::from_canonical_u32))
+            .collect(),
+        2,
+    );
+    let airs = [SplitRangeCheckAir::Values, SplitRangeCheckAir::Table];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple_with_lookups(
+        &config,
+        &[(airs[0], values_trace), (airs[1], table_trace)],
+        &mut challenger,
+        &[],
+        None,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple_with_lookups(&config, &airs, &mut challenger, &proof, &[], None)
+        .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify_multiple_with_lookups(&config, &airs[..1], &mut challenger, &proof, &[], None)
+        .expect_err("verification should fail with a missing AIR");
+}
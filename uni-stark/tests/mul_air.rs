@@ -20,7 +20,9 @@ use p3_mersenne_31::Mersenne31;
 use p3_symmetric::{
     CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
 };
-use p3_uni_stark::{prove, verify, StarkConfig, StarkGenericConfig, Val};
+use p3_uni_stark::{
+    prove, verify, ConstraintBatching, Proof, StarkConfig, StarkGenericConfig, Val,
+};
 use rand::distributions::{Distribution, Standard};
 use rand::{thread_rng, Rng};
 
@@ -128,11 +130,11 @@ where
     let mut p_challenger = challenger.clone();
     let proof = prove(&config, &air, &mut p_challenger, trace, &vec![]);
 
-    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let serialized_proof = proof.to_bytes();
     tracing::debug!("serialized_proof len: {} bytes", serialized_proof.len());
 
     let deserialized_proof =
-        postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+        Proof::<SC>::from_bytes(&serialized_proof).expect("unable to deserialize proof");
 
     let mut v_challenger = challenger.clone();
     verify(
@@ -190,6 +192,16 @@ fn prove_bb_trivial_deg4() -> Result<(), impl Debug> {
 }
 
 fn do_test_bb_twoadic(log_blowup: usize, degree: u64, log_n: usize) -> Result<(), impl Debug> {
+    do_test_bb_twoadic_with(log_blowup, degree, log_n, ConstraintBatching::Powers, None)
+}
+
+fn do_test_bb_twoadic_with(
+    log_blowup: usize,
+    degree: u64,
+    log_n: usize,
+    constraint_batching: ConstraintBatching,
+    fixed_log_quotient_degree: Option<usize>,
+) -> Result<(), impl Debug> {
     type Val = BabyBear;
     type Challenge = BinomialExtensionField<Val, 4>;
 
@@ -219,13 +231,17 @@ fn do_test_bb_twoadic(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
         log_final_poly_len: 5,
         num_queries: 40,
         proof_of_work_bits: 8,
+        deduplicate_queries: false,
         mmcs: challenge_mmcs,
     };
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
     let pcs = Pcs::new(dft, val_mmcs, fri_config);
 
     type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
-    let config = MyConfig::new(pcs);
+    let mut config = MyConfig::new(pcs).with_constraint_batching(constraint_batching);
+    if let Some(log_quotient_degree) = fixed_log_quotient_degree {
+        config = config.with_fixed_log_quotient_degree(log_quotient_degree);
+    }
 
     let air = MulAir {
         degree,
@@ -255,6 +271,40 @@ fn prove_bb_twoadic_deg5() -> Result<(), impl Debug> {
     do_test_bb_twoadic(2, 5, 6)
 }
 
+#[test]
+fn prove_bb_twoadic_quotient_degree_above_blowup() -> Result<(), impl Debug> {
+    // Degree 5 constraints need 4 quotient chunks, but the traces are only extended by 2, so the
+    // PCS extrapolates them to the quotient domain.
+    do_test_bb_twoadic(1, 5, 6)
+}
+
+#[test]
+fn prove_bb_twoadic_height_1() -> Result<(), impl Debug> {
+    // The trace and the quotient chunks are constants, and shorter than the final polynomial.
+    do_test_bb_twoadic(1, 3, 0)
+}
+
+#[test]
+fn prove_bb_twoadic_height_2() -> Result<(), impl Debug> {
+    do_test_bb_twoadic(2, 4, 1)
+}
+
+#[test]
+fn prove_bb_twoadic_constraint_groups() -> Result<(), impl Debug> {
+    do_test_bb_twoadic_with(1, 3, 7, ConstraintBatching::Groups { group_size: 2 }, None)
+}
+
+#[test]
+fn prove_bb_twoadic_fixed_quotient_degree() -> Result<(), impl Debug> {
+    // Degree 2 constraints only need 2 quotient chunks, but we ask for 4.
+    do_test_bb_twoadic_with(2, 2, 7, ConstraintBatching::Powers, Some(2))
+}
+
+#[test]
+fn prove_bb_twoadic_fixed_quotient_degree_above_blowup() -> Result<(), impl Debug> {
+    do_test_bb_twoadic_with(1, 3, 7, ConstraintBatching::Powers, Some(3))
+}
+
 fn do_test_m31_circle(log_blowup: usize, degree: u64, log_n: usize) -> Result<(), impl Debug> {
     type Val = Mersenne31;
     type Challenge = BinomialExtensionField<Val, 3>;
@@ -280,15 +330,12 @@ fn do_test_m31_circle(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
         log_final_poly_len: 0,
         num_queries: 40,
         proof_of_work_bits: 8,
+        deduplicate_queries: false,
         mmcs: challenge_mmcs,
     };
 
     type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
-    let pcs = Pcs {
-        mmcs: val_mmcs,
-        fri_config,
-        _phantom: PhantomData,
-    };
+    let pcs = Pcs::new(val_mmcs, fri_config);
 
     type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
     let config = MyConfig::new(pcs);
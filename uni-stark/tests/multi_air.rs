@@ -0,0 +1,159 @@
+use p3_air::{pad_traces, Air, AirBuilder, BaseAir, PaddingPolicy, TraceSizer};
+use p3_field::FieldAlgebra;
+use p3_fri::{create_test_fri_config, FriConfig};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{
+    test_config, ChallengeMmcs, Challenger, Dft, Pcs, Perm, TestConfig, Val, ValCompress, ValHash,
+    ValMmcs,
+};
+use p3_uni_stark::{
+    prove_multiple, verify_multiple, verify_multiple_with_report, ShapeError, VerificationError,
+};
+use rand::thread_rng;
+
+/// A table of `(x, x^exponent)` for consecutive `x`, so that AIRs with different exponents have
+/// different constraint degrees.
+#[derive(Clone, Copy)]
+struct PowerAir {
+    exponent: u64,
+}
+
+impl<F> BaseAir<F> for PowerAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for PowerAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let x: AB::Expr = local[0].into();
+        builder.assert_eq(local[1], x.exp_u64(self.exponent));
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + AB::Expr::ONE);
+    }
+}
+
+impl TraceSizer<Val> for PowerAir {
+    fn padding_row(&self, index: usize, _previous: Option<&[Val]>) -> Vec<Val> {
+        let x = Val::from_canonical_usize(index);
+        vec![x, x.exp_u64(self.exponent)]
+    }
+}
+
+impl PowerAir {
+    fn generate_trace(&self, height: usize) -> RowMajorMatrix<Val> {
+        let values = (0..height as u32)
+            .flat_map(|i| {
+                let x = Val::from_canonical_u32(i);
+                [x, x.exp_u64(self.exponent)]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+#[test]
+fn test_prove_multiple() {
+    let (config, perm) = test_config(&mut thread_rng());
+
+    let squares = PowerAir { exponent: 2 };
+    let cubes = PowerAir { exponent: 3 };
+    let airs_and_traces = [
+        (squares, squares.generate_trace(1 << 3)),
+        (cubes, cubes.generate_trace(1 << 5)),
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple(&config, &airs_and_traces, &mut challenger, &[]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple(&config, &[squares, cubes], &mut challenger, &proof, &[])
+        .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple(&config, &[cubes, squares], &mut challenger, &proof, &[])
+        .expect_err("verification should fail with the AIRs in another order");
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple(&config, &[squares], &mut challenger, &proof, &[])
+        .expect_err("verification should fail with a missing AIR");
+
+    let mut challenger = Challenger::new(perm);
+    let report = verify_multiple_with_report(&config, &[squares], &mut challenger, &proof, &[]);
+    assert!(matches!(
+        report.errors[..],
+        [VerificationError::InvalidProofShape(ShapeError::NumAirs)]
+    ));
+}
+
+#[test]
+fn test_prove_multiple_with_tiny_traces() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let val_mmcs = ValMmcs::new(ValHash::new(perm.clone()), ValCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    // The traces of height 1 and 2 are shorter than the final polynomial of FRI.
+    let fri_config = FriConfig {
+        log_final_poly_len: 2,
+        ..create_test_fri_config(challenge_mmcs)
+    };
+    let config = TestConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config));
+
+    let squares = PowerAir { exponent: 2 };
+    let cubes = PowerAir { exponent: 3 };
+    let airs = [squares, cubes, squares];
+    let airs_and_traces = [
+        (squares, squares.generate_trace(1)),
+        (cubes, cubes.generate_trace(1 << 4)),
+        (squares, squares.generate_trace(2)),
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple(&config, &airs_and_traces, &mut challenger, &[]);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple(&config, &airs, &mut challenger, &proof, &[]).expect("verification failed");
+
+    // Only tiny traces, so that every polynomial is shorter than the final polynomial.
+    let airs_and_traces = [(squares, squares.generate_trace(1))];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple(&config, &airs_and_traces, &mut challenger, &[]);
+    let mut challenger = Challenger::new(perm);
+    verify_multiple(&config, &[squares], &mut challenger, &proof, &[])
+        .expect("verification failed");
+}
+
+#[test]
+fn test_pad_traces() {
+    let (config, perm) = test_config(&mut thread_rng());
+
+    let squares = PowerAir { exponent: 2 };
+    let cubes = PowerAir { exponent: 3 };
+    let unpadded = [
+        (squares, squares.generate_trace(3)),
+        (cubes, cubes.generate_trace(20)),
+    ];
+    for (policy, heights) in [
+        (PaddingPolicy::Minimal, [4, 32]),
+        (PaddingPolicy::SharedMaximum, [32, 32]),
+        (PaddingPolicy::Fixed(64), [64, 64]),
+    ] {
+        let mut airs_and_traces = unpadded.clone();
+        pad_traces(&mut airs_and_traces, policy);
+        assert_eq!(
+            airs_and_traces.each_ref().map(|(_, trace)| trace.height()),
+            heights
+        );
+        for (air, trace) in &airs_and_traces {
+            assert_eq!(*trace, air.generate_trace(trace.height()));
+        }
+
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_multiple(&config, &airs_and_traces, &mut challenger, &[]);
+        let mut challenger = Challenger::new(perm.clone());
+        verify_multiple(&config, &[squares, cubes], &mut challenger, &proof, &[])
+            .expect("verification failed");
+    }
+}
@@ -0,0 +1,90 @@
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir, PeriodicAirBuilder};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{prove, verify};
+use rand::thread_rng;
+
+/// A running sum of a periodic column, with the last row a public value.
+struct PeriodicSumAir {
+    increments: Vec<u32>,
+}
+
+impl<F: Field> BaseAir<F> for PeriodicSumAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![self
+            .increments
+            .iter()
+            .map(|&increment| F::from_canonical_u32(increment))
+            .collect()]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + PeriodicAirBuilder> Air<AB> for PeriodicSumAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        let increment: AB::Expr = builder.periodic_values()[0].into();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + increment);
+        builder.when_last_row().assert_eq(local[0], x);
+    }
+}
+
+fn generate_trace(increments: &[u32], height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height)
+        .scan(0, |sum, i| {
+            let value = *sum;
+            *sum += increments[i % increments.len()];
+            Some(Val::from_canonical_u32(value))
+        })
+        .collect();
+    RowMajorMatrix::new_col(values)
+}
+
+fn do_test(increments: Vec<u32>, log_height: usize) {
+    let (config, perm) = test_config(&mut thread_rng());
+
+    let trace = generate_trace(&increments, 1 << log_height);
+    let public_values = [trace.get(trace.height() - 1, 0)];
+    let air = PeriodicSumAir { increments };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &air,
+        &mut challenger,
+        &proof,
+        &[public_values[0] + Val::ONE],
+    )
+    .expect_err("verification should fail with the wrong public value");
+}
+
+#[test]
+fn test_periodic() {
+    do_test(vec![1, 2, 3, 4], 5);
+}
+
+#[test]
+fn test_periodic_constant() {
+    do_test(vec![7], 4);
+}
+
+#[test]
+fn test_periodic_full_height() {
+    do_test((0..16).map(|i| i * i).collect(), 4);
+}
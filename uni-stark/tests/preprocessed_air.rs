@@ -0,0 +1,186 @@
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{
+    prove, prove_multiple, prove_multiple_with_preprocessed, prove_with_preprocessed,
+    setup_multiple_preprocessed, setup_preprocessed, verify, verify_multiple,
+    verify_multiple_with_preprocessed, verify_with_preprocessed, VerificationError,
+};
+use rand::thread_rng;
+
+/// Proves knowledge of `(offset + i)^2` for every row `i`, where the column `offset + i` is fixed
+/// by the AIR as a preprocessed column.
+struct SquaresAir {
+    height: usize,
+    offset: u32,
+}
+
+impl<F: Field> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let values = (0..self.height as u32)
+            .map(|i| F::from_canonical_u32(self.offset + i))
+            .collect();
+        Some(RowMajorMatrix::new_col(values))
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let c = preprocessed.row_slice(0)[0];
+        let x = main.row_slice(0)[0];
+        builder.assert_eq(x, c * c);
+    }
+}
+
+fn generate_trace(air: &SquaresAir) -> RowMajorMatrix<Val> {
+    let values = (0..air.height as u32)
+        .map(|i| Val::from_canonical_u32(air.offset + i).square())
+        .collect();
+    RowMajorMatrix::new_col(values)
+}
+
+#[test]
+fn test_preprocessed() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = SquaresAir {
+        height: 1 << 4,
+        offset: 3,
+    };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, generate_trace(&air), &[]);
+
+    // Without hiding, the commitment made by `prove` is the one made by `setup_preprocessed`.
+    let (_, vk) = setup_preprocessed(&config, &air, 4).unwrap();
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_preprocessed(&config, &air, &mut challenger, &proof, &[], Some(&vk))
+        .expect("verification failed");
+
+    // The verifier does not commit to the preprocessed trace itself.
+    let mut challenger = Challenger::new(perm);
+    let result = verify(&config, &air, &mut challenger, &proof, &[]);
+    assert!(matches!(
+        result,
+        Err(VerificationError::MissingPreprocessedKey)
+    ));
+}
+
+#[test]
+fn test_reused_preprocessed_commitment() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = SquaresAir {
+        height: 1 << 4,
+        offset: 3,
+    };
+    let (prover_data, vk) = setup_preprocessed(&config, &air, 4).unwrap();
+    assert_eq!(vk.width(), 1);
+    assert_eq!(vk.degree_bits(), 4);
+
+    for _ in 0..2 {
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_with_preprocessed(
+            &config,
+            &air,
+            &mut challenger,
+            generate_trace(&air),
+            &[],
+            Some(&prover_data),
+        );
+        let mut challenger = Challenger::new(perm.clone());
+        verify_with_preprocessed(&config, &air, &mut challenger, &proof, &[], Some(&vk))
+            .expect("verification failed");
+    }
+}
+
+#[test]
+fn test_wrong_preprocessed_commitment() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let air = SquaresAir {
+        height: 1 << 4,
+        offset: 3,
+    };
+    let other_air = SquaresAir {
+        height: 1 << 4,
+        offset: 4,
+    };
+    let (_, other_vk) = setup_preprocessed(&config, &other_air, 4).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, generate_trace(&air), &[]);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_preprocessed(&config, &air, &mut challenger, &proof, &[], Some(&other_vk))
+        .expect_err("verification should fail with another preprocessed commitment");
+    let mut challenger = Challenger::new(perm);
+    let result = verify_with_preprocessed(&config, &air, &mut challenger, &proof, &[], None);
+    assert!(matches!(
+        result,
+        Err(VerificationError::MissingPreprocessedKey)
+    ));
+}
+
+#[test]
+fn test_prove_multiple_preprocessed() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let airs_and_traces = || {
+        [(1 << 3, 3), (1 << 5, 7)].map(|(height, offset)| {
+            let air = SquaresAir { height, offset };
+            let trace = generate_trace(&air);
+            (air, trace)
+        })
+    };
+    let airs = airs_and_traces().map(|(air, _)| air);
+    let (prover_data, vk) = setup_multiple_preprocessed(&config, &airs, &[3, 5]).unwrap();
+    assert_eq!(vk.widths(), [Some(1), Some(1)]);
+
+    for _ in 0..2 {
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_multiple_with_preprocessed(
+            &config,
+            &airs_and_traces(),
+            &mut challenger,
+            &[],
+            Some(&prover_data),
+        );
+        let mut challenger = Challenger::new(perm.clone());
+        verify_multiple_with_preprocessed(&config, &airs, &mut challenger, &proof, &[], Some(&vk))
+            .expect("verification failed");
+    }
+
+    // Without hiding, the commitment made by `prove_multiple` is the one made by
+    // `setup_multiple_preprocessed`.
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple(&config, &airs_and_traces(), &mut challenger, &[]);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple_with_preprocessed(&config, &airs, &mut challenger, &proof, &[], Some(&vk))
+        .expect("verification failed");
+
+    let other_airs =
+        [(1 << 3, 3), (1 << 5, 8)].map(|(height, offset)| SquaresAir { height, offset });
+    let (_, other_vk) = setup_multiple_preprocessed(&config, &other_airs, &[3, 5]).unwrap();
+    let mut challenger = Challenger::new(perm.clone());
+    verify_multiple_with_preprocessed(
+        &config,
+        &airs,
+        &mut challenger,
+        &proof,
+        &[],
+        Some(&other_vk),
+    )
+    .expect_err("verification should fail with other preprocessed traces");
+
+    // The verifier does not commit to the preprocessed traces itself.
+    let mut challenger = Challenger::new(perm);
+    let result = verify_multiple(&config, &airs, &mut challenger, &proof, &[]);
+    assert!(matches!(
+        result,
+        Err(VerificationError::MissingPreprocessedKey)
+    ));
+}
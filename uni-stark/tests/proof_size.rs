@@ -0,0 +1,31 @@
+use itertools::Itertools;
+use p3_commit::Mmcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::batch_opening_len_bound;
+use p3_uni_stark::testing::{Perm, Val, ValCompress, ValHash, ValMmcs};
+use rand::thread_rng;
+
+#[test]
+fn test_batch_opening_len_bound() {
+    let mut rng = thread_rng();
+    let perm = Perm::new_from_rng_128(&mut rng);
+    let mmcs = ValMmcs::new(ValHash::new(perm.clone()), ValCompress::new(perm));
+
+    let mats = vec![
+        RowMajorMatrix::<Val>::rand(&mut rng, 100, 8),
+        RowMajorMatrix::<Val>::rand(&mut rng, 100, 3),
+        RowMajorMatrix::<Val>::rand(&mut rng, 12, 5),
+    ];
+    let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+
+    let (_, prover_data) = mmcs.commit(mats);
+    let (opened_values, proof) = mmcs.open_batch(42, &prover_data);
+
+    // Three rows of 8, 3 and 5 values and 7 digests of 8 values, of at most 5 bytes each, and
+    // the lengths of the six sequences.
+    let bound = batch_opening_len_bound(&mmcs.proof_size_hint(&dims));
+    assert_eq!(bound, 6 + 16 * 5 + 7 * 8 * 5);
+    let encoded = postcard::to_allocvec(&(opened_values, proof)).unwrap();
+    assert!(encoded.len() <= bound);
+}
@@ -0,0 +1,88 @@
+use std::rc::Rc;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_field::FieldAlgebra;
+use p3_matrix::Matrix;
+use p3_uni_stark::{
+    get_constraint_profile, get_max_constraint_degree, simplify_constraints, Entry,
+    SymbolicExpression, SymbolicVariable,
+};
+
+type F = BabyBear;
+
+fn main_var(index: usize) -> SymbolicExpression<F> {
+    SymbolicVariable::new(Entry::Main { offset: 0 }, index).into()
+}
+
+#[test]
+fn test_simplify() {
+    let (x, y) = (main_var(0), main_var(1));
+    let zero = SymbolicExpression::<F>::ZERO;
+    let one = SymbolicExpression::<F>::ONE;
+
+    let expr = (x.clone() + zero.clone()) * one.clone() - (zero.clone() + y.clone());
+    assert_eq!(expr.simplify().to_string(), "main[0][0] - main[0][1]");
+
+    let expr = x.clone() * y.clone() * zero.clone() + x.clone();
+    let simplified = expr.simplify();
+    assert_eq!(simplified.to_string(), "main[0][0]");
+    assert_eq!(simplified.degree_multiple(), 1);
+
+    let expr = -(-(x.clone() * y.clone())) - x.clone() * y.clone();
+    let simplified = expr.simplify();
+    assert_eq!(simplified.to_string(), "0");
+    assert_eq!(simplified.degree_multiple(), 0);
+
+    let expr = x * SymbolicExpression::NEG_ONE * (one.clone() + one);
+    assert_eq!(expr.simplify().to_string(), "(-main[0][0]) * 2");
+}
+
+#[test]
+fn test_simplify_shares_subexpressions() {
+    let (x, y) = (main_var(0), main_var(1));
+    let constraints = vec![
+        x.clone() * y.clone() + x.clone(),
+        (x.clone() * y.clone()) * SymbolicExpression::ZERO,
+        x.clone() * y.clone() - y,
+    ];
+    let simplified = simplify_constraints(&constraints);
+    assert_eq!(simplified.len(), constraints.len());
+    assert_eq!(simplified[1].to_string(), "0");
+    match (&simplified[0], &simplified[2]) {
+        (
+            SymbolicExpression::Add { x: product_0, .. },
+            SymbolicExpression::Sub { x: product_2, .. },
+        ) => assert!(Rc::ptr_eq(product_0, product_2)),
+        _ => panic!("unexpected simplified constraints"),
+    }
+}
+
+/// A machine-generated-looking AIR, whose constraints contain terms with zero coefficients.
+struct ZeroCoefficientAir;
+
+impl<F> BaseAir<F> for ZeroCoefficientAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ZeroCoefficientAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let (a, b): (AB::Expr, AB::Expr) = (local[0].into(), local[1].into());
+        builder.assert_zero(a.clone() * b.clone() * b.clone() * AB::Expr::ZERO + a.clone() - b);
+        builder.assert_zero(a.clone() * AB::Expr::ZERO);
+    }
+}
+
+#[test]
+fn test_simplified_degree() {
+    let air = ZeroCoefficientAir;
+    assert_eq!(get_max_constraint_degree::<F, _>(&air, 0, 0), 1);
+    let profile = get_constraint_profile::<F, _>(&air, 0, 0);
+    assert_eq!(profile.len(), 2);
+    assert_eq!(profile[0].num_multiplications, 0);
+    assert_eq!(profile[1].degree, 0);
+}
@@ -0,0 +1,255 @@
+use p3_air::utils::periodic_selector;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PeriodicAirBuilder};
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::testing::{test_config, Challenger, Val};
+use p3_uni_stark::{prove, prove_multiple, verify, verify_multiple, ShapeError, VerificationError};
+use rand::thread_rng;
+
+/// The Fibonacci sequence in a single column, with each row the sum of the previous two, and the
+/// last row a public value.
+#[derive(Clone, Copy)]
+struct FibonacciColumnAir;
+
+impl<F> BaseAir<F> for FibonacciColumnAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn window_size(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciColumnAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        let (row_0, row_1, row_2) = (main.row_slice(0), main.row_slice(1), main.row_slice(2));
+        builder.when_first_row().assert_zero(row_0[0]);
+        builder.when_first_row().assert_one(row_1[0]);
+        builder
+            .when_transition_window(3)
+            .assert_eq(row_2[0], row_0[0] + row_1[0]);
+        builder.when_last_row().assert_eq(row_0[0], x);
+    }
+}
+
+/// Constrains only the last row, in a window of a single row, which is too small to be proven.
+struct SingleRowAir;
+
+impl<F> BaseAir<F> for SingleRowAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn window_size(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SingleRowAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        builder.when_last_row().assert_eq(main.row_slice(0)[0], x);
+    }
+}
+
+/// A counter which spreads each count over `STEP` rows, i.e. whose `i`-th row is `i / STEP`, and
+/// whose last row is a public value.
+struct StridedCounterAir;
+
+const STEP: usize = 4;
+
+impl<F: Field> BaseAir<F> for StridedCounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn window_size(&self) -> usize {
+        STEP + 1
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![periodic_selector(STEP, STEP - 1)]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + PeriodicAirBuilder> Air<AB> for StridedCounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        let is_last_of_count: AB::Expr = builder.periodic_values()[0].into();
+        let (local, next, after_step) =
+            (main.row_slice(0), main.row_slice(1), main.row_slice(STEP));
+        builder.when_first_row().assert_zero(local[0]);
+        // Within a count, consecutive rows are equal. The last row of the trace is the last of a
+        // count, so this does not wrap around.
+        builder
+            .when(AB::Expr::ONE - is_last_of_count)
+            .assert_eq(next[0], local[0]);
+        builder
+            .when_transition_step(STEP)
+            .assert_eq(after_step[0], local[0] + AB::Expr::ONE);
+        builder.when_last_row().assert_eq(local[0], x);
+    }
+}
+
+fn generate_trace(height: usize) -> RowMajorMatrix<Val> {
+    let mut values = vec![Val::ZERO, Val::ONE];
+    while values.len() < height {
+        values.push(values[values.len() - 2] + values[values.len() - 1]);
+    }
+    RowMajorMatrix::new_col(values)
+}
+
+fn generate_strided_counter_trace(height: usize) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new_col(
+        (0..height)
+            .map(|i| Val::from_canonical_usize(i / STEP))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_window() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_trace(1 << 4);
+    // The 15th Fibonacci number.
+    let public_values = [Val::from_canonical_u32(610)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciColumnAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(
+        &config,
+        &FibonacciColumnAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &FibonacciColumnAir,
+        &mut challenger,
+        &proof,
+        &[Val::from_canonical_u32(611)],
+    )
+    .expect_err("verification should fail with the wrong public value");
+}
+
+#[test]
+fn test_window_multiple() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let public_values = [Val::from_canonical_u32(610)];
+    let airs_and_traces = [
+        (FibonacciColumnAir, generate_trace(1 << 4)),
+        (FibonacciColumnAir, generate_trace(1 << 4)),
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_multiple(&config, &airs_and_traces, &mut challenger, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify_multiple(
+        &config,
+        &[FibonacciColumnAir; 2],
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_transition_step() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_strided_counter_trace(1 << 4);
+    let public_values = [Val::from_canonical_u32(3)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &StridedCounterAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(
+        &config,
+        &StridedCounterAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &StridedCounterAir,
+        &mut challenger,
+        &proof,
+        &[Val::from_canonical_u32(4)],
+    )
+    .expect_err("verification should fail with the wrong public value");
+}
+
+#[test]
+#[should_panic(expected = "the window of an AIR must have at least two rows")]
+fn test_single_row_window_prove() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_trace(1 << 4);
+    let public_values = [Val::from_canonical_u32(610)];
+    let mut challenger = Challenger::new(perm);
+    prove(
+        &config,
+        &SingleRowAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+}
+
+#[test]
+fn test_single_row_window_verify() {
+    let (config, perm) = test_config(&mut thread_rng());
+    let trace = generate_trace(1 << 4);
+    let public_values = [Val::from_canonical_u32(610)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciColumnAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+
+    let mut challenger = Challenger::new(perm);
+    let error = verify(
+        &config,
+        &SingleRowAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect_err("a window of a single row should be rejected");
+    assert!(matches!(
+        error,
+        VerificationError::InvalidProofShape(ShapeError::WindowSize { air: 0 })
+    ));
+}
@@ -0,0 +1,320 @@
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, Interaction, LookupAir, PairBuilder,
+    VirtualPairCol,
+};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::{ExtensionMmcs, ProverRng};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, HidingFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeHidingMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove, prove_with_key, prove_with_lookups, setup_keys, setup_preprocessed, verify,
+    verify_with_key, verify_with_lookups, ConstraintBatching, StarkConfig, VerificationError,
+    ZkStarkConfig,
+};
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// Proves knowledge of a secret starting pair `(a, b)` of a Fibonacci-like sequence whose `n`-th
+/// term is the public value.
+struct SecretFibonacciAir;
+
+impl<F> BaseAir<F> for SecretFibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SecretFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = builder.public_values()[0];
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.when_transition().assert_eq(next[0], local[1]);
+        builder
+            .when_transition()
+            .assert_eq(next[1], local[0] + local[1]);
+        builder.when_last_row().assert_eq(local[1], x);
+    }
+}
+
+fn generate_fibonacci_trace(a: u32, b: u32, height: usize) -> RowMajorMatrix<Val> {
+    let mut values = Vec::with_capacity(2 * height);
+    let (mut left, mut right) = (Val::from_canonical_u32(a), Val::from_canonical_u32(b));
+    for _ in 0..height {
+        values.extend([left, right]);
+        (left, right) = (right, left + right);
+    }
+    RowMajorMatrix::new(values, 2)
+}
+
+/// Range checks its first column against a preprocessed table of the values `0..8`, which receives
+/// each of its entries as many times as the second column says.
+struct RangeCheckAir;
+
+impl<F: Field> BaseAir<F> for RangeCheckAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new_col(
+            (0..8).map(F::from_canonical_u32).collect(),
+        ))
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for RangeCheckAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+impl<F: Field> LookupAir<F> for RangeCheckAir {
+    fn interactions(&self) -> Vec<Interaction<F>> {
+        vec![
+            Interaction::send(vec![VirtualPairCol::single_main(0)], VirtualPairCol::ONE, 0),
+            Interaction::receive(
+                vec![VirtualPairCol::single_preprocessed(0)],
+                VirtualPairCol::single_main(1),
+                0,
+            ),
+        ]
+    }
+}
+
+/// Proves knowledge of a secret offset between its only column and a preprocessed column of the
+/// values `0..8`.
+struct OffsetAir;
+
+impl<F: Field> BaseAir<F> for OffsetAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new_col(
+            (0..8).map(F::from_canonical_u32).collect(),
+        ))
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for OffsetAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let preprocessed = builder.preprocessed();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (c_local, c_next) = (preprocessed.row_slice(0), preprocessed.row_slice(1));
+        builder
+            .when_transition()
+            .assert_eq(next[0] - local[0], c_next[0] - c_local[0]);
+    }
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs = MerkleTreeHidingMmcs<
+    <Val as Field>::Packing,
+    <Val as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type Pcs = HidingFriPcs<Val, Dft, ValMmcs, ChallengeMmcs, StdRng>;
+type MyConfig = ZkStarkConfig<Pcs, Challenge, Challenger>;
+
+/// A PCS with the given blowup, whose randomness is forked from `rng`.
+fn setup_pcs(log_blowup: usize, perm: &Perm, rng: &mut ProverRng<StdRng>) -> Pcs {
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress, rng.fork());
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup,
+        log_final_poly_len: 0,
+        num_queries: 2,
+        proof_of_work_bits: 1,
+        deduplicate_queries: false,
+        mmcs: challenge_mmcs,
+    };
+    Pcs::new(Dft::default(), val_mmcs, fri_config, 4, rng.fork())
+}
+
+fn setup(log_blowup: usize, perm: &Perm, mut rng: ProverRng<StdRng>) -> MyConfig {
+    MyConfig::new(setup_pcs(log_blowup, perm, &mut rng))
+}
+
+#[test]
+fn test_zk() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = setup(1, &perm, ProverRng::new(StdRng::from_entropy()));
+    let trace = generate_fibonacci_trace(3, 5, 1 << 4);
+    let public_values = [trace.get(trace.height() - 1, 1)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &SecretFibonacciAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(
+        &config,
+        &SecretFibonacciAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    // The trace of a zero-knowledge proof is committed over a larger domain.
+    let pcs = setup_pcs(1, &perm, &mut ProverRng::new(StdRng::from_entropy()));
+    let non_zk_config = StarkConfig::new(pcs);
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &non_zk_config,
+        &SecretFibonacciAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect_err("verification should fail without zero-knowledge mode");
+}
+
+#[test]
+fn test_zk_config_knobs() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = setup(1, &perm, ProverRng::new(StdRng::from_entropy()))
+        .with_quotient_chunk_size(3)
+        .with_constraint_batching(ConstraintBatching::Groups { group_size: 1 })
+        .with_fixed_log_quotient_degree(3);
+    let trace = generate_fibonacci_trace(3, 5, 1 << 4);
+    let public_values = [trace.get(trace.height() - 1, 1)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &SecretFibonacciAir,
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+    assert_eq!(proof.opened_values().quotient_chunks().len(), 8);
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &SecretFibonacciAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_zk_lookups() {
+    // The LogUp constraints have degree 3, so the quotient has 8 chunks in zero-knowledge mode, and
+    // the preprocessed trace, committed over the trace domain, needs a blowup of 8 to cover them.
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = setup(3, &perm, ProverRng::new(StdRng::from_entropy()));
+    let values = [3, 1, 4, 1, 5, 0, 2, 6];
+    let mut multiplicities = [0; 8];
+    for value in values {
+        multiplicities[value] += 1;
+    }
+    let trace = RowMajorMatrix::new(
+        values
+            .into_iter()
+            .zip(multiplicities)
+            .flat_map(|(value, multiplicity)| [value, multiplicity].map(Val::from_canonical_usize))
+            .collect(),
+        2,
+    );
+
+    let (prover_data, vk) = setup_preprocessed(&config, &RangeCheckAir, 3).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_lookups(
+        &config,
+        &RangeCheckAir,
+        &mut challenger,
+        trace,
+        &[],
+        Some(&prover_data),
+    );
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_lookups(
+        &config,
+        &RangeCheckAir,
+        &mut challenger,
+        &proof,
+        &[],
+        Some(&vk),
+    )
+    .expect("verification failed");
+
+    // The hiding commitment to the preprocessed trace is random, so it can only come from setup.
+    let mut challenger = Challenger::new(perm);
+    let result = verify_with_lookups(&config, &RangeCheckAir, &mut challenger, &proof, &[], None);
+    assert!(matches!(
+        result,
+        Err(VerificationError::MissingPreprocessedKey)
+    ));
+}
+
+#[test]
+fn test_zk_preprocessed_keys() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = setup(3, &perm, ProverRng::new(StdRng::from_entropy()));
+    let (pk, vk) = setup_keys(&config, &OffsetAir, 0);
+
+    for secret in [3, 7] {
+        let trace = RowMajorMatrix::new_col(
+            (0..8)
+                .map(|i| Val::from_canonical_u32(secret + i))
+                .collect(),
+        );
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_with_key(&config, &pk, &OffsetAir, &mut challenger, trace, &[]);
+        let mut challenger = Challenger::new(perm.clone());
+        verify_with_key(&config, &vk, &mut challenger, &proof, &[]).expect("verification failed");
+    }
+}
+
+#[test]
+fn test_zk_reproducible() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let prove_with_seed = |seed| {
+        let config = setup(1, &perm, ProverRng::seed_from_u64(seed));
+        let trace = generate_fibonacci_trace(3, 5, 1 << 4);
+        let public_values = [trace.get(trace.height() - 1, 1)];
+        let mut challenger = Challenger::new(perm.clone());
+        prove(
+            &config,
+            &SecretFibonacciAir,
+            &mut challenger,
+            trace,
+            &public_values,
+        )
+        .to_bytes()
+    };
+
+    assert_eq!(prove_with_seed(1), prove_with_seed(1));
+    assert_ne!(prove_with_seed(1), prove_with_seed(2));
+}
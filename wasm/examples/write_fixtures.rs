@@ -0,0 +1,58 @@
+//! Writes a proof, its verifying key and its public values to the directory given as the first
+//! argument, by default `js/fixtures`, for the JavaScript tests of the bindings, along with the
+//! proof with one of its FRI openings tampered with.
+
+use std::{env, fs};
+
+use p3_baby_bear::BabyBear;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_pinned_config::keccak::{baby_bear_keccak_config, keccak_challenger, KeccakConfig};
+use p3_uni_stark::testing::{fibonacci_trace, FibonacciAir};
+use p3_uni_stark::{prove_with_key, setup_keys, Proof};
+
+fn main() {
+    let dir = env::args().nth(1).unwrap_or_else(|| "js/fixtures".into());
+    let (trace, pis) = fibonacci_trace(BabyBear::ZERO, BabyBear::ONE, 1 << 6);
+
+    let config = baby_bear_keccak_config();
+    let (pk, vk) = setup_keys(&config, &FibonacciAir, pis.len());
+    let proof = prove_with_key(
+        &config,
+        &pk,
+        &FibonacciAir,
+        &mut keccak_challenger(),
+        trace,
+        &pis,
+    );
+
+    fs::create_dir_all(&dir).expect("unable to create the fixtures directory");
+    fs::write(format!("{dir}/proof.bin"), proof.to_bytes()).expect("unable to write the proof");
+    fs::write(
+        format!("{dir}/tampered_proof.bin"),
+        tamper_opening(&proof).to_bytes(),
+    )
+    .expect("unable to write the tampered proof");
+    fs::write(format!("{dir}/vk.bin"), vk.to_bytes()).expect("unable to write the key");
+    let pis: Vec<_> = pis
+        .iter()
+        .map(|x| x.as_canonical_u32().to_string())
+        .collect();
+    fs::write(
+        format!("{dir}/public_values.json"),
+        format!("[{}]", pis.join(",")),
+    )
+    .expect("unable to write the public values");
+}
+
+/// `proof` with the first value opened at the first FRI query changed, so that it no longer matches
+/// the trace commitment.
+fn tamper_opening(proof: &Proof<KeccakConfig<BabyBear>>) -> Proof<KeccakConfig<BabyBear>> {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&proof.to_json().expect("unable to encode the proof"))
+            .expect("the encoding is valid JSON");
+    let value = &mut json["proof"]["opening_proof"]["query_proofs"][0]["input_proof"][0]
+        ["opened_values"][0][0];
+    *value =
+        ((value.as_u64().expect("a field element") + 1) % u64::from(BabyBear::ORDER_U32)).into();
+    Proof::from_json(&json.to_string()).expect("the tampered proof still decodes")
+}
@@ -0,0 +1,44 @@
+// Tests of the JavaScript bindings of `p3-wasm`. From the `wasm` directory, build them and the
+// fixtures, then run the tests:
+//
+//   wasm-pack build --release --target nodejs --out-dir js/pkg -- --features wasm-bindgen
+//   cargo run --release --example write_fixtures -- js/fixtures
+//   cd js && npm test
+
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { test } from "node:test";
+
+import { verify_proof } from "./pkg/p3_wasm.js";
+
+const fixture = (name) => readFileSync(new URL(`./fixtures/${name}`, import.meta.url));
+
+const proof = fixture("proof.bin");
+const tamperedProof = fixture("tampered_proof.bin");
+const vk = fixture("vk.bin");
+const publicValues = Uint32Array.from(JSON.parse(fixture("public_values.json")));
+
+test("accepts a valid proof", () => {
+  verify_proof(proof, vk, publicValues);
+});
+
+test("rejects wrong public values", () => {
+  const wrong = publicValues.slice();
+  wrong[2] += 1;
+  assert.throws(() => verify_proof(proof, vk, wrong), /Verification/);
+});
+
+test("rejects a proof with a tampered FRI opening", () => {
+  // A rejection is an `Error` from `verify_proof`, not a trap of the module.
+  assert.throws(() => verify_proof(tamperedProof, vk, publicValues), /Verification/);
+});
+
+test("rejects a malformed proof", () => {
+  assert.throws(() => verify_proof(proof.subarray(0, proof.length - 1), vk, publicValues), /Proof/);
+});
+
+test("rejects public values outside the field", () => {
+  const wrong = publicValues.slice();
+  wrong[0] = 0xffffffff;
+  assert.throws(() => verify_proof(proof, vk, wrong), /PublicValue\(0\)/);
+});
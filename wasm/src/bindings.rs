@@ -0,0 +1,9 @@
+use alloc::format;
+
+use wasm_bindgen::prelude::*;
+
+/// Verify a proof for JavaScript, throwing an `Error` describing why it was rejected.
+#[wasm_bindgen]
+pub fn verify_proof(proof: &[u8], vk: &[u8], public_values: &[u32]) -> Result<(), JsError> {
+    crate::verify_proof(proof, vk, public_values).map_err(|err| JsError::new(&format!("{err:?}")))
+}
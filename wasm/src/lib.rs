@@ -0,0 +1,19 @@
+//! A verifier of `uni-stark` proofs for WebAssembly, such as in the browser.
+//!
+//! `verify_proof` checks a proof made with the `KeccakConfig` over BabyBear of `p3-pinned-config`
+//! against its verifying key, so the verifier needs neither the AIR's code nor any randomness or
+//! threads. With the `wasm-bindgen` feature, it is exported to JavaScript, e.g. by building with
+//! `wasm-pack build --release wasm -- --features wasm-bindgen`. For the smallest module, build
+//! with the `wasm-release` profile of the workspace. The harness in `js` tests the bindings.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "wasm-bindgen")]
+extern crate std;
+
+#[cfg(feature = "wasm-bindgen")]
+mod bindings;
+mod verify;
+
+pub use verify::*;
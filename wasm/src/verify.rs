@@ -0,0 +1,21 @@
+use p3_baby_bear::BabyBear;
+use p3_pinned_config::keccak::{
+    baby_bear_keccak_config, keccak_challenger, KeccakChallenge, KeccakConfig,
+};
+use p3_pinned_config::{verify_encoded, VerifyEncodedError};
+use p3_uni_stark::PcsError;
+
+pub type WasmVerifyError =
+    VerifyEncodedError<PcsError<KeccakConfig<BabyBear>>, KeccakChallenge<BabyBear>>;
+
+/// Verify a proof made with the `KeccakConfig` over BabyBear, against a verifying key, each in the
+/// binary encoding of `p3-uni-stark`, and public values given as canonical field elements.
+pub fn verify_proof(proof: &[u8], vk: &[u8], public_values: &[u32]) -> Result<(), WasmVerifyError> {
+    verify_encoded(
+        &baby_bear_keccak_config(),
+        &mut keccak_challenger(),
+        proof,
+        vk,
+        public_values,
+    )
+}
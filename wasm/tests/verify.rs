@@ -0,0 +1,57 @@
+use p3_baby_bear::BabyBear;
+use p3_field::{FieldAlgebra, PrimeField32};
+use p3_pinned_config::keccak::{baby_bear_keccak_config, keccak_challenger};
+use p3_uni_stark::testing::{fibonacci_trace, FibonacciAir};
+use p3_uni_stark::{prove_with_key, setup_keys, ProofDecodingError};
+use p3_wasm::{verify_proof, WasmVerifyError};
+
+/// The encoded proof and verifying key of a Fibonacci sequence of `n` rows, and its public values.
+fn prove_fibonacci(n: usize) -> (Vec<u8>, Vec<u8>, Vec<u32>) {
+    let (trace, pis) = fibonacci_trace(BabyBear::ZERO, BabyBear::ONE, n);
+
+    let config = baby_bear_keccak_config();
+    let (pk, vk) = setup_keys(&config, &FibonacciAir, pis.len());
+    let proof = prove_with_key(
+        &config,
+        &pk,
+        &FibonacciAir,
+        &mut keccak_challenger(),
+        trace,
+        &pis,
+    );
+    let pis = pis.iter().map(|x| x.as_canonical_u32()).collect();
+    (proof.to_bytes(), vk.to_bytes(), pis)
+}
+
+#[test]
+fn test_verify_proof() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 4);
+    verify_proof(&proof, &vk, &pis).expect("verification failed");
+
+    let mut wrong_pis = pis.clone();
+    wrong_pis[2] += 1;
+    assert!(matches!(
+        verify_proof(&proof, &vk, &wrong_pis),
+        Err(WasmVerifyError::Verification(_))
+    ));
+}
+
+#[test]
+fn test_verify_proof_malformed() {
+    let (proof, vk, pis) = prove_fibonacci(1 << 3);
+    assert!(matches!(
+        verify_proof(&proof[..proof.len() - 1], &vk, &pis),
+        Err(WasmVerifyError::Proof(_))
+    ));
+    // The key and the proof have different magics.
+    assert!(matches!(
+        verify_proof(&proof, &proof, &pis),
+        Err(WasmVerifyError::VerifyingKey(
+            ProofDecodingError::InvalidMagic
+        ))
+    ));
+    assert!(matches!(
+        verify_proof(&proof, &vk, &[pis[0], u32::MAX, pis[2]]),
+        Err(WasmVerifyError::PublicValue(1))
+    ));
+}